@@ -0,0 +1,98 @@
+//! Assessment throughput on cooperative-sized inputs: `cargo bench --bench assessment`
+
+use african_lca_backend::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::collections::HashMap;
+
+const FARM: &str = include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+const FACILITY: &str = include_str!("../../app/processing/example_cassava_processing.json");
+
+/// `input` with its `field` array replaced by `count` numbered copies of its entries
+fn synthetic(input: &str, field: &str, count: usize) -> serde_json::Value {
+    let mut input: serde_json::Value = serde_json::from_str(input).unwrap();
+    let templates = input[field].as_array().unwrap().clone();
+    input[field] = (0..count).map(|i| {
+        let mut item = templates[i % templates.len()].clone();
+        item["id"] = format!("item-{}", i).into();
+        item["name"] = format!("{} {}", item["name"].as_str().unwrap_or("Item"), i).into();
+        item
+    }).collect();
+    input
+}
+
+fn assessments(c: &mut Criterion) {
+    let farm = synthetic(FARM, "foods", 500);
+    let facility = synthetic(FACILITY, "processed_products", 500);
+    let runner = AssessmentRunner::new(runner_config(&farm));
+
+    c.bench_function("comprehensive farm, 500 foods", |b| {
+        b.iter(|| run_input(&runner, AssessmentKind::Comprehensive, &farm).unwrap())
+    });
+    c.bench_function("processing facility, 500 products", |b| {
+        b.iter(|| run_input(&runner, AssessmentKind::Processing, &facility).unwrap())
+    });
+}
+
+/// Factor lookups for 500 foods in every impact category: the structured index against the
+/// string keys it replaced
+fn factor_lookup(c: &mut Criterion) {
+    let mut loader = DataLoader::new();
+    loader.load_default_factors().unwrap();
+    let mut index = FactorIndex::default();
+    let mut by_string = HashMap::new();
+    for factor in loader.get_factors() {
+        index.insert(factor.clone());
+        let key = match &factor.crop_type {
+            Some(crop) => format!("{:?}_{:?}_{}_{}", factor.food_category, factor.country, crop, factor.impact_category),
+            None => format!("{:?}_{:?}_{}", factor.food_category, factor.country, factor.impact_category),
+        };
+        by_string.insert(key, factor.clone());
+    }
+    let crops = [
+        (FoodCategory::Cereals, Some("Maize")), (FoodCategory::Legumes, Some("Cowpea")),
+        (FoodCategory::Roots, Some("Cassava")), (FoodCategory::Vegetables, None),
+    ];
+    let foods: Vec<_> = (0..500).map(|i| &crops[i % crops.len()]).collect();
+    let categories = [
+        "Global warming", "Water consumption", "Land use", "Biodiversity loss", "Soil degradation",
+        "Terrestrial acidification", "Freshwater eutrophication", "Marine eutrophication",
+        "Water scarcity", "Fossil depletion", "Mineral depletion", "Particulate matter formation",
+        "Photochemical oxidation",
+    ];
+
+    c.bench_function("factor lookup, string keys", |b| b.iter(|| {
+        let mut found = 0;
+        for (category, crop) in &foods {
+            for impact in categories {
+                let mut hierarchy = Vec::new();
+                if let Some(crop) = crop {
+                    hierarchy.push(format!("{:?}_{:?}_{}", category, Country::Ghana, crop));
+                }
+                hierarchy.push(format!("{:?}_{:?}", category, Country::Ghana));
+                if let Some(crop) = crop {
+                    hierarchy.push(format!("{:?}_Global_{}", category, crop));
+                }
+                hierarchy.push(format!("{:?}_Global", category));
+                found += hierarchy.iter().any(|base| by_string.contains_key(&format!("{}_{}", base, impact))) as usize;
+            }
+        }
+        black_box(found)
+    }));
+    c.bench_function("factor lookup, index", |b| b.iter(|| {
+        let mut found = 0;
+        for (category, crop) in &foods {
+            let chain = index.fallback_chain(category, *crop, &Country::Ghana);
+            for impact in categories {
+                found += index.find(&chain, impact).is_some() as usize;
+            }
+        }
+        black_box(found)
+    }));
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = assessments, factor_lookup
+}
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+//! Memory and time to set up engines on loaded factors: `cargo bench --bench engines`
+//!
+//! Servers and batch runs clone a loaded engine per request. The factor databases are shared
+//! between clones, so each clone should allocate the same few bytes however many factors are
+//! loaded. The built-in farm factors are built by the first loader only; later loaders share
+//! them.
+
+use african_lca_backend::*;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// System allocator counting the bytes it hands out
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Bytes allocated while running `f`
+fn allocated_by<T>(f: impl FnOnce() -> T) -> (usize, T) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let value = f();
+    (ALLOCATED.load(Ordering::Relaxed) - before, value)
+}
+
+fn methodology() -> LCAMethodology {
+    LCAMethodology::iso_default()
+}
+
+/// Farm engine on `copies` copies of the default factors, each under its own crop name
+fn loaded_engine(copies: usize) -> AfricanLCAEngine {
+    let mut loader = DataLoader::new();
+    loader.load_default_factors().unwrap();
+    let factors: FactorIndex = (0..copies)
+        .flat_map(|copy| loader.get_factors().iter().map(move |factor| ImpactFactor {
+            crop_type: factor.crop_type.as_ref().map(|crop| format!("{} {}", crop, copy)),
+            ..factor.clone()
+        }))
+        .collect();
+    let mut engine = AfricanLCAEngine::new(methodology());
+    engine.load_factor_index(std::sync::Arc::new(factors));
+    engine
+}
+
+/// Bytes `load_default_factors` allocates on a new loader
+fn default_factor_bytes() -> usize {
+    let mut loader = DataLoader::new();
+    allocated_by(|| loader.load_default_factors().unwrap()).0
+}
+
+fn engines(c: &mut Criterion) {
+    let first = default_factor_bytes();
+    println!("Default farm factors: {} bytes for the first loader, {} for the next", first, default_factor_bytes());
+    c.bench_function("load default farm factors", |b| b.iter_batched(
+        DataLoader::new,
+        |mut loader| {
+            loader.load_default_factors().unwrap();
+            loader
+        },
+        BatchSize::SmallInput
+    ));
+
+    for copies in [1, 10] {
+        let engine = loaded_engine(copies);
+        let (bytes, clones) = allocated_by(|| (0..1000).map(|_| engine.clone()).collect::<Vec<_>>());
+        println!("1000 farm engines on {} copies of the default factors: {} bytes ({} per engine)", copies, bytes, bytes / clones.len());
+    }
+
+    let mut loader = ProcessingDataLoader::new();
+    loader.load_default_factors().unwrap();
+    let mut processing = ProcessingLCAEngine::new(methodology());
+    processing.load_impact_factors(loader.get_factors().clone());
+    processing.load_benchmarks(loader.benchmarks.clone());
+    let (bytes, clones) = allocated_by(|| (0..1000).map(|_| processing.clone()).collect::<Vec<_>>());
+    println!("1000 processing engines: {} bytes ({} per engine)", bytes, bytes / clones.len());
+
+    let engine = loaded_engine(1);
+    c.bench_function("clone a loaded farm engine", |b| b.iter(|| engine.clone()));
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = engines
+}
+criterion_main!(benches);
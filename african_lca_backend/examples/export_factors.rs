@@ -0,0 +1,14 @@
+//! Writes the default factor databases embedded in the WebAssembly build:
+//! `cargo run --example export_factors --features wasm`
+
+use african_lca_backend::wasm::encode_factors;
+use african_lca_backend::FactorDatabases;
+use std::fs;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/default_factors.bin");
+    let bytes = encode_factors(&FactorDatabases::defaults())?;
+    fs::write(path, &bytes)?;
+    println!("Wrote {} bytes to {}", bytes.len(), path);
+    Ok(())
+}
@@ -0,0 +1,69 @@
+#ifndef AFRICAN_LCA_H
+#define AFRICAN_LCA_H
+
+/* Generated by cbindgen from src/ffi.rs - do not edit; see tests/ffi.rs */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// The call succeeded
+#define LCA_OK 0
+
+// A required pointer was null or the input was not UTF-8
+#define LCA_INVALID_ARGUMENT 1
+
+// The remaining codes match ErrorCode::exit_code
+#define LCA_INVALID_JSON 2
+
+#define LCA_INVALID_INPUT 3
+
+#define LCA_ASSESSMENT_FAILED 4
+
+#define LCA_SERIALIZATION_FAILED 5
+
+// The library panicked; the runner can still be used
+#define LCA_PANIC 7
+
+// Loaded factor databases, opaque to C
+typedef struct LcaRunner LcaRunner;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Load the default factor databases. Returns null on failure; free with `lca_runner_free`.
+struct LcaRunner *lca_runner_new(void);
+
+// Release a runner from `lca_runner_new`; null is ignored.
+//
+// # Safety
+// `runner` must be null or a pointer from `lca_runner_new` not yet freed.
+void lca_runner_free(struct LcaRunner *runner);
+
+// Run the assessment described by `input_json`. On LCA_OK, `*out_json` receives the results
+// JSON (free with `lca_string_free`) and `*out_len` its length in bytes without the NUL;
+// on any other code `*out_json` is null and `lca_last_error_message` says why.
+//
+// # Safety
+// `runner` must come from `lca_runner_new`, `input_json` must be NUL-terminated, and
+// `out_json` and `out_len` must be valid for writes.
+int32_t lca_run_assessment(const struct LcaRunner *runner,
+                           const char *input_json,
+                           char **out_json,
+                           size_t *out_len);
+
+// Message of the last failed call on this thread, or null after a success. The pointer
+// stays valid until the next call on the same thread; do not free it.
+const char *lca_last_error_message(void);
+
+// Release a string returned by the library; null is ignored.
+//
+// # Safety
+// `s` must be null or a string from `lca_run_assessment` not yet freed.
+void lca_string_free(char *s);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* AFRICAN_LCA_H */
@@ -0,0 +1,52 @@
+-- Assessments with their results, the foods or products they cover, and the factor
+-- sources behind each impact category
+
+CREATE TABLE assessments (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL CHECK (kind IN ('farm', 'processing')),
+    company_name TEXT NOT NULL,
+    country TEXT NOT NULL,
+    region TEXT,
+    assessment_date TEXT NOT NULL, -- RFC 3339, UTC
+    document TEXT NOT NULL         -- the assessment as JSON, results left out
+);
+
+CREATE INDEX assessments_country_date ON assessments (country, assessment_date);
+CREATE INDEX assessments_company ON assessments (company_name);
+
+CREATE TABLE results (
+    assessment_id TEXT PRIMARY KEY REFERENCES assessments (id) ON DELETE CASCADE,
+    single_score REAL NOT NULL,
+    global_warming REAL,           -- kg CO2-eq
+    overall_confidence TEXT NOT NULL,
+    document TEXT NOT NULL         -- LCAResults as JSON
+);
+
+CREATE TABLE foods (
+    assessment_id TEXT NOT NULL REFERENCES assessments (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    food_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    category TEXT NOT NULL,
+    quantity_kg REAL NOT NULL,
+    area_ha REAL,
+    PRIMARY KEY (assessment_id, position)
+);
+
+CREATE TABLE products (
+    assessment_id TEXT NOT NULL REFERENCES assessments (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    product_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    product_type TEXT NOT NULL,
+    annual_production_t REAL NOT NULL,
+    PRIMARY KEY (assessment_id, position)
+);
+
+CREATE TABLE factor_provenance (
+    assessment_id TEXT NOT NULL REFERENCES assessments (id) ON DELETE CASCADE,
+    impact_category TEXT NOT NULL,
+    source TEXT NOT NULL
+);
+
+CREATE INDEX factor_provenance_assessment ON factor_provenance (assessment_id);
@@ -0,0 +1,9 @@
+-- Product types were stored by their Rust debug spelling before the columns switched to
+-- the names they are serialized under. The two agree for every type but Other, written
+-- Other("x") rather than {"Other":"x"}; its name is unescaped (\\ and \") on the way.
+
+UPDATE products
+SET product_type = json_object('Other', replace(replace(replace(
+        substr(product_type, 8, length(product_type) - 9),
+        '\\', char(1)), '\"', '"'), char(1), '\'))
+WHERE product_type LIKE 'Other("%")';
@@ -0,0 +1,199 @@
+/*!
+ * Before/after comparison of two assessment inputs
+ *
+ * Runs a baseline and an alternative input through the same runner (same factor databases
+ * and configuration) and reports how the results moved: the change of every midpoint, of the
+ * single score and of the data quality rating, which inputs differ, and the categories that
+ * shifted most. Unlike `production::compare_assessments`, which compares farm scenarios
+ * already assessed in code, this works from the JSON inputs of the CLI and takes facilities too.
+ */
+
+use crate::input::*;
+use crate::production::{ConfidenceLevel, LCAResults};
+use crate::runner::AssessmentRunner;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Fields that differ between any two runs and say nothing about the inputs
+const RUN_FIELDS: [&str; 4] = ["id", "assessment_date", "results", "trend_analysis"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssessmentComparison {
+    pub assessment_type: AssessmentKind, // of the baseline
+    pub midpoint_changes: BTreeMap<String, CategoryChange>,
+    pub single_score: CategoryChange,
+    pub data_quality: ConfidenceChange,
+    pub input_differences: Vec<InputDifference>,
+    pub largest_shifts: Vec<String>, // categories by relative shift, largest first
+}
+
+/// One result in both runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryChange {
+    pub unit: String,
+    pub baseline: f64,
+    pub alternative: f64,
+    pub absolute_change: f64, // alternative - baseline
+    pub percent_change: Option<f64>, // None when the baseline is zero
+}
+
+impl CategoryChange {
+    fn new(unit: &str, baseline: f64, alternative: f64) -> Self {
+        Self {
+            unit: unit.to_string(),
+            baseline,
+            alternative,
+            absolute_change: alternative - baseline,
+            percent_change: (baseline.abs() > f64::EPSILON)
+                .then(|| (alternative - baseline) / baseline.abs() * 100.0),
+        }
+    }
+
+    /// Change relative to the larger of the two values (0-2), so a category that appears
+    /// from nothing ranks as a large shift instead of an infinite one
+    fn relative_shift(&self) -> f64 {
+        let scale = self.baseline.abs().max(self.alternative.abs());
+        if scale > f64::EPSILON { self.absolute_change.abs() / scale } else { 0.0 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfidenceChange {
+    pub baseline: ConfidenceLevel,
+    pub alternative: ConfidenceLevel,
+    pub changed: bool,
+}
+
+/// An input field with different values in the two assessments, as a dotted path
+/// (e.g. "management_practices.water_management.irrigation_system")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputDifference {
+    pub field: String,
+    pub baseline: serde_json::Value, // null when the field is missing
+    pub alternative: serde_json::Value,
+}
+
+/// Run both inputs with `runner` and compare the results. Farm inputs (simple or
+/// comprehensive) compare with farm inputs and facilities with facilities; companies and mixed
+/// pairs are refused as invalid input.
+pub fn compare_assessments(
+    runner: &AssessmentRunner,
+    baseline: &serde_json::Value,
+    alternative: &serde_json::Value
+) -> Result<AssessmentComparison, ErrorReport> {
+    let (baseline_kind, alternative_kind) = (AssessmentKind::detect(baseline), AssessmentKind::detect(alternative));
+    let is_farm = |kind| matches!(kind, AssessmentKind::Simple | AssessmentKind::Comprehensive);
+    if baseline_kind == AssessmentKind::Company || alternative_kind == AssessmentKind::Company {
+        return Err(ErrorReport::new(ErrorCode::InvalidInput, "Company assessments cannot be compared; compare their facilities"));
+    }
+    if is_farm(baseline_kind) != is_farm(alternative_kind) {
+        return Err(ErrorReport::new(ErrorCode::InvalidInput, format!(
+            "Cannot compare a {} with a {}", baseline_kind.label(), alternative_kind.label()
+        )));
+    }
+
+    let (baseline_inputs, baseline_results) = run(runner, baseline_kind, baseline)?;
+    let (alternative_inputs, alternative_results) = run(runner, alternative_kind, alternative)?;
+
+    let midpoint_changes: BTreeMap<String, CategoryChange> = baseline_results.midpoint_impacts.iter()
+        .filter_map(|(category, before)| alternative_results.midpoint_impacts.get(category)
+            .map(|after| (category.clone(), CategoryChange::new(&before.unit, before.value, after.value))))
+        .collect();
+    let mut largest_shifts: Vec<&String> = midpoint_changes.keys().collect();
+    largest_shifts.sort_by(|a, b| midpoint_changes[*b].relative_shift().total_cmp(&midpoint_changes[*a].relative_shift())
+        .then_with(|| a.cmp(b)));
+    let largest_shifts = largest_shifts.into_iter().cloned().collect();
+
+    let mut input_differences = Vec::new();
+    diff_fields("", &baseline_inputs, &alternative_inputs, &mut input_differences);
+
+    let (before, after) = (baseline_results.data_quality.overall_confidence, alternative_results.data_quality.overall_confidence);
+    Ok(AssessmentComparison {
+        assessment_type: baseline_kind,
+        single_score: CategoryChange::new(
+            &baseline_results.single_score.unit,
+            baseline_results.single_score.value,
+            alternative_results.single_score.value
+        ),
+        data_quality: ConfidenceChange { changed: before != after, baseline: before, alternative: after },
+        midpoint_changes,
+        input_differences,
+        largest_shifts,
+    })
+}
+
+/// The assessment as parsed, without its run fields, and its results
+fn run(
+    runner: &AssessmentRunner,
+    kind: AssessmentKind,
+    input: &serde_json::Value
+) -> Result<(serde_json::Value, LCAResults), ErrorReport> {
+    let failed = |e: serde_json::Error| ErrorReport::new(ErrorCode::SerializationFailed, e);
+    let mut assessment: serde_json::Value = serde_json::from_str(&run_input(runner, kind, input)?).map_err(failed)?;
+    let results = assessment.get_mut("results").map(serde_json::Value::take).unwrap_or_default();
+    let results = serde_json::from_value(results).map_err(failed)?;
+    if let Some(fields) = assessment.as_object_mut() {
+        fields.retain(|field, _| !RUN_FIELDS.contains(&field.as_str()));
+    }
+    Ok((assessment, results))
+}
+
+/// Differences between two documents, descending into objects; arrays and values that differ
+/// are reported whole
+fn diff_fields(path: &str, baseline: &serde_json::Value, alternative: &serde_json::Value, differences: &mut Vec<InputDifference>) {
+    match (baseline.as_object(), alternative.as_object()) {
+        (Some(before), Some(after)) => {
+            let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+            fields.sort();
+            fields.dedup();
+            for field in fields {
+                let nested = if path.is_empty() { field.clone() } else { format!("{}.{}", path, field) };
+                let missing = serde_json::Value::Null;
+                diff_fields(&nested, before.get(field).unwrap_or(&missing), after.get(field).unwrap_or(&missing), differences);
+            }
+        }
+        _ if baseline != alternative => differences.push(InputDifference {
+            field: path.to_string(),
+            baseline: baseline.clone(),
+            alternative: alternative.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FARM: &str = include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+    const FACILITY: &str = include_str!("../../app/processing/example_cassava_processing.json");
+
+    #[test]
+    fn drip_irrigation_shifts_the_water_categories_most() {
+        let baseline: serde_json::Value = serde_json::from_str(FARM).unwrap();
+        let mut drip = baseline.clone();
+        drip["management_practices"]["water_management"]["irrigation_system"] = "Drip irrigation".into();
+        let runner = AssessmentRunner::new(runner_config(&baseline));
+
+        let comparison = compare_assessments(&runner, &baseline, &drip).unwrap();
+        assert!(comparison.largest_shifts[..2].iter().all(|category| category.starts_with("Water")), "{:?}", comparison.largest_shifts);
+        assert!(comparison.midpoint_changes["Water consumption"].absolute_change > 0.0);
+        let differences: Vec<&str> = comparison.input_differences.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(differences, ["management_practices.water_management.irrigation_system"]);
+        assert_eq!(comparison.input_differences[0].alternative, "Drip irrigation");
+
+        let unchanged = compare_assessments(&runner, &baseline, &baseline).unwrap();
+        assert!(unchanged.input_differences.is_empty() && !unchanged.data_quality.changed);
+        assert!(unchanged.midpoint_changes.values().all(|change| change.absolute_change == 0.0));
+    }
+
+    #[test]
+    fn farms_are_not_compared_with_facilities() {
+        let farm: serde_json::Value = serde_json::from_str(FARM).unwrap();
+        let facility: serde_json::Value = serde_json::from_str(FACILITY).unwrap();
+        let runner = AssessmentRunner::new(runner_config(&farm));
+
+        let error = compare_assessments(&runner, &farm, &facility).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+    }
+}
@@ -0,0 +1,207 @@
+/*!
+ * Errors - why an input could not be assessed
+ *
+ * `InputError` points at the offending field of an input document, `DataLoadError` at a
+ * record of a factor file, `FactorError` at missing characterization data, `UnitError` at a
+ * quantity in the wrong unit and `EngineError` at an assessment the engines could not
+ * complete. `ExportError`, `ReportError` and `StorageError` are failures writing results
+ * out, to exchange formats, printable reports and the local history. The input and engine errors each convert into the
+ * `ErrorReport` that the CLI turns into its exit status and the server into its error body.
+ */
+
+use crate::input::{ErrorCode, ErrorReport};
+use crate::units::Unit;
+use thiserror::Error;
+
+/// A field of the input that is missing or holds an unusable value. `field` is its path in
+/// the document, e.g. "foods[2].category".
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum InputError {
+    #[error("Missing {field}")]
+    Missing { field: String },
+
+    #[error("{field} must be {expected}, got {value}")]
+    WrongType { field: String, expected: &'static str, value: String },
+
+    #[error("Unknown {field}: {value} (expected one of: {})", .valid.join(", "))]
+    UnknownValue { field: String, value: String, valid: Vec<String> },
+
+    #[error("Invalid {field}: {reason}")]
+    Invalid { field: String, reason: String },
+
+    #[error("{field} is {value}, outside the possible range {min} to {max}")]
+    OutOfRange { field: String, value: f64, min: f64, max: f64 },
+
+    #[error("{field} is {value}, outside the plausible range {min} to {max}")]
+    Implausible { field: String, value: f64, min: f64, max: f64 },
+}
+
+impl InputError {
+    /// Path of the offending field
+    pub fn field(&self) -> &str {
+        match self {
+            InputError::Missing { field }
+            | InputError::WrongType { field, .. }
+            | InputError::UnknownValue { field, .. }
+            | InputError::Invalid { field, .. }
+            | InputError::OutOfRange { field, .. }
+            | InputError::Implausible { field, .. } => field,
+        }
+    }
+
+    pub fn invalid(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        InputError::Invalid { field: field.into(), reason: reason.into() }
+    }
+}
+
+/// A factor file that could not be read, or a record in it that could not be parsed.
+/// `line` is the record's line in the file.
+#[derive(Debug, Error)]
+pub enum DataLoadError {
+    #[error("Cannot read {path}: {reason}")]
+    Unreadable { path: String, reason: String },
+
+    #[error("{path}, line {line}: unknown {column} {value} (expected one of: {})", .valid.join(", "))]
+    UnknownValue { path: String, line: u64, column: &'static str, value: String, valid: Vec<String> },
+
+    #[error("{path}, line {line}: {column} must be a number, got {value}")]
+    NotANumber { path: String, line: u64, column: &'static str, value: String },
+
+    #[error("{path}, line {line}: {error}")]
+    Unit { path: String, line: u64, error: UnitError },
+}
+
+/// A quantity or factor in a unit that does not convert into the one needed
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum UnitError {
+    #[error("Unknown unit {0}")]
+    Unknown(String),
+
+    #[error("Cannot convert {from} to {to}")]
+    Incompatible { from: Unit, to: Unit },
+
+    #[error("Unit '{declared}' does not convert to the expected '{expected}'")]
+    Mismatch { declared: String, expected: String },
+}
+
+/// Characterization data an assessment needs but the engine does not have
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum FactorError {
+    #[error("No impact factors loaded - call load_impact_factors before assessing")]
+    NoneLoaded,
+}
+
+/// An assessment the engines could not complete
+#[derive(Debug, Error)]
+pub enum EngineError {
+    #[error(transparent)]
+    Input(#[from] InputError),
+
+    #[error(transparent)]
+    Factor(#[from] FactorError),
+
+    #[error("{0} has not been assessed")]
+    NotAssessed(String),
+
+    #[error("No food at index {index} (assessment has {count})")]
+    NoSuchFood { index: usize, count: usize },
+
+    /// The assessment ran, but what was asked of its results does not apply to it
+    #[error("{0}")]
+    NotApplicable(String),
+}
+
+/// An assessment that could not be written as an ILCD package or SimaPro process
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Assessment has no results - run it before exporting")]
+    NotAssessed,
+
+    #[error("Assessment was run without include_inventory; {format} export needs its inventory")]
+    NoInventory { format: &'static str },
+
+    #[error("Assessment has no production to express the inventory per kg of")]
+    NoProduction,
+
+    #[error("Cannot write {path}: {source}")]
+    Io { path: String, source: std::io::Error },
+}
+
+/// A printable report that could not be drawn up or rendered
+#[derive(Debug, Error)]
+pub enum ReportError {
+    #[error("Assessment has no results - run it before reporting")]
+    NotAssessed,
+
+    #[error("Cannot render the report: {0}")]
+    Html(#[from] askama::Error),
+
+    #[cfg(feature = "pdf")]
+    #[error("Cannot render the PDF report: {0}")]
+    Pdf(#[from] printpdf::Error),
+}
+
+/// The local assessment history could not be opened, read or written (feature `storage`)
+#[cfg(feature = "storage")]
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+
+    #[error("Stored document is not valid: {0}")]
+    Document(#[from] serde_json::Error),
+
+    /// A document saved by an earlier build that cannot be brought to the current layout
+    #[error("Stored document cannot be migrated: {0}")]
+    Migration(#[from] InputError),
+
+    #[error("Database schema version {found} is newer than this build supports ({supported})")]
+    TooNew { found: usize, supported: usize },
+
+    #[error("Stored {column} is not valid: {value}")]
+    Column { column: &'static str, value: String },
+}
+
+impl From<InputError> for ErrorReport {
+    fn from(e: InputError) -> Self {
+        let field = e.field().to_string();
+        ErrorReport { field: Some(field), ..ErrorReport::new(ErrorCode::InvalidInput, e) }
+    }
+}
+
+impl From<EngineError> for ErrorReport {
+    /// Inputs the engine rejects are invalid input like any other; everything else is a
+    /// failed assessment
+    fn from(e: EngineError) -> Self {
+        match e {
+            EngineError::Input(e) => e.into(),
+            e => ErrorReport::new(ErrorCode::AssessmentFailed, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_values_list_the_valid_alternatives() {
+        let e = InputError::UnknownValue {
+            field: "foods[0].category".to_string(),
+            value: "Grains".to_string(),
+            valid: vec!["Cereals".to_string(), "Legumes".to_string()],
+        };
+        assert_eq!(e.to_string(), "Unknown foods[0].category: Grains (expected one of: Cereals, Legumes)");
+    }
+
+    #[test]
+    fn reports_keep_the_field_and_map_engine_input_errors_to_invalid_input() {
+        let report = ErrorReport::from(EngineError::from(InputError::Missing { field: "country".to_string() }));
+        assert_eq!(report.code, ErrorCode::InvalidInput);
+        assert_eq!(report.field.as_deref(), Some("country"));
+
+        let report = ErrorReport::from(EngineError::from(FactorError::NoneLoaded));
+        assert_eq!(report.code, ErrorCode::AssessmentFailed);
+        assert_eq!(report.field, None);
+    }
+}
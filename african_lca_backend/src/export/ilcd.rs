@@ -0,0 +1,489 @@
+/*!
+ * ILCD export
+ *
+ * Writes a completed farm assessment as an ILCD package that openLCA imports
+ * (File > Import > ILCD):
+ *
+ * ILCD/processes/<assessment id>.xml   the farm as one process per kg of product, with the
+ *                                      LCI elementary flows, LCIA results and data quality
+ * ILCD/flows/<flow id>.xml             the product flow and each elementary flow
+ *
+ * Elementary flows use ILCD names (lower case, CAS number, ILCD compartment categories) and
+ * the standard ILCD flow properties, so importers can map them to their reference flows.
+ * Inventory items that are already characterized (kg CO2-eq, kg Fe-eq) are not elementary
+ * flows; they are listed in the process comment and reach the LCIA results only.
+ */
+
+use crate::production::*;
+use chrono::Datelike;
+use std::fmt::Write as _;
+use super::{create_dir, write};
+use crate::error::ExportError;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Standard ILCD flow properties: (UUID, name, reference unit)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlowProperty {
+    Mass,
+    Volume,
+    AreaTime,
+}
+
+impl FlowProperty {
+    pub fn uuid(self) -> &'static str {
+        match self {
+            FlowProperty::Mass => "93a60a56-a3c8-11da-a746-0800200b9a66",
+            FlowProperty::Volume => "93a60a56-a3c8-22da-a746-0800200c9a66",
+            FlowProperty::AreaTime => "93a60a56-a3c8-21da-a746-0800200c9a66",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FlowProperty::Mass => "Mass",
+            FlowProperty::Volume => "Volume",
+            FlowProperty::AreaTime => "Area*time",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            FlowProperty::Mass => "kg",
+            FlowProperty::Volume => "m3",
+            FlowProperty::AreaTime => "m2*a",
+        }
+    }
+
+    /// Unit the LCI records this property in
+    fn inventory_unit(self) -> &'static str {
+        match self {
+            FlowProperty::AreaTime => "m2*year",
+            _ => self.unit(),
+        }
+    }
+}
+
+/// An ILCD elementary flow
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementaryFlow {
+    pub name: &'static str,
+    pub cas_number: Option<&'static str>,
+    pub categories: &'static [&'static str], // ILCD elementary flow categorization, top level first
+    pub property: FlowProperty,
+}
+
+impl ElementaryFlow {
+    /// Stable across exports, so repeated imports reuse the same flow
+    pub fn uuid(&self) -> Uuid {
+        Uuid::new_v5(&Uuid::NAMESPACE_URL, format!("urn:african-lca:flow:{}:{}", self.categories.join("/"), self.name).as_bytes())
+    }
+}
+
+const AIR: &[&str] = &["Emissions", "Emissions to air", "Emissions to air, unspecified"];
+const WATER: &[&str] = &["Emissions", "Emissions to water", "Emissions to fresh water"];
+const RESOURCE_WATER: &[&str] = &["Resources", "Resources from water"];
+const LAND_OCCUPATION: &[&str] = &["Land use", "Land occupation"];
+
+/// ILCD flow for an inventory substance, or None when it is not an elementary flow in
+/// physical units (pre-characterized items)
+pub fn elementary_flow(flow: &LciFlow) -> Option<ElementaryFlow> {
+    let (name, cas_number, categories, property) = match (flow.substance.as_str(), flow.compartment.as_str()) {
+        (s, "air") if s.starts_with("Dinitrogen monoxide (N2O)") => ("nitrous oxide", Some("10024-97-2"), AIR, FlowProperty::Mass),
+        ("Carbon dioxide (CO2)", "air") => ("carbon dioxide, fossil", Some("124-38-9"), AIR, FlowProperty::Mass),
+        // Rice paddies and manure
+        ("Methane (CH4)", "air") => ("methane, non-fossil", Some("74-82-8"), AIR, FlowProperty::Mass),
+        ("Ammonia (NH3)", "air") => ("ammonia", Some("7664-41-7"), AIR, FlowProperty::Mass),
+        ("Nitrate (NO3-)", "water") => ("nitrate", Some("14797-55-8"), WATER, FlowProperty::Mass),
+        ("Nitrogen (N)", "water") => ("nitrogen", Some("7727-37-9"), WATER, FlowProperty::Mass),
+        ("Phosphorus (P)", "water") => ("phosphorus", Some("7723-14-0"), WATER, FlowProperty::Mass),
+        ("Water", "resource") => ("fresh water", Some("7732-18-5"), RESOURCE_WATER, FlowProperty::Volume),
+        ("Land occupation, annual crop" | "Land occupation, feed crops", "resource") => {
+            ("occupation, arable land", None, LAND_OCCUPATION, FlowProperty::AreaTime)
+        },
+        ("Land occupation, poultry housing and range" | "Land occupation, pasture", "resource") => {
+            ("occupation, agricultural land", None, LAND_OCCUPATION, FlowProperty::AreaTime)
+        },
+        _ => return None,
+    };
+    (flow.unit == property.inventory_unit()).then_some(ElementaryFlow { name, cas_number, categories, property })
+}
+
+/// An elementary flow exchange per kg of product
+#[derive(Debug, Clone, PartialEq)]
+pub struct IlcdExchange {
+    pub flow: ElementaryFlow,
+    pub direction: &'static str, // "Input" for resources, "Output" for emissions
+    pub amount: f64,
+    pub comment: String, // the activity that caused it
+}
+
+/// Elementary flow exchanges per kg of product, and the inventory items left out
+pub fn ilcd_exchanges(assessment: &Assessment) -> Result<(Vec<IlcdExchange>, Vec<LciFlow>), ExportError> {
+    let results = assessment.results.as_ref().ok_or(ExportError::NotAssessed)?;
+    let flows = results.lci_inventory.as_ref().ok_or(ExportError::NoInventory { format: "ILCD" })?;
+    let production_kg: f64 = assessment.foods.iter().map(|food| food.quantity_kg).sum();
+    if production_kg <= 0.0 {
+        return Err(ExportError::NoProduction);
+    }
+
+    let mut exchanges = Vec::new();
+    let mut excluded = Vec::new();
+    for flow in flows {
+        match elementary_flow(flow) {
+            Some(elementary) => exchanges.push(IlcdExchange {
+                direction: if flow.compartment == "resource" { "Input" } else { "Output" },
+                flow: elementary,
+                amount: flow.quantity / production_kg,
+                comment: flow.source.clone(),
+            }),
+            None => excluded.push(flow.clone()),
+        }
+    }
+    Ok((exchanges, excluded))
+}
+
+/// Write `assessment` as an ILCD package under `dir` and return the process dataset's path
+pub fn export_ilcd(assessment: &Assessment, dir: &Path) -> Result<PathBuf, ExportError> {
+    let (exchanges, excluded) = ilcd_exchanges(assessment)?;
+    let processes = dir.join("ILCD").join("processes");
+    let flows = dir.join("ILCD").join("flows");
+    create_dir(&processes)?;
+    create_dir(&flows)?;
+
+    let product = product_name(assessment);
+    let product_id = Uuid::new_v5(&assessment.id, b"product");
+    write(&flows.join(format!("{}.xml", product_id)), flow_dataset(
+        &product_id, &product, None, "Product flow", &["Agricultural products"], FlowProperty::Mass
+    ))?;
+    for exchange in &exchanges {
+        let flow = &exchange.flow;
+        write(&flows.join(format!("{}.xml", flow.uuid())), flow_dataset(
+            &flow.uuid(), flow.name, flow.cas_number, "Elementary flow", flow.categories, flow.property
+        ))?;
+    }
+
+    let path = processes.join(format!("{}.xml", assessment.id));
+    write(&path, process_dataset(assessment, &product_id, &product, &exchanges, &excluded))?;
+    Ok(path)
+}
+
+pub fn product_name(assessment: &Assessment) -> String {
+    let foods: Vec<&str> = assessment.foods.iter().map(|food| food.name.as_str()).collect();
+    format!("{} ({}, {})", foods.join(", "), assessment.company_name, assessment.country)
+}
+
+pub fn location_code(country: &Country) -> &'static str {
+    match country {
+        Country::Ghana => "GH",
+        Country::Nigeria => "NG",
+        Country::Global => "GLO",
+    }
+}
+
+/// ILCD data quality level of a 0-1 representativeness score
+fn quality_level(score: f64) -> &'static str {
+    match score {
+        s if s >= 0.9 => "Very good",
+        s if s >= 0.7 => "Good",
+        s if s >= 0.5 => "Fair",
+        s if s >= 0.3 => "Poor",
+        _ => "Very poor",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn flow_dataset(
+    id: &Uuid,
+    name: &str,
+    cas_number: Option<&str>,
+    flow_type: &str,
+    categories: &[&str],
+    property: FlowProperty
+) -> String {
+    let classes: String = categories.iter().enumerate()
+        .map(|(level, category)| format!("            <common:category level=\"{}\">{}</common:category>\n", level, escape(category)))
+        .collect();
+    let (classification, cas) = if flow_type == "Elementary flow" {
+        (format!("          <common:elementaryFlowCategorization>\n{}          </common:elementaryFlowCategorization>", classes),
+         cas_number.map(|cas| format!("\n      <CASNumber>{}</CASNumber>", cas)).unwrap_or_default())
+    } else {
+        (format!("          <common:classification>\n{}          </common:classification>", classes.replace("common:category", "common:class")), String::new())
+    };
+
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<flowDataSet xmlns="http://lca.jrc.it/ILCD/Flow" xmlns:common="http://lca.jrc.it/ILCD/Common" version="1.1">
+  <flowInformation>
+    <dataSetInformation>
+      <common:UUID>{id}</common:UUID>
+      <name>
+        <baseName xml:lang="en">{name}</baseName>
+      </name>
+      <classificationInformation>
+{classification}
+      </classificationInformation>{cas}
+    </dataSetInformation>
+    <quantitativeReference>
+      <referenceToReferenceFlowProperty>0</referenceToReferenceFlowProperty>
+    </quantitativeReference>
+  </flowInformation>
+  <modellingAndValidation>
+    <LCIMethod>
+      <typeOfDataSet>{flow_type}</typeOfDataSet>
+    </LCIMethod>
+  </modellingAndValidation>
+  <flowProperties>
+    <flowProperty dataSetInternalID="0">
+      <referenceToFlowPropertyDataSet type="flow property data set" refObjectId="{property_id}" uri="../flowproperties/{property_id}.xml">
+        <common:shortDescription xml:lang="en">{property_name}</common:shortDescription>
+      </referenceToFlowPropertyDataSet>
+      <meanValue>1.0</meanValue>
+    </flowProperty>
+  </flowProperties>
+</flowDataSet>
+"#,
+        name = escape(name),
+        property_id = property.uuid(),
+        property_name = property.name(),
+    )
+}
+
+fn process_dataset(
+    assessment: &Assessment,
+    product_id: &Uuid,
+    product: &str,
+    exchanges: &[IlcdExchange],
+    excluded: &[LciFlow]
+) -> String {
+    let results = assessment.results.as_ref();
+    let methodology = &assessment.methodology;
+    let production_kg: f64 = assessment.foods.iter().map(|food| food.quantity_kg).sum();
+
+    let mut comment = format!(
+        "Farm assessment of {} in {}: {:.0} kg of product per year. System boundary {:?}, allocation {:?}, characterization {:?}. \
+         Results schema version {}.",
+        assessment.company_name, assessment.country, production_kg,
+        methodology.system_boundary, methodology.allocation_method, methodology.characterization_method,
+        assessment.schema_version
+    );
+    if !excluded.is_empty() {
+        let items: Vec<String> = excluded.iter()
+            .map(|flow| format!("{} {:.3} {} ({})", flow.substance, flow.quantity, flow.unit, flow.source))
+            .collect();
+        let _ = write!(comment, " Pre-characterized inventory items included in the LCIA results only: {}.", items.join("; "));
+    }
+
+    let mut exchange_xml = format!(r#"    <exchange dataSetInternalID="0">
+      <referenceToFlowDataSet type="flow data set" refObjectId="{product_id}" uri="../flows/{product_id}.xml">
+        <common:shortDescription xml:lang="en">{product}</common:shortDescription>
+      </referenceToFlowDataSet>
+      <exchangeDirection>Output</exchangeDirection>
+      <meanAmount>1.0</meanAmount>
+      <resultingAmount>1.0</resultingAmount>
+    </exchange>
+"#, product = escape(product));
+    for (index, exchange) in exchanges.iter().enumerate() {
+        let flow_id = exchange.flow.uuid();
+        let _ = write!(exchange_xml, r#"    <exchange dataSetInternalID="{id}">
+      <referenceToFlowDataSet type="flow data set" refObjectId="{flow_id}" uri="../flows/{flow_id}.xml">
+        <common:shortDescription xml:lang="en">{name}</common:shortDescription>
+      </referenceToFlowDataSet>
+      <exchangeDirection>{direction}</exchangeDirection>
+      <meanAmount>{amount:e}</meanAmount>
+      <resultingAmount>{amount:e}</resultingAmount>
+      <generalComment xml:lang="en">{comment} ({unit})</generalComment>
+    </exchange>
+"#,
+            id = index + 1,
+            name = exchange.flow.name,
+            direction = exchange.direction,
+            amount = exchange.amount,
+            comment = escape(&exchange.comment),
+            unit = exchange.flow.property.unit(),
+        );
+    }
+
+    let mut lcia_xml = String::new();
+    let mut categories: Vec<(String, (f64, String))> = assessment.midpoint_impacts_per_kg().unwrap_or_default().into_iter().collect();
+    categories.sort_by(|a, b| a.0.cmp(&b.0));
+    for (category, (amount, unit)) in categories {
+        let method_id = Uuid::new_v5(&Uuid::NAMESPACE_URL, format!("urn:african-lca:lcia:{:?}:{}", methodology.characterization_method, category).as_bytes());
+        let _ = write!(lcia_xml, r#"    <LCIAResult>
+      <referenceToLCIAMethodDataSet type="LCIA method data set" refObjectId="{method_id}" uri="../lciamethods/{method_id}.xml">
+        <common:shortDescription xml:lang="en">{method:?}; {category}; {unit}</common:shortDescription>
+      </referenceToLCIAMethodDataSet>
+      <meanAmount>{amount:e}</meanAmount>
+    </LCIAResult>
+"#,
+            method = methodology.characterization_method,
+            category = escape(&category),
+            unit = escape(&unit),
+        );
+    }
+
+    let mut quality_xml = String::new();
+    let mut sources = String::new();
+    if let Some(quality) = results.map(|r| &r.data_quality) {
+        let overall = match quality.overall_confidence {
+            ConfidenceLevel::High => "Good",
+            ConfidenceLevel::Medium => "Fair",
+            ConfidenceLevel::Low => "Poor",
+            ConfidenceLevel::VeryLow => "Very poor",
+        };
+        for (indicator, level) in [
+            ("Technological representativeness", quality_level(quality.technological_representativeness)),
+            ("Time representativeness", quality_level(quality.temporal_representativeness)),
+            ("Geographical representativeness", quality_level(quality.geographical_representativeness)),
+            ("Completeness", quality_level(quality.completeness_score)),
+            ("Overall quality", overall),
+        ] {
+            let _ = writeln!(quality_xml, r#"          <common:dataQualityIndicator name="{}" value="{}"/>"#, indicator, level);
+        }
+        let mix: Vec<String> = quality.data_source_mix.iter()
+            .map(|source| format!("{:?} {:.0}% (pedigree quality {:.2})", source.source_type, source.percentage, source.quality_score))
+            .collect();
+        sources = format!("Impact factors by source: {}. Data quality indicators are derived from the factors' pedigree scores.", mix.join(", "));
+    }
+
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<processDataSet xmlns="http://lca.jrc.it/ILCD/Process" xmlns:common="http://lca.jrc.it/ILCD/Common" version="1.1">
+  <processInformation>
+    <dataSetInformation>
+      <common:UUID>{id}</common:UUID>
+      <name>
+        <baseName xml:lang="en">{product}</baseName>
+      </name>
+      <common:generalComment xml:lang="en">{comment}</common:generalComment>
+    </dataSetInformation>
+    <quantitativeReference type="Reference flow(s)">
+      <referenceToReferenceFlow>0</referenceToReferenceFlow>
+      <functionalUnitOrOther xml:lang="en">{functional_unit}</functionalUnitOrOther>
+    </quantitativeReference>
+    <time>
+      <common:referenceYear>{year}</common:referenceYear>
+    </time>
+    <geography>
+      <locationOfOperationSupplyOrProduction location="{location}"/>
+    </geography>
+  </processInformation>
+  <modellingAndValidation>
+    <LCIMethodAndAllocation>
+      <typeOfDataSet>Unit process, black box</typeOfDataSet>
+      <LCIMethodPrinciple>{principle:?}</LCIMethodPrinciple>
+    </LCIMethodAndAllocation>
+    <dataSourcesTreatmentAndRepresentativeness>
+      <dataSelectionAndCombinationPrinciples xml:lang="en">{sources}</dataSelectionAndCombinationPrinciples>
+    </dataSourcesTreatmentAndRepresentativeness>
+    <validation>
+      <review type="Not reviewed">
+        <common:dataQualityIndicators>
+{quality_xml}        </common:dataQualityIndicators>
+      </review>
+    </validation>
+  </modellingAndValidation>
+  <exchanges>
+{exchange_xml}  </exchanges>
+  <LCIAResults>
+{lcia_xml}  </LCIAResults>
+</processDataSet>
+"#,
+        id = assessment.id,
+        product = escape(product),
+        comment = escape(&comment),
+        functional_unit = escape(&methodology.functional_unit),
+        year = assessment.assessment_date.year(),
+        location = location_code(&assessment.country),
+        principle = methodology.modeling_approach,
+        sources = escape(&sources),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::create_comprehensive_input;
+    use crate::runner::{AssessmentRunner, RunnerConfig};
+    use std::fs;
+
+    const FARM: &str = include_str!("../../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+
+    /// The intercrop case study with its NPK and urea rates doubled
+    fn fertilizer_heavy_farm() -> Assessment {
+        let mut input: serde_json::Value = serde_json::from_str(FARM).unwrap();
+        input["company_name"] = "Tamale Grains & Legumes".into();
+        for application in input["management_practices"]["fertilization"]["fertilizer_applications"].as_array_mut().unwrap() {
+            application["application_rate"] = (application["application_rate"].as_f64().unwrap() * 2.0).into();
+        }
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        runner.run_comprehensive(create_comprehensive_input(&input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn fertilizer_flows_follow_ilcd_names_and_units() {
+        let farm = fertilizer_heavy_farm();
+        let (exchanges, excluded) = ilcd_exchanges(&farm).unwrap();
+        let production_kg: f64 = farm.foods.iter().map(|food| food.quantity_kg).sum();
+        let inventory = farm.results.as_ref().unwrap().lci_inventory.as_ref().unwrap();
+        assert_eq!(exchanges.len() + excluded.len(), inventory.len());
+
+        for exchange in &exchanges {
+            let flow = &exchange.flow;
+            assert_eq!(flow.name, flow.name.to_lowercase(), "ILCD flow names are lower case");
+            assert!(["Emissions", "Resources", "Land use"].contains(&flow.categories[0]));
+            assert_eq!(exchange.direction == "Input", flow.categories[0] != "Emissions");
+            assert!(exchange.amount >= 0.0);
+        }
+        let total = |name: &str| exchanges.iter().filter(|e| e.flow.name == name).map(|e| e.amount).sum::<f64>();
+        let inventory_total = |prefix: &str| inventory.iter()
+            .filter(|flow| flow.substance.starts_with(prefix))
+            .map(|flow| flow.quantity)
+            .sum::<f64>() / production_kg;
+        assert!(total("nitrous oxide") > 0.0);
+        assert!((total("nitrous oxide") - inventory_total("Dinitrogen monoxide")).abs() < 1e-12);
+        assert!((total("nitrate") - inventory_total("Nitrate")).abs() < 1e-12);
+        assert!(total("carbon dioxide, fossil") > 0.0);
+
+        // NPK's phosphate and potash are recorded in kg Fe-eq, not as mass flows
+        assert!(excluded.iter().any(|flow| flow.substance == "Phosphate rock"));
+        assert!(excluded.iter().all(|flow| elementary_flow(flow).is_none()));
+    }
+
+    #[test]
+    fn export_writes_a_process_that_references_its_flows() {
+        let farm = fertilizer_heavy_farm();
+        let dir = std::env::temp_dir().join(format!("ilcd-export-{}", Uuid::new_v4()));
+        let path = export_ilcd(&farm, &dir).unwrap();
+        let process = fs::read_to_string(&path).unwrap();
+
+        assert!(process.contains("Tamale Grains &amp; Legumes"));
+        assert!(process.contains(r#"<locationOfOperationSupplyOrProduction location="GH"/>"#));
+        assert!(process.contains("<functionalUnitOrOther xml:lang=\"en\">1 kg product</functionalUnitOrOther>"));
+        assert!(process.contains(r#"name="Geographical representativeness""#));
+        assert_eq!(process.matches("<LCIAResult>").count(), farm.results.as_ref().unwrap().midpoint_impacts.len());
+        assert_eq!(process.matches("<exchange ").count(), process.matches("</exchange>").count());
+
+        for reference in process.split("refObjectId=\"").skip(1) {
+            let id = &reference[..36];
+            if process.contains(&format!("../flows/{}.xml", id)) {
+                assert!(dir.join("ILCD/flows").join(format!("{}.xml", id)).exists(), "missing flow {}", id);
+            }
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn export_needs_the_inventory() {
+        let mut farm = fertilizer_heavy_farm();
+        farm.results.as_mut().unwrap().lci_inventory = None;
+        let error = export_ilcd(&farm, &std::env::temp_dir()).unwrap_err();
+        assert!(matches!(error, ExportError::NoInventory { format: "ILCD" }));
+        assert!(error.to_string().contains("include_inventory"));
+    }
+}
@@ -0,0 +1,18 @@
+use crate::error::ExportError;
+use std::fs;
+use std::path::Path;
+
+pub mod ilcd;
+pub mod simapro;
+
+pub use ilcd::*;
+pub use simapro::*;
+
+/// Create `dir` and any missing parents
+fn create_dir(dir: &Path) -> Result<(), ExportError> {
+    fs::create_dir_all(dir).map_err(|source| ExportError::Io { path: dir.display().to_string(), source })
+}
+
+fn write(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), ExportError> {
+    fs::write(path, contents).map_err(|source| ExportError::Io { path: path.display().to_string(), source })
+}
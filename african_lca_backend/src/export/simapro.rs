@@ -0,0 +1,398 @@
+/*!
+ * SimaPro CSV export
+ *
+ * Writes a completed farm assessment as a SimaPro CSV process (File > Import > SimaPro CSV)
+ * holding the farm's annual totals:
+ *
+ * Products                 the assessed foods, allocated by mass
+ * Materials/fuels          fertilizers and fuels as applied or burned on the farm
+ * Electricity/heat         grid electricity
+ * Resources, Emissions     inventory items mapped through data/simapro_substances.csv
+ *
+ * The technosphere inputs are named after the farm's inputs (e.g. "Urea (46-0-0)") for the
+ * importer to link to background processes. The LCI books the CO2 of producing them
+ * (fertilizer manufacture, power generation) together with the farm's own CO2; it is taken
+ * out of the exported carbon dioxide so the background data does not count it twice. Inventory items with no SimaPro substance, including pre-characterized items
+ * (kg CO2-eq, kg Fe-eq), are listed as {Warning: ...} lines in the file header.
+ */
+
+use crate::production::*;
+use super::ilcd::{location_code, product_name};
+use super::{create_dir, write};
+use crate::error::ExportError;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+const SUBSTANCES: &str = include_str!("../../data/simapro_substances.csv");
+
+/// One row of the bundled substance table
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubstanceMapping {
+    pub substance: String,
+    pub compartment: String,    // "air" | "water" | "soil" | "resource", as in LciFlow
+    pub unit: String,           // inventory unit the mapping applies to
+    pub simapro_name: String,
+    pub sub_compartment: String,
+    pub simapro_unit: String,
+}
+
+/// The bundled substance table
+pub fn substance_mappings() -> Vec<SubstanceMapping> {
+    SUBSTANCES.lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+            match fields.as_slice() {
+                [substance, compartment, unit, name, sub_compartment, simapro_unit] => Some(SubstanceMapping {
+                    substance: substance.to_string(),
+                    compartment: compartment.to_string(),
+                    unit: unit.to_string(),
+                    simapro_name: name.to_string(),
+                    sub_compartment: sub_compartment.to_string(),
+                    simapro_unit: simapro_unit.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// A technosphere input of the farm, in SimaPro's section for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TechnosphereInput {
+    pub section: &'static str, // "Materials/fuels" | "Electricity/heat"
+    pub name: String,
+    pub unit: &'static str,
+    pub amount: f64, // per year
+    pub comment: String,
+    pub production_co2_kg: f64, // CO2 the LCI books for producing it
+}
+
+/// Fertilizers, fuels and grid electricity the farm uses in a year, with the quantities the
+/// LCI derives its emissions from
+pub fn technosphere_inputs(assessment: &Assessment) -> Vec<TechnosphereInput> {
+    let factors = EmissionFactorsDatabase::default();
+    let mut inputs = Vec::new();
+
+    if let Some(fertilization) = assessment.management_practices.as_ref().and_then(|m| m.fertilization.as_ref()) {
+        let total_area_ha: f64 = assessment.foods.iter().filter_map(|food| food.area_allocated).sum();
+        for app in fertilization.fertilizer_applications.iter().filter(|_| fertilization.uses_fertilizers) {
+            let area_ha = app.treated_area_ha.unwrap_or(total_area_ha);
+            if area_ha == 0.0 {
+                continue;
+            }
+            let amount = app.application_rate * area_ha * app.applications_per_season as f64;
+            inputs.push(TechnosphereInput {
+                section: "Materials/fuels",
+                name: match &app.npk_ratio {
+                    Some(ratio) => format!("{} ({})", app.fertilizer_type, ratio),
+                    None => app.fertilizer_type.clone(),
+                },
+                unit: "kg",
+                amount,
+                comment: format!("{} kg/ha x {} ha x {} applications", app.application_rate, area_ha, app.applications_per_season),
+                production_co2_kg: amount * factors.fertilizer_production_co2(&app.fertilizer_type),
+            });
+        }
+    }
+
+    if let Some(equipment_energy) = &assessment.equipment_energy {
+        for fuel in &equipment_energy.fuel_consumption {
+            inputs.push(TechnosphereInput {
+                section: "Materials/fuels",
+                name: fuel.fuel_type.clone(),
+                unit: "l",
+                amount: fuel.monthly_consumption * 12.0,
+                comment: format!("Burned on the farm for {}", fuel.primary_use),
+                production_co2_kg: 0.0, // its combustion CO2 is the farm's own
+            });
+        }
+        for energy in equipment_energy.energy_sources.iter()
+            .filter(|energy| energy.energy_type.contains("Electricity") || energy.energy_type.contains("Grid")) {
+            let amount = energy.monthly_consumption * 12.0;
+            inputs.push(TechnosphereInput {
+                section: "Electricity/heat",
+                name: format!("Electricity, low voltage {{{}}}", location_code(&assessment.country)),
+                unit: "kWh",
+                amount,
+                comment: format!("{} for {}", energy.energy_type, energy.primary_use),
+                production_co2_kg: amount * factors.electricity_co2(&assessment.country),
+            });
+        }
+    }
+    inputs
+}
+
+/// SimaPro rows of each section, and the inventory items with no SimaPro substance
+#[derive(Debug, Clone, Default)]
+pub struct SimaProInventory {
+    pub resources: Vec<String>,
+    pub emissions_to_air: Vec<String>,
+    pub emissions_to_water: Vec<String>,
+    pub emissions_to_soil: Vec<String>,
+    pub unmapped: Vec<LciFlow>,
+}
+
+/// Map the assessment's inventory to SimaPro resource and emission rows
+pub fn simapro_inventory(assessment: &Assessment) -> Result<SimaProInventory, ExportError> {
+    let results = assessment.results.as_ref().ok_or(ExportError::NotAssessed)?;
+    let flows = results.lci_inventory.as_ref().ok_or(ExportError::NoInventory { format: "SimaPro" })?;
+    let mappings = substance_mappings();
+    let supplied_co2: f64 = technosphere_inputs(assessment).iter().map(|input| input.production_co2_kg).sum();
+
+    let mut inventory = SimaProInventory::default();
+    for flow in flows {
+        let mut quantity = flow.quantity;
+        if flow.substance == "Carbon dioxide (CO2)" && flow.compartment == "air" {
+            quantity -= supplied_co2;
+            // Only rounding is left when all of it came from the inputs
+            if quantity <= 1e-9 * flow.quantity {
+                continue;
+            }
+        }
+        let mapping = mappings.iter()
+            .find(|m| m.substance == flow.substance && m.compartment == flow.compartment && m.unit == flow.unit);
+        let Some(mapping) = mapping else {
+            inventory.unmapped.push(flow.clone());
+            continue;
+        };
+        let row = format!(
+            "{};{};{};{};Undefined;0;0;0;{}",
+            mapping.simapro_name, mapping.sub_compartment, mapping.simapro_unit, quantity, field(&flow.source)
+        );
+        match flow.compartment.as_str() {
+            "resource" => inventory.resources.push(row),
+            "air" => inventory.emissions_to_air.push(row),
+            "water" => inventory.emissions_to_water.push(row),
+            "soil" => inventory.emissions_to_soil.push(row),
+            _ => inventory.unmapped.push(flow.clone()),
+        }
+    }
+    Ok(inventory)
+}
+
+/// SimaPro fields are separated by semicolons and end at the line
+fn field(text: &str) -> String {
+    text.replace(';', ",").replace(['\r', '\n'], " ")
+}
+
+/// The assessment as a SimaPro CSV process
+pub fn simapro_csv(assessment: &Assessment) -> Result<String, ExportError> {
+    let inventory = simapro_inventory(assessment)?;
+    let production_kg: f64 = assessment.foods.iter().map(|food| food.quantity_kg).sum();
+    if production_kg <= 0.0 {
+        return Err(ExportError::NoProduction);
+    }
+    let methodology = &assessment.methodology;
+    let date = assessment.assessment_date;
+
+    let mut csv = format!(
+        "{{SimaPro 9.5.0.0}}\n{{processes}}\n{{Date: {}}}\n{{Time: {}}}\n{{Project: {}}}\n{{CSV Format version: 9.0.0}}\n\
+         {{CSV separator: Semicolon}}\n{{Decimal separator: .}}\n{{Date separator: /}}\n{{Short date format: dd/MM/yyyy}}\n\
+         {{Results schema version: {}}}\n",
+        date.format("%d/%m/%Y"), date.format("%H:%M:%S"), field(&assessment.company_name), field(&assessment.schema_version)
+    );
+    for flow in &inventory.unmapped {
+        let _ = writeln!(
+            csv, "{{Warning: no SimaPro substance for {} ({} {}, {}); not exported}}",
+            field(&flow.substance), flow.quantity, field(&flow.unit), field(&flow.source)
+        );
+    }
+
+    let comment = format!(
+        "Farm assessment of {} in {}: {:.0} kg of product per year. Functional unit {}. System boundary {:?}, \
+         allocation {:?}, characterization {:?}, {:?} modelling. Emissions are annual totals from the farm's \
+         inventory (IPCC 2019 Tier 1 field emissions, fuel combustion). Fertilizer and electricity production \
+         are represented by the technosphere inputs, which need linking to background processes on import.",
+        assessment.company_name, assessment.country, production_kg, methodology.functional_unit,
+        methodology.system_boundary, methodology.allocation_method, methodology.characterization_method,
+        methodology.modeling_approach
+    );
+    csv.push_str("\nProcess\n");
+    let process_id = format!("ALCA{}", &assessment.id.simple().to_string()[..12].to_uppercase());
+    for (name, value) in [
+        ("Category type", "material".to_string()),
+        ("Process identifier", process_id),
+        ("Type", "Unit process".to_string()),
+        ("Process name", product_name(assessment)),
+        ("Status", "Draft".to_string()),
+        ("Time period", "2020 and after".to_string()),
+        ("Geography", format!("Africa, {}", assessment.country)),
+        ("Technology", "Average technology".to_string()),
+        ("Representativeness", "Data from a specific farm".to_string()),
+        ("Multiple output allocation", "Physical causality".to_string()),
+        ("Cut off rules", "Capital goods and pesticides are reported as CO2-eq only and not exported".to_string()),
+        ("Capital goods", if methodology.include_capital_goods { "Included in the LCIA results" } else { "Excluded" }.to_string()),
+        ("Boundary with nature", "Agricultural production is part of the production system".to_string()),
+        ("Infrastructure", "No".to_string()),
+        ("Date", date.format("%d/%m/%Y").to_string()),
+        ("Record", format!("Assessment {}", assessment.id)),
+        ("Comment", comment),
+        ("Allocation rules", "Inputs and emissions are allocated to the products by mass".to_string()),
+    ] {
+        let _ = write!(csv, "\n{}\n{}\n", name, field(&value));
+    }
+
+    let mut products = Vec::new();
+    for food in &assessment.foods {
+        products.push(format!(
+            "{};kg;{};{};not defined;Agricultural;{}",
+            field(&food.name), food.quantity_kg, food.quantity_kg / production_kg * 100.0,
+            field(&format!("{:?} from {}", food.category, assessment.company_name))
+        ));
+    }
+    let inputs = technosphere_inputs(assessment);
+    let input_rows = |section: &str| -> Vec<String> {
+        inputs.iter()
+            .filter(|input| input.section == section)
+            .map(|input| format!("{};{};{};Undefined;0;0;0;{}", field(&input.name), input.unit, input.amount, field(&input.comment)))
+            .collect()
+    };
+
+    for (section, rows) in [
+        ("Products", products),
+        ("Resources", inventory.resources),
+        ("Materials/fuels", input_rows("Materials/fuels")),
+        ("Electricity/heat", input_rows("Electricity/heat")),
+        ("Emissions to air", inventory.emissions_to_air),
+        ("Emissions to water", inventory.emissions_to_water),
+        ("Emissions to soil", inventory.emissions_to_soil),
+    ] {
+        let _ = write!(csv, "\n{}\n", section);
+        for row in rows {
+            let _ = writeln!(csv, "{}", row);
+        }
+    }
+    csv.push_str("\nEnd\n");
+    Ok(csv)
+}
+
+/// Write `assessment` as `<dir>/<assessment id>.csv` and return its path
+pub fn export_simapro(assessment: &Assessment, dir: &Path) -> Result<PathBuf, ExportError> {
+    let csv = simapro_csv(assessment)?;
+    create_dir(dir)?;
+    let path = dir.join(format!("{}.csv", assessment.id));
+    write(&path, csv)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::create_comprehensive_input;
+    use crate::runner::{AssessmentRunner, RunnerConfig};
+    use std::collections::HashMap;
+    use std::fs;
+
+    const FARM: &str = include_str!("../../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+
+    /// The intercrop case study with 100 kWh of grid electricity a month
+    fn electrified_farm() -> Assessment {
+        let mut input: serde_json::Value = serde_json::from_str(FARM).unwrap();
+        input["equipment_energy"]["energy_sources"] = serde_json::json!([{
+            "energy_type": "Grid Electricity",
+            "monthly_consumption": 100.0,
+            "primary_use": "Grain drying; storage",
+            "cost": null,
+            "currency": null
+        }]);
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        runner.run_comprehensive(create_comprehensive_input(&input).unwrap()).unwrap()
+    }
+
+    /// Header lines, and the rows of each section of the process block
+    fn parse(csv: &str) -> (Vec<&str>, HashMap<&str, Vec<Vec<&str>>>) {
+        let header: Vec<&str> = csv.lines().take_while(|line| line.starts_with('{')).collect();
+        let mut sections = HashMap::new();
+        for block in csv.split("\n\n").skip(1) {
+            let mut lines = block.lines();
+            let name = lines.next().unwrap();
+            sections.insert(name, lines.map(|line| line.split(';').collect()).collect());
+        }
+        (header, sections)
+    }
+
+    #[test]
+    fn csv_has_the_simapro_sections_and_rows() {
+        let farm = electrified_farm();
+        let csv = simapro_csv(&farm).unwrap();
+        let (header, sections) = parse(&csv);
+
+        assert_eq!(header[0], "{SimaPro 9.5.0.0}");
+        assert!(header.contains(&"{CSV separator: Semicolon}"));
+        assert!(csv.ends_with("\nEnd\n"));
+        for section in ["Process name", "Comment", "Products", "Resources", "Materials/fuels", "Electricity/heat",
+                        "Emissions to air", "Emissions to water", "Emissions to soil"] {
+            assert!(sections.contains_key(section), "missing section {}", section);
+        }
+        assert!(sections["Comment"][0][0].contains("Functional unit 1 kg product"));
+
+        let products = &sections["Products"];
+        assert_eq!(products.len(), 2);
+        assert_eq!(products[0][..2], ["Maize", "kg"]);
+        let allocation: f64 = products.iter().map(|row| row[3].parse::<f64>().unwrap()).sum();
+        assert!((allocation - 100.0).abs() < 1e-9);
+
+        // NPK and urea over the 4.2 ha, and a year of diesel
+        let materials = &sections["Materials/fuels"];
+        assert_eq!(materials.len(), 3);
+        assert_eq!(materials[1][..3], ["Urea (46-0-0)", "kg", "315"]);
+        assert_eq!(materials[2][..3], ["Diesel", "l", "540"]);
+        assert_eq!(sections["Electricity/heat"], [vec!["Electricity, low voltage {GH}", "kWh", "1200", "Undefined", "0", "0", "0", "Grid Electricity for Grain drying, storage"]]);
+    }
+
+    #[test]
+    fn inventory_items_map_to_simapro_substances() {
+        let farm = electrified_farm();
+        let csv = simapro_csv(&farm).unwrap();
+        let (header, sections) = parse(&csv);
+        let names = |section: &str| sections[section].iter().map(|row| row[0]).collect::<Vec<_>>();
+
+        assert!(names("Emissions to air").contains(&"Dinitrogen monoxide"));
+        assert!(names("Emissions to water").contains(&"Nitrate"));
+        assert!(sections["Emissions to water"].iter().any(|row| row[..3] == ["Nitrate", "groundwater", "kg"]));
+        assert_eq!(names("Resources"), ["Occupation, annual crop"]);
+
+        // Diesel burned on the farm is an emission; fertilizer and power production are not
+        let inventory = farm.results.as_ref().unwrap().lci_inventory.as_ref().unwrap();
+        let co2: Vec<&Vec<&str>> = sections["Emissions to air"].iter().filter(|row| row[0] == "Carbon dioxide, fossil").collect();
+        assert_eq!(co2.len(), 1);
+        let factors = EmissionFactorsDatabase::default();
+        let diesel_co2 = 540.0 * factors.co2_from_diesel.value;
+        assert!((co2[0][3].parse::<f64>().unwrap() - diesel_co2).abs() < 1e-9 * diesel_co2);
+        let inventory_co2 = inventory.iter().find(|flow| flow.substance == "Carbon dioxide (CO2)").unwrap().quantity;
+        assert!(inventory_co2 > diesel_co2 + 1200.0 * factors.electricity_co2(&Country::Ghana));
+
+        // Every other inventory item is exported or warned about
+        let warnings = header.iter().filter(|line| line.starts_with("{Warning:")).count();
+        let exported: usize = ["Resources", "Emissions to air", "Emissions to water", "Emissions to soil"].iter()
+            .map(|section| sections[section].len())
+            .sum();
+        assert_eq!(exported + warnings, inventory.len());
+        assert!(header.iter().any(|line| line.starts_with("{Warning: no SimaPro substance for Phosphate rock")));
+    }
+
+    #[test]
+    fn export_writes_the_csv_and_needs_the_inventory() {
+        let mut farm = electrified_farm();
+        let dir = std::env::temp_dir().join(format!("simapro-export-{}", uuid::Uuid::new_v4()));
+        let path = export_simapro(&farm, &dir).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), simapro_csv(&farm).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+
+        // A file where the directory should be
+        let blocked = std::env::temp_dir().join(format!("simapro-export-{}", uuid::Uuid::new_v4()));
+        fs::write(&blocked, "").unwrap();
+        let error = export_simapro(&farm, &blocked).unwrap_err();
+        assert!(matches!(error, ExportError::Io { ref path, .. } if *path == blocked.display().to_string()));
+        fs::remove_file(&blocked).unwrap();
+
+        farm.results.as_mut().unwrap().lci_inventory = None;
+        let error = simapro_csv(&farm).unwrap_err();
+        assert!(matches!(error, ExportError::NoInventory { format: "SimaPro" }));
+        assert!(error.to_string().contains("include_inventory"));
+
+        farm.results = None;
+        assert!(matches!(simapro_csv(&farm), Err(ExportError::NotAssessed)));
+    }
+}
@@ -0,0 +1,192 @@
+/*!
+ * Farm to Fork - production linked to processing
+ *
+ * Processing assessments are gate-to-gate: the raw materials arrive without any burden.
+ * FarmToForkEngine assesses the supplying farm first and charges its per-kg impacts, plus
+ * the inbound transport from the facility's raw material sourcing, to every raw material
+ * the farm supplies. The facility's results then cover the chain from cultivation to the
+ * processed product (SystemBoundary::FarmToFork).
+ *
+ * Farm impacts are per kg of the farm's total output (mass allocation across its foods).
+ * A raw material is supplied by the farm when its name contains one of the farm's foods or
+ * crop types, e.g. "Maize kernels" from "Maize".
+ */
+
+use crate::processing::{ProcessingAssessment, ProcessingLCAEngine, UpstreamBurden};
+use crate::production::*;
+use crate::error::EngineError;
+use std::collections::BTreeMap;
+
+pub struct FarmToForkEngine {
+    production: AfricanLCAEngine,
+    processing: ProcessingLCAEngine,
+}
+
+impl FarmToForkEngine {
+    pub fn new(production: AfricanLCAEngine, processing: ProcessingLCAEngine) -> Self {
+        Self { production, processing }
+    }
+
+    /// Assess `farm`, then `facility` with the farm's burdens on the raw materials it
+    /// supplies. Both keep their own results; the returned results are the facility's
+    /// annual totals with farm-only categories (e.g. soil degradation) added for the
+    /// supplied raw materials.
+    pub fn assess(
+        &mut self,
+        farm: &mut Assessment,
+        facility: &mut ProcessingAssessment
+    ) -> Result<LCAResults, EngineError> {
+        self.production.perform_assessment(farm)?;
+        let farm_per_kg = farm.midpoint_impacts_per_kg()
+            .ok_or_else(|| EngineError::NotApplicable("Farm assessment produced no per-kg results - does it have any production?".to_string()))?;
+
+        let supplied: Vec<String> = facility.processed_products.iter()
+            .flat_map(|product| &product.raw_material_inputs)
+            .map(|input| input.material_name.clone())
+            .filter(|material| supplies(farm, material))
+            .collect();
+        if supplied.is_empty() {
+            return Err(EngineError::NotApplicable(format!(
+                "None of {}'s raw materials come from {} - name them after the farm's foods (e.g. \"Maize kernels\" for Maize)",
+                facility.facility_profile.facility_name, farm.company_name
+            )));
+        }
+
+        // Water scarcity is derived at the facility from the combined water consumption
+        let cultivation = UpstreamBurden {
+            per_kg: farm_per_kg.iter()
+                .filter(|(category, _)| category.as_str() != "Water scarcity")
+                .map(|(category, (value, _))| (category.clone(), *value))
+                .collect(),
+            source: format!("cultivation at {}", farm.company_name),
+        };
+        let sourcing = &facility.processing_operations.raw_material_sourcing;
+        let transport = UpstreamBurden {
+            per_kg: BTreeMap::from([(
+                "Global warming".to_string(),
+                sourcing.average_transport_distance / 1000.0 * sourcing.transport_mode.emission_factor_per_tkm(),
+            )]),
+            source: format!("transport ({:.0} km by {:?})", sourcing.average_transport_distance, sourcing.transport_mode),
+        };
+
+        self.processing.clear_upstream_burdens();
+        for material in &supplied {
+            self.processing.add_upstream_burden(material, cultivation.clone());
+            self.processing.add_upstream_burden(material, transport.clone());
+        }
+        facility.methodology.system_boundary = SystemBoundary::FarmToFork;
+        let assessed = self.processing.perform_processing_assessment(facility);
+        self.processing.clear_upstream_burdens();
+        assessed?;
+
+        // Bought from the farm, including what is lost in storage before processing
+        let loss_factor = facility.processing_operations.raw_material_sourcing.storage_practices.loss_factor();
+        let supplied_kg: f64 = facility.processed_products.iter()
+            .flat_map(|product| product.raw_material_inputs.iter()
+                .filter(|input| supplied.contains(&input.material_name))
+                .map(move |input| input.quantity_per_tonne_output * product.annual_production))
+            .sum::<f64>() * loss_factor;
+        let results = facility.results.as_mut().ok_or_else(|| EngineError::NotAssessed("Processing assessment".to_string()))?;
+
+        // Categories only the farm assesses, for the raw materials it supplies
+        let farm_results = farm.results.as_ref().ok_or_else(|| EngineError::NotAssessed("Farm assessment".to_string()))?;
+        for (category, (per_kg, unit)) in &farm_per_kg {
+            if category == "Water scarcity" || results.midpoint_impacts.contains_key(category) {
+                continue;
+            }
+            let value = per_kg * supplied_kg;
+            results.midpoint_impacts.insert(category.clone(), MidpointResult {
+                value,
+                unit: unit.clone(),
+                basis: ImpactBasis::Total,
+                uncertainty_range: (value * 0.7, value * 1.3),
+                geometric_sd: None,
+                data_quality_score: farm_results.midpoint_impacts[category].data_quality_score,
+                contributing_sources: vec![format!(
+                    "Cultivation at {} ({:.0} kg raw material): {:.3} {} per kg",
+                    farm.company_name, supplied_kg, per_kg, unit
+                )],
+                metadata: None,
+            });
+        }
+        for material in facility.processed_products.iter()
+            .flat_map(|product| &product.raw_material_inputs)
+            .filter(|input| !supplied.contains(&input.material_name)) {
+            results.data_quality.warnings.push(format!(
+                "{} does not come from {}; its cultivation is outside the farm-to-fork results",
+                material.material_name, farm.company_name
+            ));
+        }
+
+        Ok(results.clone())
+    }
+}
+
+/// Whether `material` names one of the farm's foods or crop types
+fn supplies(farm: &Assessment, material: &str) -> bool {
+    let material = material.to_lowercase();
+    farm.foods.iter()
+        .flat_map(|food| std::iter::once(&food.name).chain(food.crop_type.as_ref()))
+        .any(|name| !name.is_empty() && material.contains(&name.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{create_comprehensive_input, create_processing_input};
+    use crate::runner::{AssessmentRunner, RunnerConfig};
+
+    const FARM: &str = include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+    const MAIZE_MILL: &str = include_str!("../../app/processing/example_assessment.json");
+
+    fn mill(runner: &AssessmentRunner) -> ProcessingAssessment {
+        let input: serde_json::Value = serde_json::from_str(MAIZE_MILL).unwrap();
+        create_processing_input(&input).unwrap().into_assessment(runner.config())
+    }
+
+    #[test]
+    fn maize_farm_burden_reaches_the_maize_mill() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let mut farm = runner.run_comprehensive(create_comprehensive_input(&serde_json::from_str(FARM).unwrap()).unwrap()).unwrap();
+        let gate_to_gate = runner.run_processing(create_processing_input(&serde_json::from_str(MAIZE_MILL).unwrap()).unwrap())
+            .unwrap().results.unwrap();
+
+        let mut facility = mill(&runner);
+        let results = runner.farm_to_fork_engine(&farm).assess(&mut farm, &mut facility).unwrap();
+        assert_eq!(facility.methodology.system_boundary, SystemBoundary::FarmToFork);
+
+        // 1050 kg maize per tonne of flour, 4000 t of flour, trucked to the mill, plus what is
+        // lost in storage before milling
+        let maize_kg = 1050.0 * 4000.0 * facility.processing_operations.raw_material_sourcing.storage_practices.loss_factor();
+        let farm_gwp_per_kg = farm.midpoint_impacts_per_kg().unwrap()["Global warming"].0;
+        let transport_per_kg = facility.processing_operations.raw_material_sourcing.average_transport_distance / 1000.0 * 0.11;
+        let added = results.midpoint_impacts["Global warming"].value - gate_to_gate.midpoint_impacts["Global warming"].value;
+        assert!(farm_gwp_per_kg > 0.0);
+        assert!((added - maize_kg * (farm_gwp_per_kg + transport_per_kg)).abs() < 1e-6 * added);
+        assert!(results.breakdown_by_food.values().all(|product| product["Global warming"].contributing_sources.iter()
+            .any(|source| source.starts_with("Upstream cultivation at"))));
+        let scopes = results.ghg_scopes.as_ref().unwrap();
+        assert!(scopes.scope_3.sources.iter().any(|source| source.source.starts_with("Upstream cultivation at")));
+        assert!((scopes.total() - results.midpoint_impacts["Global warming"].value).abs() < 1e-9 * scopes.total());
+
+        // The farm's own categories come along for the maize it supplies
+        let soil = &results.midpoint_impacts["Soil degradation"];
+        assert!((soil.value - maize_kg * farm.midpoint_impacts_per_kg().unwrap()["Soil degradation"].0).abs() < 1e-6 * soil.value);
+        assert!(results.single_score.value >= gate_to_gate.single_score.value);
+    }
+
+    #[test]
+    fn unrelated_raw_materials_are_refused() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let mut farm = runner.run_comprehensive(create_comprehensive_input(&serde_json::from_str(FARM).unwrap()).unwrap()).unwrap();
+        let mut facility = mill(&runner);
+        for product in facility.processed_products.iter_mut() {
+            for input in product.raw_material_inputs.iter_mut() {
+                input.material_name = "Sorghum grain".to_string();
+            }
+        }
+
+        let error = runner.farm_to_fork_engine(&farm).assess(&mut farm, &mut facility).unwrap_err();
+        assert!(error.to_string().contains("raw materials come from"));
+    }
+}
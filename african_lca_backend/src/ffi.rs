@@ -0,0 +1,177 @@
+/*!
+ * C interface (feature `ffi`)
+ *
+ * Offline assessments for native apps such as the Android field app (through the NDK).
+ * The JSON in and out is the same as the CLI's. Strings cross the boundary as NUL-terminated
+ * UTF-8; strings returned by the library belong to the caller and go back through
+ * `lca_string_free`. Panics never unwind into the caller: they are caught and reported
+ * as LCA_PANIC.
+ *
+ * The header is include/african_lca.h, generated by cbindgen; after changing this file
+ * regenerate it with `LCA_UPDATE_HEADER=1 cargo test --features ffi --test ffi`.
+ */
+
+use crate::input::*;
+use crate::runner::{AssessmentRunner, RunnerConfig};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+/// The call succeeded
+pub const LCA_OK: i32 = 0;
+/// A required pointer was null or the input was not UTF-8
+pub const LCA_INVALID_ARGUMENT: i32 = 1;
+/// The remaining codes match ErrorCode::exit_code
+pub const LCA_INVALID_JSON: i32 = 2;
+pub const LCA_INVALID_INPUT: i32 = 3;
+pub const LCA_ASSESSMENT_FAILED: i32 = 4;
+pub const LCA_SERIALIZATION_FAILED: i32 = 5;
+/// The library panicked; the runner can still be used
+pub const LCA_PANIC: i32 = 7;
+
+/// Loaded factor databases, opaque to C
+pub struct LcaRunner {
+    runner: AssessmentRunner,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl ToString) {
+    // Interior NULs would truncate the message on the C side
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Load the default factor databases. Returns null on failure; free with `lca_runner_free`.
+#[no_mangle]
+pub extern "C" fn lca_runner_new() -> *mut LcaRunner {
+    match catch_unwind(|| AssessmentRunner::new(RunnerConfig::default())) {
+        Ok(runner) => {
+            clear_last_error();
+            Box::into_raw(Box::new(LcaRunner { runner }))
+        },
+        Err(payload) => {
+            set_last_error(format!("Loading factor databases panicked: {}", panic_message(&*payload)));
+            ptr::null_mut()
+        },
+    }
+}
+
+/// Release a runner from `lca_runner_new`; null is ignored.
+///
+/// # Safety
+/// `runner` must be null or a pointer from `lca_runner_new` not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn lca_runner_free(runner: *mut LcaRunner) {
+    if !runner.is_null() {
+        drop(Box::from_raw(runner));
+    }
+}
+
+/// Run the assessment described by `input_json`. On LCA_OK, `*out_json` receives the results
+/// JSON (free with `lca_string_free`) and `*out_len` its length in bytes without the NUL;
+/// on any other code `*out_json` is null and `lca_last_error_message` says why.
+///
+/// # Safety
+/// `runner` must come from `lca_runner_new`, `input_json` must be NUL-terminated, and
+/// `out_json` and `out_len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn lca_run_assessment(
+    runner: *const LcaRunner,
+    input_json: *const c_char,
+    out_json: *mut *mut c_char,
+    out_len: *mut usize,
+) -> i32 {
+    if out_json.is_null() || out_len.is_null() {
+        set_last_error("out_json and out_len must not be null");
+        return LCA_INVALID_ARGUMENT;
+    }
+    *out_json = ptr::null_mut();
+    *out_len = 0;
+    if runner.is_null() || input_json.is_null() {
+        set_last_error("runner and input_json must not be null");
+        return LCA_INVALID_ARGUMENT;
+    }
+    let input = match CStr::from_ptr(input_json).to_str() {
+        Ok(input) => input,
+        Err(e) => {
+            set_last_error(format!("input_json is not UTF-8: {}", e));
+            return LCA_INVALID_ARGUMENT;
+        },
+    };
+
+    let runner = &(*runner).runner;
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let input: serde_json::Value = serde_json::from_str(input)
+            .map_err(|e| ErrorReport::new(ErrorCode::InvalidJson, e))?;
+        run_input(&runner.with_config(runner_config(&input)), AssessmentKind::detect(&input), &input)
+    }));
+
+    match result {
+        Ok(Ok(json)) => match CString::new(json) {
+            Ok(json) => {
+                clear_last_error();
+                *out_len = json.as_bytes().len();
+                *out_json = json.into_raw();
+                LCA_OK
+            },
+            Err(e) => {
+                set_last_error(format!("Results contain a NUL byte: {}", e));
+                LCA_SERIALIZATION_FAILED
+            },
+        },
+        Ok(Err(e)) => {
+            set_last_error(&e.message);
+            e.code.exit_code()
+        },
+        Err(payload) => {
+            set_last_error(format!("Assessment panicked: {}", panic_message(&*payload)));
+            LCA_PANIC
+        },
+    }
+}
+
+/// Message of the last failed call on this thread, or null after a success. The pointer
+/// stays valid until the next call on the same thread; do not free it.
+#[no_mangle]
+pub extern "C" fn lca_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Release a string returned by the library; null is ignored.
+///
+/// # Safety
+/// `s` must be null or a string from `lca_run_assessment` not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn lca_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_match_the_cli_exit_codes() {
+        assert_eq!(LCA_INVALID_JSON, ErrorCode::InvalidJson.exit_code());
+        assert_eq!(LCA_INVALID_INPUT, ErrorCode::InvalidInput.exit_code());
+        assert_eq!(LCA_ASSESSMENT_FAILED, ErrorCode::AssessmentFailed.exit_code());
+        assert_eq!(LCA_SERIALIZATION_FAILED, ErrorCode::SerializationFailed.exit_code());
+        assert!(LCA_PANIC > ErrorCode::PayloadTooLarge.exit_code());
+    }
+}
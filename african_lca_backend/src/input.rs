@@ -0,0 +1,1689 @@
+/*!
+ * Assessment Input - the JSON format accepted by the CLI and the HTTP server
+ *
+ * Detects which kind of assessment a JSON document describes, parses it into the typed
+ * inputs of `AssessmentRunner`, and runs it. Failures carry an `ErrorCode` so both front
+ * ends report them the same way: as the CLI's exit status or as the server's error body.
+ */
+
+use crate::*;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::ser::{CompactFormatter, Formatter, PrettyFormatter};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use uuid::Uuid;
+
+/// Failure classes shared by the CLI and the HTTP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    InvalidJson,         // not a JSON document
+    InvalidInput,        // JSON that does not describe an assessment
+    AssessmentFailed,    // the engine rejected the assessment
+    SerializationFailed, // results could not be written out
+    PayloadTooLarge,     // server only: body over the configured limit
+}
+
+impl ErrorCode {
+    /// Process exit status of the CLI; 1 stays reserved for usage and file errors
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCode::InvalidJson => 2,
+            ErrorCode::InvalidInput => 3,
+            ErrorCode::AssessmentFailed => 4,
+            ErrorCode::SerializationFailed => 5,
+            ErrorCode::PayloadTooLarge => 6,
+        }
+    }
+}
+
+/// A failure as the CLI prints it and the server returns it. `field` is the path of the
+/// offending input field, when there is one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl ErrorReport {
+    pub fn new(code: ErrorCode, message: impl fmt::Display) -> Self {
+        Self { code, message: message.to_string(), field: None }
+    }
+}
+
+impl fmt::Display for ErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ErrorReport {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssessmentKind {
+    Simple,
+    Comprehensive,
+    Processing,
+    Company,
+}
+
+impl AssessmentKind {
+    /// Kind of assessment an input describes, from the sections it contains
+    pub fn detect(input: &serde_json::Value) -> Self {
+        let is_company = input.get("facilities").and_then(|v| v.as_array()).is_some();
+
+        let is_processing = input.get("facility_profile").is_some() ||
+                           input.get("processing_operations").is_some() ||
+                           input.get("processed_products").is_some();
+
+        let is_comprehensive = input.get("farm_profile").is_some() ||
+                              input.get("management_practices").is_some();
+
+        if is_company {
+            AssessmentKind::Company
+        } else if is_processing {
+            AssessmentKind::Processing
+        } else if is_comprehensive {
+            AssessmentKind::Comprehensive
+        } else {
+            AssessmentKind::Simple
+        }
+    }
+
+    /// Facility endpoints take a single facility or a company of facilities
+    pub fn facility_or_company(self) -> Self {
+        match self {
+            AssessmentKind::Company => AssessmentKind::Company,
+            _ => AssessmentKind::Processing,
+        }
+    }
+
+    /// Name used in progress and error messages
+    pub fn label(self) -> &'static str {
+        match self {
+            AssessmentKind::Simple => "assessment",
+            AssessmentKind::Comprehensive => "comprehensive assessment",
+            AssessmentKind::Processing => "processing assessment",
+            AssessmentKind::Company => "company assessment",
+        }
+    }
+}
+
+/// Run settings carried in the input alongside the assessment
+pub fn runner_config(input: &serde_json::Value) -> RunnerConfig {
+    RunnerConfig {
+        seed: input.get("seed").and_then(|v| v.as_u64()),
+        include_inventory: input.get("include_inventory").and_then(|v| v.as_bool()).unwrap_or(true),
+        include_capital_goods: input.get("include_capital_goods").and_then(|v| v.as_bool()).unwrap_or(false),
+        modeling_approach: input.get("modeling_approach")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default(),
+        impact_categories: input.get("impact_categories")
+            .and_then(|v| serde_json::from_value(v.clone()).ok()),
+        plausibility: input.get("plausibility")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default(),
+        merge_duplicate_foods: input.get("merge_duplicate_foods").and_then(|v| v.as_bool()).unwrap_or(true),
+        include_result_metadata: input.get("include_result_metadata").and_then(|v| v.as_bool()).unwrap_or(false),
+        exchange_rates: input.get("exchange_rates")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default(),
+    }
+}
+
+/// Parse an input of the given kind and check its plausibility without running it
+pub fn validate_input(kind: AssessmentKind, input: &serde_json::Value) -> Result<(), ErrorReport> {
+    let plausibility = runner_config(input).plausibility;
+    let violations = match kind {
+        AssessmentKind::Simple => plausibility.check_farm(&create_simple_input(input)?.foods, None),
+        AssessmentKind::Comprehensive => {
+            let farm = create_comprehensive_input(input)?;
+            plausibility.check_farm(&farm.foods, farm.management_practices.as_ref())
+        }
+        AssessmentKind::Processing => {
+            let facility = create_processing_input(input)?;
+            plausibility.check_facility(&facility.facility_profile, &facility.processed_products, "")
+        }
+        AssessmentKind::Company => create_company_assessment(input)?.facilities.iter()
+            .enumerate()
+            .flat_map(|(i, facility)| plausibility.check_facility(
+                &facility.facility_profile, &facility.processed_products, &format!("facilities[{}]", i)
+            ))
+            .collect(),
+    };
+    plausibility.enforce(violations)?;
+    Ok(())
+}
+
+/// Parse, run and serialize an input of the given kind, as pretty-printed JSON
+pub fn run_input(runner: &AssessmentRunner, kind: AssessmentKind, input: &serde_json::Value) -> Result<String, ErrorReport> {
+    run_serialized(runner, kind, input, true)
+}
+
+/// As `run_input`, with the JSON on a single line
+pub fn run_input_compact(runner: &AssessmentRunner, kind: AssessmentKind, input: &serde_json::Value) -> Result<String, ErrorReport> {
+    run_serialized(runner, kind, input, false)
+}
+
+/// Results are rounded to the significant figures their uncertainty supports, unless the
+/// input asks for `"full_precision": true`
+fn run_serialized(runner: &AssessmentRunner, kind: AssessmentKind, input: &serde_json::Value, pretty: bool) -> Result<String, ErrorReport> {
+    let targets = parse_targets(input)?;
+    let full_precision = input.get("full_precision").and_then(|v| v.as_bool()).unwrap_or(false);
+    let display = |results: Option<&mut LCAResults>| {
+        if let Some(results) = results.filter(|_| !full_precision) {
+            results.round_for_display();
+        }
+    };
+
+    let output = match kind {
+        AssessmentKind::Simple => {
+            let mut assessment = runner.run_simple(create_simple_input(input)?)?;
+            if let Some(ref targets) = targets {
+                assessment.check_targets(targets)?;
+            }
+            display(assessment.results.as_mut());
+            to_json(&assessment, pretty)
+        }
+        AssessmentKind::Comprehensive => {
+            let mut assessment = runner.run_comprehensive(create_comprehensive_input(input)?)?;
+            if let Some(ref targets) = targets {
+                assessment.check_targets(targets)?;
+            }
+            display(assessment.results.as_mut());
+            to_json(&assessment, pretty)
+        }
+        AssessmentKind::Processing => {
+            let mut assessment = runner.run_processing(create_processing_input(input)?)?;
+            if let Some(ref targets) = targets {
+                assessment.check_targets(targets)?;
+            }
+            display(assessment.results.as_mut());
+            to_json(&assessment, pretty)
+        }
+        AssessmentKind::Company => {
+            let mut company = runner.run_company(create_company_assessment(input)?)?;
+            for facility in &mut company.facilities {
+                display(facility.results.as_mut());
+            }
+            if let Some(results) = company.results.as_mut().filter(|_| !full_precision) {
+                results.round_for_display();
+            }
+            to_json(&company, pretty)
+        }
+    };
+    output.map_err(|e| ErrorReport::new(ErrorCode::SerializationFailed, e))
+}
+
+/// Significant digits every number is written with, so reruns and archived reports only
+/// differ where a result really changed, not in the last bits of a float
+pub const SIGNIFICANT_DIGITS: usize = 10;
+
+/// Serialize results as the CLI and server write them: maps are sorted by key and floats
+/// rounded to `SIGNIFICANT_DIGITS`, so the same input always gives the same bytes
+pub fn to_json<T: Serialize>(value: &T, pretty: bool) -> serde_json::Result<String> {
+    let mut json = Vec::new();
+    if pretty {
+        value.serialize(&mut serde_json::Serializer::with_formatter(&mut json, NormalizedFloats(PrettyFormatter::new())))?;
+    } else {
+        value.serialize(&mut serde_json::Serializer::with_formatter(&mut json, NormalizedFloats(CompactFormatter)))?;
+    }
+    Ok(String::from_utf8(json).expect("serde_json output is UTF-8"))
+}
+
+/// Rounds floats to `SIGNIFICANT_DIGITS` before the wrapped formatter writes them
+struct NormalizedFloats<F>(F);
+
+impl<F: Formatter> Formatter for NormalizedFloats<F> {
+    fn write_f32<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f32) -> io::Result<()> {
+        self.write_f64(writer, value as f64)
+    }
+
+    fn write_f64<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        let rounded: f64 = format!("{:.*e}", SIGNIFICANT_DIGITS - 1, value).parse().unwrap_or(value);
+        // -0.0 and 0.0 are the same result
+        self.0.write_f64(writer, if rounded == 0.0 { 0.0 } else { rounded })
+    }
+
+    fn begin_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_array(writer)
+    }
+
+    fn end_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_array(writer)
+    }
+
+    fn begin_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W, first: bool) -> io::Result<()> {
+        self.0.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_array_value(writer)
+    }
+
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_object(writer)
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object(writer)
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W, first: bool) -> io::Result<()> {
+        self.0.begin_object_key(writer, first)
+    }
+
+    fn end_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object_key(writer)
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_object_value(writer)
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object_value(writer)
+    }
+}
+
+/// Path of `key` within the object at `path`, as reported in errors ("" is the document)
+fn field(path: &str, key: &str) -> String {
+    if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) }
+}
+
+/// Path of the `index`th element of the array at `path`
+fn element(path: &str, index: usize) -> String {
+    format!("{}[{}]", path, index)
+}
+
+/// The first of `keys` (a field and its aliases) that is present and not null, with its path
+fn required<'a>(value: &'a serde_json::Value, path: &str, keys: &[&str]) -> Result<(&'a serde_json::Value, String), InputError> {
+    keys.iter()
+        .find_map(|key| value.get(*key).filter(|v| !v.is_null()).map(|v| (v, field(path, key))))
+        .ok_or_else(|| InputError::Missing { field: field(path, keys[0]) })
+}
+
+fn wrong_type(field: String, expected: &'static str, value: &serde_json::Value) -> InputError {
+    InputError::WrongType { field, expected, value: value.to_string() }
+}
+
+fn required_str<'a>(value: &'a serde_json::Value, path: &str, keys: &[&str]) -> Result<&'a str, InputError> {
+    let (v, field) = required(value, path, keys)?;
+    v.as_str().ok_or_else(|| wrong_type(field, "a string", v))
+}
+
+fn required_f64(value: &serde_json::Value, path: &str, keys: &[&str]) -> Result<f64, InputError> {
+    let (v, field) = required(value, path, keys)?;
+    v.as_f64().ok_or_else(|| wrong_type(field, "a number", v))
+}
+
+fn required_array<'a>(value: &'a serde_json::Value, path: &str, key: &str) -> Result<&'a Vec<serde_json::Value>, InputError> {
+    let (v, field) = required(value, path, &[key])?;
+    v.as_array().ok_or_else(|| wrong_type(field, "an array", v))
+}
+
+/// The option named `value`; otherwise an error naming the valid ones
+fn one_of<T: Clone>(field: String, value: &str, options: &[(&str, T)]) -> Result<T, InputError> {
+    options.iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, option)| option.clone())
+        .ok_or_else(|| InputError::UnknownValue {
+            field,
+            value: value.to_string(),
+            valid: options.iter().map(|(name, _)| name.to_string()).collect(),
+        })
+}
+
+/// Optional per-functional-unit limits by midpoint category ("targets": {"Global warming": 0.5}),
+/// per kg for farms and per tonne for facilities
+fn parse_targets(input: &serde_json::Value) -> Result<Option<HashMap<String, f64>>, InputError> {
+    let Some(targets) = input.get("targets") else {
+        return Ok(None);
+    };
+    let targets = targets.as_object()
+        .ok_or_else(|| wrong_type("targets".to_string(), "an object of limits by impact category", targets))?;
+    targets.iter()
+        .map(|(category, limit)| limit.as_f64()
+            .map(|limit| (category.clone(), limit))
+            .ok_or_else(|| wrong_type(field("targets", category), "a number", limit)))
+        .collect::<Result<HashMap<_, _>, _>>()
+        .map(Some)
+}
+
+fn parse_country(field: String, s: &str) -> Result<Country, InputError> {
+    one_of(field, s, &[
+        ("Ghana", Country::Ghana),
+        ("Nigeria", Country::Nigeria),
+        ("Global", Country::Global),
+    ])
+}
+
+pub fn create_comprehensive_input(input: &serde_json::Value) -> Result<ComprehensiveInput, InputError> {
+    let company_name = required_str(input, "", &["company_name"])?.to_string();
+    let country_str = required_str(input, "", &["country"])?;
+    let country = parse_country("country".to_string(), country_str)?;
+
+    let region = input["region"].as_str().map(|s| s.to_string());
+    let coordinates = parse_coordinates(input)?;
+
+    // Parse farm profile if present
+    let farm_profile = if let Some(fp) = input.get("farm_profile") {
+        Some(FarmProfile {
+            farmer_name: fp["farmer_name"].as_str().unwrap_or("").to_string(),
+            farm_name: fp["farm_name"].as_str().unwrap_or("").to_string(),
+            total_farm_size: fp["total_farm_size"].as_f64().unwrap_or(0.0),
+            farming_experience: fp["farming_experience"].as_u64().unwrap_or(0) as u32,
+            farm_type: parse_farm_type(
+                "farm_profile.farm_type".to_string(),
+                fp["farm_type"].as_str().unwrap_or("Smallholder")
+            )?,
+            primary_farming_system: parse_farming_system(
+                "farm_profile.primary_farming_system".to_string(),
+                fp["primary_farming_system"].as_str().unwrap_or("Subsistence")
+            )?,
+            certifications: fp["certifications"].as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+            participates_in_programs: fp["participates_in_programs"].as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+        })
+    } else {
+        None
+    };
+
+    // Parse management practices if present
+    let management_practices = if let Some(mp) = input.get("management_practices") {
+        Some(parse_management_practices(mp)?)
+    } else {
+        None
+    };
+
+    // Parse equipment_energy if present
+    let equipment_energy = if let Some(ee) = input.get("equipment_energy") {
+        Some(parse_equipment_energy(ee)?)
+    } else {
+        None
+    };
+
+    // Parse foods
+    let foods = required_array(input, "", "foods")?.iter()
+        .enumerate()
+        .map(|(i, food_value)| parse_food_item(food_value, &element("foods", i), country_str))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ComprehensiveInput {
+        company_name,
+        currency: optional_currency(input, "")?,
+        country,
+        region,
+        coordinates,
+        foods,
+        weighting_method: parse_weighting_method(input)?,
+        farm_profile,
+        management_practices,
+        equipment_energy,
+    })
+}
+
+pub fn create_simple_input(input: &serde_json::Value) -> Result<SimpleInput, InputError> {
+    let company_name = required_str(input, "", &["company_name"])?.to_string();
+    let country = parse_country("country".to_string(), required_str(input, "", &["country"])?)?;
+
+    let mut foods = Vec::new();
+    for (i, food_value) in required_array(input, "", "foods")?.iter().enumerate() {
+        let path = element("foods", i);
+        let food = FoodItem {
+            id: required_str(food_value, &path, &["id"])?.to_string(),
+            name: required_str(food_value, &path, &["name"])?.to_string(),
+            quantity_kg: required_f64(food_value, &path, &["quantity_kg"])?,
+            category: parse_food_category(
+                field(&path, "category"),
+                required_str(food_value, &path, &["category"])?
+            )?,
+            crop_type: food_value["crop_type"].as_str().map(|s| s.to_string()),
+            origin_country: food_value["origin_country"]
+                .as_str()
+                .map(|s| s.to_string()),
+            production_system: None,
+            seasonal_factor: None,
+            variety: None,
+            area_allocated: None,
+            cropping_pattern: None,
+            intercropping_partners: None,
+            post_harvest_losses: None,
+            aquaculture: None,
+            poultry: None,
+            dairy: None,
+            perennial: None,
+            head_count: None,
+        };
+        foods.push(food);
+    }
+
+    Ok(SimpleInput {
+        company_name,
+        currency: optional_currency(input, "")?,
+        country,
+        coordinates: parse_coordinates(input)?,
+        foods,
+        weighting_method: parse_weighting_method(input)?,
+    })
+}
+
+// Helper parsing functions
+/// `weighting_method` is either a method name or an object of endpoint weights
+fn parse_weighting_method(input: &serde_json::Value) -> Result<WeightingMethod, InputError> {
+    let method = match input.get("weighting_method") {
+        None | Some(serde_json::Value::Null) => WeightingMethod::AfricanPriorities,
+        Some(serde_json::Value::Object(weights)) => WeightingMethod::CustomWeights(
+            weights.iter()
+                .map(|(category, w)| w.as_f64()
+                    .map(|w| (category.clone(), w))
+                    .ok_or_else(|| wrong_type(field("weighting_method", category), "a number", w)))
+                .collect::<Result<_, _>>()?
+        ),
+        Some(name) => {
+            let name_str = name.as_str()
+                .ok_or_else(|| wrong_type("weighting_method".to_string(), "a method name or an object of weights", name))?;
+            one_of("weighting_method".to_string(), name_str, &[
+                ("AfricanPriorities", WeightingMethod::AfricanPriorities),
+                ("EqualWeights", WeightingMethod::EqualWeights),
+                ("ExpertJudgment", WeightingMethod::ExpertJudgment),
+                ("SocialPreferences", WeightingMethod::SocialPreferences),
+                ("None", WeightingMethod::None),
+            ])?
+        }
+    };
+    method.validate()?;
+    Ok(method)
+}
+
+/// Farm location as top-level `latitude`/`longitude`; both must be given
+fn parse_coordinates(input: &serde_json::Value) -> Result<Option<GeoCoordinates>, InputError> {
+    match (input.get("latitude").and_then(|v| v.as_f64()), input.get("longitude").and_then(|v| v.as_f64())) {
+        (Some(latitude), Some(longitude)) => {
+            if !(-90.0..=90.0).contains(&latitude) {
+                return Err(InputError::invalid("latitude", format!("must be between -90 and 90, got {}", latitude)));
+            }
+            if !(-180.0..=180.0).contains(&longitude) {
+                return Err(InputError::invalid("longitude", format!("must be between -180 and 180, got {}", longitude)));
+            }
+            Ok(Some(GeoCoordinates { latitude, longitude }))
+        }
+        (None, None) => Ok(None),
+        (Some(_), None) => Err(InputError::Missing { field: "longitude".to_string() }),
+        (None, Some(_)) => Err(InputError::Missing { field: "latitude".to_string() }),
+    }
+}
+
+fn parse_food_category(field: String, s: &str) -> Result<FoodCategory, InputError> {
+    one_of(field, s, &[
+        ("Cereals", FoodCategory::Cereals),
+        ("Legumes", FoodCategory::Legumes),
+        ("Vegetables", FoodCategory::Vegetables),
+        ("Fruits", FoodCategory::Fruits),
+        ("Meat", FoodCategory::Meat),
+        ("Poultry", FoodCategory::Poultry),
+        ("Fish", FoodCategory::Fish),
+        ("Dairy", FoodCategory::Dairy),
+        ("Eggs", FoodCategory::Eggs),
+        ("Oils", FoodCategory::Oils),
+        ("Nuts", FoodCategory::Nuts),
+        ("Roots", FoodCategory::Roots),
+        ("Other", FoodCategory::Other),
+    ])
+}
+
+fn parse_farm_type(field: String, s: &str) -> Result<FarmType, InputError> {
+    one_of(field, s, &[
+        ("Smallholder", FarmType::Smallholder),
+        ("SmallScale", FarmType::SmallScale),
+        ("MediumScale", FarmType::MediumScale),
+        ("Commercial", FarmType::Commercial),
+        ("Cooperative", FarmType::Cooperative),
+        ("MixedLivestock", FarmType::MixedLivestock),
+    ])
+}
+
+fn parse_farming_system(field: String, s: &str) -> Result<FarmingSystem, InputError> {
+    one_of(field, s, &[
+        ("Subsistence", FarmingSystem::Subsistence),
+        ("SemiCommercial", FarmingSystem::SemiCommercial),
+        ("Commercial", FarmingSystem::Commercial),
+        ("Organic", FarmingSystem::Organic),
+        ("Agroecological", FarmingSystem::Agroecological),
+        ("Conventional", FarmingSystem::Conventional),
+        ("IntegratedFarming", FarmingSystem::IntegratedFarming),
+    ])
+}
+
+fn parse_food_item(food_value: &serde_json::Value, path: &str, country: &str) -> Result<FoodItem, InputError> {
+    Ok(FoodItem {
+        id: required_str(food_value, path, &["crop_id", "id"])?.to_string(),
+        name: required_str(food_value, path, &["crop_name", "name"])?.to_string(),
+        quantity_kg: required_f64(food_value, path, &["annual_production", "quantity_kg"])?,
+        category: parse_food_category(
+            field(path, "category"),
+            required_str(food_value, path, &["category"])?
+        )?,
+        crop_type: food_value.get("variety")
+            .or_else(|| food_value.get("crop_type"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        origin_country: Some(country.to_string()),
+        production_system: food_value.get("production_system")
+            .and_then(|v| v.as_str())
+            .and_then(|s| parse_production_system(field(path, "production_system"), s).ok()),
+        seasonal_factor: None,
+        variety: food_value.get("variety")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        area_allocated: food_value.get("area_allocated")
+            .and_then(|v| v.as_f64()),
+        cropping_pattern: food_value.get("cropping_pattern")
+            .and_then(|v| v.as_str())
+            .and_then(|s| parse_cropping_pattern(field(path, "cropping_pattern"), s).ok()),
+        intercropping_partners: food_value.get("intercropping_partners")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect()),
+        post_harvest_losses: food_value.get("post_harvest_losses")
+            .and_then(|v| v.as_f64()),
+        aquaculture: food_value.get("aquaculture")
+            .filter(|v| !v.is_null())
+            .map(|aq| parse_aquaculture_details(aq, &field(path, "aquaculture")))
+            .transpose()?,
+        poultry: food_value.get("poultry")
+            .filter(|v| !v.is_null())
+            .map(|p| parse_poultry_profile(p, &field(path, "poultry")))
+            .transpose()?,
+        dairy: food_value.get("dairy")
+            .filter(|v| !v.is_null())
+            .map(|d| parse_dairy_herd_profile(d, &field(path, "dairy")))
+            .transpose()?,
+        perennial: food_value.get("perennial")
+            .filter(|v| !v.is_null())
+            .map(|p| parse_perennial_crop_profile(p, &field(path, "perennial")))
+            .transpose()?,
+        head_count: food_value.get("head_count")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as u32),
+    })
+}
+
+fn parse_aquaculture_details(aq: &serde_json::Value, path: &str) -> Result<AquacultureDetails, InputError> {
+    Ok(AquacultureDetails {
+        system: parse_aquaculture_system(
+            field(path, "system"),
+            aq.get("system").and_then(|v| v.as_str()).unwrap_or("EarthenPond")
+        )?,
+        feed_conversion_ratio: required_f64(aq, path, &["feed_conversion_ratio"])?,
+        feed_protein_percent: aq.get("feed_protein_percent").and_then(|v| v.as_f64()),
+        feed_phosphorus_percent: aq.get("feed_phosphorus_percent").and_then(|v| v.as_f64()),
+        effluent_treatment: aq.get("effluent_treatment").and_then(|v| v.as_bool()).unwrap_or(false),
+        feed_type: aq.get("feed_type")
+            .and_then(|v| v.as_str())
+            .map(|s| parse_aqua_feed_type(field(path, "feed_type"), s))
+            .transpose()?,
+        aeration_hours_per_day: aq.get("aeration_hours_per_day").and_then(|v| v.as_f64()),
+        water_exchange_rate: aq.get("water_exchange_rate").and_then(|v| v.as_f64()),
+    })
+}
+
+fn parse_aqua_feed_type(field: String, s: &str) -> Result<AquaFeedType, InputError> {
+    one_of(field, s, &[
+        ("CommercialPellets", AquaFeedType::CommercialPellets),
+        ("FarmMade", AquaFeedType::FarmMade),
+        ("HighFishmeal", AquaFeedType::HighFishmeal),
+    ])
+}
+
+fn parse_aquaculture_system(field: String, s: &str) -> Result<AquacultureSystem, InputError> {
+    one_of(field, s, &[
+        ("EarthenPond", AquacultureSystem::EarthenPond),
+        ("ConcreteTank", AquacultureSystem::ConcreteTank),
+        ("Cage", AquacultureSystem::Cage),
+        ("RecirculatingSystem", AquacultureSystem::RecirculatingSystem),
+        ("RAS", AquacultureSystem::RecirculatingSystem),
+    ])
+}
+
+fn parse_poultry_profile(p: &serde_json::Value, path: &str) -> Result<PoultryProfile, InputError> {
+    let (flock_size, flock_size_field) = required(p, path, &["flock_size"])?;
+    Ok(PoultryProfile {
+        system: parse_poultry_system(
+            field(path, "system"),
+            p.get("system").and_then(|v| v.as_str()).unwrap_or("DeepLitter")
+        )?,
+        flock_size: flock_size.as_u64()
+            .ok_or_else(|| wrong_type(flock_size_field, "a whole number", flock_size))? as u32,
+        feed_conversion_ratio: p.get("feed_conversion_ratio").and_then(|v| v.as_f64()),
+        feed_protein_percent: p.get("feed_protein_percent").and_then(|v| v.as_f64()),
+        manure_system: p.get("manure_system")
+            .and_then(|v| v.as_str())
+            .map(|s| parse_poultry_manure_system(field(path, "manure_system"), s))
+            .transpose()?,
+    })
+}
+
+fn parse_poultry_system(field: String, s: &str) -> Result<PoultrySystem, InputError> {
+    one_of(field, s, &[
+        ("Battery", PoultrySystem::Battery),
+        ("BatteryCage", PoultrySystem::Battery),
+        ("DeepLitter", PoultrySystem::DeepLitter),
+        ("FreeRange", PoultrySystem::FreeRange),
+    ])
+}
+
+fn parse_poultry_manure_system(field: String, s: &str) -> Result<PoultryManureSystem, InputError> {
+    one_of(field, s, &[
+        ("Litter", PoultryManureSystem::Litter),
+        ("Slurry", PoultryManureSystem::Slurry),
+        ("Range", PoultryManureSystem::Range),
+    ])
+}
+
+fn parse_dairy_herd_profile(d: &serde_json::Value, path: &str) -> Result<DairyHerdProfile, InputError> {
+    let (herd_size, herd_size_field) = required(d, path, &["herd_size"])?;
+    Ok(DairyHerdProfile {
+        herd_size: herd_size.as_u64()
+            .ok_or_else(|| wrong_type(herd_size_field, "a whole number", herd_size))? as u32,
+        milk_yield_kg_per_cow: required_f64(d, path, &["milk_yield_kg_per_cow"])?,
+        feed_intake_kg_dm_per_day: d.get("feed_intake_kg_dm_per_day").and_then(|v| v.as_f64()),
+        feed_digestibility_percent: d.get("feed_digestibility_percent").and_then(|v| v.as_f64()),
+        feed_protein_percent: d.get("feed_protein_percent").and_then(|v| v.as_f64()),
+        concentrate_kg_per_day: d.get("concentrate_kg_per_day").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        manure_system: d.get("manure_system")
+            .and_then(|v| v.as_str())
+            .map(|s| parse_dairy_manure_system(field(path, "manure_system"), s))
+            .transpose()?,
+    })
+}
+
+fn parse_perennial_crop_profile(p: &serde_json::Value, path: &str) -> Result<PerennialCropProfile, InputError> {
+    let (age, age_field) = required(p, path, &["plantation_age_years"])?;
+    let establishment = p.get("establishment_inputs").unwrap_or(&serde_json::Value::Null);
+    Ok(PerennialCropProfile {
+        plantation_age_years: age.as_u64()
+            .ok_or_else(|| wrong_type(age_field, "a whole number", age))? as u32,
+        expected_productive_lifetime_years: p.get("expected_productive_lifetime_years").and_then(|v| v.as_f64()),
+        establishment_inputs: EstablishmentInputs {
+            immature_years: establishment.get("immature_years").and_then(|v| v.as_u64()).map(|years| years as u32),
+            seedlings_per_ha: establishment.get("seedlings_per_ha").and_then(|v| v.as_f64()),
+            land_preparation_diesel_l_per_ha: establishment.get("land_preparation_diesel_l_per_ha")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0),
+            fertilizer_n_kg_per_ha_per_year: establishment.get("fertilizer_n_kg_per_ha_per_year")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0),
+        },
+        shade_system: p.get("shade_system").and_then(|v| v.as_bool()).unwrap_or(false),
+    })
+}
+
+fn parse_dairy_manure_system(field: String, s: &str) -> Result<DairyManureSystem, InputError> {
+    one_of(field, s, &[
+        ("Pasture", DairyManureSystem::Pasture),
+        ("SolidStorage", DairyManureSystem::SolidStorage),
+        ("DailySpread", DairyManureSystem::DailySpread),
+        ("Liquid", DairyManureSystem::Liquid),
+    ])
+}
+
+fn parse_production_system(field: String, s: &str) -> Result<ProductionSystem, InputError> {
+    one_of(field, s, &[
+        ("Rainfed", ProductionSystem::Rainfed),
+        ("Irrigated", ProductionSystem::Irrigated),
+        ("Smallholder", ProductionSystem::Smallholder),
+        ("Intensive", ProductionSystem::Intensive),
+        ("Extensive", ProductionSystem::Extensive),
+        ("Agroforestry", ProductionSystem::Agroforestry),
+        ("Organic", ProductionSystem::Organic),
+        ("Conventional", ProductionSystem::Conventional),
+    ])
+}
+
+fn parse_cropping_pattern(field: String, s: &str) -> Result<CroppingPattern, InputError> {
+    one_of(field, s, &[
+        ("Monoculture", CroppingPattern::Monoculture),
+        ("Intercropping", CroppingPattern::Intercropping),
+        ("RelayCropping", CroppingPattern::RelayCropping),
+        ("Agroforestry", CroppingPattern::Agroforestry),
+        ("CropRotation", CroppingPattern::CropRotation),
+    ])
+}
+
+fn parse_pump_type(field: String, s: &str) -> Result<PumpType, InputError> {
+    one_of(field, s, &[
+        ("Diesel", PumpType::Diesel),
+        ("Electric", PumpType::Electric),
+        ("Solar", PumpType::Solar),
+        ("Gravity", PumpType::Gravity),
+    ])
+}
+
+fn parse_management_practices(mp: &serde_json::Value) -> Result<ManagementPractices, InputError> {
+    let soil_mgmt = mp.get("soil_management").unwrap_or(&serde_json::Value::Null);
+    let fertilization = mp.get("fertilization").filter(|v| !v.is_null()); // None: not answered
+    let water_mgmt = mp.get("water_management").unwrap_or(&serde_json::Value::Null);
+    let pest_mgmt = mp.get("pest_management").unwrap_or(&serde_json::Value::Null);
+    
+    Ok(ManagementPractices {
+        soil_management: SoilManagement {
+            soil_type: soil_mgmt.get("soil_type")
+                .and_then(|v| v.as_str())
+                .and_then(|s| parse_soil_type("management_practices.soil_management.soil_type".to_string(), s).ok()),
+            uses_compost: soil_mgmt.get("compost_use")
+                .and_then(|cu| cu.get("uses_compost"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            compost_source: soil_mgmt.get("compost_use")
+                .and_then(|cu| cu.get("compost_source"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            conservation_practices: soil_mgmt.get("conservation_practices")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+            soil_testing_frequency: soil_mgmt.get("soil_testing_frequency")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        },
+        fertilization: fertilization.map(parse_fertilization).transpose()?,
+        water_management: crate::models::WaterManagement {
+            water_source: water_mgmt.get("water_source")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+            irrigation_system: water_mgmt.get("irrigation_system")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            water_conservation_practices: water_mgmt.get("water_conservation_practices")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+            pump_type: water_mgmt.get("pump_type")
+                .and_then(|v| v.as_str())
+                .map(|s| parse_pump_type("management_practices.water_management.pump_type".to_string(), s))
+                .transpose()?,
+            lift_height_m: water_mgmt.get("lift_height_m").and_then(|v| v.as_f64()),
+        },
+        pest_management: PestManagement {
+            management_approach: pest_mgmt.get("management_approach")
+                .and_then(|v| v.as_str())
+                .unwrap_or("IntegratedIPM")
+                .to_string(),
+            uses_ipm: pest_mgmt.get("uses_ipm")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            pesticides_used: pest_mgmt.get("pesticides")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|app| {
+                    Some(PesticideApplication {
+                        pesticide_type: app.get("pesticide_type")?.as_str()?.to_string(),
+                        active_ingredient: app.get("active_ingredient")?.as_str()?.to_string(),
+                        application_rate: app.get("application_rate")?.as_f64()?,
+                        applications_per_season: app.get("applications_per_season")?.as_u64()? as u32,
+                        target_pests: app.get("target_pests")
+                            .and_then(|v| v.as_array())
+                            .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+                            .unwrap_or_default(),
+                        treated_area_ha: app.get("treated_area_ha").and_then(|v| v.as_f64()),
+                    })
+                }).collect())
+                .unwrap_or_default(),
+            monitoring_frequency: pest_mgmt.get("pest_monitoring_frequency")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        },
+    })
+}
+
+/// A fertilization section that was answered. Without `uses_fertilizers`, fertilizer use is
+/// taken from whether any applications are listed.
+fn parse_fertilization(fertilization: &serde_json::Value) -> Result<FertilizationPractices, InputError> {
+    let fertilizer_applications: Vec<FertilizerApplication> = fertilization.get("fertilizer_applications")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().enumerate().filter_map(|(i, app)| {
+            let application = FertilizerApplication {
+                fertilizer_type: app.get("fertilizer_type")?.as_str()?.to_string(),
+                npk_ratio: app.get("npk_ratio").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                application_rate: app.get("application_rate")?.as_f64()?,
+                applications_per_season: app.get("applications_per_season")?.as_u64()? as u32,
+                cost: app.get("cost").and_then(|v| v.as_f64()),
+                currency: None,
+                treated_area_ha: app.get("treated_area_ha").and_then(|v| v.as_f64()),
+            };
+            let path = element("management_practices.fertilization.fertilizer_applications", i);
+            Some(optional_currency(app, &path).map(|currency| FertilizerApplication { currency, ..application }))
+        }).collect::<Result<Vec<_>, _>>())
+        .transpose()?
+        .unwrap_or_default();
+    Ok(FertilizationPractices {
+        uses_fertilizers: fertilization.get("uses_fertilizers")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(!fertilizer_applications.is_empty()),
+        fertilizer_applications,
+        soil_test_based: fertilization.get("soil_test_based")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        follows_nutrient_plan: fertilization.get("follows_nutrient_plan")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+    })
+}
+
+fn parse_soil_type(field: String, s: &str) -> Result<SoilType, InputError> {
+    one_of(field, s, &[
+        ("Sandy", SoilType::Sandy),
+        ("Clay", SoilType::Clay),
+        ("Loam", SoilType::Loam),
+        ("SandyLoam", SoilType::SandyLoam),
+        ("ClayLoam", SoilType::ClayLoam),
+        ("SiltLoam", SoilType::SiltLoam),
+        ("Lateritic", SoilType::Lateritic),
+        ("Volcanic", SoilType::Volcanic),
+    ])
+}
+
+fn parse_currency(field: String, s: &str) -> Result<Currency, InputError> {
+    s.parse().map_err(|_| InputError::UnknownValue {
+        field,
+        value: s.to_string(),
+        valid: ["GHS", "NGN", "USD"].map(String::from).to_vec(),
+    })
+}
+
+/// The optional `currency` of the object at `path`: at the top level, the one costs are
+/// reported in (the runner falls back to the country's own); on a cost line item, the one it
+/// was paid in. An unknown code is an error rather than a cost taken to be in another currency.
+fn optional_currency(value: &serde_json::Value, path: &str) -> Result<Option<Currency>, InputError> {
+    let field = field(path, "currency");
+    value.get("currency")
+        .filter(|v| !v.is_null())
+        .map(|v| parse_currency(field.clone(), v.as_str().ok_or_else(|| wrong_type(field.clone(), "a string", v))?))
+        .transpose()
+}
+
+fn parse_equipment_energy(ee: &serde_json::Value) -> Result<EquipmentEnergy, InputError> {
+    let equipment = ee.get("equipment")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|eq| {
+            Some(FarmEquipment {
+                equipment_type: eq.get("equipment_type")?.as_str()?.to_string(),
+                power_source: eq.get("power_source")?.as_str()?.to_string(),
+                age: eq.get("age")?.as_u64()? as u32,
+                hours_per_year: eq.get("hours_per_year")?.as_f64()?,
+                fuel_efficiency: eq.get("fuel_efficiency").and_then(|v| v.as_f64()),
+            })
+        }).collect())
+        .unwrap_or_default();
+
+    let energy_sources = ee.get("energy_sources")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().enumerate().filter_map(|(i, es)| {
+            let usage = EnergyUsage {
+                energy_type: es.get("energy_type")?.as_str()?.to_string(),
+                monthly_consumption: es.get("monthly_consumption")?.as_f64()?,
+                primary_use: es.get("primary_use")?.as_str()?.to_string(),
+                cost: es.get("cost").and_then(|v| v.as_f64()),
+                currency: None,
+            };
+            let path = element("equipment_energy.energy_sources", i);
+            Some(optional_currency(es, &path).map(|currency| EnergyUsage { currency, ..usage }))
+        }).collect::<Result<Vec<_>, _>>())
+        .transpose()?
+        .unwrap_or_default();
+
+    let fuel_consumption = ee.get("fuel_consumption")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().enumerate().filter_map(|(i, fc)| {
+            let usage = FuelUsage {
+                fuel_type: fc.get("fuel_type")?.as_str()?.to_string(),
+                monthly_consumption: fc.get("monthly_consumption")?.as_f64()?,
+                primary_use: fc.get("primary_use")?.as_str()?.to_string(),
+                cost: fc.get("cost").and_then(|v| v.as_f64()),
+                currency: None,
+            };
+            let path = element("equipment_energy.fuel_consumption", i);
+            Some(optional_currency(fc, &path).map(|currency| FuelUsage { currency, ..usage }))
+        }).collect::<Result<Vec<_>, _>>())
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(EquipmentEnergy {
+        equipment,
+        energy_sources,
+        fuel_consumption,
+    })
+}
+
+pub fn create_company_assessment(input: &serde_json::Value) -> Result<CompanyAssessment, InputError> {
+    let facilities_array = required_array(input, "", "facilities")?;
+
+    let mut facilities = Vec::new();
+    for (i, facility_value) in facilities_array.iter().enumerate() {
+        // Sites inherit company-wide settings they don't override
+        let mut facility_input = facility_value.clone();
+        if let Some(obj) = facility_input.as_object_mut() {
+            for key in ["country", "currency", "region", "include_inventory", "modeling_approach", "impact_categories", "include_result_metadata"] {
+                if let Some(value) = input.get(key) {
+                    obj.entry(key).or_insert_with(|| value.clone());
+                }
+            }
+        }
+        let facility = processing_input(&facility_input, &element("facilities", i))?;
+        facilities.push(facility.into_assessment(&runner_config(&facility_input)));
+    }
+
+    let company_name = input["company_name"].as_str()
+        .map(|s| s.to_string())
+        .or_else(|| facilities.first().map(|f| f.facility_profile.company_name.clone()))
+        .unwrap_or_default();
+
+    Ok(CompanyAssessment {
+        id: Uuid::new_v4(),
+        company_name,
+        facilities,
+        assessment_date: Utc::now(),
+        results: None,
+    })
+}
+
+pub fn create_processing_input(input: &serde_json::Value) -> Result<ProcessingInput, InputError> {
+    processing_input(input, "")
+}
+
+/// A facility at `path`: the document itself, or one of a company's facilities
+fn processing_input(input: &serde_json::Value, path: &str) -> Result<ProcessingInput, InputError> {
+    let country = parse_country(field(path, "country"), required_str(input, path, &["country"])?)?;
+
+    let region = input["region"].as_str().map(|s| s.to_string());
+
+    // Parse facility profile
+    let facility_profile = parse_facility_profile(
+        required(input, path, &["facility_profile"])?.0,
+        &field(path, "facility_profile")
+    )?;
+
+    // Parse processing operations
+    let processing_operations = parse_processing_operations(
+        required(input, path, &["processing_operations"])?.0,
+        &field(path, "processing_operations")
+    )?;
+
+    // Parse processed products
+    let products_path = field(path, "processed_products");
+    let processed_products = required_array(input, path, "processed_products")?.iter()
+        .enumerate()
+        .map(|(i, product_value)| parse_processed_product(product_value, &element(&products_path, i)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Earlier output for the same facility, for the trend analysis, possibly in an older layout
+    let previous_results = input.get("previous_results")
+        .filter(|v| !v.is_null())
+        .map(|v| crate::schema::migrate(v.clone())
+            .and_then(|v| serde_json::from_value::<ProcessingAssessment>(v)
+                .map_err(|e| InputError::invalid(field(path, "previous_results"), e.to_string()))))
+        .transpose()?;
+
+    Ok(ProcessingInput {
+        facility_profile,
+        processing_operations,
+        processed_products,
+        country,
+        currency: optional_currency(input, path)?,
+        region,
+        previous_results,
+    })
+}
+
+fn parse_facility_profile(fp: &serde_json::Value, path: &str) -> Result<ProcessingFacilityProfile, InputError> {
+    Ok(ProcessingFacilityProfile {
+        facility_name: fp["facility_name"].as_str().unwrap_or("").to_string(),
+        company_name: fp["company_name"].as_str().unwrap_or("").to_string(),
+        facility_type: parse_facility_type(
+            field(path, "facility_type"),
+            fp["facility_type"].as_str().unwrap_or("General")
+        )?,
+        processing_capacity: fp["processing_capacity"].as_f64().unwrap_or(0.0),
+        operational_hours_per_day: fp["operational_hours_per_day"].as_f64().unwrap_or(8.0),
+        operational_days_per_year: fp["operational_days_per_year"].as_u64().unwrap_or(250) as u32,
+        established_year: fp["established_year"].as_u64().map(|y| y as u32),
+        certifications: fp["certifications"].as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_default(),
+        employee_count: fp["employee_count"].as_u64().map(|c| c as u32),
+        facility_size: fp["facility_size"].as_f64(),
+        location_type: parse_location_type(
+            field(path, "location_type"),
+            fp["location_type"].as_str().unwrap_or("Rural")
+        )?,
+        operating_months: fp.get("operating_months")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_u64()).map(|m| m as u32).collect()),
+        daytime_operation_fraction: fp.get("daytime_operation_fraction").and_then(|v| v.as_f64()),
+    })
+}
+
+fn parse_processing_operations(po: &serde_json::Value, path: &str) -> Result<ProcessingOperations, InputError> {
+    let section = |key: &str| po.get(key).unwrap_or(&serde_json::Value::Null);
+    Ok(ProcessingOperations {
+        energy_management: parse_energy_management(section("energy_management"), &field(path, "energy_management"))?,
+        water_management: parse_water_management_processing(section("water_management"), &field(path, "water_management"))?,
+        waste_management: parse_waste_management(section("waste_management"), &field(path, "waste_management"))?,
+        raw_material_sourcing: parse_raw_material_sourcing(section("raw_material_sourcing")),
+        equipment_efficiency: parse_equipment_efficiency(section("equipment_efficiency")),
+        refrigeration: po.get("refrigeration")
+            .filter(|v| !v.is_null())
+            .map(|r| parse_refrigeration(r, &field(path, "refrigeration")))
+            .transpose()?,
+        tariffs: po.get("tariffs")
+            .filter(|v| !v.is_null())
+            .map(parse_utility_tariffs)
+            .unwrap_or_default(),
+        overhead: po.get("overhead")
+            .filter(|v| !v.is_null())
+            .map(parse_facility_overhead)
+            .unwrap_or_default(),
+    })
+}
+
+fn parse_facility_overhead(o: &serde_json::Value) -> FacilityOverhead {
+    FacilityOverhead {
+        energy_kwh_per_year: o["energy_kwh_per_year"].as_f64(),
+        water_m3_per_year: o["water_m3_per_year"].as_f64(),
+        legacy_multipliers: o["legacy_multipliers"].as_bool().unwrap_or(false),
+    }
+}
+
+fn parse_utility_tariffs(t: &serde_json::Value) -> UtilityTariffs {
+    UtilityTariffs {
+        electricity_per_kwh: t["electricity_per_kwh"].as_f64(),
+        diesel_per_litre: t["diesel_per_litre"].as_f64(),
+        water_per_m3: t["water_per_m3"].as_f64(),
+    }
+}
+
+fn parse_refrigeration(r: &serde_json::Value, path: &str) -> Result<RefrigerationSystem, InputError> {
+    Ok(RefrigerationSystem {
+        // Leakage is characterized by the refrigerant's GWP, which no default can stand in for
+        refrigerant_type: parse_refrigerant_type(
+            field(path, "refrigerant_type"),
+            required_str(r, path, &["refrigerant_type"])?
+        )?,
+        total_charge_kg: r.get("total_charge_kg").and_then(|v| v.as_f64()),
+        annual_leakage_rate_percent: r.get("annual_leakage_rate_percent").and_then(|v| v.as_f64()),
+        equipment_lifetime_years: r.get("equipment_lifetime_years").and_then(|v| v.as_f64()),
+        end_of_life_recovery_percent: r.get("end_of_life_recovery_percent").and_then(|v| v.as_f64()),
+    })
+}
+
+fn parse_refrigerant_type(field: String, s: &str) -> Result<RefrigerantType, InputError> {
+    one_of(field, s, &[
+        ("R22", RefrigerantType::R22),
+        ("R134a", RefrigerantType::R134a),
+        ("R404A", RefrigerantType::R404A),
+        ("R600a", RefrigerantType::R600a),
+        ("Ammonia", RefrigerantType::Ammonia),
+        ("R717", RefrigerantType::Ammonia),
+        ("CO2", RefrigerantType::CO2),
+        ("R744", RefrigerantType::CO2),
+    ])
+}
+
+fn parse_processed_product(pp: &serde_json::Value, path: &str) -> Result<ProcessedProduct, InputError> {
+    let raw_materials = pp.get("raw_material_inputs")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|rm| {
+            Some(RawMaterialInput {
+                material_name: rm.get("material_name")?.as_str()?.to_string(),
+                quantity_per_tonne_output: rm.get("quantity_per_tonne_output")?.as_f64()?,
+                source_location: rm.get("source_location").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                quality_requirements: rm.get("quality_requirements")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+                    .unwrap_or_default(),
+                seasonal_availability: rm.get("seasonal_availability").and_then(|v| v.as_bool()).unwrap_or(true),
+            })
+        }).collect())
+        .unwrap_or_default();
+
+    let steps_path = field(path, "processing_steps");
+    let processing_steps = pp.get("processing_steps")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter()
+            .enumerate()
+            .filter_map(|(i, ps)| parse_processing_step(ps, &element(&steps_path, i)).transpose())
+            .collect::<Result<Vec<_>, _>>())
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(ProcessedProduct {
+        id: required_str(pp, path, &["id", "product_id"])?.to_string(),
+        name: required_str(pp, path, &["name", "product_name"])?.to_string(),
+        product_type: parse_product_type(required_str(pp, path, &["product_type"])?),
+        annual_production: required_f64(pp, path, &["annual_production"])?,
+        raw_material_inputs: raw_materials,
+        processing_steps,
+        packaging: parse_packaging_info(pp.get("packaging").unwrap_or(&serde_json::Value::Null)),
+        quality_grade: parse_quality_grade(pp.get("quality_grade").and_then(|v| v.as_str()).unwrap_or("Standard")),
+        market_destination: parse_market_destination(pp.get("market_destination").and_then(|v| v.as_str()).unwrap_or("Local")),
+        wastewater_load: pp.get("wastewater_load").and_then(|wl| {
+            Some(WastewaterLoad {
+                cod_mg_per_l: wl.get("cod_mg_per_l")?.as_f64()?,
+                bod_mg_per_l: wl.get("bod_mg_per_l").and_then(|v| v.as_f64()).unwrap_or(0.0),
+                total_nitrogen_mg_per_l: wl.get("total_nitrogen_mg_per_l").and_then(|v| v.as_f64()).unwrap_or(0.0),
+                total_phosphorus_mg_per_l: wl.get("total_phosphorus_mg_per_l").and_then(|v| v.as_f64()).unwrap_or(0.0),
+            })
+        }),
+        price_per_tonne: pp.get("price_per_tonne").and_then(|v| v.as_f64()),
+    })
+}
+
+// Helper parsing functions for processing-specific enums
+/// Steps missing required fields are skipped; a drying step may omit its energy intensity
+fn parse_processing_step(ps: &serde_json::Value, path: &str) -> Result<Option<ProcessingStep>, InputError> {
+    let drying_method = ps.get("drying_method")
+        .and_then(|v| v.as_str())
+        .map(|s| parse_drying_method(field(path, "drying_method"), s))
+        .transpose()?;
+    let energy_intensity = match (ps.get("energy_intensity").and_then(|v| v.as_f64()), drying_method) {
+        (Some(intensity), _) => intensity,
+        (None, Some(_)) => 0.0, // typical intensity of the dryer
+        (None, None) => return Ok(None),
+    };
+    let (Some(step_name), Some(water_usage), Some(duration)) = (
+        ps.get("step_name").and_then(|v| v.as_str()),
+        ps.get("water_usage").and_then(|v| v.as_f64()),
+        ps.get("duration").and_then(|v| v.as_f64()),
+    ) else {
+        return Ok(None);
+    };
+
+    Ok(Some(ProcessingStep {
+        step_name: step_name.to_string(),
+        energy_intensity,
+        water_usage,
+        duration,
+        yield_efficiency: ps.get("yield_efficiency").and_then(|v| v.as_f64()).unwrap_or(95.0),
+        emissions_factor: ps.get("emissions_factor").and_then(|v| v.as_f64()),
+        drying_method,
+    }))
+}
+
+fn parse_drying_method(field: String, s: &str) -> Result<DryingMethod, InputError> {
+    one_of(field, s, &[
+        ("Sun", DryingMethod::Sun),
+        ("SolarAssisted", DryingMethod::SolarAssisted),
+        ("Solar-assisted", DryingMethod::SolarAssisted),
+        ("Biomass", DryingMethod::Biomass),
+        ("Electric", DryingMethod::Electric),
+        ("Diesel", DryingMethod::Diesel),
+    ])
+}
+
+fn parse_facility_type(field: String, s: &str) -> Result<ProcessingFacilityType, InputError> {
+    one_of(field, s, &[
+        ("Mill", ProcessingFacilityType::Mill),
+        ("Bakery", ProcessingFacilityType::Bakery),
+        ("CassivaProcessing", ProcessingFacilityType::CassivaProcessing),
+        ("RiceProcessing", ProcessingFacilityType::RiceProcessing),
+        ("PalmOilMill", ProcessingFacilityType::PalmOilMill),
+        ("CocoaProcessing", ProcessingFacilityType::CocoaProcessing),
+        ("FishProcessing", ProcessingFacilityType::FishProcessing),
+        ("MeatProcessing", ProcessingFacilityType::MeatProcessing),
+        ("DairyProcessing", ProcessingFacilityType::DairyProcessing),
+        ("FruitProcessing", ProcessingFacilityType::FruitProcessing),
+        ("VegetableProcessing", ProcessingFacilityType::VegetableProcessing),
+        ("General", ProcessingFacilityType::General),
+    ])
+}
+
+fn parse_location_type(field: String, s: &str) -> Result<LocationType, InputError> {
+    one_of(field, s, &[
+        ("Urban", LocationType::Urban),
+        ("PeriUrban", LocationType::PeriUrban),
+        ("Rural", LocationType::Rural),
+        ("Industrial", LocationType::Industrial),
+    ])
+}
+
+/// Product types outside the known ones are kept by name
+fn parse_product_type(s: &str) -> ProductType {
+    match s {
+        "FlourMaize" => ProductType::FlourMaize,
+        "FlourWheat" => ProductType::FlourWheat,
+        "FlourCassava" => ProductType::FlourCassava,
+        "FlourPlantain" => ProductType::FlourPlantain,
+        "RiceProcessed" => ProductType::RiceProcessed,
+        "PalmOil" => ProductType::PalmOil,
+        "CocoaPowder" => ProductType::CocoaPowder,
+        "CocoaButter" => ProductType::CocoaButter,
+        "BakedGoods" => ProductType::BakedGoods,
+        "ProcessedFish" => ProductType::ProcessedFish,
+        "ProcessedMeat" => ProductType::ProcessedMeat,
+        "Dairy" => ProductType::Dairy,
+        "FruitJuice" => ProductType::FruitJuice,
+        "DriedFruits" => ProductType::DriedFruits,
+        _ => ProductType::Other(s.to_string()),
+    }
+}
+
+// Simplified implementations for the remaining parsing functions
+fn parse_energy_management(em: &serde_json::Value, path: &str) -> Result<EnergyManagement, InputError> {
+    let mix_path = field(path, "energy_mix");
+    let energy_mix = em.get("energy_mix")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter()
+            .enumerate()
+            .map(|(i, share)| parse_energy_share(share, &element(&mix_path, i)))
+            .collect::<Result<Vec<_>, _>>())
+        .transpose()?
+        .unwrap_or_default();
+
+    // With a mix, the primary source defaults to its largest share
+    let primary_energy_source = match em.get("primary_energy_source").and_then(|v| v.as_str()) {
+        Some(source) => parse_energy_source(field(path, "primary_energy_source"), source)?,
+        None => energy_mix.iter()
+            .max_by(|a, b| a.percentage.total_cmp(&b.percentage))
+            .map(|share| share.source)
+            .unwrap_or(EnergySource::GridElectricity),
+    };
+
+    let mut energy_management = EnergyManagement {
+        primary_energy_source,
+        secondary_energy_sources: em.get("secondary_energy_sources")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| parse_energy_source(field(path, "secondary_energy_sources"), s))
+                .collect::<Result<Vec<_>, _>>())
+            .transpose()?
+            .unwrap_or_default(),
+        monthly_electricity_consumption: em.get("monthly_electricity_consumption").and_then(|v| v.as_f64()),
+        monthly_fuel_consumption: em.get("monthly_fuel_consumption").and_then(|v| v.as_f64()),
+        fuel_type: em.get("fuel_type").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        renewable_energy_percentage: em.get("renewable_energy_percentage").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        energy_efficiency_measures: em.get("energy_efficiency_measures")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_default(),
+        backup_generator: em.get("backup_generator").and_then(|v| v.as_bool()).unwrap_or(false),
+        energy_mix,
+        grid_emission_factor: em.get("grid_emission_factor")
+            .filter(|v| !v.is_null())
+            .map(|factor| parse_electricity_emission_factor(factor, &field(path, "grid_emission_factor")))
+            .transpose()?,
+        captive_generation: em.get("captive_generation").and_then(|v| v.as_bool()).unwrap_or(false),
+        backup_generator_hours_per_year: em.get("backup_generator_hours_per_year").and_then(|v| v.as_f64()),
+    };
+
+    if let Some(hours) = energy_management.backup_generator_hours_per_year.filter(|hours| *hours < 0.0) {
+        return Err(InputError::invalid(
+            field(path, "backup_generator_hours_per_year"),
+            format!("cannot be negative, got {}", hours)
+        ));
+    }
+    energy_management.validate_energy_mix()
+        .map_err(|reason| InputError::invalid(mix_path, reason))?;
+    if !energy_management.energy_mix.is_empty() {
+        // Derived from the mix so the two can't contradict each other
+        energy_management.renewable_energy_percentage = energy_management.renewable_share_percent();
+    }
+
+    Ok(energy_management)
+}
+
+/// Facility-specific grid factor, given as a number or as {"kg_co2e_per_kwh", "source"}
+fn parse_electricity_emission_factor(factor: &serde_json::Value, path: &str) -> Result<ElectricityEmissionFactor, InputError> {
+    let (kg_co2e_per_kwh, source) = match factor.as_f64() {
+        Some(value) => (value, "user supplied".to_string()),
+        None => (
+            required_f64(factor, path, &["kg_co2e_per_kwh"])?,
+            factor.get("source").and_then(|v| v.as_str()).unwrap_or("user supplied").to_string(),
+        ),
+    };
+    if kg_co2e_per_kwh < 0.0 {
+        return Err(InputError::invalid(path, format!("cannot be negative, got {}", kg_co2e_per_kwh)));
+    }
+    Ok(ElectricityEmissionFactor { kg_co2e_per_kwh, source })
+}
+
+fn parse_energy_share(share: &serde_json::Value, path: &str) -> Result<EnergyShare, InputError> {
+    Ok(EnergyShare {
+        source: parse_energy_source(field(path, "source"), required_str(share, path, &["source"])?)?,
+        percentage: required_f64(share, path, &["percentage"])?,
+        metered_kwh: share.get("metered_kwh").and_then(|v| v.as_f64()),
+    })
+}
+
+fn parse_energy_source(field: String, s: &str) -> Result<EnergySource, InputError> {
+    one_of(field, s, &[
+        ("GridElectricity", EnergySource::GridElectricity),
+        ("DieselGenerator", EnergySource::DieselGenerator),
+        ("SolarPower", EnergySource::SolarPower),
+        ("Biomass", EnergySource::Biomass),
+        ("LPG", EnergySource::LPG),
+        ("NaturalGas", EnergySource::NaturalGas),
+        ("HydroElectricity", EnergySource::HydroElectricity),
+        ("WindPower", EnergySource::WindPower),
+        ("Mixed", EnergySource::Mixed),
+    ])
+}
+
+fn parse_water_management_processing(wm: &serde_json::Value, path: &str) -> Result<crate::processing::models::WaterManagement, InputError> {
+    Ok(crate::processing::models::WaterManagement {
+        water_source: vec!["Municipal".to_string()],
+        monthly_water_consumption: Some(1000.0),
+        water_treatment: crate::processing::models::WaterTreatment::BasicFiltration,
+        water_conservation_measures: vec![],
+        wastewater_treatment: parse_wastewater_treatment(
+            field(path, "wastewater_treatment"),
+            wm.get("wastewater_treatment").and_then(|v| v.as_str()).unwrap_or("BasicSedimentation")
+        )?,
+        recycled_water_percentage: wm.get("recycled_water_percentage").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        rainwater_harvesting_m3_per_year: wm.get("rainwater_harvesting_m3_per_year").and_then(|v| v.as_f64()).unwrap_or(0.0),
+    })
+}
+
+fn parse_wastewater_treatment(field: String, s: &str) -> Result<crate::processing::models::WastewaterTreatment, InputError> {
+    use crate::processing::models::WastewaterTreatment;
+    one_of(field, s, &[
+        ("None", WastewaterTreatment::None),
+        ("BasicSedimentation", WastewaterTreatment::BasicSedimentation),
+        ("BiologicalTreatment", WastewaterTreatment::BiologicalTreatment),
+        ("ChemicalTreatment", WastewaterTreatment::ChemicalTreatment),
+        ("Advanced", WastewaterTreatment::Advanced),
+    ])
+}
+
+fn parse_waste_management(wm: &serde_json::Value, path: &str) -> Result<crate::processing::models::WasteManagement, InputError> {
+    let streams_path = field(path, "waste_streams");
+    Ok(crate::processing::models::WasteManagement {
+        solid_waste_generation: Some(100.0),
+        organic_waste_percentage: 70.0,
+        waste_disposal_method: crate::processing::models::WasteDisposalMethod::Landfill,
+        recycling_programs: vec![],
+        byproduct_utilization: vec![],
+        waste_streams: wm.get("waste_streams")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter()
+                .enumerate()
+                .map(|(i, ws)| parse_waste_stream(ws, &element(&streams_path, i)))
+                .collect::<Result<Vec<_>, _>>())
+            .transpose()?
+            .unwrap_or_default(),
+    })
+}
+
+fn parse_waste_stream(ws: &serde_json::Value, path: &str) -> Result<WasteStream, InputError> {
+    Ok(WasteStream {
+        stream_name: required_str(ws, path, &["stream_name"])?.to_string(),
+        tonnes_per_year: required_f64(ws, path, &["tonnes_per_year"])?,
+        composition: parse_waste_composition(field(path, "composition"), required_str(ws, path, &["composition"])?)?,
+        disposal_method: parse_waste_disposal_method(
+            field(path, "disposal_method"),
+            ws.get("disposal_method").and_then(|v| v.as_str()).unwrap_or("Landfill")
+        )?,
+    })
+}
+
+fn parse_waste_composition(field: String, s: &str) -> Result<WasteComposition, InputError> {
+    one_of(field, s, &[
+        ("CropResidue", WasteComposition::CropResidue),
+        ("FoodScraps", WasteComposition::FoodScraps),
+        ("Sludge", WasteComposition::Sludge),
+        ("Paper", WasteComposition::Paper),
+        ("Plastic", WasteComposition::Plastic),
+        ("Metal", WasteComposition::Metal),
+    ])
+}
+
+fn parse_waste_disposal_method(field: String, s: &str) -> Result<crate::processing::models::WasteDisposalMethod, InputError> {
+    use crate::processing::models::WasteDisposalMethod;
+    one_of(field, s, &[
+        ("Landfill", WasteDisposalMethod::Landfill),
+        ("Incineration", WasteDisposalMethod::Incineration),
+        ("Composting", WasteDisposalMethod::Composting),
+        ("AnaerobicDigestion", WasteDisposalMethod::AnaerobicDigestion),
+        ("Recycling", WasteDisposalMethod::Recycling),
+        ("Mixed", WasteDisposalMethod::Mixed),
+        ("OpenBurning", WasteDisposalMethod::OpenBurning),
+        ("EnergyRecovery", WasteDisposalMethod::EnergyRecovery),
+    ])
+}
+
+fn parse_raw_material_sourcing(_rms: &serde_json::Value) -> RawMaterialSourcing {
+    RawMaterialSourcing {
+        local_sourcing_percentage: 80.0,
+        average_transport_distance: 50.0,
+        transport_mode: TransportMode::Truck,
+        supplier_sustainability_practices: vec![],
+        seasonal_variation: true,
+        storage_practices: StoragePractices {
+            storage_type: "Warehouse".to_string(),
+            climate_control: false,
+            pest_control_methods: vec![],
+            storage_loss_percentage: 5.0,
+            storage_days: None,
+        },
+    }
+}
+
+fn parse_equipment_efficiency(_ee: &serde_json::Value) -> EquipmentEfficiency {
+    EquipmentEfficiency {
+        equipment_age: EquipmentAge::Mature,
+        maintenance_frequency: MaintenanceFrequency::Monthly,
+        automation_level: AutomationLevel::SemiAutomated,
+        equipment_utilization_rate: 75.0,
+        modernization_investments: vec![],
+    }
+}
+
+fn parse_packaging_info(_pi: &serde_json::Value) -> PackagingInfo {
+    PackagingInfo {
+        packaging_material: PackagingMaterial::PlasticBag,
+        package_size: 50.0,
+        packaging_weight_per_unit: 0.1,
+        recyclable: false,
+    }
+}
+
+/// Unknown grades are taken as Standard
+fn parse_quality_grade(s: &str) -> QualityGrade {
+    match s {
+        "Premium" => QualityGrade::Premium,
+        "Basic" => QualityGrade::Basic,
+        "Industrial" => QualityGrade::Industrial,
+        _ => QualityGrade::Standard,
+    }
+}
+
+/// Unknown destinations are taken as Local
+fn parse_market_destination(s: &str) -> MarketDestination {
+    match s {
+        "Regional" => MarketDestination::Regional,
+        "National" => MarketDestination::National,
+        "Export" => MarketDestination::Export,
+        "Mixed" => MarketDestination::Mixed,
+        _ => MarketDestination::Local,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FACILITY: &str = include_str!("../../app/processing/example_cassava_processing.json");
+
+    fn farm() -> serde_json::Value {
+        json!({
+            "company_name": "Test farm",
+            "country": "Ghana",
+            "foods": [
+                {"id": "1", "name": "Maize", "quantity_kg": 1000.0, "category": "Cereals"},
+                {"id": "2", "name": "Cowpea", "quantity_kg": 500.0, "category": "Legumes"}
+            ]
+        })
+    }
+
+    #[test]
+    fn errors_name_the_field_the_value_and_the_alternatives() {
+        let mut input = farm();
+        input["foods"][1]["category"] = json!("Pulses");
+        match create_simple_input(&input).unwrap_err() {
+            InputError::UnknownValue { field, value, valid } => {
+                assert_eq!(field, "foods[1].category");
+                assert_eq!(value, "Pulses");
+                assert!(valid.iter().any(|v| v == "Legumes"));
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let mut input = farm();
+        input["country"] = json!("Togo");
+        assert!(matches!(create_comprehensive_input(&input), Err(InputError::UnknownValue { ref field, .. }) if field == "country"));
+
+        let mut input = farm();
+        input.as_object_mut().unwrap().remove("country");
+        assert_eq!(create_simple_input(&input).unwrap_err(), InputError::Missing { field: "country".to_string() });
+
+        let mut input = farm();
+        input["foods"][0]["quantity_kg"] = json!("a tonne");
+        assert!(matches!(
+            create_comprehensive_input(&input),
+            Err(InputError::WrongType { ref field, .. }) if field == "foods[0].quantity_kg"
+        ));
+
+        let mut input = farm();
+        input["latitude"] = json!(6.7);
+        assert_eq!(create_simple_input(&input).unwrap_err(), InputError::Missing { field: "longitude".to_string() });
+
+        let mut input = farm();
+        input["weighting_method"] = json!({"Human Health": 0.5, "Ecosystem Quality": 0.5, "Oceans": 0.0});
+        assert!(matches!(
+            create_simple_input(&input),
+            Err(InputError::UnknownValue { ref field, ref value, .. }) if field == "weighting_method" && value == "Oceans"
+        ));
+    }
+
+    #[test]
+    fn line_items_in_unknown_currencies_are_rejected() {
+        let mut input = farm();
+        input["equipment_energy"] = json!({
+            "fuel_consumption": [
+                {"fuel_type": "Diesel", "monthly_consumption": 20.0, "primary_use": "Tractor", "cost": 300.0, "currency": "usd"},
+                {"fuel_type": "Petrol", "monthly_consumption": 5.0, "primary_use": "Pump", "cost": 40.0, "currency": "EUR"}
+            ]
+        });
+        assert!(matches!(
+            create_comprehensive_input(&input),
+            Err(InputError::UnknownValue { ref field, ref value, .. })
+                if field == "equipment_energy.fuel_consumption[1].currency" && value == "EUR"
+        ));
+
+        input["equipment_energy"]["fuel_consumption"][1]["currency"] = json!("NGN");
+        let fuel = create_comprehensive_input(&input).unwrap().equipment_energy.unwrap().fuel_consumption;
+        assert_eq!(fuel.iter().map(|f| f.currency).collect::<Vec<_>>(), [Some(Currency::USD), Some(Currency::NGN)]);
+
+        input["management_practices"] = json!({
+            "fertilization": {"fertilizer_applications": [
+                {"fertilizer_type": "Urea", "application_rate": 50.0, "applications_per_season": 1, "cost": 10.0, "currency": "XOF"}
+            ]}
+        });
+        assert!(matches!(
+            create_comprehensive_input(&input),
+            Err(InputError::UnknownValue { ref field, .. })
+                if field == "management_practices.fertilization.fertilizer_applications[0].currency"
+        ));
+    }
+
+    #[test]
+    fn costs_are_in_the_countrys_currency_unless_the_input_names_one() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let assessment = runner.run_simple(create_simple_input(&farm()).unwrap()).unwrap();
+        assert_eq!(assessment.currency, Currency::GHS);
+
+        let mut input = farm();
+        input["currency"] = json!("usd");
+        let assessment = runner.run_comprehensive(create_comprehensive_input(&input).unwrap()).unwrap();
+        assert_eq!(assessment.currency, Currency::USD);
+
+        input["currency"] = json!("CFA");
+        assert!(matches!(
+            create_simple_input(&input),
+            Err(InputError::UnknownValue { ref field, ref valid, .. }) if field == "currency" && valid.len() == 3
+        ));
+    }
+
+    #[test]
+    fn facilities_cost_in_the_countrys_currency_unless_the_input_names_one() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let mut input: serde_json::Value = serde_json::from_str(FACILITY).unwrap();
+        let assessment = runner.run_processing(create_processing_input(&input).unwrap()).unwrap();
+        assert_eq!(assessment.currency, Currency::NGN);
+
+        input["currency"] = json!("USD");
+        let assessment = runner.run_processing(create_processing_input(&input).unwrap()).unwrap();
+        assert_eq!(assessment.currency, Currency::USD);
+
+        input["currency"] = json!("CFA");
+        assert!(matches!(
+            create_processing_input(&input),
+            Err(InputError::UnknownValue { ref field, .. }) if field == "currency"
+        ));
+    }
+
+    #[test]
+    fn facility_errors_carry_the_path_within_the_facility() {
+        let facility: serde_json::Value = serde_json::from_str(FACILITY).unwrap();
+        assert!(create_processing_input(&facility).is_ok());
+
+        let mut input = facility.clone();
+        input["processing_operations"]["energy_management"]["energy_mix"] = json!([{"source": "Coal", "percentage": 100.0}]);
+        assert!(matches!(
+            create_processing_input(&input),
+            Err(InputError::UnknownValue { ref field, .. }) if field == "processing_operations.energy_management.energy_mix[0].source"
+        ));
+
+        let mut unnamed_refrigerant = facility.clone();
+        unnamed_refrigerant["processing_operations"]["refrigeration"] = json!({"total_charge_kg": 40.0});
+        assert_eq!(
+            create_processing_input(&unnamed_refrigerant).unwrap_err(),
+            InputError::Missing { field: "processing_operations.refrigeration.refrigerant_type".to_string() }
+        );
+
+        let mut unknown_country = facility.clone();
+        unknown_country["country"] = json!("Atlantis");
+        let company = json!({"company_name": "Group", "facilities": [facility, unknown_country]});
+        assert!(matches!(
+            create_company_assessment(&company),
+            Err(InputError::UnknownValue { ref field, .. }) if field == "facilities[1].country"
+        ));
+    }
+
+    #[test]
+    fn reports_keep_the_field_and_the_exit_code() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let mut input = farm();
+        input["foods"][0]["category"] = json!("Grains");
+        let error = run_input(&runner, AssessmentKind::Simple, &input).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+        assert_eq!(error.field.as_deref(), Some("foods[0].category"));
+        assert!(error.message.contains("expected one of: Cereals, Legumes"), "{}", error.message);
+
+        // Rejected by the engine rather than the parser, but still a problem with the input
+        let mut input = farm();
+        input["impact_categories"] = json!(["Ozone depletion"]);
+        let error = run_input(&runner.with_config(runner_config(&input)), AssessmentKind::Simple, &input).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+        assert_eq!(error.field.as_deref(), Some("impact_categories"));
+    }
+}
@@ -1,16 +1,56 @@
+pub mod error;
 pub mod production;
 pub mod utils;
 pub mod processing;
+pub mod runner;
+pub mod input;
+pub mod plausibility;
+pub mod units;
+pub mod export;
+pub mod farm_to_fork;
+pub mod report;
+pub mod compare;
+pub mod ndjson;
+pub mod schema;
+pub mod scoring;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "storage")]
+pub mod storage;
 
+pub use error::{InputError, DataLoadError, FactorError, UnitError, EngineError, ExportError, ReportError};
+#[cfg(feature = "storage")]
+pub use error::StorageError;
 pub use production::*;
 pub use utils::*;
+pub use runner::*;
+pub use input::*;
+pub use plausibility::{Plausibility, PlausibilityBounds, Bound, Strictness, Severity, Violation};
+pub use units::{Quantity, Unit, Dimension, Mass, Volume, Energy, Area};
+pub use export::{export_ilcd, export_simapro};
+pub use farm_to_fork::FarmToForkEngine;
+pub use report::Report;
+pub use compare::{AssessmentComparison, CategoryChange, ConfidenceChange, InputDifference};
+pub use ndjson::{run_ndjson, NdjsonOptions, NdjsonSummary};
+pub use schema::{migrate, SCHEMA_VERSION};
+pub use scoring::{available_weights, normalization_reference, weighted_single_score};
+#[cfg(feature = "server")]
+pub use server::ServerConfig;
 pub use processing::{
     ProcessingLCAEngine, ProcessingDataLoader, ProcessingAssessment,
     ProcessingFacilityProfile, ProcessingOperations, ProcessedProduct,
-    ProcessingStep, RawMaterialInput, PackagingInfo, PackagingMaterial, QualityGrade, MarketDestination,
-    ProcessingFacilityType, LocationType, ProductType, EnergySource, EnergyManagement,
+    ProcessingStep, DryingMethod, RawMaterialInput, PackagingInfo, PackagingMaterial, QualityGrade, MarketDestination,
+    ProcessingFacilityType, LocationType, ProductType, EnergySource, EnergyManagement, EnergyShare, ElectricityEmissionFactor,
     EquipmentAge, MaintenanceFrequency, AutomationLevel, EquipmentEfficiency,
-    TransportMode, RawMaterialSourcing, StoragePractices
+    TransportMode, RawMaterialSourcing, StoragePractices, RefrigerationSystem, RefrigerantType,
+    WastewaterLoad, CompanyAssessment, CompanyResults, SiteComparison, SiteRanking,
+    CapacityRange, ProcessingScaleCurve,
+    WasteStream, WasteComposition, WasteStreamImpact, UtilityTariffs, FacilityOverhead,
+    TrendAnalysis, IntensityTrend, ProductTrend, TrendStatus, ImplementedMeasure, UpstreamBurden
 };
 
 #[cfg(test)]
@@ -0,0 +1,280 @@
+/*!
+ * Newline-delimited JSON batches
+ *
+ * Runs one assessment per input line and writes one JSON object per output line as soon as
+ * the assessment is done, so national datasets of any size stream through in bounded memory:
+ * at most `NdjsonOptions::window` lines are read ahead of the last one written, however long
+ * the input. Each output line carries the 1-based input line it answers:
+ *
+ * ```text
+ * {"line":1,"result":{ ...assessment... }}
+ * {"line":2,"error":{"code":"INVALID_JSON","message":"..."}}
+ * ```
+ *
+ * Output follows input order unless `ordered` is off, in which case lines are written as
+ * they complete and the line numbers say which input each belongs to. Blank lines are skipped.
+ */
+
+use crate::input::*;
+use crate::runner::AssessmentRunner;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[derive(Debug, Clone)]
+pub struct NdjsonOptions {
+    pub workers: usize,
+    pub ordered: bool, // false: write lines as they complete
+    pub window: usize, // lines read but not yet written, at most
+}
+
+impl Default for NdjsonOptions {
+    fn default() -> Self {
+        Self { workers: 1, ordered: true, window: 64 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NdjsonSummary {
+    pub assessed: usize,
+    pub failed: usize,
+    pub peak_in_flight: usize, // most lines held at once; never above the window
+}
+
+/// Run every line of `input` and write the results to `output`. Failed lines become error
+/// objects; only reading the input or writing the output ends the batch early.
+pub fn run_ndjson(
+    runner: &AssessmentRunner,
+    input: impl BufRead,
+    mut output: impl Write,
+    options: &NdjsonOptions
+) -> io::Result<NdjsonSummary> {
+    let workers = options.workers.max(1);
+    let window = options.window.max(workers);
+    let (job_tx, job_rx) = mpsc::channel::<(usize, usize, String)>();
+    let (done_tx, done_rx) = mpsc::channel::<(usize, bool, String)>();
+    let job_rx = Arc::new(Mutex::new(job_rx));
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let (job_rx, done_tx) = (Arc::clone(&job_rx), done_tx.clone());
+            scope.spawn(move || loop {
+                // The lock is released before the line runs
+                let job = job_rx.lock().map_err(|_| ()).and_then(|jobs| jobs.recv().map_err(|_| ()));
+                let Ok((sequence, line_number, line)) = job else { break };
+                let (ok, object) = run_line(runner, line_number, &line);
+                if done_tx.send((sequence, ok, object)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(done_tx);
+
+        let mut writer = OutputWriter { output: &mut output, ordered: options.ordered, next: 0, pending: BTreeMap::new(), summary: NdjsonSummary::default() };
+        // Workers stop once dispatch drops the job channel, also when the batch ends early
+        dispatch(input, job_tx, &done_rx, &mut writer, window)?;
+        Ok(writer.summary)
+    })
+}
+
+/// Hand lines to the workers, keeping at most `window` of them unwritten
+fn dispatch<W: Write>(
+    input: impl BufRead,
+    jobs: mpsc::Sender<(usize, usize, String)>,
+    done: &mpsc::Receiver<(usize, bool, String)>,
+    writer: &mut OutputWriter<W>,
+    window: usize
+) -> io::Result<()> {
+    let (mut in_flight, mut dispatched) = (0, 0);
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        while in_flight >= window {
+            in_flight -= writer.accept(done.recv().map_err(worker_lost)?)?;
+        }
+        // A send only fails once every worker is gone, which recv reports
+        let _ = jobs.send((dispatched, index + 1, line));
+        dispatched += 1;
+        in_flight += 1;
+        writer.summary.peak_in_flight = writer.summary.peak_in_flight.max(in_flight);
+        while let Ok(finished) = done.try_recv() {
+            in_flight -= writer.accept(finished)?;
+        }
+    }
+    drop(jobs);
+    while in_flight > 0 {
+        in_flight -= writer.accept(done.recv().map_err(worker_lost)?)?;
+    }
+    Ok(())
+}
+
+/// Output object of one input line, and whether it holds a result
+fn run_line(runner: &AssessmentRunner, line_number: usize, line: &str) -> (bool, String) {
+    let result = serde_json::from_str::<serde_json::Value>(line)
+        .map_err(|e| ErrorReport::new(ErrorCode::InvalidJson, e))
+        .and_then(|input| run_input_compact(&runner.with_config(runner_config(&input)), AssessmentKind::detect(&input), &input));
+    match result {
+        Ok(json) => (true, format!("{{\"line\":{},\"result\":{}}}", line_number, json)),
+        Err(error) => (false, format!(
+            "{{\"line\":{},\"error\":{}}}",
+            line_number,
+            serde_json::to_string(&error).unwrap_or_else(|_| "null".to_string())
+        )),
+    }
+}
+
+fn worker_lost(_: mpsc::RecvError) -> io::Error {
+    io::Error::other("an assessment worker stopped unexpectedly")
+}
+
+struct OutputWriter<'a, W: Write> {
+    output: &'a mut W,
+    ordered: bool,
+    next: usize, // sequence number of the next line to write, when ordered
+    pending: BTreeMap<usize, (bool, String)>,
+    summary: NdjsonSummary,
+}
+
+impl<W: Write> OutputWriter<'_, W> {
+    /// Take a finished line and write whatever is now due; returns the number of lines written
+    fn accept(&mut self, (sequence, ok, object): (usize, bool, String)) -> io::Result<usize> {
+        if !self.ordered {
+            self.write(ok, &object)?;
+            return Ok(1);
+        }
+        self.pending.insert(sequence, (ok, object));
+        let mut written = 0;
+        while let Some((ok, object)) = self.pending.remove(&self.next) {
+            self.write(ok, &object)?;
+            self.next += 1;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    fn write(&mut self, ok: bool, object: &str) -> io::Result<()> {
+        if ok { self.summary.assessed += 1 } else { self.summary.failed += 1 }
+        writeln!(self.output, "{}", object)?;
+        self.output.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::RunnerConfig;
+    use std::io::{BufReader, Read};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const LINES: usize = 10_000;
+
+    /// Synthetic input made one line at a time, counting the lines handed out
+    struct Synthetic {
+        next: usize,
+        produced: Arc<AtomicUsize>,
+        buffer: Vec<u8>,
+    }
+
+    fn synthetic_line(n: usize) -> String {
+        match n % 100 {
+            0 => "{not json".to_string(),
+            50 => r#"{"company_name": "No foods", "country": "Ghana"}"#.to_string(),
+            _ => serde_json::json!({
+                "company_name": format!("Farm {}", n),
+                "country": "Ghana",
+                "foods": [{ "id": "maize", "name": "Maize", "quantity_kg": 100.0 + n as f64, "category": "Cereals" }],
+                "include_inventory": false
+            }).to_string(),
+        }
+    }
+
+    impl Read for Synthetic {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.buffer.is_empty() && self.next < LINES {
+                self.next += 1;
+                self.produced.store(self.next, Ordering::SeqCst);
+                self.buffer = format!("{}\n", synthetic_line(self.next)).into_bytes();
+            }
+            let n = buf.len().min(self.buffer.len());
+            buf[..n].copy_from_slice(&self.buffer[..n]);
+            self.buffer.drain(..n);
+            Ok(n)
+        }
+    }
+
+    /// Checks each line as it is written and keeps none of them
+    struct Checker {
+        produced: Arc<AtomicUsize>,
+        written: usize,
+        max_read_ahead: usize,
+        pending: Vec<u8>,
+    }
+
+    impl Write for Checker {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.pending.extend_from_slice(buf);
+            while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.pending.drain(..=end).collect();
+                self.written += 1;
+                self.max_read_ahead = self.max_read_ahead.max(self.produced.load(Ordering::SeqCst) - self.written);
+                let object: serde_json::Value = serde_json::from_slice(&line).unwrap();
+                let number = object["line"].as_u64().unwrap() as usize;
+                assert_eq!(number, self.written, "output out of input order");
+                match number % 100 {
+                    0 => assert_eq!(object["error"]["code"], "INVALID_JSON"),
+                    50 => assert_eq!(object["error"]["code"], "INVALID_INPUT"),
+                    // Sample the results rather than checking all of them
+                    n if n % 10 == 7 => {
+                        let result = &object["result"];
+                        assert_eq!(result["company_name"], format!("Farm {}", number));
+                        assert_eq!(result["foods"][0]["quantity_kg"], 100.0 + number as f64);
+                        assert!(result["results"]["midpoint_impacts"]["Global warming"]["value"].as_f64().unwrap() > 0.0);
+                    }
+                    _ => assert!(object["result"].is_object()),
+                }
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn ten_thousand_lines_stream_in_order_with_bounded_read_ahead() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let produced = Arc::new(AtomicUsize::new(0));
+        let input = BufReader::new(Synthetic { next: 0, produced: Arc::clone(&produced), buffer: Vec::new() });
+        let mut checker = Checker { produced, written: 0, max_read_ahead: 0, pending: Vec::new() };
+        let options = NdjsonOptions { workers: 4, ordered: true, window: 32 };
+
+        let summary = run_ndjson(&runner, input, &mut checker, &options).unwrap();
+        assert_eq!(checker.written, LINES);
+        assert_eq!(summary.assessed + summary.failed, LINES);
+        assert_eq!(summary.failed, 2 * LINES / 100);
+        assert!(summary.peak_in_flight <= options.window);
+        // The window plus the one line the reader is on; the input is never collected
+        assert!(checker.max_read_ahead <= options.window + 1, "read {} lines ahead", checker.max_read_ahead);
+    }
+
+    #[test]
+    fn unordered_output_tags_every_line() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let input = format!("{}\n\n{}\n{}\n", synthetic_line(1), synthetic_line(100), synthetic_line(3));
+        let mut output = Vec::new();
+        let options = NdjsonOptions { workers: 3, ordered: false, ..NdjsonOptions::default() };
+
+        let summary = run_ndjson(&runner, input.as_bytes(), &mut output, &options).unwrap();
+        assert_eq!((summary.assessed, summary.failed), (2, 1));
+        let mut lines: Vec<u64> = String::from_utf8(output).unwrap().lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["line"].as_u64().unwrap())
+            .collect();
+        lines.sort();
+        assert_eq!(lines, [1, 3, 4]); // the blank line 2 has no output
+    }
+}
@@ -0,0 +1,369 @@
+/*!
+ * Plausibility - quantitative sanity checks on an input before the engines run it
+ *
+ * Parsing accepts any number of the right type; these checks catch the ones no farm or
+ * facility could report, such as 5e9 kg of maize, 50,000 kg/ha of urea or a step that yields
+ * 400%. Each checked field has a plausible and a possible range. Values outside the possible
+ * range are always errors. Values outside the plausible range are warnings attached to the
+ * results' DataQuality, or errors under `Strictness::Strict`.
+ */
+
+use crate::error::InputError;
+use crate::processing::{ProcessedProduct, ProcessingFacilityProfile};
+use crate::production::data::plausibility_check;
+use crate::production::*;
+use serde::{Deserialize, Serialize};
+
+/// How values outside their plausible range are treated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strictness {
+    #[default]
+    Lenient, // run, with a warning in DataQuality
+    Strict,  // reject the input
+}
+
+/// Plausible and possible (min, max) range of one field
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bound {
+    pub plausible: (f64, f64),
+    pub possible: (f64, f64),
+}
+
+impl Bound {
+    pub const fn new(plausible: (f64, f64), possible: (f64, f64)) -> Self {
+        Self { plausible, possible }
+    }
+
+    fn check(&self, field: String, value: f64) -> Option<Violation> {
+        let outside = |(min, max): (f64, f64)| !(min..=max).contains(&value);
+        if outside(self.possible) {
+            let (min, max) = self.possible;
+            Some(Violation::error(InputError::OutOfRange { field, value, min, max }))
+        } else if outside(self.plausible) {
+            let (min, max) = self.plausible;
+            Some(Violation::warning(InputError::Implausible { field, value, min, max }))
+        } else {
+            None
+        }
+    }
+}
+
+/// Ranges of the checked fields. The defaults span smallholder plots to large commercial
+/// farms and mills in Ghana and Nigeria.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlausibilityBounds {
+    pub quantity_kg: Bound,                  // per food and year
+    pub area_ha: Bound,                      // per food
+    pub post_harvest_losses_percent: Bound,
+    pub fertilizer_rate_kg_per_ha: Bound,    // fertilizer per application
+    pub pesticide_rate_kg_per_ha: Bound,     // active ingredient per application
+    pub applications_per_season: Bound,
+    pub annual_production_tonnes: Bound,     // per processed product
+    pub yield_efficiency_percent: Bound,     // per processing step
+    pub operational_hours_per_day: Bound,
+    pub operational_days_per_year: Bound,
+    pub capacity_utilisation: Bound,         // annual production / (capacity × operating days)
+}
+
+impl Default for PlausibilityBounds {
+    fn default() -> Self {
+        Self {
+            quantity_kg: Bound::new((1.0, 1e7), (0.0, 1e9)),
+            area_ha: Bound::new((0.01, 10_000.0), (0.0, 1e6)),
+            post_harvest_losses_percent: Bound::new((0.0, 60.0), (0.0, 100.0)),
+            fertilizer_rate_kg_per_ha: Bound::new((1.0, 1_000.0), (0.0, 10_000.0)),
+            pesticide_rate_kg_per_ha: Bound::new((0.01, 20.0), (0.0, 200.0)),
+            applications_per_season: Bound::new((1.0, 12.0), (0.0, 52.0)),
+            annual_production_tonnes: Bound::new((0.1, 1e6), (0.0, 1e8)),
+            yield_efficiency_percent: Bound::new((30.0, 100.0), (0.0, 100.0)),
+            operational_hours_per_day: Bound::new((1.0, 24.0), (0.0, 24.0)),
+            operational_days_per_year: Bound::new((30.0, 365.0), (0.0, 366.0)),
+            // Nameplate capacity is nominal; overtime stretches it a little
+            capacity_utilisation: Bound::new((0.0, 1.0), (0.0, 1.25)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A checked field outside its range
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub severity: Severity,
+    pub error: InputError,
+}
+
+impl Violation {
+    fn error(error: InputError) -> Self {
+        Self { severity: Severity::Error, error }
+    }
+
+    fn warning(error: InputError) -> Self {
+        Self { severity: Severity::Warning, error }
+    }
+}
+
+/// Plausibility settings of a run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Plausibility {
+    pub strictness: Strictness,
+    pub bounds: PlausibilityBounds,
+}
+
+impl Plausibility {
+    /// Quantities, areas, losses and application rates of a farm, and the production checks of
+    /// `plausibility_check`: yields, output per animal and nitrogen per hectare
+    pub fn check_farm(&self, foods: &[FoodItem], management: Option<&ManagementPractices>) -> Vec<Violation> {
+        let bounds = &self.bounds;
+        let mut violations = Vec::new();
+        for (i, food) in foods.iter().enumerate() {
+            let path = format!("foods[{}]", i);
+            violations.extend(bounds.quantity_kg.check(format!("{}.quantity_kg", path), food.quantity_kg));
+            violations.extend(food.area_allocated.and_then(|area| bounds.area_ha.check(format!("{}.area_allocated", path), area)));
+            violations.extend(food.post_harvest_losses.and_then(|losses| {
+                bounds.post_harvest_losses_percent.check(format!("{}.post_harvest_losses", path), losses)
+            }));
+        }
+        if let Some(management) = management {
+            if let Some(fertilization) = &management.fertilization {
+                for (i, app) in fertilization.fertilizer_applications.iter().enumerate() {
+                    let path = format!("management_practices.fertilization.fertilizer_applications[{}]", i);
+                    violations.extend(bounds.fertilizer_rate_kg_per_ha.check(format!("{}.application_rate", path), app.application_rate));
+                    violations.extend(bounds.applications_per_season.check(
+                        format!("{}.applications_per_season", path), app.applications_per_season as f64
+                    ));
+                }
+            }
+            for (i, app) in management.pest_management.pesticides_used.iter().enumerate() {
+                let path = format!("management_practices.pest_management.pesticides[{}]", i);
+                violations.extend(bounds.pesticide_rate_kg_per_ha.check(format!("{}.application_rate", path), app.application_rate));
+                violations.extend(bounds.applications_per_season.check(
+                    format!("{}.applications_per_season", path), app.applications_per_season as f64
+                ));
+            }
+        }
+
+        // Quantity entered in tonnes, area in acres or rates per farm: implausible, not impossible
+        violations.extend(plausibility_check(foods, management).into_iter().map(|warning| {
+            let i = foods.iter().position(|food| food.id == warning.food_id).unwrap_or_default();
+            let field = match warning.kind {
+                CalculationWarningKind::NitrogenAboveAgronomicMaximum => "management_practices.fertilization".to_string(),
+                _ => format!("foods[{}].quantity_kg", i),
+            };
+            Violation::warning(InputError::invalid(field, warning.message))
+        }));
+
+        violations
+    }
+
+    /// Operating schedule, production and step yields of a facility at `path` ("" for the
+    /// document itself), and its production against what its capacity allows
+    pub fn check_facility(&self, profile: &ProcessingFacilityProfile, products: &[ProcessedProduct], path: &str) -> Vec<Violation> {
+        let bounds = &self.bounds;
+        let field = |key: String| if path.is_empty() { key } else { format!("{}.{}", path, key) };
+        let mut violations = Vec::new();
+        violations.extend(bounds.operational_hours_per_day.check(
+            field("facility_profile.operational_hours_per_day".to_string()), profile.operational_hours_per_day
+        ));
+        violations.extend(bounds.operational_days_per_year.check(
+            field("facility_profile.operational_days_per_year".to_string()), profile.operational_days_per_year as f64
+        ));
+        for (i, product) in products.iter().enumerate() {
+            let product_path = field(format!("processed_products[{}]", i));
+            violations.extend(bounds.annual_production_tonnes.check(format!("{}.annual_production", product_path), product.annual_production));
+            for (j, step) in product.processing_steps.iter().enumerate() {
+                violations.extend(bounds.yield_efficiency_percent.check(
+                    format!("{}.processing_steps[{}].yield_efficiency", product_path, j), step.yield_efficiency
+                ));
+            }
+        }
+
+        // Without a declared capacity there is nothing to compare against
+        let capacity = profile.annual_throughput_capacity();
+        if capacity > 0.0 {
+            let production: f64 = products.iter().map(|product| product.annual_production).sum();
+            let utilisation = production / capacity;
+            let (_, plausible_max) = bounds.capacity_utilisation.plausible;
+            let (_, possible_max) = bounds.capacity_utilisation.possible;
+            if utilisation > plausible_max {
+                let error = InputError::invalid(field("processed_products".to_string()), format!(
+                    "annual production of {} t is {:.0}% of the {:.0} t the facility can process ({} t/day × {:.0} days)",
+                    production, utilisation * 100.0, capacity, profile.processing_capacity, profile.annual_operating_days()
+                ));
+                violations.push(if utilisation > possible_max { Violation::error(error) } else { Violation::warning(error) });
+            }
+        }
+        violations
+    }
+
+    /// The first violation that is an error under this strictness, or else the messages of
+    /// the warnings
+    pub fn enforce(&self, violations: Vec<Violation>) -> Result<Vec<String>, InputError> {
+        let is_error = |violation: &Violation| {
+            violation.severity == Severity::Error || self.strictness == Strictness::Strict
+        };
+        if let Some(violation) = violations.iter().find(|violation| is_error(violation)) {
+            return Err(violation.error.clone());
+        }
+        Ok(violations.into_iter().map(|violation| violation.error.to_string()).collect())
+    }
+}
+
+/// Add the warnings of `enforce` to the data quality of the results. The engine already
+/// warns about implausible yields itself, so those are not repeated.
+pub fn attach_warnings(results: Option<&mut LCAResults>, warnings: Vec<String>) {
+    let Some(results) = results else {
+        return;
+    };
+    let data_quality = &mut results.data_quality;
+    for warning in warnings {
+        if !data_quality.warnings.iter().any(|existing| warning.ends_with(existing.as_str())) {
+            data_quality.warnings.push(warning);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processing::{ProcessingStep, ProductType};
+
+    fn food(quantity_kg: f64, area_allocated: Option<f64>) -> FoodItem {
+        serde_json::from_value(serde_json::json!({
+            "id": "1", "name": "Maize", "category": "Cereals", "crop_type": "Maize",
+            "quantity_kg": quantity_kg, "area_allocated": area_allocated,
+        })).unwrap()
+    }
+
+    fn urea(application_rate: f64) -> ManagementPractices {
+        serde_json::from_value(serde_json::json!({
+            "soil_management": {"soil_type": null, "uses_compost": false, "compost_source": null,
+                                "conservation_practices": [], "soil_testing_frequency": null},
+            "fertilization": {"uses_fertilizers": true, "soil_test_based": false, "follows_nutrient_plan": false,
+                              "fertilizer_applications": [{"fertilizer_type": "Urea", "npk_ratio": null,
+                                  "application_rate": application_rate, "applications_per_season": 1,
+                                  "cost": null, "currency": null}]},
+            "water_management": {"water_source": [], "irrigation_system": null, "water_conservation_practices": []},
+            "pest_management": {"management_approach": "None", "uses_ipm": false, "pesticides_used": [],
+                                "monitoring_frequency": null},
+        })).unwrap()
+    }
+
+    fn strict() -> Plausibility {
+        Plausibility { strictness: Strictness::Strict, ..Plausibility::default() }
+    }
+
+    fn fields(violations: &[Violation], severity: Severity) -> Vec<&str> {
+        violations.iter().filter(|v| v.severity == severity).map(|v| v.error.field()).collect()
+    }
+
+    #[test]
+    fn farm_quantities_areas_and_rates_are_bounded() {
+        let lenient = Plausibility::default();
+        assert!(lenient.check_farm(&[food(2000.0, Some(1.0))], Some(&urea(100.0))).is_empty());
+
+        let violations = lenient.check_farm(&[food(5e9, None), food(2000.0, Some(-1.0))], None);
+        assert_eq!(fields(&violations, Severity::Error), ["foods[0].quantity_kg", "foods[1].area_allocated"]);
+
+        let violations = lenient.check_farm(&[food(2000.0, Some(1.0))], Some(&urea(50_000.0)));
+        assert_eq!(fields(&violations, Severity::Error), ["management_practices.fertilization.fertilizer_applications[0].application_rate"]);
+        let violations = lenient.check_farm(&[food(2000.0, Some(1.0))], Some(&urea(2_000.0)));
+        assert_eq!(fields(&violations, Severity::Warning), [
+            "management_practices.fertilization.fertilizer_applications[0].application_rate",
+            "management_practices.fertilization", // 920 kg N/ha is beyond what maize can take up
+        ]);
+        assert!(matches!(violations[0].error, InputError::Implausible { value, max, .. } if value == 2_000.0 && max == 1_000.0));
+    }
+
+    #[test]
+    fn yields_outside_the_crop_band_are_warnings() {
+        // 2,000 t of maize on a hectare: tonnes entered as kg
+        let violations = Plausibility::default().check_farm(&[food(2_000_000.0, Some(1.0))], None);
+        assert_eq!(fields(&violations, Severity::Warning), ["foods[0].quantity_kg"]);
+        assert!(violations[0].error.to_string().contains("outside the plausible 0.2-12 t/ha"));
+    }
+
+    #[test]
+    fn strictness_decides_whether_implausible_values_fail() {
+        let violations = Plausibility::default().check_farm(&[food(2000.0, Some(1.0))], Some(&urea(2_000.0)));
+        let warnings = Plausibility::default().enforce(violations.clone()).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("outside the plausible range 1 to 1000"), "{}", warnings[0]);
+        assert!(matches!(strict().enforce(violations), Err(InputError::Implausible { .. })));
+
+        // Impossible values fail either way
+        let violations = Plausibility::default().check_farm(&[food(5e9, None)], None);
+        assert!(matches!(Plausibility::default().enforce(violations), Err(InputError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn bounds_are_configurable() {
+        let mut organic_only = Plausibility::default();
+        organic_only.bounds.fertilizer_rate_kg_per_ha = Bound::new((0.0, 50.0), (0.0, 100.0));
+        let violations = organic_only.check_farm(&[food(2000.0, Some(1.0))], Some(&urea(100.0)));
+        assert_eq!(violations[0].severity, Severity::Warning);
+        let violations = organic_only.check_farm(&[food(2000.0, Some(1.0))], Some(&urea(150.0)));
+        assert_eq!(violations[0].severity, Severity::Error);
+    }
+
+    fn mill(capacity: f64, hours: f64, days: u32) -> ProcessingFacilityProfile {
+        serde_json::from_value(serde_json::json!({
+            "facility_name": "Mill", "company_name": "Mill Co", "facility_type": "Mill",
+            "processing_capacity": capacity, "operational_hours_per_day": hours,
+            "operational_days_per_year": days, "established_year": null, "certifications": [],
+            "employee_count": null, "facility_size": null, "location_type": "Rural",
+        })).unwrap()
+    }
+
+    fn flour(annual_production: f64, yield_efficiency: f64) -> ProcessedProduct {
+        let step = ProcessingStep {
+            step_name: "Milling".to_string(),
+            energy_intensity: 50.0,
+            water_usage: 0.0,
+            duration: 1.0,
+            yield_efficiency,
+            emissions_factor: None,
+            drying_method: None,
+        };
+        serde_json::from_value(serde_json::json!({
+            "id": "flour", "name": "Maize flour", "product_type": ProductType::FlourMaize,
+            "annual_production": annual_production, "raw_material_inputs": [], "processing_steps": [step],
+            "packaging": {"packaging_material": "PlasticBag", "package_size": 50.0,
+                          "packaging_weight_per_unit": 0.1, "recyclable": false},
+            "quality_grade": "Standard", "market_destination": "Local",
+        })).unwrap()
+    }
+
+    #[test]
+    fn facility_schedule_yields_and_capacity_are_bounded() {
+        let check = |profile: &ProcessingFacilityProfile, products: &[ProcessedProduct]| {
+            Plausibility::default().check_facility(profile, products, "")
+        };
+        assert!(check(&mill(20.0, 8.0, 250), &[flour(4_000.0, 95.0)]).is_empty());
+
+        let violations = check(&mill(20.0, 30.0, 400), &[flour(4_000.0, 400.0)]);
+        assert_eq!(fields(&violations, Severity::Error), [
+            "facility_profile.operational_hours_per_day",
+            "facility_profile.operational_days_per_year",
+            "processed_products[0].processing_steps[0].yield_efficiency",
+        ]);
+
+        // 20 t/day × 250 days = 5,000 t a year
+        let violations = check(&mill(20.0, 8.0, 250), &[flour(5_500.0, 95.0)]);
+        assert_eq!(fields(&violations, Severity::Warning), ["processed_products"]);
+        let violations = check(&mill(20.0, 8.0, 250), &[flour(9_000.0, 95.0)]);
+        assert_eq!(fields(&violations, Severity::Error), ["processed_products"]);
+        assert!(violations[0].error.to_string().contains("180% of the 5000 t"), "{}", violations[0].error);
+
+        // Facilities of a company are reported under their place in the company
+        let violations = Plausibility::default().check_facility(&mill(20.0, 30.0, 250), &[], "facilities[2]");
+        assert_eq!(violations[0].error.field(), "facilities[2].facility_profile.operational_hours_per_day");
+    }
+}
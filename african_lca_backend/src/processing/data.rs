@@ -1,12 +1,25 @@
 use crate::models::*;
 use crate::processing::models::*;
-use std::error::Error;
-use std::collections::HashMap;
+use crate::error::DataLoadError;
+use std::collections::{BTreeMap, HashMap};
 
 pub struct ProcessingDataLoader {
     pub impact_factors: Vec<ProcessingImpactFactor>,
     pub benchmarks: Vec<ProcessingBenchmark>,
-    pub regional_factors: HashMap<String, f64>,
+    pub scale_curves: Vec<ProcessingScaleCurve>,
+    pub regional_factors: HashMap<RegionalFactorKey, f64>,
+    pub capex_table: HashMap<String, f64>, // USD, keyed by capex_key
+}
+
+/// Key of the up-front cost of a recommendation category at a facility size
+pub fn capex_key(category: &ProcessingRecommendationCategory, size: &CapacityRange) -> String {
+    format!("{}_{}", serialized_name(category), serialized_name(size))
+}
+
+impl Default for ProcessingDataLoader {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ProcessingDataLoader {
@@ -14,34 +27,82 @@ impl ProcessingDataLoader {
         Self {
             impact_factors: Vec::new(),
             benchmarks: Vec::new(),
+            scale_curves: Vec::new(),
             regional_factors: Self::initialize_processing_regional_factors(),
+            capex_table: Self::initialize_capex_table(),
         }
     }
 
-    fn initialize_processing_regional_factors() -> HashMap<String, f64> {
-        let mut factors = HashMap::new();
-        
-        // Energy emission factors by country (kg CO2/kWh)
-        factors.insert("Ghana_grid_emission_factor".to_string(), 0.45);
-        factors.insert("Nigeria_grid_emission_factor".to_string(), 0.52);
-        
-        // Water scarcity factors for processing industries
-        factors.insert("Ghana_processing_water_scarcity".to_string(), 25.0);
-        factors.insert("Nigeria_north_processing_water_scarcity".to_string(), 35.0);
-        factors.insert("Nigeria_south_processing_water_scarcity".to_string(), 20.0);
-        
-        // Waste management efficiency factors
-        factors.insert("Ghana_waste_management_efficiency".to_string(), 0.3);
-        factors.insert("Nigeria_waste_management_efficiency".to_string(), 0.25);
-        
-        // Labor productivity factors (output per worker-hour)
-        factors.insert("Ghana_processing_productivity".to_string(), 2.5);
-        factors.insert("Nigeria_processing_productivity".to_string(), 2.2);
-        
-        factors
+    /// Typical installed cost of each recommendation category by facility size (2024 prices),
+    /// in USD from supplier quotes for West African agro-processors. Assessments convert
+    /// it at their own exchange rates.
+    fn initialize_capex_table() -> HashMap<String, f64> {
+        use ProcessingRecommendationCategory::*;
+        let usd: [(ProcessingRecommendationCategory, [f64; 4]); 8] = [
+            //                   Small     Medium     Large      VeryLarge
+            (EnergyEfficiency,    [5_000.0, 20_000.0, 60_000.0, 150_000.0]),  // motors, VFDs, LED lighting
+            (WaterConservation,   [8_000.0, 30_000.0, 90_000.0, 200_000.0]),  // settling, filtration, recirculation
+            (WasteReduction,      [3_000.0, 10_000.0, 30_000.0, 80_000.0]),   // composting pads, segregation
+            (ProcessOptimization, [2_000.0, 8_000.0, 25_000.0, 60_000.0]),
+            (RawMaterialSourcing, [2_000.0, 8_000.0, 25_000.0, 60_000.0]),
+            (EquipmentUpgrade,    [15_000.0, 60_000.0, 180_000.0, 400_000.0]),
+            (RenewableEnergy,     [10_000.0, 40_000.0, 120_000.0, 300_000.0]), // rooftop PV with inverters
+            (WasteToEnergy,       [25_000.0, 100_000.0, 300_000.0, 700_000.0]), // covered lagoons, digesters
+        ];
+        let sizes = [CapacityRange::Small, CapacityRange::Medium, CapacityRange::Large, CapacityRange::VeryLarge];
+
+        let mut table = HashMap::new();
+        for (category, costs) in &usd {
+            for (size, cost) in sizes.iter().zip(costs) {
+                table.insert(capex_key(category, size), *cost);
+            }
+        }
+        table
     }
 
-    pub fn load_default_factors(&mut self) -> Result<(), Box<dyn Error>> {
+    fn initialize_processing_regional_factors() -> HashMap<RegionalFactorKey, f64> {
+        use RegionalFactorKind::*;
+        let national = |country, kind, value| (RegionalFactorKey::national(country, kind), value);
+        let regional = |country, region, kind, value| (RegionalFactorKey::regional(country, region, kind), value);
+        HashMap::from([
+            // Energy emission factors by country (kg CO2/kWh)
+            national(Country::Ghana, GridEmission, 0.45),
+            national(Country::Nigeria, GridEmission, 0.52),
+            national(Country::Global, GridEmission, 0.50),
+            // Regional overrides where the supply mix differs from the national grid
+            regional(Country::Ghana, "Northern", GridEmission, 0.48), // long lines, thermal imports
+            regional(Country::Nigeria, "Lagos", GridEmission, 0.47), // gas-fired Egbin supply
+            regional(Country::Nigeria, "Kano", GridEmission, 0.56),
+            // Captive generation (open-cycle gas turbines on industrial estates)
+            national(Country::Ghana, CaptiveGenerationEmission, 0.55),
+            national(Country::Nigeria, CaptiveGenerationEmission, 0.58),
+            national(Country::Global, CaptiveGenerationEmission, 0.55),
+            // Marginal (operating margin) grid factors for consequential modelling
+            national(Country::Ghana, MarginalGridEmission, 0.62),
+            national(Country::Nigeria, MarginalGridEmission, 0.68), // gas peakers
+            national(Country::Global, MarginalGridEmission, 0.70),
+
+            // Water scarcity factors for processing industries
+            national(Country::Ghana, ProcessingWaterScarcity, 25.0),
+            regional(Country::Nigeria, "north", ProcessingWaterScarcity, 35.0),
+            regional(Country::Nigeria, "south", ProcessingWaterScarcity, 20.0),
+
+            // Waste management efficiency factors
+            national(Country::Ghana, WasteManagementEfficiency, 0.3),
+            national(Country::Nigeria, WasteManagementEfficiency, 0.25),
+
+            // Landfill gas captured (fraction of CH4 generated); most sites are open dumps
+            national(Country::Ghana, LandfillGasCapture, 0.05), // Kpone engineered landfill
+            national(Country::Nigeria, LandfillGasCapture, 0.0),
+            national(Country::Global, LandfillGasCapture, 0.2),
+
+            // Labor productivity factors (output per worker-hour)
+            national(Country::Ghana, ProcessingProductivity, 2.5),
+            national(Country::Nigeria, ProcessingProductivity, 2.2),
+        ])
+    }
+
+    pub fn load_default_factors(&mut self) -> Result<(), DataLoadError> {
         // Load Ghana-specific processing factors
         self.add_ghana_processing_factors();
         
@@ -53,6 +114,9 @@ impl ProcessingDataLoader {
         
         // Load processing benchmarks
         self.add_processing_benchmarks();
+
+        // Economies of scale by facility size class
+        self.add_scale_curves();
         
         Ok(())
     }
@@ -78,6 +142,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::Mill,
@@ -97,6 +162,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::Mill,
@@ -116,6 +182,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 3,
                 },
+                capacity_range: None,
             },
 
             // CASSAVA PROCESSING - Major industry in Ghana
@@ -137,6 +204,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 3,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::CassivaProcessing,
@@ -156,6 +224,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 3,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::CassivaProcessing,
@@ -175,6 +244,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
 
             // PALM OIL PROCESSING - Important for Ghana
@@ -196,6 +266,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 3,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::PalmOilMill,
@@ -215,6 +286,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
 
             // FISH PROCESSING - Coastal Ghana
@@ -236,6 +308,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 4,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::FishProcessing,
@@ -255,6 +328,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 3,
                 },
+                capacity_range: None,
             },
         ];
 
@@ -282,6 +356,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::RiceProcessing,
@@ -301,6 +376,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::RiceProcessing,
@@ -320,6 +396,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
 
             // CASSAVA PROCESSING - Nigeria is world's largest producer
@@ -341,6 +418,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::CassivaProcessing,
@@ -360,6 +438,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
 
             // FLOUR MILLING - Wheat and other grains
@@ -381,6 +460,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 3,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::Mill,
@@ -400,6 +480,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                capacity_range: None,
             },
 
             // PALM OIL PROCESSING - Growing industry
@@ -421,6 +502,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 4,
                 },
+                capacity_range: None,
             },
 
             // MEAT PROCESSING - Growing urban demand
@@ -442,6 +524,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 4,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::MeatProcessing,
@@ -461,6 +544,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 4,
                 },
+                capacity_range: None,
             },
         ];
 
@@ -488,6 +572,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 5,
                     technological_correlation: 4,
                 },
+                capacity_range: None,
             },
             ProcessingImpactFactor {
                 facility_type: ProcessingFacilityType::Mill,
@@ -507,6 +592,7 @@ impl ProcessingDataLoader {
                     geographical_correlation: 5,
                     technological_correlation: 4,
                 },
+                capacity_range: None,
             },
         ];
 
@@ -520,7 +606,7 @@ impl ProcessingDataLoader {
                 facility_type: ProcessingFacilityType::Mill,
                 capacity_range: CapacityRange::Small,
                 country: Country::Ghana,
-                benchmarks: HashMap::from([
+                benchmarks: BTreeMap::from([
                     ("Energy consumption".to_string(), ProcessingBenchmarkValue {
                         best_practice: 50.0, // kWh/tonne
                         average: 70.0,
@@ -539,7 +625,7 @@ impl ProcessingDataLoader {
                 facility_type: ProcessingFacilityType::Mill,
                 capacity_range: CapacityRange::Medium,
                 country: Country::Ghana,
-                benchmarks: HashMap::from([
+                benchmarks: BTreeMap::from([
                     ("Energy consumption".to_string(), ProcessingBenchmarkValue {
                         best_practice: 45.0,
                         average: 65.0,
@@ -560,7 +646,7 @@ impl ProcessingDataLoader {
                 facility_type: ProcessingFacilityType::RiceProcessing,
                 capacity_range: CapacityRange::Medium,
                 country: Country::Nigeria,
-                benchmarks: HashMap::from([
+                benchmarks: BTreeMap::from([
                     ("Energy consumption".to_string(), ProcessingBenchmarkValue {
                         best_practice: 85.0,
                         average: 110.0,
@@ -581,7 +667,7 @@ impl ProcessingDataLoader {
                 facility_type: ProcessingFacilityType::CassivaProcessing,
                 capacity_range: CapacityRange::Small,
                 country: Country::Ghana,
-                benchmarks: HashMap::from([
+                benchmarks: BTreeMap::from([
                     ("Energy consumption".to_string(), ProcessingBenchmarkValue {
                         best_practice: 30.0,
                         average: 45.0,
@@ -601,6 +687,38 @@ impl ProcessingDataLoader {
         self.benchmarks.extend(benchmarks);
     }
 
+    fn add_scale_curves(&mut self) {
+        let curve = |facility_type, small, medium, large, very_large, source: &str| ProcessingScaleCurve {
+            facility_type,
+            small,
+            medium,
+            large,
+            very_large,
+            source: source.to_string(),
+        };
+
+        self.scale_curves.extend(vec![
+            // Hammer/plate mills use more energy per tonne than roller mills
+            curve(ProcessingFacilityType::Mill, 1.25, 1.0, 0.9, 0.85,
+                  "Engineering estimate: specific energy of plate vs. roller milling"),
+            curve(ProcessingFacilityType::RiceProcessing, 1.25, 1.0, 0.9, 0.85,
+                  "Engineering estimate: single-pass vs. multi-stage rice mills"),
+            // Village gari roasting on open fires is the least efficient step at small scale
+            curve(ProcessingFacilityType::CassivaProcessing, 1.3, 1.0, 0.85, 0.8,
+                  "Engineering estimate: open-fire vs. mechanised gari roasting"),
+            // Artisanal presses boil fruit in open drums with low oil extraction
+            curve(ProcessingFacilityType::PalmOilMill, 1.4, 1.0, 0.85, 0.8,
+                  "Engineering estimate: artisanal vs. industrial palm oil extraction"),
+            // Applied to facility types without a dedicated curve
+            curve(ProcessingFacilityType::General, 1.2, 1.0, 0.9, 0.8,
+                  "Generic economies of scale"),
+        ]);
+    }
+
+    pub fn get_scale_curves(&self) -> &Vec<ProcessingScaleCurve> {
+        &self.scale_curves
+    }
+
     pub fn get_factors(&self) -> &Vec<ProcessingImpactFactor> {
         &self.impact_factors
     }
@@ -609,7 +727,11 @@ impl ProcessingDataLoader {
         &self.benchmarks
     }
 
-    pub fn get_regional_factor(&self, key: &str) -> Option<f64> {
+    pub fn get_regional_factor(&self, key: &RegionalFactorKey) -> Option<f64> {
         self.regional_factors.get(key).copied()
     }
+
+    pub fn get_capex(&self, key: &str) -> Option<f64> {
+        self.capex_table.get(key).copied()
+    }
 }
@@ -1,68 +1,287 @@
 use crate::models::*;
+use crate::error::{EngineError, InputError};
+use crate::units::{Mass, Volume};
 use crate::processing::models::{
-    ProcessingAssessment, ProcessingFacilityProfile, ProcessingOperations, ProcessedProduct,
-    ProcessingImpactFactor, ProcessingBenchmark, ProcessingFacilityType, ProductType,
-    EnergySource, WasteDisposalMethod, WastewaterTreatment, LocationType,
-    EquipmentAge, MaintenanceFrequency,
-    ProcessingRecommendation, ProcessingRecommendationCategory, ImplementationCost, ComplexityLevel,
+    ProcessingAssessment, CompanyAssessment, CompanyResults, SiteComparison, SiteRanking,
+    TrendAnalysis, IntensityTrend, ProductTrend, TrendStatus, ImplementedMeasure,
+    ProcessingFacilityProfile, ProcessingOperations, ProcessedProduct, FacilityOverhead,
+    ProcessingImpactFactor, ProcessingBenchmark, ProcessingScaleCurve, UpstreamBurden,
+    ProcessingFacilityType, ProductType, CapacityRange,
+    EnergySource, ElectricityEmissionFactor, DryingMethod, WasteDisposalMethod, WasteManagement,
+    WasteComposition, WasteStreamImpact, WastewaterTreatment, LocationType,
+    EquipmentAge, MaintenanceFrequency, RefrigerationSystem, StoragePractices, WastewaterLoad, WaterManagement,
+    ProcessingRecommendation, ProcessingRecommendationCategory, RegionalFactorKey, RegionalFactorKind, ImplementationCost, ComplexityLevel,
     Priority as ProcessingPriority
 };
-use std::collections::HashMap;
-use log::info;
+use crate::processing::data::capex_key;
+use crate::production::interpretation::{
+    ScoreReference, SingleScoreInterpretation, default_score_references, find_score_reference, food_loss_footprint,
+    interpret_single_score
+};
+use crate::scoring::{african_reference_per_kg, weighted_single_score};
+use crate::production::carbon_footprint::{CarbonOriginSplit, ProductCarbonFootprint};
+use crate::production::ghg_scopes::{ScopeBreakdown, ScopeTable};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use log::{info, warn};
+use rayon::prelude::*;
+
+/// GWP100 of biogenic methane used for effluent ponds (IPCC AR5, consistent with the LCI module)
+const CH4_GWP100: f64 = 28.0;
+/// GWP100 of N2O (IPCC AR5, paired with CH4_GWP100)
+const N2O_GWP100: f64 = 265.0;
+/// IPCC 2019 maximum CH4 producing capacity (kg CH4/kg COD)
+const CH4_PER_KG_COD: f64 = 0.25;
+/// Share of the operating-month overhead load (offices, security lighting, cold rooms)
+/// that continues while a seasonal facility stands idle
+const IDLE_STANDBY_FRACTION: f64 = 0.1;
+/// Open-cycle gas turbine supplying an industrial estate (kg CO2-eq/kWh)
+const CAPTIVE_GENERATION_FACTOR: f64 = 0.55;
+
+/// Pollutant mass leaving the facility in its effluent each year
+#[derive(Debug, Clone, Copy, Default)]
+struct WastewaterDischarge {
+    nitrogen_kg: f64,
+    phosphorus_kg: f64,
+    methane_kg: f64,
+}
+
+/// Facility overhead for the operating months, allocated to products by production share
+#[derive(Debug, Clone, Default)]
+struct OverheadAllocation {
+    energy_kwh: f64, // excluding idle-month standby
+    water_m3: f64,
+    total_production: f64,
+    source: String, // entered by the user or the default used
+    legacy_energy_factor: Option<f64>, // deprecated multiplier applied to steps instead
+}
+
+impl OverheadAllocation {
+    fn share(&self, production: f64) -> f64 {
+        if self.total_production > 0.0 { production / self.total_production } else { 0.0 }
+    }
+
+    fn is_legacy(&self) -> bool {
+        self.legacy_energy_factor.is_some()
+    }
+}
+
+/// Facility electricity from the utility bill versus the step-level and overhead estimate.
+/// When both exist, the metered figure sets the total and the estimate is scaled to match.
+#[derive(Debug, Clone, Default)]
+struct EnergyReconciliation {
+    metered_kwh: Option<f64>,
+    step_based_kwh: f64, // steps, overhead and idle-month standby
+    process_kwh: f64, // processing steps (including the legacy multiplier when in use)
+    idle_standby_kwh: f64,
+    total_production: f64,
+    overhead: OverheadAllocation,
+    grid_factor: ElectricityEmissionFactor, // for electricity drawn from the grid
+}
+
+impl EnergyReconciliation {
+    /// Multiplier applied to the estimate so it sums to the metered total
+    fn scale(&self) -> f64 {
+        match self.metered_kwh {
+            Some(metered) if self.step_based_kwh > 0.0 => metered / self.step_based_kwh,
+            _ => 1.0,
+        }
+    }
+
+    /// Multiplier from a product's step electricity to its share of the estimate, before
+    /// metered scaling: 1 with explicit overhead, else the legacy multiplier with idle-month
+    /// standby spread over step energy
+    fn step_factor(&self) -> f64 {
+        match self.overhead.legacy_energy_factor {
+            Some(factor) if self.process_kwh > 0.0 => factor * (self.process_kwh + self.idle_standby_kwh) / self.process_kwh,
+            Some(factor) => factor,
+            None => 1.0,
+        }
+    }
+
+    /// Overhead and idle-month standby allocated to a product, before metered scaling
+    fn overhead_kwh(&self, production: f64) -> f64 {
+        if self.overhead.is_legacy() {
+            return 0.0;
+        }
+        (self.overhead.energy_kwh + self.idle_standby_kwh) * self.overhead.share(production)
+    }
+
+    /// Relative gap between estimated and metered consumption, when the meter and step
+    /// intensities are both known (overhead alone is too rough to check a bill against)
+    fn discrepancy_percent(&self) -> Option<f64> {
+        match self.metered_kwh {
+            Some(metered) if self.process_kwh > 0.0 => {
+                Some((self.step_based_kwh - metered).abs() / metered * 100.0)
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Pedigree assumed for impacts calculated from the facility's own activity data
+/// (step intensities, meter readings, effluent loads) using generic engineering coefficients
+const ACTIVITY_DATA_PEDIGREE: PedigreeScore = PedigreeScore {
+    reliability: 3,
+    completeness: 3,
+    temporal_correlation: 1,
+    geographical_correlation: 1,
+    technological_correlation: 2,
+};
 
+/// Where a product's result for one impact category came from
+#[derive(Debug, Clone)]
+enum FactorBasis {
+    /// A loaded ProcessingImpactFactor
+    Specific { country: Country, source: String, pedigree: PedigreeScore },
+    /// Calculated from the facility's reported activity data
+    ActivityData,
+    /// Hardcoded default estimate for the facility/product type
+    DefaultEstimate,
+}
+
+impl From<&ProcessingImpactFactor> for FactorBasis {
+    fn from(factor: &ProcessingImpactFactor) -> Self {
+        FactorBasis::Specific {
+            country: factor.country,
+            source: factor.source.clone(),
+            pedigree: factor.pedigree_score.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FactorUse {
+    product: String,
+    category: String,
+    basis: FactorBasis,
+}
+
+impl FactorUse {
+    fn pedigree(&self) -> Option<&PedigreeScore> {
+        match &self.basis {
+            FactorBasis::Specific { pedigree, .. } => Some(pedigree),
+            FactorBasis::ActivityData => Some(&ACTIVITY_DATA_PEDIGREE),
+            FactorBasis::DefaultEstimate => None,
+        }
+    }
+}
+
+/// Facility type, product type, country, impact category and the size class a factor is
+/// specific to (None for every size)
+type FactorKey = (ProcessingFacilityType, ProductType, Country, String, Option<CapacityRange>);
+
+/// Cheap to clone: the loaded databases are shared between clones and copied only when one
+/// is modified
+#[derive(Clone)]
 pub struct ProcessingLCAEngine {
-    impact_factors: HashMap<String, ProcessingImpactFactor>,
-    benchmarks: HashMap<String, ProcessingBenchmark>,
+    impact_factors: Arc<HashMap<FactorKey, ProcessingImpactFactor>>,
+    benchmarks: Arc<HashMap<(ProcessingFacilityType, CapacityRange, Country), ProcessingBenchmark>>,
+    scale_curves: Arc<HashMap<ProcessingFacilityType, ProcessingScaleCurve>>,
     characterization_factors: CharacterizationFactors,
-    regional_factors: HashMap<String, f64>,
+    regional_factors: Arc<HashMap<RegionalFactorKey, f64>>,
+    capex_table: Arc<HashMap<String, f64>>,
     methodology: LCAMethodology,
+    upstream_burdens: Arc<HashMap<String, Vec<UpstreamBurden>>>, // by raw material name
+    scope_table: Arc<ScopeTable>, // GHG Protocol scope of each emission source
+    score_references: Arc<Vec<ScoreReference>>, // single score distributions, first match wins
 }
 
 impl ProcessingLCAEngine {
     pub fn new(methodology: LCAMethodology) -> Self {
         Self {
-            impact_factors: HashMap::new(),
-            benchmarks: HashMap::new(),
+            impact_factors: Arc::default(),
+            benchmarks: Arc::default(),
+            scale_curves: Arc::default(),
             characterization_factors: CharacterizationFactors::default(),
-            regional_factors: HashMap::new(),
+            regional_factors: Arc::default(),
+            capex_table: Arc::default(),
             methodology,
+            upstream_burdens: Arc::default(),
+            scope_table: Arc::default(),
+            score_references: default_score_references(),
         }
     }
 
+    /// Replace the methodology, keeping the loaded factors
+    pub fn set_methodology(&mut self, methodology: LCAMethodology) {
+        self.methodology = methodology;
+    }
+
+    /// Charge `burden` for every kg of the raw material named `material_name` that a
+    /// product uses; a material can carry several (e.g. cultivation and transport)
+    pub fn add_upstream_burden(&mut self, material_name: &str, burden: UpstreamBurden) {
+        Arc::make_mut(&mut self.upstream_burdens).entry(material_name.to_string()).or_default().push(burden);
+    }
+
+    pub fn clear_upstream_burdens(&mut self) {
+        self.upstream_burdens = Arc::default();
+    }
+
     pub fn load_impact_factors(&mut self, factors: Vec<ProcessingImpactFactor>) {
         for factor in factors {
-            let key = self.create_factor_key(&factor);
-            self.impact_factors.insert(key, factor);
+            let key = (
+                factor.facility_type,
+                factor.product_type.clone(),
+                factor.country,
+                factor.impact_category.clone(),
+                factor.capacity_range,
+            );
+            Arc::make_mut(&mut self.impact_factors).insert(key, factor);
         }
         info!("Loaded {} processing impact factors", self.impact_factors.len());
     }
 
     pub fn load_benchmarks(&mut self, benchmarks: Vec<ProcessingBenchmark>) {
         for benchmark in benchmarks {
-            let key = format!("{:?}_{:?}_{:?}", benchmark.facility_type, benchmark.capacity_range, benchmark.country);
-            self.benchmarks.insert(key, benchmark);
+            let key = (benchmark.facility_type, benchmark.capacity_range, benchmark.country);
+            Arc::make_mut(&mut self.benchmarks).insert(key, benchmark);
         }
         info!("Loaded {} processing benchmarks", self.benchmarks.len());
     }
 
-    pub fn load_regional_factors(&mut self, factors: HashMap<String, f64>) {
-        self.regional_factors = factors;
+    /// Single score distributions to interpret results against, searched before those
+    /// already loaded (the bundled defaults for a new engine)
+    pub fn load_score_references(&mut self, references: Vec<ScoreReference>) {
+        Arc::make_mut(&mut self.score_references).splice(0..0, references);
+    }
+
+    pub fn load_scale_curves(&mut self, curves: Vec<ProcessingScaleCurve>) {
+        for curve in curves {
+            Arc::make_mut(&mut self.scale_curves).insert(curve.facility_type, curve);
+        }
+        info!("Loaded {} processing scale curves", self.scale_curves.len());
+    }
+
+    pub fn load_regional_factors(&mut self, factors: impl IntoIterator<Item = (RegionalFactorKey, f64)>) {
+        self.regional_factors = Arc::new(factors.into_iter().collect());
+    }
+
+    pub fn load_capex_table(&mut self, table: HashMap<String, f64>) {
+        self.capex_table = Arc::new(table);
     }
 
-    fn create_factor_key(&self, factor: &ProcessingImpactFactor) -> String {
-        format!("{:?}_{:?}_{:?}_{}", 
-            factor.facility_type, 
-            factor.product_type, 
-            factor.country, 
-            factor.impact_category)
+    /// Replace the GHG scope classification, e.g. with rows for contracted transport
+    pub fn load_scope_table(&mut self, table: ScopeTable) {
+        self.scope_table = Arc::new(table);
     }
 
-    pub fn perform_processing_assessment(&self, assessment: &mut ProcessingAssessment) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn perform_processing_assessment(&self, assessment: &mut ProcessingAssessment) -> Result<(), EngineError> {
         info!("Starting processing LCA assessment for {} using {:?}", 
               assessment.facility_profile.company_name, self.methodology.characterization_method);
 
-        let mut midpoint_impacts = HashMap::new();
-        let mut breakdown_by_product = HashMap::new();
+        if assessment.processed_products.is_empty() {
+            return Err(InputError::invalid("processed_products", "an assessment needs at least one product").into());
+        }
+        // Out-of-range percentages would turn impact multipliers negative
+        assessment.processing_operations.validate_percentages()?;
+        assessment.exchange_rates.validate()?;
+        assessment.facility_profile.validate_daytime_operation()?;
+        self.methodology.validate_impact_categories(&self.all_processing_impact_categories())?;
+
+        let mut midpoint_impacts = BTreeMap::new();
+        let mut breakdown_by_product = BTreeMap::new();
+        let mut product_results_list = Vec::new();
 
         let impact_categories = self.get_processing_impact_categories();
 
@@ -71,56 +290,269 @@ impl ProcessingLCAEngine {
             midpoint_impacts.insert(category.clone(), MidpointResult {
                 value: 0.0,
                 unit: self.get_processing_impact_unit(category),
+                basis: ImpactBasis::Total,
                 uncertainty_range: (0.0, 0.0),
+                geometric_sd: None,
                 data_quality_score: 0.0,
                 contributing_sources: Vec::new(),
+                metadata: None,
             });
         }
 
-        // Calculate impacts for each processed product
-        for product in &assessment.processed_products {
-            let product_results = self.calculate_product_impacts(
-                product, 
-                &assessment.facility_profile,
-                &assessment.processing_operations,
-                &assessment.country
-            )?;
+        let energy = self.reconcile_energy(
+            &assessment.processed_products,
+            &assessment.facility_profile,
+            &assessment.processing_operations,
+            &assessment.country,
+            &assessment.region
+        );
+
+        // Calculate impacts for each processed product, in parallel; the engine is read-only
+        // here, and the results are combined below in product order so totals do not depend
+        // on thread scheduling
+        let per_product = assessment.processed_products.par_iter()
+            .map(|product| {
+                let (mut product_factor_uses, mut product_ghg_sources) = (Vec::new(), Vec::new());
+                let mut product_results = self.calculate_product_impacts(
+                    product,
+                    &assessment.facility_profile,
+                    &assessment.processing_operations,
+                    &assessment.country,
+                    &energy,
+                    &mut product_factor_uses,
+                    &mut product_ghg_sources
+                )?;
+                self.add_upstream_burdens(
+                    product,
+                    &assessment.processing_operations.raw_material_sourcing.storage_practices,
+                    &assessment.country,
+                    &mut product_results,
+                    &mut product_ghg_sources
+                );
+                Ok::<_, EngineError>((product, product_results, product_factor_uses, product_ghg_sources))
+            })
+            .collect::<Result<Vec<_>, EngineError>>()?;
+
+        let mut factor_uses = Vec::new();
+        let mut ghg_sources = Vec::new();
+        let mut product_origin_splits = Vec::new();
+        for (product, product_results, product_factor_uses, product_ghg_sources) in per_product {
+            factor_uses.extend(product_factor_uses);
+            product_origin_splits.push(CarbonOriginSplit::classify(
+                product_ghg_sources.clone(),
+                product_results.get("Global warming").map(|gwp| gwp.value).unwrap_or(0.0),
+                &self.scope_table
+            ));
+            ghg_sources.extend(product_ghg_sources);
             
             // Add to breakdown
             breakdown_by_product.insert(
                 format!("{} ({} tonnes/year)", product.name, product.annual_production),
                 product_results.clone()
             );
+            product_results_list.push((product, product_results.clone()));
 
             // Aggregate impacts
             for (category, result) in product_results {
                 if let Some(total_result) = midpoint_impacts.get_mut(&category) {
-                    self.aggregate_midpoint_results(total_result, &result);
+                    total_result.aggregate(&result);
+                }
+            }
+        }
+
+        let product_sources_end = ghg_sources.len();
+
+        // Facility-level fugitive refrigerant emissions (not attributable to a single product)
+        if let Some(ref refrigeration) = assessment.processing_operations.refrigeration {
+            let refrigerant_gwp = self.calculate_refrigerant_emissions(
+                refrigeration,
+                &assessment.facility_profile,
+                &assessment.country
+            );
+            if let Some(gwp) = midpoint_impacts.get_mut("Global warming") {
+                gwp.value += refrigerant_gwp;
+                gwp.uncertainty_range.0 += refrigerant_gwp * 0.5;
+                gwp.uncertainty_range.1 += refrigerant_gwp * 1.5;
+                gwp.contributing_sources.push(format!(
+                    "Refrigerant leakage ({:?}): {:.0} kg CO2-eq/year",
+                    refrigeration.refrigerant_type, refrigerant_gwp
+                ));
+                ghg_sources.push((format!("Refrigerant leakage ({:?})", refrigeration.refrigerant_type), refrigerant_gwp));
+            }
+        }
+
+        // Separately handled waste streams, each with its own fate
+        let waste_streams = self.waste_stream_impacts(
+            &assessment.processing_operations.waste_management,
+            &assessment.country,
+            &energy.grid_factor
+        );
+        if let Some(gwp) = midpoint_impacts.get_mut("Global warming") {
+            for stream in &waste_streams {
+                let (low, high) = (stream.gwp_kg_co2e * 0.5, stream.gwp_kg_co2e * 1.5);
+                gwp.value += stream.gwp_kg_co2e;
+                gwp.uncertainty_range.0 += low.min(high);
+                gwp.uncertainty_range.1 += low.max(high);
+                gwp.contributing_sources.push(format!(
+                    "Waste stream {} ({:.0} t, {:?}): {:.0} kg CO2-eq/year",
+                    stream.stream_name, stream.tonnes_per_year, stream.disposal_method, stream.gwp_kg_co2e
+                ));
+                ghg_sources.push((format!("Waste stream {} ({:?})", stream.stream_name, stream.disposal_method), stream.gwp_kg_co2e));
+            }
+        }
+
+        // Consequential: utilized byproducts avoid producing what they replace
+        if self.methodology.modeling_approach == ModelingApproach::Consequential {
+            let credits = self.byproduct_credits(&assessment.processing_operations.waste_management, &assessment.facility_profile);
+            if let Some(gwp) = midpoint_impacts.get_mut("Global warming") {
+                for (byproduct, tonnes, credit) in credits {
+                    gwp.value -= credit;
+                    gwp.uncertainty_range.0 -= credit * 1.5;
+                    gwp.uncertainty_range.1 -= credit * 0.5;
+                    gwp.contributing_sources.push(format!(
+                        "System expansion credit for {} ({:.0} t utilized): -{:.0} kg CO2-eq/year",
+                        byproduct, tonnes, credit
+                    ));
+                    ghg_sources.push((format!("System expansion credit for {}", byproduct), -credit));
                 }
             }
         }
 
         // Apply processing-specific adjustments
         self.apply_processing_adjustments(&mut midpoint_impacts, &assessment.processing_operations, &assessment.country);
+        midpoint_impacts.retain(|category, _| self.methodology.includes_category(category));
+        let sign_warnings: Vec<String> = midpoint_impacts.iter_mut()
+            .filter_map(|(category, result)| result.settle_sign(category))
+            .collect();
+        for product_results in breakdown_by_product.values_mut() {
+            product_results.retain(|category, _| self.methodology.includes_category(category));
+        }
+
+        // Per-tonne intensities, with facility-level burdens allocated across products
+        let product_footprints = self.calculate_product_footprints(&midpoint_impacts, &product_results_list);
 
         // Calculate endpoint impacts
         let endpoint_impacts = self.calculate_processing_endpoint_impacts(&midpoint_impacts)?;
 
         // Calculate single score
-        let single_score = self.calculate_processing_single_score(&endpoint_impacts)?;
+        let total_production: f64 = assessment.processed_products.iter().map(|p| p.annual_production).sum();
+        let single_score = self.calculate_processing_single_score(&endpoint_impacts, total_production, &assessment.country)?;
 
         // Assess data quality
-        let data_quality = self.assess_processing_data_quality(&assessment.processed_products, &assessment.country)?;
+        let mut data_quality = self.assess_processing_data_quality(
+            &assessment.processed_products,
+            &assessment.facility_profile,
+            &assessment.country,
+            &factor_uses
+        )?;
+        data_quality.warnings.extend(sign_warnings);
+        if let Some(warning) = self.methodology.characterization_warning() {
+            warn!("{}", warning);
+            data_quality.warnings.push(warning);
+        }
+        let cleaning_steps: Vec<&str> = assessment.processed_products.iter()
+            .flat_map(|product| &product.processing_steps)
+            .map(|step| step.step_name.as_str())
+            .filter(|name| name.to_lowercase().contains("clean") || name.contains("CIP"))
+            .collect();
+        if !cleaning_steps.is_empty() && !energy.overhead.is_legacy()
+            && assessment.processing_operations.overhead.water_m3_per_year.is_none() {
+            data_quality.warnings.push(format!(
+                "Steps {} already count cleaning water, and the default facility overhead ({:.0} m3/year) includes general cleaning; enter overhead water without cleaning to avoid double counting",
+                cleaning_steps.join(", "), energy.overhead.water_m3
+            ));
+        }
+        if let Some(discrepancy) = energy.discrepancy_percent().filter(|pct| *pct > 25.0) {
+            data_quality.warnings.push(format!(
+                "Metered electricity ({:.0} kWh/year) differs from step-level estimate ({:.0} kWh/year) by {:.0}%; totals use metered consumption",
+                energy.metered_kwh.unwrap_or_default(), energy.step_based_kwh, discrepancy
+            ));
+        }
 
-        // Generate recommendations
-        let recommendations = self.generate_processing_recommendations(
+        // Generate recommendations, costed in the assessment's currency
+        let mut recommendations = self.generate_processing_recommendations(
             &midpoint_impacts, 
             &assessment.facility_profile,
-            &assessment.processing_operations
+            &assessment.processing_operations,
+            &assessment.processed_products,
+            &assessment.country,
+            &energy
         )?;
+        self.cost_recommendations(
+            &mut recommendations,
+            &midpoint_impacts,
+            &assessment.facility_profile,
+            &assessment.processing_operations,
+            (&assessment.currency, &assessment.exchange_rates),
+            &energy
+        );
+
+        // Compare against peers of the same type and size class
+        let benchmarking = self.benchmark_facility(
+            &midpoint_impacts,
+            &assessment.facility_profile,
+            &assessment.country,
+            energy.total_production
+        );
+
+        // Impacts embedded in raw material lost to processing yield
+        let yield_losses: HashMap<String, f64> = assessment.processed_products.iter()
+            .map(|product| (
+                format!("{} ({} tonnes/year)", product.name, product.annual_production),
+                1.0 - product.processing_steps.iter()
+                    .map(|step| (step.yield_efficiency / 100.0).clamp(0.01, 1.0))
+                    .product::<f64>()
+            ))
+            .collect();
+        let food_loss = food_loss_footprint(&midpoint_impacts, &breakdown_by_product, &yield_losses);
+        let product_carbon_footprints = midpoint_impacts.get("Global warming").map(|gwp| self.carbon_footprints(
+            assessment,
+            gwp,
+            &product_results_list,
+            &product_origin_splits,
+            &ghg_sources[product_sources_end..],
+            &data_quality
+        ));
+        let ghg_scopes = midpoint_impacts.get("Global warming")
+            .map(|gwp| ScopeBreakdown::classify(ghg_sources, gwp.value, &self.scope_table));
+
+        let mut recommendations: Vec<Recommendation> = recommendations.into_iter().map(|rec| Recommendation {
+            category: match rec.category {
+                ProcessingRecommendationCategory::EnergyEfficiency => RecommendationCategory::EnergyEfficiency,
+                ProcessingRecommendationCategory::WaterConservation => RecommendationCategory::WaterManagement,
+                ProcessingRecommendationCategory::WasteReduction => RecommendationCategory::PostHarvest,
+                ProcessingRecommendationCategory::EquipmentUpgrade => RecommendationCategory::SystemDesign,
+                _ => RecommendationCategory::EnergyEfficiency,
+            },
+            title: rec.title,
+            description: rec.description,
+            potential_impact_reduction: rec.potential_savings,
+            implementation_difficulty: match rec.complexity {
+                ComplexityLevel::Simple => DifficultyLevel::Low,
+                ComplexityLevel::Moderate => DifficultyLevel::Medium,
+                ComplexityLevel::Complex => DifficultyLevel::High,
+                ComplexityLevel::VeryComplex => DifficultyLevel::High,
+            },
+            cost_category: match rec.implementation_cost {
+                ImplementationCost::Low => CostCategory::LowCost,
+                ImplementationCost::Medium => CostCategory::MediumCost,
+                ImplementationCost::High => CostCategory::HighCost,
+                ImplementationCost::VeryHigh => CostCategory::HighCost,
+            },
+            priority: match rec.priority {
+                ProcessingPriority::Critical | ProcessingPriority::High => Priority::High,
+                ProcessingPriority::Medium => Priority::Medium,
+                ProcessingPriority::Low => Priority::Low,
+            },
+            estimated_annual_savings: rec.estimated_annual_savings,
+            payback_months: rec.payback_period,
+            marginal_abatement_cost: None,
+        }).collect();
+        let global_warming_kg = midpoint_impacts.get("Global warming").map_or(0.0, |result| result.value);
+        prioritize_recommendations(&mut recommendations, global_warming_kg, &assessment.currency, &assessment.exchange_rates);
 
         // Store results
-        assessment.results = Some(LCAResults {
+        let mut results = LCAResults {
             midpoint_impacts,
             endpoint_impacts,
             single_score,
@@ -129,44 +561,581 @@ impl ProcessingLCAEngine {
             sensitivity_analysis: None,
             comparative_analysis: None,
             management_analysis: None,
-            benchmarking: None,
-            recommendations: Some(recommendations.into_iter().map(|rec| Recommendation {
-                category: match rec.category {
-                    ProcessingRecommendationCategory::EnergyEfficiency => RecommendationCategory::EnergyEfficiency,
-                    ProcessingRecommendationCategory::WaterConservation => RecommendationCategory::WaterManagement,
-                    ProcessingRecommendationCategory::WasteReduction => RecommendationCategory::PostHarvest,
-                    ProcessingRecommendationCategory::EquipmentUpgrade => RecommendationCategory::SystemDesign,
-                    _ => RecommendationCategory::EnergyEfficiency,
-                },
-                title: rec.title,
-                description: rec.description,
-                potential_impact_reduction: rec.potential_savings,
-                implementation_difficulty: match rec.complexity {
-                    ComplexityLevel::Simple => DifficultyLevel::Low,
-                    ComplexityLevel::Moderate => DifficultyLevel::Medium,
-                    ComplexityLevel::Complex => DifficultyLevel::High,
-                    ComplexityLevel::VeryComplex => DifficultyLevel::High,
-                },
-                cost_category: match rec.implementation_cost {
-                    ImplementationCost::Low => CostCategory::LowCost,
-                    ImplementationCost::Medium => CostCategory::MediumCost,
-                    ImplementationCost::High => CostCategory::HighCost,
-                    ImplementationCost::VeryHigh => CostCategory::HighCost,
-                },
-                priority: Priority::Medium,
-            }).collect()),
+            benchmarking,
+            recommendations: Some(recommendations),
             lci_inventory: None,
-        });
+            product_footprints: Some(product_footprints),
+            capacity_range: Some(assessment.facility_profile.capacity_range()),
+            food_loss_footprint: Some(food_loss),
+            ghg_scopes,
+            target_compliance: None,
+            product_carbon_footprints,
+            water_footprint: None,
+            impacts_per_ha: None,
+        };
+        if assessment.methodology.include_result_metadata {
+            results.attach_metadata(&self.methodology.characterization_method);
+        }
+        assessment.results = Some(results);
 
         info!("Processing assessment completed for {}", assessment.facility_profile.company_name);
         Ok(())
     }
 
+    /// Assess every facility of a company, then consolidate: midpoint and endpoint totals are
+    /// summed, the single score is weighted by each site's production, and sites are ranked
+    /// by impact intensity per tonne for every midpoint category.
+    pub fn perform_company_assessment(&self, company: &mut CompanyAssessment) -> Result<(), EngineError> {
+        info!("Starting company assessment for {} ({} facilities)", company.company_name, company.facilities.len());
+        if company.facilities.is_empty() {
+            return Err(InputError::invalid("facilities", "a company assessment needs at least one facility").into());
+        }
+
+        for facility in company.facilities.iter_mut() {
+            self.perform_processing_assessment(facility)?;
+        }
+
+        let sites: Vec<(&str, f64, &LCAResults)> = company.facilities.iter()
+            .filter_map(|f| f.results.as_ref().map(|r| (
+                f.facility_profile.facility_name.as_str(),
+                f.processed_products.iter().map(|p| p.annual_production).sum::<f64>(),
+                r
+            )))
+            .collect();
+        let total_production: f64 = sites.iter().map(|(_, production, _)| production).sum();
+
+        let mut midpoint_impacts: BTreeMap<String, MidpointResult> = BTreeMap::new();
+        let mut endpoint_impacts: BTreeMap<String, EndpointResult> = BTreeMap::new();
+        for (name, _, results) in &sites {
+            for (category, result) in &results.midpoint_impacts {
+                let total = midpoint_impacts.entry(category.clone()).or_insert_with(|| MidpointResult {
+                    value: 0.0,
+                    unit: result.unit.clone(),
+                    basis: result.basis,
+                    uncertainty_range: (0.0, 0.0),
+                    geometric_sd: None,
+                    data_quality_score: result.data_quality_score,
+                    contributing_sources: Vec::new(),
+                    metadata: None,
+                });
+                let site = MidpointResult {
+                    contributing_sources: vec![format!("{}: {:.2} {}", name, result.value, result.unit)],
+                    ..result.clone()
+                };
+                total.aggregate(&site);
+                total.data_quality_score = total.data_quality_score.min(result.data_quality_score);
+            }
+            for (category, result) in &results.endpoint_impacts {
+                let total = endpoint_impacts.entry(category.clone()).or_insert_with(|| EndpointResult {
+                    value: 0.0,
+                    unit: result.unit.clone(),
+                    uncertainty_range: (0.0, 0.0),
+                    normalization_factor: result.normalization_factor,
+                    regional_adaptation_factor: result.regional_adaptation_factor,
+                    contributions: BTreeMap::new(),
+                    metadata: None,
+                });
+                total.value += result.value;
+                total.uncertainty_range.0 += result.uncertainty_range.0;
+                total.uncertainty_range.1 += result.uncertainty_range.1;
+            }
+        }
+        // Endpoints are linear in the midpoints, so the consolidated midpoints split them
+        let characterization = self.endpoint_characterization();
+        for (category, total) in &mut endpoint_impacts {
+            total.contributions = endpoint_contributions(category, &characterization, &midpoint_impacts);
+        }
+
+        let production_weight = |production: f64| {
+            if total_production > 0.0 { production / total_production } else { 1.0 / sites.len() as f64 }
+        };
+        let single_score_value: f64 = sites.iter()
+            .map(|(_, production, r)| r.single_score.value * production_weight(*production))
+            .sum();
+        let single_score_range = sites.iter().fold((0.0, 0.0), |acc, (_, production, r)| (
+            acc.0 + r.single_score.uncertainty_range.0 * production_weight(*production),
+            acc.1 + r.single_score.uncertainty_range.1 * production_weight(*production),
+        ));
+        // Sites in one country are compared with its references, mixed ones with Global
+        let country = match company.facilities.first() {
+            Some(first) if company.facilities.iter().all(|facility| facility.country == first.country) => first.country,
+            _ => Country::Global,
+        };
+        // The contributions of the company's endpoints per tonne, which score as the mean
+        let per_tonne = per_tonne_endpoints(&endpoint_impacts, total_production);
+        let (company_score, contributions) = weighted_single_score(&per_tonne, self.weighting_method(), &self.reference_basis())?;
+        let single_score = SingleScoreResult {
+            value: single_score_value,
+            unit: company_score.unit,
+            uncertainty_range: single_score_range,
+            weighting_factors: company_score.weighting_factors,
+            methodology: "Production-weighted mean of facility single scores".to_string(),
+            endpoint_contributions: company_score.endpoint_contributions,
+            interpretation: Some(self.interpret_score(single_score_value, &contributions, &per_tonne, &country)),
+        };
+
+        let mut categories: Vec<&String> = midpoint_impacts.keys().collect();
+        categories.sort();
+        let site_comparisons = categories.into_iter()
+            .map(|category| self.compare_sites(category, &midpoint_impacts[category], total_production, &sites))
+            .collect();
+
+        let mut results = CompanyResults {
+            midpoint_impacts,
+            endpoint_impacts,
+            single_score,
+            total_production,
+            site_comparisons,
+        };
+        if self.methodology.include_result_metadata {
+            attach_midpoint_metadata(&mut results.midpoint_impacts, &self.methodology.characterization_method);
+            attach_endpoint_metadata(&mut results.endpoint_impacts, &self.methodology.characterization_method);
+        }
+        company.results = Some(results);
+
+        info!("Company assessment completed for {}", company.company_name);
+        Ok(())
+    }
+
+    fn compare_sites(
+        &self,
+        category: &str,
+        company_total: &MidpointResult,
+        total_production: f64,
+        sites: &[(&str, f64, &LCAResults)]
+    ) -> SiteComparison {
+        let intensity = |value: f64, production: f64| if production > 0.0 { value / production } else { 0.0 };
+        let company_intensity = intensity(company_total.value, total_production);
+
+        let mut rankings: Vec<SiteRanking> = sites.iter()
+            .map(|(name, production, results)| {
+                let site_intensity = intensity(
+                    results.midpoint_impacts.get(category).map(|r| r.value).unwrap_or(0.0),
+                    *production
+                );
+                let percentage_difference = if company_intensity.abs() > f64::EPSILON {
+                    (site_intensity - company_intensity) / company_intensity.abs() * 100.0
+                } else {
+                    0.0
+                };
+                SiteRanking {
+                    rank: 0,
+                    facility_name: name.to_string(),
+                    intensity: site_intensity,
+                    percentage_difference,
+                    performance_category: match percentage_difference {
+                        d if d <= -20.0 => PerformanceCategory::Excellent,
+                        d if d <= -5.0 => PerformanceCategory::Good,
+                        d if d < 5.0 => PerformanceCategory::Average,
+                        d if d < 20.0 => PerformanceCategory::BelowAverage,
+                        _ => PerformanceCategory::Poor,
+                    },
+                }
+            })
+            .collect();
+        rankings.sort_by(|a, b| a.intensity.total_cmp(&b.intensity));
+        for (i, ranking) in rankings.iter_mut().enumerate() {
+            ranking.rank = i + 1;
+        }
+
+        SiteComparison {
+            impact_category: category.to_string(),
+            unit: format!("{} per tonne", company_total.unit),
+            company_intensity,
+            rankings,
+        }
+    }
+
+    /// Compare an assessed facility with an earlier assessment of the same facility: facility
+    /// and product intensities per tonne for every shared category, categories that worsened
+    /// beyond the regression threshold, and measures implemented since (detected from changed
+    /// inputs) with the saving recommended last time against the reduction achieved.
+    pub fn analyze_trends(
+        &self,
+        current: &ProcessingAssessment,
+        previous: &ProcessingAssessment
+    ) -> Result<TrendAnalysis, EngineError> {
+        // Trends compare the results of both assessments
+        let current_results = current.results.as_ref()
+            .ok_or_else(|| EngineError::NotAssessed("Current assessment".to_string()))?;
+        let previous_results = previous.results.as_ref()
+            .ok_or_else(|| EngineError::NotAssessed("Previous assessment".to_string()))?;
+
+        let production = |assessment: &ProcessingAssessment| {
+            assessment.processed_products.iter().map(|p| p.annual_production).sum::<f64>()
+        };
+        let (current_production, previous_production) = (production(current), production(previous));
+        let intensity = |value: f64, production: f64| if production > 0.0 { value / production } else { 0.0 };
+
+        let mut categories: Vec<&String> = current_results.midpoint_impacts.keys()
+            .filter(|category| previous_results.midpoint_impacts.contains_key(*category))
+            .collect();
+        categories.sort();
+        let category_trends: Vec<IntensityTrend> = categories.into_iter()
+            .map(|category| {
+                let now = &current_results.midpoint_impacts[category];
+                Self::intensity_trend(
+                    category,
+                    format!("{} per tonne", now.unit),
+                    intensity(previous_results.midpoint_impacts[category].value, previous_production),
+                    intensity(now.value, current_production)
+                )
+            })
+            .collect();
+
+        let mut product_trends = Vec::new();
+        if let (Some(now), Some(before)) = (&current_results.product_footprints, &previous_results.product_footprints) {
+            let mut names: Vec<&String> = now.keys().filter(|name| before.contains_key(*name)).collect();
+            names.sort();
+            for name in names {
+                let mut categories: Vec<&String> = now[name].per_tonne.keys()
+                    .filter(|category| before[name].per_tonne.contains_key(*category))
+                    .collect();
+                categories.sort();
+                product_trends.push(ProductTrend {
+                    product_name: name.clone(),
+                    trends: categories.into_iter()
+                        .map(|category| Self::intensity_trend(
+                            category,
+                            format!("{} per tonne", self.get_processing_impact_unit(category)),
+                            before[name].per_tonne[category],
+                            now[name].per_tonne[category]
+                        ))
+                        .collect(),
+                });
+            }
+        }
+
+        let regressed_categories = category_trends.iter()
+            .filter(|trend| trend.status == TrendStatus::Regressed)
+            .map(|trend| trend.impact_category.clone())
+            .collect();
+
+        let implemented_measures = self.detect_implemented_measures(current, previous, previous_results, &category_trends);
+
+        Ok(TrendAnalysis {
+            previous_assessment_id: previous.id,
+            previous_assessment_date: previous.assessment_date,
+            category_trends,
+            product_trends,
+            regressed_categories,
+            implemented_measures,
+        })
+    }
+
+    fn intensity_trend(category: &str, unit: String, previous: f64, current: f64) -> IntensityTrend {
+        let change_percent = if previous.abs() > f64::EPSILON {
+            (current - previous) / previous.abs() * 100.0
+        } else {
+            0.0
+        };
+        IntensityTrend {
+            impact_category: category.to_string(),
+            unit,
+            previous_intensity: previous,
+            current_intensity: current,
+            change_percent,
+            status: TrendStatus::from_change(change_percent),
+        }
+    }
+
+    /// Measures whose inputs changed between the two assessments, each paired with the
+    /// matching recommendation from the previous results when there was one
+    fn detect_implemented_measures(
+        &self,
+        current: &ProcessingAssessment,
+        previous: &ProcessingAssessment,
+        previous_results: &LCAResults,
+        category_trends: &[IntensityTrend]
+    ) -> Vec<ImplementedMeasure> {
+        let (now, before) = (&current.processing_operations, &previous.processing_operations);
+        let mut measures: Vec<(String, &[&str], &str)> = Vec::new();
+
+        let (renewable_now, renewable_before) = (
+            now.energy_management.renewable_share_percent(),
+            before.energy_management.renewable_share_percent()
+        );
+        if renewable_now > renewable_before {
+            measures.push((
+                format!("Renewable electricity raised from {:.0}% to {:.0}%", renewable_before, renewable_now),
+                &["Raise the solar share of the energy mix", "Switch to grid electricity or solar power"],
+                "Global warming"
+            ));
+        }
+        let new_efficiency_measures: Vec<&String> = now.energy_management.energy_efficiency_measures.iter()
+            .filter(|measure| !before.energy_management.energy_efficiency_measures.contains(measure))
+            .collect();
+        if !new_efficiency_measures.is_empty() {
+            measures.push((
+                format!("Energy efficiency measures added: {}", new_efficiency_measures.iter().map(|m| m.as_str()).collect::<Vec<_>>().join(", ")),
+                &["Implement energy-efficient equipment"],
+                "Energy consumption"
+            ));
+        }
+        let (recycled_now, recycled_before) = (
+            now.water_management.recycle_fraction() * 100.0,
+            before.water_management.recycle_fraction() * 100.0
+        );
+        if recycled_now > recycled_before {
+            measures.push((
+                format!("Process water recycling raised from {:.0}% to {:.0}%", recycled_before, recycled_now),
+                &["Raise process water recycling"],
+                "Water consumption"
+            ));
+        }
+        if now.water_management.wastewater_treatment != before.water_management.wastewater_treatment {
+            measures.push((
+                format!(
+                    "Wastewater treatment changed from {:?} to {:?}",
+                    before.water_management.wastewater_treatment, now.water_management.wastewater_treatment
+                ),
+                &["Capture biogas from effluent ponds"],
+                "Global warming"
+            ));
+        }
+        if let (Some(refrigeration_now), Some(refrigeration_before)) = (&now.refrigeration, &before.refrigeration) {
+            if refrigeration_now.refrigerant_type != refrigeration_before.refrigerant_type {
+                measures.push((
+                    format!(
+                        "Refrigerant changed from {:?} to {:?}",
+                        refrigeration_before.refrigerant_type, refrigeration_now.refrigerant_type
+                    ),
+                    &["Convert to low-GWP refrigerant"],
+                    "Global warming"
+                ));
+            }
+        }
+
+        let recommendations = previous_results.recommendations.as_deref().unwrap_or_default();
+        measures.into_iter()
+            .map(|(measure, titles, category)| {
+                let recommendation = recommendations.iter()
+                    .find(|rec| titles.iter().any(|title| rec.title.starts_with(title)));
+                ImplementedMeasure {
+                    measure,
+                    recommendation: recommendation.map(|rec| rec.title.clone()),
+                    impact_category: category.to_string(),
+                    predicted_savings_percent: recommendation
+                        .and_then(|rec| rec.potential_impact_reduction.get(category).copied()),
+                    realized_savings_percent: category_trends.iter()
+                        .find(|trend| trend.impact_category == category)
+                        .map_or(0.0, |trend| -trend.change_percent),
+                }
+            })
+            .collect()
+    }
+
+    /// Divide each product's impacts by its annual production. Burdens that are only known
+    /// at facility level (the difference between facility totals and the sum of product
+    /// results, e.g. refrigerant leakage and regional adjustments) are allocated using the
+    /// methodology's allocation method, so intensities × production sum to facility totals.
+    fn calculate_product_footprints(
+        &self,
+        facility_totals: &BTreeMap<String, MidpointResult>,
+        product_results: &[(&ProcessedProduct, BTreeMap<String, MidpointResult>)]
+    ) -> BTreeMap<String, ProductFootprint> {
+        let shares = self.allocation_shares(product_results.iter().map(|(product, _)| *product));
+        let mut footprints = BTreeMap::new();
+
+        for ((product, results), share) in product_results.iter().zip(shares) {
+            let cumulative_yield = product.processing_steps.iter()
+                .map(|step| (step.yield_efficiency / 100.0).clamp(0.01, 1.0))
+                .product::<f64>();
+
+            let mut per_tonne = BTreeMap::new();
+            let mut yield_adjusted_per_tonne = BTreeMap::new();
+            for (category, total) in facility_totals {
+                let products_sum: f64 = product_results.iter()
+                    .filter_map(|(_, r)| r.get(category))
+                    .map(|r| r.value)
+                    .sum();
+                let facility_level = total.value - products_sum;
+                let own = results.get(category).map(|r| r.value).unwrap_or(0.0);
+                let allocated = own + facility_level * share;
+
+                let intensity = if product.annual_production > 0.0 {
+                    allocated / product.annual_production
+                } else {
+                    0.0
+                };
+                per_tonne.insert(category.clone(), intensity);
+                // Yield losses mean more throughput per tonne of saleable product
+                yield_adjusted_per_tonne.insert(category.clone(), intensity / cumulative_yield);
+            }
+
+            footprints.insert(product.name.clone(), ProductFootprint {
+                annual_production: product.annual_production,
+                allocation_share: share,
+                allocation_basis: self.methodology.allocation_method,
+                per_tonne,
+                cumulative_yield,
+                yield_adjusted_per_tonne,
+            });
+        }
+
+        footprints
+    }
+
+    /// ISO 14067 footprint of each product per tonne, allocated as in the product footprints:
+    /// the product's own emissions plus its allocation share of the facility-level ones
+    /// (refrigerants, waste streams, credits and adjustments), each split by carbon origin.
+    fn carbon_footprints(
+        &self,
+        assessment: &ProcessingAssessment,
+        facility_gwp: &MidpointResult,
+        product_results: &[(&ProcessedProduct, BTreeMap<String, MidpointResult>)],
+        product_splits: &[CarbonOriginSplit],
+        facility_sources: &[(String, f64)],
+        data_quality: &DataQuality
+    ) -> BTreeMap<String, ProductCarbonFootprint> {
+        let products_gwp: f64 = product_results.iter()
+            .filter_map(|(_, results)| results.get("Global warming"))
+            .map(|gwp| gwp.value)
+            .sum();
+        let facility_split = CarbonOriginSplit::classify(
+            facility_sources.to_vec(),
+            facility_gwp.value - products_gwp,
+            &self.scope_table
+        );
+        let shares = self.allocation_shares(product_results.iter().map(|(product, _)| *product));
+
+        product_results.iter().zip(product_splits).zip(shares)
+            .map(|(((product, _), own), share)| {
+                let allocated = own.plus(&facility_split.scaled(share));
+                let per_tonne = if product.annual_production > 0.0 {
+                    allocated.scaled(1.0 / product.annual_production)
+                } else {
+                    CarbonOriginSplit::default()
+                };
+                (product.id.clone(), ProductCarbonFootprint::new(
+                    &product.id,
+                    &product.name,
+                    "tonne",
+                    per_tonne,
+                    &assessment.methodology.system_boundary,
+                    data_quality.overall_confidence,
+                    facility_gwp.data_quality_score
+                ))
+            })
+            .collect()
+    }
+
+    /// Allocation shares per product. Economic allocation uses revenue (production × price)
+    /// when every product has a price; all other cases fall back to mass. Consequential
+    /// modelling does not allocate: the determining product (highest revenue, or highest
+    /// production without prices) carries the shared burdens, net of byproduct credits.
+    fn allocation_shares<'a>(&self, products: impl Iterator<Item = &'a ProcessedProduct>) -> Vec<f64> {
+        let products: Vec<&ProcessedProduct> = products.collect();
+        if self.methodology.modeling_approach == ModelingApproach::Consequential {
+            let priced = products.iter().all(|p| p.price_per_tonne.is_some());
+            let size = |p: &ProcessedProduct| p.annual_production * if priced { p.price_per_tonne.unwrap_or(0.0) } else { 1.0 };
+            let determining = products.iter()
+                .enumerate()
+                .fold(None, |best: Option<(usize, f64)>, (i, p)| match best {
+                    Some((_, largest)) if largest >= size(p) => best,
+                    _ => Some((i, size(p))),
+                })
+                .map(|(i, _)| i);
+            return (0..products.len()).map(|i| if Some(i) == determining { 1.0 } else { 0.0 }).collect();
+        }
+        let weights: Vec<f64> = match self.methodology.allocation_method {
+            AllocationMethod::Economic if products.iter().all(|p| p.price_per_tonne.is_some()) => {
+                products.iter()
+                    .map(|p| p.annual_production * p.price_per_tonne.unwrap_or(0.0))
+                    .collect()
+            },
+            AllocationMethod::Mass => products.iter().map(|p| p.annual_production).collect(),
+            _ => {
+                info!("{:?} allocation unavailable for shared facility burdens - using mass",
+                      self.methodology.allocation_method);
+                products.iter().map(|p| p.annual_production).collect()
+            },
+        };
+
+        let total: f64 = weights.iter().sum();
+        if total > 0.0 {
+            weights.iter().map(|w| w / total).collect()
+        } else {
+            vec![1.0 / products.len().max(1) as f64; products.len()]
+        }
+    }
+
+    /// Raw material burdens for the categories the product is assessed in, inflated by what
+    /// is lost in storage, and the electricity of climate-controlled storage before processing
+    fn add_upstream_burdens(
+        &self,
+        product: &ProcessedProduct,
+        storage: &StoragePractices,
+        country: &Country,
+        impacts: &mut BTreeMap<String, MidpointResult>,
+        ghg_sources: &mut Vec<(String, f64)>
+    ) {
+        for input in &product.raw_material_inputs {
+            // Material bought, so that what survives storage covers what processing uses
+            let material_kg = input.quantity_per_tonne_output * product.annual_production * storage.loss_factor();
+            for burden in self.upstream_burdens.get(&input.material_name).into_iter().flatten() {
+                for (category, per_kg) in &burden.per_kg {
+                    let Some(result) = impacts.get_mut(category) else {
+                        continue;
+                    };
+                    let value = per_kg * material_kg;
+                    let (low, high) = (value * 0.7, value * 1.3);
+                    result.value += value;
+                    result.uncertainty_range.0 += low.min(high);
+                    result.uncertainty_range.1 += low.max(high);
+                    result.contributing_sources.push(format!(
+                        "Upstream {} ({:.0} kg {}): {:.3} {}",
+                        burden.source, material_kg, input.material_name, value, result.unit
+                    ));
+                    if category == "Global warming" {
+                        ghg_sources.push((format!("Upstream {}", burden.source), value));
+                    }
+                }
+            }
+
+            // Held off-site before delivery, so on the national grid rather than the facility's supply
+            let storage_kwh = material_kg / 1000.0 * storage.days() * storage.electricity_kwh_per_tonne_day();
+            if storage_kwh <= 0.0 {
+                continue;
+            }
+            let label = format!(
+                "Upstream storage of {} ({:.0} days, {})", input.material_name, storage.days(), storage.storage_type
+            );
+            let grid_kg_co2e = storage_kwh * EnergySource::GridElectricity.emission_factor(country);
+            for (category, value) in [("Energy consumption", storage_kwh), ("Global warming", grid_kg_co2e)] {
+                let Some(result) = impacts.get_mut(category) else {
+                    continue;
+                };
+                let (low, high) = (value * 0.7, value * 1.5);
+                result.value += value;
+                result.uncertainty_range.0 += low;
+                result.uncertainty_range.1 += high;
+                result.contributing_sources.push(format!("{}: {:.3} {}", label, value, result.unit));
+            }
+            if impacts.contains_key("Global warming") {
+                ghg_sources.push((label, grid_kg_co2e));
+            }
+        }
+    }
+
+    /// The categories to calculate: the methodology's selection, plus the water categories
+    /// that net freshwater use and water scarcity are derived from
     fn get_processing_impact_categories(&self) -> Vec<String> {
+        let water_derived = self.methodology.includes_category("Water scarcity");
+        let water_consumption = water_derived || self.methodology.includes_category("Water consumption");
+        self.all_processing_impact_categories().into_iter()
+            .filter(|category| self.methodology.includes_category(category)
+                || (category == "Water consumption" && water_derived)
+                || (category == "Gross process water use" && water_consumption))
+            .collect()
+    }
+
+    fn all_processing_impact_categories(&self) -> Vec<String> {
         vec![
             "Global warming".to_string(),
             "Energy consumption".to_string(),
-            "Water consumption".to_string(),
+            "Gross process water use".to_string(), // before recycling and rainwater
+            "Water consumption".to_string(),       // net freshwater withdrawal
             "Water scarcity".to_string(),
             "Wastewater generation".to_string(),
             "Solid waste generation".to_string(),
@@ -185,6 +1154,7 @@ impl ProcessingLCAEngine {
         match category {
             "Global warming" => "kg CO2-eq".to_string(),
             "Energy consumption" => "kWh".to_string(),
+            "Gross process water use" => "m3".to_string(),
             "Water consumption" => "m3".to_string(),
             "Water scarcity" => "m3 H2O-eq".to_string(),
             "Wastewater generation" => "m3".to_string(),
@@ -201,143 +1171,501 @@ impl ProcessingLCAEngine {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn calculate_product_impacts(
         &self,
         product: &ProcessedProduct,
         facility: &ProcessingFacilityProfile,
         operations: &ProcessingOperations,
-        country: &Country
-    ) -> Result<HashMap<String, MidpointResult>, Box<dyn std::error::Error>> {
+        country: &Country,
+        energy: &EnergyReconciliation,
+        factor_uses: &mut Vec<FactorUse>,
+        ghg_sources: &mut Vec<(String, f64)>
+    ) -> Result<BTreeMap<String, MidpointResult>, EngineError> {
         
-        let mut impacts = HashMap::new();
+        let mut impacts = BTreeMap::new();
         let impact_categories = self.get_processing_impact_categories();
+        let discharge = self.calculate_wastewater_discharge(product, facility, operations, &energy.overhead);
+        // Without processing steps (or a meter, for energy) there is no activity data to calculate from
+        let has_step_data = !product.processing_steps.is_empty();
+        let has_energy_data = has_step_data || energy.metered_kwh.is_some();
+        // Gross process water after facility adjustments, and where it came from
+        let mut gross_water = (0.0, FactorBasis::ActivityData);
+        // Global warming by emission source, before facility adjustments
+        let mut gwp_sources = Vec::new();
 
         for category in &impact_categories {
+            let mut contributing_sources = vec!["Processing calculations".to_string()];
+            let mut basis = FactorBasis::ActivityData;
+
             let impact_value = match category.as_str() {
-                "Global warming" => self.calculate_gwp_impact(product, facility, operations),
-                "Energy consumption" => self.calculate_energy_impact(product, facility, operations),
-                "Water consumption" => self.calculate_water_impact(product, facility, operations),
-                "Wastewater generation" => self.calculate_wastewater_impact(product, facility, operations),
-                "Solid waste generation" => self.calculate_waste_impact(product, facility, operations),
-                _ => self.calculate_generic_processing_impact(product, facility, country, category),
+                "Global warming" | "Energy consumption" if !has_energy_data => {
+                    self.calculate_generic_processing_impact(product, facility, country, category, &mut basis)
+                },
+                "Gross process water use" if !has_step_data => {
+                    self.calculate_generic_processing_impact(product, facility, country, "Water consumption", &mut basis)
+                },
+                "Water consumption" => {
+                    let water = &operations.water_management;
+                    // Harvested rainwater is shared across products by output
+                    let rainwater = if energy.total_production > 0.0 {
+                        water.rainwater_harvesting_m3_per_year * product.annual_production / energy.total_production
+                    } else {
+                        0.0
+                    };
+                    contributing_sources.push(format!(
+                        "Net of {:.0}% recycling and {:.0} m3 rainwater from {:.0} m3 gross process water",
+                        water.recycle_fraction() * 100.0, rainwater, gross_water.0
+                    ));
+                    basis = gross_water.1.clone();
+                    water.net_freshwater_withdrawal(gross_water.0, rainwater)
+                },
+                "Global warming" => {
+                    gwp_sources = self.gwp_sources(product, facility, operations, country, energy);
+                    let pond_ch4 = discharge.methane_kg * CH4_GWP100;
+                    if pond_ch4 > 0.0 {
+                        contributing_sources.push(format!(
+                            "Wastewater CH4 ({:?}): {:.0} kg CO2-eq",
+                            operations.water_management.wastewater_treatment, pond_ch4
+                        ));
+                        gwp_sources.push((
+                            format!("Wastewater CH4 ({:?})", operations.water_management.wastewater_treatment),
+                            pond_ch4
+                        ));
+                    }
+                    if operations.energy_management.effective_mix().iter().any(|share| share.source == EnergySource::GridElectricity) {
+                        contributing_sources.push(format!(
+                            "Grid electricity: {:.3} kg CO2-eq/kWh ({})",
+                            energy.grid_factor.kg_co2e_per_kwh, energy.grid_factor.source
+                        ));
+                    }
+                    gwp_sources.iter().map(|(_, kg_co2e)| kg_co2e).sum()
+                },
+                "Freshwater eutrophication" | "Marine eutrophication" => {
+                    match self.find_processing_factor(product, facility, country, category) {
+                        Some(factor) => {
+                            basis = FactorBasis::from(factor);
+                            factor.value_per_tonne * product.annual_production
+                        },
+                        None => {
+                            contributing_sources.push(format!(
+                                "Effluent N/P load after {:?}",
+                                operations.water_management.wastewater_treatment
+                            ));
+                            if category == "Freshwater eutrophication" {
+                                discharge.phosphorus_kg // kg P-eq
+                            } else {
+                                discharge.nitrogen_kg // kg N-eq
+                            }
+                        }
+                    }
+                },
+                "Energy consumption" => {
+                    contributing_sources.extend(self.describe_energy_basis(product, energy));
+                    self.calculate_energy_impact(product, energy)
+                },
+                "Gross process water use" => {
+                    let (step_m3, overhead_m3) = self.water_split(product, &energy.overhead);
+                    contributing_sources.push(format!("Processing steps: {:.0} m3", step_m3));
+                    contributing_sources.push(format!("Facility overhead: {:.0} m3 ({})", overhead_m3, energy.overhead.source));
+                    self.calculate_water_impact(product, operations, &energy.overhead)
+                },
+                "Wastewater generation" => self.calculate_wastewater_impact(product, operations, &energy.overhead),
+                "Solid waste generation" => {
+                    let waste_kg = self.calculate_waste_impact(product, facility, operations, energy.total_production);
+                    let streams = &operations.waste_management.waste_streams;
+                    let stream_total: f64 = streams.iter().map(|stream| stream.tonnes_per_year.max(0.0)).sum();
+                    if stream_total > 0.0 {
+                        contributing_sources.extend(streams.iter().map(|stream| format!(
+                            "{} ({:?}): {:.0} kg",
+                            stream.stream_name, stream.composition, waste_kg * stream.tonnes_per_year.max(0.0) / stream_total
+                        )));
+                    }
+                    waste_kg
+                },
+                _ => self.calculate_generic_processing_impact(product, facility, country, category, &mut basis),
             };
+            if let FactorBasis::Specific { ref source, .. } = basis {
+                contributing_sources.push(source.clone());
+            }
 
-            // Apply facility-specific adjustments
-            let adjusted_impact = self.apply_facility_adjustments(impact_value, facility, operations, category);
+            // Apply facility-specific adjustments; metered consumption already reflects them,
+            // and net water is derived from the already adjusted gross figure
+            let adjusted_impact = if (category == "Energy consumption" && energy.metered_kwh.is_some())
+                || category == "Water consumption" {
+                impact_value
+            } else {
+                self.apply_facility_adjustments(impact_value, facility, operations, category)
+            };
+            if category == "Gross process water use" {
+                gross_water = (adjusted_impact, basis.clone());
+            }
+            if category == "Global warming" {
+                // The adjustments scale every source alike
+                let adjustment = self.apply_facility_adjustments(1.0, facility, operations, category);
+                if gwp_sources.is_empty() && impact_value != 0.0 {
+                    gwp_sources.push(("Processing default factor (no energy data)".to_string(), impact_value));
+                }
+                ghg_sources.extend(gwp_sources.drain(..).map(|(source, kg_co2e)| (source, kg_co2e * adjustment)));
+            }
 
-            // Calculate uncertainty
-            let uncertainty_range = (adjusted_impact * 0.7, adjusted_impact * 1.3); // Simplified uncertainty
+            // Calculate uncertainty; credits make the value negative, so order the ends
+            let (low, high) = (adjusted_impact * 0.7, adjusted_impact * 1.3); // Simplified uncertainty
+            let uncertainty_range = (low.min(high), low.max(high));
 
             impacts.insert(category.clone(), MidpointResult {
                 value: adjusted_impact,
                 unit: self.get_processing_impact_unit(category),
+                basis: ImpactBasis::Total,
                 uncertainty_range,
+                geometric_sd: None,
                 data_quality_score: 0.7, // Medium quality for processing data
-                contributing_sources: vec!["Processing calculations".to_string()],
+                contributing_sources,
+                metadata: None,
+            });
+            factor_uses.push(FactorUse {
+                product: product.name.clone(),
+                category: category.clone(),
+                basis,
             });
         }
 
         Ok(impacts)
     }
 
-    fn calculate_gwp_impact(
+    /// Annual GWP of a product before facility adjustments, by emission source as
+    /// (source, kg CO2-eq); sources are named for the GHG scope table and the same source
+    /// in several products adds up
+    fn gwp_sources(
         &self,
         product: &ProcessedProduct,
         facility: &ProcessingFacilityProfile,
-        operations: &ProcessingOperations
-    ) -> f64 {
-        let mut total_gwp = 0.0;
+        operations: &ProcessingOperations,
+        country: &Country,
+        energy: &EnergyReconciliation
+    ) -> Vec<(String, f64)> {
+        let mut sources = Vec::new();
+        let (drying_heat_kwh, drying_heat_gwp) = self.drying_heat(product);
+        let product_kwh = self.calculate_energy_impact(product, energy) - drying_heat_kwh;
+        let energy_management = &operations.energy_management;
 
         // Energy-related emissions
-        let energy_gwp = match operations.energy_management.primary_energy_source {
-            EnergySource::GridElectricity => {
-                // Emission factor for West African grid (kg CO2/kWh)
-                let grid_factor = 0.45; // Average for Ghana/Nigeria
-                let energy_per_tonne = self.estimate_energy_consumption_per_tonne(product, facility);
-                energy_per_tonne * grid_factor * product.annual_production
-            },
-            EnergySource::DieselGenerator => {
-                let diesel_factor = 2.68; // kg CO2/liter
-                let fuel_consumption = operations.energy_management.monthly_fuel_consumption.unwrap_or(1000.0) * 12.0;
-                fuel_consumption * diesel_factor
-            },
-            EnergySource::SolarPower => {
-                // Very low emissions for solar
-                let solar_factor = 0.05; // kg CO2/kWh
-                let energy_per_tonne = self.estimate_energy_consumption_per_tonne(product, facility);
-                energy_per_tonne * solar_factor * product.annual_production
-            },
-            _ => {
-                // Default mixed energy factor
-                let mixed_factor = 0.35;
-                let energy_per_tonne = self.estimate_energy_consumption_per_tonne(product, facility);
-                energy_per_tonne * mixed_factor * product.annual_production
-            },
-        };
+        if !energy_management.energy_mix.is_empty() {
+            // Weighted by each source's share of the electricity supplied
+            for share in energy_management.effective_mix() {
+                sources.push((
+                    Self::energy_source_name(&share.source).to_string(),
+                    product_kwh * share.percentage / 100.0 * Self::source_emission_factor(&share.source, country, &energy.grid_factor)
+                ));
+            }
+        } else {
+            match energy_management.primary_energy_source {
+                EnergySource::GridElectricity => {
+                    sources.push(("Grid electricity".to_string(), product_kwh * energy.grid_factor.kg_co2e_per_kwh));
+                },
+                EnergySource::DieselGenerator => {
+                    let diesel_factor = 2.68; // kg CO2/liter
+                    let fuel_consumption = operations.energy_management.monthly_fuel_consumption.unwrap_or(1000.0)
+                        * facility.months_in_operation();
+                    sources.push(("Diesel generator".to_string(), fuel_consumption * diesel_factor));
+                },
+                EnergySource::SolarPower => {
+                    // Very low emissions for solar
+                    let solar_factor = 0.05; // kg CO2/kWh
+                    sources.push(("Solar power".to_string(), product_kwh * solar_factor));
+                },
+                _ => {
+                    // Default mixed energy factor
+                    let mixed_factor = 0.35;
+                    sources.push(("Mixed energy".to_string(), product_kwh * mixed_factor));
+                },
+            }
+        }
 
-        total_gwp += energy_gwp;
+        if drying_heat_gwp != 0.0 {
+            sources.push(("Drying heat".to_string(), drying_heat_gwp));
+        }
 
         // Process-specific emissions
         for step in &product.processing_steps {
             if let Some(step_emissions) = step.emissions_factor {
-                total_gwp += step_emissions * product.annual_production;
+                sources.push((format!("Process emissions ({})", step.step_name), step_emissions * product.annual_production));
             }
         }
 
-        // Waste-related emissions
-        let waste_gwp = match operations.waste_management.waste_disposal_method {
-            WasteDisposalMethod::Landfill => {
+        // Waste-related emissions; separately reported streams are added at facility level
+        if operations.waste_management.waste_streams.is_empty() {
+            let method = &operations.waste_management.waste_disposal_method;
+            let organic_waste = operations.waste_management.solid_waste_generation.unwrap_or(100.0) * facility.annual_operating_days();
+            let organic_fraction = operations.waste_management.organic_fraction();
+            let waste_gwp = match method {
                 // Methane emissions from organic waste in landfill
-                let organic_waste = operations.waste_management.solid_waste_generation.unwrap_or(100.0) * 365.0;
-                let organic_fraction = operations.waste_management.organic_waste_percentage / 100.0;
-                organic_waste * organic_fraction * 0.5 // kg CO2-eq/kg organic waste
-            },
-            WasteDisposalMethod::Composting => {
+                WasteDisposalMethod::Landfill => Some(organic_waste * organic_fraction * 0.5), // kg CO2-eq/kg organic waste
                 // Lower emissions from composting
-                let organic_waste = operations.waste_management.solid_waste_generation.unwrap_or(100.0) * 365.0;
-                let organic_fraction = operations.waste_management.organic_waste_percentage / 100.0;
-                organic_waste * organic_fraction * 0.1
-            },
-            WasteDisposalMethod::AnaerobicDigestion => {
+                WasteDisposalMethod::Composting => Some(organic_waste * organic_fraction * 0.1),
                 // Negative emissions due to biogas capture
-                let organic_waste = operations.waste_management.solid_waste_generation.unwrap_or(100.0) * 365.0;
-                let organic_fraction = operations.waste_management.organic_waste_percentage / 100.0;
-                -(organic_waste * organic_fraction * 0.2) // Carbon credit
-            },
-            _ => 0.0,
+                WasteDisposalMethod::AnaerobicDigestion => Some(-(organic_waste * organic_fraction * 0.2)), // Carbon credit
+                _ => None,
+            };
+            if let Some(waste_gwp) = waste_gwp {
+                sources.push((format!("Solid waste disposal ({:?})", method), waste_gwp));
+            }
+        }
+
+        sources
+    }
+
+    fn energy_source_name(source: &EnergySource) -> &'static str {
+        match source {
+            EnergySource::GridElectricity => "Grid electricity",
+            EnergySource::DieselGenerator => "Diesel generator",
+            EnergySource::SolarPower => "Solar power",
+            EnergySource::Biomass => "Biomass",
+            EnergySource::LPG => "LPG",
+            EnergySource::NaturalGas => "Natural gas",
+            EnergySource::HydroElectricity => "Hydro electricity",
+            EnergySource::WindPower => "Wind power",
+            EnergySource::Mixed => "Mixed energy",
+        }
+    }
+
+    /// Fugitive refrigerant emissions in kg CO2-eq/year: operating leakage
+    /// (charge × leakage rate × GWP) plus unrecovered end-of-life charge amortized
+    /// over the equipment lifetime.
+    pub fn calculate_refrigerant_emissions(
+        &self,
+        refrigeration: &RefrigerationSystem,
+        facility: &ProcessingFacilityProfile,
+        country: &Country
+    ) -> f64 {
+        let charge_kg = refrigeration.total_charge_kg.unwrap_or(
+            facility.processing_capacity * RefrigerationSystem::DEFAULT_CHARGE_KG_PER_TONNE_DAY
+        );
+        let leakage_rate = refrigeration.annual_leakage_rate_percent
+            .unwrap_or(RefrigerationSystem::typical_leakage_rate_percent(country)) / 100.0;
+        let lifetime = refrigeration.equipment_lifetime_years
+            .unwrap_or(RefrigerationSystem::DEFAULT_LIFETIME_YEARS)
+            .max(1.0);
+        let recovery = refrigeration.end_of_life_recovery_percent
+            .unwrap_or(RefrigerationSystem::typical_recovery_percent(country)) / 100.0;
+
+        let gwp = refrigeration.refrigerant_type.gwp100();
+        let operating_leakage = charge_kg * leakage_rate * gwp;
+        let end_of_life = charge_kg * (1.0 - recovery).max(0.0) * gwp / lifetime;
+
+        operating_leakage + end_of_life
+    }
+
+    /// Compare metered electricity (monthly bill × operating months) with the step-level
+    /// estimate summed over all products, plus facility overhead and idle-month standby.
+    fn reconcile_energy(
+        &self,
+        products: &[ProcessedProduct],
+        facility: &ProcessingFacilityProfile,
+        operations: &ProcessingOperations,
+        country: &Country,
+        region: &Option<String>
+    ) -> EnergyReconciliation {
+        let months = facility.months_in_operation();
+        let step_kwh: f64 = products.iter()
+            .map(|p| self.estimate_energy_consumption_per_tonne(p) * p.annual_production)
+            .sum();
+        let overhead = self.allocate_overhead(products, facility, operations);
+        let (process_kwh, monthly_overhead_kwh) = match overhead.legacy_energy_factor {
+            Some(factor) => (step_kwh * factor, step_kwh * (factor - 1.0) / months),
+            None => (step_kwh, overhead.energy_kwh / months),
         };
+        let idle_standby_kwh = (12.0 - months) * monthly_overhead_kwh * IDLE_STANDBY_FRACTION;
+        let overhead_kwh = if overhead.is_legacy() { 0.0 } else { overhead.energy_kwh };
+
+        EnergyReconciliation {
+            metered_kwh: operations.energy_management.monthly_electricity_consumption
+                .map(|kwh| kwh * months)
+                .or_else(|| operations.energy_management.metered_mix_kwh())
+                .filter(|kwh| *kwh > 0.0),
+            step_based_kwh: process_kwh + overhead_kwh + idle_standby_kwh,
+            process_kwh,
+            idle_standby_kwh,
+            total_production: products.iter().map(|p| p.annual_production).sum(),
+            overhead,
+            grid_factor: self.grid_emission_factor(facility, operations, country, region),
+        }
+    }
 
-        total_gwp += waste_gwp;
+    /// Overhead energy and water for the operating months, as entered or defaulted for the
+    /// facility type and size; the deprecated multipliers replace both when enabled
+    fn allocate_overhead(
+        &self,
+        products: &[ProcessedProduct],
+        facility: &ProcessingFacilityProfile,
+        operations: &ProcessingOperations
+    ) -> OverheadAllocation {
+        let total_production = products.iter().map(|p| p.annual_production).sum();
+        let entered = &operations.overhead;
+        if entered.legacy_multipliers {
+            return OverheadAllocation {
+                total_production,
+                source: "legacy facility-type multipliers".to_string(),
+                legacy_energy_factor: Some(self.energy_overhead_factor(facility)),
+                ..OverheadAllocation::default()
+            };
+        }
 
-        total_gwp
+        let size = facility.capacity_range();
+        let (default_kwh, default_m3) = FacilityOverhead::default_for(&facility.facility_type, &size);
+        let source = match (entered.energy_kwh_per_year, entered.water_m3_per_year) {
+            (Some(_), Some(_)) => "entered".to_string(),
+            (None, None) => format!("default for {:?} facility ({:?})", facility.facility_type, size),
+            _ => format!("partly entered, rest default for {:?} facility ({:?})", facility.facility_type, size),
+        };
+        let operating_fraction = facility.months_in_operation() / 12.0;
+        OverheadAllocation {
+            energy_kwh: entered.energy_kwh_per_year.unwrap_or(default_kwh).max(0.0) * operating_fraction,
+            water_m3: entered.water_m3_per_year.unwrap_or(default_m3).max(0.0) * operating_fraction,
+            total_production,
+            source,
+            legacy_energy_factor: None,
+        }
+    }
+
+    /// Grid electricity intensity: a facility-specific figure when given, then captive
+    /// generation, then the loaded regional and national factors, then the built-in national
+    /// default. Consequential modelling replaces the grid average with the national marginal
+    /// factor. Hours on the backup generator blend diesel into the result.
+    fn grid_emission_factor(
+        &self,
+        facility: &ProcessingFacilityProfile,
+        operations: &ProcessingOperations,
+        country: &Country,
+        region: &Option<String>
+    ) -> ElectricityEmissionFactor {
+        let energy_management = &operations.energy_management;
+        let loaded = |key: RegionalFactorKey| self.regional_factors.get(&key)
+            .map(|value| (*value, format!("regional factor {}", key)));
+
+        let (base, source) = if let Some(ref factor) = energy_management.grid_emission_factor {
+            (factor.kg_co2e_per_kwh, format!("facility-specific: {}", factor.source))
+        } else if energy_management.captive_generation {
+            loaded(RegionalFactorKey::national(*country, RegionalFactorKind::CaptiveGenerationEmission))
+                .unwrap_or((CAPTIVE_GENERATION_FACTOR, "captive gas turbine default".to_string()))
+        } else if self.methodology.modeling_approach == ModelingApproach::Consequential {
+            // The margin is set by national dispatch, so regional supply mixes don't apply
+            loaded(RegionalFactorKey::national(*country, RegionalFactorKind::MarginalGridEmission))
+                .unwrap_or_else(|| (
+                    EnergySource::marginal_grid_emission_factor(country),
+                    format!("{} marginal grid default", country)
+                ))
+        } else {
+            region.as_ref()
+                .and_then(|region| loaded(RegionalFactorKey::regional(*country, region, RegionalFactorKind::GridEmission)))
+                .or_else(|| loaded(RegionalFactorKey::national(*country, RegionalFactorKind::GridEmission)))
+                .unwrap_or_else(|| (
+                    EnergySource::GridElectricity.emission_factor(country),
+                    format!("{} national grid default", country)
+                ))
+        };
+
+        let annual_hours = facility.operational_hours_per_day * facility.annual_operating_days();
+        let backup_share = energy_management.backup_generator_share(annual_hours);
+        if backup_share > 0.0 {
+            let diesel = EnergySource::DieselGenerator.emission_factor(country);
+            ElectricityEmissionFactor {
+                kg_co2e_per_kwh: base * (1.0 - backup_share) + diesel * backup_share,
+                source: format!("{} with {:.0}% of hours on diesel backup", source, backup_share * 100.0),
+            }
+        } else {
+            ElectricityEmissionFactor { kg_co2e_per_kwh: base, source }
+        }
+    }
+
+    /// Emissions per kWh from a source, using the facility's resolved grid factor for the grid
+    fn source_emission_factor(source: &EnergySource, country: &Country, grid_factor: &ElectricityEmissionFactor) -> f64 {
+        match source {
+            EnergySource::GridElectricity => grid_factor.kg_co2e_per_kwh,
+            other => other.emission_factor(country),
+        }
     }
 
     fn calculate_energy_impact(
         &self,
         product: &ProcessedProduct,
-        facility: &ProcessingFacilityProfile,
-        _operations: &ProcessingOperations
+        energy: &EnergyReconciliation
     ) -> f64 {
-        // Calculate total energy consumption
-        let energy_per_tonne = self.estimate_energy_consumption_per_tonne(product, facility);
-        energy_per_tonne * product.annual_production
+        let step_kwh = self.estimate_energy_consumption_per_tonne(product) * product.annual_production;
+        let electricity_kwh = match energy.metered_kwh {
+            _ if energy.step_based_kwh > 0.0 => {
+                (step_kwh * energy.step_factor() + energy.overhead_kwh(product.annual_production)) * energy.scale()
+            },
+            // No step intensities anywhere: share the bill by production
+            Some(metered) if energy.total_production > 0.0 => {
+                metered * product.annual_production / energy.total_production
+            },
+            _ => step_kwh,
+        };
+        electricity_kwh + self.drying_heat(product).0
+    }
+
+    /// Annual heat (kWh) and emissions (kg CO2-eq) of drying steps that do not run on
+    /// electricity; sun drying contributes neither
+    fn drying_heat(&self, product: &ProcessedProduct) -> (f64, f64) {
+        product.processing_steps.iter()
+            .filter_map(|step| step.drying_method.map(|method| (step.drying_heat_per_tonne(), method)))
+            .fold((0.0, 0.0), |(kwh, co2e), (heat, method)| (
+                kwh + heat * product.annual_production,
+                co2e + heat * product.annual_production * method.heat_emission_factor()
+            ))
+    }
+
+    /// State which energy source was used and break the product total down by step
+    fn describe_energy_basis(
+        &self,
+        product: &ProcessedProduct,
+        energy: &EnergyReconciliation
+    ) -> Vec<String> {
+        let basis = match energy.metered_kwh {
+            Some(_) if energy.process_kwh > 0.0 => format!(
+                "Energy basis: metered consumption, step and overhead estimates scaled by {:.2}", energy.scale()
+            ),
+            Some(_) => "Energy basis: metered consumption allocated by production (no step intensities)".to_string(),
+            None => "Energy basis: step-level intensities (no metered consumption reported)".to_string(),
+        };
+
+        let per_step = product.annual_production * energy.step_factor() * energy.scale();
+        let standby = (energy.idle_standby_kwh > 0.0).then(|| format!(
+            "Idle-month standby included: {:.0} kWh/year across the facility", energy.idle_standby_kwh
+        ));
+        let overhead = if energy.overhead.is_legacy() {
+            format!("Facility overhead: included in steps ({})", energy.overhead.source)
+        } else {
+            format!(
+                "Facility overhead: {:.0} kWh allocated by production ({})",
+                energy.overhead_kwh(product.annual_production) * energy.scale(), energy.overhead.source
+            )
+        };
+        std::iter::once(basis)
+            .chain(standby)
+            .chain(std::iter::once(overhead))
+            .chain(product.processing_steps.iter()
+                .filter(|step| step.energy_per_tonne() > 0.0)
+                .map(|step| match step.drying_method {
+                    Some(method) if !method.uses_electricity() => format!(
+                        "{}: {:.0} kWh heat ({:?} drying)",
+                        step.step_name, step.drying_heat_per_tonne() * product.annual_production, method
+                    ),
+                    _ => format!("{}: {:.0} kWh", step.step_name, step.electricity_per_tonne() * per_step),
+                }))
+            .collect()
     }
 
+    /// Gross process water (m3): the steps' water plus the product's share of facility
+    /// overhead water, or steps plus a flat 30% under the legacy multipliers
     fn calculate_water_impact(
         &self,
         product: &ProcessedProduct,
-        _facility: &ProcessingFacilityProfile,
-        operations: &ProcessingOperations
+        operations: &ProcessingOperations,
+        overhead: &OverheadAllocation
     ) -> f64 {
-        // Calculate water consumption per tonne of product
-        let base_water_per_tonne = product.processing_steps.iter()
-            .map(|step| step.water_usage)
-            .sum::<f64>();
-
-        // Add cooling and cleaning water (estimate)
-        let additional_water = base_water_per_tonne * 0.3; // 30% additional for cooling/cleaning
-        
-        let total_water_per_tonne = base_water_per_tonne + additional_water;
+        let (step_m3, overhead_m3) = self.water_split(product, overhead);
         
         // Apply water efficiency factor
         let efficiency_factor = match operations.water_management.water_conservation_measures.len() {
@@ -347,17 +1675,31 @@ impl ProcessingLCAEngine {
             _ => 0.7,
         };
 
-        total_water_per_tonne * efficiency_factor * product.annual_production / 1000.0 // Convert to m3
+        (step_m3 + overhead_m3) * efficiency_factor
+    }
+
+    /// Step-level and overhead water (m3) of a product, before conservation measures
+    fn water_split(&self, product: &ProcessedProduct, overhead: &OverheadAllocation) -> (f64, f64) {
+        let litres_per_tonne = product.processing_steps.iter()
+            .map(|step| step.water_usage)
+            .sum::<f64>();
+        let step_m3 = Volume::litres(litres_per_tonne * product.annual_production).in_m3();
+        let overhead_m3 = if overhead.is_legacy() {
+            step_m3 * 0.3 // cooling and cleaning
+        } else {
+            overhead.water_m3 * overhead.share(product.annual_production)
+        };
+        (step_m3, overhead_m3)
     }
 
     fn calculate_wastewater_impact(
         &self,
         product: &ProcessedProduct,
-        _facility: &ProcessingFacilityProfile,
-        operations: &ProcessingOperations
+        operations: &ProcessingOperations,
+        overhead: &OverheadAllocation
     ) -> f64 {
         // Estimate wastewater as 80% of water consumption
-        let water_impact = self.calculate_water_impact(product, _facility, operations);
+        let water_impact = self.calculate_water_impact(product, operations, overhead);
         
         // Apply treatment factor
         let treatment_factor = match operations.water_management.wastewater_treatment {
@@ -371,66 +1713,286 @@ impl ProcessingLCAEngine {
         water_impact * 0.8 * treatment_factor
     }
 
+    /// Pollutant loads discharged after treatment, and CH4 from anaerobic conditions.
+    /// Loads use the product's measured concentrations when given, else facility-type defaults.
+    fn calculate_wastewater_discharge(
+        &self,
+        product: &ProcessedProduct,
+        facility: &ProcessingFacilityProfile,
+        operations: &ProcessingOperations,
+        overhead: &OverheadAllocation
+    ) -> WastewaterDischarge {
+        // Untreated wastewater volume (m3): 80% of process water
+        let volume_m3 = self.calculate_water_impact(product, operations, overhead) * 0.8;
+        if volume_m3 <= 0.0 {
+            return WastewaterDischarge::default();
+        }
+
+        let load = product.wastewater_load.clone()
+            .unwrap_or_else(|| WastewaterLoad::default_for(&facility.facility_type));
+        let treatment = &operations.water_management.wastewater_treatment;
+        let (_, n_removal, p_removal) = treatment.removal_efficiencies();
+
+        // mg/L × m3 = g; convert to kg
+        let raw_cod_kg = load.cod_mg_per_l * volume_m3 / 1000.0;
+        let raw_n_kg = load.total_nitrogen_mg_per_l * volume_m3 / 1000.0;
+        let raw_p_kg = load.total_phosphorus_mg_per_l * volume_m3 / 1000.0;
+
+        WastewaterDischarge {
+            nitrogen_kg: raw_n_kg * (1.0 - n_removal),
+            phosphorus_kg: raw_p_kg * (1.0 - p_removal),
+            // IPCC 2019 Vol.5 Eq. 6.4: CH4 = TOW × Bo × MCF
+            methane_kg: raw_cod_kg * CH4_PER_KG_COD * treatment.methane_correction_factor(&facility.facility_type),
+        }
+    }
+
+    /// Solid waste (kg/year) of a product: the facility's waste allocated by the product's share
+    /// of capacity. A facility without a declared capacity allocates by its share of actual
+    /// production instead.
     fn calculate_waste_impact(
         &self,
         product: &ProcessedProduct,
-        _facility: &ProcessingFacilityProfile,
-        operations: &ProcessingOperations
+        facility: &ProcessingFacilityProfile,
+        operations: &ProcessingOperations,
+        total_production: f64
     ) -> f64 {
-        // Calculate waste per tonne of production
-        let daily_waste = operations.waste_management.solid_waste_generation.unwrap_or(100.0);
-        let annual_waste = daily_waste * _facility.operational_days_per_year as f64;
-        
-        // Allocate to this product based on production volume
-        let facility_total_production = _facility.processing_capacity * _facility.operational_days_per_year as f64;
-        let allocation_factor = product.annual_production / facility_total_production;
-        
+        let waste_streams = &operations.waste_management.waste_streams;
+        let annual_waste = if !waste_streams.is_empty() {
+            waste_streams.iter().map(|stream| Mass::tonnes(stream.tonnes_per_year.max(0.0)).in_kg()).sum()
+        } else {
+            // Calculate waste per tonne of production (kg/day)
+            let daily_waste = operations.waste_management.solid_waste_generation.unwrap_or(100.0);
+            daily_waste * facility.annual_operating_days()
+        };
+
+        let capacity = facility.annual_throughput_capacity();
+        let basis = if capacity > 0.0 { capacity } else { total_production };
+        let allocation_factor = if basis > 0.0 { product.annual_production / basis } else { 0.0 };
+
         annual_waste * allocation_factor
     }
 
-    fn calculate_generic_processing_impact(
+    /// System-expansion credits (byproduct, tonnes/year, kg CO2-eq/year avoided) for byproducts
+    /// put to use, taking each utilized percentage of the facility's solid waste. A byproduct
+    /// displaces the product its use replaces; uses matching none of these earn no credit.
+    fn byproduct_credits(&self, waste: &WasteManagement, facility: &ProcessingFacilityProfile) -> Vec<(String, f64, f64)> {
+        let waste_tonnes = if !waste.waste_streams.is_empty() {
+            waste.waste_streams.iter().map(|stream| stream.tonnes_per_year.max(0.0)).sum()
+        } else {
+            Mass::kg(waste.solid_waste_generation.unwrap_or(100.0) * facility.annual_operating_days()).in_tonnes()
+        };
+
+        waste.byproduct_utilization.iter()
+            .filter_map(|byproduct| {
+                let method = byproduct.utilization_method.to_lowercase();
+                // kg CO2-eq per tonne of the displaced product
+                let displaced = if method.contains("feed") {
+                    400.0 // maize grain fed at 1:1 dry matter
+                } else if ["fuel", "briquette", "energy", "boiler"].iter().any(|use_| method.contains(use_)) {
+                    540.0 // fuelwood, 1.8 kg CO2/kg with a 30% non-renewable share
+                } else if ["compost", "fertili", "mulch"].iter().any(|use_| method.contains(use_)) {
+                    30.0 // ~5 kg mineral N per tonne at 6 kg CO2-eq/kg N
+                } else {
+                    info!("No displaced product for {} used as {} - no system expansion credit",
+                          byproduct.byproduct_name, byproduct.utilization_method);
+                    return None;
+                };
+                let tonnes = waste_tonnes * byproduct.percentage_utilized.clamp(0.0, 100.0) / 100.0;
+                Some((byproduct.byproduct_name.clone(), tonnes, tonnes * displaced))
+            })
+            .collect()
+    }
+
+    /// GWP of every reported waste stream under its own disposal method
+    pub fn waste_stream_impacts(
+        &self,
+        waste: &WasteManagement,
+        country: &Country,
+        grid_factor: &ElectricityEmissionFactor
+    ) -> Vec<WasteStreamImpact> {
+        waste.waste_streams.iter()
+            .map(|stream| WasteStreamImpact {
+                stream_name: stream.stream_name.clone(),
+                tonnes_per_year: stream.tonnes_per_year,
+                composition: stream.composition,
+                disposal_method: stream.disposal_method,
+                gwp_kg_co2e: self.waste_stream_gwp(
+                    stream.tonnes_per_year, stream.composition, stream.disposal_method, country, grid_factor
+                ),
+            })
+            .collect()
+    }
+
+    /// Net kg CO2-eq/year of waste under a disposal method: landfill CH4 net of the country's
+    /// gas capture, CH4/N2O and fossil CO2 from burning, composting CH4/N2O, and credits for
+    /// biogas electricity, boiler fuel displaced and virgin material avoided. Biogenic CO2 is excluded.
+    fn waste_stream_gwp(
+        &self,
+        tonnes: f64,
+        composition: WasteComposition,
+        disposal: WasteDisposalMethod,
+        country: &Country,
+        grid_factor: &ElectricityEmissionFactor
+    ) -> f64 {
+        let tonnes = tonnes.max(0.0);
+        let burns = composition.heating_value() > 0.0;
+        let kg = Mass::tonnes(tonnes).in_kg();
+        let fossil_co2 = |oxidized: f64| kg * composition.fossil_carbon() * oxidized * 44.0 / 12.0;
+        // Decomposable carbon converted to CH4 (DOCf 0.5, 50% CH4 in gas), kg CH4
+        let ch4_potential = kg * composition.degradable_organic_carbon() * 0.5 * 0.5 * 16.0 / 12.0;
+
+        match disposal {
+            WasteDisposalMethod::Landfill | WasteDisposalMethod::Mixed => {
+                let capture = self.regional_factors.get(&RegionalFactorKey::national(*country, RegionalFactorKind::LandfillGasCapture))
+                    .copied()
+                    .unwrap_or(0.0)
+                    .clamp(0.0, 1.0);
+                // IPCC 2006 Vol. 5 Eq. 3.1 over full decay: MCF 0.6 for unmanaged sites, 10% oxidized in cover
+                ch4_potential * 0.6 * (1.0 - capture) * (1.0 - 0.1) * CH4_GWP100
+            },
+            // IPCC 2006 Vol. 5 Ch. 5: 6.5 kg CH4 and 0.15 kg N2O per t, 58% of fossil carbon oxidized
+            WasteDisposalMethod::OpenBurning if burns => {
+                tonnes * (6.5 * CH4_GWP100 + 0.15 * N2O_GWP100) + fossil_co2(0.58)
+            },
+            WasteDisposalMethod::Incineration if burns => {
+                tonnes * (0.2 * CH4_GWP100 + 0.05 * N2O_GWP100) + fossil_co2(1.0)
+            },
+            // IPCC 2006 Vol. 5 Table 4.1, wet weight
+            WasteDisposalMethod::Composting if composition.is_biodegradable() => {
+                tonnes * (4.0 * CH4_GWP100 + 0.24 * N2O_GWP100)
+            },
+            WasteDisposalMethod::AnaerobicDigestion if composition.is_biodegradable() => {
+                // Biogas CH4 at 50 MJ/kg burned in a 35%-efficient gas engine displaces grid electricity
+                let electricity_kwh = ch4_potential * 50.0 / 3.6 * 0.35;
+                tonnes * 0.8 * CH4_GWP100 - electricity_kwh * grid_factor.kg_co2e_per_kwh
+            },
+            WasteDisposalMethod::EnergyRecovery if burns => {
+                // Stationary combustion (30 g CH4, 4 g N2O per GJ); heat at 70% boiler efficiency
+                // displaces diesel-fired heat
+                let heat_gj = tonnes * composition.heating_value();
+                let combustion = heat_gj * (0.030 * CH4_GWP100 + 0.004 * N2O_GWP100) + fossil_co2(1.0);
+                combustion - heat_gj * 0.7 * 1000.0 / 3.6 * DryingMethod::Diesel.heat_emission_factor()
+            },
+            WasteDisposalMethod::Recycling => -tonnes * composition.recycling_credit(),
+            _ => 0.0,
+        }
+    }
+
+    /// Facility/product-specific factor per tonne, if one has been loaded
+    fn find_processing_factor(
         &self,
         product: &ProcessedProduct,
         facility: &ProcessingFacilityProfile,
         country: &Country,
         category: &str
+    ) -> Option<&ProcessingImpactFactor> {
+        let key = |size| (facility.facility_type, product.product_type.clone(), *country, category.to_string(), size);
+        // A factor for the facility's size class wins over one covering every size
+        self.impact_factors.get(&key(Some(facility.capacity_range())))
+            .or_else(|| self.impact_factors.get(&key(None)))
+    }
+
+    /// Scale multiplier for the facility's size class, from the curve for its type
+    /// (or the General curve); no adjustment when no curves are loaded
+    fn scale_factor(&self, facility: &ProcessingFacilityProfile) -> f64 {
+        self.scale_curves.get(&facility.facility_type)
+            .or_else(|| self.scale_curves.get(&ProcessingFacilityType::General))
+            .map_or(1.0, |curve| curve.multiplier(facility.capacity_range()))
+    }
+
+    /// Benchmark set for the facility's type, size class and country
+    fn find_benchmark(&self, facility: &ProcessingFacilityProfile, country: &Country) -> Option<&ProcessingBenchmark> {
+        self.benchmarks.get(&(facility.facility_type, facility.capacity_range(), *country))
+    }
+
+    /// Per-tonne intensities against peers of the same type and size class. The type comparison
+    /// holds the percentage difference from the peer average; the percentile runs from 0 at
+    /// worst practice through 50 at the average to 100 at best practice.
+    fn benchmark_facility(
+        &self,
+        impacts: &BTreeMap<String, MidpointResult>,
+        facility: &ProcessingFacilityProfile,
+        country: &Country,
+        total_production: f64
+    ) -> Option<BenchmarkingResults> {
+        let benchmark = self.find_benchmark(facility, country)?;
+        if total_production <= 0.0 {
+            return None;
+        }
+
+        let mut type_comparison = BTreeMap::new();
+        let mut percentiles = Vec::new();
+        let mut best_practices = Vec::new();
+        for (category, value) in &benchmark.benchmarks {
+            let Some(result) = impacts.get(category) else { continue };
+            let intensity = result.value / total_production;
+
+            if value.average > 0.0 {
+                type_comparison.insert(category.clone(), (intensity - value.average) / value.average * 100.0);
+            }
+            let percentile = if intensity <= value.average {
+                50.0 + 50.0 * (value.average - intensity) / (value.average - value.best_practice).max(f64::EPSILON)
+            } else {
+                50.0 - 50.0 * (intensity - value.average) / (value.worst_practice - value.average).max(f64::EPSILON)
+            };
+            percentiles.push(percentile.clamp(0.0, 100.0));
+            if intensity <= value.best_practice {
+                best_practices.push(format!(
+                    "{}: {:.1} {} meets best practice for {:?} facilities ({:.1})",
+                    category, intensity, value.unit, benchmark.capacity_range, value.best_practice
+                ));
+            }
+        }
+
+        Some(BenchmarkingResults {
+            farm_type_comparison: type_comparison,
+            regional_comparison: BTreeMap::new(),
+            performance_percentile: if percentiles.is_empty() {
+                50.0
+            } else {
+                percentiles.iter().sum::<f64>() / percentiles.len() as f64
+            },
+            best_practices_identified: best_practices,
+        })
+    }
+
+    fn calculate_generic_processing_impact(
+        &self,
+        product: &ProcessedProduct,
+        facility: &ProcessingFacilityProfile,
+        country: &Country,
+        category: &str,
+        basis: &mut FactorBasis
     ) -> f64 {
         // Try to find specific impact factor
-        let key = format!("{:?}_{:?}_{:?}_{}", 
-            facility.facility_type, 
-            product.product_type, 
-            country, 
-            category);
-        
-        if let Some(factor) = self.impact_factors.get(&key) {
+        if let Some(factor) = self.find_processing_factor(product, facility, country, category) {
+            *basis = FactorBasis::from(factor);
             return factor.value_per_tonne * product.annual_production;
         }
 
         // Fallback to default estimates
+        *basis = FactorBasis::DefaultEstimate;
         self.get_default_processing_impact(&facility.facility_type, &product.product_type, category) * product.annual_production
     }
 
-    fn estimate_energy_consumption_per_tonne(
-        &self,
-        product: &ProcessedProduct,
-        facility: &ProcessingFacilityProfile
-    ) -> f64 {
-        // Sum energy intensity from all processing steps
-        let step_energy: f64 = product.processing_steps.iter()
-            .map(|step| step.energy_intensity)
-            .sum();
+    fn estimate_energy_consumption_per_tonne(&self, product: &ProcessedProduct) -> f64 {
+        // Sum the electricity of all processing steps; fuel-fired and sun drying is heat
+        product.processing_steps.iter()
+            .map(|step| step.electricity_per_tonne())
+            .sum()
+    }
 
-        // Add overhead energy (lighting, administration, etc.)
-        let overhead_factor = match facility.facility_type {
+    /// Deprecated overhead multiplier on step intensities, used only when
+    /// FacilityOverhead::legacy_multipliers is set
+    fn energy_overhead_factor(&self, facility: &ProcessingFacilityProfile) -> f64 {
+        match facility.facility_type {
             ProcessingFacilityType::Mill => 1.2,
             ProcessingFacilityType::Bakery => 1.5,
             ProcessingFacilityType::FishProcessing => 1.8, // Refrigeration
             ProcessingFacilityType::MeatProcessing => 1.8,
             ProcessingFacilityType::DairyProcessing => 1.6,
             _ => 1.3,
-        };
-
-        step_energy * overhead_factor
+        }
     }
 
     fn apply_facility_adjustments(
@@ -443,13 +2005,7 @@ impl ProcessingLCAEngine {
         let mut adjusted_impact = base_impact;
 
         // Facility scale efficiency (economies of scale)
-        let scale_factor = match facility.processing_capacity {
-            cap if cap < 10.0 => 1.2,    // Small facilities less efficient
-            cap if cap < 100.0 => 1.0,   // Medium facilities baseline
-            cap if cap < 1000.0 => 0.9,  // Large facilities more efficient
-            _ => 0.8,                     // Very large facilities most efficient
-        };
-        adjusted_impact *= scale_factor;
+        adjusted_impact *= self.scale_factor(facility);
 
         // Facility age efficiency
         let age_factor = if let Some(year) = facility.established_year {
@@ -494,12 +2050,15 @@ impl ProcessingLCAEngine {
 
         // Category-specific adjustments
         match category {
-            "Energy consumption" | "Global warming" => {
-                // Renewable energy reduces these impacts
-                let renewable_factor = 1.0 - (operations.energy_management.renewable_energy_percentage / 100.0 * 0.8);
+            // Renewable energy reduces these impacts, only for the daytime share of consumption
+            // under a time-of-use profile; a declared mix is already reflected in the
+            // per-source emission factors
+            "Energy consumption" | "Global warming" if operations.energy_management.energy_mix.is_empty() => {
+                let offset_share = operations.energy_management.renewable_share_percent() / 100.0 * facility.renewable_offset_share();
+                let renewable_factor = 1.0 - offset_share * 0.8;
                 adjusted_impact *= renewable_factor;
             },
-            "Water consumption" | "Water scarcity" => {
+            "Gross process water use" | "Water scarcity" => {
                 // Water conservation measures
                 let conservation_factor = 1.0 - (operations.water_management.water_conservation_measures.len() as f64 * 0.05);
                 adjusted_impact *= conservation_factor.max(0.7);
@@ -512,12 +2071,12 @@ impl ProcessingLCAEngine {
 
     fn apply_processing_adjustments(
         &self,
-        impacts: &mut HashMap<String, MidpointResult>,
+        impacts: &mut BTreeMap<String, MidpointResult>,
         operations: &ProcessingOperations,
         country: &Country
     ) {
         // Apply regional water scarcity adjustments
-        if let Some(water_result) = impacts.get_mut("Water consumption") {
+        if let Some(water_result) = impacts.get_mut("Water consumption").filter(|_| self.methodology.includes_category("Water scarcity")) {
             let aware_factor = match country {
                 Country::Ghana => 20.0,
                 Country::Nigeria => 25.0, // Higher scarcity in northern regions
@@ -527,12 +2086,15 @@ impl ProcessingLCAEngine {
             let water_scarcity_impact = MidpointResult {
                 value: water_result.value * aware_factor,
                 unit: "m3 H2O-eq".to_string(),
+                basis: ImpactBasis::Total,
                 uncertainty_range: (
                     water_result.uncertainty_range.0 * aware_factor,
                     water_result.uncertainty_range.1 * aware_factor
                 ),
+                geometric_sd: None,
                 data_quality_score: water_result.data_quality_score,
                 contributing_sources: vec![format!("AWARE regional factor: {}", aware_factor)],
+                metadata: None,
             };
 
             impacts.insert("Water scarcity".to_string(), water_scarcity_impact);
@@ -545,89 +2107,122 @@ impl ProcessingLCAEngine {
         }
     }
 
+    /// Midpoint-to-endpoint characterization as (endpoint, midpoint, factor per midpoint unit)
+    pub fn endpoint_characterization(&self) -> Vec<(&'static str, &'static str, f64)> {
+        vec![
+            // Human Health (DALY)
+            ("Human Health", "Global warming", self.characterization_factors.human_health.climate_health_africa),
+            ("Human Health", "Air pollution", self.characterization_factors.human_health.air_quality_health_africa),
+            // Ecosystem Quality (species.yr), with the farm engine's factors
+            ("Ecosystem Quality", "Global warming", 1.2e-14),
+            ("Ecosystem Quality", "Land use", 2.1e-10),
+            // Resource Scarcity (USD)
+            ("Resource Scarcity", "Energy consumption", 0.08), // USD per kWh
+            ("Resource Scarcity", "Water scarcity", self.characterization_factors.resource_scarcity.water_scarcity_africa),
+        ]
+    }
+
     fn calculate_processing_endpoint_impacts(
         &self,
-        midpoint: &HashMap<String, MidpointResult>
-    ) -> Result<HashMap<String, EndpointResult>, Box<dyn std::error::Error>> {
+        midpoint: &BTreeMap<String, MidpointResult>
+    ) -> Result<BTreeMap<String, EndpointResult>, EngineError> {
         
-        let mut endpoint = HashMap::new();
-
-        // Human Health (DALY)
-        let mut human_health = 0.0;
-
-        if let Some(gwp) = midpoint.get("Global warming") {
-            human_health += gwp.value * self.characterization_factors.human_health.climate_health_africa;
-        }
-
-        if let Some(air_pollution) = midpoint.get("Air pollution") {
-            human_health += air_pollution.value * self.characterization_factors.human_health.air_quality_health_africa;
-        }
+        let mut endpoint = BTreeMap::new();
+        let characterization = self.endpoint_characterization();
+        let characterize = |endpoint: &str| -> f64 {
+            characterization.iter()
+                .filter(|(e, _, _)| *e == endpoint)
+                .filter_map(|(_, category, factor)| midpoint.get(*category).map(|r| r.value * factor))
+                .sum()
+        };
 
+        let human_health = characterize("Human Health");
         endpoint.insert("Human Health".to_string(), EndpointResult {
             value: human_health,
             unit: "DALY".to_string(),
-            uncertainty_range: (human_health * 0.5, human_health * 2.0),
-            normalization_factor: Some(5.2e-2),
+            uncertainty_range: ((human_health * 0.5).min(human_health * 2.0), (human_health * 0.5).max(human_health * 2.0)),
+            normalization_factor: per_tonne_reference("Human Health"),
             regional_adaptation_factor: Some(1.5),
+            contributions: endpoint_contributions("Human Health", &characterization, midpoint),
+            metadata: None,
         });
 
-        // Resource Scarcity (USD)
-        let mut resource_scarcity = 0.0;
-
-        if let Some(energy) = midpoint.get("Energy consumption") {
-            resource_scarcity += energy.value * 0.08; // USD per kWh
-        }
-
-        if let Some(water_scarcity) = midpoint.get("Water scarcity") {
-            resource_scarcity += water_scarcity.value * self.characterization_factors.resource_scarcity.water_scarcity_africa;
-        }
+        let ecosystem_quality = characterize("Ecosystem Quality");
+        endpoint.insert("Ecosystem Quality".to_string(), EndpointResult {
+            value: ecosystem_quality,
+            unit: "species.yr".to_string(),
+            uncertainty_range: ((ecosystem_quality * 0.3).min(ecosystem_quality * 3.0), (ecosystem_quality * 0.3).max(ecosystem_quality * 3.0)),
+            normalization_factor: per_tonne_reference("Ecosystem Quality"),
+            regional_adaptation_factor: Some(1.8),
+            contributions: endpoint_contributions("Ecosystem Quality", &characterization, midpoint),
+            metadata: None,
+        });
 
+        let resource_scarcity = characterize("Resource Scarcity");
         endpoint.insert("Resource Scarcity".to_string(), EndpointResult {
             value: resource_scarcity,
             unit: "USD".to_string(),
             uncertainty_range: (resource_scarcity * 0.7, resource_scarcity * 1.5),
-            normalization_factor: Some(8.5e3),
+            normalization_factor: per_tonne_reference("Resource Scarcity"),
             regional_adaptation_factor: Some(1.3),
+            contributions: endpoint_contributions("Resource Scarcity", &characterization, midpoint),
+            metadata: None,
         });
 
+        // Endpoints none of whose midpoints were assessed are left out rather than reported as zero
+        endpoint.retain(|name, _| characterization.iter()
+            .any(|(e, category, _)| e == name && midpoint.contains_key(*category)));
+
         Ok(endpoint)
     }
 
-    fn calculate_processing_single_score(
+    /// Single score of the facility's endpoints per tonne of product, on the farm engine's
+    /// normalization and weighting so that scores from either engine compare
+    pub(crate) fn calculate_processing_single_score(
         &self,
-        endpoint: &HashMap<String, EndpointResult>
-    ) -> Result<SingleScoreResult, Box<dyn std::error::Error>> {
-        
-        let weighting_factors = HashMap::from([
-            ("Human Health".to_string(), 0.6),      // Higher weight for health in processing
-            ("Resource Scarcity".to_string(), 0.4), // Resource efficiency important
-        ]);
-
-        let mut single_score = 0.0;
-        
-        for (category, result) in endpoint {
-            if let Some(weight) = weighting_factors.get(category) {
-                single_score += result.value * weight;
-            }
-        }
+        endpoint: &BTreeMap<String, EndpointResult>,
+        total_production: f64,
+        country: &Country
+    ) -> Result<SingleScoreResult, EngineError> {
+        let per_tonne = per_tonne_endpoints(endpoint, total_production);
+        let (mut score, contributions) = weighted_single_score(&per_tonne, self.weighting_method(), &self.reference_basis())?;
+        score.interpretation = Some(self.interpret_score(score.value, &contributions, &per_tonne, country));
+        Ok(score)
+    }
 
-        // Normalize to 0-1 scale using sigmoid function
-        let normalized_score = 1.0 / (1.0 + (-0.1 * (single_score - 50.0)).exp());
+    fn weighting_method(&self) -> &WeightingMethod {
+        self.methodology.weighting_method.as_ref().unwrap_or(&WeightingMethod::None)
+    }
 
-        Ok(SingleScoreResult {
-            value: normalized_score,
-            unit: "Processing Environmental Impact Index (0-1, lower is better)".to_string(),
-            uncertainty_range: (normalized_score * 0.8, normalized_score * 1.2),
-            weighting_factors,
-            methodology: "Processing-adapted African LCA methodology".to_string(),
-        })
+    /// Normalization named in the methodology note, with the per-tonne basis it is applied on
+    fn reference_basis(&self) -> String {
+        format!(
+            "{:?} (per tonne of product, per-kg references × 1000)",
+            self.methodology.normalization_method.as_ref().unwrap_or(&NormalizationMethod::None)
+        )
+    }
+
+    /// Interpretation of a score against the facility references for the weighting
+    fn interpret_score(
+        &self,
+        score: f64,
+        contributions: &BTreeMap<String, f64>,
+        endpoint: &BTreeMap<String, EndpointResult>,
+        country: &Country
+    ) -> SingleScoreInterpretation {
+        let reference = find_score_reference(
+            &self.score_references, country, &self.methodology.functional_unit, Some(self.weighting_method())
+        );
+        interpret_single_score(score, contributions, endpoint, reference, country)
     }
 
     fn assess_processing_data_quality(
         &self,
         products: &[ProcessedProduct],
-        _country: &Country
-    ) -> Result<DataQuality, Box<dyn std::error::Error>> {
+        facility: &ProcessingFacilityProfile,
+        country: &Country,
+        factor_uses: &[FactorUse]
+    ) -> Result<DataQuality, EngineError> {
         
         let mut warnings = Vec::new();
         let mut recommendations = Vec::new();
@@ -641,50 +2236,156 @@ impl ProcessingLCAEngine {
             if product.raw_material_inputs.is_empty() {
                 warnings.push(format!("No raw material inputs defined for {}", product.name));
             }
+
+            // Products for which no loaded factor matched fall back on generic estimates
+            let uses: Vec<&FactorUse> = factor_uses.iter().filter(|u| u.product == product.name).collect();
+            let defaults = uses.iter().filter(|u| matches!(u.basis, FactorBasis::DefaultEstimate)).count();
+            if defaults > 0 && !uses.iter().any(|u| matches!(u.basis, FactorBasis::Specific { .. })) {
+                warnings.push(format!(
+                    "No {} processing factors for {} ({:?}/{:?}); {} categories rely on default estimates",
+                    country, product.name, facility.facility_type, product.product_type, defaults
+                ));
+            }
+        }
+
+        // Pedigree scores of the factors actually used; default estimates are excluded
+        // from the quality score, as in the production engine
+        let mut quality_scores = Vec::new();
+        let mut source_scores: Vec<(DataSource, Vec<f64>)> = Vec::new();
+        for factor_use in factor_uses {
+            let source_type = match &factor_use.basis {
+                FactorBasis::Specific { country: Country::Global, .. } => DataSource::Global,
+                FactorBasis::Specific { country, .. } => DataSource::CountrySpecific(*country),
+                FactorBasis::ActivityData => DataSource::Hybrid,
+                FactorBasis::DefaultEstimate => DataSource::Estimated,
+            };
+            let score = factor_use.pedigree().map_or(0.0, |p| p.calculate_overall_quality_score());
+            if factor_use.pedigree().is_some() {
+                quality_scores.push(score);
+            }
+            if let FactorBasis::Specific { ref source, .. } = factor_use.basis {
+                if score < 0.5 {
+                    warnings.push(format!("Low data quality for {} {}: {}",
+                        factor_use.product, factor_use.category, source));
+                }
+            }
+
+            match source_scores.iter_mut().find(|(s, _)| *s == source_type) {
+                Some((_, scores)) => scores.push(score),
+                None => source_scores.push((source_type, vec![score])),
+            }
+        }
+
+        let mean = |values: &[f64]| if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 };
+        let overall_quality = mean(&quality_scores);
+        let completeness_score = if factor_uses.is_empty() {
+            0.0
+        } else {
+            quality_scores.len() as f64 / factor_uses.len() as f64
+        };
+
+        let data_source_mix = source_scores.into_iter()
+            .map(|(source_type, scores)| DataSourceContribution {
+                source_type,
+                percentage: scores.len() as f64 / factor_uses.len() as f64 * 100.0,
+                quality_score: mean(&scores),
+            })
+            .collect();
+
+        // Representativeness from the pedigree indicators (1 = best, 5 = worst)
+        let representativeness = |indicator: fn(&PedigreeScore) -> u8| {
+            let scores: Vec<f64> = factor_uses.iter()
+                .filter_map(FactorUse::pedigree)
+                .map(|p| 1.0 - (indicator(p) as f64 - 1.0) / 4.0)
+                .collect();
+            mean(&scores)
+        };
+
+        // Seasonal raw materials can only be processed while the facility runs
+        let seasonal_products: Vec<&str> = products.iter()
+            .filter(|p| p.raw_material_inputs.iter().any(|rm| rm.seasonal_availability))
+            .map(|p| p.name.as_str())
+            .collect();
+        let claimed_production: f64 = products.iter().map(|p| p.annual_production).sum();
+        let feasible_throughput = facility.annual_throughput_capacity();
+        if !seasonal_products.is_empty() && claimed_production > feasible_throughput {
+            warnings.push(format!(
+                "Claimed production of {:.0} t/year exceeds the {:.0} t feasible in {} operating months at {} t/day; {} depend on seasonal raw materials",
+                claimed_production, feasible_throughput, facility.months_in_operation(),
+                facility.processing_capacity, seasonal_products.join(", ")
+            ));
         }
 
         // Generate recommendations
         recommendations.push("Consider implementing energy monitoring systems for better data quality".to_string());
         recommendations.push("Regular water consumption monitoring recommended".to_string());
+        if completeness_score < 0.5 {
+            recommendations.push("Collect processing step data or site-specific factors for categories relying on default estimates".to_string());
+        }
 
-        let confidence_level = if warnings.len() > products.len() {
-            ConfidenceLevel::Low
-        } else {
-            ConfidenceLevel::Medium
+        // Quality of the data used, discounted by the share of categories left on defaults
+        let confidence_level = match overall_quality * completeness_score {
+            q if q > 0.7 => ConfidenceLevel::High,
+            q if q > 0.5 => ConfidenceLevel::Medium,
+            q if q > 0.3 => ConfidenceLevel::Low,
+            _ => ConfidenceLevel::VeryLow,
         };
 
         Ok(DataQuality {
             overall_confidence: confidence_level,
-            data_source_mix: vec![
-                DataSourceContribution {
-                    source_type: DataSource::Estimated,
-                    percentage: 60.0,
-                    quality_score: 0.6,
-                },
-                DataSourceContribution {
-                    source_type: DataSource::Global,
-                    percentage: 40.0,
-                    quality_score: 0.5,
-                },
-            ],
+            data_source_mix,
             regional_adaptation: true,
-            completeness_score: 0.7,
-            temporal_representativeness: 0.8,
-            geographical_representativeness: 0.6,
-            technological_representativeness: 0.7,
+            completeness_score,
+            quality_score: overall_quality,
+            temporal_representativeness: representativeness(|p| p.temporal_correlation),
+            geographical_representativeness: representativeness(|p| p.geographical_correlation),
+            technological_representativeness: representativeness(|p| p.technological_correlation),
             warnings,
             recommendations,
+            calculation_warnings: Vec::new(),
+            factor_overrides: Vec::new(),
         })
     }
 
     fn generate_processing_recommendations(
         &self,
-        impacts: &HashMap<String, MidpointResult>,
-        _facility: &ProcessingFacilityProfile,
-        operations: &ProcessingOperations
-    ) -> Result<Vec<ProcessingRecommendation>, Box<dyn std::error::Error>> {
+        impacts: &BTreeMap<String, MidpointResult>,
+        facility: &ProcessingFacilityProfile,
+        operations: &ProcessingOperations,
+        products: &[ProcessedProduct],
+        country: &Country,
+        energy: &EnergyReconciliation
+    ) -> Result<Vec<ProcessingRecommendation>, EngineError> {
         
         let mut recommendations = Vec::new();
+        let grid_factor = &energy.grid_factor;
+
+        // Biogas capture when effluent pond methane dominates the carbon footprint
+        let pond_ch4_co2e: f64 = products.iter()
+            .map(|product| self.calculate_wastewater_discharge(product, facility, operations, &energy.overhead).methane_kg * CH4_GWP100)
+            .sum();
+        if let Some(gwp) = impacts.get("Global warming") {
+            if gwp.value > 0.0 && pond_ch4_co2e / gwp.value > 0.5 {
+                recommendations.push(ProcessingRecommendation {
+                    category: ProcessingRecommendationCategory::WasteToEnergy,
+                    title: "Capture biogas from effluent ponds".to_string(),
+                    description: format!(
+                        "Cover anaerobic ponds or install a digester; pond methane is about {:.0}% of the facility's GWP",
+                        pond_ch4_co2e / gwp.value * 100.0
+                    ),
+                    potential_savings: BTreeMap::from([
+                        ("Global warming".to_string(), (pond_ch4_co2e / gwp.value * 80.0).min(80.0)),
+                        ("Energy consumption".to_string(), 30.0),
+                    ]),
+                    complexity: ComplexityLevel::Complex,
+                    implementation_cost: ImplementationCost::High,
+                    payback_period: None,
+                    estimated_annual_savings: None,
+                    capital_cost: None,
+                    priority: ProcessingPriority::Critical,
+                });
+            }
+        }
 
         // Energy efficiency recommendations
         if let Some(energy_impact) = impacts.get("Energy consumption") {
@@ -695,13 +2396,15 @@ impl ProcessingLCAEngine {
                             category: ProcessingRecommendationCategory::EnergyEfficiency,
                             title: "Switch to grid electricity or solar power".to_string(),
                             description: "Reduce reliance on diesel generators by connecting to the grid or installing solar panels".to_string(),
-                            potential_savings: HashMap::from([
+                            potential_savings: BTreeMap::from([
                                 ("Global warming".to_string(), 40.0),
                                 ("Energy consumption".to_string(), 20.0),
                             ]),
                             complexity: ComplexityLevel::Moderate,
                             implementation_cost: ImplementationCost::High,
-                            payback_period: Some(24.0),
+                            payback_period: None,
+                            estimated_annual_savings: None,
+                            capital_cost: None,
                             priority: ProcessingPriority::High,
                         });
                     },
@@ -710,13 +2413,15 @@ impl ProcessingLCAEngine {
                             category: ProcessingRecommendationCategory::EnergyEfficiency,
                             title: "Implement energy-efficient equipment".to_string(),
                             description: "Upgrade to energy-efficient motors, LED lighting, and optimize equipment operation".to_string(),
-                            potential_savings: HashMap::from([
+                            potential_savings: BTreeMap::from([
                                 ("Energy consumption".to_string(), 25.0),
                                 ("Global warming".to_string(), 20.0),
                             ]),
                             complexity: ComplexityLevel::Moderate,
                             implementation_cost: ImplementationCost::Medium,
-                            payback_period: Some(18.0),
+                            payback_period: None,
+                            estimated_annual_savings: None,
+                            capital_cost: None,
                             priority: ProcessingPriority::Medium,
                         });
                     }
@@ -724,27 +2429,114 @@ impl ProcessingLCAEngine {
             }
         }
 
-        // Water conservation recommendations
-        if let Some(water_impact) = impacts.get("Water consumption") {
-            if water_impact.value > 500.0 { // High water consumption threshold
+        // Shift part of the energy mix to solar
+        let energy_management = &operations.energy_management;
+        let renewable_share = energy_management.renewable_share_percent();
+        if renewable_share < 50.0 {
+            let mix = energy_management.effective_mix();
+            let factor = |source: &EnergySource| Self::source_emission_factor(source, country, grid_factor);
+            let mix_factor: f64 = mix.iter()
+                .map(|share| share.percentage / 100.0 * factor(&share.source))
+                .sum();
+            // Move up to 20 points from the most carbon-intensive source
+            let dirtiest = mix.iter()
+                .filter(|share| !share.source.is_renewable())
+                .max_by(|a, b| factor(&a.source).total_cmp(&factor(&b.source)));
+            if let (Some(dirtiest), true) = (dirtiest, mix_factor > 0.0) {
+                let shifted = dirtiest.percentage.min(20.0) / 100.0;
+                let reduction = shifted * (factor(&dirtiest.source)
+                    - factor(&EnergySource::SolarPower)) / mix_factor * 100.0;
+                recommendations.push(ProcessingRecommendation {
+                    category: ProcessingRecommendationCategory::RenewableEnergy,
+                    title: "Raise the solar share of the energy mix".to_string(),
+                    description: format!(
+                        "Renewables supply {:.0}% of electricity; replacing {:.0} points of {:?} with solar cuts energy-related emissions by about {:.0}%",
+                        renewable_share, shifted * 100.0, dirtiest.source, reduction
+                    ),
+                    potential_savings: BTreeMap::from([
+                        ("Global warming".to_string(), reduction),
+                    ]),
+                    complexity: ComplexityLevel::Moderate,
+                    implementation_cost: ImplementationCost::High,
+                    payback_period: None,
+                    estimated_annual_savings: None,
+                    capital_cost: None,
+                    priority: ProcessingPriority::Medium,
+                });
+            }
+        }
+
+        // Water recycling: quantify the freshwater saved by raising the recycle rate
+        const TARGET_RECYCLE_FRACTION: f64 = 0.6; // closed-loop washing and cooling water
+        let water = &operations.water_management;
+        let gross_water = impacts.get("Gross process water use").map_or(0.0, |r| r.value);
+        if gross_water > 500.0 && water.recycle_fraction() < TARGET_RECYCLE_FRACTION { // High water use threshold
+            let improved = WaterManagement {
+                recycled_water_percentage: TARGET_RECYCLE_FRACTION * 100.0,
+                ..water.clone()
+            };
+            let net_now = water.net_freshwater_withdrawal(gross_water, water.rainwater_harvesting_m3_per_year);
+            let net_target = improved.net_freshwater_withdrawal(gross_water, water.rainwater_harvesting_m3_per_year);
+            let saving_percent = if net_now > 0.0 { (net_now - net_target) / net_now * 100.0 } else { 0.0 };
+            if saving_percent > 0.0 {
                 recommendations.push(ProcessingRecommendation {
-                            category: ProcessingRecommendationCategory::WaterConservation,
-                    title: "Install water recycling system".to_string(),
-                    description: "Implement water treatment and recycling for process water reuse".to_string(),
-                    potential_savings: HashMap::from([
-                        ("Water consumption".to_string(), 40.0),
-                        ("Water scarcity".to_string(), 40.0),
+                    category: ProcessingRecommendationCategory::WaterConservation,
+                    title: format!(
+                        "Raise process water recycling from {:.0}% to {:.0}%",
+                        water.recycle_fraction() * 100.0, TARGET_RECYCLE_FRACTION * 100.0
+                    ),
+                    description: format!(
+                        "Treating and reusing process water cuts freshwater withdrawal from {:.0} to {:.0} m3/year",
+                        net_now, net_target
+                    ),
+                    potential_savings: BTreeMap::from([
+                        ("Water consumption".to_string(), saving_percent),
+                        ("Water scarcity".to_string(), saving_percent),
                     ]),
                     complexity: ComplexityLevel::Complex,
                     implementation_cost: ImplementationCost::High,
-                    payback_period: Some(36.0),
-                    priority: ProcessingPriority::High,
+                    payback_period: None,
+                    estimated_annual_savings: None,
+                    capital_cost: None,
+                    priority: if saving_percent >= 40.0 { ProcessingPriority::High } else { ProcessingPriority::Medium },
                 });
             }
         }
 
-        // Waste management recommendations
-        if let Some(waste_impact) = impacts.get("Solid waste generation") {
+        // Waste management recommendations: with reported streams, divert the worst one
+        let waste = &operations.waste_management;
+        if !waste.waste_streams.is_empty() {
+            let worst = self.waste_stream_impacts(waste, country, grid_factor).into_iter()
+                .max_by(|a, b| a.gwp_kg_co2e.total_cmp(&b.gwp_kg_co2e))
+                .filter(|stream| stream.gwp_kg_co2e > 0.0);
+            if let Some(worst) = worst {
+                let alternative = worst.composition.preferred_disposal();
+                let saved = worst.gwp_kg_co2e - self.waste_stream_gwp(
+                    worst.tonnes_per_year, worst.composition, alternative, country, grid_factor
+                );
+                let facility_gwp = impacts.get("Global warming").map_or(0.0, |r| r.value);
+                if saved > 0.0 && facility_gwp > 0.0 {
+                    let saving_percent = (saved / facility_gwp * 100.0).min(100.0);
+                    recommendations.push(ProcessingRecommendation {
+                        category: ProcessingRecommendationCategory::WasteReduction,
+                        title: format!("Divert {} from {:?} to {:?}", worst.stream_name, worst.disposal_method, alternative),
+                        description: format!(
+                            "{} is the largest waste emission source at {:.0} kg CO2-eq/year; {:?} would save about {:.0} kg CO2-eq/year",
+                            worst.stream_name, worst.gwp_kg_co2e, alternative, saved
+                        ),
+                        potential_savings: BTreeMap::from([
+                            ("Global warming".to_string(), saving_percent),
+                        ]),
+                        complexity: ComplexityLevel::Moderate,
+                        implementation_cost: ImplementationCost::Medium,
+                        payback_period: None,
+                        estimated_annual_savings: None,
+                        capital_cost: None,
+                        priority: if saving_percent >= 20.0 { ProcessingPriority::High } else { ProcessingPriority::Medium },
+                    });
+                }
+            }
+        } else if let Some(waste_impact) = impacts.get("Solid waste generation") {
             if waste_impact.value > 1000.0 { // High waste generation threshold
                 match operations.waste_management.waste_disposal_method {
                     WasteDisposalMethod::Landfill => {
@@ -752,13 +2544,15 @@ impl ProcessingLCAEngine {
                             category: ProcessingRecommendationCategory::WasteReduction,
                             title: "Implement composting or anaerobic digestion".to_string(),
                             description: "Convert organic waste to compost or biogas instead of landfilling".to_string(),
-                            potential_savings: HashMap::from([
+                            potential_savings: BTreeMap::from([
                                 ("Global warming".to_string(), 60.0),
                                 ("Solid waste generation".to_string(), 80.0),
                             ]),
                             complexity: ComplexityLevel::Moderate,
                             implementation_cost: ImplementationCost::Medium,
-                            payback_period: Some(24.0),
+                            payback_period: None,
+                            estimated_annual_savings: None,
+                            capital_cost: None,
                             priority: ProcessingPriority::High,
                         });
                     },
@@ -767,12 +2561,14 @@ impl ProcessingLCAEngine {
                             category: ProcessingRecommendationCategory::WasteReduction,
                             title: "Increase waste reduction and recycling".to_string(),
                             description: "Implement waste minimization practices and expand recycling programs".to_string(),
-                            potential_savings: HashMap::from([
+                            potential_savings: BTreeMap::from([
                                 ("Solid waste generation".to_string(), 30.0),
                             ]),
                             complexity: ComplexityLevel::Simple,
                             implementation_cost: ImplementationCost::Low,
-                            payback_period: Some(12.0),
+                            payback_period: None,
+                            estimated_annual_savings: None,
+                            capital_cost: None,
                             priority: ProcessingPriority::Medium,
                         });
                     }
@@ -780,6 +2576,30 @@ impl ProcessingLCAEngine {
             }
         }
 
+        // Low-GWP conversion when leakage of a high-GWP refrigerant dominates the carbon footprint
+        if let (Some(refrigeration), Some(gwp)) = (&operations.refrigeration, impacts.get("Global warming")) {
+            let refrigerant_co2e = self.calculate_refrigerant_emissions(refrigeration, facility, country);
+            if refrigeration.refrigerant_type.is_high_gwp() && gwp.value > 0.0 && refrigerant_co2e / gwp.value > 0.5 {
+                recommendations.push(ProcessingRecommendation {
+                    category: ProcessingRecommendationCategory::EquipmentUpgrade,
+                    title: "Convert to low-GWP refrigerant".to_string(),
+                    description: format!(
+                        "Replace {:?} with ammonia, CO2 or hydrocarbon systems and tighten leak checks during servicing; its leakage is about {:.0}% of the facility's GWP",
+                        refrigeration.refrigerant_type, refrigerant_co2e / gwp.value * 100.0
+                    ),
+                    potential_savings: BTreeMap::from([
+                        ("Global warming".to_string(), 30.0),
+                    ]),
+                    complexity: ComplexityLevel::Complex,
+                    implementation_cost: ImplementationCost::High,
+                    payback_period: None,
+                    estimated_annual_savings: None,
+                    capital_cost: None,
+                    priority: ProcessingPriority::High,
+                });
+            }
+        }
+
         // Equipment efficiency recommendations
         match operations.equipment_efficiency.equipment_age {
             EquipmentAge::Old | EquipmentAge::VeryOld => {
@@ -787,13 +2607,15 @@ impl ProcessingLCAEngine {
                             category: ProcessingRecommendationCategory::EquipmentUpgrade,
                     title: "Equipment modernization program".to_string(),
                     description: "Develop a phased approach to replace old equipment with energy-efficient alternatives".to_string(),
-                    potential_savings: HashMap::from([
+                    potential_savings: BTreeMap::from([
                         ("Energy consumption".to_string(), 35.0),
                         ("Global warming".to_string(), 25.0),
                     ]),
                     complexity: ComplexityLevel::Complex,
                     implementation_cost: ImplementationCost::High,
-                    payback_period: Some(48.0),
+                    payback_period: None,
+                    estimated_annual_savings: None,
+                    capital_cost: None,
                     priority: ProcessingPriority::Medium,
                 });
             },
@@ -803,21 +2625,52 @@ impl ProcessingLCAEngine {
         Ok(recommendations)
     }
 
-    fn aggregate_midpoint_results(&self, total: &mut MidpointResult, addition: &MidpointResult) {
-        total.value += addition.value;
-        
-        // Simple uncertainty propagation
-        let total_variance = (total.uncertainty_range.1 - total.uncertainty_range.0).powi(2) / 16.0;
-        let addition_variance = (addition.uncertainty_range.1 - addition.uncertainty_range.0).powi(2) / 16.0;
-        let combined_variance = total_variance + addition_variance;
-        let combined_std = combined_variance.sqrt();
-
-        total.uncertainty_range = (
-            (total.value - 2.0 * combined_std).max(0.0),
-            total.value + 2.0 * combined_std
-        );
+    /// Annual savings and payback of each recommendation from the facility's own bills: its
+    /// energy and water savings percentages are applied to what the facility pays per year
+    /// at its tariffs, and payback is the category's capex for this size class, converted
+    /// from USD at the given rates, over those savings. Anything that cannot be costed is
+    /// left as None rather than guessed.
+    fn cost_recommendations(
+        &self,
+        recommendations: &mut [ProcessingRecommendation],
+        impacts: &BTreeMap<String, MidpointResult>,
+        facility: &ProcessingFacilityProfile,
+        operations: &ProcessingOperations,
+        (currency, rates): (&Currency, &ExchangeRates),
+        energy: &EnergyReconciliation
+    ) {
+        let tariffs = &operations.tariffs;
+        let electricity_kwh = energy.metered_kwh.unwrap_or(energy.step_based_kwh);
+        let diesel_litres = operations.energy_management.monthly_fuel_consumption.map(|litres| litres * 12.0);
+        let energy_bill = match (tariffs.electricity_per_kwh, tariffs.diesel_per_litre.zip(diesel_litres)) {
+            (None, None) => None,
+            (electricity, diesel) => Some(
+                electricity.map_or(0.0, |price| price * electricity_kwh)
+                    + diesel.map_or(0.0, |(price, litres)| price * litres)
+            ),
+        };
+        let water = &operations.water_management;
+        let gross_water = impacts.get("Gross process water use").map_or(0.0, |r| r.value);
+        let water_bill = tariffs.water_per_m3.map(|price| {
+            price * water.net_freshwater_withdrawal(gross_water, water.rainwater_harvesting_m3_per_year)
+        });
 
-        total.contributing_sources.extend(addition.contributing_sources.clone());
+        let size = facility.capacity_range();
+        for recommendation in recommendations.iter_mut() {
+            let savings: Vec<f64> = [("Energy consumption", energy_bill), ("Water consumption", water_bill)].iter()
+                .filter_map(|(category, bill)| {
+                    let percent = recommendation.potential_savings.get(*category)?;
+                    Some(percent / 100.0 * (*bill)?)
+                })
+                .collect();
+            recommendation.estimated_annual_savings = (!savings.is_empty()).then(|| savings.iter().sum());
+            recommendation.capital_cost = self.capex_table.get(&capex_key(&recommendation.category, &size))
+                .map(|usd| rates.convert(*usd, &Currency::USD, currency));
+            recommendation.payback_period = match (recommendation.capital_cost, recommendation.estimated_annual_savings) {
+                (Some(capex), Some(annual)) if annual > 0.0 => Some(capex / annual * 12.0),
+                _ => None,
+            };
+        }
     }
 
     fn get_default_processing_impact(&self, facility_type: &ProcessingFacilityType, product_type: &ProductType, impact: &str) -> f64 {
@@ -863,3 +2716,1210 @@ impl ProcessingLCAEngine {
         }
     }
 }
+
+/// Normalization reference of an endpoint per tonne of product
+fn per_tonne_reference(endpoint: &str) -> Option<f64> {
+    african_reference_per_kg(endpoint).map(|reference| reference * 1000.0)
+}
+
+/// Annual endpoint results divided by the tonnes produced, the basis of the single score. A
+/// facility without production is scored on its annual totals.
+fn per_tonne_endpoints(endpoint: &BTreeMap<String, EndpointResult>, total_production: f64) -> BTreeMap<String, EndpointResult> {
+    let tonnes = if total_production > 0.0 { total_production } else { 1.0 };
+    endpoint.iter()
+        .map(|(category, result)| (category.clone(), EndpointResult {
+            value: result.value / tonnes,
+            unit: format!("{} per tonne", result.unit),
+            uncertainty_range: (result.uncertainty_range.0 / tonnes, result.uncertainty_range.1 / tonnes),
+            contributions: result.contributions.iter()
+                .map(|(category, contribution)| (category.clone(), contribution.scaled(1.0 / tonnes)))
+                .collect(),
+            ..result.clone()
+        }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processing::models::{self as processing, *};
+    use crate::processing::data::ProcessingDataLoader;
+
+    fn methodology() -> LCAMethodology {
+        LCAMethodology::african_processing()
+    }
+
+    fn engine() -> ProcessingLCAEngine {
+        ProcessingLCAEngine::new(methodology())
+    }
+
+    fn facility(facility_type: ProcessingFacilityType) -> ProcessingFacilityProfile {
+        ProcessingFacilityProfile {
+            facility_name: "Test Facility".to_string(),
+            company_name: "Test Processors".to_string(),
+            facility_type,
+            processing_capacity: 20.0,
+            operational_hours_per_day: 16.0,
+            operational_days_per_year: 300,
+            established_year: Some(2010),
+            certifications: vec![],
+            employee_count: Some(40),
+            facility_size: None,
+            location_type: LocationType::Industrial,
+            operating_months: None,
+            daytime_operation_fraction: None,
+        }
+    }
+
+    fn operations(wastewater_treatment: WastewaterTreatment) -> ProcessingOperations {
+        ProcessingOperations {
+            energy_management: EnergyManagement {
+                primary_energy_source: EnergySource::GridElectricity,
+                secondary_energy_sources: vec![],
+                monthly_electricity_consumption: Some(10000.0),
+                monthly_fuel_consumption: None,
+                fuel_type: None,
+                renewable_energy_percentage: 0.0,
+                energy_efficiency_measures: vec![],
+                backup_generator: false,
+                energy_mix: vec![],
+                grid_emission_factor: None,
+                captive_generation: false,
+                backup_generator_hours_per_year: None,
+            },
+            water_management: processing::WaterManagement {
+                water_source: vec!["Borehole".to_string()],
+                monthly_water_consumption: None,
+                water_treatment: WaterTreatment::None,
+                water_conservation_measures: vec![],
+                recycled_water_percentage: 0.0,
+                rainwater_harvesting_m3_per_year: 0.0,
+                wastewater_treatment,
+            },
+            waste_management: WasteManagement {
+                solid_waste_generation: Some(100.0),
+                organic_waste_percentage: 70.0,
+                waste_disposal_method: WasteDisposalMethod::Composting,
+                recycling_programs: vec![],
+                byproduct_utilization: vec![],
+                waste_streams: vec![],
+            },
+            raw_material_sourcing: RawMaterialSourcing {
+                local_sourcing_percentage: 80.0,
+                average_transport_distance: 50.0,
+                transport_mode: TransportMode::Truck,
+                supplier_sustainability_practices: vec![],
+                seasonal_variation: true,
+                storage_practices: StoragePractices {
+                    storage_type: "Warehouse".to_string(),
+                    climate_control: false,
+                    pest_control_methods: vec![],
+                    storage_loss_percentage: 0.0,
+                    storage_days: None,
+                },
+            },
+            equipment_efficiency: EquipmentEfficiency {
+                equipment_age: EquipmentAge::Mature,
+                maintenance_frequency: MaintenanceFrequency::Monthly,
+                automation_level: AutomationLevel::SemiAutomated,
+                equipment_utilization_rate: 75.0,
+                modernization_investments: vec![],
+            },
+            refrigeration: None,
+            tariffs: UtilityTariffs::default(),
+            overhead: FacilityOverhead::default(),
+        }
+    }
+
+    fn crude_palm_oil() -> ProcessedProduct {
+        ProcessedProduct {
+            id: "cpo".to_string(),
+            name: "Crude palm oil".to_string(),
+            product_type: ProductType::PalmOil,
+            annual_production: 1000.0,
+            raw_material_inputs: vec![],
+            processing_steps: vec![ProcessingStep {
+                step_name: "Sterilization and pressing".to_string(),
+                energy_intensity: 100.0,
+                water_usage: 3500.0, // L/t - source of POME
+                duration: 4.0,
+                yield_efficiency: 95.0,
+                emissions_factor: None,
+                drying_method: None,
+            }],
+            packaging: PackagingInfo {
+                packaging_material: PackagingMaterial::Metal,
+                package_size: 200.0,
+                packaging_weight_per_unit: 15.0,
+                recyclable: true,
+            },
+            quality_grade: QualityGrade::Standard,
+            market_destination: MarketDestination::Local,
+            wastewater_load: None,
+            price_per_tonne: None,
+        }
+    }
+
+    fn assessment(
+        facility_type: ProcessingFacilityType,
+        operations: ProcessingOperations,
+        processed_products: Vec<ProcessedProduct>
+    ) -> ProcessingAssessment {
+        ProcessingAssessment {
+            schema_version: crate::schema::current_schema_version(),
+            id: uuid::Uuid::new_v4(),
+            facility_profile: facility(facility_type),
+            processing_operations: operations,
+            processed_products,
+            country: Country::Ghana,
+            currency: Currency::GHS,
+            exchange_rates: ExchangeRates::default(),
+            region: None,
+            assessment_date: chrono::Utc::now(),
+            methodology: methodology(),
+            results: None,
+            trend_analysis: None,
+        }
+    }
+
+    #[test]
+    fn r404a_leakage_matches_charge_times_rate_times_gwp() {
+        let refrigeration = RefrigerationSystem {
+            refrigerant_type: RefrigerantType::R404A,
+            total_charge_kg: Some(200.0),
+            annual_leakage_rate_percent: Some(15.0),
+            equipment_lifetime_years: Some(15.0),
+            end_of_life_recovery_percent: Some(0.0),
+        };
+
+        let emissions = engine().calculate_refrigerant_emissions(
+            &refrigeration, &facility(ProcessingFacilityType::FishProcessing), &Country::Ghana);
+
+        let operating = 200.0 * 0.15 * 4728.0; // 141,840 kg CO2-eq/year
+        let end_of_life = 200.0 * 4728.0 / 15.0;
+        assert!((emissions - (operating + end_of_life)).abs() < 1e-6, "got {emissions}");
+    }
+
+    #[test]
+    fn low_gwp_conversion_is_recommended_only_when_leakage_dominates() {
+        let conversion = |charge_kg: f64| {
+            let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+            ops.refrigeration = Some(RefrigerationSystem {
+                refrigerant_type: RefrigerantType::R404A,
+                total_charge_kg: Some(charge_kg),
+                annual_leakage_rate_percent: Some(15.0),
+                equipment_lifetime_years: None,
+                end_of_life_recovery_percent: None,
+            });
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+            engine().perform_processing_assessment(&mut mill).unwrap();
+            mill.results.unwrap().recommendations.unwrap_or_default().into_iter()
+                .find(|r| r.title == "Convert to low-GWP refrigerant")
+        };
+
+        // A few kg in a cold room next to the mill's energy and effluent
+        assert!(conversion(2.0).is_none());
+        let large_plant = conversion(2000.0).expect("conversion recommended");
+        assert!(large_plant.description.contains("R404A"), "{}", large_plant.description);
+    }
+
+    #[test]
+    fn ammonia_has_no_fugitive_gwp() {
+        let refrigeration = RefrigerationSystem {
+            refrigerant_type: RefrigerantType::Ammonia,
+            total_charge_kg: Some(200.0),
+            annual_leakage_rate_percent: None,
+            equipment_lifetime_years: None,
+            end_of_life_recovery_percent: None,
+        };
+
+        let emissions = engine().calculate_refrigerant_emissions(
+            &refrigeration, &facility(ProcessingFacilityType::FishProcessing), &Country::Nigeria);
+        assert_eq!(emissions, 0.0);
+    }
+
+    #[test]
+    fn untreated_pome_drives_eutrophication_and_methane() {
+        let engine = engine();
+        let mill = facility(ProcessingFacilityType::PalmOilMill);
+        let product = crude_palm_oil();
+
+        let products = std::slice::from_ref(&product);
+        let with_overhead = |treatment| {
+            let mut ops = operations(treatment);
+            ops.overhead.water_m3_per_year = Some(1050.0);
+            ops
+        };
+        let energy = engine.reconcile_energy(products, &mill, &with_overhead(WastewaterTreatment::None), &Country::Ghana, &None);
+
+        let untreated = engine.calculate_product_impacts(
+            &product, &mill, &with_overhead(WastewaterTreatment::None), &Country::Ghana, &energy, &mut Vec::new(), &mut Vec::new()).unwrap();
+        let treated = engine.calculate_product_impacts(
+            &product, &mill, &with_overhead(WastewaterTreatment::BiologicalTreatment), &Country::Ghana, &energy, &mut Vec::new(), &mut Vec::new()).unwrap();
+
+        // Open anaerobic ponds: (1000 t × 3.5 m3/t + 1050 m3 overhead) × 0.8 × 50 kg COD/m3 × 0.25 × 0.8 × 28
+        let pond_co2e = 3640.0 * 50.0 * 0.25 * 0.8 * CH4_GWP100;
+        let gwp_gap = untreated["Global warming"].value - treated["Global warming"].value;
+        assert!(gwp_gap > 0.9 * pond_co2e, "GWP gap {gwp_gap} vs pond CH4 {pond_co2e}");
+
+        for category in ["Freshwater eutrophication", "Marine eutrophication"] {
+            assert!(untreated[category].value > 1.4 * treated[category].value,
+                    "{category}: {} vs {}", untreated[category].value, treated[category].value);
+        }
+
+        let recommendations = engine.generate_processing_recommendations(
+            &untreated, &mill, &with_overhead(WastewaterTreatment::None), products, &Country::Ghana, &energy).unwrap();
+        assert!(recommendations.iter().any(|r| r.title.contains("biogas")));
+    }
+
+    #[test]
+    fn product_intensities_sum_back_to_facility_totals() {
+        let mut kernel_oil = crude_palm_oil();
+        kernel_oil.id = "pko".to_string();
+        kernel_oil.name = "Palm kernel oil".to_string();
+        kernel_oil.annual_production = 250.0;
+        kernel_oil.processing_steps[0].yield_efficiency = 45.0;
+
+        let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+        ops.refrigeration = Some(RefrigerationSystem {
+            refrigerant_type: RefrigerantType::R22,
+            total_charge_kg: Some(50.0),
+            annual_leakage_rate_percent: Some(10.0),
+            equipment_lifetime_years: None,
+            end_of_life_recovery_percent: None,
+        });
+        let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil(), kernel_oil]);
+        engine().perform_processing_assessment(&mut mill).unwrap();
+
+        let results = mill.results.unwrap();
+        let footprints = results.product_footprints.expect("product footprints");
+        for (category, total) in &results.midpoint_impacts {
+            let reconstructed: f64 = footprints.values()
+                .map(|f| f.per_tonne[category] * f.annual_production)
+                .sum();
+            assert!((reconstructed - total.value).abs() <= 1e-6 * total.value.abs().max(1.0),
+                    "{category}: {reconstructed} != {}", total.value);
+        }
+
+        // Mass allocation: 1000 t vs 250 t
+        assert!((footprints["Crude palm oil"].allocation_share - 0.8).abs() < 1e-12);
+
+        // 45% yield means more than double the burden per saleable tonne
+        let pko = &footprints["Palm kernel oil"];
+        assert!((pko.cumulative_yield - 0.45).abs() < 1e-12);
+        assert!(pko.yield_adjusted_per_tonne["Global warming"] > 2.0 * pko.per_tonne["Global warming"]);
+    }
+
+    /// Small mill that reports only annual output, so results lean on loaded factors or defaults
+    fn output_only_mill(facility_type: ProcessingFacilityType, product_type: ProductType) -> DataQuality {
+        let mut engine = engine();
+        let mut loader = crate::processing::data::ProcessingDataLoader::new();
+        loader.load_default_factors().unwrap();
+        engine.load_impact_factors(loader.get_factors().clone());
+
+        let mut ops = operations(WastewaterTreatment::None);
+        ops.energy_management.monthly_electricity_consumption = None;
+        let mut product = crude_palm_oil();
+        product.product_type = product_type;
+        product.processing_steps.clear();
+        let mut mill = assessment(facility_type, ops, vec![product]);
+        engine.perform_processing_assessment(&mut mill).unwrap();
+        mill.results.unwrap().data_quality
+    }
+
+    #[test]
+    fn ghana_specific_factors_raise_confidence_over_defaults() {
+        let maize = output_only_mill(ProcessingFacilityType::Mill, ProductType::FlourMaize);
+        let bakery = output_only_mill(ProcessingFacilityType::Bakery, ProductType::BakedGoods);
+
+        assert!(maize.completeness_score > bakery.completeness_score);
+        assert!(matches!(maize.overall_confidence, ConfidenceLevel::Low), "{:?}", maize.overall_confidence);
+        assert!(matches!(bakery.overall_confidence, ConfidenceLevel::VeryLow), "{:?}", bakery.overall_confidence);
+        assert!(maize.data_source_mix.iter().any(|s| s.source_type == DataSource::CountrySpecific(Country::Ghana)));
+
+        let default_warning = |dq: &DataQuality| dq.warnings.iter().any(|w| w.contains("rely on default estimates"));
+        assert!(!default_warning(&maize));
+        assert!(default_warning(&bakery), "{:?}", bakery.warnings);
+    }
+
+    #[test]
+    fn capacity_thresholds_are_exclusive_upper_bounds() {
+        let mill = |t| CapacityRange::from_capacity(t, &ProcessingFacilityType::Mill);
+        assert_eq!(mill(9.99), CapacityRange::Small);
+        assert_eq!(mill(10.0), CapacityRange::Medium);
+        assert_eq!(mill(99.9), CapacityRange::Medium);
+        assert_eq!(mill(100.0), CapacityRange::Large);
+        assert_eq!(mill(1000.0), CapacityRange::VeryLarge);
+
+        // Smaller batch sectors reach each class at lower tonnages
+        assert_eq!(CapacityRange::from_capacity(1.0, &ProcessingFacilityType::Bakery), CapacityRange::Medium);
+        assert_eq!(CapacityRange::from_capacity(4.9, &ProcessingFacilityType::CassivaProcessing), CapacityRange::Small);
+        assert_eq!(CapacityRange::from_capacity(20.0, &ProcessingFacilityType::DairyProcessing), CapacityRange::Large);
+    }
+
+    #[test]
+    fn mill_size_selects_its_factor_set() {
+        let factor = |category: &str, value_per_tonne, capacity_range, source: &str| ProcessingImpactFactor {
+            facility_type: ProcessingFacilityType::Mill,
+            product_type: ProductType::FlourMaize,
+            country: Country::Ghana,
+            impact_category: category.to_string(),
+            value_per_tonne,
+            unit: "kg".to_string(),
+            confidence: ConfidenceLevel::Medium,
+            source: source.to_string(),
+            year: 2024,
+            uncertainty_range: (0.0, 0.0),
+            pedigree_score: PedigreeScore {
+                reliability: 2,
+                completeness: 2,
+                temporal_correlation: 1,
+                geographical_correlation: 1,
+                technological_correlation: 2,
+            },
+            capacity_range,
+        };
+        let mut engine = engine();
+        engine.load_impact_factors(vec![
+            factor("Air pollution", 2.0, Some(CapacityRange::Small), "Plate mill survey"),
+            factor("Air pollution", 0.5, Some(CapacityRange::Large), "Roller mill survey"),
+            factor("Land use", 0.1, None, "Any mill"),
+        ]);
+        let mut loader = crate::processing::data::ProcessingDataLoader::new();
+        loader.load_default_factors().unwrap();
+        engine.load_benchmarks(loader.get_benchmarks().clone());
+
+        let run = |capacity: f64| {
+            let mut product = crude_palm_oil();
+            product.name = "Maize flour".to_string();
+            product.product_type = ProductType::FlourMaize;
+            let mut mill = assessment(ProcessingFacilityType::Mill, operations(WastewaterTreatment::None), vec![product]);
+            mill.facility_profile.processing_capacity = capacity;
+            engine.perform_processing_assessment(&mut mill).unwrap();
+            mill.results.unwrap()
+        };
+        let small = run(5.0);
+        let large = run(500.0);
+
+        let sources = |results: &LCAResults, category: &str| results.breakdown_by_food.values()
+            .flat_map(|impacts| impacts[category].contributing_sources.clone())
+            .collect::<Vec<_>>();
+        assert!(sources(&small, "Air pollution").contains(&"Plate mill survey".to_string()));
+        assert!(sources(&large, "Air pollution").contains(&"Roller mill survey".to_string()));
+        assert!(sources(&small, "Land use").contains(&"Any mill".to_string()));
+        assert!(sources(&large, "Land use").contains(&"Any mill".to_string()));
+
+        assert_eq!(small.capacity_range, Some(CapacityRange::Small));
+        assert_eq!(large.capacity_range, Some(CapacityRange::Large));
+        // Ghana benchmarks exist for small and medium mills only
+        assert!(small.benchmarking.is_some_and(|b| b.farm_type_comparison.contains_key("Energy consumption")));
+        assert!(large.benchmarking.is_none());
+    }
+
+    /// Crude palm oil mill whose steps and overhead estimate 130,000 kWh/year
+    /// (100 kWh/t × 1000 t + 30,000 kWh overhead)
+    fn metered_mill(monthly_kwh: Option<f64>, step_intensity: f64) -> ProcessingAssessment {
+        let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+        ops.energy_management.monthly_electricity_consumption = monthly_kwh;
+        ops.overhead.energy_kwh_per_year = Some(30_000.0);
+        let mut product = crude_palm_oil();
+        product.processing_steps[0].energy_intensity = step_intensity;
+        let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![product]);
+        engine().perform_processing_assessment(&mut mill).unwrap();
+        mill
+    }
+
+    #[test]
+    fn payback_follows_tariffs_and_capex_table() {
+        let loader = ProcessingDataLoader::new();
+        let mut engine = engine();
+        engine.load_capex_table(loader.capex_table.clone());
+
+        let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+        ops.tariffs = UtilityTariffs { electricity_per_kwh: Some(2.0), diesel_per_litre: None, water_per_m3: Some(10.0) };
+        let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+        engine.perform_processing_assessment(&mut mill).unwrap();
+        let results = mill.results.as_ref().unwrap();
+        // The table is in USD, converted at the assessment's rates
+        let capex = |category| loader.get_capex(&capex_key(&category, &mill.facility_profile.capacity_range())).unwrap() * 15.0;
+        let recommendation = |title: &str| results.recommendations.as_ref().unwrap().iter()
+            .find(|r| r.title.starts_with(title))
+            .unwrap_or_else(|| panic!("no recommendation starting with {title}"))
+            .clone();
+
+        // 10,000 kWh/month metered at GH₵2/kWh; efficient equipment saves 25% of it
+        let efficiency = recommendation("Implement energy-efficient equipment");
+        let savings = 0.25 * 120_000.0 * 2.0;
+        assert!((efficiency.estimated_annual_savings.unwrap() - savings).abs() < 1e-6);
+        let payback = capex(ProcessingRecommendationCategory::EnergyEfficiency) / savings * 12.0;
+        assert!((efficiency.payback_months.unwrap() - payback).abs() < 1e-6);
+
+        // Recycling 60% of gross process water leaves 40% of it to buy at GH₵10/m3
+        let gross = results.midpoint_impacts["Gross process water use"].value;
+        let recycling = recommendation("Raise process water recycling");
+        let savings = gross * 0.6 * 10.0;
+        assert!((recycling.estimated_annual_savings.unwrap() - savings).abs() < 1e-6);
+        let payback = capex(ProcessingRecommendationCategory::WaterConservation) / savings * 12.0;
+        assert!((recycling.payback_months.unwrap() - payback).abs() < 1e-6);
+
+        // Without tariffs nothing is costed
+        let mut uncosted = assessment(ProcessingFacilityType::PalmOilMill, operations(WastewaterTreatment::BiologicalTreatment), vec![crude_palm_oil()]);
+        engine.perform_processing_assessment(&mut uncosted).unwrap();
+        assert!(uncosted.results.unwrap().recommendations.unwrap().iter()
+            .all(|r| r.estimated_annual_savings.is_none() && r.payback_months.is_none()));
+
+        // At a weaker cedi the same equipment takes longer to pay back
+        let mut weaker_cedi = ProcessingAssessment {
+            exchange_rates: ExchangeRates { as_of: "2025-06-30".to_string(), ghs_per_usd: 30.0, ..ExchangeRates::default() },
+            results: None,
+            ..mill.clone()
+        };
+        engine.perform_processing_assessment(&mut weaker_cedi).unwrap();
+        let weaker_efficiency = weaker_cedi.results.unwrap().recommendations.unwrap().into_iter()
+            .find(|r| r.title == efficiency.title).unwrap();
+        assert!((weaker_efficiency.payback_months.unwrap() - 2.0 * efficiency.payback_months.unwrap()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn lower_energy_intensity_than_last_year_shows_as_improved() {
+        let last_year = metered_mill(None, 150.0);
+        let mut this_year = metered_mill(None, 100.0);
+        this_year.processing_operations.energy_management.energy_efficiency_measures = vec!["Variable speed drives".to_string()];
+        engine().perform_processing_assessment(&mut this_year).unwrap();
+
+        let trend = engine().analyze_trends(&this_year, &last_year).unwrap();
+        let energy = trend.category_trends.iter().find(|t| t.impact_category == "Energy consumption").unwrap();
+        assert!(energy.change_percent < 0.0, "got {}", energy.change_percent);
+        assert_eq!(energy.status, TrendStatus::Improved);
+        let product = &trend.product_trends[0];
+        assert!(product.trends.iter().any(|t| t.impact_category == "Energy consumption" && t.change_percent < 0.0));
+        assert!(!trend.regressed_categories.contains(&"Energy consumption".to_string()));
+
+        let measure = &trend.implemented_measures[0];
+        assert_eq!(measure.impact_category, "Energy consumption");
+        assert!(measure.realized_savings_percent > 0.0);
+
+        // Reversed, the same change is a regression beyond the 10% threshold
+        let reversed = engine().analyze_trends(&last_year, &this_year).unwrap();
+        assert!(reversed.regressed_categories.contains(&"Energy consumption".to_string()));
+    }
+
+    #[test]
+    fn grid_factor_follows_loaded_country_and_region_data() {
+        let mut engine = engine();
+        engine.load_regional_factors(crate::processing::data::ProcessingDataLoader::new().regional_factors);
+        let mill = |country: Country, region: Option<&str>, ops: ProcessingOperations| {
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+            mill.country = country;
+            mill.region = region.map(str::to_string);
+            engine.perform_processing_assessment(&mut mill).unwrap();
+            mill.results.unwrap().midpoint_impacts["Global warming"].clone()
+        };
+        let grid_factor = |country: Country, region: Option<&str>, ops: &ProcessingOperations| {
+            engine.reconcile_energy(&[crude_palm_oil()], &facility(ProcessingFacilityType::PalmOilMill),
+                ops, &country, &region.map(str::to_string)).grid_factor.kg_co2e_per_kwh
+        };
+        let metered = operations(WastewaterTreatment::BiologicalTreatment);
+
+        // Same metered kWh, different loaded grid factors
+        assert_eq!(grid_factor(Country::Ghana, None, &metered), 0.45);
+        assert_eq!(grid_factor(Country::Nigeria, None, &metered), 0.52);
+        assert_eq!(grid_factor(Country::Nigeria, Some("Lagos"), &metered), 0.47);
+        let ghana = mill(Country::Ghana, None, metered.clone());
+        let nigeria = mill(Country::Nigeria, None, metered.clone());
+        assert!(nigeria.value > ghana.value);
+        assert!(nigeria.contributing_sources.iter().any(|s| s.contains("Nigeria_grid_emission_factor")));
+
+        // A facility-specific factor equal to Nigeria's closes the gap and is credited
+        let mut supplier = metered.clone();
+        supplier.energy_management.grid_emission_factor = Some(ElectricityEmissionFactor {
+            kg_co2e_per_kwh: 0.52,
+            source: "ECG supply contract 2024".to_string(),
+        });
+        let ghana_supplier = mill(Country::Ghana, None, supplier);
+        assert!((ghana_supplier.value - nigeria.value).abs() < 1e-9 * nigeria.value);
+        assert!(ghana_supplier.contributing_sources.iter().any(|s| s.contains("ECG supply contract 2024")));
+
+        // 480 of 4800 operating hours on diesel backup
+        let mut outages = metered.clone();
+        outages.energy_management.backup_generator_hours_per_year = Some(480.0);
+        let blended = 0.9 * 0.45 + 0.1 * EnergySource::DieselGenerator.emission_factor(&Country::Ghana);
+        assert!((grid_factor(Country::Ghana, None, &outages) - blended).abs() < 1e-12);
+
+        let mut captive = metered;
+        captive.energy_management.captive_generation = true;
+        assert_eq!(grid_factor(Country::Nigeria, Some("Lagos"), &captive), 0.58);
+    }
+
+    #[test]
+    fn sun_dried_cassava_flour_uses_less_energy_than_electric_drying() {
+        let flour = |method: DryingMethod| {
+            let mut product = crude_palm_oil();
+            product.name = "Cassava flour".to_string();
+            product.product_type = ProductType::FlourCassava;
+            product.processing_steps.push(ProcessingStep {
+                step_name: "Drying".to_string(),
+                energy_intensity: 0.0, // not measured: the dryer's typical demand applies
+                water_usage: 0.0,
+                duration: 8.0,
+                yield_efficiency: 95.0,
+                emissions_factor: None,
+                drying_method: Some(method),
+            });
+            let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+            ops.energy_management.monthly_electricity_consumption = None;
+            let mut plant = assessment(ProcessingFacilityType::CassivaProcessing, ops, vec![product]);
+            engine().perform_processing_assessment(&mut plant).unwrap();
+            plant.results.unwrap().midpoint_impacts
+        };
+
+        let sun = flour(DryingMethod::Sun);
+        let electric = flour(DryingMethod::Electric);
+        let diesel = flour(DryingMethod::Diesel);
+        assert!(sun["Energy consumption"].value < electric["Energy consumption"].value);
+        assert!(sun["Global warming"].value < electric["Global warming"].value);
+        // Diesel heat counts as energy and carries its own combustion emissions
+        assert!(diesel["Energy consumption"].value > sun["Energy consumption"].value);
+        assert!(diesel["Global warming"].value > sun["Global warming"].value);
+    }
+
+    #[test]
+    fn rice_husks_as_boiler_fuel_turn_into_a_credit() {
+        let mill_with_husks = |disposal: WasteDisposalMethod| {
+            let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+            ops.waste_management.waste_streams = vec![
+                WasteStream {
+                    stream_name: "Rice husks".to_string(),
+                    tonnes_per_year: 400.0,
+                    composition: WasteComposition::CropResidue,
+                    disposal_method: disposal,
+                },
+                WasteStream {
+                    stream_name: "Sack offcuts".to_string(),
+                    tonnes_per_year: 2.0,
+                    composition: WasteComposition::Plastic,
+                    disposal_method: WasteDisposalMethod::Landfill,
+                },
+            ];
+            let mut mill = assessment(ProcessingFacilityType::RiceProcessing, ops, vec![crude_palm_oil()]);
+            engine().perform_processing_assessment(&mut mill).unwrap();
+            mill
+        };
+        let husks_gwp = |mill: &ProcessingAssessment| engine()
+            .waste_stream_impacts(&mill.processing_operations.waste_management, &Country::Ghana, &ElectricityEmissionFactor {
+                kg_co2e_per_kwh: 0.45,
+                source: "test".to_string(),
+            })[0].gwp_kg_co2e;
+
+        let burned = mill_with_husks(WasteDisposalMethod::OpenBurning);
+        let fuel = mill_with_husks(WasteDisposalMethod::EnergyRecovery);
+        assert!(husks_gwp(&burned) > 0.0);
+        assert!(husks_gwp(&fuel) < 0.0);
+
+        let results = |mill: &ProcessingAssessment| mill.results.clone().unwrap();
+        assert!(results(&fuel).midpoint_impacts["Global warming"].value < results(&burned).midpoint_impacts["Global warming"].value);
+        assert!(results(&burned).midpoint_impacts["Global warming"].contributing_sources.iter()
+            .any(|s| s.starts_with("Waste stream Rice husks")));
+        // Both streams count towards waste generated, whatever their fate
+        let waste = &results(&fuel).midpoint_impacts["Solid waste generation"];
+        assert!(waste.contributing_sources.iter().any(|s| s.starts_with("Sack offcuts")));
+
+        // The recommendation goes after the burned husks, not the plastic
+        let recommendations = results(&burned).recommendations.unwrap();
+        assert!(recommendations.iter().any(|r| r.title == "Divert Rice husks from OpenBurning to EnergyRecovery"));
+    }
+
+    #[test]
+    fn overhead_is_added_once_and_allocated_by_production() {
+        let gross_water = |mill: &ProcessingAssessment| mill.results.as_ref().unwrap().midpoint_impacts["Gross process water use"].value;
+        let energy_use = |mill: &ProcessingAssessment| mill.results.as_ref().unwrap().midpoint_impacts["Energy consumption"].value;
+        let run = |overhead: FacilityOverhead| {
+            let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+            ops.energy_management.monthly_electricity_consumption = None;
+            ops.overhead = overhead;
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+            mill.facility_profile.location_type = LocationType::Urban; // no location adjustment
+            engine().perform_processing_assessment(&mut mill).unwrap();
+            mill
+        };
+
+        // Entered: 100 kWh/t × 1000 t + 30,000 kWh; 3.5 m3/t × 1000 t + 500 m3
+        let entered = run(FacilityOverhead { energy_kwh_per_year: Some(30_000.0), water_m3_per_year: Some(500.0), legacy_multipliers: false });
+        assert!((energy_use(&entered) - 130_000.0).abs() < 1e-6, "got {}", energy_use(&entered));
+        assert!((gross_water(&entered) - 4_000.0).abs() < 1e-6, "got {}", gross_water(&entered));
+
+        // Not entered: the default for a mill of this type and size
+        let defaulted = run(FacilityOverhead::default());
+        let mill = facility(ProcessingFacilityType::PalmOilMill);
+        let (kwh, m3) = FacilityOverhead::default_for(&mill.facility_type, &mill.capacity_range());
+        assert!((energy_use(&defaulted) - (100_000.0 + kwh)).abs() < 1e-6);
+        assert!((gross_water(&defaulted) - (3_500.0 + m3)).abs() < 1e-6);
+
+        // The deprecated multipliers still reproduce earlier results
+        let legacy = run(FacilityOverhead { legacy_multipliers: true, ..FacilityOverhead::default() });
+        assert!((energy_use(&legacy) - 130_000.0).abs() < 1e-6);
+        assert!((gross_water(&legacy) - 4_550.0).abs() < 1e-6);
+
+        // Two products split the overhead by production, not by step energy
+        let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+        ops.energy_management.monthly_electricity_consumption = None;
+        ops.overhead.energy_kwh_per_year = Some(30_000.0);
+        let mut kernel_oil = crude_palm_oil();
+        kernel_oil.name = "Palm kernel oil".to_string();
+        kernel_oil.annual_production = 500.0;
+        kernel_oil.processing_steps[0].energy_intensity = 0.0;
+        let mut two_products = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil(), kernel_oil]);
+        two_products.facility_profile.location_type = LocationType::Urban;
+        engine().perform_processing_assessment(&mut two_products).unwrap();
+        let breakdown = &two_products.results.unwrap().breakdown_by_food;
+        assert!((breakdown["Palm kernel oil (500 tonnes/year)"]["Energy consumption"].value - 10_000.0).abs() < 1e-6);
+        assert!((breakdown["Crude palm oil (1000 tonnes/year)"]["Energy consumption"].value - 120_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn co_products_split_overhead_instead_of_each_paying_it() {
+        let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+        ops.energy_management.monthly_electricity_consumption = None;
+        ops.overhead.energy_kwh_per_year = Some(30_000.0);
+        ops.overhead.water_m3_per_year = Some(1_000.0);
+        let half = |name: &str| ProcessedProduct { name: name.to_string(), annual_production: 500.0, ..crude_palm_oil() };
+        let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![half("Crude palm oil"), half("Red palm oil")]);
+        mill.facility_profile.location_type = LocationType::Urban;
+        engine().perform_processing_assessment(&mut mill).unwrap();
+
+        let results = mill.results.unwrap();
+        for category in ["Energy consumption", "Gross process water use"] {
+            let products: Vec<f64> = results.breakdown_by_food.values().map(|impacts| impacts[category].value).collect();
+            assert_eq!(products.len(), 2);
+            // Per product: 500 t of steps plus half the overhead; together, the facility total
+            let expected = if category == "Energy consumption" { 100.0 * 500.0 + 15_000.0 } else { 3.5 * 500.0 + 500.0 };
+            assert!(products.iter().all(|v| (v - expected).abs() < 1e-6), "{category}: {products:?}");
+            assert!((products.iter().sum::<f64>() - results.midpoint_impacts[category].value).abs() < 1e-6);
+        }
+    }
+
+    fn energy_warnings(mill: &ProcessingAssessment) -> Vec<String> {
+        mill.results.as_ref().unwrap().data_quality.warnings.iter()
+            .filter(|w| w.contains("Metered electricity"))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn agreeing_meter_and_steps_raise_no_warning() {
+        let mill = metered_mill(Some(130_000.0 / 12.0), 100.0);
+        let energy = &mill.results.as_ref().unwrap().midpoint_impacts["Energy consumption"];
+
+        assert!((energy.value - 130_000.0).abs() < 1e-6, "got {}", energy.value);
+        assert!(energy_warnings(&mill).is_empty());
+    }
+
+    #[test]
+    fn metered_total_wins_and_steps_are_rescaled() {
+        // Bill says 390,000 kWh/year - three times the step estimate
+        let mill = metered_mill(Some(32_500.0), 100.0);
+        let energy = &mill.results.as_ref().unwrap().midpoint_impacts["Energy consumption"];
+
+        assert!((energy.value - 390_000.0).abs() < 1e-6, "got {}", energy.value);
+        assert!(energy.contributing_sources.iter().any(|s| s.contains("scaled by 3.00")));
+        assert!(energy.contributing_sources.iter().any(|s| s == "Sterilization and pressing: 300000 kWh"));
+        assert!(energy.contributing_sources.iter().any(|s| s.starts_with("Facility overhead: 90000 kWh")));
+
+        let warnings = energy_warnings(&mill);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("by 67%"), "{}", warnings[0]);
+    }
+
+    #[test]
+    fn single_energy_source_is_named() {
+        let metered_only = metered_mill(Some(10_000.0), 0.0);
+        let energy = &metered_only.results.as_ref().unwrap().midpoint_impacts["Energy consumption"];
+        assert!((energy.value - 120_000.0).abs() < 1e-6, "got {}", energy.value);
+        assert!(energy.contributing_sources.iter().any(|s| s.contains("no step intensities")));
+
+        let steps_only = metered_mill(None, 100.0);
+        let energy = &steps_only.results.as_ref().unwrap().midpoint_impacts["Energy consumption"];
+        assert!(energy.contributing_sources.iter().any(|s| s.contains("no metered consumption")));
+        assert!(energy_warnings(&metered_only).is_empty() && energy_warnings(&steps_only).is_empty());
+    }
+
+    #[test]
+    fn night_shifts_get_less_solar_credit() {
+        let run = |daytime: Option<f64>| {
+            let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+            ops.energy_management.renewable_energy_percentage = 60.0;
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+            mill.facility_profile.daytime_operation_fraction = daytime;
+            engine().perform_processing_assessment(&mut mill).map(|_| mill)
+        };
+        let gwp = |mill: &ProcessingAssessment| mill.results.as_ref().unwrap().midpoint_impacts["Global warming"].value;
+
+        let flat = run(None).unwrap();
+        let daytime_only = run(Some(1.0)).unwrap();
+        let night_shift = run(Some(0.5)).unwrap();
+        assert!((gwp(&daytime_only) - gwp(&flat)).abs() < 1e-9 * gwp(&flat));
+        assert!(gwp(&night_shift) > gwp(&daytime_only));
+
+        // Half the consumption at night halves the credit: 1 - 0.6 * 0.8 against 1 - 0.3 * 0.8
+        let mill = facility(ProcessingFacilityType::PalmOilMill);
+        let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+        ops.energy_management.renewable_energy_percentage = 60.0;
+        let daytime = engine().apply_facility_adjustments(100.0, &mill, &ops, "Energy consumption");
+        let night = engine().apply_facility_adjustments(100.0, &ProcessingFacilityProfile { daytime_operation_fraction: Some(0.5), ..mill }, &ops, "Energy consumption");
+        assert!((night / daytime - 0.76 / 0.52).abs() < 1e-9);
+
+        let error = run(Some(1.5)).unwrap_err().to_string();
+        assert!(error.contains("daytime_operation_fraction must be between 0 and 1"), "{error}");
+    }
+
+    #[test]
+    fn renewable_percentage_is_bounded() {
+        let run = |renewable: f64| {
+            let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+            ops.energy_management.renewable_energy_percentage = renewable;
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+            engine().perform_processing_assessment(&mut mill).map(|_| mill)
+        };
+        let gwp = |mill: &ProcessingAssessment| mill.results.as_ref().unwrap().midpoint_impacts["Global warming"].value;
+
+        let none = run(0.0).unwrap();
+        let all = run(100.0).unwrap();
+        assert!(gwp(&all) > 0.0 && gwp(&all) < gwp(&none));
+
+        let error = run(150.0).unwrap_err().to_string();
+        assert!(error.contains("renewable_energy_percentage must be between 0 and 100, got 150"), "{error}");
+
+        // Used directly, an out-of-range share is clamped rather than producing a negative multiplier
+        let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+        ops.energy_management.renewable_energy_percentage = 150.0;
+        ops.waste_management.organic_waste_percentage = 150.0;
+        let mill = facility(ProcessingFacilityType::PalmOilMill);
+        for category in ["Global warming", "Energy consumption"] {
+            assert!(engine().apply_facility_adjustments(100.0, &mill, &ops, category) > 0.0);
+        }
+        assert_eq!(ops.waste_management.organic_fraction(), 1.0);
+    }
+
+    #[test]
+    fn recycling_cuts_net_withdrawal_but_not_gross_use() {
+        let run = |recycled: f64| {
+            let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+            ops.water_management.recycled_water_percentage = recycled;
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+            engine().perform_processing_assessment(&mut mill).unwrap();
+            mill.results.unwrap()
+        };
+        let once_through = run(0.0);
+        let recycling = run(60.0);
+        let value = |results: &LCAResults, category: &str| results.midpoint_impacts[category].value;
+
+        let gross = value(&once_through, "Gross process water use");
+        assert!(gross > 500.0, "got {gross}");
+        assert_eq!(value(&recycling, "Gross process water use"), gross);
+        assert!((value(&once_through, "Water consumption") - gross).abs() < 1e-6);
+        assert!((value(&recycling, "Water consumption") - 0.4 * gross).abs() < 1e-6);
+        assert!((value(&recycling, "Water scarcity") - 0.4 * value(&once_through, "Water scarcity")).abs() < 1e-6);
+
+        let saving = |results: &LCAResults| results.recommendations.as_ref().unwrap().iter()
+            .find(|r| r.title.contains("recycling from"))
+            .map(|r| r.potential_impact_reduction["Water consumption"]);
+        assert!((saving(&once_through).unwrap() - 60.0).abs() < 1e-6);
+        assert!(saving(&recycling).is_none());
+
+        // Rainwater can't push withdrawal below the make-up minimum
+        let water = WaterManagement { rainwater_harvesting_m3_per_year: 10_000.0, ..operations(WastewaterTreatment::None).water_management };
+        assert_eq!(water.net_freshwater_withdrawal(gross, 10_000.0), gross * WaterManagement::MIN_MAKEUP_FRACTION);
+    }
+
+    #[test]
+    fn step_water_in_litres_per_tonne_is_counted_in_m3() {
+        // 3500 L/t over 1000 t is 3500 m3, not 3.5 million
+        let overhead = OverheadAllocation { water_m3: 400.0, total_production: 4000.0, ..OverheadAllocation::default() };
+        assert_eq!(engine().water_split(&crude_palm_oil(), &overhead), (3500.0, 100.0));
+
+        let legacy = OverheadAllocation { legacy_energy_factor: Some(1.3), ..overhead };
+        assert_eq!(engine().water_split(&crude_palm_oil(), &legacy), (3500.0, 1050.0));
+    }
+
+    #[test]
+    fn waste_in_tonnes_per_year_is_counted_in_kg() {
+        let mut ops = operations(WastewaterTreatment::None);
+        ops.waste_management.waste_streams = vec![WasteStream {
+            stream_name: "Empty fruit bunches".to_string(),
+            tonnes_per_year: 2.5,
+            composition: WasteComposition::CropResidue,
+            disposal_method: WasteDisposalMethod::Landfill,
+        }];
+        let palm_mill = facility(ProcessingFacilityType::PalmOilMill);
+        let product = ProcessedProduct { annual_production: palm_mill.annual_throughput_capacity(), ..crude_palm_oil() };
+        assert_eq!(engine().calculate_waste_impact(&product, &palm_mill, &ops, product.annual_production), 2500.0);
+
+        let waste = WasteManagement { waste_streams: vec![], solid_waste_generation: Some(40.0), ..ops.waste_management };
+        let days = palm_mill.annual_operating_days();
+        assert_eq!(engine().calculate_waste_impact(&product, &palm_mill, &ProcessingOperations { waste_management: waste, ..ops }, product.annual_production), 40.0 * days);
+    }
+
+    fn grid_solar_mix(solar_percent: f64) -> Vec<EnergyShare> {
+        vec![
+            EnergyShare { source: EnergySource::GridElectricity, percentage: 100.0 - solar_percent, metered_kwh: None },
+            EnergyShare { source: EnergySource::SolarPower, percentage: solar_percent, metered_kwh: None },
+        ]
+    }
+
+    #[test]
+    fn rising_solar_share_lowers_gwp() {
+        let gwp: Vec<f64> = [0.0, 25.0, 50.0, 75.0, 100.0].iter()
+            .map(|&solar| {
+                let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+                ops.energy_management.energy_mix = grid_solar_mix(solar);
+                let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+                engine().perform_processing_assessment(&mut mill).unwrap();
+                mill.results.unwrap().midpoint_impacts["Global warming"].value
+            })
+            .collect();
+
+        assert!(gwp.windows(2).all(|pair| pair[1] < pair[0]), "{gwp:?}");
+    }
+
+    #[test]
+    fn selected_categories_keep_the_water_they_are_derived_from() {
+        let water_only = LCAMethodology { impact_categories: Some(vec!["Water scarcity".to_string()]), ..methodology() };
+        let run = |engine: &ProcessingLCAEngine| {
+            let mut facility = assessment(ProcessingFacilityType::PalmOilMill, operations(WastewaterTreatment::None), vec![crude_palm_oil()]);
+            engine.perform_processing_assessment(&mut facility).unwrap();
+            facility.results.unwrap()
+        };
+        let full = run(&engine());
+        let mut selected_engine = engine();
+        selected_engine.set_methodology(water_only);
+        let selected = run(&selected_engine);
+
+        assert_eq!(selected.midpoint_impacts.keys().collect::<Vec<_>>(), ["Water scarcity"]);
+        assert_eq!(selected.midpoint_impacts["Water scarcity"].value, full.midpoint_impacts["Water scarcity"].value);
+        assert_eq!(selected.endpoint_impacts.keys().collect::<Vec<_>>(), ["Resource Scarcity"]);
+        assert_eq!(selected.single_score.weighting_factors["Resource Scarcity"], 1.0);
+    }
+
+    #[test]
+    fn equal_burdens_per_kg_score_alike_on_farms_and_facilities() {
+        let per_kg = [("Human Health", 0.02, "DALY"), ("Ecosystem Quality", 3.0e-9, "species.yr"), ("Resource Scarcity", 6.0e3, "USD")];
+        let endpoints = |scale: f64, reference: fn(&str) -> Option<f64>| per_kg.iter()
+            .map(|&(name, value, unit)| (name.to_string(), EndpointResult {
+                value: value * scale,
+                unit: unit.to_string(),
+                uncertainty_range: (value * scale * 0.5, value * scale * 2.0),
+                normalization_factor: reference(name),
+                regional_adaptation_factor: None,
+                contributions: BTreeMap::new(),
+                metadata: None,
+            }))
+            .collect::<BTreeMap<_, _>>();
+
+        let farm_engine = crate::production::AfricanLCAEngine::new(LCAMethodology::african_production());
+        let farm = farm_engine.calculate_enhanced_single_score(&endpoints(1.0, african_reference_per_kg), &Country::Ghana).unwrap();
+        // A year of 250 t at the same burden per kg
+        let facility = engine()
+            .calculate_processing_single_score(&endpoints(250.0 * 1000.0, per_tonne_reference), 250.0, &Country::Ghana)
+            .unwrap();
+
+        assert!(farm.value > 0.0 && farm.value < 1.0, "{}", farm.value);
+        assert!((facility.value - farm.value).abs() < 1e-12, "{} vs {}", facility.value, farm.value);
+        assert!((facility.uncertainty_range.1 - farm.uncertainty_range.1).abs() < 1e-12);
+        assert_eq!((&facility.unit, &facility.weighting_factors), (&farm.unit, &farm.weighting_factors));
+        assert_eq!(facility.interpretation.unwrap().band, farm.interpretation.unwrap().band);
+
+        // Assessed facilities report all three endpoints and the basis of their score
+        let mut mill = assessment(ProcessingFacilityType::PalmOilMill, operations(WastewaterTreatment::None), vec![crude_palm_oil()]);
+        engine().perform_processing_assessment(&mut mill).unwrap();
+        let results = mill.results.unwrap();
+        assert_eq!(results.endpoint_impacts.keys().collect::<Vec<_>>(), ["Ecosystem Quality", "Human Health", "Resource Scarcity"]);
+        assert!(results.single_score.methodology.contains("AfricanContext (per tonne of product"), "{}", results.single_score.methodology);
+    }
+
+    #[test]
+    fn consequential_modeling_raises_the_benefit_of_solar() {
+        let solar_saving = |approach: ModelingApproach| {
+            let engine = ProcessingLCAEngine::new(LCAMethodology { modeling_approach: approach, ..methodology() });
+            let gwp = |solar: f64| {
+                let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+                ops.energy_management.energy_mix = grid_solar_mix(solar);
+                let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+                mill.country = Country::Nigeria;
+                engine.perform_processing_assessment(&mut mill).unwrap();
+                mill.results.unwrap().midpoint_impacts["Global warming"].value
+            };
+            gwp(0.0) - gwp(40.0)
+        };
+
+        // Solar displaces gas peakers at the margin, not the average Nigerian grid
+        let attributional = solar_saving(ModelingApproach::Attributional);
+        let consequential = solar_saving(ModelingApproach::Consequential);
+        let marginal_to_average = (EnergySource::marginal_grid_emission_factor(&Country::Nigeria) - 0.05)
+            / (EnergySource::GridElectricity.emission_factor(&Country::Nigeria) - 0.05);
+        assert!(attributional > 0.0);
+        assert!((consequential / attributional - marginal_to_average).abs() < 1e-6,
+                "{consequential} vs {attributional}");
+    }
+
+    #[test]
+    fn consequential_credits_byproducts_instead_of_allocating() {
+        let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+        // Metered supply, so only the byproduct treatment differs between approaches
+        ops.energy_management.grid_emission_factor = Some(ElectricityEmissionFactor {
+            kg_co2e_per_kwh: 0.45,
+            source: "utility bill".to_string(),
+        });
+        ops.waste_management.byproduct_utilization = vec![ByproductUtilization {
+            byproduct_name: "Palm kernel cake".to_string(),
+            utilization_method: "Animal feed".to_string(),
+            percentage_utilized: 50.0,
+        }];
+        let kernel_oil = ProcessedProduct { id: "pko".to_string(), name: "Palm kernel oil".to_string(), annual_production: 200.0, ..crude_palm_oil() };
+        let run = |approach: ModelingApproach| {
+            let engine = ProcessingLCAEngine::new(LCAMethodology { modeling_approach: approach, ..methodology() });
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops.clone(), vec![crude_palm_oil(), kernel_oil.clone()]);
+            engine.perform_processing_assessment(&mut mill).unwrap();
+            mill.results.unwrap()
+        };
+
+        let (attributional, consequential) = (run(ModelingApproach::Attributional), run(ModelingApproach::Consequential));
+        // 100 kg/day over 300 days, half fed in place of maize at 400 kg CO2-eq/t
+        let credit = 15.0 * 400.0;
+        let gwp = |results: &LCAResults| results.midpoint_impacts["Global warming"].value;
+        assert!((gwp(&attributional) - gwp(&consequential) - credit).abs() < 1e-6);
+        assert!(consequential.midpoint_impacts["Global warming"].contributing_sources.iter()
+            .any(|source| source.starts_with("System expansion credit for Palm kernel cake")));
+
+        // The determining product takes the whole credit; the co-product keeps only its own burdens
+        let footprints = consequential.product_footprints.clone().unwrap();
+        let own = |name: &str, tonnes: f64| consequential.breakdown_by_food.iter()
+            .find(|(key, _)| key.starts_with(name))
+            .map(|(_, results)| results["Global warming"].value / tonnes)
+            .unwrap();
+        assert!((footprints["Palm kernel oil"].per_tonne["Global warming"] - own("Palm kernel oil", 200.0)).abs() < 1e-9);
+        assert!((footprints["Crude palm oil"].per_tonne["Global warming"] - (own("Crude palm oil", 1000.0) - credit / 1000.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn energy_mix_is_validated_and_drives_renewable_share() {
+        let mut energy = operations(WastewaterTreatment::None).energy_management;
+        energy.energy_mix = grid_solar_mix(30.0);
+        assert!(energy.validate_energy_mix().is_ok());
+        assert!((energy.renewable_share_percent() - 30.0).abs() < 1e-9);
+
+        energy.energy_mix[0].percentage = 50.0;
+        assert!(energy.validate_energy_mix().is_err());
+    }
+
+    #[test]
+    fn five_month_season_bills_five_twelfths_of_annual_energy() {
+        let run = |operating_months: Option<Vec<u32>>| {
+            let mut mill = assessment(
+                ProcessingFacilityType::PalmOilMill,
+                operations(WastewaterTreatment::BiologicalTreatment),
+                vec![crude_palm_oil()]
+            );
+            mill.facility_profile.operating_months = operating_months;
+            engine().perform_processing_assessment(&mut mill).unwrap();
+            mill.results.unwrap().midpoint_impacts["Energy consumption"].value
+        };
+
+        let year_round = run(None);
+        let seasonal = run(Some(vec![11, 12, 1, 2, 3]));
+        assert!((seasonal - year_round * 5.0 / 12.0).abs() < 1e-6, "{seasonal} vs {year_round}");
+    }
+
+    #[test]
+    fn seasonal_raw_material_caps_feasible_throughput() {
+        let mut kernels = crude_palm_oil();
+        kernels.annual_production = 5000.0;
+        kernels.raw_material_inputs = vec![RawMaterialInput {
+            material_name: "Fresh fruit bunches".to_string(),
+            quantity_per_tonne_output: 5000.0,
+            source_location: None,
+            quality_requirements: vec![],
+            seasonal_availability: true,
+        }];
+
+        let mut mill = assessment(
+            ProcessingFacilityType::PalmOilMill,
+            operations(WastewaterTreatment::BiologicalTreatment),
+            vec![kernels]
+        );
+        // 20 t/day over 5 months (152 days) allows ~3,040 t
+        mill.facility_profile.operating_months = Some(vec![3, 4, 5, 6, 7]);
+        assert!((mill.facility_profile.annual_operating_days() - 365.0 * 5.0 / 12.0).abs() < 1e-9);
+
+        engine().perform_processing_assessment(&mut mill).unwrap();
+        let warnings = &mill.results.unwrap().data_quality.warnings;
+        assert!(warnings.iter().any(|w| w.contains("5 operating months") && w.contains("Crude palm oil")),
+                "{warnings:?}");
+    }
+
+    #[test]
+    fn climate_controlled_storage_raises_the_processed_products_footprint() {
+        let run = |climate_control: bool, storage_loss_percentage: f64| {
+            let mut ops = operations(WastewaterTreatment::None);
+            ops.raw_material_sourcing.storage_practices = StoragePractices {
+                storage_type: "Cold storage".to_string(),
+                climate_control,
+                pest_control_methods: vec![],
+                storage_loss_percentage,
+                storage_days: Some(60.0),
+            };
+            let mut oil = crude_palm_oil();
+            oil.raw_material_inputs = vec![RawMaterialInput {
+                material_name: "Fresh fruit bunches".to_string(),
+                quantity_per_tonne_output: 5000.0,
+                source_location: None,
+                quality_requirements: vec![],
+                seasonal_availability: false,
+            }];
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![oil]);
+            let mut engine = engine();
+            engine.add_upstream_burden("Fresh fruit bunches", UpstreamBurden {
+                per_kg: BTreeMap::from([("Global warming".to_string(), 0.1)]),
+                source: "cultivation (Test Estate)".to_string(),
+            });
+            engine.perform_processing_assessment(&mut mill).unwrap();
+            mill.results.unwrap()
+        };
+        let ambient = run(false, 0.0);
+        let cooled = run(true, 0.0);
+
+        // 5,000 t of fruit held 60 days at 0.3 kWh per tonne-day on the grid
+        let kwh = 5000.0 * 60.0 * 0.3;
+        let energy = |results: &LCAResults| results.midpoint_impacts["Energy consumption"].value;
+        let gwp = |results: &LCAResults| results.midpoint_impacts["Global warming"].value;
+        assert!((energy(&cooled) - energy(&ambient) - kwh).abs() < 1e-6 * kwh);
+        let grid_co2e = kwh * EnergySource::GridElectricity.emission_factor(&Country::Ghana);
+        assert!((gwp(&cooled) - gwp(&ambient) - grid_co2e).abs() < 1e-6 * grid_co2e);
+        let scope_3 = &cooled.ghg_scopes.as_ref().unwrap().scope_3;
+        assert!(scope_3.sources.iter().any(|source| source.source.starts_with("Upstream storage of Fresh fruit bunches")));
+
+        // A fifth lost in storage means a quarter more fruit bought, with its cultivation burden
+        let lossy = run(false, 20.0);
+        let cultivation = 5_000_000.0 * 0.1;
+        assert!((gwp(&lossy) - gwp(&ambient) - cultivation * 0.25).abs() < 1e-6 * cultivation);
+    }
+
+    #[test]
+    fn company_consolidates_sites_and_ranks_newer_equipment_first() {
+        let site = |name: &str, equipment_age: EquipmentAge| {
+            let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+            ops.equipment_efficiency.equipment_age = equipment_age;
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+            mill.facility_profile.facility_name = name.to_string();
+            mill
+        };
+        let mut company = CompanyAssessment {
+            id: uuid::Uuid::new_v4(),
+            company_name: "Test Processors".to_string(),
+            facilities: vec![site("Kumasi", EquipmentAge::VeryOld), site("Takoradi", EquipmentAge::New)],
+            assessment_date: chrono::Utc::now(),
+            results: None,
+        };
+        engine().perform_company_assessment(&mut company).unwrap();
+
+        let results = company.results.as_ref().unwrap();
+        assert_eq!(results.total_production, 2000.0);
+        for (category, total) in &results.midpoint_impacts {
+            let sum: f64 = company.facilities.iter()
+                .map(|f| f.results.as_ref().unwrap().midpoint_impacts[category].value)
+                .sum();
+            assert!((total.value - sum).abs() <= 1e-9 * sum.abs().max(1.0), "{category}");
+        }
+
+        let gwp = results.site_comparisons.iter()
+            .find(|c| c.impact_category == "Global warming")
+            .unwrap();
+        assert_eq!(gwp.rankings[0].facility_name, "Takoradi");
+        assert_eq!(gwp.rankings[1].rank, 2);
+        assert!(matches!(gwp.rankings[0].performance_category, PerformanceCategory::Excellent | PerformanceCategory::Good));
+        assert!(matches!(gwp.rankings[1].performance_category, PerformanceCategory::BelowAverage | PerformanceCategory::Poor));
+    }
+
+    #[test]
+    fn ghg_scopes_add_up_and_grid_factor_moves_scope_2_alone() {
+        let run = |grid_factor: f64| {
+            let mut ops = operations(WastewaterTreatment::None);
+            ops.energy_management.grid_emission_factor = Some(ElectricityEmissionFactor {
+                kg_co2e_per_kwh: grid_factor,
+                source: "utility bill".to_string(),
+            });
+            let mut mill = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+            engine().perform_processing_assessment(&mut mill).unwrap();
+            mill.results.unwrap()
+        };
+        let (low, high) = (run(0.45), run(0.60));
+
+        for results in [&low, &high] {
+            let scopes = results.ghg_scopes.as_ref().unwrap();
+            let gwp = results.midpoint_impacts["Global warming"].value;
+            assert!((scopes.total() - gwp).abs() < 1e-9 * gwp, "{} vs {}", scopes.total(), gwp);
+            assert!(scopes.scope_1.sources.iter().any(|source| source.source.starts_with("Wastewater CH4")));
+            assert!(scopes.scope_2.sources.iter().any(|source| source.source == "Grid electricity"));
+            assert!(scopes.scope_3.sources.iter().any(|source| source.source == "Solid waste disposal (Composting)"));
+        }
+        let (low, high) = (low.ghg_scopes.unwrap(), high.ghg_scopes.unwrap());
+        assert_eq!(high.scope_1.kg_co2e, low.scope_1.kg_co2e);
+        assert_eq!(high.scope_3.kg_co2e, low.scope_3.kg_co2e);
+        assert!((high.scope_2.kg_co2e / low.scope_2.kg_co2e - 0.60 / 0.45).abs() < 1e-9);
+    }
+
+    #[test]
+    fn carbon_footprints_add_up_to_each_products_global_warming_per_tonne() {
+        let kernel_oil = ProcessedProduct { id: "pko".to_string(), name: "Palm kernel oil".to_string(), annual_production: 200.0, ..crude_palm_oil() };
+        let mut mill = assessment(ProcessingFacilityType::PalmOilMill, operations(WastewaterTreatment::None), vec![crude_palm_oil(), kernel_oil]);
+        engine().perform_processing_assessment(&mut mill).unwrap();
+        let results = mill.results.unwrap();
+
+        let product_footprints = results.product_footprints.unwrap();
+        let carbon_footprints = results.product_carbon_footprints.expect("carbon footprints");
+        for product in &mill.processed_products {
+            let pcf = &carbon_footprints[&product.id];
+            let gwp = product_footprints[&product.name].per_tonne["Global warming"];
+            let sum = pcf.fossil_gwp + pcf.biogenic_gwp + pcf.land_use_change_gwp;
+            assert!((sum - gwp).abs() < 1e-9 * gwp, "{}: {} vs {}", product.id, sum, gwp);
+            assert_eq!((pcf.declared_unit.as_str(), pcf.unit.as_str()), ("1 tonne", "kg CO2-eq per tonne"));
+            assert!(pcf.biogenic_gwp > 0.0, "untreated POME CH4 is biogenic");
+            assert!(pcf.boundary_statement.starts_with("Gate to gate"));
+        }
+    }
+
+    #[test]
+    fn anaerobic_digestion_can_make_a_facility_a_net_carbon_sink() {
+        let mut ops = operations(WastewaterTreatment::BiologicalTreatment);
+        ops.waste_management.waste_streams = vec![WasteStream {
+            stream_name: "Fruit rejects".to_string(),
+            tonnes_per_year: 20000.0,
+            composition: WasteComposition::FoodScraps,
+            disposal_method: WasteDisposalMethod::AnaerobicDigestion,
+        }];
+        let mut facility = assessment(ProcessingFacilityType::PalmOilMill, ops, vec![crude_palm_oil()]);
+        engine().perform_processing_assessment(&mut facility).unwrap();
+        let results = facility.results.unwrap();
+
+        let gwp = &results.midpoint_impacts["Global warming"];
+        assert!(gwp.value < 0.0, "{}", gwp.value);
+        assert!(gwp.uncertainty_range.0 <= gwp.value && gwp.value <= gwp.uncertainty_range.1, "{:?}", gwp.uncertainty_range);
+        assert!(gwp.contributing_sources.iter().any(|source| source.starts_with("Net credit")));
+        assert!(results.data_quality.warnings.iter().any(|warning| warning.starts_with("Global warming is a net credit")));
+        assert!(results.midpoint_impacts.iter()
+            .filter(|(category, _)| !allows_net_credit(category))
+            .all(|(_, result)| result.value >= 0.0));
+    }
+
+    #[test]
+    fn facility_targets_are_per_tonne() {
+        let mut mill = assessment(ProcessingFacilityType::PalmOilMill, operations(WastewaterTreatment::None), vec![crude_palm_oil()]);
+        engine().perform_processing_assessment(&mut mill).unwrap();
+        let per_tonne = mill.results.as_ref().unwrap().midpoint_impacts["Global warming"].value / 1000.0;
+        mill.check_targets(&HashMap::from([("Global warming".to_string(), per_tonne * 1.1)])).unwrap();
+
+        let compliance = mill.results.unwrap().target_compliance.unwrap();
+        assert_eq!(compliance.len(), 1);
+        assert!(compliance["Global warming"].meets_target);
+        assert_eq!(compliance["Global warming"].unit, "kg CO2-eq per tonne");
+    }
+}
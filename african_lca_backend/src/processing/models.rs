@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
+use crate::error::{EngineError, InputError};
 use chrono::{DateTime, Utc};
-use crate::models::{Country, LCAMethodology, LCAResults, ConfidenceLevel, PedigreeScore};
+use crate::models::{
+    Country, Currency, ExchangeRates, LCAMethodology, LCAResults, ConfidenceLevel, PedigreeScore,
+    MidpointResult, EndpointResult, SingleScoreResult, PerformanceCategory, round_to_uncertainty
+};
 
 // ======================================================================
 // PROCESSING FACILITY PROFILE
@@ -21,9 +25,61 @@ pub struct ProcessingFacilityProfile {
     pub employee_count: Option<u32>,
     pub facility_size: Option<f64>, // m2
     pub location_type: LocationType,
+    #[serde(default)]
+    pub operating_months: Option<Vec<u32>>, // 1 = January; None means year-round
+    #[serde(default)]
+    pub daytime_operation_fraction: Option<f64>, // share of energy use in daylight hours (0-1); None means no time-of-use profile
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ProcessingFacilityProfile {
+    /// Months per year the facility runs, from the declared schedule (12 when none is given)
+    pub fn months_in_operation(&self) -> f64 {
+        let mut months: Vec<u32> = self.operating_months.iter()
+            .flatten()
+            .copied()
+            .filter(|m| (1..=12).contains(m))
+            .collect();
+        months.sort_unstable();
+        months.dedup();
+
+        if months.is_empty() { 12.0 } else { months.len() as f64 }
+    }
+
+    /// Operating days per year, capped by the calendar days in the operating months
+    pub fn annual_operating_days(&self) -> f64 {
+        (self.operational_days_per_year as f64).min(self.months_in_operation() / 12.0 * 365.0)
+    }
+
+    /// Maximum throughput (tonnes/year) the declared capacity and schedule allow
+    pub fn annual_throughput_capacity(&self) -> f64 {
+        self.processing_capacity * self.annual_operating_days()
+    }
+
+    /// Share of consumption the renewable percentage can offset. Solar only covers daytime
+    /// use, so with a time-of-use profile night shifts stay on grid or diesel; without one
+    /// the renewable percentage applies to all consumption.
+    pub fn renewable_offset_share(&self) -> f64 {
+        self.daytime_operation_fraction.map_or(1.0, |fraction| fraction.clamp(0.0, 1.0))
+    }
+
+    pub fn validate_daytime_operation(&self) -> Result<(), InputError> {
+        match self.daytime_operation_fraction {
+            Some(fraction) if !(0.0..=1.0).contains(&fraction) => Err(InputError::invalid(
+                "facility_profile.daytime_operation_fraction",
+                format!("daytime_operation_fraction must be between 0 and 1, got {}", fraction)
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Size class of the facility, used for benchmarks and scale-specific factors
+    pub fn capacity_range(&self) -> CapacityRange {
+        CapacityRange::from_capacity(self.processing_capacity, &self.facility_type)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
 pub enum ProcessingFacilityType {
     Mill,
     Bakery,
@@ -39,7 +95,7 @@ pub enum ProcessingFacilityType {
     General,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LocationType {
     Urban,
     PeriUrban,
@@ -58,6 +114,93 @@ pub struct ProcessingOperations {
     pub waste_management: WasteManagement,
     pub raw_material_sourcing: RawMaterialSourcing,
     pub equipment_efficiency: EquipmentEfficiency,
+    #[serde(default)]
+    pub refrigeration: Option<RefrigerationSystem>,
+    #[serde(default)]
+    pub tariffs: UtilityTariffs,
+    #[serde(default)]
+    pub overhead: FacilityOverhead,
+}
+
+/// Facility-wide consumption not tied to a processing step (lighting, offices, yard and
+/// general cleaning), allocated to products by their share of production. Amounts left
+/// out default to a typical figure for the facility type and size.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FacilityOverhead {
+    pub energy_kwh_per_year: Option<f64>, // for a year-round facility
+    pub water_m3_per_year: Option<f64>,
+    /// Deprecated: instead of adding overhead, scale step energy by a facility-type
+    /// multiplier and step water by 30%. Only for reproducing earlier results.
+    #[serde(default)]
+    pub legacy_multipliers: bool,
+}
+
+impl FacilityOverhead {
+    /// Typical year-round overhead (kWh, m3) of a facility of this type and size
+    pub fn default_for(facility_type: &ProcessingFacilityType, size: &CapacityRange) -> (f64, f64) {
+        let (kwh, m3) = match facility_type {
+            ProcessingFacilityType::Mill => (6_000.0, 100.0),
+            ProcessingFacilityType::Bakery => (12_000.0, 250.0),
+            ProcessingFacilityType::FishProcessing => (30_000.0, 400.0), // cold rooms, ice
+            ProcessingFacilityType::MeatProcessing => (30_000.0, 600.0),
+            ProcessingFacilityType::DairyProcessing => (25_000.0, 800.0), // hygiene washdown
+            _ => (8_000.0, 150.0),
+        };
+        let scale = match size {
+            CapacityRange::Small => 1.0,
+            CapacityRange::Medium => 4.0,
+            CapacityRange::Large => 12.0,
+            CapacityRange::VeryLarge => 30.0,
+        };
+        (kwh * scale, m3 * scale)
+    }
+}
+
+/// Prices the facility pays, in the assessment's currency; used to cost
+/// recommendations. Missing prices leave the affected savings unquantified.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UtilityTariffs {
+    pub electricity_per_kwh: Option<f64>,
+    pub diesel_per_litre: Option<f64>,
+    pub water_per_m3: Option<f64>,
+}
+
+impl ProcessingOperations {
+    /// Every user-entered percentage must lie within 0-100% and any energy mix must add up
+    pub fn validate_percentages(&self) -> Result<(), InputError> {
+        let mut fields = vec![
+            ("renewable_energy_percentage".to_string(), self.energy_management.renewable_energy_percentage),
+            ("recycled_water_percentage".to_string(), self.water_management.recycled_water_percentage),
+            ("organic_waste_percentage".to_string(), self.waste_management.organic_waste_percentage),
+            ("local_sourcing_percentage".to_string(), self.raw_material_sourcing.local_sourcing_percentage),
+            ("storage_loss_percentage".to_string(), self.raw_material_sourcing.storage_practices.storage_loss_percentage),
+            ("equipment_utilization_rate".to_string(), self.equipment_efficiency.equipment_utilization_rate),
+        ];
+        for byproduct in &self.waste_management.byproduct_utilization {
+            fields.push((format!("percentage_utilized ({})", byproduct.byproduct_name), byproduct.percentage_utilized));
+        }
+        if let Some(ref refrigeration) = self.refrigeration {
+            fields.extend(refrigeration.annual_leakage_rate_percent.map(|v| ("annual_leakage_rate_percent".to_string(), v)));
+            fields.extend(refrigeration.end_of_life_recovery_percent.map(|v| ("end_of_life_recovery_percent".to_string(), v)));
+        }
+
+        let mut errors: Vec<String> = fields.into_iter()
+            .filter(|(_, value)| !(0.0..=100.0).contains(value))
+            .map(|(name, value)| format!("{} must be between 0 and 100, got {}", name, value))
+            .collect();
+        if let Err(e) = self.energy_management.validate_energy_mix() {
+            errors.push(e);
+        }
+        errors.extend(self.waste_management.waste_streams.iter()
+            .filter(|stream| stream.tonnes_per_year < 0.0)
+            .map(|stream| format!("tonnes_per_year ({}) cannot be negative, got {}", stream.stream_name, stream.tonnes_per_year)));
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(InputError::invalid("processing_operations", errors.join("; ")))
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,9 +213,158 @@ pub struct EnergyManagement {
     pub renewable_energy_percentage: f64,
     pub energy_efficiency_measures: Vec<String>,
     pub backup_generator: bool,
+    #[serde(default)]
+    pub energy_mix: Vec<EnergyShare>, // supersedes the primary/secondary sources when given
+    #[serde(default)]
+    pub grid_emission_factor: Option<ElectricityEmissionFactor>, // facility-specific, e.g. from the supplier
+    #[serde(default)]
+    pub captive_generation: bool, // "grid" supply is an on-site or estate gas turbine plant
+    #[serde(default)]
+    pub backup_generator_hours_per_year: Option<f64>, // diesel backup running during outages
+}
+
+/// What a regional factor of the processing engine quantifies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RegionalFactorKind {
+    GridEmission, // kg CO2/kWh
+    CaptiveGenerationEmission, // kg CO2/kWh
+    MarginalGridEmission, // kg CO2/kWh, for consequential modelling
+    ProcessingWaterScarcity,
+    WasteManagementEfficiency,
+    LandfillGasCapture, // fraction of the CH4 generated
+    ProcessingProductivity, // output per worker-hour
 }
 
+impl RegionalFactorKind {
+    fn name(self) -> &'static str {
+        match self {
+            RegionalFactorKind::GridEmission => "grid_emission_factor",
+            RegionalFactorKind::CaptiveGenerationEmission => "captive_generation_emission_factor",
+            RegionalFactorKind::MarginalGridEmission => "marginal_grid_emission_factor",
+            RegionalFactorKind::ProcessingWaterScarcity => "processing_water_scarcity",
+            RegionalFactorKind::WasteManagementEfficiency => "waste_management_efficiency",
+            RegionalFactorKind::LandfillGasCapture => "landfill_gas_capture",
+            RegionalFactorKind::ProcessingProductivity => "processing_productivity",
+        }
+    }
+}
+
+/// A regional factor of the processing engine: what it quantifies, for which country and,
+/// where the supply differs within it, which region
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RegionalFactorKey {
+    pub country: Country,
+    pub region: Option<String>, // None for the whole country
+    pub kind: RegionalFactorKind,
+}
+
+impl RegionalFactorKey {
+    pub fn national(country: Country, kind: RegionalFactorKind) -> Self {
+        RegionalFactorKey { country, region: None, kind }
+    }
+
+    pub fn regional(country: Country, region: &str, kind: RegionalFactorKind) -> Self {
+        RegionalFactorKey { country, region: Some(region.to_string()), kind }
+    }
+}
+
+/// The name sources cite the factor by, e.g. "Nigeria_Lagos_grid_emission_factor"
+impl std::fmt::Display for RegionalFactorKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.region {
+            Some(region) => write!(f, "{}_{}_{}", self.country, region, self.kind.name()),
+            None => write!(f, "{}_{}", self.country, self.kind.name()),
+        }
+    }
+}
+
+/// Carbon intensity of grid electricity and where it came from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ElectricityEmissionFactor {
+    pub kg_co2e_per_kwh: f64,
+    pub source: String,
+}
+
+/// One source's share of facility electricity
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyShare {
+    pub source: EnergySource,
+    pub percentage: f64,
+    pub metered_kwh: Option<f64>, // annual kWh, if this source is metered separately
+}
+
+impl EnergyManagement {
+    /// Shares used in the calculation, summing to 100%. Shares are taken from metered kWh
+    /// when every source is metered; without a mix the primary source supplies everything.
+    pub fn effective_mix(&self) -> Vec<EnergyShare> {
+        if self.energy_mix.is_empty() {
+            return vec![EnergyShare {
+                source: self.primary_energy_source,
+                percentage: 100.0,
+                metered_kwh: None,
+            }];
+        }
+
+        let metered_total: f64 = self.energy_mix.iter().filter_map(|s| s.metered_kwh).sum();
+        let all_metered = self.energy_mix.iter().all(|s| s.metered_kwh.is_some()) && metered_total > 0.0;
+        let declared_total: f64 = self.energy_mix.iter().map(|s| s.percentage.max(0.0)).sum();
+
+        self.energy_mix.iter().map(|share| EnergyShare {
+            percentage: if all_metered {
+                share.metered_kwh.unwrap_or(0.0) / metered_total * 100.0
+            } else if declared_total > 0.0 {
+                share.percentage.max(0.0) / declared_total * 100.0
+            } else {
+                0.0
+            },
+            ..share.clone()
+        }).collect()
+    }
+
+    /// Renewable share (%), derived from the mix when one is given
+    pub fn renewable_share_percent(&self) -> f64 {
+        if self.energy_mix.is_empty() {
+            return self.renewable_energy_percentage.clamp(0.0, 100.0);
+        }
+        self.effective_mix().iter()
+            .filter(|share| share.source.is_renewable())
+            .map(|share| share.percentage)
+            .sum()
+    }
+
+    /// Annual kWh from per-source meters, when every source in the mix is metered
+    pub fn metered_mix_kwh(&self) -> Option<f64> {
+        if self.energy_mix.is_empty() {
+            return None;
+        }
+        self.energy_mix.iter().map(|s| s.metered_kwh).sum::<Option<f64>>()
+    }
+
+    /// Share of operating hours the backup generator carries the grid load
+    pub fn backup_generator_share(&self, annual_operating_hours: f64) -> f64 {
+        match self.backup_generator_hours_per_year {
+            Some(hours) if annual_operating_hours > 0.0 => (hours / annual_operating_hours).clamp(0.0, 1.0),
+            _ => 0.0,
+        }
+    }
+
+    /// Declared shares must be non-negative and add up to 100% (±2 points)
+    pub fn validate_energy_mix(&self) -> Result<(), String> {
+        if self.energy_mix.is_empty() {
+            return Ok(());
+        }
+        if let Some(share) = self.energy_mix.iter().find(|s| s.percentage < 0.0) {
+            return Err(format!("Negative energy share for {:?}: {}%", share.source, share.percentage));
+        }
+        let total: f64 = self.energy_mix.iter().map(|s| s.percentage).sum();
+        if (total - 100.0).abs() > 2.0 {
+            return Err(format!("Energy mix shares sum to {:.1}%, expected 100%", total));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EnergySource {
     GridElectricity,
     DieselGenerator,
@@ -85,6 +377,45 @@ pub enum EnergySource {
     Mixed,
 }
 
+impl EnergySource {
+    pub fn is_renewable(&self) -> bool {
+        matches!(self,
+            EnergySource::SolarPower | EnergySource::HydroElectricity |
+            EnergySource::WindPower | EnergySource::Biomass)
+    }
+
+    /// Life-cycle emissions per kWh delivered (kg CO2-eq/kWh)
+    pub fn emission_factor(&self, country: &Country) -> f64 {
+        match self {
+            EnergySource::GridElectricity => match country {
+                Country::Ghana => 0.45,   // Ghana Energy Commission 2021
+                Country::Nigeria => 0.58, // NERC 2020
+                Country::Global => 0.50,
+            },
+            EnergySource::DieselGenerator => 0.89, // 2.68 kg CO2/L at ~3 kWh/L for small gensets
+            EnergySource::SolarPower => 0.05,
+            EnergySource::Biomass => 0.10, // biogenic CO2 excluded; supply chain and CH4/N2O
+            EnergySource::LPG => 0.23,
+            EnergySource::NaturalGas => 0.20,
+            EnergySource::HydroElectricity => 0.024,
+            EnergySource::WindPower => 0.012,
+            EnergySource::Mixed => 0.35,
+        }
+    }
+
+    /// Grid electricity from the plants that respond to a change in demand, for consequential
+    /// modelling: the simple operating margin of UNFCCC CDM Tool 07, i.e. the fossil units
+    /// dispatched last. In Nigeria these are open-cycle gas turbines at ~30% efficiency; in
+    /// Ghana the simple-cycle oil and gas units at Takoradi and Tema.
+    pub fn marginal_grid_emission_factor(country: &Country) -> f64 {
+        match country {
+            Country::Ghana => 0.62,
+            Country::Nigeria => 0.68,
+            Country::Global => 0.70,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WaterManagement {
     pub water_source: Vec<String>,
@@ -92,9 +423,30 @@ pub struct WaterManagement {
     pub water_treatment: WaterTreatment,
     pub water_conservation_measures: Vec<String>,
     pub wastewater_treatment: WastewaterTreatment,
+    #[serde(default)]
+    pub recycled_water_percentage: f64, // share of process water reused after treatment
+    #[serde(default)]
+    pub rainwater_harvesting_m3_per_year: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl WaterManagement {
+    /// Evaporation, blowdown and water leaving in product cannot be recycled, so at least
+    /// this share of gross process water is always drawn fresh as make-up
+    pub const MIN_MAKEUP_FRACTION: f64 = 0.1;
+
+    pub fn recycle_fraction(&self) -> f64 {
+        (self.recycled_water_percentage / 100.0).clamp(0.0, 1.0)
+    }
+
+    /// Freshwater drawn from the environment (m3) for a given gross process water use,
+    /// after recycling and harvested rainwater
+    pub fn net_freshwater_withdrawal(&self, gross_m3: f64, rainwater_m3: f64) -> f64 {
+        (gross_m3 * (1.0 - self.recycle_fraction()) - rainwater_m3.max(0.0))
+            .max(gross_m3 * Self::MIN_MAKEUP_FRACTION)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum WaterTreatment {
     None,
     BasicFiltration,
@@ -103,7 +455,7 @@ pub enum WaterTreatment {
     Comprehensive,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum WastewaterTreatment {
     None,
     BasicSedimentation,
@@ -112,6 +464,67 @@ pub enum WastewaterTreatment {
     Advanced,
 }
 
+impl WastewaterTreatment {
+    /// Fractional removal of (COD/BOD, total N, total P) before discharge
+    /// (Metcalf & Eddy 2014; World Bank EHS Guidelines for food processing)
+    pub fn removal_efficiencies(&self) -> (f64, f64, f64) {
+        match self {
+            WastewaterTreatment::None => (0.0, 0.0, 0.0),
+            WastewaterTreatment::BasicSedimentation => (0.30, 0.10, 0.15),
+            WastewaterTreatment::BiologicalTreatment => (0.85, 0.40, 0.30),
+            WastewaterTreatment::ChemicalTreatment => (0.60, 0.20, 0.80),
+            WastewaterTreatment::Advanced => (0.95, 0.80, 0.90),
+        }
+    }
+
+    /// IPCC 2019 methane correction factor for the treatment pathway. High-strength
+    /// effluents (POME, cassava) left untreated are held in open anaerobic ponds.
+    pub fn methane_correction_factor(&self, facility_type: &ProcessingFacilityType) -> f64 {
+        match self {
+            WastewaterTreatment::None => match facility_type {
+                ProcessingFacilityType::PalmOilMill | ProcessingFacilityType::CassivaProcessing => 0.8, // deep anaerobic lagoon
+                _ => 0.1, // discharge to rivers/lakes
+            },
+            WastewaterTreatment::BasicSedimentation => 0.2, // shallow anaerobic settling
+            WastewaterTreatment::BiologicalTreatment => 0.0, // well-managed aerobic
+            WastewaterTreatment::ChemicalTreatment => 0.0,
+            WastewaterTreatment::Advanced => 0.0,
+        }
+    }
+}
+
+/// Pollutant concentrations of untreated process wastewater (mg/L)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WastewaterLoad {
+    pub cod_mg_per_l: f64,
+    pub bod_mg_per_l: f64,
+    pub total_nitrogen_mg_per_l: f64,
+    pub total_phosphorus_mg_per_l: f64,
+}
+
+impl WastewaterLoad {
+    /// Typical raw effluent strength by facility type (UNIDO 2011; Wu et al. 2010 for POME;
+    /// Cumbana et al. 2007 for cassava wastewater)
+    pub fn default_for(facility_type: &ProcessingFacilityType) -> Self {
+        let (cod, bod, n, p) = match facility_type {
+            ProcessingFacilityType::PalmOilMill => (50_000.0, 25_000.0, 750.0, 180.0),
+            ProcessingFacilityType::CassivaProcessing => (15_000.0, 8_000.0, 500.0, 60.0),
+            ProcessingFacilityType::MeatProcessing => (6_000.0, 3_000.0, 400.0, 50.0),
+            ProcessingFacilityType::DairyProcessing => (4_000.0, 2_500.0, 150.0, 40.0),
+            ProcessingFacilityType::FishProcessing => (3_000.0, 1_800.0, 300.0, 40.0),
+            ProcessingFacilityType::CocoaProcessing => (3_000.0, 1_500.0, 100.0, 20.0),
+            ProcessingFacilityType::FruitProcessing | ProcessingFacilityType::VegetableProcessing => (2_500.0, 1_500.0, 50.0, 10.0),
+            _ => (1_000.0, 500.0, 40.0, 8.0),
+        };
+        Self {
+            cod_mg_per_l: cod,
+            bod_mg_per_l: bod,
+            total_nitrogen_mg_per_l: n,
+            total_phosphorus_mg_per_l: p,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WasteManagement {
     pub solid_waste_generation: Option<f64>, // kg/day
@@ -119,9 +532,104 @@ pub struct WasteManagement {
     pub waste_disposal_method: WasteDisposalMethod,
     pub recycling_programs: Vec<String>,
     pub byproduct_utilization: Vec<ByproductUtilization>,
+    #[serde(default)]
+    pub waste_streams: Vec<WasteStream>, // supersedes the aggregate fields when given
+}
+
+impl WasteManagement {
+    /// Organic share of solid waste as a 0-1 fraction
+    pub fn organic_fraction(&self) -> f64 {
+        (self.organic_waste_percentage / 100.0).clamp(0.0, 1.0)
+    }
+}
+
+/// A separately collected waste stream and where it goes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasteStream {
+    pub stream_name: String,
+    pub tonnes_per_year: f64,
+    pub composition: WasteComposition,
+    pub disposal_method: WasteDisposalMethod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WasteComposition {
+    CropResidue, // husks, shells, fibre, cobs
+    FoodScraps, // peels, trimmings, rejects
+    Sludge, // effluent treatment sludge
+    Paper,
+    Plastic,
+    Metal,
+}
+
+impl WasteComposition {
+    /// Degradable organic carbon, t C per t wet waste (IPCC 2006 Vol. 5, Table 2.4)
+    pub fn degradable_organic_carbon(&self) -> f64 {
+        match self {
+            WasteComposition::CropResidue => 0.43, // as wood/straw
+            WasteComposition::FoodScraps => 0.15,
+            WasteComposition::Sludge => 0.05,
+            WasteComposition::Paper => 0.40,
+            WasteComposition::Plastic | WasteComposition::Metal => 0.0,
+        }
+    }
+
+    /// Fossil carbon, t C per t wet waste, released as CO2 when burned
+    pub fn fossil_carbon(&self) -> f64 {
+        match self {
+            WasteComposition::Plastic => 0.75,
+            WasteComposition::Paper => 0.01,
+            _ => 0.0,
+        }
+    }
+
+    /// Lower heating value as received (GJ/t)
+    pub fn heating_value(&self) -> f64 {
+        match self {
+            WasteComposition::CropResidue => 14.0,
+            WasteComposition::FoodScraps => 4.0,
+            WasteComposition::Sludge => 2.0,
+            WasteComposition::Paper => 12.0,
+            WasteComposition::Plastic => 35.0,
+            WasteComposition::Metal => 0.0,
+        }
+    }
+
+    /// Virgin material production avoided by recycling (kg CO2-eq per t)
+    pub fn recycling_credit(&self) -> f64 {
+        match self {
+            WasteComposition::Plastic => 1500.0,
+            WasteComposition::Metal => 1800.0, // steel scrap replacing basic oxygen furnace steel
+            WasteComposition::Paper => 700.0,
+            _ => 0.0,
+        }
+    }
+
+    pub fn is_biodegradable(&self) -> bool {
+        self.degradable_organic_carbon() > 0.0
+    }
+
+    /// Lowest-impact fate usually available to a food processor
+    pub fn preferred_disposal(&self) -> WasteDisposalMethod {
+        match self {
+            WasteComposition::CropResidue => WasteDisposalMethod::EnergyRecovery,
+            WasteComposition::FoodScraps | WasteComposition::Sludge => WasteDisposalMethod::AnaerobicDigestion,
+            WasteComposition::Paper | WasteComposition::Plastic | WasteComposition::Metal => WasteDisposalMethod::Recycling,
+        }
+    }
 }
 
+/// Net GWP of one waste stream; negative when credits exceed emissions
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasteStreamImpact {
+    pub stream_name: String,
+    pub tonnes_per_year: f64,
+    pub composition: WasteComposition,
+    pub disposal_method: WasteDisposalMethod,
+    pub gwp_kg_co2e: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum WasteDisposalMethod {
     Landfill,
     Incineration,
@@ -129,6 +637,8 @@ pub enum WasteDisposalMethod {
     AnaerobicDigestion,
     Recycling,
     Mixed,
+    OpenBurning,
+    EnergyRecovery, // boiler or dryer fuel
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,7 +658,7 @@ pub struct RawMaterialSourcing {
     pub storage_practices: StoragePractices,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TransportMode {
     Truck,
     Rail,
@@ -156,12 +666,126 @@ pub enum TransportMode {
     Mixed,
 }
 
+impl TransportMode {
+    /// Well-to-wheel kg CO2-eq per tonne-km; GLEC Framework defaults, trucks raised for
+    /// the older and often part-loaded fleet on West African roads
+    pub fn emission_factor_per_tkm(&self) -> f64 {
+        match self {
+            TransportMode::Truck => 0.11,
+            TransportMode::Rail => 0.03,
+            TransportMode::Ship => 0.02,
+            TransportMode::Mixed => 0.07,
+        }
+    }
+}
+
+/// Cradle-to-gate impacts of one kg of a raw material brought into the facility, charged
+/// to the products that use it (by default processing ignores what happens before the gate)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpstreamBurden {
+    pub per_kg: BTreeMap<String, f64>, // midpoint category -> impact per kg of raw material
+    pub source: String, // where the burden was assessed, e.g. the supplying farm
+}
+
+/// How raw material is held between harvest and processing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoragePractices {
-    pub storage_type: String,
+    pub storage_type: String, // e.g. "Warehouse", "Cold store", "Controlled atmosphere"
     pub climate_control: bool,
     pub pest_control_methods: Vec<String>,
-    pub storage_loss_percentage: f64,
+    pub storage_loss_percentage: f64, // of the raw material stored
+    #[serde(default)]
+    pub storage_days: Option<f64>, // DEFAULT_STORAGE_DAYS when absent
+}
+
+impl StoragePractices {
+    /// Time raw material is held before processing when none is given
+    pub const DEFAULT_STORAGE_DAYS: f64 = 30.0;
+
+    /// Electricity to hold a tonne of raw material for a day: none without climate control,
+    /// about 0.3 kWh in a chilled store (cold-store benchmarks of Evans et al. 2014), and
+    /// half as much again in controlled atmosphere for scrubbing and nitrogen generation
+    pub fn electricity_kwh_per_tonne_day(&self) -> f64 {
+        if !self.climate_control {
+            return 0.0;
+        }
+        let storage_type = self.storage_type.to_lowercase();
+        if storage_type.contains("controlled atmosphere") || storage_type == "ca" {
+            0.45
+        } else {
+            0.3
+        }
+    }
+
+    pub fn days(&self) -> f64 {
+        self.storage_days.unwrap_or(Self::DEFAULT_STORAGE_DAYS).max(0.0)
+    }
+
+    /// Raw material bought per kg that reaches processing, covering what is lost in storage
+    pub fn loss_factor(&self) -> f64 {
+        1.0 / (1.0 - self.storage_loss_percentage.clamp(0.0, 95.0) / 100.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefrigerationSystem {
+    pub refrigerant_type: RefrigerantType,
+    pub total_charge_kg: Option<f64>, // defaults from processing capacity when absent
+    pub annual_leakage_rate_percent: Option<f64>, // defaults to country-typical rate
+    pub equipment_lifetime_years: Option<f64>,
+    pub end_of_life_recovery_percent: Option<f64>,
+}
+
+impl RefrigerationSystem {
+    /// Typical charge per tonne/day of capacity for cold-chain processors (UNEP 2018)
+    pub const DEFAULT_CHARGE_KG_PER_TONNE_DAY: f64 = 1.0;
+    pub const DEFAULT_LIFETIME_YEARS: f64 = 15.0;
+
+    /// Country-typical annual leakage rates (% of charge) for commercial/industrial
+    /// refrigeration, reflecting servicing practice (UNEP RTOC 2018, HCFC phase-out plans)
+    pub fn typical_leakage_rate_percent(country: &Country) -> f64 {
+        match country {
+            Country::Ghana => 20.0,
+            Country::Nigeria => 25.0,
+            Country::Global => 15.0,
+        }
+    }
+
+    /// Typical share of charge recovered at decommissioning; reclaim infrastructure is limited
+    pub fn typical_recovery_percent(country: &Country) -> f64 {
+        match country {
+            Country::Ghana | Country::Nigeria => 10.0,
+            Country::Global => 30.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RefrigerantType {
+    R22,
+    R134a,
+    R404A,
+    R600a,
+    Ammonia,
+    CO2,
+}
+
+impl RefrigerantType {
+    /// GWP100 in kg CO2-eq/kg (IPCC AR6; blends by mass-weighted components)
+    pub fn gwp100(&self) -> f64 {
+        match self {
+            RefrigerantType::R22 => 1960.0,
+            RefrigerantType::R134a => 1530.0,
+            RefrigerantType::R404A => 4728.0, // 44% R125, 52% R143a, 4% R134a
+            RefrigerantType::R600a => 3.0,
+            RefrigerantType::Ammonia => 0.0,
+            RefrigerantType::CO2 => 1.0,
+        }
+    }
+
+    pub fn is_high_gwp(&self) -> bool {
+        matches!(self, RefrigerantType::R22 | RefrigerantType::R404A)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -173,7 +797,7 @@ pub struct EquipmentEfficiency {
     pub modernization_investments: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EquipmentAge {
     New, // < 2 years
     Recent, // 2-5 years
@@ -182,7 +806,7 @@ pub enum EquipmentAge {
     VeryOld, // > 20 years
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MaintenanceFrequency {
     Daily,
     Weekly,
@@ -193,7 +817,7 @@ pub enum MaintenanceFrequency {
     Irregular,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AutomationLevel {
     Manual,
     SemiAutomated,
@@ -216,9 +840,14 @@ pub struct ProcessedProduct {
     pub packaging: PackagingInfo,
     pub quality_grade: QualityGrade,
     pub market_destination: MarketDestination,
+    #[serde(default)]
+    pub wastewater_load: Option<WastewaterLoad>, // overrides facility-type defaults
+    #[serde(default)]
+    pub price_per_tonne: Option<f64>, // used for economic allocation
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
 pub enum ProductType {
     FlourMaize,
     FlourWheat,
@@ -254,6 +883,70 @@ pub struct ProcessingStep {
     pub duration: f64, // hours
     pub yield_efficiency: f64, // percentage
     pub emissions_factor: Option<f64>, // kg CO2-eq per tonne
+    #[serde(default)]
+    pub drying_method: Option<DryingMethod>, // for drying steps; sets the energy carrier
+}
+
+impl ProcessingStep {
+    /// Energy per tonne (kWh), falling back to the dryer's typical demand when a drying
+    /// step has no measured intensity
+    pub fn energy_per_tonne(&self) -> f64 {
+        match self.drying_method {
+            Some(method) if self.energy_intensity <= 0.0 => method.typical_energy_intensity(),
+            _ => self.energy_intensity,
+        }
+    }
+
+    /// Share of the step energy drawn from facility electricity
+    pub fn electricity_per_tonne(&self) -> f64 {
+        match self.drying_method {
+            Some(method) if !method.uses_electricity() => 0.0,
+            _ => self.energy_per_tonne(),
+        }
+    }
+
+    /// Heat (kWh) from the sun or burning fuel in the dryer, outside the electricity supply
+    pub fn drying_heat_per_tonne(&self) -> f64 {
+        self.energy_per_tonne() - self.electricity_per_tonne()
+    }
+}
+
+/// How a drying step (cassava flour, gari, dried fruit) gets its heat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DryingMethod {
+    Sun,
+    SolarAssisted,
+    Biomass,
+    Electric,
+    Diesel,
+}
+
+impl DryingMethod {
+    /// Typical kWh per tonne of dried product: mechanical dryers evaporate about 1.2 t of water
+    /// per tonne of cassava flour at 0.75-1.2 kWh per kg water; solar-assisted dryers only run fans
+    pub fn typical_energy_intensity(&self) -> f64 {
+        match self {
+            DryingMethod::Sun => 0.0,
+            DryingMethod::SolarAssisted => 40.0,
+            DryingMethod::Electric => 900.0,
+            DryingMethod::Diesel => 1200.0,
+            DryingMethod::Biomass => 1400.0, // open furnaces lose more heat
+        }
+    }
+
+    /// Electric dryers and the fans of solar dryers run on the facility supply
+    pub fn uses_electricity(&self) -> bool {
+        matches!(self, DryingMethod::Electric | DryingMethod::SolarAssisted)
+    }
+
+    /// Emissions per kWh of heat for dryers that burn fuel (kg CO2-eq/kWh)
+    pub fn heat_emission_factor(&self) -> f64 {
+        match self {
+            DryingMethod::Diesel => 0.27, // 2.68 kg CO2/L at 10 kWh/L
+            DryingMethod::Biomass => 0.04, // CH4 and N2O only; biogenic CO2 excluded
+            _ => 0.0,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -264,7 +957,7 @@ pub struct PackagingInfo {
     pub recyclable: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PackagingMaterial {
     PlasticBag,
     PaperBag,
@@ -276,7 +969,7 @@ pub enum PackagingMaterial {
     Composite,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum QualityGrade {
     Premium,
     Standard,
@@ -284,7 +977,7 @@ pub enum QualityGrade {
     Industrial,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MarketDestination {
     Local,
     Regional,
@@ -299,15 +992,160 @@ pub enum MarketDestination {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessingAssessment {
+    #[serde(default = "crate::schema::current_schema_version")]
+    pub schema_version: String, // layout of this document, see `schema`
     pub id: Uuid,
     pub facility_profile: ProcessingFacilityProfile,
     pub processing_operations: ProcessingOperations,
     pub processed_products: Vec<ProcessedProduct>,
     pub country: Country,
+    pub currency: Currency, // of its tariffs and the costs of its recommendations
+    #[serde(default)]
+    pub exchange_rates: ExchangeRates, // the capex table's dollar costs are converted at these
     pub region: Option<String>,
     pub assessment_date: DateTime<Utc>,
     pub methodology: LCAMethodology,
     pub results: Option<LCAResults>,
+    #[serde(default)]
+    pub trend_analysis: Option<TrendAnalysis>, // change since a previous assessment of the facility
+}
+
+impl ProcessingAssessment {
+    /// Midpoint results per tonne of total production as (value, unit), or None before the
+    /// assessment has run
+    pub fn midpoint_impacts_per_tonne(&self) -> Option<HashMap<String, (f64, String)>> {
+        let results = self.results.as_ref()?;
+        let production: f64 = self.processed_products.iter().map(|product| product.annual_production).sum();
+        if production <= 0.0 {
+            return None;
+        }
+        Some(results.midpoint_impacts.iter()
+            .map(|(category, result)| (category.clone(), (result.value / production, result.unit.clone())))
+            .collect())
+    }
+
+    /// Compare the results with per-tonne `targets` (midpoint category -> limit) and store
+    /// the outcome in LCAResults::target_compliance, as Assessment::check_targets does per kg
+    pub fn check_targets(&mut self, targets: &HashMap<String, f64>) -> Result<(), EngineError> {
+        let per_tonne = self.midpoint_impacts_per_tonne()
+            .ok_or_else(|| EngineError::NotApplicable("Targets can only be checked on an assessed facility with production".to_string()))?;
+        let compliance = crate::production::interpretation::target_compliance(&per_tonne, "tonne", targets);
+        if let Some(results) = self.results.as_mut() {
+            results.target_compliance = Some(compliance);
+        }
+        Ok(())
+    }
+}
+
+/// Year-over-year change in per-tonne intensities against an earlier assessment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendAnalysis {
+    pub previous_assessment_id: Uuid,
+    pub previous_assessment_date: DateTime<Utc>,
+    pub category_trends: Vec<IntensityTrend>, // facility intensity per tonne, sorted by category
+    pub product_trends: Vec<ProductTrend>,
+    pub regressed_categories: Vec<String>, // worsened by more than the regression threshold
+    pub implemented_measures: Vec<ImplementedMeasure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntensityTrend {
+    pub impact_category: String,
+    pub unit: String,
+    pub previous_intensity: f64,
+    pub current_intensity: f64,
+    pub change_percent: f64, // negative is a reduction
+    pub status: TrendStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductTrend {
+    pub product_name: String,
+    pub trends: Vec<IntensityTrend>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TrendStatus {
+    Improved,
+    Stable,
+    Regressed,
+}
+
+impl TrendStatus {
+    /// Changes within ±1% are noise; an increase beyond 10% is flagged as a regression
+    pub const IMPROVEMENT_THRESHOLD_PERCENT: f64 = -1.0;
+    pub const REGRESSION_THRESHOLD_PERCENT: f64 = 10.0;
+
+    pub fn from_change(change_percent: f64) -> Self {
+        if change_percent < Self::IMPROVEMENT_THRESHOLD_PERCENT {
+            TrendStatus::Improved
+        } else if change_percent > Self::REGRESSION_THRESHOLD_PERCENT {
+            TrendStatus::Regressed
+        } else {
+            TrendStatus::Stable
+        }
+    }
+}
+
+/// A measure detected from changed inputs, with the saving recommended last time
+/// against the reduction actually achieved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImplementedMeasure {
+    pub measure: String,
+    pub recommendation: Option<String>, // title of the earlier recommendation, if one was made
+    pub impact_category: String,
+    pub predicted_savings_percent: Option<f64>,
+    pub realized_savings_percent: f64, // reduction in facility intensity per tonne
+}
+
+/// Several facilities owned by one company, assessed individually and consolidated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompanyAssessment {
+    pub id: Uuid,
+    pub company_name: String,
+    pub facilities: Vec<ProcessingAssessment>,
+    pub assessment_date: DateTime<Utc>,
+    pub results: Option<CompanyResults>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompanyResults {
+    pub midpoint_impacts: BTreeMap<String, MidpointResult>, // sum over facilities
+    pub endpoint_impacts: BTreeMap<String, EndpointResult>, // sum over facilities
+    pub single_score: SingleScoreResult, // production-weighted mean of facility scores
+    pub total_production: f64, // tonnes/year
+    pub site_comparisons: Vec<SiteComparison>,
+}
+
+impl CompanyResults {
+    /// As LCAResults::round_for_display, for the company totals
+    pub fn round_for_display(&mut self) {
+        for result in self.midpoint_impacts.values_mut() {
+            round_to_uncertainty(&mut result.value, &mut result.uncertainty_range);
+        }
+        for result in self.endpoint_impacts.values_mut() {
+            round_to_uncertainty(&mut result.value, &mut result.uncertainty_range);
+        }
+        round_to_uncertainty(&mut self.single_score.value, &mut self.single_score.uncertainty_range);
+    }
+}
+
+/// Facilities ranked by impact intensity (per tonne) for one impact category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteComparison {
+    pub impact_category: String,
+    pub unit: String,
+    pub company_intensity: f64,
+    pub rankings: Vec<SiteRanking>, // best (lowest intensity) first
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteRanking {
+    pub rank: usize,
+    pub facility_name: String,
+    pub intensity: f64,
+    pub percentage_difference: f64, // versus the company intensity
+    pub performance_category: PerformanceCategory,
 }
 
 // ======================================================================
@@ -327,6 +1165,30 @@ pub struct ProcessingImpactFactor {
     pub year: i32,
     pub uncertainty_range: (f64, f64),
     pub pedigree_score: PedigreeScore,
+    #[serde(default)]
+    pub capacity_range: Option<CapacityRange>, // None applies to every facility size
+}
+
+/// Multiplier on per-tonne impacts for each size class, reflecting economies of scale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingScaleCurve {
+    pub facility_type: ProcessingFacilityType,
+    pub small: f64,
+    pub medium: f64,
+    pub large: f64,
+    pub very_large: f64,
+    pub source: String,
+}
+
+impl ProcessingScaleCurve {
+    pub fn multiplier(&self, range: CapacityRange) -> f64 {
+        match range {
+            CapacityRange::Small => self.small,
+            CapacityRange::Medium => self.medium,
+            CapacityRange::Large => self.large,
+            CapacityRange::VeryLarge => self.very_large,
+        }
+    }
 }
 
 // ======================================================================
@@ -338,15 +1200,49 @@ pub struct ProcessingBenchmark {
     pub facility_type: ProcessingFacilityType,
     pub capacity_range: CapacityRange,
     pub country: Country,
-    pub benchmarks: HashMap<String, ProcessingBenchmarkValue>,
+    pub benchmarks: BTreeMap<String, ProcessingBenchmarkValue>, // sorted, so results come out in a fixed order
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CapacityRange {
-    Small,   // < 10 tonnes/day
-    Medium,  // 10-100 tonnes/day
-    Large,   // 100-1000 tonnes/day
-    VeryLarge, // > 1000 tonnes/day
+    Small,
+    Medium,
+    Large,
+    VeryLarge,
+}
+
+impl CapacityRange {
+    /// Upper bounds (tonnes/day, exclusive) of the Small, Medium and Large classes.
+    /// Throughput is measured in product output, so sectors with small batch sizes
+    /// (bakeries, dairies, abattoirs) reach "large" at much lower tonnages than grain mills.
+    pub fn thresholds(facility_type: &ProcessingFacilityType) -> (f64, f64, f64) {
+        match facility_type {
+            // Grain mills and rice mills: hammer/plate mills < 10 t/d, roller mills to 1000 t/d
+            ProcessingFacilityType::Mill | ProcessingFacilityType::RiceProcessing => (10.0, 100.0, 1000.0),
+            // Gari/cassava flour: most output comes from village units under 5 t/d
+            ProcessingFacilityType::CassivaProcessing => (5.0, 50.0, 500.0),
+            // Crude palm oil: artisanal presses < 5 t/d, industrial mills above 100 t/d
+            ProcessingFacilityType::PalmOilMill => (5.0, 100.0, 500.0),
+            ProcessingFacilityType::Bakery => (1.0, 10.0, 100.0),
+            ProcessingFacilityType::FishProcessing
+            | ProcessingFacilityType::MeatProcessing
+            | ProcessingFacilityType::DairyProcessing => (2.0, 20.0, 200.0),
+            ProcessingFacilityType::CocoaProcessing
+            | ProcessingFacilityType::FruitProcessing
+            | ProcessingFacilityType::VegetableProcessing => (5.0, 50.0, 500.0),
+            ProcessingFacilityType::General => (10.0, 100.0, 1000.0),
+        }
+    }
+
+    pub fn from_capacity(tonnes_per_day: f64, facility_type: &ProcessingFacilityType) -> Self {
+        let (small, medium, large) = Self::thresholds(facility_type);
+        match tonnes_per_day {
+            t if t < small => CapacityRange::Small,
+            t if t < medium => CapacityRange::Medium,
+            t if t < large => CapacityRange::Large,
+            _ => CapacityRange::VeryLarge,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -366,14 +1262,18 @@ pub struct ProcessingRecommendation {
     pub category: ProcessingRecommendationCategory,
     pub title: String,
     pub description: String,
-    pub potential_savings: HashMap<String, f64>,
+    pub potential_savings: BTreeMap<String, f64>,
     pub implementation_cost: ImplementationCost,
-    pub payback_period: Option<f64>, // months
+    pub payback_period: Option<f64>, // months; None when savings or capex cannot be costed
+    #[serde(default)]
+    pub estimated_annual_savings: Option<f64>, // in the assessment's currency
+    #[serde(default)]
+    pub capital_cost: Option<f64>, // same currency, from the capex table
     pub complexity: ComplexityLevel,
     pub priority: Priority,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ProcessingRecommendationCategory {
     EnergyEfficiency,
     WaterConservation,
@@ -385,7 +1285,7 @@ pub enum ProcessingRecommendationCategory {
     WasteToEnergy,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ImplementationCost {
     Low,     // < $1,000
     Medium,  // $1,000 - $10,000
@@ -393,7 +1293,7 @@ pub enum ImplementationCost {
     VeryHigh, // > $100,000
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ComplexityLevel {
     Simple,
     Moderate,
@@ -401,7 +1301,7 @@ pub enum ComplexityLevel {
     VeryComplex,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Priority {
     Critical,
     High,
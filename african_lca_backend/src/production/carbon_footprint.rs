@@ -0,0 +1,159 @@
+/*!
+ * Product carbon footprints (ISO 14067)
+ *
+ * The Global warming result of each food or processed product per declared unit, split into
+ * the fossil, biogenic and direct land use change (dLUC) emissions ISO 14067 asks to be
+ * reported separately. Each emission source takes its origin from the same rows of
+ * data/ghg_scopes.csv that give it a GHG Protocol scope.
+ *
+ * Biogenic CO2 taken up and released by the crop is left out, as everywhere else in the
+ * tool; the biogenic figure is CH4 from rice paddies, manure, wastewater and organic waste.
+ * The tool does not model land use change, so the dLUC figure only holds sources a custom
+ * table assigns to "luc".
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::production::ghg_scopes::{with_remainder, CarbonOrigin, ScopeTable};
+use crate::production::models::{ConfidenceLevel, SystemBoundary};
+
+/// Global warming split by the origin of the carbon
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CarbonOriginSplit {
+    pub fossil: f64,
+    pub biogenic: f64,
+    pub land_use_change: f64,
+}
+
+impl CarbonOriginSplit {
+    /// Split (source, kg CO2-eq) emissions by the origin the table gives each source. Any
+    /// difference to `total_kg_co2e` takes the origin of the table's "Unattributed" match,
+    /// so the split always adds up to the total.
+    pub fn classify(
+        emissions: impl IntoIterator<Item = (String, f64)>,
+        total_kg_co2e: f64,
+        table: &ScopeTable
+    ) -> Self {
+        let mut split = Self::default();
+        for (source, kg_co2e) in with_remainder(emissions, total_kg_co2e) {
+            match table.classify(&source).origin {
+                CarbonOrigin::Fossil => split.fossil += kg_co2e,
+                CarbonOrigin::Biogenic => split.biogenic += kg_co2e,
+                CarbonOrigin::LandUseChange => split.land_use_change += kg_co2e,
+            }
+        }
+        split
+    }
+
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self {
+            fossil: self.fossil * factor,
+            biogenic: self.biogenic * factor,
+            land_use_change: self.land_use_change * factor,
+        }
+    }
+
+    pub fn plus(&self, other: &Self) -> Self {
+        Self {
+            fossil: self.fossil + other.fossil,
+            biogenic: self.biogenic + other.biogenic,
+            land_use_change: self.land_use_change + other.land_use_change,
+        }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.fossil + self.biogenic + self.land_use_change
+    }
+}
+
+/// Carbon footprint of one product per declared unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductCarbonFootprint {
+    pub product_id: String,
+    pub product_name: String,
+    pub declared_unit: String, // "1 kg" or "1 tonne"
+    pub unit: String, // e.g. "kg CO2-eq per kg"
+    pub fossil_gwp: f64,
+    pub biogenic_gwp: f64,
+    pub land_use_change_gwp: f64,
+    pub total_gwp: f64, // the three above; equals the Global warming result per declared unit
+    pub system_boundary: SystemBoundary,
+    pub boundary_statement: String,
+    pub data_quality: ConfidenceLevel,
+    pub data_quality_score: f64, // of the Global warming result
+}
+
+impl ProductCarbonFootprint {
+    /// Footprint of `split` kg CO2-eq per declared unit of `declared_unit` ("kg" or "tonne")
+    pub fn new(
+        product_id: &str,
+        product_name: &str,
+        declared_unit: &str,
+        split: CarbonOriginSplit,
+        system_boundary: &SystemBoundary,
+        data_quality: ConfidenceLevel,
+        data_quality_score: f64
+    ) -> Self {
+        Self {
+            product_id: product_id.to_string(),
+            product_name: product_name.to_string(),
+            declared_unit: format!("1 {}", declared_unit),
+            unit: format!("kg CO2-eq per {}", declared_unit),
+            fossil_gwp: split.fossil,
+            biogenic_gwp: split.biogenic,
+            land_use_change_gwp: split.land_use_change,
+            total_gwp: split.total(),
+            system_boundary: *system_boundary,
+            boundary_statement: boundary_statement(system_boundary),
+            data_quality,
+            data_quality_score,
+        }
+    }
+}
+
+/// What a footprint under `boundary` covers and leaves out
+pub fn boundary_statement(boundary: &SystemBoundary) -> String {
+    let stages = match boundary {
+        SystemBoundary::CradleToGate => "Cradle to gate: production and delivery of inputs (fertilizer, pesticides, feed, fuel, electricity), capital goods and the emissions of the assessed site up to the product leaving its gate. Distribution, use and end of life are excluded.",
+        SystemBoundary::GateToGate => "Gate to gate (partial carbon footprint): activities at the assessed facility only, from receipt of raw materials to dispatch of products. Cultivation of raw materials, distribution, use and end of life are excluded.",
+        SystemBoundary::FarmToFork => "Farm to fork: cultivation of the raw materials on the supplying farms, their transport to the facility and processing. Retail, consumption and end of life are excluded.",
+        SystemBoundary::CradleToGrave => "Cradle to grave: production of inputs, the assessed site, distribution, use and end of life as far as the assessment covers them.",
+    };
+    format!(
+        "{} Biogenic CO2 uptake and release by crops are excluded; land use change is not modelled, so its emissions are reported as zero unless assigned in the GHG source table.",
+        stages
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origins_come_from_the_scope_table_and_close_the_total() {
+        let table = ScopeTable::default();
+        let emissions = vec![
+            ("Methane emissions from rice cultivation (2 ha)".to_string(), 300.0),
+            ("Diesel consumption: 10 L/month (120.0 L/year) for Land preparation".to_string(), 320.0),
+            ("Waste stream Husks (Landfill)".to_string(), 80.0),
+        ];
+
+        let split = CarbonOriginSplit::classify(emissions, 1000.0, &table);
+        assert_eq!(split.biogenic, 380.0);
+        assert_eq!(split.fossil, 620.0); // diesel and the unattributed remainder
+        assert_eq!(split.land_use_change, 0.0);
+        assert_eq!(split.total(), 1000.0);
+    }
+
+    #[test]
+    fn footprints_state_their_declared_unit_and_boundary() {
+        let split = CarbonOriginSplit { fossil: 1.5, biogenic: 0.5, land_use_change: 0.0 };
+        let pcf = ProductCarbonFootprint::new(
+            "rice-1", "Rice", "kg", split, &SystemBoundary::GateToGate, ConfidenceLevel::Medium, 0.7
+        );
+        assert_eq!(pcf.declared_unit, "1 kg");
+        assert_eq!(pcf.unit, "kg CO2-eq per kg");
+        assert_eq!(pcf.total_gwp, 2.0);
+        assert!(pcf.boundary_statement.starts_with("Gate to gate"));
+    }
+}
@@ -1,20 +1,512 @@
 use crate::models::*;
+use crate::production::lca::impact_unit;
+use crate::production::lci::nitrogen_content;
+#[cfg(not(target_arch = "wasm32"))]
 use csv::Reader;
-use std::error::Error;
+use serde::{Deserialize, Serialize};
+use crate::error::{DataLoadError, UnitError};
+use crate::units::{factor_scale, Mass, Unit};
 use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use log::warn;
+
+/// A factor whose unit or magnitude doesn't fit its impact category
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitWarning {
+    pub factor: String, // "Category/Country/crop/impact"
+    pub kind: UnitWarningKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitWarningKind {
+    UnknownCategory,
+    UnitMismatch,
+    ImplausibleMagnitude,
+}
+
+/// Largest plausible per-kg value for each category (beef-level burdens are the upper end)
+fn plausible_max_per_kg(category: &str) -> f64 {
+    match category {
+        "Global warming" => 200.0,
+        "Water consumption" => 100.0,
+        "Water scarcity" => 5000.0,
+        "Land use" | "Biodiversity loss" | "Soil degradation" => 1000.0,
+        "Terrestrial acidification" | "Marine eutrophication" | "Mineral depletion" => 5.0,
+        "Freshwater eutrophication" | "Particulate matter formation" | "Photochemical oxidation" => 1.0,
+        "Fossil depletion" => 50.0,
+        _ => f64::INFINITY,
+    }
+}
+
+/// Check each factor's unit against the expected unit for its impact category and flag
+/// implausible magnitudes (e.g. water reported in litres rather than m3).
+pub fn validate_factor_units(factors: &[ImpactFactor]) -> Vec<UnitWarning> {
+    let mut warnings = Vec::new();
+
+    for factor in factors {
+        let label = format!("{:?}/{:?}/{}/{}",
+            factor.food_category,
+            factor.country,
+            factor.crop_type.as_deref().unwrap_or("-"),
+            factor.impact_category);
+        let expected = impact_unit(&factor.impact_category);
+
+        if expected == "Unknown" {
+            warnings.push(UnitWarning {
+                factor: label,
+                kind: UnitWarningKind::UnknownCategory,
+                message: format!("Unknown impact category '{}'", factor.impact_category),
+            });
+            continue;
+        }
+
+        if factor.unit.trim() != expected {
+            warnings.push(UnitWarning {
+                factor: label.clone(),
+                kind: UnitWarningKind::UnitMismatch,
+                message: format!("Unit '{}' does not match expected '{}'", factor.unit, expected),
+            });
+        }
+
+        let max = plausible_max_per_kg(&factor.impact_category);
+        if factor.value_per_kg.abs() > max {
+            warnings.push(UnitWarning {
+                factor: label,
+                kind: UnitWarningKind::ImplausibleMagnitude,
+                message: format!("Value {} {} per kg exceeds plausible maximum {}",
+                                 factor.value_per_kg, factor.unit, max),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Convert a factor declared in another unit of its category's quantity - water in litres, a
+/// burden per tonne of product - to the category's expected unit, and fail on a unit of a
+/// different quantity. Factors of unknown categories are left for validate_factor_units.
+pub fn normalize_factor_unit(factor: &mut ImpactFactor) -> Result<(), UnitError> {
+    let expected = impact_unit(&factor.impact_category);
+    if expected == "Unknown" {
+        return Ok(());
+    }
+    let scale = factor_scale(&factor.unit, expected, Unit::Kg)?;
+    factor.value_per_kg *= scale;
+    factor.uncertainty_range = (factor.uncertainty_range.0 * scale, factor.uncertainty_range.1 * scale);
+    factor.unit = expected.to_string();
+    Ok(())
+}
+
+/// Plausible yield band (t/ha) per crop, spanning unfertilized smallholder plots to the best
+/// managed fields in Ghana and Nigeria (FAOSTAT 2018-2022 and on-station trial records), with
+/// the typical yield of the two countries in between. Checked in order, so more specific
+/// names come first ("cocoyam" before "yam").
+const PLAUSIBLE_YIELDS: &[(&str, f64, f64, f64)] = &[
+    ("maize", 0.2, 1.9, 12.0),
+    ("rice", 0.3, 2.8, 10.0),
+    ("sorghum", 0.1, 1.1, 6.0),
+    ("millet", 0.1, 0.9, 4.0),
+    ("wheat", 0.5, 1.5, 8.0),
+    ("cassava", 1.5, 12.0, 60.0),
+    ("cocoyam", 1.0, 6.0, 25.0),
+    ("sweet potato", 1.0, 4.0, 40.0),
+    ("yam", 2.0, 12.0, 45.0),
+    ("plantain", 1.5, 10.0, 40.0),
+    ("banana", 2.0, 10.0, 60.0),
+    ("cowpea", 0.1, 0.9, 3.0),
+    ("soybean", 0.2, 1.1, 4.5),
+    ("groundnut", 0.2, 1.1, 4.5),
+    ("bean", 0.1, 0.8, 4.0),
+    ("cocoa", 0.05, 0.4, 2.5),
+    ("cashew", 0.05, 0.3, 2.0),
+    ("oil palm", 2.0, 6.0, 30.0), // fresh fruit bunches
+    ("tomato", 2.0, 8.0, 80.0),
+    ("onion", 2.0, 10.0, 60.0),
+    ("pepper", 1.0, 6.0, 30.0),
+    ("okra", 1.0, 4.0, 20.0),
+    ("pineapple", 10.0, 30.0, 80.0),
+    ("mango", 2.0, 10.0, 30.0),
+];
+
+/// Yield band and typical yield (t/ha) for a food grown on land: by crop when known, else by
+/// category
+fn yield_band(food: &FoodItem) -> Option<(f64, f64, f64)> {
+    let crop = crop_name(food);
+    PLAUSIBLE_YIELDS.iter()
+        .find(|(name, _, _, _)| crop.contains(name))
+        .map(|(_, low, typical, high)| (*low, *typical, *high))
+        .or(match food.category {
+            FoodCategory::Cereals => Some((0.1, 1.5, 12.0)),
+            FoodCategory::Legumes => Some((0.1, 0.9, 4.5)),
+            FoodCategory::Roots => Some((1.0, 10.0, 60.0)),
+            FoodCategory::Vegetables | FoodCategory::Fruits => Some((1.0, 8.0, 80.0)),
+            FoodCategory::Nuts => Some((0.05, 0.6, 4.5)),
+            _ => None, // livestock and processed foods have no per-hectare yield
+        })
+}
+
+/// Yield band (t/ha) for a food grown on allocated land
+fn plausible_yield_range(food: &FoodItem) -> Option<(f64, f64)> {
+    yield_band(food).map(|(low, _, high)| (low, high))
+}
+
+/// Typical yield (t/ha) of the food's crop in Ghana and Nigeria, for estimating land that
+/// was not reported
+pub fn typical_yield(food: &FoodItem) -> Option<f64> {
+    yield_band(food).map(|(_, typical, _)| typical)
+}
+
+/// Highest plausible nitrogen rate (kg N/ha a season) per crop: the top of the Ghana MoFA and
+/// Nigerian FMARD recommendations with a margin for intensive growers. Legumes fix their own
+/// nitrogen and need only a starter dose. Checked in order like PLAUSIBLE_YIELDS.
+const MAX_NITROGEN_RATES: &[(&str, f64)] = &[
+    ("maize", 200.0),
+    ("rice", 180.0),
+    ("sorghum", 120.0),
+    ("millet", 100.0),
+    ("wheat", 180.0),
+    ("cassava", 120.0),
+    ("cocoyam", 120.0),
+    ("sweet potato", 100.0),
+    ("yam", 120.0),
+    ("plantain", 300.0),
+    ("banana", 300.0),
+    ("cowpea", 60.0),
+    ("soybean", 60.0),
+    ("groundnut", 60.0),
+    ("bean", 60.0),
+    ("cocoa", 150.0),
+    ("cashew", 150.0),
+    ("oil palm", 250.0),
+    ("tomato", 250.0),
+    ("onion", 200.0),
+    ("pepper", 250.0),
+    ("okra", 150.0),
+    ("pineapple", 400.0),
+    ("mango", 200.0),
+];
+
+/// Most water a hectare can use in a season (m3/ha): crop evapotranspiration at the hottest,
+/// driest sites of the two countries (FAO Irrigation and Drainage Paper 56), plus percolation
+/// for paddy rice and a full year for perennials. More per hectare is beyond what the climate
+/// can evaporate.
+const MAX_SEASONAL_WATER: &[(&str, f64)] = &[
+    ("rice", 20_000.0),
+    ("cassava", 15_000.0),
+    ("plantain", 25_000.0),
+    ("banana", 25_000.0),
+    ("cocoa", 18_000.0),
+    ("oil palm", 20_000.0),
+    ("pineapple", 12_000.0),
+    ("mango", 18_000.0),
+];
+
+/// Output per animal and year (kg): carcass weight per animal sold for Meat and Poultry, milk
+/// per cow in milk for Dairy, eggs per hen housed for Eggs. West African breeds (FAOSTAT
+/// 2018-2022, ILRI herd surveys); an empty name covers the rest of the category.
+const OUTPUT_PER_HEAD: &[(FoodCategory, &str, f64, f64)] = &[
+    (FoodCategory::Meat, "cattle", 80.0, 350.0),
+    (FoodCategory::Meat, "beef", 80.0, 350.0),
+    (FoodCategory::Meat, "goat", 6.0, 30.0),
+    (FoodCategory::Meat, "sheep", 8.0, 35.0),
+    (FoodCategory::Meat, "mutton", 8.0, 35.0),
+    (FoodCategory::Meat, "lamb", 8.0, 35.0),
+    (FoodCategory::Meat, "pig", 30.0, 120.0),
+    (FoodCategory::Meat, "pork", 30.0, 120.0),
+    (FoodCategory::Meat, "rabbit", 0.5, 2.5),
+    (FoodCategory::Meat, "grasscutter", 1.5, 6.0),
+    (FoodCategory::Meat, "", 0.5, 400.0),
+    (FoodCategory::Poultry, "guinea fowl", 0.5, 1.5),
+    (FoodCategory::Poultry, "", 0.6, 4.0),
+    (FoodCategory::Dairy, "", 100.0, 8_000.0),
+    (FoodCategory::Eggs, "", 2.0, 20.0),
+];
+
+fn crop_name(food: &FoodItem) -> String {
+    food.crop_type.as_deref().unwrap_or(&food.name).to_lowercase()
+}
+
+/// Highest plausible nitrogen rate (kg N/ha a season) for a crop: by crop when known, else by
+/// category
+fn max_nitrogen_rate(food: &FoodItem) -> Option<f64> {
+    let crop = crop_name(food);
+    MAX_NITROGEN_RATES.iter()
+        .find(|(name, _)| crop.contains(name))
+        .map(|(_, max)| *max)
+        .or(match food.category {
+            FoodCategory::Cereals => Some(200.0),
+            FoodCategory::Legumes => Some(60.0),
+            FoodCategory::Roots => Some(150.0),
+            FoodCategory::Vegetables => Some(250.0),
+            FoodCategory::Fruits => Some(300.0),
+            FoodCategory::Nuts => Some(150.0),
+            _ => None, // nothing is fertilized per hectare
+        })
+}
+
+/// Most water (m3/ha a season) the climate allows a crop to use: by crop when known, else
+/// by category
+fn max_seasonal_water(food: &FoodItem) -> Option<f64> {
+    let crop = crop_name(food);
+    MAX_SEASONAL_WATER.iter()
+        .find(|(name, _)| crop.contains(name))
+        .map(|(_, max)| *max)
+        .or(match food.category {
+            FoodCategory::Cereals | FoodCategory::Vegetables => Some(10_000.0),
+            FoodCategory::Legumes => Some(8_000.0),
+            FoodCategory::Roots => Some(15_000.0),
+            FoodCategory::Fruits => Some(20_000.0),
+            FoodCategory::Nuts => Some(18_000.0),
+            _ => None,
+        })
+}
+
+/// Number of animals a livestock food's output is spread over, and the plausible output
+/// (kg) per animal
+fn output_per_head_band(food: &FoodItem) -> Option<(u32, f64, f64)> {
+    let head_count = match food.category {
+        FoodCategory::Meat | FoodCategory::Poultry => food.head_count,
+        FoodCategory::Dairy => food.dairy.as_ref().map(|dairy| dairy.herd_size),
+        FoodCategory::Eggs => food.poultry.as_ref().map(|poultry| poultry.flock_size),
+        _ => None,
+    }.filter(|count| *count > 0)?;
+    let crop = crop_name(food);
+    OUTPUT_PER_HEAD.iter()
+        .find(|(category, name, _, _)| *category == food.category && crop.contains(name))
+        .map(|(_, _, low, high)| (head_count, *low, *high))
+}
+
+/// Production-side checks of a farm: each crop's yield against its plausible band, each
+/// livestock food's output per animal against typical carcass weights and yields, and the
+/// nitrogen the fertilizers supply against the agronomic maximum of the farm's crops. The run
+/// goes ahead either way.
+pub fn plausibility_check(foods: &[FoodItem], management: Option<&ManagementPractices>) -> Vec<CalculationWarning> {
+    let mut warnings: Vec<CalculationWarning> = foods.iter().filter_map(yield_check).collect();
+    warnings.extend(foods.iter().filter_map(output_per_head_check));
+    warnings.extend(nitrogen_rate_check(foods, management));
+    warnings
+}
+
+/// A quantity and allocated area that imply a yield outside the crop's plausible band. Very
+/// high yields usually mean tonnes entered as kg or acres as hectares.
+fn yield_check(food: &FoodItem) -> Option<CalculationWarning> {
+    let area = food.area_allocated.filter(|area| *area > 0.0)?;
+    let (low, high) = plausible_yield_range(food)?;
+    let yield_t_ha = Mass::kg(food.quantity_kg).in_tonnes() / area;
+    let (kind, hint) = if yield_t_ha > high {
+        (CalculationWarningKind::ImplausiblyHighYield, "check whether the quantity is in tonnes rather than kg")
+    } else if yield_t_ha < low {
+        (CalculationWarningKind::ImplausiblyLowYield, "check whether the area is in acres or m2 rather than hectares")
+    } else {
+        return None;
+    };
+    Some(CalculationWarning {
+        food_id: food.id.clone(),
+        kind,
+        message: format!(
+            "{}: {} kg on {} ha is {:.2} t/ha, outside the plausible {}-{} t/ha; {}",
+            food.name, food.quantity_kg, area, yield_t_ha, low, high, hint
+        ),
+    })
+}
+
+/// Livestock output per animal outside the plausible band, usually a mistyped number of
+/// animals or a quantity in live weight or tonnes
+fn output_per_head_check(food: &FoodItem) -> Option<CalculationWarning> {
+    let (head_count, low, high) = output_per_head_band(food)?;
+    let per_head = food.quantity_kg / head_count as f64;
+    if (low..=high).contains(&per_head) {
+        return None;
+    }
+    Some(CalculationWarning {
+        food_id: food.id.clone(),
+        kind: CalculationWarningKind::ImplausibleOutputPerHead,
+        message: format!(
+            "{}: {} kg from {} animals is {:.1} kg per animal, outside the plausible {}-{} kg; \
+             check the number of animals and that the quantity is in kg",
+            food.name, food.quantity_kg, head_count, per_head, low, high
+        ),
+    })
+}
+
+/// Nitrogen per hectare and season above the agronomic maximum of the most nitrogen-hungry
+/// crop on the farm; fertilizer is reported for the farm, so that crop is the one it may
+/// have gone to
+fn nitrogen_rate_check(foods: &[FoodItem], management: Option<&ManagementPractices>) -> Option<CalculationWarning> {
+    let fertilization = management?.fertilization.as_ref().filter(|f| f.uses_fertilizers)?;
+    let n_kg_per_ha: f64 = fertilization.fertilizer_applications.iter()
+        .map(|app| app.application_rate * app.applications_per_season as f64
+            * nitrogen_content(&app.fertilizer_type, &app.npk_ratio))
+        .sum();
+    let (food, max) = foods.iter()
+        .filter_map(|food| max_nitrogen_rate(food).map(|max| (food, max)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    (n_kg_per_ha > max).then(|| CalculationWarning {
+        food_id: food.id.clone(),
+        kind: CalculationWarningKind::NitrogenAboveAgronomicMaximum,
+        message: format!(
+            "{}: fertilizers supply {:.0} kg N/ha a season, above the plausible 0-{} kg N/ha; \
+             check whether application rates are per farm rather than per hectare",
+            food.name, n_kg_per_ha, max
+        ),
+    })
+}
+
+/// Water per hectare beyond what the climate lets the crop evaporate, from the food's water
+/// consumption per kg. A food whose yield is already implausible is left to that warning.
+pub fn water_use_check(food: &FoodItem, water_m3_per_kg: f64) -> Option<CalculationWarning> {
+    let area = food.area_allocated.filter(|area| *area > 0.0)?;
+    if yield_check(food).is_some() {
+        return None;
+    }
+    let max = max_seasonal_water(food)?;
+    let m3_per_ha = water_m3_per_kg * food.quantity_kg / area;
+    (m3_per_ha > max).then(|| CalculationWarning {
+        food_id: food.id.clone(),
+        kind: CalculationWarningKind::ImplausibleWaterUse,
+        message: format!(
+            "{}: {:.3} m3/kg over {} kg on {} ha is {:.0} m3/ha, outside the plausible 0-{} m3/ha; \
+             check the water consumption and its unit",
+            food.name, water_m3_per_kg, food.quantity_kg, area, m3_per_ha, max
+        ),
+    })
+}
+
+/// Merge foods listed more than once - same id, name and crop type, as merged data sources
+/// produce - into one entry with their quantities and areas summed, and describe each merge.
+/// The area is only summed when every entry reports one; post-harvest losses become the
+/// quantity-weighted mean. Other details are kept from the first entry.
+pub fn merge_duplicate_foods(foods: Vec<FoodItem>) -> (Vec<FoodItem>, Vec<String>) {
+    let mut merged: Vec<(FoodItem, usize)> = Vec::with_capacity(foods.len());
+    for food in foods {
+        let existing = merged.iter_mut().find(|(first, _)| {
+            first.id == food.id && first.name == food.name && first.crop_type == food.crop_type
+        });
+        let Some((first, count)) = existing else {
+            merged.push((food, 1));
+            continue;
+        };
+        let quantity_kg = first.quantity_kg + food.quantity_kg;
+        first.post_harvest_losses = match (first.post_harvest_losses, food.post_harvest_losses) {
+            (Some(a), Some(b)) if quantity_kg > 0.0 => Some((a * first.quantity_kg + b * food.quantity_kg) / quantity_kg),
+            (losses, None) | (None, losses) => losses,
+            (Some(a), Some(_)) => Some(a),
+        };
+        first.area_allocated = first.area_allocated.zip(food.area_allocated).map(|(a, b)| a + b);
+        first.quantity_kg = quantity_kg;
+        *count += 1;
+    }
+
+    let warnings = merged.iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(food, count)| format!(
+            "{} (id {}) was listed {} times - merged into one entry of {} kg{}",
+            food.name, food.id, count, food.quantity_kg,
+            food.area_allocated.map_or_else(|| " without an area".to_string(), |area| format!(" on {} ha", area))
+        ))
+        .collect();
+    (merged.into_iter().map(|(food, _)| food).collect(), warnings)
+}
+
+/// Latitude/longitude box assigned to one climate zone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClimateZoneBand {
+    pub zone: ClimateZone,
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+}
+
+impl ClimateZoneBand {
+    pub fn contains(&self, coordinates: &GeoCoordinates) -> bool {
+        (self.min_latitude..self.max_latitude).contains(&coordinates.latitude)
+            && (self.min_longitude..self.max_longitude).contains(&coordinates.longitude)
+    }
+}
+
+/// Agro-ecological zone of one region (administrative region or state) of a country
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionZone {
+    pub country: Country,
+    pub region: String, // without a trailing "Region"/"State"
+    pub zone: AgroEcologicalZone,
+}
+
+impl RegionZone {
+    /// Whether an assessment's free-text region names this one, ignoring case and a
+    /// trailing "Region" or "State" ("Northern Region", "kano state")
+    pub fn matches(&self, country: &Country, region: &str) -> bool {
+        let region = region.trim().to_lowercase();
+        let region = region.strip_suffix(" region")
+            .or_else(|| region.strip_suffix(" state"))
+            .unwrap_or(&region);
+        &self.country == country && self.region.to_lowercase() == region
+    }
+}
+
+/// Multiplier on one impact category for foods grown in one agro-ecological zone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClimateAdjustmentRule {
+    pub zone: AgroEcologicalZone,
+    pub food_category: Option<FoodCategory>, // None: any category
+    pub crop_type: Option<String>, // None: any crop; matched against the crop type or name
+    pub impact_category: String,
+    pub factor: f64,
+    pub source: String, // listed in the contributing sources of adjusted results
+}
+
+impl ClimateAdjustmentRule {
+    pub fn applies_to(&self, zone: AgroEcologicalZone, food: &FoodItem, impact_category: &str) -> bool {
+        self.zone == zone
+            && self.impact_category == impact_category
+            && self.food_category.is_none_or(|category| category == food.category)
+            && self.crop_type.as_ref().is_none_or(|crop| {
+                let crop = crop.to_lowercase();
+                food.crop_type.as_ref().unwrap_or(&food.name).to_lowercase().contains(&crop)
+            })
+    }
+}
+
+/// Built-in factors, built on first use and shared by every loader that loads them
+static DEFAULT_FACTORS: LazyLock<Arc<Vec<ImpactFactor>>> = LazyLock::new(|| {
+    let mut factors = Vec::new();
+    // Ghana-specific factors based on research
+    DataLoader::add_ghana_factors(&mut factors);
+    // Nigeria-specific factors
+    DataLoader::add_nigeria_factors(&mut factors);
+    // Global averages as fallback (updated with research data)
+    DataLoader::add_global_factors(&mut factors);
+    DataLoader::log_unit_warnings(&factors);
+    Arc::new(factors)
+});
 
 pub struct DataLoader {
-    pub impact_factors: Vec<ImpactFactor>,
+    // Shared with the built-in set until factors are added to it
+    pub impact_factors: Arc<Vec<ImpactFactor>>,
     pub regional_factors: HashMap<String, f64>,
     pub climate_adjustments: HashMap<String, f64>,
+    pub climate_zones: Vec<ClimateZoneBand>, // first matching band wins
+    pub region_zones: Vec<RegionZone>,
+    pub climate_adjustment_rules: Vec<ClimateAdjustmentRule>, // first matching rule wins
+}
+
+impl Default for DataLoader {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DataLoader {
     pub fn new() -> Self {
         Self {
-            impact_factors: Vec::new(),
+            impact_factors: Arc::default(),
             regional_factors: Self::initialize_regional_factors(),
             climate_adjustments: Self::initialize_climate_adjustments(),
+            climate_zones: Self::initialize_climate_zones(),
+            region_zones: Self::initialize_region_zones(),
+            climate_adjustment_rules: Self::initialize_climate_adjustment_rules(),
         }
     }
 
@@ -25,6 +517,7 @@ impl DataLoader {
         factors.insert("Ghana_water_scarcity".to_string(), 20.0); // AWARE factor
         factors.insert("Nigeria_north_water_scarcity".to_string(), 30.0); // High scarcity
         factors.insert("Nigeria_south_water_scarcity".to_string(), 15.0); // Moderate scarcity
+        factors.insert("Global_water_scarcity".to_string(), GLOBAL_AVERAGE_AWARE); // World average
         
         // Biodiversity impact factors (MSA - Mean Species Abundance)
         factors.insert("intensive_biodiversity_factor".to_string(), 0.2); // 80% loss
@@ -50,24 +543,157 @@ impl DataLoader {
         // Precipitation adjustments for wet/dry seasons
         adjustments.insert("wet_season_factor".to_string(), 1.5);
         adjustments.insert("dry_season_factor".to_string(), 0.7);
+
+        // Zone-specific sets, used when the farm's coordinates place it in a zone;
+        // the unprefixed values above remain the country default
+        for (zone, decomposition, methane, respiration) in [
+            (ClimateZone::HumidTropical, 1.3, 1.2, 1.4), // warm and wet year-round
+            (ClimateZone::SemiArid, 1.0, 1.0, 1.1),      // long dry season slows turnover
+            (ClimateZone::Arid, 0.8, 0.9, 0.9),          // moisture-limited decomposition
+        ] {
+            let prefix = zone.key_prefix();
+            adjustments.insert(format!("{}_tropical_decomposition_factor", prefix), decomposition);
+            adjustments.insert(format!("{}_methane_emission_factor", prefix), methane);
+            adjustments.insert(format!("{}_soil_respiration_factor", prefix), respiration);
+        }
         
         adjustments
     }
 
-    pub fn load_default_factors(&mut self) -> Result<(), Box<dyn Error>> {
-        // Ghana-specific factors based on research
-        self.add_ghana_factors();
-        
-        // Nigeria-specific factors
-        self.add_nigeria_factors();
-        
-        // Global averages as fallback (updated with research data)
-        self.add_global_factors();
-        
+    fn initialize_climate_zones() -> Vec<ClimateZoneBand> {
+        // West African rainfall belts run roughly east-west, so latitude sets the zone
+        let band = |zone, min_latitude, max_latitude| ClimateZoneBand {
+            zone,
+            min_latitude,
+            max_latitude,
+            min_longitude: -20.0,
+            max_longitude: 16.0,
+        };
+        vec![
+            band(ClimateZone::Arid, 14.0, 25.0),          // Sahel: Niger, northern Nigeria border
+            band(ClimateZone::SemiArid, 9.0, 14.0),       // Savanna: northern Ghana, Kano, Kaduna
+            band(ClimateZone::HumidTropical, 4.0, 9.0),   // Forest belt and coast: Kumasi, Accra, Lagos
+        ]
+    }
+
+    /// Add the built-in Ghana, Nigeria and global factors. They are built once per process;
+    /// a loader with no factors of its own shares them instead of holding a copy.
+    fn initialize_region_zones() -> Vec<RegionZone> {
+        use AgroEcologicalZone::*;
+        let ghana = [
+            ("Western", HighRainforest),
+            ("Western North", HighRainforest),
+            ("Central", SemiDeciduousForest),
+            ("Ashanti", SemiDeciduousForest),
+            ("Eastern", SemiDeciduousForest),
+            ("Ahafo", SemiDeciduousForest),
+            ("Greater Accra", CoastalSavannah),
+            ("Volta", CoastalSavannah),
+            ("Bono", DerivedSavannah), // forest-savannah transition
+            ("Bono East", DerivedSavannah),
+            ("Oti", DerivedSavannah),
+            ("Northern", GuineaSavannah),
+            ("Savannah", GuineaSavannah),
+            ("North East", GuineaSavannah),
+            ("Upper West", GuineaSavannah),
+            ("Upper East", SudanSavannah),
+        ];
+        let nigeria = [
+            ("Borno", Sahel),
+            ("Yobe", Sahel),
+            ("Sokoto", SudanSavannah),
+            ("Kebbi", SudanSavannah),
+            ("Zamfara", SudanSavannah),
+            ("Katsina", SudanSavannah),
+            ("Kano", SudanSavannah),
+            ("Jigawa", SudanSavannah),
+            ("Bauchi", SudanSavannah),
+            ("Gombe", SudanSavannah),
+            ("Kaduna", GuineaSavannah),
+            ("Niger", GuineaSavannah),
+            ("Plateau", GuineaSavannah),
+            ("Adamawa", GuineaSavannah),
+            ("Taraba", GuineaSavannah),
+            ("Nasarawa", GuineaSavannah),
+            ("Federal Capital Territory", GuineaSavannah),
+            ("FCT", GuineaSavannah),
+            ("Kwara", GuineaSavannah),
+            ("Kogi", GuineaSavannah),
+            ("Benue", GuineaSavannah),
+            ("Oyo", DerivedSavannah),
+            ("Osun", DerivedSavannah),
+            ("Ekiti", DerivedSavannah),
+            ("Enugu", DerivedSavannah),
+            ("Ogun", HighRainforest),
+            ("Lagos", HighRainforest),
+            ("Ondo", HighRainforest),
+            ("Edo", HighRainforest),
+            ("Delta", HighRainforest),
+            ("Anambra", HighRainforest),
+            ("Imo", HighRainforest),
+            ("Abia", HighRainforest),
+            ("Ebonyi", HighRainforest),
+            ("Cross River", HighRainforest),
+            ("Akwa Ibom", HighRainforest),
+            ("Rivers", MangroveSwamp),
+            ("Bayelsa", MangroveSwamp),
+        ];
+        let zones = |country: Country, regions: &[(&str, AgroEcologicalZone)]| regions.iter()
+            .map(move |(region, zone)| RegionZone { country, region: region.to_string(), zone: *zone })
+            .collect::<Vec<_>>();
+        [zones(Country::Ghana, &ghana), zones(Country::Nigeria, &nigeria)].concat()
+    }
+
+    fn initialize_climate_adjustment_rules() -> Vec<ClimateAdjustmentRule> {
+        use AgroEcologicalZone::*;
+        let mut rules = Vec::new();
+        // (zone, paddy rice CH4, soil organic matter decomposition)
+        for (zone, paddy_methane, decomposition) in [
+            (Sahel, 0.85, 0.8),               // short season, irrigated schemes only
+            (SudanSavannah, 0.9, 0.9),
+            (GuineaSavannah, 1.0, 1.0),       // rainfed lowland, one flooding season
+            (CoastalSavannah, 1.05, 1.0),
+            (DerivedSavannah, 1.15, 1.2),     // two rainy seasons
+            (SemiDeciduousForest, 1.25, 1.3),
+            (HighRainforest, 1.35, 1.4),      // long flooding, warm, waterlogged soils
+            (MangroveSwamp, 1.3, 1.3),
+        ] {
+            let rule = |food_category, crop_type: Option<&str>, impact_category: &str, factor, source: &str| ClimateAdjustmentRule {
+                zone,
+                food_category,
+                crop_type: crop_type.map(str::to_string),
+                impact_category: impact_category.to_string(),
+                factor,
+                source: source.to_string(),
+            };
+            rules.extend([
+                rule(Some(FoodCategory::Cereals), Some("rice"), "Global warming", paddy_methane,
+                     "Paddy CH4 scaled for flooding season length and temperature (IPCC 2019 Vol 4 Ch 5.5)"),
+                rule(Some(FoodCategory::Cereals), None, "Global warming", 1.0,
+                     "Upland cereals are not flooded and emit no paddy CH4"),
+                rule(None, None, "Soil degradation", decomposition,
+                     "Soil organic matter decomposition by rainfall and temperature regime"),
+            ]);
+        }
+        rules
+    }
+
+    pub fn load_default_factors(&mut self) -> Result<(), DataLoadError> {
+        if self.impact_factors.is_empty() {
+            self.impact_factors = Arc::clone(&DEFAULT_FACTORS);
+        } else {
+            Arc::make_mut(&mut self.impact_factors).extend(DEFAULT_FACTORS.iter().cloned());
+        }
         Ok(())
     }
 
-    fn add_ghana_factors(&mut self) {
+    fn log_unit_warnings(factors: &[ImpactFactor]) {
+        for warning in validate_factor_units(factors) {
+            warn!("Impact factor {}: {}", warning.factor, warning.message);
+        }
+    }
+
+    fn add_ghana_factors(factors: &mut Vec<ImpactFactor>) {
         let ghana_factors = vec![
             // CEREALS - Updated with research data
             ImpactFactor {
@@ -88,6 +714,7 @@ impl DataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 3,
                 },
+                gsd: None,
             },
             ImpactFactor {
                 food_category: FoodCategory::Cereals,
@@ -107,6 +734,7 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
             ImpactFactor {
                 food_category: FoodCategory::Cereals,
@@ -126,6 +754,7 @@ impl DataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 3,
                 },
+                gsd: None,
             },
 
             // WATER CONSUMPTION - Research-based values
@@ -147,6 +776,7 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
             ImpactFactor {
                 food_category: FoodCategory::Cereals,
@@ -166,6 +796,7 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
             ImpactFactor {
                 food_category: FoodCategory::Cereals,
@@ -185,6 +816,7 @@ impl DataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 3,
                 },
+                gsd: None,
             },
 
             // LEGUMES
@@ -206,6 +838,7 @@ impl DataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 3,
                 },
+                gsd: None,
             },
             ImpactFactor {
                 food_category: FoodCategory::Legumes,
@@ -225,6 +858,7 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
             ImpactFactor {
                 food_category: FoodCategory::Legumes,
@@ -244,6 +878,7 @@ impl DataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 3,
                 },
+                gsd: None,
             },
 
             // LIVESTOCK - Research-based emission factors
@@ -265,6 +900,7 @@ impl DataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 4,
                 },
+                gsd: None,
             },
             ImpactFactor {
                 food_category: FoodCategory::Meat,
@@ -284,6 +920,7 @@ impl DataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 3,
                 },
+                gsd: None,
             },
 
             // AQUACULTURE
@@ -305,6 +942,7 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 3,
                 },
+                gsd: None,
             },
             ImpactFactor {
                 food_category: FoodCategory::Fish,
@@ -324,9 +962,13 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
 
             // CASH CROPS
+            // Cradle-to-farm-gate average including establishment. A fallback once a cocoa
+            // food carries a PerennialCropProfile: the farm's Global warming then comes from
+            // its inventory, with establishment amortized over the plantation's lifetime.
             ImpactFactor {
                 food_category: FoodCategory::Other,
                 country: Country::Ghana,
@@ -345,6 +987,7 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
 
             // LAND USE - Biodiversity impacts
@@ -366,85 +1009,121 @@ impl DataLoader {
                     geographical_correlation: 2,
                     technological_correlation: 4,
                 },
+                gsd: None,
             },
-        ];
-
-        self.impact_factors.extend(ghana_factors);
-    }
 
-    fn add_nigeria_factors(&mut self) {
-        let nigeria_factors = vec![
-            // CEREALS
+            // VEGETABLES AND FRUITS - staples and export crops
             ImpactFactor {
-                food_category: FoodCategory::Cereals,
-                country: Country::Nigeria,
-                crop_type: Some("Rice".to_string()),
+                food_category: FoodCategory::Vegetables,
+                country: Country::Ghana,
+                crop_type: Some("Tomato".to_string()),
                 impact_category: "Global warming".to_string(),
-                value_per_kg: 2.5, // Research: Higher due to large-scale production
+                value_per_kg: 0.45, // Research: 0.25-0.8 kg CO2-eq/kg
                 unit: "kg CO2-eq".to_string(),
                 confidence: ConfidenceLevel::Medium,
-                source: "Nigeria agricultural GHG inventory 2021; IPCC AR6".to_string(),
+                source: "Clune et al. 2017 fresh produce GHG review; open-field, low input".to_string(),
                 year: 2024,
-                uncertainty_range: (1.8, 3.2),
+                uncertainty_range: (0.25, 0.8),
                 pedigree_score: PedigreeScore {
                     reliability: 3,
                     completeness: 3,
                     temporal_correlation: 2,
-                    geographical_correlation: 2,
+                    geographical_correlation: 3,
                     technological_correlation: 3,
                 },
+                gsd: None,
             },
             ImpactFactor {
-                food_category: FoodCategory::Cereals,
-                country: Country::Nigeria,
-                crop_type: Some("Sorghum".to_string()),
+                food_category: FoodCategory::Vegetables,
+                country: Country::Ghana,
+                crop_type: Some("Tomato".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 0.214, // Research: 214 L/kg, mostly rain-fed
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Ghana national average".to_string(),
+                year: 2024,
+                uncertainty_range: (0.15, 0.3),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 3,
+                    geographical_correlation: 2,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Vegetables,
+                country: Country::Ghana,
+                crop_type: Some("Tomato".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 1.3, // 10000 m2 / 7500 kg
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "MoFA SRID Ghana 2022 average yield (7.5 t/ha)".to_string(),
+                year: 2024,
+                uncertainty_range: (0.9, 2.0),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Vegetables,
+                country: Country::Ghana,
+                crop_type: Some("Pepper".to_string()),
                 impact_category: "Global warming".to_string(),
-                value_per_kg: 0.6, // Research: 0.3-0.8 kg CO2-eq/kg drought-tolerant
+                value_per_kg: 0.6, // Research: 0.35-1.0 kg CO2-eq/kg
                 unit: "kg CO2-eq".to_string(),
                 confidence: ConfidenceLevel::Medium,
-                source: "ICRISAT Nigeria; Drought-tolerant cereals study 2023".to_string(),
+                source: "Clune et al. 2017 fresh produce GHG review; open-field chilli".to_string(),
                 year: 2024,
-                uncertainty_range: (0.3, 0.8),
+                uncertainty_range: (0.35, 1.0),
                 pedigree_score: PedigreeScore {
                     reliability: 3,
                     completeness: 3,
                     temporal_correlation: 2,
-                    geographical_correlation: 1,
+                    geographical_correlation: 3,
                     technological_correlation: 3,
                 },
+                gsd: None,
             },
             ImpactFactor {
-                food_category: FoodCategory::Cereals,
-                country: Country::Nigeria,
-                crop_type: Some("Sorghum".to_string()),
+                food_category: FoodCategory::Vegetables,
+                country: Country::Ghana,
+                crop_type: Some("Pepper".to_string()),
                 impact_category: "Water consumption".to_string(),
-                value_per_kg: 3.048, // Research: 3,048 L/kg
+                value_per_kg: 0.379, // Research: 379 L/kg
                 unit: "m3".to_string(),
-                confidence: ConfidenceLevel::High,
-                source: "Water Footprint Network; Nigeria sorghum systems".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Ghana national average".to_string(),
                 year: 2024,
-                uncertainty_range: (2.5, 3.8),
+                uncertainty_range: (0.25, 0.5),
                 pedigree_score: PedigreeScore {
                     reliability: 2,
                     completeness: 2,
                     temporal_correlation: 3,
-                    geographical_correlation: 1,
+                    geographical_correlation: 2,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
-
-            // ROOTS - Nigeria is a major producer
             ImpactFactor {
-                food_category: FoodCategory::Roots,
-                country: Country::Nigeria,
-                crop_type: Some("Yam".to_string()),
-                impact_category: "Global warming".to_string(),
-                value_per_kg: 0.4, // Research: Low for root crops
-                unit: "kg CO2-eq".to_string(),
+                food_category: FoodCategory::Vegetables,
+                country: Country::Ghana,
+                crop_type: Some("Pepper".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 1.9, // 10000 m2 / 5300 kg
+                unit: "m2a crop-eq".to_string(),
                 confidence: ConfidenceLevel::High,
-                source: "IITA Nigeria yam research 2024; National yam inventory".to_string(),
+                source: "MoFA SRID Ghana 2022 average yield (5.3 t/ha)".to_string(),
                 year: 2024,
-                uncertainty_range: (0.3, 0.6),
+                uncertainty_range: (1.2, 3.0),
                 pedigree_score: PedigreeScore {
                     reliability: 2,
                     completeness: 2,
@@ -452,37 +1131,59 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
             ImpactFactor {
-                food_category: FoodCategory::Roots,
-                country: Country::Nigeria,
-                crop_type: Some("Yam".to_string()),
+                food_category: FoodCategory::Fruits,
+                country: Country::Ghana,
+                crop_type: Some("Plantain".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.35, // Research: 0.2-0.6 kg CO2-eq/kg
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Clune et al. 2017 fresh produce GHG review; low-input smallholder".to_string(),
+                year: 2024,
+                uncertainty_range: (0.2, 0.6),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 3,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Ghana,
+                crop_type: Some("Plantain".to_string()),
                 impact_category: "Water consumption".to_string(),
-                value_per_kg: 0.343, // Research: 343 L/kg - very efficient
+                value_per_kg: 0.79, // Research: 790 L/kg (banana and plantain)
                 unit: "m3".to_string(),
-                confidence: ConfidenceLevel::High,
-                source: "Water Footprint Network; Nigeria root crops study 2023".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Ghana national average".to_string(),
                 year: 2024,
-                uncertainty_range: (0.25, 0.45),
+                uncertainty_range: (0.6, 1.0),
                 pedigree_score: PedigreeScore {
                     reliability: 2,
                     completeness: 2,
-                    temporal_correlation: 2,
-                    geographical_correlation: 1,
+                    temporal_correlation: 3,
+                    geographical_correlation: 2,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
             ImpactFactor {
-                food_category: FoodCategory::Roots,
-                country: Country::Nigeria,
-                crop_type: Some("Cassava".to_string()),
-                impact_category: "Global warming".to_string(),
-                value_per_kg: 0.3, // Research: Very low emissions
-                unit: "kg CO2-eq".to_string(),
+                food_category: FoodCategory::Fruits,
+                country: Country::Ghana,
+                crop_type: Some("Plantain".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 0.9, // 10000 m2 / 11000 kg
+                unit: "m2a crop-eq".to_string(),
                 confidence: ConfidenceLevel::High,
-                source: "Nigeria cassava value chain LCA 2023; IITA research".to_string(),
+                source: "MoFA SRID Ghana 2022 average yield (11.0 t/ha)".to_string(),
                 year: 2024,
-                uncertainty_range: (0.2, 0.5),
+                uncertainty_range: (0.7, 1.3),
                 pedigree_score: PedigreeScore {
                     reliability: 2,
                     completeness: 2,
@@ -490,79 +1191,777 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
             ImpactFactor {
-                food_category: FoodCategory::Roots,
-                country: Country::Nigeria,
-                crop_type: Some("Cassava".to_string()),
+                food_category: FoodCategory::Fruits,
+                country: Country::Ghana,
+                crop_type: Some("Pineapple".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.4, // Research: 0.25-0.7 kg CO2-eq/kg
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Clune et al. 2017 fresh produce GHG review; MD2 export plantations, fertilized".to_string(),
+                year: 2024,
+                uncertainty_range: (0.25, 0.7),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 3,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Ghana,
+                crop_type: Some("Pineapple".to_string()),
                 impact_category: "Water consumption".to_string(),
-                value_per_kg: 0.564, // Research: 564 L/kg
+                value_per_kg: 0.255, // Research: 255 L/kg
                 unit: "m3".to_string(),
-                confidence: ConfidenceLevel::High,
-                source: "Water Footprint Network; Nigeria cassava systems 2023".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Ghana national average".to_string(),
                 year: 2024,
-                uncertainty_range: (0.4, 0.7),
+                uncertainty_range: (0.2, 0.35),
                 pedigree_score: PedigreeScore {
                     reliability: 2,
                     completeness: 2,
-                    temporal_correlation: 2,
-                    geographical_correlation: 1,
+                    temporal_correlation: 3,
+                    geographical_correlation: 2,
                     technological_correlation: 2,
                 },
+                gsd: None,
             },
-
-            // LIVESTOCK - Nigeria has extensive cattle systems
             ImpactFactor {
-                food_category: FoodCategory::Meat,
-                country: Country::Nigeria,
-                crop_type: Some("Cattle".to_string()),
-                impact_category: "Global warming".to_string(),
-                value_per_kg: 25.0, // Research: 20-50 kg CO2-eq/kg for extensive systems
-                unit: "kg CO2-eq".to_string(),
-                confidence: ConfidenceLevel::Low, // High variability
-                source: "Nigeria livestock GHG inventory 2021; Extensive systems research".to_string(),
+                food_category: FoodCategory::Fruits,
+                country: Country::Ghana,
+                crop_type: Some("Pineapple".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 0.2, // 10000 m2 / 50000 kg
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "MoFA SRID Ghana 2022 average yield (50.0 t/ha)".to_string(),
                 year: 2024,
-                uncertainty_range: (15.0, 40.0),
+                uncertainty_range: (0.15, 0.35),
                 pedigree_score: PedigreeScore {
-                    reliability: 4,
-                    completeness: 4,
-                    temporal_correlation: 2,
-                    geographical_correlation: 2,
-                    technological_correlation: 5,
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
                 },
+                gsd: None,
             },
-
-            // AQUACULTURE - Catfish dominant
             ImpactFactor {
-                food_category: FoodCategory::Fish,
-                country: Country::Nigeria,
-                crop_type: Some("Catfish".to_string()),
+                food_category: FoodCategory::Fruits,
+                country: Country::Ghana,
+                crop_type: Some("Mango".to_string()),
                 impact_category: "Global warming".to_string(),
-                value_per_kg: 4.5, // Research: Higher for intensive catfish systems
+                value_per_kg: 0.45, // Research: 0.25-0.8 kg CO2-eq/kg
                 unit: "kg CO2-eq".to_string(),
                 confidence: ConfidenceLevel::Medium,
-                source: "Nigeria aquaculture LCA 2023; Catfish systems study".to_string(),
+                source: "Clune et al. 2017 fresh produce GHG review; orchard, low input".to_string(),
                 year: 2024,
-                uncertainty_range: (3.0, 6.0),
+                uncertainty_range: (0.25, 0.8),
                 pedigree_score: PedigreeScore {
                     reliability: 3,
                     completeness: 3,
                     temporal_correlation: 2,
-                    geographical_correlation: 1,
+                    geographical_correlation: 3,
                     technological_correlation: 3,
                 },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Ghana,
+                crop_type: Some("Mango".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 1.8, // Research: 1800 L/kg - tree crop, mostly green water
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Ghana national average".to_string(),
+                year: 2024,
+                uncertainty_range: (1.2, 2.4),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 3,
+                    geographical_correlation: 2,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Ghana,
+                crop_type: Some("Mango".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 1.4, // 10000 m2 / 7000 kg
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "MoFA SRID Ghana 2022 average yield (7.0 t/ha)".to_string(),
+                year: 2024,
+                uncertainty_range: (0.9, 2.2),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+
+            // EGGS, OILS AND NUTS - layers, oil palm fruit, groundnuts and shea
+            ImpactFactor {
+                food_category: FoodCategory::Eggs,
+                country: Country::Ghana,
+                crop_type: Some("Layers".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 4.2, // Research: 3-6 kg CO2-eq/kg, imported maize-soy layer feed
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "FAO GLEAM 2.0 sub-Saharan Africa layers; Ghana poultry feed survey 2023".to_string(),
+                year: 2024,
+                uncertainty_range: (3.0, 6.0),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Eggs,
+                country: Country::Ghana,
+                crop_type: Some("Layers".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 0.2, // Rainfed feed crops; drinking and cleaning water
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::Low,
+                source: "Water Footprint Network chicken eggs; Ghana layer farm survey 2023".to_string(),
+                year: 2024,
+                uncertainty_range: (0.12, 0.35),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 4,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Oils,
+                country: Country::Ghana,
+                crop_type: Some("Oil palm".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.25, // Per kg fresh fruit bunches, smallholder groves without land use change
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "RSPO PalmGHG smallholder defaults; CSIR-OPRI Ghana 2021".to_string(),
+                year: 2024,
+                uncertainty_range: (0.15, 0.45),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Oils,
+                country: Country::Ghana,
+                crop_type: Some("Oil palm".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 1.7, // Research: ~6 t FFB/ha smallholder yields
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "MoFA SRID Ghana agricultural statistics 2023".to_string(),
+                year: 2024,
+                uncertainty_range: (1.2, 2.5),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 2,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Nuts,
+                country: Country::Ghana,
+                crop_type: Some("Groundnuts".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 1.6, // Research: 1.1-2.4 kg CO2-eq/kg unshelled, low fertilizer use
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "CSIR-SARI groundnut trials 2022; IPCC 2019 Tier 1 field N2O".to_string(),
+                year: 2024,
+                uncertainty_range: (1.1, 2.4),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Nuts,
+                country: Country::Ghana,
+                crop_type: Some("Groundnuts".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 2.782, // Research: 2,782 L/kg
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Water Footprint Network; CSIR-CRI 2023".to_string(),
+                year: 2024,
+                uncertainty_range: (2.2, 3.4),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Nuts,
+                country: Country::Ghana,
+                crop_type: Some("Shea".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.35, // Wild parkland trees: collection and parboiling of kernels on firewood
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Low,
+                source: "Northern Ghana shea value chain studies 2022; IPCC 2006 wood fuel".to_string(),
+                year: 2024,
+                uncertainty_range: (0.15, 0.7),
+                pedigree_score: PedigreeScore {
+                    reliability: 4,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+        ];
+
+        factors.extend(ghana_factors);
+    }
+
+    fn add_nigeria_factors(factors: &mut Vec<ImpactFactor>) {
+        let nigeria_factors = vec![
+            // CEREALS
+            ImpactFactor {
+                food_category: FoodCategory::Cereals,
+                country: Country::Nigeria,
+                crop_type: Some("Rice".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 2.5, // Research: Higher due to large-scale production
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Nigeria agricultural GHG inventory 2021; IPCC AR6".to_string(),
+                year: 2024,
+                uncertainty_range: (1.8, 3.2),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Cereals,
+                country: Country::Nigeria,
+                crop_type: Some("Sorghum".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.6, // Research: 0.3-0.8 kg CO2-eq/kg drought-tolerant
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "ICRISAT Nigeria; Drought-tolerant cereals study 2023".to_string(),
+                year: 2024,
+                uncertainty_range: (0.3, 0.8),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 1,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Cereals,
+                country: Country::Nigeria,
+                crop_type: Some("Sorghum".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 3.048, // Research: 3,048 L/kg
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "Water Footprint Network; Nigeria sorghum systems".to_string(),
+                year: 2024,
+                uncertainty_range: (2.5, 3.8),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 3,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+
+            // ROOTS - Nigeria is a major producer
+            ImpactFactor {
+                food_category: FoodCategory::Roots,
+                country: Country::Nigeria,
+                crop_type: Some("Yam".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.4, // Research: Low for root crops
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "IITA Nigeria yam research 2024; National yam inventory".to_string(),
+                year: 2024,
+                uncertainty_range: (0.3, 0.6),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Roots,
+                country: Country::Nigeria,
+                crop_type: Some("Yam".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 0.343, // Research: 343 L/kg - very efficient
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "Water Footprint Network; Nigeria root crops study 2023".to_string(),
+                year: 2024,
+                uncertainty_range: (0.25, 0.45),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 2,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Roots,
+                country: Country::Nigeria,
+                crop_type: Some("Cassava".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.3, // Research: Very low emissions
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "Nigeria cassava value chain LCA 2023; IITA research".to_string(),
+                year: 2024,
+                uncertainty_range: (0.2, 0.5),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Roots,
+                country: Country::Nigeria,
+                crop_type: Some("Cassava".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 0.564, // Research: 564 L/kg
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "Water Footprint Network; Nigeria cassava systems 2023".to_string(),
+                year: 2024,
+                uncertainty_range: (0.4, 0.7),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 2,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+
+            // LIVESTOCK - Nigeria has extensive cattle systems
+            ImpactFactor {
+                food_category: FoodCategory::Meat,
+                country: Country::Nigeria,
+                crop_type: Some("Cattle".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 25.0, // Research: 20-50 kg CO2-eq/kg for extensive systems
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Low, // High variability
+                source: "Nigeria livestock GHG inventory 2021; Extensive systems research".to_string(),
+                year: 2024,
+                uncertainty_range: (15.0, 40.0),
+                pedigree_score: PedigreeScore {
+                    reliability: 4,
+                    completeness: 4,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 5,
+                },
+                gsd: None,
+            },
+
+            // AQUACULTURE - Catfish dominant
+            ImpactFactor {
+                food_category: FoodCategory::Fish,
+                country: Country::Nigeria,
+                crop_type: Some("Catfish".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 4.5, // Research: Higher for intensive catfish systems
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Nigeria aquaculture LCA 2023; Catfish systems study".to_string(),
+                year: 2024,
+                uncertainty_range: (3.0, 6.0),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 1,
+                    technological_correlation: 3,
+                },
+                gsd: None,
             },
             ImpactFactor {
                 food_category: FoodCategory::Fish,
                 country: Country::Nigeria,
-                crop_type: Some("Catfish".to_string()),
+                crop_type: Some("Catfish".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 0.007, // Research: 6.0-8.0 L/kg intensive systems
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "Nigeria aquaculture water assessment 2024".to_string(),
+                year: 2024,
+                uncertainty_range: (0.006, 0.008),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+
+            // VEGETABLES AND FRUITS - staples
+            ImpactFactor {
+                food_category: FoodCategory::Vegetables,
+                country: Country::Nigeria,
+                crop_type: Some("Tomato".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.5, // Research: 0.3-0.9 kg CO2-eq/kg
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Clune et al. 2017 fresh produce GHG review; dry-season irrigated north".to_string(),
+                year: 2024,
+                uncertainty_range: (0.3, 0.9),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 3,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Vegetables,
+                country: Country::Nigeria,
+                crop_type: Some("Tomato".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 0.25, // Research: more irrigation than in Ghana
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Nigeria national average".to_string(),
+                year: 2024,
+                uncertainty_range: (0.18, 0.35),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 3,
+                    geographical_correlation: 2,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Vegetables,
+                country: Country::Nigeria,
+                crop_type: Some("Tomato".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 1.6, // 10000 m2 / 6200 kg
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "FAOSTAT 2022 Nigeria average yield (6.2 t/ha)".to_string(),
+                year: 2024,
+                uncertainty_range: (1.0, 2.5),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Vegetables,
+                country: Country::Nigeria,
+                crop_type: Some("Pepper".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.65, // Research: 0.4-1.1 kg CO2-eq/kg
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Clune et al. 2017 fresh produce GHG review; open-field chilli and sweet pepper".to_string(),
+                year: 2024,
+                uncertainty_range: (0.4, 1.1),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 3,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Vegetables,
+                country: Country::Nigeria,
+                crop_type: Some("Pepper".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 0.4, // Research: 400 L/kg
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Nigeria national average".to_string(),
+                year: 2024,
+                uncertainty_range: (0.28, 0.55),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 3,
+                    geographical_correlation: 2,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Vegetables,
+                country: Country::Nigeria,
+                crop_type: Some("Pepper".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 2.2, // 10000 m2 / 4500 kg
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "FAOSTAT 2022 Nigeria average yield (4.5 t/ha)".to_string(),
+                year: 2024,
+                uncertainty_range: (1.4, 3.3),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Nigeria,
+                crop_type: Some("Plantain".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.35, // Research: 0.2-0.6 kg CO2-eq/kg
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Clune et al. 2017 fresh produce GHG review; low-input smallholder".to_string(),
+                year: 2024,
+                uncertainty_range: (0.2, 0.6),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 3,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Nigeria,
+                crop_type: Some("Plantain".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 0.8, // Research: 800 L/kg (banana and plantain)
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Nigeria national average".to_string(),
+                year: 2024,
+                uncertainty_range: (0.6, 1.0),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 3,
+                    geographical_correlation: 2,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Nigeria,
+                crop_type: Some("Plantain".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 1.2, // 10000 m2 / 8300 kg
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "FAOSTAT 2022 Nigeria average yield (8.3 t/ha)".to_string(),
+                year: 2024,
+                uncertainty_range: (0.8, 1.7),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Nigeria,
+                crop_type: Some("Pineapple".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.35, // Research: 0.2-0.6 kg CO2-eq/kg
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Clune et al. 2017 fresh produce GHG review; smallholder, low input".to_string(),
+                year: 2024,
+                uncertainty_range: (0.2, 0.6),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 3,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Nigeria,
+                crop_type: Some("Pineapple".to_string()),
                 impact_category: "Water consumption".to_string(),
-                value_per_kg: 0.007, // Research: 6.0-8.0 L/kg intensive systems
+                value_per_kg: 0.255, // Research: 255 L/kg
                 unit: "m3".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Nigeria national average".to_string(),
+                year: 2024,
+                uncertainty_range: (0.2, 0.35),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 3,
+                    geographical_correlation: 2,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Nigeria,
+                crop_type: Some("Pineapple".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 0.4, // 10000 m2 / 25000 kg
+                unit: "m2a crop-eq".to_string(),
                 confidence: ConfidenceLevel::High,
-                source: "Nigeria aquaculture water assessment 2024".to_string(),
+                source: "FAOSTAT 2022 Nigeria average yield (25.0 t/ha)".to_string(),
                 year: 2024,
-                uncertainty_range: (0.006, 0.008),
+                uncertainty_range: (0.25, 0.6),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 1,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Nigeria,
+                crop_type: Some("Mango".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.4, // Research: 0.25-0.7 kg CO2-eq/kg
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Clune et al. 2017 fresh produce GHG review; orchard, low input".to_string(),
+                year: 2024,
+                uncertainty_range: (0.25, 0.7),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 3,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Nigeria,
+                crop_type: Some("Mango".to_string()),
+                impact_category: "Water consumption".to_string(),
+                value_per_kg: 1.8, // Research: 1800 L/kg - tree crop, mostly green water
+                unit: "m3".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "Mekonnen & Hoekstra 2011 (Water Footprint Network), Nigeria national average".to_string(),
+                year: 2024,
+                uncertainty_range: (1.2, 2.4),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 3,
+                    geographical_correlation: 2,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Fruits,
+                country: Country::Nigeria,
+                crop_type: Some("Mango".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 1.6, // 10000 m2 / 6300 kg
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::High,
+                source: "FAOSTAT 2022 Nigeria average yield (6.3 t/ha)".to_string(),
+                year: 2024,
+                uncertainty_range: (1.0, 2.5),
                 pedigree_score: PedigreeScore {
                     reliability: 2,
                     completeness: 2,
@@ -570,13 +1969,136 @@ impl DataLoader {
                     geographical_correlation: 1,
                     technological_correlation: 2,
                 },
+                gsd: None,
+            },
+
+            // EGGS, OILS AND NUTS - layers, oil palm fruit, groundnuts and shea
+            ImpactFactor {
+                food_category: FoodCategory::Eggs,
+                country: Country::Nigeria,
+                crop_type: Some("Layers".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 4.6, // Research: 3.2-6.5 kg CO2-eq/kg, battery cages on grid and diesel power
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "FAO GLEAM 2.0 sub-Saharan Africa layers; Nigeria poultry sector review 2022".to_string(),
+                year: 2024,
+                uncertainty_range: (3.2, 6.5),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Oils,
+                country: Country::Nigeria,
+                crop_type: Some("Oil palm".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.3, // Per kg fresh fruit bunches, semi-wild groves
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Low,
+                source: "RSPO PalmGHG smallholder defaults; NIFOR Nigeria 2021".to_string(),
+                year: 2024,
+                uncertainty_range: (0.18, 0.55),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 4,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Oils,
+                country: Country::Nigeria,
+                crop_type: Some("Oil palm".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 2.5, // Research: ~4 t FFB/ha in semi-wild groves
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "NBS Nigeria agricultural survey 2022".to_string(),
+                year: 2024,
+                uncertainty_range: (1.7, 3.5),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 2,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Nuts,
+                country: Country::Nigeria,
+                crop_type: Some("Groundnuts".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 1.9, // Research: lower savanna yields than Ghana
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "ICRISAT Nigeria groundnut studies 2021; IPCC 2019 Tier 1 field N2O".to_string(),
+                year: 2024,
+                uncertainty_range: (1.3, 2.8),
+                pedigree_score: PedigreeScore {
+                    reliability: 3,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Nuts,
+                country: Country::Nigeria,
+                crop_type: Some("Groundnuts".to_string()),
+                impact_category: "Land use".to_string(),
+                value_per_kg: 9.1, // Research: ~1.1 t/ha
+                unit: "m2a crop-eq".to_string(),
+                confidence: ConfidenceLevel::Medium,
+                source: "NBS Nigeria agricultural survey 2022".to_string(),
+                year: 2024,
+                uncertainty_range: (7.0, 12.0),
+                pedigree_score: PedigreeScore {
+                    reliability: 2,
+                    completeness: 2,
+                    temporal_correlation: 2,
+                    geographical_correlation: 1,
+                    technological_correlation: 2,
+                },
+                gsd: None,
+            },
+            ImpactFactor {
+                food_category: FoodCategory::Nuts,
+                country: Country::Nigeria,
+                crop_type: Some("Shea".to_string()),
+                impact_category: "Global warming".to_string(),
+                value_per_kg: 0.4, // Wild parkland trees: collection and parboiling of kernels on firewood
+                unit: "kg CO2-eq".to_string(),
+                confidence: ConfidenceLevel::Low,
+                source: "Nigeria shea value chain studies 2022; IPCC 2006 wood fuel".to_string(),
+                year: 2024,
+                uncertainty_range: (0.15, 0.8),
+                pedigree_score: PedigreeScore {
+                    reliability: 4,
+                    completeness: 3,
+                    temporal_correlation: 2,
+                    geographical_correlation: 2,
+                    technological_correlation: 3,
+                },
+                gsd: None,
             },
         ];
 
-        self.impact_factors.extend(nigeria_factors);
+        factors.extend(nigeria_factors);
     }
 
-    fn add_global_factors(&mut self) {
+    fn add_global_factors(factors: &mut Vec<ImpactFactor>) {
         // Updated global factors with IPCC AR6 and latest research
         let global_factors = vec![
             // Global cereals with updated values
@@ -598,90 +2120,144 @@ impl DataLoader {
                     geographical_correlation: 5,
                     technological_correlation: 4,
                 },
+                gsd: None,
             },
             // Add more global factors...
         ];
 
-        self.impact_factors.extend(global_factors);
+        factors.extend(global_factors);
     }
 
     // Enhanced parsing functions with better error handling
-    pub fn load_from_csv(&mut self, file_path: &str) -> Result<(), Box<dyn Error>> {
-        let mut reader = Reader::from_path(file_path)?;
-        
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from_csv(&mut self, file_path: &str) -> Result<(), DataLoadError> {
+        let unreadable = |e: csv::Error| DataLoadError::Unreadable { path: file_path.to_string(), reason: e.to_string() };
+        let mut reader = Reader::from_path(file_path).map_err(unreadable)?;
+
         for result in reader.records() {
-            let record = result?;
-            
+            let record = result.map_err(unreadable)?;
+
             if record.len() < 12 { // Updated for new fields
                 continue; // Skip incomplete records
             }
-            
-            let impact_factor = ImpactFactor {
-                food_category: self.parse_food_category(&record[0])?,
-                country: self.parse_country(&record[1])?,
+            let fields = CsvRecord { path: file_path, record: &record };
+            let pedigree = |index: usize| record.get(index).and_then(|s| s.parse().ok()).unwrap_or(5);
+
+            let mut impact_factor = ImpactFactor {
+                food_category: fields.one_of(0, "food category", FOOD_CATEGORIES)?,
+                country: fields.one_of(1, "country", &[
+                    ("Ghana", Country::Ghana),
+                    ("Nigeria", Country::Nigeria),
+                    ("Global", Country::Global),
+                ])?,
                 crop_type: if record[2].is_empty() { None } else { Some(record[2].to_string()) },
                 impact_category: record[3].to_string(),
-                value_per_kg: record[4].parse()?,
+                value_per_kg: fields.number(4, "value_per_kg")?,
                 unit: record[5].to_string(),
-                confidence: self.parse_confidence(&record[6])?,
+                confidence: fields.one_of(6, "confidence level", &[
+                    ("High", ConfidenceLevel::High),
+                    ("Medium", ConfidenceLevel::Medium),
+                    ("Low", ConfidenceLevel::Low),
+                ])?,
                 source: record[7].to_string(),
-                year: record[8].parse()?,
-                uncertainty_range: (record[9].parse()?, record[10].parse()?),
+                year: fields.number(8, "year")?,
+                uncertainty_range: (fields.number(9, "uncertainty_low")?, fields.number(10, "uncertainty_high")?),
                 pedigree_score: PedigreeScore {
-                    reliability: record[11].parse().unwrap_or(5),
-                    completeness: record[12].parse().unwrap_or(5),
-                    temporal_correlation: record[13].parse().unwrap_or(5),
-                    geographical_correlation: record[14].parse().unwrap_or(5),
-                    technological_correlation: record[15].parse().unwrap_or(5),
+                    reliability: pedigree(11),
+                    completeness: pedigree(12),
+                    temporal_correlation: pedigree(13),
+                    geographical_correlation: pedigree(14),
+                    technological_correlation: pedigree(15),
+                },
+                gsd: match record.get(16) {
+                    Some(gsd) if !gsd.trim().is_empty() => Some(fields.number(16, "gsd")?),
+                    _ => None,
                 },
             };
-            
-            self.impact_factors.push(impact_factor);
+            normalize_factor_unit(&mut impact_factor).map_err(|error| DataLoadError::Unit {
+                path: file_path.to_string(),
+                line: fields.line(),
+                error,
+            })?;
+
+            Arc::make_mut(&mut self.impact_factors).push(impact_factor);
         }
+
+        Self::log_unit_warnings(&self.impact_factors);
         
         Ok(())
     }
 
-    // Existing parsing functions remain the same...
-    fn parse_food_category(&self, s: &str) -> Result<FoodCategory, Box<dyn Error>> {
-        match s {
-            "Cereals" => Ok(FoodCategory::Cereals),
-            "Legumes" => Ok(FoodCategory::Legumes),
-            "Vegetables" => Ok(FoodCategory::Vegetables),
-            "Fruits" => Ok(FoodCategory::Fruits),
-            "Meat" => Ok(FoodCategory::Meat),
-            "Poultry" => Ok(FoodCategory::Poultry),
-            "Fish" => Ok(FoodCategory::Fish),
-            "Dairy" => Ok(FoodCategory::Dairy),
-            "Eggs" => Ok(FoodCategory::Eggs),
-            "Oils" => Ok(FoodCategory::Oils),
-            "Nuts" => Ok(FoodCategory::Nuts),
-            "Roots" => Ok(FoodCategory::Roots),
-            "Other" => Ok(FoodCategory::Other),
-            _ => Err(format!("Unknown food category: {}", s).into()),
+    /// Add climate adjustment rules from a CSV file with the columns zone, food_category,
+    /// crop_type, impact_category, factor, source (empty food category or crop matches any).
+    /// They are searched before the rules already loaded.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_climate_adjustment_rules_from_csv(&mut self, file_path: &str) -> Result<(), DataLoadError> {
+        use AgroEcologicalZone::*;
+        let unreadable = |e: csv::Error| DataLoadError::Unreadable { path: file_path.to_string(), reason: e.to_string() };
+        let mut reader = Reader::from_path(file_path).map_err(unreadable)?;
+
+        let mut rules = Vec::new();
+        for result in reader.records() {
+            let record = result.map_err(unreadable)?;
+            if record.len() < 6 {
+                continue; // Skip incomplete records
+            }
+            let fields = CsvRecord { path: file_path, record: &record };
+
+            rules.push(ClimateAdjustmentRule {
+                zone: fields.one_of(0, "zone", &[
+                    ("Sahel", Sahel),
+                    ("SudanSavannah", SudanSavannah),
+                    ("GuineaSavannah", GuineaSavannah),
+                    ("CoastalSavannah", CoastalSavannah),
+                    ("DerivedSavannah", DerivedSavannah),
+                    ("SemiDeciduousForest", SemiDeciduousForest),
+                    ("HighRainforest", HighRainforest),
+                    ("MangroveSwamp", MangroveSwamp),
+                ])?,
+                food_category: if record[1].is_empty() { None } else { Some(fields.one_of(1, "food category", FOOD_CATEGORIES)?) },
+                crop_type: if record[2].is_empty() { None } else { Some(record[2].to_string()) },
+                impact_category: record[3].to_string(),
+                factor: fields.number(4, "factor")?,
+                source: record[5].to_string(),
+            });
         }
+        self.climate_adjustment_rules.splice(0..0, rules);
+
+        Ok(())
     }
 
-    fn parse_country(&self, s: &str) -> Result<Country, Box<dyn Error>> {
-        match s {
-            "Ghana" => Ok(Country::Ghana),
-            "Nigeria" => Ok(Country::Nigeria),
-            "Global" => Ok(Country::Global),
-            _ => Err(format!("Unknown country: {}", s).into()),
-        }
+    pub fn get_factors(&self) -> &Vec<ImpactFactor> {
+        &self.impact_factors
     }
 
-    fn parse_confidence(&self, s: &str) -> Result<ConfidenceLevel, Box<dyn Error>> {
-        match s {
-            "High" => Ok(ConfidenceLevel::High),
-            "Medium" => Ok(ConfidenceLevel::Medium),
-            "Low" => Ok(ConfidenceLevel::Low),
-            _ => Err(format!("Unknown confidence level: {}", s).into()),
+    /// Crops with factors of their own for `country`, by food category and name. Crops left
+    /// out fall back to category or global defaults.
+    pub fn available_crops(&self, country: &Country) -> Vec<(FoodCategory, String)> {
+        let mut crops: Vec<(FoodCategory, String)> = Vec::new();
+        for factor in self.impact_factors.iter().filter(|factor| &factor.country == country) {
+            let Some(crop) = &factor.crop_type else { continue };
+            let listed = crops.iter().any(|(category, name)| {
+                name == crop && *category == factor.food_category
+            });
+            if !listed {
+                crops.push((factor.food_category, crop.clone()));
+            }
         }
+        crops.sort_by_cached_key(|(category, name)| (serialized_name(category), name.clone()));
+        crops
     }
 
-    pub fn get_factors(&self) -> &Vec<ImpactFactor> {
-        &self.impact_factors
+    /// Impact categories with a factor for `food_category` in `country`, for any crop
+    pub fn available_categories(&self, country: &Country, food_category: &FoodCategory) -> Vec<String> {
+        let mut categories: Vec<String> = self.impact_factors.iter()
+            .filter(|factor| &factor.country == country && &factor.food_category == food_category)
+            .map(|factor| factor.impact_category.clone())
+            .collect();
+        categories.sort();
+        categories.dedup();
+        categories
     }
 
     pub fn get_regional_factor(&self, key: &str) -> Option<f64> {
@@ -691,4 +2267,364 @@ impl DataLoader {
     pub fn get_climate_adjustment(&self, key: &str) -> Option<f64> {
         self.climate_adjustments.get(key).copied()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const FOOD_CATEGORIES: &[(&str, FoodCategory)] = &[
+    ("Cereals", FoodCategory::Cereals),
+    ("Legumes", FoodCategory::Legumes),
+    ("Vegetables", FoodCategory::Vegetables),
+    ("Fruits", FoodCategory::Fruits),
+    ("Meat", FoodCategory::Meat),
+    ("Poultry", FoodCategory::Poultry),
+    ("Fish", FoodCategory::Fish),
+    ("Dairy", FoodCategory::Dairy),
+    ("Eggs", FoodCategory::Eggs),
+    ("Oils", FoodCategory::Oils),
+    ("Nuts", FoodCategory::Nuts),
+    ("Roots", FoodCategory::Roots),
+    ("Other", FoodCategory::Other),
+];
+
+/// One record of a factor file, for errors that name the file, line and column
+#[cfg(not(target_arch = "wasm32"))]
+struct CsvRecord<'a> {
+    path: &'a str,
+    record: &'a csv::StringRecord,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl CsvRecord<'_> {
+    fn line(&self) -> u64 {
+        self.record.position().map_or(0, |position| position.line())
+    }
+
+    fn one_of<T: Clone>(&self, index: usize, column: &'static str, options: &[(&str, T)]) -> Result<T, DataLoadError> {
+        let value = &self.record[index];
+        options.iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, option)| option.clone())
+            .ok_or_else(|| DataLoadError::UnknownValue {
+                path: self.path.to_string(),
+                line: self.line(),
+                column,
+                value: value.to_string(),
+                valid: options.iter().map(|(name, _)| name.to_string()).collect(),
+            })
+    }
+
+    fn number<T: std::str::FromStr>(&self, index: usize, column: &'static str) -> Result<T, DataLoadError> {
+        let value = &self.record[index];
+        value.parse().map_err(|_| DataLoadError::NotANumber {
+            path: self.path.to_string(),
+            line: self.line(),
+            column,
+            value: value.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn water_factor(value_per_kg: f64, unit: &str) -> ImpactFactor {
+        ImpactFactor {
+            food_category: FoodCategory::Fish,
+            country: Country::Ghana,
+            crop_type: Some("Tilapia".to_string()),
+            impact_category: "Water consumption".to_string(),
+            value_per_kg,
+            unit: unit.to_string(),
+            confidence: ConfidenceLevel::Medium,
+            source: "Test".to_string(),
+            year: 2024,
+            uncertainty_range: (value_per_kg * 0.5, value_per_kg * 1.5),
+            pedigree_score: PedigreeScore {
+                reliability: 2,
+                completeness: 2,
+                temporal_correlation: 2,
+                geographical_correlation: 2,
+                technological_correlation: 2,
+            },
+            gsd: None,
+        }
+    }
+
+    #[test]
+    fn default_factors_are_unit_consistent() {
+        let mut loader = DataLoader::new();
+        loader.load_default_factors().unwrap();
+        assert_eq!(validate_factor_units(loader.get_factors()), vec![]);
+    }
+
+    #[test]
+    fn loaders_share_the_default_factors_until_one_adds_to_them() {
+        let loaders: Vec<DataLoader> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|_| scope.spawn(|| {
+                let mut loader = DataLoader::new();
+                loader.load_default_factors().unwrap();
+                loader
+            })).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        assert!(loaders.iter().all(|loader| Arc::ptr_eq(&loader.impact_factors, &loaders[0].impact_factors)));
+
+        // Loading the defaults after factors of its own appends them to a copy
+        let mut own = load("Cereals,Ghana,Maize,Global warming,0.6,kg CO2-eq,High,Survey,2023,0.4,0.8,2\n").unwrap();
+        own.load_default_factors().unwrap();
+        assert_eq!(own.get_factors().len(), loaders[0].get_factors().len() + 1);
+        assert_eq!(own.get_factors()[0].source, "Survey");
+        assert!(!Arc::ptr_eq(&own.impact_factors, &loaders[0].impact_factors));
+    }
+
+    #[test]
+    fn ghana_crops_are_listed_and_others_fall_back() {
+        let mut loader = DataLoader::new();
+        loader.load_default_factors().unwrap();
+
+        let crops: Vec<String> = loader.available_crops(&Country::Ghana).into_iter()
+            .map(|(category, name)| format!("{:?} {}", category, name))
+            .collect();
+        assert_eq!(crops, [
+            "Cereals Maize", "Cereals Millet", "Cereals Rice",
+            "Eggs Layers",
+            "Fish Tilapia",
+            "Fruits Mango", "Fruits Pineapple", "Fruits Plantain",
+            "Legumes Cowpea", "Legumes Groundnuts",
+            "Meat Cattle", "Meat Goat",
+            "Nuts Groundnuts", "Nuts Shea",
+            "Oils Oil palm",
+            "Other Cocoa",
+            "Vegetables Pepper", "Vegetables Tomato",
+        ]);
+        assert!(loader.available_crops(&Country::Nigeria).iter().any(|(_, name)| name == "Cassava"));
+
+        assert_eq!(
+            loader.available_categories(&Country::Ghana, &FoodCategory::Cereals),
+            ["Biodiversity loss", "Global warming", "Water consumption"]
+        );
+        assert!(loader.available_categories(&Country::Ghana, &FoodCategory::Roots).is_empty());
+    }
+
+    fn crop(name: &str, category: FoodCategory, quantity_kg: f64, area_ha: f64) -> FoodItem {
+        serde_json::from_value(serde_json::json!({
+            "id": name.to_lowercase(), "name": name, "quantity_kg": quantity_kg,
+            "category": category, "area_allocated": area_ha
+        })).unwrap()
+    }
+
+    #[test]
+    fn implausibly_high_yield_is_flagged() {
+        // 100 t/ha of maize: tonnes entered as kg
+        let warnings = plausibility_check(&[crop("Maize", FoodCategory::Cereals, 10000.0, 0.1)], None);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, CalculationWarningKind::ImplausiblyHighYield);
+        assert_eq!(warnings[0].food_id, "maize");
+        assert!(warnings[0].message.contains("100.00 t/ha"));
+
+        // Fine for cassava, and for maize on a hectare
+        assert!(plausibility_check(&[
+            crop("Cassava", FoodCategory::Roots, 10000.0, 0.5),
+            crop("Maize", FoodCategory::Cereals, 10000.0, 4.0),
+        ], None).is_empty());
+    }
+
+    #[test]
+    fn duplicate_foods_are_merged() {
+        let mut late = crop("Maize", FoodCategory::Cereals, 3000.0, 1.0);
+        late.post_harvest_losses = Some(20.0);
+        let mut early = crop("Maize", FoodCategory::Cereals, 1000.0, 0.5);
+        early.post_harvest_losses = Some(10.0);
+        let cowpea = crop("Cowpea", FoodCategory::Legumes, 400.0, 0.5);
+
+        let (foods, warnings) = merge_duplicate_foods(vec![early.clone(), cowpea.clone(), late]);
+        assert_eq!(foods.len(), 2);
+        assert_eq!((foods[0].quantity_kg, foods[0].area_allocated), (4000.0, Some(1.5)));
+        assert_eq!(foods[0].post_harvest_losses, Some(17.5));
+        assert_eq!(warnings, ["Maize (id maize) was listed 2 times - merged into one entry of 4000 kg on 1.5 ha"]);
+
+        // Half an area is no area; different crop types are different foods
+        let unmeasured = FoodItem { area_allocated: None, ..early.clone() };
+        let (foods, _) = merge_duplicate_foods(vec![early.clone(), unmeasured]);
+        assert_eq!(foods[0].area_allocated, None);
+        let white = FoodItem { crop_type: Some("White maize".to_string()), ..early.clone() };
+        let (foods, warnings) = merge_duplicate_foods(vec![early, white]);
+        assert_eq!((foods.len(), warnings.len()), (2, 0));
+    }
+
+    #[test]
+    fn implausibly_low_yield_is_flagged() {
+        // 25 kg/ha of cassava, 80 kg/ha of an unlisted root crop
+        let warnings = plausibility_check(&[
+            crop("Cassava", FoodCategory::Roots, 50.0, 2.0),
+            crop("Taro", FoodCategory::Roots, 80.0, 1.0),
+        ], None);
+        let kinds: Vec<_> = warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, vec![CalculationWarningKind::ImplausiblyLowYield; 2]);
+
+        // Livestock and foods without an area are not checked
+        assert!(plausibility_check(&[
+            crop("Beef", FoodCategory::Meat, 50.0, 2.0),
+            FoodItem { area_allocated: None, ..crop("Maize", FoodCategory::Cereals, 1.0, 1.0) },
+        ], None).is_empty());
+    }
+
+    fn herd(name: &str, quantity_kg: f64, head_count: u32) -> FoodItem {
+        FoodItem { head_count: Some(head_count), area_allocated: None, ..crop(name, FoodCategory::Meat, quantity_kg, 1.0) }
+    }
+
+    #[test]
+    fn livestock_output_is_checked_against_carcass_weights() {
+        // 500 head of cattle at 150 kg carcass each, and 100 goats at 15 kg
+        assert!(plausibility_check(&[herd("Beef", 75_000.0, 500), herd("Goat", 1_500.0, 100)], None).is_empty());
+
+        // 1,500 kg a head: tonnes of live weight, or a head count missing a zero
+        let warnings = plausibility_check(&[herd("Beef", 750_000.0, 500)], None);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, CalculationWarningKind::ImplausibleOutputPerHead);
+        assert_eq!(warnings[0].food_id, "beef");
+        assert!(warnings[0].message.contains("1500.0 kg per animal, outside the plausible 80-350 kg"), "{}", warnings[0].message);
+    }
+
+    fn fertilized(fertilizer_type: &str, application_rate: f64) -> ManagementPractices {
+        let mut practices = ManagementPractices::baseline();
+        practices.fertilization = Some(FertilizationPractices {
+            uses_fertilizers: true,
+            fertilizer_applications: vec![FertilizerApplication {
+                fertilizer_type: fertilizer_type.to_string(),
+                npk_ratio: None,
+                application_rate,
+                applications_per_season: 1,
+                cost: None,
+                currency: None,
+                treated_area_ha: None,
+            }],
+            soil_test_based: false,
+            follows_nutrient_plan: false,
+        });
+        practices
+    }
+
+    #[test]
+    fn nitrogen_above_the_agronomic_maximum_is_flagged() {
+        let maize = crop("Maize", FoodCategory::Cereals, 2000.0, 1.0);
+        let cowpea = crop("Cowpea", FoodCategory::Legumes, 500.0, 0.5);
+
+        // 200 kg urea is 92 kg N/ha: fine for maize, too much for cowpea on its own
+        assert!(plausibility_check(&[maize.clone(), cowpea.clone()], Some(&fertilized("Urea", 200.0))).is_empty());
+        let warnings = plausibility_check(std::slice::from_ref(&cowpea), Some(&fertilized("Urea", 200.0)));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, CalculationWarningKind::NitrogenAboveAgronomicMaximum);
+        assert!(warnings[0].message.starts_with("Cowpea: fertilizers supply 92 kg N/ha"), "{}", warnings[0].message);
+
+        // 600 kg urea is 276 kg N/ha, beyond even maize
+        let warnings = plausibility_check(&[maize, cowpea], Some(&fertilized("Urea", 600.0)));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].food_id, "maize");
+        assert!(warnings[0].message.contains("above the plausible 0-200 kg N/ha"), "{}", warnings[0].message);
+    }
+
+    #[test]
+    fn water_use_beyond_the_climate_is_flagged() {
+        let maize = crop("Maize", FoodCategory::Cereals, 2000.0, 1.0);
+        assert!(water_use_check(&maize, 1.5).is_none());
+
+        // 8 m3/kg on 2 t/ha is 16,000 m3/ha, 1,600 mm in one maize season
+        let warning = water_use_check(&maize, 8.0).expect("water warning");
+        assert_eq!(warning.kind, CalculationWarningKind::ImplausibleWaterUse);
+        assert!(warning.message.contains("16000 m3/ha, outside the plausible 0-10000 m3/ha"), "{}", warning.message);
+
+        // Paddy rice may take that much; an implausible yield gets the yield warning instead
+        assert!(water_use_check(&crop("Rice", FoodCategory::Cereals, 3000.0, 1.0), 5.0).is_none());
+        assert!(water_use_check(&crop("Maize", FoodCategory::Cereals, 200_000.0, 1.0), 8.0).is_none());
+    }
+
+    #[test]
+    fn wrong_unit_and_litre_magnitude_are_flagged() {
+        let warnings = validate_factor_units(&[water_factor(3500.0, "L")]);
+        let kinds: Vec<_> = warnings.iter().map(|w| w.kind.clone()).collect();
+        assert_eq!(kinds, vec![UnitWarningKind::UnitMismatch, UnitWarningKind::ImplausibleMagnitude]);
+        assert!(warnings[0].factor.contains("Tilapia"));
+
+        assert!(validate_factor_units(&[water_factor(3.5, "m3")]).is_empty());
+    }
+
+    /// A loader with the factor file of `rows` loaded
+    fn load(rows: &str) -> Result<DataLoader, DataLoadError> {
+        let header = "category,country,crop,impact,value,unit,confidence,source,year,low,high,reliability\n";
+        let path = std::env::temp_dir().join(format!("factors-{}.csv", uuid::Uuid::new_v4()));
+        std::fs::write(&path, format!("{}{}", header, rows)).unwrap();
+        let mut loader = DataLoader::new();
+        let result = loader.load_from_csv(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        result.map(|_| loader)
+    }
+
+    #[test]
+    fn factor_file_errors_name_the_line_and_column() {
+        assert!(load("Cereals,Ghana,Maize,Global warming,0.6,kg CO2-eq,High,Survey,2023,0.4,0.8,2\n").is_ok());
+        match load("Cereals,Ghana,Maize,Global warming,0.6,kg CO2-eq,High,Survey,2023,0.4,0.8,2\nCereals,Togo,Maize,Global warming,0.6,kg CO2-eq,High,Survey,2023,0.4,0.8,2\n") {
+            Err(DataLoadError::UnknownValue { line, column, value, valid, .. }) => {
+                assert_eq!((line, column, value.as_str()), (3, "country", "Togo"));
+                assert_eq!(valid, ["Ghana", "Nigeria", "Global"]);
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+        assert!(matches!(
+            load("Cereals,Ghana,Maize,Global warming,high,kg CO2-eq,High,Survey,2023,0.4,0.8,2\n"),
+            Err(DataLoadError::NotANumber { line: 2, column: "value_per_kg", .. })
+        ));
+        assert!(matches!(
+            DataLoader::new().load_from_csv("/nonexistent/factors.csv"),
+            Err(DataLoadError::Unreadable { .. })
+        ));
+    }
+
+    #[test]
+    fn factor_files_are_converted_to_the_expected_unit() {
+        let loader = load(concat!(
+            "Fish,Ghana,Tilapia,Water consumption,3500,L,High,Survey,2023,2000,5000,2\n",
+            "Cereals,Ghana,Maize,Global warming,600,kg CO2-eq per t,High,Survey,2023,400,800,2\n",
+        )).unwrap();
+        let factors = loader.get_factors();
+        assert_eq!((factors[0].value_per_kg, factors[0].unit.as_str()), (3.5, "m3"));
+        assert_eq!(factors[0].uncertainty_range, (2.0, 5.0));
+        assert_eq!((factors[1].value_per_kg, factors[1].unit.as_str()), (0.6, "kg CO2-eq"));
+        assert!(validate_factor_units(factors).is_empty());
+
+        match load("Cereals,Ghana,Maize,Global warming,0.6,kg SO2-eq,High,Survey,2023,0.4,0.8,2\n") {
+            Err(DataLoadError::Unit { line, error, .. }) => {
+                assert_eq!(line, 2);
+                assert_eq!(error.to_string(), "Unit 'kg SO2-eq' does not convert to the expected 'kg CO2-eq'");
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn loaded_climate_rules_come_before_the_defaults() {
+        let path = std::env::temp_dir().join(format!("climate-{}.csv", uuid::Uuid::new_v4()));
+        std::fs::write(&path, concat!(
+            "zone,food_category,crop_type,impact_category,factor,source\n",
+            "GuineaSavannah,Cereals,Rice,Global warming,1.1,Tamale paddy survey\n",
+        )).unwrap();
+        let mut loader = DataLoader::new();
+        let result = loader.load_climate_adjustment_rules_from_csv(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        result.unwrap();
+
+        let zone = loader.region_zones.iter()
+            .find(|zone| zone.matches(&Country::Ghana, " northern region"))
+            .map(|zone| zone.zone);
+        assert_eq!(zone, Some(AgroEcologicalZone::GuineaSavannah));
+
+        let rice: FoodItem = serde_json::from_value(serde_json::json!({
+            "id": "rice", "name": "Paddy rice", "quantity_kg": 1000.0, "category": "Cereals"
+        })).unwrap();
+        let rule = loader.climate_adjustment_rules.iter()
+            .find(|rule| rule.applies_to(AgroEcologicalZone::GuineaSavannah, &rice, "Global warming"))
+            .unwrap();
+        assert_eq!((rule.factor, rule.source.as_str()), (1.1, "Tamale paddy survey"));
+    }
+}
@@ -0,0 +1,220 @@
+/*!
+ * Impact factor index
+ *
+ * Farm impact factors keyed by (food category, country, crop type, impact category), with crop
+ * types and impact categories interned to ids as factors are loaded. A food's fallback chain
+ *
+ * 1. crop type in the country
+ * 2. food category in the country
+ * 3. crop type, global
+ * 4. food category, global
+ *
+ * is resolved once per food (FactorIndex::fallback_chain); each impact category is then found
+ * by hashing a few small keys instead of formatting and hashing four strings.
+ */
+
+use std::collections::HashMap;
+
+use crate::production::models::{Country, FoodCategory, ImpactFactor};
+
+/// Interned crop type name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CropTypeId(u32);
+
+/// Interned impact category name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImpactCategoryId(u32);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FactorKey {
+    pub food_category: FoodCategory,
+    pub country: Country,
+    pub crop_type: Option<CropTypeId>, // None for factors of the whole food category
+    pub impact_category: ImpactCategoryId,
+}
+
+/// The levels searched for a food, most specific first
+#[derive(Debug, Clone)]
+pub struct FallbackChain {
+    food_category: FoodCategory,
+    levels: Vec<(Country, Option<CropTypeId>)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FactorIndex {
+    crop_types: HashMap<String, CropTypeId>,
+    impact_categories: HashMap<String, ImpactCategoryId>,
+    factors: HashMap<FactorKey, ImpactFactor>,
+}
+
+impl FactorIndex {
+    /// Add a factor; it replaces any earlier factor with the same key
+    pub fn insert(&mut self, factor: ImpactFactor) {
+        let crop_type = factor.crop_type.as_deref().map(|crop| intern(&mut self.crop_types, crop, CropTypeId));
+        let key = FactorKey {
+            food_category: factor.food_category,
+            country: factor.country,
+            crop_type,
+            impact_category: intern(&mut self.impact_categories, &factor.impact_category, ImpactCategoryId),
+        };
+        self.factors.insert(key, factor);
+    }
+
+    /// Index of `factors`, cloning each once
+    pub fn from_factors(factors: &[ImpactFactor]) -> Self {
+        factors.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.factors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.factors.is_empty()
+    }
+
+    /// Levels to search for a food of `food_category` and `crop_type` grown in `country`.
+    /// Crop types without any factor are left out, as no level with them can match.
+    pub fn fallback_chain(&self, food_category: &FoodCategory, crop_type: Option<&str>, country: &Country) -> FallbackChain {
+        let crop_type = crop_type.and_then(|crop| self.crop_types.get(crop).copied());
+        let mut levels = Vec::with_capacity(4);
+        if crop_type.is_some() {
+            levels.push((*country, crop_type));
+        }
+        levels.push((*country, None));
+        if crop_type.is_some() {
+            levels.push((Country::Global, crop_type));
+        }
+        levels.push((Country::Global, None));
+        FallbackChain { food_category: *food_category, levels }
+    }
+
+    /// The most specific factor for `impact_category` along `chain`
+    pub fn find(&self, chain: &FallbackChain, impact_category: &str) -> Option<&ImpactFactor> {
+        let impact_category = *self.impact_categories.get(impact_category)?;
+        chain.levels.iter().find_map(|(country, crop_type)| self.factors.get(&FactorKey {
+            food_category: chain.food_category,
+            country: *country,
+            crop_type: *crop_type,
+            impact_category,
+        }))
+    }
+}
+
+impl FromIterator<ImpactFactor> for FactorIndex {
+    fn from_iter<I: IntoIterator<Item = ImpactFactor>>(factors: I) -> Self {
+        let mut index = Self::default();
+        for factor in factors {
+            index.insert(factor);
+        }
+        index
+    }
+}
+
+fn intern<Id: Copy>(ids: &mut HashMap<String, Id>, name: &str, id: fn(u32) -> Id) -> Id {
+    if let Some(existing) = ids.get(name) {
+        return *existing;
+    }
+    let new = id(ids.len() as u32);
+    ids.insert(name.to_string(), new);
+    new
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::production::models::{ConfidenceLevel, PedigreeScore};
+    use proptest::prelude::*;
+
+    const CATEGORIES: [FoodCategory; 3] = [FoodCategory::Cereals, FoodCategory::Legumes, FoodCategory::Roots];
+    const COUNTRIES: [Country; 3] = [Country::Ghana, Country::Nigeria, Country::Global];
+    const CROPS: [&str; 4] = ["Maize", "Rice", "Cowpea", "Cassava"];
+    const IMPACTS: [&str; 3] = ["Global warming", "Water consumption", "Land use"];
+
+    fn factor(category: usize, country: usize, crop: Option<usize>, impact: usize, value_per_kg: f64) -> ImpactFactor {
+        ImpactFactor {
+            food_category: CATEGORIES[category],
+            country: COUNTRIES[country],
+            crop_type: crop.map(|crop| CROPS[crop].to_string()),
+            impact_category: IMPACTS[impact].to_string(),
+            value_per_kg,
+            unit: "kg CO2-eq".to_string(),
+            confidence: ConfidenceLevel::Medium,
+            source: "Test".to_string(),
+            year: 2024,
+            uncertainty_range: (value_per_kg * 0.5, value_per_kg * 1.5),
+            pedigree_score: PedigreeScore {
+                reliability: 2,
+                completeness: 2,
+                temporal_correlation: 2,
+                geographical_correlation: 2,
+                technological_correlation: 2,
+            },
+            gsd: None,
+        }
+    }
+
+    /// The string-keyed lookup the index replaced
+    fn string_keyed_lookup(factors: &[ImpactFactor], category: &FoodCategory, crop: Option<&str>, country: &Country, impact: &str) -> Option<f64> {
+        let table: HashMap<String, f64> = factors.iter().map(|factor| (match &factor.crop_type {
+            Some(crop) => format!("{:?}_{:?}_{}_{}", factor.food_category, factor.country, crop, factor.impact_category),
+            None => format!("{:?}_{:?}_{}", factor.food_category, factor.country, factor.impact_category),
+        }, factor.value_per_kg)).collect();
+        let mut hierarchy = Vec::new();
+        if let Some(crop) = crop {
+            hierarchy.push(format!("{:?}_{:?}_{}", category, country, crop));
+        }
+        hierarchy.push(format!("{:?}_{:?}", category, country));
+        if let Some(crop) = crop {
+            hierarchy.push(format!("{:?}_Global_{}", category, crop));
+        }
+        hierarchy.push(format!("{:?}_Global", category));
+        hierarchy.iter().find_map(|base| table.get(&format!("{}_{}", base, impact)).copied())
+    }
+
+    #[test]
+    fn specific_factors_win_over_fallbacks() {
+        let mut index = FactorIndex::default();
+        index.insert(factor(0, 2, None, 0, 1.4));
+        index.insert(factor(0, 0, None, 0, 1.8));
+        index.insert(factor(0, 0, Some(1), 0, 2.2));
+
+        let value = |crop, country: &Country| index.find(&index.fallback_chain(&FoodCategory::Cereals, crop, country), "Global warming")
+            .map(|factor| factor.value_per_kg);
+        assert_eq!(value(Some("Rice"), &Country::Ghana), Some(2.2));
+        assert_eq!(value(Some("Maize"), &Country::Ghana), Some(1.8));
+        assert_eq!(value(Some("Rice"), &Country::Nigeria), Some(1.4));
+        assert_eq!(index.find(&index.fallback_chain(&FoodCategory::Roots, None, &Country::Ghana), "Global warming").map(|f| f.value_per_kg), None);
+        assert_eq!(index.find(&index.fallback_chain(&FoodCategory::Cereals, None, &Country::Ghana), "Eutrophication").map(|f| f.value_per_kg), None);
+        assert_eq!(index.len(), 3);
+    }
+
+    proptest! {
+        #[test]
+        fn lookups_match_the_string_keyed_table(
+            factors in prop::collection::vec(
+                (0..3usize, 0..3usize, prop::option::of(0..4usize), 0..3usize, 0.0..10.0f64),
+                0..40
+            ),
+            category in 0..3usize,
+            crop in prop::option::of(0..5usize), // 4: a crop without factors
+            country in 0..3usize,
+            impact in 0..3usize
+        ) {
+            let factors: Vec<ImpactFactor> = factors.into_iter()
+                .map(|(category, country, crop, impact, value)| factor(category, country, crop, impact, value))
+                .collect();
+            let mut index = FactorIndex::default();
+            for factor in &factors {
+                index.insert(factor.clone());
+            }
+            let crop = crop.map(|crop| CROPS.get(crop).copied().unwrap_or("Sorghum"));
+
+            let chain = index.fallback_chain(&CATEGORIES[category], crop, &COUNTRIES[country]);
+            prop_assert_eq!(
+                index.find(&chain, IMPACTS[impact]).map(|factor| factor.value_per_kg),
+                string_keyed_lookup(&factors, &CATEGORIES[category], crop, &COUNTRIES[country], IMPACTS[impact])
+            );
+        }
+    }
+}
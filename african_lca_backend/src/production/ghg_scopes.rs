@@ -0,0 +1,263 @@
+/*!
+ * GHG Protocol scopes
+ *
+ * Splits the Global warming result of a farm or facility into the scopes of the GHG Protocol
+ * Corporate Standard for corporate reporting:
+ *
+ * Scope 1  direct emissions: fuel burned on site, livestock, rice CH4, soil N2O, refrigerant leakage
+ * Scope 2  purchased electricity
+ * Scope 3  value chain: fertilizer and pesticide manufacture, capital goods, transport of
+ *          inputs, upstream agriculture of processed raw materials, waste treated off site
+ *
+ * Each emission source is classified by the first row of data/ghg_scopes.csv whose pattern
+ * it contains. Rows can be added in front (ScopeTable::reassign) to move edge cases, e.g.
+ * diesel burned in rented equipment from scope 1 to scope 3. The same rows give each source
+ * its carbon origin for product carbon footprints (see carbon_footprint).
+ */
+
+use serde::{Deserialize, Serialize};
+
+const SCOPE_TABLE: &str = include_str!("../../data/ghg_scopes.csv");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+    Scope1,
+    Scope2,
+    Scope3,
+}
+
+impl Scope {
+    fn from_number(number: &str) -> Option<Self> {
+        match number {
+            "1" => Some(Scope::Scope1),
+            "2" => Some(Scope::Scope2),
+            "3" => Some(Scope::Scope3),
+            _ => None,
+        }
+    }
+}
+
+/// Where the carbon of an emission comes from, as ISO 14067 reports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CarbonOrigin {
+    Fossil,
+    Biogenic,
+    LandUseChange,
+}
+
+impl CarbonOrigin {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fossil" => Some(CarbonOrigin::Fossil),
+            "biogenic" => Some(CarbonOrigin::Biogenic),
+            "luc" => Some(CarbonOrigin::LandUseChange),
+            _ => None,
+        }
+    }
+}
+
+/// One row of the classification table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeRule {
+    pub pattern: String, // case-insensitive substring of the source, "*" for any
+    pub scope: Scope,
+    pub origin: CarbonOrigin,
+    pub category: String, // GHG Protocol category, for documentation
+}
+
+impl ScopeRule {
+    fn matches(&self, source: &str) -> bool {
+        self.pattern == "*" || source.to_lowercase().contains(&self.pattern.to_lowercase())
+    }
+}
+
+/// Ordered classification rules; the first match wins
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeTable {
+    pub rules: Vec<ScopeRule>,
+}
+
+impl Default for ScopeTable {
+    /// The bundled data/ghg_scopes.csv
+    fn default() -> Self {
+        Self::parse(SCOPE_TABLE).expect("bundled GHG scope table is valid")
+    }
+}
+
+impl ScopeTable {
+    /// Parse `pattern;scope;origin;category` rows; lines starting with # are comments. The
+    /// table must end in a "*" row so every source gets a scope.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+            let rule = match fields.as_slice() {
+                [pattern, scope, origin, category] if !pattern.is_empty() => Scope::from_number(scope)
+                    .zip(CarbonOrigin::from_name(origin))
+                    .map(|(scope, origin)| ScopeRule {
+                        pattern: pattern.to_string(),
+                        scope,
+                        origin,
+                        category: category.to_string(),
+                    }),
+                _ => None,
+            };
+            rules.push(rule.ok_or_else(|| format!(
+                "GHG scope table line {}: expected pattern;1|2|3;fossil|biogenic|luc;category, got {:?}", number + 1, line
+            ))?);
+        }
+        if rules.last().map(|rule| rule.pattern.as_str()) != Some("*") {
+            return Err("GHG scope table must end with a \"*\" row for unmatched sources".to_string());
+        }
+        Ok(Self { rules })
+    }
+
+    /// Classify sources containing `pattern` as `scope`, ahead of every existing rule; their
+    /// carbon origin stays as the table had it
+    pub fn reassign(&mut self, pattern: &str, scope: Scope, category: &str) {
+        let origin = self.classify(pattern).origin;
+        self.rules.insert(0, ScopeRule {
+            pattern: pattern.to_string(),
+            scope,
+            origin,
+            category: category.to_string(),
+        });
+    }
+
+    pub fn classify(&self, source: &str) -> &ScopeRule {
+        self.rules.iter()
+            .find(|rule| rule.matches(source))
+            .unwrap_or_else(|| self.rules.last().expect("scope table is not empty"))
+    }
+}
+
+/// Emissions of one source within a scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScopeSource {
+    pub source: String,
+    pub category: String,
+    pub kg_co2e: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScopeTotal {
+    pub kg_co2e: f64,
+    pub sources: Vec<ScopeSource>, // largest first
+}
+
+/// Annual Global warming total split into GHG Protocol scopes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScopeBreakdown {
+    pub unit: String, // "kg CO2-eq/year"
+    pub scope_1: ScopeTotal,
+    pub scope_2: ScopeTotal,
+    pub scope_3: ScopeTotal,
+}
+
+impl ScopeBreakdown {
+    /// Classify annual (source, kg CO2-eq) emissions, adding up repeated sources (e.g. the
+    /// grid electricity of several products). When they do not add up to
+    /// `total_kg_co2e` (e.g. a result estimated from category factors), the difference is
+    /// reported as an "Unattributed" source in the scope the table gives it.
+    pub fn classify(
+        emissions: impl IntoIterator<Item = (String, f64)>,
+        total_kg_co2e: f64,
+        table: &ScopeTable
+    ) -> Self {
+        let mut breakdown = ScopeBreakdown {
+            unit: "kg CO2-eq/year".to_string(),
+            scope_1: ScopeTotal::default(),
+            scope_2: ScopeTotal::default(),
+            scope_3: ScopeTotal::default(),
+        };
+        for (source, kg_co2e) in with_remainder(emissions, total_kg_co2e) {
+            breakdown.add(source, kg_co2e, table);
+        }
+        for scope in [&mut breakdown.scope_1, &mut breakdown.scope_2, &mut breakdown.scope_3] {
+            scope.sources.sort_by(|a, b| b.kg_co2e.abs().total_cmp(&a.kg_co2e.abs()));
+        }
+        breakdown
+    }
+
+    fn add(&mut self, source: String, kg_co2e: f64, table: &ScopeTable) {
+        let rule = table.classify(&source);
+        let scope = match rule.scope {
+            Scope::Scope1 => &mut self.scope_1,
+            Scope::Scope2 => &mut self.scope_2,
+            Scope::Scope3 => &mut self.scope_3,
+        };
+        scope.kg_co2e += kg_co2e;
+        match scope.sources.iter_mut().find(|existing| existing.source == source) {
+            Some(existing) => existing.kg_co2e += kg_co2e,
+            None => scope.sources.push(ScopeSource { source, category: rule.category.clone(), kg_co2e }),
+        }
+    }
+
+    pub fn scope(&self, scope: Scope) -> &ScopeTotal {
+        match scope {
+            Scope::Scope1 => &self.scope_1,
+            Scope::Scope2 => &self.scope_2,
+            Scope::Scope3 => &self.scope_3,
+        }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.scope_1.kg_co2e + self.scope_2.kg_co2e + self.scope_3.kg_co2e
+    }
+}
+
+/// The emissions, plus the difference to `total_kg_co2e` as an "Unattributed" source when
+/// they do not add up to it
+pub fn with_remainder(emissions: impl IntoIterator<Item = (String, f64)>, total_kg_co2e: f64) -> Vec<(String, f64)> {
+    let mut emissions: Vec<(String, f64)> = emissions.into_iter().collect();
+    let remainder = total_kg_co2e - emissions.iter().map(|(_, kg_co2e)| kg_co2e).sum::<f64>();
+    if remainder.abs() > 1e-9 * total_kg_co2e.abs().max(1.0) {
+        emissions.push(("Unattributed (impact factor estimate)".to_string(), remainder));
+    }
+    emissions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_table_covers_the_reporting_scopes() {
+        let table = ScopeTable::default();
+        let scope = |source: &str| table.classify(source).scope;
+        assert_eq!(scope("Diesel consumption: 45 L/month (540.0 L/year) for Land preparation"), Scope::Scope1);
+        assert_eq!(scope("Direct N2O emissions from Urea application"), Scope::Scope1);
+        assert_eq!(scope("Methane emissions from rice cultivation (2 ha)"), Scope::Scope1);
+        assert_eq!(scope("Refrigerant leakage (R404A)"), Scope::Scope1);
+        assert_eq!(scope("Grid Electricity consumption: 300 kWh/month (3600.0 kWh/year) for Irrigation"), Scope::Scope2);
+        assert_eq!(scope("Production and transport of Urea"), Scope::Scope3);
+        assert_eq!(scope("Upstream cultivation at Asante Farm (4200000 kg Maize kernels)"), Scope::Scope3);
+        assert_eq!(scope("Diesel consumption: 20 L/month (240.0 L/year) for Rented tractor"), Scope::Scope3);
+    }
+
+    #[test]
+    fn reassigned_sources_move_scope_and_unattributed_emissions_close_the_total() {
+        let mut table = ScopeTable::default();
+        table.reassign("irrigation pump", Scope::Scope3, "Category 1: purchased services");
+        let emissions = vec![
+            ("Diesel consumption: 10 L/month (120.0 L/year) for Irrigation pump".to_string(), 320.0),
+            ("Diesel consumption: 10 L/month (120.0 L/year) for Land preparation".to_string(), 320.0),
+        ];
+
+        let breakdown = ScopeBreakdown::classify(emissions, 1000.0, &table);
+        assert_eq!(breakdown.scope_3.kg_co2e, 320.0);
+        assert_eq!(breakdown.scope_1.kg_co2e, 680.0);
+        assert!(breakdown.scope_1.sources.iter().any(|source| source.source.starts_with("Unattributed") && source.kg_co2e == 360.0));
+        assert_eq!(breakdown.total(), 1000.0);
+    }
+
+    #[test]
+    fn tables_without_a_catch_all_are_refused() {
+        assert!(ScopeTable::parse("Grid electricity;2;fossil;Purchased electricity\n").is_err());
+        assert!(ScopeTable::parse("Grid electricity;4;fossil;Purchased electricity\n*;1;fossil;Direct\n").is_err());
+        assert!(ScopeTable::parse("Grid electricity;2;nuclear;Purchased electricity\n*;1;fossil;Direct\n").is_err());
+    }
+}
@@ -0,0 +1,689 @@
+/*!
+ * Interpretation Module - ISO 14044 Section 4.5
+ *
+ * Traces a finished result back from the single score to its origins:
+ * single score → endpoint category → midpoint category → inventory flow → source activity.
+ * Every node carries its share of the single score, obtained by running the
+ * already-computed values backward through the weighting, normalization and
+ * characterization coefficients, so the contributions of a node's children add up to it.
+ */
+
+use crate::models::*;
+use crate::error::EngineError;
+use crate::scoring::normalization_reference;
+use crate::production::lci::{GWP100_CH4, GWP100_N2O};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContributionTree {
+    pub root: ContributionNode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContributionNode {
+    pub label: String,
+    pub level: ContributionLevel,
+    pub amount: f64, // in `unit`, before normalization and weighting
+    pub unit: String,
+    pub contribution: f64, // share of the single score
+    pub children: Vec<ContributionNode>, // largest contribution first
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ContributionLevel {
+    SingleScore,
+    Endpoint,
+    Midpoint,
+    InventoryFlow,
+    SourceActivity,
+}
+
+impl ContributionTree {
+    /// Build the tree from results and the midpoint-to-endpoint characterization used to
+    /// produce them, given as (endpoint, midpoint, factor). Inventory levels are only added
+    /// for midpoints characterized directly from elementary flows.
+    pub fn from_results(results: &LCAResults, endpoint_characterization: &[(&str, &str, f64)]) -> Self {
+        let weights = &results.single_score.weighting_factors;
+        let normalized_weight = |category: &str, result: &EndpointResult| {
+            weights.get(category).map(|w| w / normalization_reference(category, result))
+        };
+
+        let raw_score: f64 = results.endpoint_impacts.iter()
+            .filter_map(|(category, result)| normalized_weight(category, result).map(|k| k * result.value))
+            .sum();
+        // The reported score is rescaled (and clamped) for display
+        let scale = if raw_score.abs() > f64::EPSILON { results.single_score.value / raw_score } else { 0.0 };
+
+        let mut endpoints: Vec<ContributionNode> = results.endpoint_impacts.iter()
+            .filter_map(|(category, result)| {
+                let k = normalized_weight(category, result)? * scale;
+                let midpoints = endpoint_characterization.iter()
+                    .filter(|(endpoint, _, _)| endpoint == category)
+                    .filter_map(|(_, midpoint, factor)| {
+                        let midpoint_result = results.midpoint_impacts.get(*midpoint)?;
+                        let contribution = k * factor * midpoint_result.value;
+                        Some(ContributionNode {
+                            label: midpoint.to_string(),
+                            level: ContributionLevel::Midpoint,
+                            amount: midpoint_result.value,
+                            unit: midpoint_result.unit.clone(),
+                            contribution,
+                            children: flow_nodes(midpoint, contribution, results.lci_inventory.as_deref().unwrap_or_default()),
+                        })
+                    })
+                    .collect();
+                Some(ContributionNode {
+                    label: category.clone(),
+                    level: ContributionLevel::Endpoint,
+                    amount: result.value,
+                    unit: result.unit.clone(),
+                    contribution: k * result.value,
+                    children: sorted(midpoints),
+                })
+            })
+            .collect();
+        endpoints = sorted(endpoints);
+
+        ContributionTree {
+            root: ContributionNode {
+                label: "Single score".to_string(),
+                level: ContributionLevel::SingleScore,
+                amount: results.single_score.value,
+                unit: results.single_score.unit.clone(),
+                contribution: results.single_score.value,
+                children: endpoints,
+            },
+        }
+    }
+
+    /// Nodes without children; their contributions sum to the single score
+    pub fn leaves(&self) -> Vec<&ContributionNode> {
+        fn collect<'a>(node: &'a ContributionNode, leaves: &mut Vec<&'a ContributionNode>) {
+            if node.children.is_empty() {
+                leaves.push(node);
+            }
+            for child in &node.children {
+                collect(child, leaves);
+            }
+        }
+        let mut leaves = Vec::new();
+        collect(&self.root, &mut leaves);
+        leaves
+    }
+}
+
+/// Impacts embedded in lost food, per midpoint category. `loss_fractions` gives the lost
+/// share (0-1) of each entry of `breakdown` (keyed as in LCAResults::breakdown_by_food);
+/// a category's loss share is the breakdown-weighted mean of those fractions, applied to the
+/// category total so burdens only known at farm or facility level are included.
+pub fn food_loss_footprint(
+    midpoint_impacts: &BTreeMap<String, MidpointResult>,
+    breakdown: &BTreeMap<String, BTreeMap<String, MidpointResult>>,
+    loss_fractions: &HashMap<String, f64>,
+) -> BTreeMap<String, FoodLossFootprint> {
+    midpoint_impacts.iter()
+        .map(|(category, total)| {
+            let (lost, attributed) = breakdown.iter()
+                .filter_map(|(key, results)| {
+                    let fraction = loss_fractions.get(key).copied().unwrap_or(0.0).clamp(0.0, 1.0);
+                    Some((fraction, results.get(category)?.value))
+                })
+                .fold((0.0, 0.0), |(lost, sum), (fraction, value)| (lost + fraction * value, sum + value));
+            let share = if attributed.abs() > f64::EPSILON { lost / attributed } else { 0.0 };
+            (category.clone(), FoodLossFootprint {
+                value: total.value * share,
+                unit: total.unit.clone(),
+                share_percent: share * 100.0,
+            })
+        })
+        .collect()
+}
+
+/// Compare per-functional-unit midpoints, as (value, unit) by category, with `targets` in
+/// the same units; only categories with both a value and a target are compared
+pub fn target_compliance(
+    per_unit: &HashMap<String, (f64, String)>,
+    functional_unit: &str,
+    targets: &HashMap<String, f64>
+) -> BTreeMap<String, TargetCompliance> {
+    targets.iter()
+        .filter_map(|(category, target)| {
+            let (value, unit) = per_unit.get(category)?;
+            Some((category.clone(), TargetCompliance {
+                target: *target,
+                value: *value,
+                unit: format!("{} per {}", unit, functional_unit),
+                gap: value - target,
+                meets_target: value <= target,
+            }))
+        })
+        .collect()
+}
+
+/// Band of a single score; lower scores are better
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScoreBand {
+    Excellent,
+    Good,
+    Average,
+    Poor,
+}
+
+/// Upper limits of the Excellent, Good and Average bands; scores above `average` are Poor
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBands {
+    pub excellent: f64,
+    pub good: f64,
+    pub average: f64,
+}
+
+impl Default for ScoreBands {
+    /// Quarters of the 0-1 display scale
+    fn default() -> Self {
+        ScoreBands { excellent: 0.25, good: 0.5, average: 0.75 }
+    }
+}
+
+impl ScoreBands {
+    /// Band of `score`; a score on a limit falls in the better band
+    pub fn band(&self, score: f64) -> ScoreBand {
+        match score {
+            s if s <= self.excellent => ScoreBand::Excellent,
+            s if s <= self.good => ScoreBand::Good,
+            s if s <= self.average => ScoreBand::Average,
+            _ => ScoreBand::Poor,
+        }
+    }
+}
+
+/// Single scores of comparable assessments: the same functional unit and weighting, in one
+/// country (or Global). Scores of different weightings or functional units are on different
+/// scales, so each combination has its own bands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreReference {
+    pub country: Country,
+    pub functional_unit: String, // matched on its unit, e.g. "kg" in "1 kg product"
+    pub weighting_method: Option<WeightingMethod>, // None: any (the processing index has fixed weights)
+    pub bands: ScoreBands,
+    pub percentiles: Vec<(f64, f64)>, // (percentile, single score), ascending
+    pub source: String,
+}
+
+impl ScoreReference {
+    /// Share (0-100) of the reference assessments scoring below `score`, interpolated
+    /// between the listed percentiles and the ends of the 0-1 scale
+    pub fn percentile(&self, score: f64) -> f64 {
+        let points: Vec<(f64, f64)> = std::iter::once((0.0, 0.0))
+            .chain(self.percentiles.iter().copied())
+            .chain(std::iter::once((100.0, 1.0)))
+            .collect();
+        let score = score.clamp(0.0, 1.0);
+        points.windows(2)
+            .find(|pair| score <= pair[1].1)
+            .map(|pair| {
+                let ((p0, s0), (p1, s1)) = (pair[0], pair[1]);
+                if s1 > s0 { p0 + (p1 - p0) * (score - s0) / (s1 - s0) } else { p1 }
+            })
+            .unwrap_or(100.0)
+    }
+}
+
+/// Unit of a functional unit ("kg" in "1 kg product"), for matching references
+fn functional_unit_basis(functional_unit: &str) -> String {
+    let mut words = functional_unit.split_whitespace();
+    let first = words.next().unwrap_or_default();
+    let unit = if first.parse::<f64>().is_ok() { words.next().unwrap_or_default() } else { first };
+    unit.to_lowercase()
+}
+
+/// The reference for a score of `functional_unit` weighted with `weighting` (None for fixed
+/// weights) in `country`: the country's own before Global, an exact weighting before one
+/// for any. Unweighted scores use the equal weights they are computed with.
+pub fn find_score_reference<'a>(
+    references: &'a [ScoreReference],
+    country: &Country,
+    functional_unit: &str,
+    weighting: Option<&WeightingMethod>,
+) -> Option<&'a ScoreReference> {
+    let weighting = weighting.map(|method| match method {
+        WeightingMethod::None => &WeightingMethod::EqualWeights,
+        method => method,
+    });
+    let basis = functional_unit_basis(functional_unit);
+    let candidates = || references.iter()
+        .filter(|reference| functional_unit_basis(&reference.functional_unit) == basis);
+    [Some(*country), Some(Country::Global)].into_iter().flatten()
+        .flat_map(|country| [weighting, None].map(|weighting| (country, weighting)))
+        .find_map(|(country, weighting)| candidates().find(|reference| {
+            reference.country == country && reference.weighting_method.as_ref() == weighting
+        }))
+}
+
+/// What a single score means: its band, where it sits among comparable assessments and
+/// what drives it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SingleScoreInterpretation {
+    pub band: ScoreBand,
+    pub label: String, // e.g. "Good: lower impact than 58% of comparable assessments in Ghana"
+    pub percentile: Option<f64>, // share of reference assessments scoring better; None without a reference
+    pub bands: ScoreBands,
+    pub reference_source: Option<String>,
+    pub endpoint_interpretations: BTreeMap<String, String>, // one sentence per endpoint category
+}
+
+/// Interpret `score` against `reference` (default bands and no percentile without one).
+/// `contributions` gives the weighted, normalized score of each endpoint before rescaling.
+pub fn interpret_single_score(
+    score: f64,
+    contributions: &BTreeMap<String, f64>,
+    endpoint: &BTreeMap<String, EndpointResult>,
+    reference: Option<&ScoreReference>,
+    country: &Country,
+) -> SingleScoreInterpretation {
+    let bands = reference.map(|reference| reference.bands).unwrap_or_default();
+    let band = bands.band(score);
+    let percentile = reference.map(|reference| reference.percentile(score));
+    let label = match (percentile, reference) {
+        (Some(percentile), Some(reference)) => format!(
+            "{:?}: lower impact than {:.0}% of comparable assessments in {}",
+            band, 100.0 - percentile, reference.country
+        ),
+        _ => format!("{:?}: no reference distribution for {}", band, country),
+    };
+
+    let total: f64 = contributions.values().map(|value| value.abs()).sum();
+    let largest = contributions.iter()
+        .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+        .map(|(category, _)| category.as_str());
+    let endpoint_interpretations = contributions.iter()
+        .filter_map(|(category, contribution)| {
+            let result = endpoint.get(category)?;
+            let share = if total > f64::EPSILON { contribution.abs() / total * 100.0 } else { 0.0 };
+            let driver = if Some(category.as_str()) == largest && share > 0.0 { ", the largest driver of the score" } else { "" };
+            Some((category.clone(), format!(
+                "{} accounts for {:.0}% of the weighted score ({:.3e} {}){}.",
+                category, share, result.value, result.unit, driver
+            )))
+        })
+        .collect();
+
+    SingleScoreInterpretation {
+        band,
+        label,
+        percentile,
+        bands,
+        reference_source: reference.map(|reference| reference.source.clone()),
+        endpoint_interpretations,
+    }
+}
+
+/// Bundled reference distributions, built on first use and shared by every engine. Farm
+/// scores are per kg on the 0-1 scale of twice the normalization reference, banded at its
+/// quarters except under equal weights, whose scores sit lower. Facility scores are the
+/// processing index per tonne, whatever the weighting.
+pub fn default_score_references() -> Arc<Vec<ScoreReference>> {
+    static REFERENCES: LazyLock<Arc<Vec<ScoreReference>>> = LazyLock::new(|| {
+        const FARMS: &str = "Indicative distribution of West African case-study farms (bundled default)";
+        const FACILITIES: &str = "Indicative distribution of West African processing facilities (bundled default)";
+        let farm = |country, method, bands: (f64, f64, f64), percentiles: [(f64, f64); 5]| ScoreReference {
+            country,
+            functional_unit: "1 kg product".to_string(),
+            weighting_method: Some(method),
+            bands: ScoreBands { excellent: bands.0, good: bands.1, average: bands.2 },
+            percentiles: percentiles.to_vec(),
+            source: FARMS.to_string(),
+        };
+        let quartiles = (0.25, 0.5, 0.75);
+        let african = [(10.0, 0.025), (25.0, 0.06), (50.0, 0.14), (75.0, 0.34), (90.0, 0.65)];
+        let mut references = vec![
+            farm(Country::Ghana, WeightingMethod::AfricanPriorities, quartiles,
+                 [(10.0, 0.02), (25.0, 0.05), (50.0, 0.12), (75.0, 0.3), (90.0, 0.6)]),
+            farm(Country::Nigeria, WeightingMethod::AfricanPriorities, quartiles,
+                 [(10.0, 0.03), (25.0, 0.07), (50.0, 0.16), (75.0, 0.38), (90.0, 0.7)]),
+            farm(Country::Global, WeightingMethod::AfricanPriorities, quartiles, african),
+            farm(Country::Global, WeightingMethod::ExpertJudgment, quartiles, african),
+            farm(Country::Global, WeightingMethod::SocialPreferences, quartiles,
+                 [(10.0, 0.03), (25.0, 0.07), (50.0, 0.16), (75.0, 0.37), (90.0, 0.68)]),
+            farm(Country::Global, WeightingMethod::EqualWeights, (0.2, 0.4, 0.65),
+                 [(10.0, 0.02), (25.0, 0.05), (50.0, 0.12), (75.0, 0.29), (90.0, 0.55)]),
+        ];
+        references.push(ScoreReference {
+            country: Country::Global,
+            functional_unit: "1 tonne product".to_string(),
+            weighting_method: None,
+            bands: ScoreBands::default(),
+            percentiles: vec![(10.0, 0.03), (25.0, 0.08), (50.0, 0.18), (75.0, 0.4), (90.0, 0.7)],
+            source: FACILITIES.to_string(),
+        });
+        Arc::new(references)
+    });
+    Arc::clone(&REFERENCES)
+}
+
+/// What a benchmark value is expressed per. Results are only compared with benchmarks on
+/// the same basis: farm totals grow with farm size and say nothing about performance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BenchmarkBasis {
+    PerKgProduct,
+    PerHectare,
+    PerCapita, // per person per year, for diets
+}
+
+impl BenchmarkBasis {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BenchmarkBasis::PerKgProduct => "per kg",
+            BenchmarkBasis::PerHectare => "per ha",
+            BenchmarkBasis::PerCapita => "per person per year",
+        }
+    }
+}
+
+/// Whether a result differs from a reference once both uncertainties are taken into account;
+/// lower impacts are better. A difference inside the uncertainty is no improvement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Significance {
+    SignificantlyBetter,
+    Indistinguishable,
+    SignificantlyWorse,
+}
+
+impl Significance {
+    /// From the 95% intervals of the result and the reference: the propagated uncertainty
+    /// ranges, or Monte Carlo percentiles where the result was sampled. Only intervals that do
+    /// not overlap differ; a reference without a range is the point (value, value).
+    pub fn of(interval: (f64, f64), reference: (f64, f64)) -> Self {
+        let ordered = |(a, b): (f64, f64)| (a.min(b), a.max(b));
+        let ((low, high), (reference_low, reference_high)) = (ordered(interval), ordered(reference));
+        if high < reference_low {
+            Significance::SignificantlyBetter
+        } else if low > reference_high {
+            Significance::SignificantlyWorse
+        } else {
+            Significance::Indistinguishable
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Significance::SignificantlyBetter => "significantly better",
+            Significance::Indistinguishable => "indistinguishable",
+            Significance::SignificantlyWorse => "significantly worse",
+        }
+    }
+}
+
+/// Reference intensity of one midpoint category in a country (Global: anywhere)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactBenchmark {
+    pub name: String, // benchmarks sharing a name make one regional comparison
+    pub country: Country,
+    pub impact_category: String,
+    pub basis: BenchmarkBasis,
+    pub value: f64, // in the category's unit, per `basis`
+    pub source: String,
+}
+
+impl ImpactBenchmark {
+    /// Compare an intensity on `basis`, with its 95% `interval` where known, with this
+    /// benchmark. An intensity on another basis is refused rather than compared. An interval
+    /// that spans the benchmark makes the farm Average whatever its point value.
+    pub fn compare(&self, intensity: f64, interval: Option<(f64, f64)>, basis: BenchmarkBasis) -> Result<BenchmarkComparison, EngineError> {
+        if basis != self.basis || self.value <= 0.0 {
+            return Err(EngineError::NotApplicable(format!(
+                "{} ({} {}) cannot be compared with a result {}",
+                self.name, self.impact_category, self.basis.label(), basis.label()
+            )));
+        }
+        let ratio = intensity / self.value;
+        let significance = interval.map(|interval| Significance::of(interval, (self.value, self.value)));
+        Ok(BenchmarkComparison {
+            benchmark_name: format!("{} - {} {}", self.name, self.impact_category, self.basis.label()),
+            your_performance: intensity,
+            benchmark_value: self.value,
+            percentage_difference: (ratio - 1.0) * 100.0,
+            performance_category: match ratio {
+                _ if significance == Some(Significance::Indistinguishable) => PerformanceCategory::Average,
+                r if r < 0.75 => PerformanceCategory::Excellent,
+                r if r < 1.0 => PerformanceCategory::Good,
+                r if r < 1.25 => PerformanceCategory::Average,
+                r if r < 1.5 => PerformanceCategory::BelowAverage,
+                _ => PerformanceCategory::Poor,
+            },
+            basis: Some(self.basis),
+            significance,
+        })
+    }
+}
+
+/// Bundled benchmark intensities for farms: national and West African averages per kg of
+/// product, and a per-hectare reference for sustainably managed farms. Indicative.
+pub fn default_impact_benchmarks() -> Arc<Vec<ImpactBenchmark>> {
+    static BENCHMARKS: LazyLock<Arc<Vec<ImpactBenchmark>>> = LazyLock::new(|| {
+        const SOURCE: &str = "Indicative intensities of West African case-study farms (bundled default)";
+        let benchmark = |name: &str, country, impact_category: &str, basis, value| ImpactBenchmark {
+            name: name.to_string(),
+            country,
+            impact_category: impact_category.to_string(),
+            basis,
+            value,
+            source: SOURCE.to_string(),
+        };
+        let mut benchmarks = Vec::new();
+        for (name, country, gwp, water) in [
+            ("West Africa farm average", Country::Global, 0.8, 0.25),
+            ("Ghana farm average", Country::Ghana, 0.7, 0.2),
+            ("Nigeria farm average", Country::Nigeria, 0.9, 0.3),
+        ] {
+            benchmarks.extend([
+                benchmark(name, country, "Global warming", BenchmarkBasis::PerKgProduct, gwp),
+                benchmark(name, country, "Water consumption", BenchmarkBasis::PerKgProduct, water),
+            ]);
+        }
+        benchmarks.push(benchmark(
+            "Sustainable farming practices", Country::Global, "Global warming", BenchmarkBasis::PerHectare, 1500.0
+        ));
+        Arc::new(benchmarks)
+    });
+    Arc::clone(&BENCHMARKS)
+}
+
+/// Characterization factor of an elementary flow for a midpoint category, mirroring
+/// LCICalculator::calculate_midpoint_impacts
+fn flow_factor(category: &str, flow: &LciFlow) -> Option<f64> {
+    let substance = flow.substance.as_str();
+    match category {
+        "Global warming" => match substance {
+            "Carbon dioxide (CO2)" | "Carbon dioxide (CO2) equivalent" => Some(1.0),
+            "Methane (CH4)" => Some(GWP100_CH4),
+            s if s.contains("N2O") => Some(GWP100_N2O),
+            _ => None,
+        },
+        "Water consumption" | "Water scarcity" => (substance == "Water").then_some(1.0),
+        "Land use" => substance.contains("Land occupation").then_some(1.0),
+        "Freshwater eutrophication" => match substance {
+            s if s.contains("Nitrate") => Some(0.01),
+            "Phosphorus (P)" if flow.compartment == "water" => Some(1.0),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A flow with its amount in the midpoint's unit
+type CharacterizedFlow<'a> = (&'a LciFlow, f64);
+
+/// Split a midpoint's contribution over the flows characterized into it, grouped by
+/// substance, with the source activities of each flow beneath
+fn flow_nodes(category: &str, contribution: f64, inventory: &[LciFlow]) -> Vec<ContributionNode> {
+    let characterized: Vec<CharacterizedFlow> = inventory.iter()
+        .filter_map(|flow| flow_factor(category, flow).map(|factor| (flow, flow.quantity * factor)))
+        .collect();
+    let total: f64 = characterized.iter().map(|(_, amount)| amount).sum();
+    if total.abs() <= f64::EPSILON {
+        return Vec::new();
+    }
+
+    let mut by_substance: BTreeMap<(&str, &str, &str), Vec<CharacterizedFlow>> = BTreeMap::new();
+    for (flow, amount) in characterized {
+        by_substance.entry((&flow.substance, &flow.compartment, &flow.unit)).or_default().push((flow, amount));
+    }
+
+    sorted(by_substance.into_iter()
+        .map(|((substance, compartment, unit), flows)| {
+            let sources = flows.iter()
+                .map(|(flow, amount)| ContributionNode {
+                    label: flow.source.clone(),
+                    level: ContributionLevel::SourceActivity,
+                    amount: flow.quantity,
+                    unit: unit.to_string(),
+                    contribution: contribution * amount / total,
+                    children: Vec::new(),
+                })
+                .collect();
+            ContributionNode {
+                label: format!("{} ({})", substance, compartment),
+                level: ContributionLevel::InventoryFlow,
+                amount: flows.iter().map(|(flow, _)| flow.quantity).sum(),
+                unit: unit.to_string(),
+                contribution: contribution * flows.iter().map(|(_, amount)| amount).sum::<f64>() / total,
+                children: sorted(sources),
+            }
+        })
+        .collect())
+}
+
+fn sorted(mut nodes: Vec<ContributionNode>) -> Vec<ContributionNode> {
+    nodes.sort_by(|a, b| b.contribution.abs().total_cmp(&a.contribution.abs()));
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_on_a_band_limit_fall_in_the_better_band() {
+        let bands = ScoreBands::default();
+        for (score, band) in [
+            (0.0, ScoreBand::Excellent), (0.25, ScoreBand::Excellent), (0.2501, ScoreBand::Good),
+            (0.5, ScoreBand::Good), (0.5001, ScoreBand::Average), (0.75, ScoreBand::Average),
+            (0.7501, ScoreBand::Poor), (1.0, ScoreBand::Poor),
+        ] {
+            assert_eq!(bands.band(score), band, "{score}");
+        }
+    }
+
+    #[test]
+    fn each_weighting_and_functional_unit_is_judged_on_its_own_bands() {
+        let references = default_score_references();
+        let find = |country, unit, method: Option<&WeightingMethod>| find_score_reference(&references, &country, unit, method).unwrap();
+
+        let african = find(Country::Ghana, "1 kg product", Some(&WeightingMethod::AfricanPriorities));
+        let equal = find(Country::Ghana, "1 kg product", Some(&WeightingMethod::EqualWeights));
+        assert_eq!(african.country, Country::Ghana);
+        assert_eq!((equal.country, equal.weighting_method.as_ref()), (Country::Global, Some(&WeightingMethod::EqualWeights)));
+        assert_eq!(african.bands.band(0.45), ScoreBand::Good);
+        assert_eq!(equal.bands.band(0.45), ScoreBand::Average);
+
+        // Unweighted scores are computed with equal weights
+        assert_eq!(find(Country::Nigeria, "1 kg", Some(&WeightingMethod::None)).bands, equal.bands);
+        // Facilities per tonne have a distribution of their own, for any weighting
+        let facility = find(Country::Ghana, "1 tonne product", Some(&WeightingMethod::AfricanPriorities));
+        assert!(facility.weighting_method.is_none() && facility.functional_unit.contains("tonne"));
+        assert_eq!(facility.source, find(Country::Nigeria, "1 tonne product", None).source);
+        assert!(find_score_reference(&references, &Country::Ghana, "1 kg product", Some(&WeightingMethod::CustomWeights(BTreeMap::new()))).is_none());
+        assert!(find_score_reference(&references, &Country::Ghana, "1 ha", Some(&WeightingMethod::AfricanPriorities)).is_none());
+    }
+
+    #[test]
+    fn only_intervals_that_clear_the_reference_are_significant() {
+        let reference = (0.7, 0.9);
+        for (interval, significance) in [
+            ((0.5, 0.69), Significance::SignificantlyBetter),
+            ((0.5, 0.7), Significance::Indistinguishable), // touching
+            ((0.9, 1.2), Significance::Indistinguishable),
+            ((0.91, 1.2), Significance::SignificantlyWorse),
+            ((1.2, 0.91), Significance::SignificantlyWorse), // mirrored range of a credit
+        ] {
+            assert_eq!(Significance::of(interval, reference), significance, "{interval:?}");
+        }
+    }
+
+    #[test]
+    fn a_range_spanning_the_benchmark_is_average_whatever_the_point_value() {
+        let benchmark = ImpactBenchmark {
+            name: "Ghana farm average".to_string(),
+            country: Country::Ghana,
+            impact_category: "Global warming".to_string(),
+            basis: BenchmarkBasis::PerKgProduct,
+            value: 0.7,
+            source: "Test".to_string(),
+        };
+        let compare = |interval| benchmark.compare(0.35, Some(interval), BenchmarkBasis::PerKgProduct).unwrap();
+
+        // Half the benchmark, and the whole range below it
+        let clear = compare((0.2, 0.69));
+        assert_eq!(clear.significance, Some(Significance::SignificantlyBetter));
+        assert_eq!(clear.performance_category, PerformanceCategory::Excellent);
+
+        // Same point value, but the range reaches the benchmark
+        let spanning = compare((0.2, 0.7));
+        assert_eq!(spanning.significance, Some(Significance::Indistinguishable));
+        assert_eq!(spanning.performance_category, PerformanceCategory::Average);
+        assert_eq!(spanning.percentage_difference, clear.percentage_difference);
+
+        // Twice the benchmark on either side of it
+        let worse = |interval| benchmark.compare(1.4, Some(interval), BenchmarkBasis::PerKgProduct).unwrap();
+        assert_eq!(worse((0.71, 2.0)).performance_category, PerformanceCategory::Poor);
+        assert_eq!(worse((0.7, 2.0)).performance_category, PerformanceCategory::Average);
+
+        // Without a range the point value decides, and no significance is claimed
+        let unknown = benchmark.compare(0.35, None, BenchmarkBasis::PerKgProduct).unwrap();
+        assert_eq!((unknown.performance_category, unknown.significance), (PerformanceCategory::Excellent, None));
+    }
+
+    #[test]
+    fn percentiles_interpolate_between_reference_points() {
+        let references = default_score_references();
+        let ghana = find_score_reference(&references, &Country::Ghana, "1 kg product", Some(&WeightingMethod::AfricanPriorities)).unwrap();
+        assert_eq!(ghana.percentile(0.0), 0.0);
+        assert!((ghana.percentile(0.12) - 50.0).abs() < 1e-9);
+        assert!((ghana.percentile(0.21) - 62.5).abs() < 1e-9);
+        assert_eq!(ghana.percentile(1.0), 100.0);
+        assert_eq!(ghana.percentile(3.0), 100.0);
+    }
+
+    #[test]
+    fn interpretation_names_the_band_percentile_and_largest_driver() {
+        let endpoint = |value: f64, unit: &str| EndpointResult {
+            value,
+            unit: unit.to_string(),
+            uncertainty_range: (value, value),
+            normalization_factor: None,
+            regional_adaptation_factor: None,
+            contributions: BTreeMap::new(),
+            metadata: None,
+        };
+        let endpoints = BTreeMap::from([
+            ("Human Health".to_string(), endpoint(1e-3, "DALY per kg")),
+            ("Resource Scarcity".to_string(), endpoint(10.0, "USD per kg")),
+        ]);
+        let contributions = BTreeMap::from([("Human Health".to_string(), 0.3), ("Resource Scarcity".to_string(), 0.1)]);
+        let references = default_score_references();
+        let reference = find_score_reference(&references, &Country::Ghana, "1 kg product", Some(&WeightingMethod::AfricanPriorities));
+
+        let interpretation = interpret_single_score(0.3, &contributions, &endpoints, reference, &Country::Ghana);
+        assert_eq!(interpretation.band, ScoreBand::Good);
+        assert_eq!(interpretation.percentile, Some(75.0));
+        assert_eq!(interpretation.label, "Good: lower impact than 25% of comparable assessments in Ghana");
+        assert!(interpretation.endpoint_interpretations["Human Health"].contains("75% of the weighted score"));
+        assert!(interpretation.endpoint_interpretations["Human Health"].ends_with("the largest driver of the score."));
+        assert!(!interpretation.endpoint_interpretations["Resource Scarcity"].contains("largest"));
+
+        let unreferenced = interpret_single_score(0.2, &contributions, &endpoints, None, &Country::Ghana);
+        assert_eq!((unreferenced.band, unreferenced.percentile), (ScoreBand::Excellent, None));
+    }
+}
@@ -1,14 +1,308 @@
 use crate::models::*;
-use crate::production::lci::{LCICalculator, InventoryItem, EnvironmentalCompartment};
+use crate::error::{EngineError, FactorError, InputError};
+use crate::production::lci::{LCICalculator, InventoryItem, EnvironmentalCompartment, PracticeEffect, ACIDIFICATION_NH3, water_footprint};
 use crate::production::lci_extended::LCIExtendedCharacterization;
-use std::collections::HashMap;
+use crate::production::interpretation::{
+    BenchmarkBasis, ContributionTree, ImpactBenchmark, ScoreReference, Significance, default_impact_benchmarks,
+    default_score_references, find_score_reference, food_loss_footprint, interpret_single_score
+};
+#[cfg(test)]
+use crate::production::interpretation::{ScoreBand, ScoreBands};
+use crate::production::carbon_footprint::{CarbonOriginSplit, ProductCarbonFootprint};
+use crate::production::ghg_scopes::{ScopeBreakdown, ScopeTable};
+use crate::production::data::{ClimateAdjustmentRule, ClimateZoneBand, RegionZone, plausibility_check, water_use_check};
+use crate::scoring::{african_reference_per_kg, weighted_single_score};
+#[cfg(test)]
+use crate::scoring::normalization_reference;
+use crate::production::factor_index::{FactorIndex, FallbackChain};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
 use log::{info, warn};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, LogNormal};
+use rayon::prelude::*;
+
+/// Reference unit (per kg product) for each production impact category.
+pub fn impact_unit(category: &str) -> &'static str {
+    match category {
+        "Global warming" => "kg CO2-eq",
+        "Water consumption" => "m3",
+        "Water scarcity" => "m3 H2O-eq",
+        "Land use" => "m2a crop-eq",
+        "Biodiversity loss" => "MSA*m2*yr",
+        "Soil degradation" => "kg soil-eq",
+        "Terrestrial acidification" => "kg SO2-eq",
+        "Freshwater eutrophication" => "kg P-eq",
+        "Marine eutrophication" => "kg N-eq",
+        "Fossil depletion" => "kg oil-eq",
+        "Mineral depletion" => "kg Fe-eq",
+        "Particulate matter formation" => "PM2.5-eq",
+        "Photochemical oxidation" => "kg NMVOC-eq",
+        "Energy consumption" => "MJ",
+        _ => "Unknown",
+    }
+}
+
+/// Percentage change of each midpoint category in assessed scenarios relative to an assessed
+/// baseline (negative = improvement), and whether the change clears both uncertainty ranges.
+/// Only significant improvements count as lowering a category. Pair scenarios with a name,
+/// e.g. from the `with_*` builders.
+pub fn compare_assessments(
+    baseline: &Assessment,
+    scenarios: &[(&str, &Assessment)],
+) -> Result<Vec<ScenarioResult>, EngineError> {
+    let baseline_impacts = &baseline.results.as_ref()
+        .ok_or_else(|| EngineError::NotAssessed("Baseline assessment".to_string()))?
+        .midpoint_impacts;
+
+    scenarios.iter().map(|(name, scenario)| {
+        let impacts = &scenario.results.as_ref()
+            .ok_or_else(|| EngineError::NotAssessed(format!("Scenario '{}'", name)))?
+            .midpoint_impacts;
+
+        let impact_changes: BTreeMap<String, f64> = baseline_impacts.iter()
+            .filter(|(_, base)| base.value.abs() > f64::EPSILON)
+            .filter_map(|(category, base)| impacts.get(category).map(|result| {
+                (category.clone(), (result.value - base.value) / base.value.abs() * 100.0)
+            }))
+            .collect();
+
+        let significance: BTreeMap<String, Significance> = impact_changes.keys()
+            .filter_map(|category| {
+                let (base, result) = (baseline_impacts[category].interval()?, impacts[category].interval()?);
+                Some((category.clone(), Significance::of(result, base)))
+            })
+            .collect();
+
+        // Without ranges to go by, a lower value counts as an improvement
+        let (improved, within_uncertainty): (Vec<&str>, Vec<&str>) = impact_changes.iter()
+            .filter(|(_, change)| **change < -0.5)
+            .map(|(category, _)| category.as_str())
+            .partition(|category| significance.get(*category).is_none_or(|s| *s == Significance::SignificantlyBetter));
+
+        let mut description = if improved.is_empty() {
+            "No category improves significantly against the baseline".to_string()
+        } else {
+            format!("Lowers {}", improved.join(", "))
+        };
+        if !within_uncertainty.is_empty() {
+            description.push_str(&format!("; {} lower, but within the uncertainty", within_uncertainty.join(", ")));
+        }
+
+        Ok(ScenarioResult {
+            scenario_name: name.to_string(),
+            description,
+            impact_changes,
+            significance,
+        })
+    }).collect()
+}
+
+/// Resolved factor: value per kg, source, lognormal GSD and pedigree.
+type ResolvedFactor = (f64, String, f64, PedigreeScore);
+
+/// Lognormal GSD of a factor: its pedigree on top of a basic uncertainty, which is the
+/// source's own range (read as a 95% interval) where it gives one and the default for the
+/// category's dominant flow otherwise
+fn factor_geometric_sd(category: &str, range: (f64, f64), pedigree: &PedigreeScore) -> f64 {
+    let (low, high) = (range.0.abs().min(range.1.abs()), range.0.abs().max(range.1.abs()));
+    let basic_variance = if low > 0.0 && high > low && high.is_finite() {
+        ((high / low).ln() / 4.0).powi(2)
+    } else {
+        FlowType::of_category(category).basic_variance()
+    };
+    pedigree.geometric_standard_deviation(basic_variance)
+}
+
+/// The GSD the factor's source gives, where it is a usable one, otherwise the one its range
+/// and pedigree imply
+fn resolved_geometric_sd(factor: &ImpactFactor) -> f64 {
+    factor.gsd
+        .filter(|gsd| gsd.is_finite() && *gsd >= 1.0)
+        .unwrap_or_else(|| factor_geometric_sd(&factor.impact_category, factor.uncertainty_range, &factor.pedigree_score))
+}
+
+/// Source prefix of factors given with override_factor
+const PRIMARY_DATA: &str = "Primary data";
+
+/// Key of each food in LCAResults::breakdown_by_food, in food order: its name and quantity,
+/// with its id where foods would otherwise share a key, and its position where even that is
+/// repeated (duplicates kept apart). No food's row overwrites another's.
+fn breakdown_keys(foods: &[FoodItem]) -> Vec<String> {
+    let repeated = |keys: &[String], key: &String| keys.iter().filter(|other| *other == key).count() > 1;
+    let labels: Vec<String> = foods.iter().map(|food| format!("{} ({}kg)", food.name, food.quantity_kg)).collect();
+    let labels: Vec<String> = foods.iter().zip(&labels)
+        .map(|(food, label)| if repeated(&labels, label) {
+            format!("{} ({}kg, id {})", food.name, food.quantity_kg, food.id)
+        } else {
+            label.clone()
+        })
+        .collect();
+    labels.iter().enumerate()
+        .map(|(i, label)| if repeated(&labels, label) { format!("{} #{}", label, i + 1) } else { label.clone() })
+        .collect()
+}
+
+/// Lost share (0-1) of each food's harvest, keyed as in LCAResults::breakdown_by_food
+fn post_harvest_loss_fractions(foods: &[FoodItem]) -> HashMap<String, f64> {
+    breakdown_keys(foods).into_iter()
+        .zip(foods)
+        .map(|(key, food)| (key, food.post_harvest_losses.unwrap_or(0.0) / 100.0))
+        .collect()
+}
+
+/// A farm without foods, or whose foods weigh nothing, has nothing to divide its impacts over;
+/// scoring it as zero impact per kg would rate an empty harvest as the best possible farm
+fn require_foods(foods: &[FoodItem]) -> Result<(), InputError> {
+    if foods.is_empty() {
+        return Err(InputError::invalid("foods", "an assessment needs at least one food"));
+    }
+    if foods.iter().map(|food| food.quantity_kg).sum::<f64>() <= 0.0 {
+        return Err(InputError::invalid("foods", "the foods must add up to more than 0 kg of production"));
+    }
+    Ok(())
+}
+
+/// A measure a farm recommendation asks for up front, priced by `reference_implementation_cost`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Measure {
+    SoilTesting,
+    EnergyServicing,
+}
+
+/// A management practice's multiplier on the category-factor impacts of a food
+struct PracticeAdjustment {
+    label: &'static str,
+    effect: Option<PracticeEffect>, // None: no inventory models it
+    categories: &'static [&'static str],
+    factor: f64,
+}
+
+/// Every practice multiplier, in the order it is applied; 1.0 where the practice changes nothing
+fn practice_adjustments(practices: &ManagementPractices) -> Vec<PracticeAdjustment> {
+    let soil = &practices.soil_management;
+    let fertilization = practices.fertilization.as_ref();
+    let water = &practices.water_management;
+    let pesticide_count = practices.pest_management.pesticides_used.len();
+    vec![
+        PracticeAdjustment {
+            label: PracticeEffect::SoilConservation.label(),
+            effect: Some(PracticeEffect::SoilConservation),
+            categories: &["Soil degradation"],
+            factor: match soil.conservation_practices.len() {
+                0 => 1.0,
+                1 | 2 => 0.92,
+                _ => 0.85,
+            },
+        },
+        PracticeAdjustment {
+            label: "Compost carbon sequestration",
+            effect: None,
+            categories: &["Global warming"],
+            factor: if soil.uses_compost { 0.92 } else { 1.0 },
+        },
+        PracticeAdjustment {
+            label: PracticeEffect::FertilizerN2O.label(),
+            effect: Some(PracticeEffect::FertilizerN2O),
+            categories: &["Global warming"],
+            factor: match fertilization {
+                Some(f) if f.soil_test_based && f.follows_nutrient_plan => 0.8, // Efficient management reduces N2O
+                Some(f) if f.soil_test_based || f.follows_nutrient_plan => 0.9,
+                Some(_) => 1.2, // Inefficient fertilizer use increases emissions
+                None => 1.0, // Not reported - no basis for an adjustment
+            },
+        },
+        PracticeAdjustment {
+            label: PracticeEffect::NutrientRunoff.label(),
+            effect: Some(PracticeEffect::NutrientRunoff),
+            categories: &["Freshwater eutrophication", "Marine eutrophication"],
+            factor: match fertilization {
+                Some(f) if f.follows_nutrient_plan => 0.7, // Good planning reduces runoff
+                Some(_) => 1.3,
+                None => 1.0,
+            },
+        },
+        PracticeAdjustment {
+            label: PracticeEffect::IrrigationEfficiency.label(),
+            effect: Some(PracticeEffect::IrrigationEfficiency),
+            categories: &["Water consumption", "Water scarcity"],
+            factor: match water.irrigation_system.as_deref() {
+                Some("Drip irrigation") | Some("Micro-sprinkler") => 0.7,
+                Some("Sprinkler") => 0.85,
+                Some("Flood irrigation") | Some("Furrow irrigation") => 1.0,
+                None => 0.5, // Rainfed farming
+                _ => 0.9,
+            },
+        },
+        PracticeAdjustment {
+            label: "Water conservation practices",
+            effect: None,
+            categories: &["Soil degradation"],
+            factor: if water.water_conservation_practices.is_empty() { 1.0 } else { 0.8 }, // less runoff and erosion
+        },
+        PracticeAdjustment {
+            label: "Pesticide use",
+            effect: None,
+            categories: &["Biodiversity loss"],
+            factor: match pesticide_count {
+                0 => 0.9,
+                1 | 2 => 0.95,
+                3..=5 => 1.0,
+                _ => 1.2,
+            },
+        },
+        PracticeAdjustment {
+            label: "Integrated pest management",
+            effect: None,
+            categories: &["Biodiversity loss", "Terrestrial acidification", "Freshwater eutrophication", "Marine eutrophication"],
+            factor: if practices.pest_management.uses_ipm { 0.9 } else { 1.0 },
+        },
+    ]
+}
+
+/// Name each practice effect the inventory accounts for once in the totals of the
+/// categories it drives
+fn note_captured_practice_effects(
+    impacts: &mut BTreeMap<String, MidpointResult>,
+    practices: &ManagementPractices,
+    captured: &BTreeSet<PracticeEffect>,
+) {
+    for adjustment in practice_adjustments(practices).into_iter().filter(|a| a.factor != 1.0) {
+        let Some(effect) = adjustment.effect.filter(|effect| captured.contains(effect)) else { continue };
+        for category in adjustment.categories {
+            if let Some(result) = impacts.get_mut(*category) {
+                info!("{}: {} taken from the inventory", category, effect.label());
+                result.contributing_sources.push(format!("{}: accounted for in the life cycle inventory", effect.label()));
+            }
+        }
+    }
+}
+
+/// Midpoints per hectare of the foods' total area: annual totals (per-kg results times
+/// production) over the area. Only computed when every food reports an area; otherwise the
+/// impacts of foods without one would be spread over land that excludes them.
+fn impacts_per_hectare(foods: &[FoodItem], midpoint_impacts: &BTreeMap<String, MidpointResult>) -> Option<BTreeMap<String, MidpointResult>> {
+    let areas: Option<Vec<f64>> = foods.iter().map(|food| food.area_allocated.filter(|area| *area > 0.0)).collect();
+    let area_ha: f64 = areas.filter(|areas| !areas.is_empty())?.iter().sum();
+    let production_kg: f64 = foods.iter().map(|food| food.quantity_kg).sum();
+    midpoint_impacts.iter()
+        .map(|(category, result)| Some((category.clone(), result.on_basis(ImpactBasis::PerHectare, production_kg, Some(area_ha))?)))
+        .collect()
+}
+
+/// A midpoint's value and 95% interval, where known, per kg of total production; None
+/// without production
+fn value_per_kg(result: &MidpointResult, production_kg: f64) -> Option<(f64, Option<(f64, f64)>)> {
+    let per_kg = result.on_basis(ImpactBasis::PerKgProduct, production_kg, None)?;
+    Some((per_kg.value, per_kg.interval()))
+}
 
 /// Convert the internal LCI inventory (map of elementary flows) into the serialisable
 /// LciFlow list exposed in the result, so the Python engine can characterize on-farm
-/// emissions via the validated canonical CFs.
-fn inventory_to_flows(inv: &HashMap<String, InventoryItem>) -> Vec<LciFlow> {
-    inv.values().map(|it| LciFlow {
+/// emissions via the validated canonical CFs. Sorted so reports are reproducible.
+fn inventory_to_flows(inv: &BTreeMap<String, InventoryItem>) -> Vec<LciFlow> {
+    let mut flows: Vec<LciFlow> = inv.values().map(|it| LciFlow {
         substance: it.substance.clone(),
         quantity: it.quantity,
         unit: it.unit.clone(),
@@ -19,60 +313,243 @@ fn inventory_to_flows(inv: &HashMap<String, InventoryItem>) -> Vec<LciFlow> {
             EnvironmentalCompartment::Resource => "resource",
         }.to_string(),
         source: it.source.clone(),
-    }).collect()
+    }).collect();
+    flows.sort_by(|a, b| {
+        (&a.compartment, &a.substance, &a.source).cmp(&(&b.compartment, &b.substance, &b.source))
+    });
+    flows
+}
+
+
+/// Seed used when none is given, so sampled results are reproducible by default
+pub const DEFAULT_SEED: u64 = 14044;
+
+/// Factor-based impacts of each food from the last assessment, before any management
+/// practice adjustments, with the foods and location they were computed for
+#[derive(Clone)]
+struct FoodImpactCache {
+    assessment_id: Uuid,
+    country: Country,
+    region: Option<String>,
+    coordinates: Option<GeoCoordinates>,
+    foods: Vec<FoodItem>,
+    food_impacts: Vec<BTreeMap<String, MidpointResult>>,
+}
+
+impl FoodImpactCache {
+    fn new(assessment: &Assessment, food_impacts: Vec<BTreeMap<String, MidpointResult>>) -> Self {
+        FoodImpactCache {
+            assessment_id: assessment.id,
+            country: assessment.country,
+            region: assessment.region.clone(),
+            coordinates: assessment.coordinates,
+            foods: assessment.foods.clone(),
+            food_impacts,
+        }
+    }
+
+    /// Whether the impacts still hold: same farm and location, and no food edited since
+    fn is_valid_for(&self, assessment: &Assessment) -> bool {
+        self.assessment_id == assessment.id
+            && self.country == assessment.country
+            && self.region == assessment.region
+            && self.coordinates == assessment.coordinates
+            && self.foods == assessment.foods
+    }
 }
 
+/// Where a farm is, as far as the climate adjustments are concerned
+#[derive(Debug, Clone, Copy)]
+struct ClimateLocation {
+    zone: Option<ClimateZone>,
+    agro_ecological_zone: Option<AgroEcologicalZone>,
+}
 
 pub struct AfricanLCAEngine {
-    impact_factors: HashMap<String, ImpactFactor>,
+    // Loaded databases are shared between clones and copied only when one is modified
+    impact_factors: Arc<FactorIndex>,
+    factor_overrides: Arc<FactorIndex>, // primary data, searched before impact_factors
     characterization_factors: CharacterizationFactors,
-    regional_factors: HashMap<String, f64>,
-    climate_adjustments: HashMap<String, f64>,
+    regional_factors: Arc<HashMap<String, f64>>,
+    climate_adjustments: Arc<HashMap<String, f64>>,
+    climate_zones: Vec<ClimateZoneBand>,
+    region_zones: Arc<Vec<RegionZone>>,
+    climate_adjustment_rules: Arc<Vec<ClimateAdjustmentRule>>, // first matching rule wins
     methodology: LCAMethodology,
     lci_calculator: LCICalculator, // NEW: ISO-compliant inventory calculator
+    seed: u64, // for every stochastic method; each run starts from this seed
+    food_impact_cache: Option<FoodImpactCache>, // for update_food
+    strict: bool, // refuse to assess with no impact factors loaded
+    scope_table: Arc<ScopeTable>, // GHG Protocol scope of each emission source
+    score_references: Arc<Vec<ScoreReference>>, // single score distributions, first match wins
+    impact_benchmarks: Arc<Vec<ImpactBenchmark>>, // every one for the country (or Global) is compared
 }
 
 impl AfricanLCAEngine {
     pub fn new(methodology: LCAMethodology) -> Self {
+        Self::new_with_seed(methodology, None)
+    }
+
+    /// Engine whose sampling methods are seeded with `seed` (DEFAULT_SEED when None)
+    pub fn new_with_seed(methodology: LCAMethodology, seed: Option<u64>) -> Self {
         Self {
-            impact_factors: HashMap::new(),
+            impact_factors: Arc::default(),
+            factor_overrides: Arc::default(),
             characterization_factors: CharacterizationFactors::default(),
-            regional_factors: HashMap::new(),
-            climate_adjustments: HashMap::new(),
+            regional_factors: Arc::default(),
+            climate_adjustments: Arc::default(),
+            climate_zones: Vec::new(),
+            region_zones: Arc::default(),
+            climate_adjustment_rules: Arc::default(),
             methodology,
             lci_calculator: LCICalculator::new(), // Initialize LCI calculator
+            seed: seed.unwrap_or(DEFAULT_SEED),
+            food_impact_cache: None,
+            strict: false,
+            scope_table: Arc::default(),
+            score_references: default_score_references(),
+            impact_benchmarks: default_impact_benchmarks(),
         }
     }
 
+    /// Reseed the sampling methods (DEFAULT_SEED when None)
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed.unwrap_or(DEFAULT_SEED);
+    }
+
+    /// In strict mode assessments fail when no impact factors are loaded, instead of
+    /// running on default estimates alone
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Replace the methodology, keeping the loaded factors
+    pub fn set_methodology(&mut self, methodology: LCAMethodology) {
+        self.methodology = methodology;
+        self.food_impact_cache = None;
+    }
+
+    /// Use `index` as the impact factors, replacing any loaded ones. Engines given the same
+    /// index share it instead of holding copies.
+    pub fn load_factor_index(&mut self, index: Arc<FactorIndex>) {
+        self.food_impact_cache = None;
+        self.impact_factors = index;
+        info!("Loaded {} impact factors", self.impact_factors.len());
+    }
+
+    /// Use a factor the company measured itself for `category` of the foods matching
+    /// `food_category`, `country` and `crop_type`, ahead of any loaded or default factor. The
+    /// factor is treated as primary data: best pedigree, and listed in the data quality's
+    /// factor_overrides wherever it is used.
+    pub fn override_factor(
+        &mut self,
+        food_category: FoodCategory,
+        country: Country,
+        crop_type: Option<&str>,
+        category: &str,
+        factor: ImpactFactor
+    ) {
+        self.food_impact_cache = None;
+        Arc::make_mut(&mut self.factor_overrides).insert(ImpactFactor {
+            food_category,
+            country,
+            crop_type: crop_type.map(str::to_string),
+            impact_category: category.to_string(),
+            confidence: ConfidenceLevel::High,
+            source: format!("{}: {}", PRIMARY_DATA, factor.source),
+            pedigree_score: PedigreeScore {
+                reliability: 1,
+                completeness: 1,
+                temporal_correlation: 1,
+                geographical_correlation: 1,
+                technological_correlation: 1,
+            },
+            ..factor
+        });
+    }
+
+    /// Add `factors` to the loaded ones. This copies an index shared with other engines;
+    /// build a FactorIndex once and pass it to load_factor_index instead.
+    #[deprecated(since = "0.1.0", note = "build a FactorIndex once and share it with load_factor_index")]
     pub fn load_impact_factors(&mut self, factors: Vec<ImpactFactor>) {
+        self.food_impact_cache = None;
+        let index = Arc::make_mut(&mut self.impact_factors);
         for factor in factors {
-            // Create hierarchical key for factor lookup
-            let key = self.create_factor_key(&factor);
-            self.impact_factors.insert(key, factor);
+            index.insert(factor);
         }
         info!("Loaded {} impact factors", self.impact_factors.len());
     }
 
     pub fn load_regional_factors(&mut self, factors: HashMap<String, f64>) {
-        self.regional_factors = factors;
+        self.regional_factors = Arc::new(factors);
+    }
+
+    /// Single score distributions to interpret results against, searched before those
+    /// already loaded (the bundled defaults for a new engine)
+    pub fn load_score_references(&mut self, references: Vec<ScoreReference>) {
+        Arc::make_mut(&mut self.score_references).splice(0..0, references);
+    }
+
+    /// Benchmark intensities to compare results with, replacing the bundled defaults
+    pub fn load_impact_benchmarks(&mut self, benchmarks: Vec<ImpactBenchmark>) {
+        self.impact_benchmarks = Arc::new(benchmarks);
     }
 
     pub fn load_climate_adjustments(&mut self, adjustments: HashMap<String, f64>) {
-        self.climate_adjustments = adjustments;
+        self.food_impact_cache = None;
+        self.climate_adjustments = Arc::new(adjustments);
+    }
+
+    /// Replace the GHG scope classification, e.g. with rows reassigning hired equipment fuel
+    pub fn load_scope_table(&mut self, table: ScopeTable) {
+        self.scope_table = Arc::new(table);
+    }
+
+    pub fn load_climate_zones(&mut self, zones: Vec<ClimateZoneBand>) {
+        self.food_impact_cache = None;
+        self.climate_zones = zones;
+    }
+
+    pub fn load_region_zones(&mut self, zones: Vec<RegionZone>) {
+        self.food_impact_cache = None;
+        self.region_zones = Arc::new(zones);
+    }
+
+    pub fn load_climate_adjustment_rules(&mut self, rules: Vec<ClimateAdjustmentRule>) {
+        self.food_impact_cache = None;
+        self.climate_adjustment_rules = Arc::new(rules);
+    }
+
+    /// Climate zone of the assessment's location: from its coordinates when given, otherwise
+    /// from its region's agro-ecological zone. None when neither is tabulated.
+    pub fn climate_zone(&self, assessment: &Assessment) -> Option<ClimateZone> {
+        assessment.coordinates.as_ref()
+            .and_then(|coordinates| self.climate_zones.iter().find(|band| band.contains(coordinates)))
+            .map(|band| band.zone)
+            .or_else(|| self.agro_ecological_zone(assessment).map(|zone| zone.climate_zone()))
+    }
+
+    /// Agro-ecological zone of the assessment's region; None without a region or outside the table
+    pub fn agro_ecological_zone(&self, assessment: &Assessment) -> Option<AgroEcologicalZone> {
+        let region = assessment.region.as_deref()?;
+        self.region_zones.iter()
+            .find(|zone| zone.matches(&assessment.country, region))
+            .map(|zone| zone.zone)
     }
 
-    fn create_factor_key(&self, factor: &ImpactFactor) -> String {
-        match &factor.crop_type {
-            Some(crop) => format!("{:?}_{:?}_{}_{}", 
-                factor.food_category, factor.country, crop, factor.impact_category),
-            None => format!("{:?}_{:?}_{}", 
-                factor.food_category, factor.country, factor.impact_category),
+    fn climate_location(&self, assessment: &Assessment) -> ClimateLocation {
+        ClimateLocation {
+            zone: self.climate_zone(assessment),
+            agro_ecological_zone: self.agro_ecological_zone(assessment),
         }
     }
 
-    pub fn perform_comprehensive_assessment(&mut self, assessment: &mut Assessment) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn perform_comprehensive_assessment(&mut self, assessment: &mut Assessment) -> Result<(), EngineError> {
         info!("Starting comprehensive LCA assessment for {} using {:?}",
               assessment.company_name, self.methodology.characterization_method);
+        self.methodology.validate_impact_categories(&self.assessed_impact_categories())?;
+        require_foods(&assessment.foods)?;
+        assessment.exchange_rates.validate()?;
 
         // DEBUG: Log equipment_energy data
         if let Some(ref eq_energy) = assessment.equipment_energy {
@@ -88,6 +565,28 @@ impl AfricanLCAEngine {
             warn!("⚠️ No equipment_energy data found in assessment!");
         }
 
+        self.check_impact_factors_loaded()?;
+
+        // Factor-based impacts per food for the breakdown, kept so update_food can reuse
+        // them. Foods are independent and the engine is read-only here, so they run in parallel
+        let climate_location = self.climate_location(assessment);
+        let food_impacts = assessment.foods.par_iter()
+            .map(|food| self.calculate_enhanced_food_impacts(food, &assessment.country, &assessment.region, climate_location))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.complete_comprehensive_assessment(assessment, &food_impacts)?;
+        self.food_impact_cache = Some(FoodImpactCache::new(assessment, food_impacts));
+
+        info!("Comprehensive assessment completed for {}", assessment.company_name);
+        Ok(())
+    }
+
+    /// ISO 14040/14044 results from the inventory, with the per-food factor impacts (in
+    /// food order) adjusted for management practices as the breakdown
+    fn complete_comprehensive_assessment(
+        &mut self,
+        assessment: &mut Assessment,
+        food_impacts: &[BTreeMap<String, MidpointResult>]
+    ) -> Result<(), EngineError> {
         // NEW ISO 14040/14044 METHODOLOGY:
         // Step 1: Calculate Life Cycle Inventory (LCI) from user inputs
         info!("Step 1: Calculating Life Cycle Inventory (LCI) from user inputs");
@@ -104,33 +603,52 @@ impl AfricanLCAEngine {
 
         // Step 3: Apply additional regional adjustments and factors
         info!("Step 3: Applying regional adjustments");
-        self.apply_regional_adjustments(&mut midpoint_impacts, &assessment.country, &assessment.region);
+        let water_footprint = water_footprint(&inventory, assessment.foods.iter().map(|food| food.quantity_kg).sum());
+        self.apply_regional_adjustments(&mut midpoint_impacts, &assessment.country, &assessment.region, water_footprint.as_ref());
+        midpoint_impacts.retain(|category, _| self.methodology.includes_category(category));
+        let sign_warnings: Vec<String> = midpoint_impacts.iter_mut()
+            .filter_map(|(category, result)| result.settle_sign(category))
+            .collect();
+
+        // Practice effects the inventory accounts for are named once in the totals and left
+        // out of the breakdown's multipliers
+        let captured_effects = self.lci_calculator.captured_effects();
+        if let Some(ref management_practices) = assessment.management_practices {
+            note_captured_practice_effects(&mut midpoint_impacts, management_practices, captured_effects);
+        }
 
         // Step 4: Calculate per-crop breakdown (if needed for detailed analysis)
-        let mut breakdown_by_food = HashMap::new();
-        for food in &assessment.foods {
-            // For breakdown, we still use the enhanced calculation but now it's supplementary
-            let mut food_results = self.calculate_enhanced_food_impacts(food, &assessment.country, &assessment.region)?;
-
-            // Apply management practice adjustments if available
-            if let Some(ref management_practices) = assessment.management_practices {
-                self.apply_management_practice_adjustments(&mut food_results, management_practices, food)?;
-            }
+        let breakdown_by_food = food_impacts.iter()
+            .zip(breakdown_keys(&assessment.foods))
+            .map(|(food_results, key)| {
+                // For breakdown, we still use the enhanced calculation but now it's supplementary
+                let mut food_results = food_results.clone();
+
+                // Apply management practice adjustments the inventory has not already captured
+                if let Some(ref management_practices) = assessment.management_practices {
+                    self.apply_management_practice_adjustments(&mut food_results, management_practices, captured_effects)?;
+                }
 
-            breakdown_by_food.insert(
-                format!("{} ({}kg)", food.name, food.quantity_kg),
-                food_results
-            );
-        }
+                food_results.retain(|category, _| self.methodology.includes_category(category));
+                Ok((key, food_results))
+            })
+            .collect::<Result<BTreeMap<_, _>, EngineError>>()?;
 
         // Calculate endpoint impacts with enhanced methodology
         let endpoint_impacts = self.calculate_enhanced_endpoint_impacts(&midpoint_impacts)?;
 
         // Calculate single score with African-adapted normalization and weighting
-        let single_score = self.calculate_enhanced_single_score(&endpoint_impacts)?;
+        let single_score = self.calculate_enhanced_single_score(&endpoint_impacts, &assessment.country)?;
 
         // Perform comprehensive data quality assessment
-        let data_quality = self.assess_data_quality(&assessment.foods, &assessment.country)?;
+        let mut data_quality = self.assess_data_quality(assessment)?;
+        data_quality.warnings.extend(sign_warnings);
+        if let Some(warning) = self.methodology.characterization_warning() {
+            warn!("{}", warning);
+            data_quality.warnings.push(warning);
+        }
+        data_quality.warnings.extend(self.lci_calculator.data_gaps().iter().cloned());
+        data_quality.warnings.extend(assessment.cost_rollup().warnings);
 
         // Perform sensitivity analysis
         let sensitivity_analysis = self.perform_sensitivity_analysis(&assessment.foods, &assessment.country)?;
@@ -138,8 +656,19 @@ impl AfricanLCAEngine {
         // Generate comparative analysis with management practice recommendations
         let comparative_analysis = self.generate_comprehensive_comparative_analysis(&midpoint_impacts, &assessment.country, &assessment.foods)?;
 
+        // Generate costed recommendations from the fertilizer/energy spend the user entered
+        let mut recommendations = self.generate_recommendations(assessment);
+        let global_warming_kg = midpoint_impacts.get("Global warming").map_or(0.0, |result| result.value);
+        prioritize_recommendations(&mut recommendations, global_warming_kg, &assessment.currency, &assessment.exchange_rates);
+
+        // Impacts embedded in post-harvest losses
+        let food_loss = food_loss_footprint(&midpoint_impacts, &breakdown_by_food, &post_harvest_loss_fractions(&assessment.foods));
+        let ghg_scopes = self.ghg_scopes(assessment, &midpoint_impacts);
+        let product_carbon_footprints = self.carbon_footprints(assessment, &midpoint_impacts, &data_quality);
+        let impacts_per_ha = impacts_per_hectare(&assessment.foods, &midpoint_impacts);
+
         // Store enhanced results
-        assessment.results = Some(LCAResults {
+        let mut results = LCAResults {
             midpoint_impacts,
             endpoint_impacts,
             single_score,
@@ -149,17 +678,31 @@ impl AfricanLCAEngine {
             comparative_analysis: Some(comparative_analysis),
             management_analysis: None, // TODO: Implement management analysis
             benchmarking: None, // TODO: Implement benchmarking
-            recommendations: None, // TODO: Implement recommendations
-            lci_inventory: Some(inventory_to_flows(&inventory)),
-        });
-
-        info!("Comprehensive assessment completed for {}", assessment.company_name);
+            recommendations: Some(recommendations),
+            lci_inventory: assessment.methodology.include_inventory
+                .then(|| inventory_to_flows(&inventory)),
+            product_footprints: None,
+            capacity_range: None,
+            food_loss_footprint: Some(food_loss),
+            ghg_scopes,
+            target_compliance: None,
+            product_carbon_footprints,
+            water_footprint,
+            impacts_per_ha,
+        };
+        if assessment.methodology.include_result_metadata {
+            results.attach_metadata(&self.methodology.characterization_method);
+        }
+        assessment.results = Some(results);
         Ok(())
     }
 
-    pub fn perform_assessment(&mut self, assessment: &mut Assessment) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn perform_assessment(&mut self, assessment: &mut Assessment) -> Result<(), EngineError> {
         info!("Starting enhanced LCA assessment for {} using {:?}",
               assessment.company_name, self.methodology.characterization_method);
+        self.methodology.validate_impact_categories(&self.assessed_impact_categories())?;
+        require_foods(&assessment.foods)?;
+        assessment.exchange_rates.validate()?;
 
         // NEW ISO 14040/14044 METHODOLOGY:
         // If we have management practices data, use the ISO-compliant LCI approach
@@ -169,53 +712,150 @@ impl AfricanLCAEngine {
         }
 
         // Otherwise, fall back to hybrid approach (LCI + category factors)
+        self.check_impact_factors_loaded()?;
         info!("Limited data available - using hybrid LCI + category factors methodology");
 
+        // Factor-based impacts per food, kept so update_food can reuse them. Computed in
+        // parallel and collected in food order, so the totals add up in the same order
+        let climate_location = self.climate_location(assessment);
+        let food_impacts = assessment.foods.par_iter()
+            .map(|food| self.calculate_enhanced_food_impacts(food, &assessment.country, &assessment.region, climate_location)
+)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.complete_hybrid_assessment(assessment, &food_impacts)?;
+        self.food_impact_cache = Some(FoodImpactCache::new(assessment, food_impacts));
+
+        info!("Enhanced assessment completed for {}", assessment.company_name);
+        Ok(())
+    }
+
+    /// Update one food and refresh the results, recomputing only that food's factor-based
+    /// impacts and reusing the other foods' impacts from the last run on this assessment.
+    /// The inventory, totals, endpoints and single score are then rebuilt exactly as in
+    /// perform_assessment, so the results match a full recompute. Falls back to a full
+    /// assessment when there is no reusable run, or the country, region, location or any
+    /// food changed since.
+    pub fn update_food(
+        &mut self,
+        assessment: &mut Assessment,
+        index: usize,
+        new_food: FoodItem
+    ) -> Result<(), EngineError> {
+        if index >= assessment.foods.len() {
+            return Err(EngineError::NoSuchFood { index, count: assessment.foods.len() });
+        }
+        let cache = self.food_impact_cache.take()
+            .filter(|cache| assessment.results.is_some() && cache.is_valid_for(assessment));
+        assessment.foods[index] = new_food;
+        let Some(mut cache) = cache else {
+            info!("No reusable per-food impacts for {} - performing full assessment", assessment.company_name);
+            return self.perform_assessment(assessment);
+        };
+
+        let climate_location = self.climate_location(assessment);
+        cache.food_impacts[index] = self.calculate_enhanced_food_impacts(
+            &assessment.foods[index], &assessment.country, &assessment.region, climate_location
+        )?;
+        cache.foods[index] = assessment.foods[index].clone();
+        if assessment.management_practices.is_some() {
+            self.complete_comprehensive_assessment(assessment, &cache.food_impacts)?;
+        } else {
+            self.complete_hybrid_assessment(assessment, &cache.food_impacts)?;
+        }
+        self.food_impact_cache = Some(cache);
+        Ok(())
+    }
+
+    /// Without impact factors every factor lookup falls back to a default estimate; a
+    /// wrong data path then yields plausible-looking but unfounded numbers
+    fn check_impact_factors_loaded(&self) -> Result<(), EngineError> {
+        if !self.impact_factors.is_empty() {
+            return Ok(());
+        }
+        if self.strict {
+            return Err(FactorError::NoneLoaded.into());
+        }
+        warn!("No impact factors loaded - all factor-based impacts are default estimates");
+        Ok(())
+    }
+
+    /// Hybrid LCI + category factor results from the per-food factor impacts (in food order)
+    fn complete_hybrid_assessment(
+        &mut self,
+        assessment: &mut Assessment,
+        food_impacts: &[BTreeMap<String, MidpointResult>]
+    ) -> Result<(), EngineError> {
         // Step 1: Calculate what we can from LCI with extended characterization
         let inventory = self.lci_calculator.calculate_inventory(assessment)?;
         let mut midpoint_impacts = self.lci_calculator.calculate_extended_midpoint_impacts(&inventory, assessment)?;
 
-        // Step 2: For missing data, supplement with category-level factors
-        let mut breakdown_by_food = HashMap::new();
-        for food in &assessment.foods {
-            let food_results = self.calculate_enhanced_food_impacts(food, &assessment.country, &assessment.region)?;
-
-            breakdown_by_food.insert(
-                format!("{} ({}kg)", food.name, food.quantity_kg),
-                food_results.clone()
-            );
+        // Step 2: For missing data, supplement with category-level factors. The inventory's
+        // results are per kg; the foods' factor-based impacts are totals, so they are spread
+        // over the farm's production before they are added.
+        let production_kg: f64 = assessment.foods.iter().map(|food| food.quantity_kg).sum();
+        let from_factors: BTreeSet<String> = midpoint_impacts.iter()
+            .filter(|(_, result)| result.value == 0.0)
+            .map(|(category, _)| category.clone())
+            .collect();
+        let mut breakdown_by_food = BTreeMap::new();
+        for (key, food_results) in breakdown_keys(&assessment.foods).into_iter().zip(food_impacts) {
+            let mut selected_results = food_results.clone();
+            selected_results.retain(|category, _| self.methodology.includes_category(category));
+            breakdown_by_food.insert(key, selected_results);
 
             // Only aggregate categories that weren't calculated from LCI
-            for (category, result) in food_results {
-                if let Some(total_result) = midpoint_impacts.get_mut(&category) {
-                    // If LCI didn't calculate this impact, use the category factor
-                    if total_result.value == 0.0 {
-                        self.aggregate_midpoint_results(total_result, &result);
+            for (category, result) in food_results.iter().filter(|(category, _)| from_factors.contains(*category)) {
+                if let Some(total_result) = midpoint_impacts.get_mut(category) {
+                    if let Some(result) = result.on_basis(total_result.basis, production_kg, None) {
+                        total_result.aggregate(&result);
                     }
                 }
             }
         }
 
         // Apply regional adjustments
-        self.apply_regional_adjustments(&mut midpoint_impacts, &assessment.country, &assessment.region);
+        let water_footprint = water_footprint(&inventory, assessment.foods.iter().map(|food| food.quantity_kg).sum());
+        self.apply_regional_adjustments(&mut midpoint_impacts, &assessment.country, &assessment.region, water_footprint.as_ref());
+        midpoint_impacts.retain(|category, _| self.methodology.includes_category(category));
+        let sign_warnings: Vec<String> = midpoint_impacts.iter_mut()
+            .filter_map(|(category, result)| result.settle_sign(category))
+            .collect();
 
         // Calculate endpoint impacts with enhanced methodology
         let endpoint_impacts = self.calculate_enhanced_endpoint_impacts(&midpoint_impacts)?;
 
         // Calculate single score with African-adapted normalization and weighting
-        let single_score = self.calculate_enhanced_single_score(&endpoint_impacts)?;
+        let single_score = self.calculate_enhanced_single_score(&endpoint_impacts, &assessment.country)?;
 
         // Perform comprehensive data quality assessment
-        let data_quality = self.assess_data_quality(&assessment.foods, &assessment.country)?;
+        let mut data_quality = self.assess_data_quality(assessment)?;
+        data_quality.warnings.extend(sign_warnings);
+        if let Some(warning) = self.methodology.characterization_warning() {
+            warn!("{}", warning);
+            data_quality.warnings.push(warning);
+        }
+        data_quality.warnings.extend(self.lci_calculator.data_gaps().iter().cloned());
+        data_quality.warnings.extend(assessment.cost_rollup().warnings);
 
         // Perform sensitivity analysis
         let sensitivity_analysis = self.perform_sensitivity_analysis(&assessment.foods, &assessment.country)?;
 
         // Generate comparative analysis
-        let comparative_analysis = self.generate_comparative_analysis(&midpoint_impacts, &assessment.country)?;
+        let comparative_analysis = self.generate_comparative_analysis(&midpoint_impacts, &assessment.country, &assessment.foods)?;
+
+        // Generate costed recommendations
+        let mut recommendations = self.generate_recommendations(assessment);
+        let global_warming_kg = midpoint_impacts.get("Global warming").map_or(0.0, |result| result.value);
+        prioritize_recommendations(&mut recommendations, global_warming_kg, &assessment.currency, &assessment.exchange_rates);
+
+        // Impacts embedded in post-harvest losses
+        let food_loss = food_loss_footprint(&midpoint_impacts, &breakdown_by_food, &post_harvest_loss_fractions(&assessment.foods));
+        let ghg_scopes = self.ghg_scopes(assessment, &midpoint_impacts);
+        let product_carbon_footprints = self.carbon_footprints(assessment, &midpoint_impacts, &data_quality);
+        let impacts_per_ha = impacts_per_hectare(&assessment.foods, &midpoint_impacts);
 
         // Store enhanced results
-        assessment.results = Some(LCAResults {
+        let mut results = LCAResults {
             midpoint_impacts,
             endpoint_impacts,
             single_score,
@@ -225,15 +865,74 @@ impl AfricanLCAEngine {
             comparative_analysis: Some(comparative_analysis),
             management_analysis: None, // TODO: Implement management analysis
             benchmarking: None, // TODO: Implement benchmarking
-            recommendations: None, // TODO: Implement recommendations
-            lci_inventory: Some(inventory_to_flows(&inventory)),
-        });
+            recommendations: Some(recommendations),
+            lci_inventory: assessment.methodology.include_inventory
+                .then(|| inventory_to_flows(&inventory)),
+            product_footprints: None,
+            capacity_range: None,
+            food_loss_footprint: Some(food_loss),
+            ghg_scopes,
+            target_compliance: None,
+            product_carbon_footprints,
+            water_footprint,
+            impacts_per_ha,
+        };
+        if assessment.methodology.include_result_metadata {
+            results.attach_metadata(&self.methodology.characterization_method);
+        }
+        assessment.results = Some(results);
 
-        info!("Enhanced assessment completed for {}", assessment.company_name);
         Ok(())
     }
 
+    /// Annual Global warming by GHG Protocol scope, from the activities of the last inventory.
+    /// Farm midpoints are per kg of output, so the total is scaled back up by production.
+    fn ghg_scopes(&self, assessment: &Assessment, midpoint_impacts: &BTreeMap<String, MidpointResult>) -> Option<ScopeBreakdown> {
+        let gwp = midpoint_impacts.get("Global warming")?;
+        let production_kg: f64 = assessment.foods.iter().map(|food| food.quantity_kg).sum();
+        let annual = if production_kg > 0.0 { gwp.value * production_kg } else { gwp.value };
+        Some(ScopeBreakdown::classify(self.lci_calculator.gwp_by_activity(), annual, &self.scope_table))
+    }
+
+    /// ISO 14067 footprint of each food per kg. The inventory covers the whole farm, so every
+    /// food carries the farm's Global warming per kg (mass allocation), split by carbon origin.
+    fn carbon_footprints(
+        &self,
+        assessment: &Assessment,
+        midpoint_impacts: &BTreeMap<String, MidpointResult>,
+        data_quality: &DataQuality
+    ) -> Option<BTreeMap<String, ProductCarbonFootprint>> {
+        let gwp = midpoint_impacts.get("Global warming")?;
+        let production_kg: f64 = assessment.foods.iter().map(|food| food.quantity_kg).sum();
+        let per_kg = if production_kg > 0.0 {
+            CarbonOriginSplit::classify(self.lci_calculator.gwp_by_activity(), gwp.value * production_kg, &self.scope_table)
+                .scaled(1.0 / production_kg)
+        } else {
+            CarbonOriginSplit::classify(Vec::new(), gwp.value, &self.scope_table)
+        };
+        Some(assessment.foods.iter()
+            .map(|food| (food.id.clone(), ProductCarbonFootprint::new(
+                &food.id,
+                &food.name,
+                "kg",
+                per_kg,
+                &assessment.methodology.system_boundary,
+                data_quality.overall_confidence,
+                gwp.data_quality_score
+            )))
+            .collect())
+    }
+
+    /// The categories to calculate: the methodology's selection, plus Water consumption
+    /// when Water scarcity is selected since scarcity is derived from it
     fn get_impact_categories(&self) -> Vec<String> {
+        self.all_impact_categories().into_iter()
+            .filter(|category| self.methodology.includes_category(category)
+                || (category == "Water consumption" && self.methodology.includes_category("Water scarcity")))
+            .collect()
+    }
+
+    fn all_impact_categories(&self) -> Vec<String> {
         vec![
             "Global warming".to_string(),
             "Water consumption".to_string(),
@@ -251,47 +950,41 @@ impl AfricanLCAEngine {
         ]
     }
 
+    /// Factor-based categories plus those only the inventory yields: there are no per-food
+    /// factors for energy, so it is reported only from fuel and electricity use
+    fn assessed_impact_categories(&self) -> Vec<String> {
+        let mut categories = self.all_impact_categories();
+        categories.push("Energy consumption".to_string());
+        categories
+    }
+
     fn get_impact_unit(&self, category: &str) -> String {
-        match category {
-            "Global warming" => "kg CO2-eq".to_string(),
-            "Water consumption" => "m3".to_string(),
-            "Water scarcity" => "m3 H2O-eq".to_string(),
-            "Land use" => "m2a crop-eq".to_string(),
-            "Biodiversity loss" => "MSA*m2*yr".to_string(),
-            "Soil degradation" => "kg soil-eq".to_string(),
-            "Terrestrial acidification" => "kg SO2-eq".to_string(),
-            "Freshwater eutrophication" => "kg P-eq".to_string(),
-            "Marine eutrophication" => "kg N-eq".to_string(),
-            "Fossil depletion" => "kg oil-eq".to_string(),
-            "Mineral depletion" => "kg Fe-eq".to_string(),
-            "Particulate matter formation" => "PM2.5-eq".to_string(),
-            "Photochemical oxidation" => "kg NMVOC-eq".to_string(),
-            _ => "Unknown".to_string(),
-        }
+        impact_unit(category).to_string()
     }
 
     fn calculate_enhanced_food_impacts(
         &self, 
         food: &FoodItem, 
         country: &Country, 
-        _region: &Option<String>
-    ) -> Result<HashMap<String, MidpointResult>, Box<dyn std::error::Error>> {
+        _region: &Option<String>,
+        climate_location: ClimateLocation
+    ) -> Result<BTreeMap<String, MidpointResult>, EngineError> {
         
-        let mut impacts = HashMap::new();
+        let mut impacts = BTreeMap::new();
         let impact_categories = self.get_impact_categories();
 
         // Hierarchical data lookup: specific crop -> category -> global fallback
         let lookup_hierarchy = self.build_lookup_hierarchy(food, country);
 
         for category in &impact_categories {
-            let (factor_value, factor_source, uncertainty, pedigree) = 
+            let (factor_value, factor_source, geometric_sd, pedigree) = 
                 self.find_best_factor(food, country, category, &lookup_hierarchy)?;
 
             // Calculate per-unit impact first (factor_value is already per kg)
             let per_unit_impact = factor_value;
 
             // Apply climate adjustments for tropical conditions (per unit)
-            let climate_adjusted_per_unit = self.apply_climate_adjustments(per_unit_impact, category, food);
+            let (climate_adjusted_per_unit, climate_source) = self.apply_climate_adjustments(per_unit_impact, category, food, climate_location);
 
             // Apply seasonal adjustments if applicable (per unit)
             let seasonally_adjusted_per_unit = self.apply_seasonal_adjustments(climate_adjusted_per_unit, food);
@@ -302,48 +995,120 @@ impl AfricanLCAEngine {
             // Scale to total impact for display purposes
             let total_impact = system_adjusted_per_unit * food.quantity_kg;
 
-            // Calculate uncertainty range (scaled to total)
-            let uncertainty_range = self.calculate_uncertainty_range(
-                total_impact, 
-                uncertainty, 
-                &pedigree
-            );
-
+            // The factor's lognormal uncertainty scales with the total
             impacts.insert(category.clone(), MidpointResult {
                 value: total_impact,
                 unit: self.get_impact_unit(category),
-                uncertainty_range,
+                basis: ImpactBasis::Total,
+                uncertainty_range: lognormal_interval(total_impact, geometric_sd),
+                geometric_sd: Some(geometric_sd),
                 data_quality_score: pedigree.calculate_overall_quality_score(),
-                contributing_sources: vec![factor_source],
+                contributing_sources: [Some(factor_source), climate_source].into_iter().flatten().collect(),
+                metadata: None,
             });
         }
 
+        if food.poultry.is_some() {
+            let items = self.lci_calculator.calculate_poultry_inventory(food, country, true);
+            self.apply_livestock_inventory(&mut impacts, &items, "Feed crops, housing and range")?;
+        }
+        if food.aquaculture.is_some() {
+            let items = self.lci_calculator.calculate_aquaculture_inventory(food, country, true);
+            self.apply_livestock_inventory(&mut impacts, &items, "Feed crops")?;
+        }
+        if food.dairy.is_some() {
+            let items = self.lci_calculator.calculate_dairy_inventory(food);
+            self.apply_livestock_inventory(&mut impacts, &items, "Feed crops and pasture")?;
+        }
+
         Ok(impacts)
     }
 
+    /// Replace the flat Poultry/Eggs/Dairy/Fish factors with herd-, flock- or farm-level
+    /// inventory for the categories a livestock profile drives (feed, enteric, manure and
+    /// pond CH4/N2O/NH3, housing and aeration energy, land and exchanged water). Categories
+    /// the inventory has no flows for keep their factors.
+    fn apply_livestock_inventory(
+        &self,
+        impacts: &mut BTreeMap<String, MidpointResult>,
+        items: &[InventoryItem],
+        land_description: &str,
+    ) -> Result<(), EngineError> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let inventory: BTreeMap<String, InventoryItem> = items.iter()
+            .map(|item| (format!("{}_{:?}", item.substance, item.compartment), item.clone()))
+            .collect();
+        let (gwp, gwp_sources) = self.lci_calculator.calculate_midpoint_impacts(&inventory)?
+            .remove("Global warming")
+            .map(|result| (result.value, result.contributing_sources))
+            .unwrap_or_default();
+
+        let land_m2a: f64 = items.iter()
+            .filter(|item| item.substance.contains("Land occupation"))
+            .map(|item| item.quantity)
+            .sum();
+        let nh3_kg: f64 = items.iter()
+            .filter(|item| item.substance == "Ammonia (NH3)")
+            .map(|item| item.quantity)
+            .sum();
+        let water_m3: f64 = items.iter()
+            .filter(|item| item.substance == "Water")
+            .map(|item| item.quantity)
+            .sum();
+        let has = |substance: &str| items.iter().any(|item| item.substance.contains(substance));
+
+        let overrides = [
+            ("Global warming", true, gwp, gwp_sources),
+            ("Land use", has("Land occupation"), land_m2a, vec![format!("{}: {:.0} m2a", land_description, land_m2a)]),
+            ("Terrestrial acidification", has("Ammonia (NH3)"), nh3_kg * ACIDIFICATION_NH3, vec![format!("Manure NH3: {:.1} kg", nh3_kg)]),
+            ("Water consumption", has("Water"), water_m3, vec![format!("Water exchange: {:.0} m3", water_m3)]),
+        ];
+        for (category, inventoried, value, sources) in overrides {
+            if !inventoried || !self.methodology.includes_category(category) {
+                continue;
+            }
+            impacts.insert(category.to_string(), MidpointResult {
+                value,
+                unit: self.get_impact_unit(category),
+                basis: ImpactBasis::Total,
+                uncertainty_range: (value * 0.7, value * 1.3),
+                geometric_sd: None,
+                data_quality_score: 0.75,
+                contributing_sources: sources,
+                metadata: None,
+            });
+        }
+
+        Ok(())
+    }
+
     #[allow(dead_code)]
     fn calculate_per_unit_food_impacts(
         &self, 
         food: &FoodItem, 
         country: &Country, 
-        _region: &Option<String>
-    ) -> Result<HashMap<String, MidpointResult>, Box<dyn std::error::Error>> {
+        _region: &Option<String>,
+        climate_location: ClimateLocation
+    ) -> Result<BTreeMap<String, MidpointResult>, EngineError> {
         
-        let mut impacts = HashMap::new();
+        let mut impacts = BTreeMap::new();
         let impact_categories = self.get_impact_categories();
 
         // Hierarchical data lookup: specific crop -> category -> global fallback
         let lookup_hierarchy = self.build_lookup_hierarchy(food, country);
 
         for category in &impact_categories {
-            let (factor_value, factor_source, uncertainty, pedigree) = 
+            let (factor_value, factor_source, geometric_sd, pedigree) = 
                 self.find_best_factor(food, country, category, &lookup_hierarchy)?;
 
             // Calculate per-unit impact (factor_value is already per kg)
             let per_unit_impact = factor_value;
 
             // Apply climate adjustments for tropical conditions (per unit)
-            let climate_adjusted_per_unit = self.apply_climate_adjustments(per_unit_impact, category, food);
+            let (climate_adjusted_per_unit, climate_source) = self.apply_climate_adjustments(per_unit_impact, category, food, climate_location);
 
             // Apply seasonal adjustments if applicable (per unit)
             let seasonally_adjusted_per_unit = self.apply_seasonal_adjustments(climate_adjusted_per_unit, food);
@@ -351,87 +1116,47 @@ impl AfricanLCAEngine {
             // Apply production system adjustments (per unit)
             let system_adjusted_per_unit = self.apply_production_system_adjustments(seasonally_adjusted_per_unit, food);
 
-            // Calculate per-unit uncertainty range
-            let uncertainty_value = (uncertainty.1 - uncertainty.0) / (2.0 * system_adjusted_per_unit).max(0.001);
-            let per_unit_uncertainty_range = self.calculate_per_unit_uncertainty_range(
-                system_adjusted_per_unit, 
-                uncertainty_value, 
-                &pedigree
-            );
-
             impacts.insert(category.clone(), MidpointResult {
                 value: system_adjusted_per_unit,
                 unit: format!("{} per kg", self.get_impact_unit(category)),
-                uncertainty_range: per_unit_uncertainty_range,
+                basis: ImpactBasis::PerKgProduct,
+                uncertainty_range: lognormal_interval(system_adjusted_per_unit, geometric_sd),
+                geometric_sd: Some(geometric_sd),
                 data_quality_score: pedigree.calculate_overall_quality_score(),
-                contributing_sources: vec![factor_source],
+                contributing_sources: [Some(factor_source), climate_source].into_iter().flatten().collect(),
+                metadata: None,
             });
         }
 
         Ok(impacts)
     }
 
-    fn calculate_per_unit_uncertainty_range(
-        &self,
-        base_value: f64,
-        uncertainty: f64,
-        pedigree: &PedigreeScore
-    ) -> (f64, f64) {
-        // Calculate uncertainty range for per-unit values
-        let combined_uncertainty = (uncertainty.powi(2) + 
-            (pedigree.reliability as f64 * 0.1).powi(2) + 
-            (pedigree.completeness as f64 * 0.05).powi(2) + 
-            (pedigree.temporal_correlation as f64 * 0.03).powi(2) + 
-            (pedigree.geographical_correlation as f64 * 0.07).powi(2) + 
-            (pedigree.technological_correlation as f64 * 0.04).powi(2)).sqrt();
-        
-        let lower = base_value * (1.0 - combined_uncertainty);
-        let upper = base_value * (1.0 + combined_uncertainty);
-        
-        (lower.max(0.0), upper)
-    }
-
-    fn build_lookup_hierarchy(&self, food: &FoodItem, country: &Country) -> Vec<String> {
-        let mut hierarchy = Vec::new();
-
-        // 1. Most specific: Country + Crop type + Category
-        if let Some(crop_type) = &food.crop_type {
-            hierarchy.push(format!("{:?}_{:?}_{}", food.category, country, crop_type));
-        }
-
-        // 2. Country + Category
-        hierarchy.push(format!("{:?}_{:?}", food.category, country));
-
-        // 3. Global + Crop type
-        if let Some(crop_type) = &food.crop_type {
-            hierarchy.push(format!("{:?}_Global_{}", food.category, crop_type));
-        }
-
-        // 4. Global + Category
-        hierarchy.push(format!("{:?}_Global", food.category));
-
-        hierarchy
+    /// Country + crop type, country + category, global + crop type, global + category
+    fn build_lookup_hierarchy(&self, food: &FoodItem, country: &Country) -> FallbackChain {
+        self.impact_factors.fallback_chain(&food.category, food.crop_type.as_deref(), country)
     }
 
     fn find_best_factor(
         &self,
         food: &FoodItem,
-        _country: &Country,
+        country: &Country,
         category: &str,
-        hierarchy: &[String]
-    ) -> Result<(f64, String, (f64, f64), PedigreeScore), Box<dyn std::error::Error>> {
-        
+        hierarchy: &FallbackChain
+    ) -> Result<ResolvedFactor, EngineError> {
+
+        // Primary data first, along its own hierarchy (the indexes intern crop types separately)
+        let primary = (!self.factor_overrides.is_empty())
+            .then(|| self.factor_overrides.fallback_chain(&food.category, food.crop_type.as_deref(), country))
+            .and_then(|chain| self.factor_overrides.find(&chain, category));
+
         // Try to find factor following the hierarchy
-        for base_key in hierarchy {
-            let full_key = format!("{}_{}", base_key, category);
-            if let Some(factor) = self.impact_factors.get(&full_key) {
-                return Ok((
-                    factor.value_per_kg,
-                    factor.source.clone(),
-                    factor.uncertainty_range,
-                    factor.pedigree_score.clone(),
-                ));
-            }
+        if let Some(factor) = primary.or_else(|| self.impact_factors.find(hierarchy, category)) {
+            return Ok((
+                factor.value_per_kg,
+                factor.source.clone(),
+                resolved_geometric_sd(factor),
+                factor.pedigree_score.clone(),
+            ));
         }
 
         // Fallback to default values with high uncertainty
@@ -450,29 +1175,45 @@ impl AfricanLCAEngine {
         Ok((
             default_value,
             "Default estimate - high uncertainty".to_string(),
-            default_uncertainty,
+            factor_geometric_sd(category, default_uncertainty, &default_pedigree),
             default_pedigree,
         ))
     }
 
-    fn apply_climate_adjustments(&self, base_impact: f64, category: &str, food: &FoodItem) -> f64 {
-        let adjustment_factor = match category {
-            "Global warming" => {
-                // Higher methane emissions in tropical conditions
-                if matches!(food.category, FoodCategory::Cereals | FoodCategory::Meat) {
-                    self.climate_adjustments.get("methane_emission_factor").unwrap_or(&1.0)
-                } else {
-                    &1.0
-                }
-            },
-            "Soil degradation" => {
-                // Higher decomposition rates in tropical conditions
-                self.climate_adjustments.get("tropical_decomposition_factor").unwrap_or(&1.0)
-            },
-            _ => &1.0,
+    /// The impact adjusted for the location's climate, and the adjustment's provenance when
+    /// it changed the value. The region's agro-ecological zone rules come first; without one
+    /// that matches, the climate zone's (or country default) factors apply.
+    fn apply_climate_adjustments(&self, base_impact: f64, category: &str, food: &FoodItem, location: ClimateLocation) -> (f64, Option<String>) {
+        let rule = location.agro_ecological_zone.and_then(|zone| {
+            self.climate_adjustment_rules.iter().find(|rule| rule.applies_to(zone, food, category))
+        });
+        let (adjustment_factor, provenance) = match rule {
+            Some(rule) => (rule.factor, format!("{}, {}", rule.zone.label(), rule.source)),
+            None => {
+                let key = match category {
+                    // Higher methane emissions in tropical conditions
+                    "Global warming" if matches!(food.category, FoodCategory::Cereals | FoodCategory::Meat) => "methane_emission_factor",
+                    // Higher decomposition rates in tropical conditions
+                    "Soil degradation" => "tropical_decomposition_factor",
+                    _ => return (base_impact, None),
+                };
+                let zone = location.zone.map_or("country default", |zone| zone.key_prefix());
+                (self.climate_adjustment(key, location.zone), format!("{} {}", zone, key))
+            }
         };
 
-        base_impact * adjustment_factor
+        let source = (adjustment_factor != 1.0)
+            .then(|| format!("Climate adjustment x{} ({})", adjustment_factor, provenance));
+        (base_impact * adjustment_factor, source)
+    }
+
+    /// The zone's adjustment when one is known and tabulated, otherwise the country default
+    fn climate_adjustment(&self, key: &str, climate_zone: Option<ClimateZone>) -> f64 {
+        climate_zone
+            .and_then(|zone| self.climate_adjustments.get(&format!("{}_{}", zone.key_prefix(), key)))
+            .or_else(|| self.climate_adjustments.get(key))
+            .copied()
+            .unwrap_or(1.0)
     }
 
     fn apply_seasonal_adjustments(&self, base_impact: f64, food: &FoodItem) -> f64 {
@@ -490,7 +1231,7 @@ impl AfricanLCAEngine {
     }
 
     fn apply_production_system_adjustments(&self, base_impact: f64, food: &FoodItem) -> f64 {
-        match &food.production_system {
+        match &food.effective_production_system() {
             Some(ProductionSystem::Intensive) => base_impact * 1.2, // Higher impacts per kg
             Some(ProductionSystem::Extensive) => base_impact * 1.5, // Much higher for livestock
             Some(ProductionSystem::Agroforestry) => base_impact * 0.8, // Lower impacts
@@ -499,79 +1240,43 @@ impl AfricanLCAEngine {
         }
     }
 
-    fn calculate_uncertainty_range(
-        &self,
-        central_value: f64,
-        base_uncertainty: (f64, f64),
-        pedigree: &PedigreeScore
-    ) -> (f64, f64) {
-        let uncertainty_factor = pedigree.calculate_uncertainty_factor();
-        let expanded_range = (
-            base_uncertainty.0 * uncertainty_factor,
-            base_uncertainty.1 * uncertainty_factor
-        );
-        
-        // Ensure uncertainty range is centered on the central value
-        let lower_bound = central_value - (central_value - expanded_range.0).abs();
-        let upper_bound = central_value + (expanded_range.1 - central_value).abs();
-        
-        (lower_bound.max(0.0), upper_bound) // Non-negative values only
-    }
-
-    fn aggregate_midpoint_results(&self, total: &mut MidpointResult, addition: &MidpointResult) {
-        // Simple addition for central values
-        total.value += addition.value;
-
-        // Uncertainty propagation (assuming independence)
-        let total_variance = (total.uncertainty_range.1 - total.uncertainty_range.0).powi(2) / 16.0; // 95% CI
-        let addition_variance = (addition.uncertainty_range.1 - addition.uncertainty_range.0).powi(2) / 16.0;
-        let combined_variance = total_variance + addition_variance;
-        let combined_std = combined_variance.sqrt();
-
-        total.uncertainty_range = (
-            (total.value - 2.0 * combined_std).max(0.0),
-            total.value + 2.0 * combined_std
-        );
-
-        // Quality score weighted by contribution
-        let total_contribution = total.value;
-        let addition_contribution = addition.value;
-        let total_weight = total_contribution + addition_contribution;
-        
-        if total_weight > 0.0 {
-            total.data_quality_score = (total.data_quality_score * total_contribution + 
-                                      addition.data_quality_score * addition_contribution) / total_weight;
-        }
-
-        // Merge sources
-        total.contributing_sources.extend(addition.contributing_sources.clone());
-    }
-
     fn apply_regional_adjustments(
         &self,
-        impacts: &mut HashMap<String, MidpointResult>,
+        impacts: &mut BTreeMap<String, MidpointResult>,
         country: &Country,
-        _region: &Option<String>
+        _region: &Option<String>,
+        water_footprint: Option<&WaterFootprint>
     ) {
         // Apply water scarcity adjustments using AWARE methodology
-        if let Some(water_result) = impacts.get_mut("Water consumption") {
-            let aware_factor = match (country, _region.as_deref()) {
-                (Country::Ghana, _) => self.regional_factors.get("Ghana_water_scarcity").unwrap_or(&20.0),
-                (Country::Nigeria, Some("Northern")) => self.regional_factors.get("Nigeria_north_water_scarcity").unwrap_or(&30.0),
-                (Country::Nigeria, _) => self.regional_factors.get("Nigeria_south_water_scarcity").unwrap_or(&15.0),
-                _ => &1.0,
+        if let Some(water_result) = impacts.get_mut("Water consumption").filter(|_| self.methodology.includes_category("Water scarcity")) {
+            // Only blue water is scarce; green (rain) and grey water do not deplete what other
+            // users can withdraw. Without the split all water consumption counts as blue.
+            let blue_share = match water_footprint {
+                Some(footprint) if water_result.value > 0.0 => (footprint.blue / water_result.value).min(1.0),
+                Some(_) => 0.0,
+                None => 1.0,
+            };
+            let (key, default) = match (country, _region.as_deref()) {
+                (Country::Ghana, _) => ("Ghana_water_scarcity", 20.0),
+                (Country::Nigeria, Some("Northern")) => ("Nigeria_north_water_scarcity", 30.0),
+                (Country::Nigeria, _) => ("Nigeria_south_water_scarcity", 15.0),
+                (Country::Global, _) => ("Global_water_scarcity", self.characterization_factors.water_scarcity.global_average_aware),
             };
+            let aware_factor = self.regional_factors.get(key).copied().unwrap_or(default);
 
             // Create water scarcity impact
             let water_scarcity_impact = MidpointResult {
-                value: water_result.value * aware_factor,
-                unit: "m3 H2O-eq".to_string(),
+                value: water_result.value * blue_share * aware_factor,
+                unit: format!("m3 H2O-eq{}", water_result.basis.unit_suffix()),
+                basis: water_result.basis,
                 uncertainty_range: (
-                    water_result.uncertainty_range.0 * aware_factor,
-                    water_result.uncertainty_range.1 * aware_factor
+                    water_result.uncertainty_range.0 * blue_share * aware_factor,
+                    water_result.uncertainty_range.1 * blue_share * aware_factor
                 ),
+                geometric_sd: water_result.geometric_sd,
                 data_quality_score: water_result.data_quality_score,
                 contributing_sources: vec![format!("AWARE regional factor: {}", aware_factor)],
+                metadata: None,
             };
 
             impacts.insert("Water scarcity".to_string(), water_scarcity_impact);
@@ -582,189 +1287,122 @@ impl AfricanLCAEngine {
         // Implementation depends on available data
     }
 
+    /// Midpoint-to-endpoint characterization as (endpoint, midpoint, factor per midpoint unit)
+    pub fn endpoint_characterization(&self) -> Vec<(&'static str, &'static str, f64)> {
+        vec![
+            // Human Health (DALY per kg)
+            ("Human Health", "Global warming", self.characterization_factors.human_health.climate_health_africa),
+            ("Human Health", "Water scarcity", self.characterization_factors.human_health.water_stress_health_africa),
+            ("Human Health", "Particulate matter formation", self.characterization_factors.human_health.air_quality_health_africa),
+            // Ecosystem Quality (species.yr per kg)
+            ("Ecosystem Quality", "Global warming", 1.2e-14),
+            ("Ecosystem Quality", "Land use", 2.1e-10),
+            ("Ecosystem Quality", "Biodiversity loss", 1.5e-12),
+            // Resource Scarcity (USD per kg)
+            ("Resource Scarcity", "Water scarcity", self.characterization_factors.resource_scarcity.water_scarcity_africa),
+            ("Resource Scarcity", "Fossil depletion", 0.055), // Global factor
+        ]
+    }
+
     fn calculate_enhanced_endpoint_impacts(
         &self,
-        midpoint: &HashMap<String, MidpointResult>
-    ) -> Result<HashMap<String, EndpointResult>, Box<dyn std::error::Error>> {
+        midpoint: &BTreeMap<String, MidpointResult>
+    ) -> Result<BTreeMap<String, EndpointResult>, EngineError> {
         
-        let mut endpoint = HashMap::new();
-
-        // Human Health (DALY per kg)
-        let mut human_health = 0.0;
-
-        if let Some(gwp) = midpoint.get("Global warming") {
-            let climate_health = gwp.value * self.characterization_factors.human_health.climate_health_africa;
-            human_health += climate_health;
-        }
-
-        if let Some(water_scarcity) = midpoint.get("Water scarcity") {
-            let water_health = water_scarcity.value * self.characterization_factors.human_health.water_stress_health_africa;
-            human_health += water_health;
-        }
-
-        if let Some(pm) = midpoint.get("Particulate matter formation") {
-            let air_health = pm.value * self.characterization_factors.human_health.air_quality_health_africa;
-            human_health += air_health;
-        }
+        let mut endpoint = BTreeMap::new();
+        let characterization = self.endpoint_characterization();
+        let characterize = |endpoint: &str| -> f64 {
+            characterization.iter()
+                .filter(|(e, _, _)| *e == endpoint)
+                .filter_map(|(_, category, factor)| midpoint.get(*category).map(|r| r.value * factor))
+                .sum()
+        };
 
+        let human_health = characterize("Human Health");
         endpoint.insert("Human Health".to_string(), EndpointResult {
             value: human_health,
             unit: "DALY per kg".to_string(),
             uncertainty_range: (human_health * 0.5, human_health * 2.0), // Simplified uncertainty
-            normalization_factor: Some(5.2e-2), // African-specific normalization (per kg)
+            normalization_factor: african_reference_per_kg("Human Health"),
             regional_adaptation_factor: Some(1.5), // Higher vulnerability in Africa
+            contributions: endpoint_contributions("Human Health", &characterization, midpoint),
+            metadata: None,
         });
 
-        // Ecosystem Quality (species.yr per kg)
-        let mut ecosystem_quality = 0.0;
-
-        if let Some(gwp) = midpoint.get("Global warming") {
-            ecosystem_quality += gwp.value * 1.2e-14;
-        }
-
-        if let Some(land_use) = midpoint.get("Land use") {
-            ecosystem_quality += land_use.value * 2.1e-10;
-        }
-
-        if let Some(biodiversity) = midpoint.get("Biodiversity loss") {
-            ecosystem_quality += biodiversity.value * 1.5e-12;
-        }
-
+        let ecosystem_quality = characterize("Ecosystem Quality");
         endpoint.insert("Ecosystem Quality".to_string(), EndpointResult {
             value: ecosystem_quality,
             unit: "species.yr per kg".to_string(),
             uncertainty_range: (ecosystem_quality * 0.3, ecosystem_quality * 3.0),
-            normalization_factor: Some(4.1e-9), // African biodiversity hotspots (per kg)
+            normalization_factor: african_reference_per_kg("Ecosystem Quality"),
             regional_adaptation_factor: Some(1.8), // Higher biodiversity sensitivity
+            contributions: endpoint_contributions("Ecosystem Quality", &characterization, midpoint),
+            metadata: None,
         });
 
-        // Resource Scarcity (USD per kg)
-        let mut resource_scarcity = 0.0;
-
-        if let Some(water_scarcity) = midpoint.get("Water scarcity") {
-            resource_scarcity += water_scarcity.value * self.characterization_factors.resource_scarcity.water_scarcity_africa;
-        }
-
-        if let Some(fossil) = midpoint.get("Fossil depletion") {
-            resource_scarcity += fossil.value * 0.055; // Global factor
-        }
-
+        let resource_scarcity = characterize("Resource Scarcity");
         endpoint.insert("Resource Scarcity".to_string(), EndpointResult {
             value: resource_scarcity,
             unit: "USD per kg".to_string(),
             uncertainty_range: (resource_scarcity * 0.7, resource_scarcity * 1.5),
-            normalization_factor: Some(8.5e3), // Higher resource constraints in Africa (per kg)
+            normalization_factor: african_reference_per_kg("Resource Scarcity"),
             regional_adaptation_factor: Some(1.3),
+            contributions: endpoint_contributions("Resource Scarcity", &characterization, midpoint),
+            metadata: None,
         });
 
+        // Endpoints none of whose midpoints were assessed are left out rather than reported as zero
+        endpoint.retain(|name, _| characterization.iter()
+            .any(|(e, category, _)| e == name && midpoint.contains_key(*category)));
+
         Ok(endpoint)
     }
 
-    fn calculate_enhanced_single_score(
+    /// Trace the single score back through weighting, normalization, endpoint and flow
+    /// characterization to the activities that caused each flow (ISO 14044 interpretation)
+    pub fn build_contribution_tree(&self, results: &LCAResults) -> ContributionTree {
+        ContributionTree::from_results(results, &self.endpoint_characterization())
+    }
+
+    /// Single score of the per-kg endpoints under the methodology's normalization and
+    /// weighting, interpreted against the references for `country`
+    pub(crate) fn calculate_enhanced_single_score(
         &self,
-        endpoint: &HashMap<String, EndpointResult>
-    ) -> Result<SingleScoreResult, Box<dyn std::error::Error>> {
-
-        // ISO 14044-compliant weighting factors
-        // Note: Weighting is value-choice dependent and should be transparent
-        let weighting_factors = match self.methodology.weighting_method {
-            Some(WeightingMethod::AfricanPriorities) => HashMap::from([
-                ("Human Health".to_string(), 0.40),      // Higher weight for human health (African context)
-                ("Ecosystem Quality".to_string(), 0.35), // Moderate weight for ecosystems
-                ("Resource Scarcity".to_string(), 0.25), // Lower weight for resources
-            ]),
-            Some(WeightingMethod::EqualWeights) => HashMap::from([
-                ("Human Health".to_string(), 0.333),     // Equal weighting (ISO default)
-                ("Ecosystem Quality".to_string(), 0.333),
-                ("Resource Scarcity".to_string(), 0.334),
-            ]),
-            _ => HashMap::from([
-                ("Human Health".to_string(), 0.333),     // Default to equal weights
-                ("Ecosystem Quality".to_string(), 0.333),
-                ("Resource Scarcity".to_string(), 0.334),
-            ]),
-        };
+        endpoint: &BTreeMap<String, EndpointResult>,
+        country: &Country
+    ) -> Result<SingleScoreResult, EngineError> {
+        let weighting_method = self.methodology.weighting_method.as_ref().unwrap_or(&WeightingMethod::None);
+        let normalization = self.methodology.normalization_method.as_ref().unwrap_or(&NormalizationMethod::None);
+        let (mut score, contributions) = weighted_single_score(endpoint, weighting_method, &format!("{:?}", normalization))?;
+        let reference = find_score_reference(
+            &self.score_references, country, &self.methodology.functional_unit, Some(weighting_method)
+        );
+        score.interpretation = Some(interpret_single_score(score.value, &contributions, endpoint, reference, country));
+        Ok(score)
+    }
 
-        let mut single_score = 0.0;
-        let mut score_uncertainty = 0.0;
-        let mut normalization_refs = HashMap::new();
-
-        // ISO 14044 methodology: Single Score = Σ(Endpoint / Normalization × Weight)
-        for (category, result) in endpoint {
-            if let Some(weight) = weighting_factors.get(category) {
-                // Use normalization factor embedded in endpoint result
-                // These are context-specific (African, Global, etc.) based on methodology
-                let norm_factor = result.normalization_factor.unwrap_or_else(|| {
-                    // Fallback normalization references (global per-capita annual basis)
-                    match category.as_str() {
-                        "Human Health" => 2.2e-2,      // DALY per kg global average
-                        "Ecosystem Quality" => 1.8e-9, // species.yr per kg
-                        "Resource Scarcity" => 4.2e3,  // USD per kg
-                        _ => 1.0,
-                    }
-                });
-
-                // Store for reporting
-                normalization_refs.insert(category.clone(), norm_factor);
-
-                // Normalized score = endpoint value / reference value
-                // This gives dimensionless "person-equivalent" units
-                let normalized_score = result.value / norm_factor;
-
-                // Weighted score
-                let weighted_score = normalized_score * weight;
-                single_score += weighted_score;
-
-                // Uncertainty propagation (ISO 14044 Section 4.4.3.3)
-                let result_uncertainty = (result.uncertainty_range.1 - result.uncertainty_range.0) / 4.0;
-                let normalized_uncertainty = result_uncertainty / norm_factor;
-                let weighted_uncertainty = normalized_uncertainty * weight;
-                score_uncertainty += weighted_uncertainty.powi(2);
-            }
-        }
-
-        let score_std = score_uncertainty.sqrt();
-
-        // Convert to percentage for user display (0-100% scale)
-        // Reference: A score of 1.0 = average impact, 0.5 = half average, 2.0 = double average
-        // For better UX, we map: 0-0.5 → Excellent, 0.5-1.0 → Good, 1.0-1.5 → Average, >1.5 → Poor
-        // Display as percentage where 100% = 2x reference (worst reasonable case)
-        let display_score = (single_score / 2.0).min(1.0).max(0.0);
-
-        Ok(SingleScoreResult {
-            value: display_score, // 0-1 scale for display (0% = best, 100% = 2x reference impact)
-            unit: "Environmental Impact Score (0-1 scale, 1.0 = 2× reference impact)".to_string(),
-            uncertainty_range: (
-                ((single_score - 2.0 * score_std) / 2.0).max(0.0),
-                ((single_score + 2.0 * score_std) / 2.0).min(1.0)
-            ),
-            weighting_factors,
-            methodology: format!(
-                "ISO 14044 compliant: {:?} normalization with {:?} weighting. Raw score: {:.3} person-equiv.",
-                self.methodology.normalization_method.as_ref().unwrap_or(&NormalizationMethod::None),
-                self.methodology.weighting_method.as_ref().unwrap_or(&WeightingMethod::None),
-                single_score
-            ),
-        })
-    }
-
-    fn assess_data_quality(
-        &self,
-        foods: &[FoodItem],
-        country: &Country
-    ) -> Result<DataQuality, Box<dyn std::error::Error>> {
-        
+    fn assess_data_quality(&self, assessment: &Assessment) -> Result<DataQuality, EngineError> {
+        let (foods, country) = (&assessment.foods, &assessment.country);
         let mut quality_scores = Vec::new();
-        let mut source_contributions = HashMap::new();
+        let mut water_warnings = Vec::new();
+        let mut source_contributions = BTreeMap::new();
+        let mut factor_overrides = Vec::new();
         let mut warnings = Vec::new();
         let mut recommendations = Vec::new();
 
+        if self.impact_factors.is_empty() {
+            warnings.push("No impact factors loaded - all factor-based impacts are default estimates".to_string());
+        }
+
         // Analyze data quality for each food item
         for food in foods {
+            let hierarchy = self.build_lookup_hierarchy(food, country);
             for category in &self.get_impact_categories() {
-                let hierarchy = self.build_lookup_hierarchy(food, country);
-                
-                if let Ok((_, source, _, pedigree)) = self.find_best_factor(food, country, category, &hierarchy) {
+
+                if let Ok((value, source, _, pedigree)) = self.find_best_factor(food, country, category, &hierarchy) {
+                    if category == "Water consumption" {
+                        water_warnings.extend(water_use_check(food, value));
+                    }
                     let quality_score = pedigree.calculate_overall_quality_score();
                     
                     // Only include factors that are not default fallbacks (which have all 5s)
@@ -772,7 +1410,15 @@ impl AfricanLCAEngine {
                         quality_scores.push(quality_score);
                         
                         // Track source contributions
-                        let source_type = if source.contains("Ghana") || source.contains("Nigeria") {
+                        let source_type = if source.starts_with(PRIMARY_DATA) {
+                            factor_overrides.push(FactorOverride {
+                                food_id: food.id.clone(),
+                                impact_category: category.clone(),
+                                value_per_kg: value,
+                                source: source.clone(),
+                            });
+                            "Primary"
+                        } else if source.contains("Ghana") || source.contains("Nigeria") {
                             "Country-specific"
                         } else if source.contains("Africa") {
                             "Regional"
@@ -805,7 +1451,8 @@ impl AfricanLCAEngine {
         let data_source_mix = source_contributions.into_iter()
             .map(|(source_type, count)| DataSourceContribution {
                 source_type: match source_type.as_str() {
-                    "Country-specific" => DataSource::CountrySpecific(country.clone()),
+                    "Primary" => DataSource::Primary,
+                    "Country-specific" => DataSource::CountrySpecific(*country),
                     "Regional" => DataSource::Regional("West Africa".to_string()),
                     "Global" => DataSource::Global,
                     _ => DataSource::Estimated,
@@ -822,17 +1469,15 @@ impl AfricanLCAEngine {
         if warnings.len() > foods.len() / 2 {
             recommendations.push("Prioritize data improvement for most uncertain factors".to_string());
         }
-
-        // Sanity checks for unusual consumption patterns
-        let total_meat_kg: f64 = foods.iter()
-            .filter(|food| matches!(food.category, FoodCategory::Meat))
-            .map(|food| food.quantity_kg)
-            .sum();
-        
-        if total_meat_kg > 20.0 { // More than 20kg of meat is unusually high
-            warnings.push(format!("High meat consumption detected: {:.1}kg. Consider reducing meat intake for environmental and health benefits.", total_meat_kg));
+        if assessment.input_completeness() < 0.5 {
+            recommendations.push(format!("Provide the missing farm inputs: {}", assessment.missing_inputs().join(", ")));
         }
 
+        // Yields, output per animal, nitrogen rates and water use no farm could reach
+        let mut calculation_warnings = plausibility_check(foods, assessment.management_practices.as_ref());
+        calculation_warnings.extend(water_warnings);
+        warnings.extend(calculation_warnings.iter().map(|warning| warning.message.clone()));
+
         // Adjusted thresholds for African context - partial local data is valuable
         let confidence_level = match overall_quality {
             q if q > 0.7 => ConfidenceLevel::High,    // Lowered from 0.8 - some Ghana data available
@@ -847,24 +1492,70 @@ impl AfricanLCAEngine {
             overall_confidence: confidence_level,
             data_source_mix,
             regional_adaptation: true,
-            completeness_score: overall_quality,
+            completeness_score: assessment.input_completeness(),
+            quality_score: overall_quality,
             temporal_representativeness: 0.8, // Most data is recent
             geographical_representativeness: if matches!(country, Country::Ghana | Country::Nigeria) { 0.7 } else { 0.4 },
             technological_representativeness: 0.6, // Mixed technology levels
             warnings,
             recommendations,
+            calculation_warnings,
+            factor_overrides,
         })
     }
 
+    /// Monte Carlo propagation of midpoint uncertainty. Each category is sampled from a
+    /// lognormal with its result as median and its geometric standard deviation, or, for
+    /// results without one, its uncertainty range as the 95% interval.
+    /// Runs with the same seed are bit-identical.
+    pub fn propagate_uncertainty(
+        &self,
+        midpoint: &BTreeMap<String, MidpointResult>,
+        iterations: usize
+    ) -> HashMap<String, MonteCarloResult> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let iterations = iterations.max(1);
+
+        // Sample categories in a fixed order so the random stream is consumed identically
+        let mut categories: Vec<&String> = midpoint.keys().collect();
+        categories.sort();
+
+        categories.into_iter().map(|category| {
+            let result = &midpoint[category];
+            let (low, high) = result.uncertainty_range;
+            let sigma = match result.geometric_sd {
+                Some(geometric_sd) => geometric_sd.ln(),
+                None if low > 0.0 && high > low && high.is_finite() => (high / low).ln() / (2.0 * 1.96),
+                None => 0.0,
+            };
+            let mut samples: Vec<f64> = if result.value > 0.0 && sigma > 0.0 && result.value.is_finite() && sigma.is_finite() {
+                let distribution = LogNormal::new(result.value.ln(), sigma).expect("finite lognormal parameters");
+                (0..iterations).map(|_| distribution.sample(&mut rng)).collect()
+            } else {
+                vec![result.value; iterations]
+            };
+            samples.sort_by(|a, b| a.total_cmp(b));
+
+            let percentile = |p: f64| samples[((p / 100.0 * iterations as f64).ceil() as usize).clamp(1, iterations) - 1];
+            (category.clone(), MonteCarloResult {
+                iterations,
+                seed: self.seed,
+                median: percentile(50.0),
+                percentile_2_5: percentile(2.5),
+                percentile_97_5: percentile(97.5),
+            })
+        }).collect()
+    }
+
     fn perform_sensitivity_analysis(
         &self,
         foods: &[FoodItem],
         _country: &Country
-    ) -> Result<SensitivityAnalysis, Box<dyn std::error::Error>> {
+    ) -> Result<SensitivityAnalysis, EngineError> {
         
         // Simplified sensitivity analysis
         let mut influential_parameters = Vec::new();
-        let uncertainty_contributions = HashMap::new();
+        let uncertainty_contributions = BTreeMap::new();
         
         // Identify most influential parameters (simplified)
         for food in foods {
@@ -879,7 +1570,7 @@ impl AfricanLCAEngine {
         }
 
         // Sort by influence
-        influential_parameters.sort_by(|a, b| b.influence_percentage.partial_cmp(&a.influence_percentage).unwrap());
+        influential_parameters.sort_by(|a, b| b.influence_percentage.total_cmp(&a.influence_percentage));
         influential_parameters.truncate(5); // Top 5
 
         // Scenario analysis
@@ -887,18 +1578,20 @@ impl AfricanLCAEngine {
             ScenarioResult {
                 scenario_name: "Best available technology".to_string(),
                 description: "Using most efficient production systems".to_string(),
-                impact_changes: HashMap::from([
+                impact_changes: BTreeMap::from([
                     ("Global warming".to_string(), -30.0),
                     ("Water consumption".to_string(), -25.0),
                 ]),
+                significance: BTreeMap::new(),
             },
             ScenarioResult {
                 scenario_name: "Climate adaptation".to_string(),
                 description: "Drought-resistant varieties and water-efficient practices".to_string(),
-                impact_changes: HashMap::from([
+                impact_changes: BTreeMap::from([
                     ("Water scarcity".to_string(), -40.0),
                     ("Biodiversity loss".to_string(), -20.0),
                 ]),
+                significance: BTreeMap::new(),
             },
         ];
 
@@ -911,63 +1604,36 @@ impl AfricanLCAEngine {
 
     fn generate_comparative_analysis(
         &self,
-        impacts: &HashMap<String, MidpointResult>,
-        _country: &Country
-    ) -> Result<ComparativeAnalysis, Box<dyn std::error::Error>> {
+        impacts: &BTreeMap<String, MidpointResult>,
+        country: &Country,
+        foods: &[FoodItem]
+    ) -> Result<ComparativeAnalysis, EngineError> {
         
-        // Simplified comparative analysis
-        let mut benchmark_comparisons = Vec::new();
-        let mut regional_comparisons = Vec::new();
-        let mut best_practices = Vec::new();
-
-        // Compare against global averages (simplified)
-        if let Some(gwp) = impacts.get("Global warming") {
-            benchmark_comparisons.push(BenchmarkComparison {
-                benchmark_name: "Global average diet".to_string(),
-                your_performance: gwp.value,
-                benchmark_value: 2000.0, // kg CO2-eq/year (example)
-                percentage_difference: ((gwp.value - 2000.0) / 2000.0) * 100.0,
-                performance_category: if gwp.value < 1500.0 { 
-                    PerformanceCategory::Excellent 
-                } else if gwp.value < 2500.0 { 
-                    PerformanceCategory::Good 
-                } else { 
-                    PerformanceCategory::BelowAverage 
-                },
-            });
-        }
-
-        // Regional comparison
-        regional_comparisons.push(RegionalComparison {
-            region_name: "West Africa average".to_string(),
-            impact_ratios: HashMap::from([
-                ("Global warming".to_string(), 1.2), // 20% above regional average
-                ("Water consumption".to_string(), 0.9), // 10% below regional average
-            ]),
-        });
+        let (benchmark_comparisons, regional_comparisons) = self.compare_with_benchmarks(impacts, country, foods);
 
         // Best practices
-        best_practices.push(BestPractice {
-            practice_name: "Increase legume consumption".to_string(),
-            description: "Replace 25% of cereal consumption with legumes".to_string(),
-            potential_impact_reduction: HashMap::from([
-                ("Global warming".to_string(), 15.0),
-                ("Land use".to_string(), 10.0),
-            ]),
-            implementation_difficulty: DifficultyLevel::Low,
-            cost_category: CostCategory::NoCost,
-        });
-
-        best_practices.push(BestPractice {
-            practice_name: "Improved livestock management".to_string(),
-            description: "Implement rotational grazing and feed supplements".to_string(),
-            potential_impact_reduction: HashMap::from([
-                ("Global warming".to_string(), 25.0),
-                ("Biodiversity loss".to_string(), 20.0),
-            ]),
-            implementation_difficulty: DifficultyLevel::Medium,
-            cost_category: CostCategory::MediumCost,
-        });
+        let best_practices = vec![
+            BestPractice {
+                practice_name: "Increase legume consumption".to_string(),
+                description: "Replace 25% of cereal consumption with legumes".to_string(),
+                potential_impact_reduction: BTreeMap::from([
+                    ("Global warming".to_string(), 15.0),
+                    ("Land use".to_string(), 10.0),
+                ]),
+                implementation_difficulty: DifficultyLevel::Low,
+                cost_category: CostCategory::NoCost,
+            },
+            BestPractice {
+                practice_name: "Improved livestock management".to_string(),
+                description: "Implement rotational grazing and feed supplements".to_string(),
+                potential_impact_reduction: BTreeMap::from([
+                    ("Global warming".to_string(), 25.0),
+                    ("Biodiversity loss".to_string(), 20.0),
+                ]),
+                implementation_difficulty: DifficultyLevel::Medium,
+                cost_category: CostCategory::MediumCost,
+            },
+        ];
 
         Ok(ComparativeAnalysis {
             benchmark_comparisons,
@@ -976,171 +1642,112 @@ impl AfricanLCAEngine {
         })
     }
 
+    /// Scale a food's category-factor impacts by the effects of the farm's management
+    /// practices. Effects the inventory already accounts for (`captured`) are noted but not
+    /// applied again, so the breakdown tells the same story as the inventory-based totals.
     fn apply_management_practice_adjustments(
         &self,
-        impacts: &mut HashMap<String, MidpointResult>,
+        impacts: &mut BTreeMap<String, MidpointResult>,
         management_practices: &ManagementPractices,
-        _food: &FoodItem
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Apply soil management adjustments based on conservation practices
-        let soil_factor = if management_practices.soil_management.conservation_practices.len() > 2 {
-            0.85  // Multiple conservation practices = 15% reduction
-        } else if management_practices.soil_management.conservation_practices.len() > 0 {
-            0.92  // Some conservation practices = 8% reduction
-        } else {
-            1.0   // No conservation practices
-        };
-
-        if let Some(soil_result) = impacts.get_mut("Soil degradation") {
-            soil_result.value *= soil_factor;
-            soil_result.uncertainty_range.0 *= soil_factor;
-            soil_result.uncertainty_range.1 *= soil_factor;
-            soil_result.contributing_sources.push(format!("Conservation practices: {}", management_practices.soil_management.conservation_practices.len()));
-        }
-
-        // Compost use affects carbon sequestration
-        let carbon_factor = if management_practices.soil_management.uses_compost {
-            0.92  // 8% reduction from carbon sequestration
-        } else {
-            1.0   // No benefit
-        };
-
-        if let Some(gwp_result) = impacts.get_mut("Global warming") {
-            gwp_result.value *= carbon_factor;
-            gwp_result.uncertainty_range.0 *= carbon_factor;
-            gwp_result.uncertainty_range.1 *= carbon_factor;
-        }
-
-        // Apply fertilizer management adjustments
-        let n2o_factor = if management_practices.fertilization.soil_test_based && management_practices.fertilization.follows_nutrient_plan {
-            0.8   // Efficient management reduces N2O
-        } else if management_practices.fertilization.soil_test_based || management_practices.fertilization.follows_nutrient_plan {
-            0.9   // Some efficiency improvement
-        } else {
-            1.2   // Inefficient fertilizer use increases emissions
-        };
-
-        if let Some(gwp_result) = impacts.get_mut("Global warming") {
-            gwp_result.value *= n2o_factor;
-        }
-
-        // Eutrophication impacts from nutrient runoff
-        let eutrophication_factor = if management_practices.fertilization.follows_nutrient_plan {
-            0.7   // Good planning reduces runoff
-        } else {
-            1.3   // Poor management increases runoff
-        };
-
-        if let Some(freshwater_eutroph) = impacts.get_mut("Freshwater eutrophication") {
-            freshwater_eutroph.value *= eutrophication_factor;
-        }
-        if let Some(marine_eutroph) = impacts.get_mut("Marine eutrophication") {
-            marine_eutroph.value *= eutrophication_factor;
-        }
-
-        // Apply water management adjustments based on irrigation system
-        let water_efficiency_factor = match management_practices.water_management.irrigation_system.as_deref() {
-            Some("Drip irrigation") | Some("Micro-sprinkler") => 0.7,  // High efficiency
-            Some("Sprinkler") => 0.85,  // Medium efficiency
-            Some("Flood irrigation") | Some("Furrow irrigation") => 1.0,  // Low efficiency
-            None => 0.5,  // Rainfed farming
-            _ => 0.9,  // Default for other systems
-        };
-
-        if let Some(water_result) = impacts.get_mut("Water consumption") {
-            water_result.value *= water_efficiency_factor;
-        }
-        if let Some(water_scarcity_result) = impacts.get_mut("Water scarcity") {
-            water_scarcity_result.value *= water_efficiency_factor;
-        }
-
-        // Conservation practices reduce runoff and erosion
-        if !management_practices.water_management.water_conservation_practices.is_empty() {
-            if let Some(soil_result) = impacts.get_mut("Soil degradation") {
-                soil_result.value *= 0.8; // 20% reduction
+        captured: &BTreeSet<PracticeEffect>,
+    ) -> Result<(), EngineError> {
+        for adjustment in practice_adjustments(management_practices).into_iter().filter(|a| a.factor != 1.0) {
+            for category in adjustment.categories {
+                let Some(result) = impacts.get_mut(*category) else { continue };
+                match adjustment.effect.filter(|effect| captured.contains(effect)) {
+                    Some(effect) => {
+                        info!("{}: {} is in the inventory; ×{:.2} multiplier not applied", category, effect.label(), adjustment.factor);
+                        result.contributing_sources.push(format!(
+                            "{}: accounted for in the life cycle inventory, ×{:.2} multiplier not applied",
+                            effect.label(), adjustment.factor
+                        ));
+                    }
+                    None => {
+                        info!("{}: {} ×{:.2}", category, adjustment.label, adjustment.factor);
+                        result.scale(adjustment.factor);
+                        result.contributing_sources.push(format!("{}: ×{:.2}", adjustment.label, adjustment.factor));
+                    }
+                }
             }
         }
 
-        // Apply pest management adjustments based on IPM and pesticide use
-        let pesticide_count = management_practices.pest_management.pesticides_used.len();
-        let pesticide_factor = if pesticide_count == 0 {
-            0.9   // No pesticides
-        } else if pesticide_count <= 2 {
-            0.95  // Low pesticide use
-        } else if pesticide_count <= 5 {
-            1.0   // Moderate use
-        } else {
-            1.2   // High pesticide use
-        };
+        Ok(())
+    }
 
-        // Affects biodiversity and ecosystem quality
-        if let Some(biodiversity_result) = impacts.get_mut("Biodiversity loss") {
-            biodiversity_result.value *= pesticide_factor;
-        }
+    /// Compare the farm's intensities with every benchmark for its country (or Global) on
+    /// the bases its results support: per kg of product, and per hectare when every food
+    /// reports its area. Benchmarks of one name also give a regional comparison, the ratio
+    /// of the farm's intensity per kg to theirs.
+    fn compare_with_benchmarks(
+        &self,
+        impacts: &BTreeMap<String, MidpointResult>,
+        country: &Country,
+        foods: &[FoodItem]
+    ) -> (Vec<BenchmarkComparison>, Vec<RegionalComparison>) {
+        let production_kg: f64 = foods.iter().map(|food| food.quantity_kg).sum();
+        let per_ha = impacts_per_hectare(foods, impacts);
+        let intensity = |category: &str, basis: BenchmarkBasis| match basis {
+            BenchmarkBasis::PerKgProduct => impacts.get(category)
+                .and_then(|result| value_per_kg(result, production_kg)),
+            BenchmarkBasis::PerHectare => per_ha.as_ref().and_then(|per_ha| per_ha.get(category))
+                .map(|result| (result.value, result.interval())),
+            BenchmarkBasis::PerCapita => None, // a farm's results have no consumers to divide by
+        };
 
-        // IPM practices reduce overall environmental impact
-        if management_practices.pest_management.uses_ipm {
-            // Apply 10% reduction across multiple categories
-            for (category, result) in impacts.iter_mut() {
-                if matches!(category.as_str(), 
-                    "Biodiversity loss" | "Terrestrial acidification" | 
-                    "Freshwater eutrophication" | "Marine eutrophication") {
-                    result.value *= 0.9;
+        let mut comparisons = Vec::new();
+        let mut regional_comparisons: Vec<RegionalComparison> = Vec::new();
+        let benchmarks = self.impact_benchmarks.iter()
+            .filter(|benchmark| benchmark.country == *country || benchmark.country == Country::Global);
+        for benchmark in benchmarks {
+            let Some((value, interval)) = intensity(&benchmark.impact_category, benchmark.basis) else {
+                info!("No {} intensity {} to compare with {}", benchmark.impact_category, benchmark.basis.label(), benchmark.name);
+                continue;
+            };
+            let comparison = match benchmark.compare(value, interval, benchmark.basis) {
+                Ok(comparison) => comparison,
+                Err(e) => {
+                    warn!("{}", e);
+                    continue;
+                }
+            };
+            if benchmark.basis == BenchmarkBasis::PerKgProduct {
+                let ratio = value / benchmark.value;
+                let regional = match regional_comparisons.iter_mut().position(|regional| regional.region_name == benchmark.name) {
+                    Some(i) => &mut regional_comparisons[i],
+                    None => {
+                        regional_comparisons.push(RegionalComparison {
+                            region_name: benchmark.name.clone(),
+                            impact_ratios: BTreeMap::new(),
+                            significance: BTreeMap::new(),
+                        });
+                        regional_comparisons.last_mut().unwrap()
+                    }
+                };
+                regional.impact_ratios.insert(benchmark.impact_category.clone(), ratio);
+                if let Some(significance) = comparison.significance {
+                    regional.significance.insert(benchmark.impact_category.clone(), significance);
                 }
             }
+            comparisons.push(comparison);
         }
-
-        Ok(())
+        (comparisons, regional_comparisons)
     }
 
     fn generate_comprehensive_comparative_analysis(
         &self,
-        impacts: &HashMap<String, MidpointResult>,
+        impacts: &BTreeMap<String, MidpointResult>,
         country: &Country,
         foods: &[FoodItem]
-    ) -> Result<ComparativeAnalysis, Box<dyn std::error::Error>> {
+    ) -> Result<ComparativeAnalysis, EngineError> {
         
-        let mut benchmark_comparisons = Vec::new();
-        let mut regional_comparisons = Vec::new();
+        let (benchmark_comparisons, regional_comparisons) = self.compare_with_benchmarks(impacts, country, foods);
         let mut best_practices = Vec::new();
 
-        // Enhanced benchmark comparisons with farm management context
-        if let Some(gwp) = impacts.get("Global warming") {
-            benchmark_comparisons.push(BenchmarkComparison {
-                benchmark_name: "Sustainable farming practices".to_string(),
-                your_performance: gwp.value,
-                benchmark_value: 1500.0, // kg CO2-eq/year for sustainable practices
-                percentage_difference: ((gwp.value - 1500.0) / 1500.0) * 100.0,
-                performance_category: if gwp.value < 1200.0 { 
-                    PerformanceCategory::Excellent 
-                } else if gwp.value < 1800.0 { 
-                    PerformanceCategory::Good 
-                } else { 
-                    PerformanceCategory::BelowAverage 
-                },
-            });
-        }
-
-        // Regional comparison with management practices
-        regional_comparisons.push(RegionalComparison {
-            region_name: match country {
-                Country::Ghana => "Ghana sustainable farming average".to_string(),
-                Country::Nigeria => "Nigeria sustainable farming average".to_string(),
-                _ => "West Africa sustainable farming average".to_string(),
-            },
-            impact_ratios: HashMap::from([
-                ("Global warming".to_string(), 1.1),
-                ("Water consumption".to_string(), 0.9),
-                ("Soil degradation".to_string(), 1.2),
-                ("Biodiversity loss".to_string(), 0.8),
-            ]),
-        });
-
         // Enhanced best practices based on current farm management
         best_practices.push(BestPractice {
             practice_name: "Implement conservation agriculture".to_string(),
             description: "Adopt no-till farming, cover crops, and crop rotation".to_string(),
-            potential_impact_reduction: HashMap::from([
+            potential_impact_reduction: BTreeMap::from([
                 ("Global warming".to_string(), 20.0),
                 ("Soil degradation".to_string(), 40.0),
                 ("Water consumption".to_string(), 15.0),
@@ -1152,7 +1759,7 @@ impl AfricanLCAEngine {
         best_practices.push(BestPractice {
             practice_name: "Optimize fertilizer application".to_string(),
             description: "Use soil testing and precision application techniques".to_string(),
-            potential_impact_reduction: HashMap::from([
+            potential_impact_reduction: BTreeMap::from([
                 ("Global warming".to_string(), 25.0),
                 ("Freshwater eutrophication".to_string(), 35.0),
                 ("Marine eutrophication".to_string(), 30.0),
@@ -1164,7 +1771,7 @@ impl AfricanLCAEngine {
         best_practices.push(BestPractice {
             practice_name: "Install efficient irrigation systems".to_string(),
             description: "Upgrade to drip irrigation or micro-sprinklers".to_string(),
-            potential_impact_reduction: HashMap::from([
+            potential_impact_reduction: BTreeMap::from([
                 ("Water consumption".to_string(), 40.0),
                 ("Water scarcity".to_string(), 40.0),
             ]),
@@ -1178,7 +1785,7 @@ impl AfricanLCAEngine {
             best_practices.push(BestPractice {
                 practice_name: "Improve livestock feed efficiency".to_string(),
                 description: "Use high-quality feed supplements and pasture management".to_string(),
-                potential_impact_reduction: HashMap::from([
+                potential_impact_reduction: BTreeMap::from([
                     ("Global warming".to_string(), 30.0),
                     ("Land use".to_string(), 25.0),
                     ("Water consumption".to_string(), 20.0),
@@ -1193,7 +1800,7 @@ impl AfricanLCAEngine {
             best_practices.push(BestPractice {
                 practice_name: "Intercrop with legumes".to_string(),
                 description: "Plant legumes between cereal rows to fix nitrogen naturally".to_string(),
-                potential_impact_reduction: HashMap::from([
+                potential_impact_reduction: BTreeMap::from([
                     ("Global warming".to_string(), 15.0),
                     ("Soil degradation".to_string(), 20.0),
                     ("Freshwater eutrophication".to_string(), 25.0),
@@ -1210,6 +1817,83 @@ impl AfricanLCAEngine {
         })
     }
 
+    // ======================================================================
+    // COSTED RECOMMENDATIONS
+    // ======================================================================
+
+    /// Generate recommendations with estimated annual savings and payback, in the
+    /// assessment's currency, derived from the fertilizer and energy costs entered.
+    pub fn generate_recommendations(&self, assessment: &Assessment) -> Vec<Recommendation> {
+        let mut recommendations = Vec::new();
+        let costs = assessment.cost_rollup();
+
+        if let Some(fertilization) = assessment.management_practices.as_ref().and_then(|p| p.fertilization.as_ref()) {
+            if fertilization.uses_fertilizers && !fertilization.soil_test_based {
+                // Soil-test-based rates typically cut fertilizer use by ~20% (FAO/IFDC field trials)
+                let savings = (costs.fertilizer > 0.0).then_some(costs.fertilizer * 0.20);
+                let implementation_cost = Self::reference_implementation_cost(Measure::SoilTesting, &assessment.currency);
+
+                recommendations.push(Recommendation {
+                    category: RecommendationCategory::FertilizerOptimization,
+                    title: "Base fertilizer rates on soil testing".to_string(),
+                    description: format!(
+                        "Test soil before each season and apply only the nutrients the crop needs. \
+                         A soil test costs about {}{:.0}.",
+                        assessment.currency.symbol(), implementation_cost
+                    ),
+                    potential_impact_reduction: BTreeMap::from([
+                        ("Global warming".to_string(), 15.0),
+                        ("Freshwater eutrophication".to_string(), 20.0),
+                        ("Marine eutrophication".to_string(), 20.0),
+                    ]),
+                    implementation_difficulty: DifficultyLevel::Low,
+                    cost_category: CostCategory::LowCost,
+                    priority: Priority::High,
+                    estimated_annual_savings: savings,
+                    payback_months: savings.map(|s| implementation_cost / s * 12.0),
+                    marginal_abatement_cost: None,
+                });
+            }
+        }
+
+        if costs.energy > 0.0 {
+            // Regular servicing and load management save 10-20% of energy use
+            let savings = costs.energy * 0.15;
+            let implementation_cost = Self::reference_implementation_cost(Measure::EnergyServicing, &assessment.currency);
+
+            recommendations.push(Recommendation {
+                category: RecommendationCategory::EnergyEfficiency,
+                title: "Service equipment and optimize energy use".to_string(),
+                description: "Tune engines and pumps, fix leaks, and avoid running equipment idle".to_string(),
+                potential_impact_reduction: BTreeMap::from([
+                    ("Global warming".to_string(), 10.0),
+                    ("Fossil depletion".to_string(), 15.0),
+                ]),
+                implementation_difficulty: DifficultyLevel::Low,
+                cost_category: CostCategory::LowCost,
+                priority: Priority::Medium,
+                estimated_annual_savings: Some(savings),
+                payback_months: Some(implementation_cost / savings * 12.0),
+                marginal_abatement_cost: None,
+            });
+        }
+
+        recommendations
+    }
+
+    /// Typical up-front cost of a measure in local currency (2024 market prices). Every
+    /// measure has a price in every currency, so a new one cannot go uncosted.
+    fn reference_implementation_cost(measure: Measure, currency: &Currency) -> f64 {
+        match (measure, currency) {
+            (Measure::SoilTesting, Currency::GHS) => 300.0,
+            (Measure::SoilTesting, Currency::NGN) => 25_000.0,
+            (Measure::SoilTesting, Currency::USD) => 25.0,
+            (Measure::EnergyServicing, Currency::GHS) => 1_500.0,
+            (Measure::EnergyServicing, Currency::NGN) => 120_000.0,
+            (Measure::EnergyServicing, Currency::USD) => 120.0,
+        }
+    }
+
     // Existing helper methods updated...
     fn get_default_impact_factor(&self, category: &FoodCategory, impact: &str) -> f64 {
         // Scientifically accurate global averages based on LCA literature (Poore & Nemecek 2018, IPCC AR6)
@@ -1340,6 +2024,50 @@ impl AfricanLCAEngine {
             (FoodCategory::Roots, "Fossil depletion") => 0.005,
             (FoodCategory::Roots, "Mineral depletion") => 0.0002,
             
+            // EGGS - Feed-driven, with manure NH3 (Poore & Nemecek 2018 global means)
+            (FoodCategory::Eggs, "Global warming") => 4.7, // kg CO2-eq/kg
+            (FoodCategory::Eggs, "Water consumption") => 0.58, // m3/kg, mostly feed irrigation
+            (FoodCategory::Eggs, "Land use") => 6.3, // m2a/kg, feed crops
+            (FoodCategory::Eggs, "Terrestrial acidification") => 0.054, // kg SO2-eq/kg, manure NH3
+            (FoodCategory::Eggs, "Freshwater eutrophication") => 0.007, // kg P-eq/kg
+            (FoodCategory::Eggs, "Marine eutrophication") => 0.025, // kg N-eq/kg
+            (FoodCategory::Eggs, "Biodiversity loss") => 1.8, // MSA*m2*yr/kg
+            (FoodCategory::Eggs, "Soil degradation") => 0.5, // kg soil-eq/kg
+            (FoodCategory::Eggs, "Particulate matter formation") => 0.035, // PM2.5-eq/kg, NH3 precursors
+            (FoodCategory::Eggs, "Photochemical oxidation") => 0.012, // kg NMVOC-eq/kg
+            (FoodCategory::Eggs, "Fossil depletion") => 0.12, // kg oil-eq/kg, feed milling and housing
+            (FoodCategory::Eggs, "Mineral depletion") => 0.005, // kg Fe-eq/kg
+
+            // OILS - Per kg of vegetable oil; mean of palm, soybean, rapeseed, sunflower and olive
+            // oil (Poore & Nemecek 2018), palm carrying most of the land use change
+            (FoodCategory::Oils, "Global warming") => 5.3, // kg CO2-eq/kg
+            (FoodCategory::Oils, "Water consumption") => 0.77, // m3/kg (palm 0.007 to olive 2.1)
+            (FoodCategory::Oils, "Land use") => 13.5, // m2a/kg (palm 2.4 to olive 26)
+            (FoodCategory::Oils, "Terrestrial acidification") => 0.03, // kg SO2-eq/kg
+            (FoodCategory::Oils, "Freshwater eutrophication") => 0.009, // kg P-eq/kg
+            (FoodCategory::Oils, "Marine eutrophication") => 0.03, // kg N-eq/kg
+            (FoodCategory::Oils, "Biodiversity loss") => 4.0, // MSA*m2*yr/kg, tropical forest conversion
+            (FoodCategory::Oils, "Soil degradation") => 0.9, // kg soil-eq/kg
+            (FoodCategory::Oils, "Particulate matter formation") => 0.015, // PM2.5-eq/kg
+            (FoodCategory::Oils, "Photochemical oxidation") => 0.01, // kg NMVOC-eq/kg
+            (FoodCategory::Oils, "Fossil depletion") => 0.25, // kg oil-eq/kg, extraction and refining
+            (FoodCategory::Oils, "Mineral depletion") => 0.003, // kg Fe-eq/kg
+
+            // NUTS - Between tree nuts (0.4 kg CO2-eq/kg with orchard carbon uptake) and
+            // groundnuts (3.2) in Poore & Nemecek 2018; land and water intensive
+            (FoodCategory::Nuts, "Global warming") => 1.8, // kg CO2-eq/kg
+            (FoodCategory::Nuts, "Water consumption") => 3.0, // m3/kg (groundnuts 1.9, tree nuts 4.1)
+            (FoodCategory::Nuts, "Land use") => 11.0, // m2a/kg (groundnuts 9.1, tree nuts 13)
+            (FoodCategory::Nuts, "Terrestrial acidification") => 0.018, // kg SO2-eq/kg
+            (FoodCategory::Nuts, "Freshwater eutrophication") => 0.006, // kg P-eq/kg
+            (FoodCategory::Nuts, "Marine eutrophication") => 0.02, // kg N-eq/kg
+            (FoodCategory::Nuts, "Biodiversity loss") => 2.0, // MSA*m2*yr/kg
+            (FoodCategory::Nuts, "Soil degradation") => 0.5, // kg soil-eq/kg, groundnut harvest exposes soil
+            (FoodCategory::Nuts, "Particulate matter formation") => 0.01, // PM2.5-eq/kg
+            (FoodCategory::Nuts, "Photochemical oxidation") => 0.005, // kg NMVOC-eq/kg
+            (FoodCategory::Nuts, "Fossil depletion") => 0.1, // kg oil-eq/kg
+            (FoodCategory::Nuts, "Mineral depletion") => 0.002, // kg Fe-eq/kg
+
             // Default for any missing combinations - use cereal averages as conservative estimate
             _ => match impact {
                 "Global warming" => 1.4,
@@ -1364,11 +2092,1306 @@ impl Clone for AfricanLCAEngine {
     fn clone(&self) -> Self {
         Self {
             impact_factors: self.impact_factors.clone(),
+            factor_overrides: self.factor_overrides.clone(),
             characterization_factors: self.characterization_factors.clone(),
             regional_factors: self.regional_factors.clone(),
             climate_adjustments: self.climate_adjustments.clone(),
+            climate_zones: self.climate_zones.clone(),
+            region_zones: self.region_zones.clone(),
+            climate_adjustment_rules: self.climate_adjustment_rules.clone(),
             methodology: self.methodology.clone(),
-            lci_calculator: LCICalculator::new(), // Create new LCI calculator instance
+            lci_calculator: self.lci_calculator.fresh(), // same emission factors, no inventory
+            seed: self.seed,
+            food_impact_cache: self.food_impact_cache.clone(),
+            strict: self.strict,
+            scope_table: self.scope_table.clone(),
+            score_references: self.score_references.clone(),
+            impact_benchmarks: self.impact_benchmarks.clone(),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::InputError;
+    use crate::production::ghg_scopes::{Scope, ScopeTotal};
+    use crate::production::lci::EmissionFactorsDatabase;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn methodology() -> LCAMethodology {
+        LCAMethodology::african_production().to_builder()
+            .characterization_method(CharacterizationMethod::ReCiPe2016)
+            .build()
+    }
+
+    fn farm_with_fertilizer_cost(cost_per_application: f64) -> Assessment {
+        Assessment {
+            schema_version: crate::schema::current_schema_version(),
+            id: Uuid::new_v4(),
+            company_name: "Test Farm".to_string(),
+            country: Country::Ghana,
+            currency: Currency::GHS,
+            exchange_rates: ExchangeRates::default(),
+            region: None,
+            coordinates: None,
+            foods: vec![FoodItem {
+                id: "1".to_string(),
+                name: "Maize".to_string(),
+                quantity_kg: 2000.0,
+                category: FoodCategory::Cereals,
+                crop_type: Some("Maize".to_string()),
+                origin_country: None,
+                production_system: None,
+                seasonal_factor: None,
+                variety: None,
+                area_allocated: Some(1.0),
+                cropping_pattern: None,
+                intercropping_partners: None,
+                post_harvest_losses: None,
+                aquaculture: None,
+                poultry: None,
+                dairy: None,
+                perennial: None,
+                head_count: None,
+            }],
+            assessment_date: Utc::now(),
+            methodology: methodology(),
+            results: None,
+            farm_profile: None,
+            management_practices: Some(ManagementPractices {
+                soil_management: SoilManagement {
+                    soil_type: None,
+                    uses_compost: false,
+                    compost_source: None,
+                    conservation_practices: vec![],
+                    soil_testing_frequency: None,
+                },
+                fertilization: Some(FertilizationPractices {
+                    uses_fertilizers: true,
+                    fertilizer_applications: vec![FertilizerApplication {
+                        fertilizer_type: "NPK".to_string(),
+                        npk_ratio: Some("15-15-15".to_string()),
+                        application_rate: 200.0,
+                        applications_per_season: 2,
+                        cost: Some(cost_per_application),
+                        currency: Some(Currency::GHS),
+                        treated_area_ha: None,
+                    }],
+                    soil_test_based: false,
+                    follows_nutrient_plan: false,
+                }),
+                water_management: WaterManagement {
+                    water_source: vec![],
+                    irrigation_system: None,
+                    water_conservation_practices: vec![],
+                    pump_type: None,
+                    lift_height_m: None,
+                },
+                pest_management: PestManagement {
+                    management_approach: "None".to_string(),
+                    uses_ipm: false,
+                    pesticides_used: vec![],
+                    monitoring_frequency: None,
+                },
+            }),
+            equipment_energy: None,
         }
     }
-}
\ No newline at end of file
+
+    fn fertilizer_recommendation(assessment: &Assessment) -> Recommendation {
+        AfricanLCAEngine::new(methodology())
+            .generate_recommendations(assessment)
+            .into_iter()
+            .find(|rec| matches!(rec.category, RecommendationCategory::FertilizerOptimization))
+            .expect("fertilizer recommendation")
+    }
+
+    #[test]
+    fn higher_fertilizer_cost_gives_higher_savings_and_faster_payback() {
+        let low = fertilizer_recommendation(&farm_with_fertilizer_cost(200.0));
+        let high = fertilizer_recommendation(&farm_with_fertilizer_cost(1_000.0));
+
+        let low_savings = low.estimated_annual_savings.expect("low-cost savings");
+        let high_savings = high.estimated_annual_savings.expect("high-cost savings");
+        assert!((low_savings - 200.0 * 2.0 * 0.20).abs() < 1e-9);
+        assert!(high_savings > low_savings);
+        assert!(high.payback_months.unwrap() < low.payback_months.unwrap());
+    }
+
+    #[test]
+    fn costs_in_several_currencies_roll_up_into_the_assessment_currency() {
+        // 150 GHS per application × 2 applications, plus 10 USD of electricity a month
+        let mut farm = farm_with_grid_electricity(100.0);
+        farm.currency = Currency::NGN;
+        let practices = farm.management_practices.as_mut().unwrap();
+        let application = &mut practices.fertilization.as_mut().unwrap().fertilizer_applications[0];
+        application.cost = Some(150.0);
+        application.currency = Some(Currency::GHS);
+        let energy = farm.equipment_energy.as_mut().unwrap();
+        energy.energy_sources[0].cost = Some(10.0);
+        energy.energy_sources[0].currency = Some(Currency::USD);
+
+        let costs = farm.cost_rollup();
+        assert_eq!(costs.currency, Currency::NGN);
+        assert_eq!(costs.exchange_rates, ExchangeRates::default());
+        assert!((costs.fertilizer - 300.0 / 15.0 * 1500.0).abs() < 1e-6, "{}", costs.fertilizer);
+        assert!((costs.energy - 120.0 * 1500.0).abs() < 1e-6, "{}", costs.energy);
+        assert!((costs.total() - (300.0 / 15.0 + 120.0) * 1500.0).abs() < 1e-6, "{}", costs.total());
+        assert!(costs.warnings.is_empty(), "{:?}", costs.warnings);
+
+        // Rates the caller gives are used, and recorded with the rollup
+        let rates = ExchangeRates { as_of: "2025-06-30".to_string(), ghs_per_usd: 12.0, ngn_per_usd: 1_550.0 };
+        let costs = Assessment { exchange_rates: rates.clone(), ..farm.clone() }.cost_rollup();
+        assert!((costs.total() - (300.0 / 12.0 + 120.0) * 1550.0).abs() < 1e-6, "{}", costs.total());
+        assert_eq!(costs.exchange_rates, rates);
+        let mut unpriced = Assessment { exchange_rates: ExchangeRates { ghs_per_usd: 0.0, ..rates }, ..farm.clone() };
+        assert!(matches!(
+            AfricanLCAEngine::new(methodology()).perform_assessment(&mut unpriced),
+            Err(EngineError::Input(InputError::Invalid { ref field, .. })) if field == "exchange_rates.ghs_per_usd"
+        ));
+
+        // A cost without a currency is taken as NGN and flagged
+        farm.equipment_energy.as_mut().unwrap().energy_sources[0].currency = None;
+        let costs = farm.cost_rollup();
+        assert!((costs.energy - 120.0).abs() < 1e-9, "{}", costs.energy);
+        assert_eq!(costs.warnings.len(), 1);
+        assert!(costs.warnings[0].contains("Grid Electricity") && costs.warnings[0].contains("NGN"), "{}", costs.warnings[0]);
+    }
+
+    #[test]
+    fn no_cost_high_impact_recommendation_outranks_a_high_cost_one() {
+        let recommendation = |title: &str, reductions: &[(&str, f64)], cost_category| Recommendation {
+            category: RecommendationCategory::SystemDesign,
+            title: title.to_string(),
+            description: String::new(),
+            potential_impact_reduction: reductions.iter().map(|(category, percent)| (category.to_string(), *percent)).collect(),
+            implementation_difficulty: DifficultyLevel::Medium,
+            cost_category,
+            priority: Priority::Medium,
+            estimated_annual_savings: None,
+            payback_months: None,
+            marginal_abatement_cost: None,
+        };
+        let mut recommendations = vec![
+            recommendation("Mulch", &[("Water consumption", 10.0)], CostCategory::LowCost),
+            recommendation("Drip irrigation", &[("Global warming", 5.0), ("Water consumption", 40.0)], CostCategory::HighCost),
+            recommendation("Intercrop with legumes", &[("Global warming", 15.0)], CostCategory::NoCost),
+        ];
+
+        prioritize_recommendations(&mut recommendations, 2000.0, &Currency::GHS, &ExchangeRates::default());
+        let titles: Vec<&str> = recommendations.iter().map(|rec| rec.title.as_str()).collect();
+        assert_eq!(titles, ["Intercrop with legumes", "Drip irrigation", "Mulch"]);
+
+        let (intercrop, drip, mulch) = (&recommendations[0], &recommendations[1], &recommendations[2]);
+        assert_eq!(intercrop.marginal_abatement_cost, Some(0.0));
+        assert_eq!(intercrop.priority, Priority::High);
+        // 600 USD a year at 15 GHS/USD over 5% of 2000 kg CO2-eq
+        assert!((drip.marginal_abatement_cost.unwrap() - 600.0 * 15.0 / 100.0).abs() < 1e-9);
+        assert_eq!(drip.priority, Priority::Low);
+        assert_eq!(mulch.marginal_abatement_cost, None);
+        assert_eq!(mulch.priority, Priority::Medium);
+    }
+
+    #[test]
+    fn costed_recommendations_that_pay_back_have_a_negative_abatement_cost() {
+        let mut farm = farm_with_fertilizer_cost(1_000.0);
+        AfricanLCAEngine::new(methodology()).perform_assessment(&mut farm).unwrap();
+        let recommendations = farm.results.unwrap().recommendations.unwrap();
+        let soil_testing = &recommendations[0];
+        assert_eq!(soil_testing.category, RecommendationCategory::FertilizerOptimization);
+        assert!(soil_testing.marginal_abatement_cost.unwrap() < 0.0);
+        assert_eq!(soil_testing.priority, Priority::High);
+    }
+
+    #[test]
+    fn missing_costs_leave_savings_unestimated() {
+        let mut assessment = farm_with_fertilizer_cost(0.0);
+        if let Some(ref mut practices) = assessment.management_practices {
+            practices.fertilization.as_mut().unwrap().fertilizer_applications[0].cost = None;
+        }
+        let rec = fertilizer_recommendation(&assessment);
+        assert!(rec.estimated_annual_savings.is_none());
+        assert!(rec.payback_months.is_none());
+    }
+
+    #[test]
+    fn inventory_is_reported_sorted_unless_excluded() {
+        let mut assessment = farm_with_fertilizer_cost(200.0);
+        AfricanLCAEngine::new(methodology()).perform_assessment(&mut assessment).unwrap();
+
+        let flows = assessment.results.unwrap().lci_inventory.expect("inventory included by default");
+        assert!(flows.iter().any(|f| f.substance.contains("N2O") && f.source.contains("NPK application")),
+                "{flows:?}");
+        let keys: Vec<_> = flows.iter().map(|f| (&f.compartment, &f.substance, &f.source)).collect();
+        assert!(keys.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let mut compact = farm_with_fertilizer_cost(200.0);
+        compact.methodology.include_inventory = false;
+        AfricanLCAEngine::new(methodology()).perform_assessment(&mut compact).unwrap();
+        assert!(compact.results.unwrap().lci_inventory.is_none());
+    }
+
+    #[test]
+    fn rainfed_crops_use_green_water_and_only_blue_water_is_scarce() {
+        let farm = farm_with_fertilizer_cost(200.0);
+        let rainfed = assessed(farm.with_irrigation("None (Rainfed)")).results.unwrap();
+        let irrigated = assessed(farm.with_irrigation("Drip irrigation")).results.unwrap();
+        let (rainfed_water, irrigated_water) = (rainfed.water_footprint.unwrap(), irrigated.water_footprint.unwrap());
+
+        // 1 ha of maize, 2000 kg: drip supplies 3000 of the 4500 m3 the crop transpires
+        assert_eq!(rainfed_water.blue, 0.0);
+        assert!((irrigated_water.blue - 3000.0 / 2000.0).abs() < 1e-12);
+        assert!((rainfed_water.green - 4500.0 / 2000.0).abs() < 1e-12);
+        assert!((irrigated_water.green - 1500.0 / 2000.0).abs() < 1e-12);
+        assert!(rainfed_water.grey > 0.0 && rainfed_water.grey == irrigated_water.grey);
+        assert!((rainfed_water.total - rainfed_water.green - rainfed_water.grey).abs() < 1e-12);
+
+        assert_eq!(rainfed.midpoint_impacts["Water scarcity"].value, 0.0);
+        let scarcity = irrigated.midpoint_impacts["Water scarcity"].value;
+        assert!((scarcity - irrigated_water.blue * 20.0).abs() < 1e-9, "{}", scarcity);
+    }
+
+    #[test]
+    fn global_water_scarcity_uses_world_average_aware_unless_overridden() {
+        let mut farm = farm_with_fertilizer_cost(200.0).with_irrigation("Drip irrigation");
+        farm.country = Country::Global;
+        farm.currency = Currency::USD;
+
+        let results = assessed(farm.clone()).results.unwrap();
+        let consumption = results.midpoint_impacts["Water consumption"].value;
+        let scarcity = results.midpoint_impacts["Water scarcity"].value;
+        assert!(consumption > 0.0);
+        assert!(scarcity > consumption);
+        assert!((scarcity - consumption * GLOBAL_AVERAGE_AWARE).abs() < 1e-6 * scarcity);
+
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.load_regional_factors(HashMap::from([("Global_water_scarcity".to_string(), 10.0)]));
+        engine.perform_assessment(&mut farm).unwrap();
+        let overridden = &farm.results.unwrap().midpoint_impacts["Water scarcity"];
+        assert!((overridden.value - consumption * 10.0).abs() < 1e-6 * overridden.value);
+    }
+
+    #[test]
+    fn endpoint_damage_and_single_score_are_split_by_contributor() {
+        let midpoint = |value: f64| MidpointResult {
+            value,
+            unit: "-".to_string(),
+            basis: ImpactBasis::PerKgProduct,
+            uncertainty_range: (value * 0.8, value * 1.25),
+            geometric_sd: None,
+            data_quality_score: 0.8,
+            contributing_sources: Vec::new(),
+            metadata: None,
+        };
+        let midpoints = BTreeMap::from([
+            ("Global warming".to_string(), midpoint(1000.0)),
+            ("Water scarcity".to_string(), midpoint(50.0)),
+            ("Particulate matter formation".to_string(), midpoint(2.0)),
+            ("Land use".to_string(), midpoint(400.0)),
+            ("Fossil depletion".to_string(), midpoint(30.0)),
+        ]);
+        let engine = AfricanLCAEngine::new(methodology());
+        let endpoints = engine.calculate_enhanced_endpoint_impacts(&midpoints).unwrap();
+        let characterization = engine.endpoint_characterization();
+
+        for (name, endpoint) in &endpoints {
+            let mut percentages = 0.0;
+            for (category, contribution) in &endpoint.contributions {
+                let factor = characterization.iter().find(|(e, c, _)| e == name && c == category).unwrap().2;
+                assert!((contribution.value - midpoints[category].value * factor).abs() <= 1e-12 * endpoint.value.abs());
+                percentages += contribution.percentage;
+            }
+            assert!((percentages - 100.0).abs() < 1e-9, "{name}: {percentages}");
+            let total: f64 = endpoint.contributions.values().map(|contribution| contribution.value).sum();
+            assert!((total - endpoint.value).abs() <= 1e-12 * endpoint.value.abs(), "{name}");
+
+            // Only the dominant contributor carries its range, scaled by the factor
+            let ranged: Vec<_> = endpoint.contributions.iter().filter(|(_, c)| c.uncertainty_range.is_some()).collect();
+            assert_eq!(ranged.len(), 1, "{name}");
+            let (category, dominant) = ranged[0];
+            assert!(endpoint.contributions.values().all(|c| c.value.abs() <= dominant.value.abs()));
+            let (low, high) = dominant.uncertainty_range.unwrap();
+            assert!((low / dominant.value - 0.8).abs() < 1e-12 && (high / dominant.value - 1.25).abs() < 1e-12, "{category}");
+        }
+        assert_eq!(endpoints["Human Health"].contributions.keys().collect::<Vec<_>>(),
+                   ["Global warming", "Particulate matter formation", "Water scarcity"]);
+
+        let score = engine.calculate_enhanced_single_score(&endpoints, &Country::Ghana).unwrap();
+        assert_eq!(score.endpoint_contributions.len(), 3);
+        let percentages: f64 = score.endpoint_contributions.values().map(|contribution| contribution.percentage).sum();
+        assert!((percentages - 100.0).abs() < 1e-9, "{percentages}");
+    }
+
+    #[test]
+    fn each_weighting_method_gives_its_own_single_score() {
+        // Normalized endpoints of 0.2, 0.5 and 1.3 person-equivalents
+        let endpoint = |value: f64| EndpointResult {
+            value,
+            unit: "-".to_string(),
+            uncertainty_range: (value, value),
+            normalization_factor: Some(1.0),
+            regional_adaptation_factor: None,
+            contributions: BTreeMap::new(),
+            metadata: None,
+        };
+        let endpoints = BTreeMap::from([
+            ("Human Health".to_string(), endpoint(0.2)),
+            ("Ecosystem Quality".to_string(), endpoint(0.5)),
+            ("Resource Scarcity".to_string(), endpoint(1.3)),
+        ]);
+        let score = |weighting_method: WeightingMethod| {
+            let mut methodology = methodology();
+            methodology.weighting_method = Some(weighting_method);
+            AfricanLCAEngine::new(methodology).calculate_enhanced_single_score(&endpoints, &Country::Ghana).map(|s| s.value)
+        };
+
+        let scores: Vec<f64> = [
+            WeightingMethod::AfricanPriorities,
+            WeightingMethod::EqualWeights,
+            WeightingMethod::ExpertJudgment,
+            WeightingMethod::SocialPreferences,
+            WeightingMethod::CustomWeights(BTreeMap::from([
+                ("Human Health".to_string(), 0.1),
+                ("Ecosystem Quality".to_string(), 0.1),
+                ("Resource Scarcity".to_string(), 0.8),
+            ])),
+        ].into_iter().map(|method| score(method).unwrap()).collect();
+        for (i, a) in scores.iter().enumerate() {
+            for b in &scores[i + 1..] {
+                assert!((a - b).abs() > 1e-6, "{scores:?}");
+            }
+        }
+
+        let unbalanced = WeightingMethod::CustomWeights(BTreeMap::from([("Human Health".to_string(), 0.5)]));
+        assert!(score(unbalanced).unwrap_err().to_string().contains("sum to 0.500"));
+    }
+
+    #[test]
+    fn single_scores_are_interpreted_against_their_weightings_references() {
+        let endpoint = |value: f64| EndpointResult {
+            value,
+            unit: "-".to_string(),
+            uncertainty_range: (value, value),
+            normalization_factor: Some(1.0),
+            regional_adaptation_factor: None,
+            contributions: BTreeMap::new(),
+            metadata: None,
+        };
+        let endpoints = BTreeMap::from([
+            ("Human Health".to_string(), endpoint(0.2)),
+            ("Ecosystem Quality".to_string(), endpoint(0.5)),
+            ("Resource Scarcity".to_string(), endpoint(1.3)),
+        ]);
+        let engine = |weighting_method| AfricanLCAEngine::new(LCAMethodology { weighting_method: Some(weighting_method), ..methodology() });
+        let interpret = |engine: &AfricanLCAEngine| engine.calculate_enhanced_single_score(&endpoints, &Country::Ghana)
+            .unwrap().interpretation.unwrap();
+
+        let african = interpret(&engine(WeightingMethod::AfricanPriorities));
+        let equal = interpret(&engine(WeightingMethod::EqualWeights));
+        assert_eq!(african.bands, ScoreBands::default());
+        assert_ne!(equal.bands, african.bands);
+        assert!(african.label.contains("in Ghana") && equal.label.contains("in Global"), "{} / {}", african.label, equal.label);
+        assert_eq!(african.endpoint_interpretations.len(), 3);
+        assert!(african.endpoint_interpretations["Resource Scarcity"].contains("largest driver"));
+
+        // A loaded distribution is preferred to the bundled ones
+        let mut survey = engine(WeightingMethod::EqualWeights);
+        survey.load_score_references(vec![ScoreReference {
+            country: Country::Ghana,
+            functional_unit: "1 kg".to_string(),
+            weighting_method: Some(WeightingMethod::EqualWeights),
+            bands: ScoreBands { excellent: 0.1, good: 0.2, average: 0.3 },
+            percentiles: vec![(50.0, 0.2)],
+            source: "Farm survey".to_string(),
+        }]);
+        let surveyed = interpret(&survey);
+        assert_eq!((surveyed.band, surveyed.reference_source.as_deref()), (ScoreBand::Poor, Some("Farm survey")));
+    }
+
+    #[test]
+    fn zero_endpoints_and_references_give_a_finite_single_score() {
+        let endpoint = |value: f64, normalization_factor: Option<f64>| EndpointResult {
+            value,
+            unit: "-".to_string(),
+            uncertainty_range: (value, value),
+            normalization_factor,
+            regional_adaptation_factor: None,
+            contributions: BTreeMap::new(),
+            metadata: None,
+        };
+        let engine = AfricanLCAEngine::new(methodology());
+        let zeros = BTreeMap::from([
+            ("Human Health".to_string(), endpoint(0.0, Some(0.0))),
+            ("Ecosystem Quality".to_string(), endpoint(0.0, None)),
+            ("Resource Scarcity".to_string(), endpoint(0.0, Some(f64::NAN))),
+        ]);
+        let score = engine.calculate_enhanced_single_score(&zeros, &Country::Ghana).unwrap();
+        assert_eq!((score.value, score.uncertainty_range), (0.0, (0.0, 0.0)));
+
+        // A zero reference falls back to the default rather than dividing by zero
+        assert_eq!(normalization_reference("Human Health", &endpoint(1.0, Some(0.0))), 2.2e-2);
+        let harmful = BTreeMap::from([("Human Health".to_string(), endpoint(0.01, Some(0.0)))]);
+        assert!(engine.calculate_enhanced_single_score(&harmful, &Country::Ghana).unwrap().value.is_finite());
+    }
+
+    #[test]
+    fn seeded_monte_carlo_runs_are_identical() {
+        let midpoint = assessed(farm_with_fertilizer_cost(200.0)).results.unwrap().midpoint_impacts;
+        let run = |seed: Option<u64>| AfricanLCAEngine::new_with_seed(methodology(), seed)
+            .propagate_uncertainty(&midpoint, 2000);
+
+        assert_eq!(run(Some(7)), run(Some(7)));
+        assert_eq!(run(None), AfricanLCAEngine::new(methodology()).propagate_uncertainty(&midpoint, 2000));
+
+        let gwp = &run(Some(7))["Global warming"];
+        assert_ne!(gwp, &run(Some(8))["Global warming"]);
+        assert_eq!(gwp.seed, 7);
+        assert!(gwp.percentile_2_5 < gwp.median && gwp.median < gwp.percentile_97_5);
+        let value = midpoint["Global warming"].value;
+        assert!((gwp.median - value).abs() < 0.05 * value, "{} vs {}", gwp.median, value);
+    }
+
+    #[test]
+    fn factor_results_carry_the_pedigree_gsd_into_range_and_monte_carlo() {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        AfricanLCAEngine::new(methodology()).perform_assessment(&mut farm).unwrap();
+        for impacts in farm.results.unwrap().breakdown_by_food.values() {
+            for (category, result) in impacts {
+                let Some(gsd) = result.geometric_sd else { continue };
+                assert!(gsd > 1.0, "{}: {}", category, gsd);
+                let (low, high) = lognormal_interval(result.value, gsd);
+                assert!((result.uncertainty_range.0 - low).abs() <= 1e-9 * high.abs().max(1.0), "{}", category);
+                assert!((result.uncertainty_range.1 - high).abs() <= 1e-9 * high.abs().max(1.0), "{}", category);
+            }
+        }
+
+        // The sampled spread follows the GSD, not the range it is reported with
+        let midpoint = BTreeMap::from([("Global warming".to_string(), MidpointResult {
+            value: 100.0,
+            unit: "kg CO2-eq".to_string(),
+            basis: ImpactBasis::Total,
+            uncertainty_range: (90.0, 110.0),
+            geometric_sd: Some(1.5),
+            data_quality_score: 0.8,
+            contributing_sources: Vec::new(),
+            metadata: None,
+        })]);
+        let gwp = &AfricanLCAEngine::new(methodology()).propagate_uncertainty(&midpoint, 20_000)["Global warming"];
+        let expected = 100.0 * 1.5f64.powf(1.96);
+        assert!((gwp.percentile_97_5 - expected).abs() < 0.05 * expected, "{} vs {}", gwp.percentile_97_5, expected);
+    }
+
+    #[test]
+    fn contribution_tree_leaves_sum_to_single_score() {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        let engine = AfricanLCAEngine::new(methodology());
+        engine.clone().perform_comprehensive_assessment(&mut farm).unwrap();
+        let results = farm.results.unwrap();
+
+        let tree = engine.build_contribution_tree(&results);
+        assert_eq!(tree.root.contribution, results.single_score.value);
+        let leaf_total: f64 = tree.leaves().iter().map(|leaf| leaf.contribution).sum();
+        assert!((leaf_total - results.single_score.value).abs() < 1e-9 * results.single_score.value.max(1.0),
+            "leaves {} vs score {}", leaf_total, results.single_score.value);
+
+        // Fertilizer N2O is traced down to the activity that emitted it
+        let sources = tree.leaves().into_iter().filter(|leaf| leaf.level == crate::production::interpretation::ContributionLevel::SourceActivity).count();
+        assert!(sources > 0);
+    }
+
+    #[test]
+    fn benchmarks_compare_intensities_so_farm_size_does_not_decide_the_category() {
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.load_factor_index(Arc::new(crate::production::data::DataLoader::new().impact_factors.iter().cloned().collect()));
+        let comparison = |quantity_kg: f64| {
+            let mut farm = farm_with_fertilizer_cost(200.0);
+            farm.management_practices = None;
+            farm.foods[0].quantity_kg = quantity_kg;
+            farm.foods[0].area_allocated = Some(quantity_kg / 2000.0);
+            engine.clone().perform_assessment(&mut farm).unwrap();
+            farm.results.unwrap().comparative_analysis.unwrap()
+        };
+
+        let (smallholder, estate) = (comparison(10.0), comparison(100_000.0));
+        assert_eq!(smallholder.benchmark_comparisons.len(), estate.benchmark_comparisons.len());
+        assert!(!smallholder.benchmark_comparisons.is_empty());
+        for (small, large) in smallholder.benchmark_comparisons.iter().zip(&estate.benchmark_comparisons) {
+            assert_eq!(small.benchmark_name, large.benchmark_name);
+            assert_eq!(small.performance_category, large.performance_category, "{}", small.benchmark_name);
+            assert!((small.your_performance - large.your_performance).abs() < 1e-9 * small.your_performance.abs().max(1.0), "{} {} vs {}", small.benchmark_name, small.your_performance, large.your_performance);
+        }
+        assert!(smallholder.benchmark_comparisons.iter().any(|c| c.basis == Some(BenchmarkBasis::PerHectare)));
+        assert_eq!(smallholder.regional_comparisons[0].impact_ratios, estate.regional_comparisons[0].impact_ratios);
+
+        // A per-capita diet benchmark is not compared with a per-kg result
+        let diet = ImpactBenchmark {
+            name: "Global average diet".to_string(),
+            country: Country::Global,
+            impact_category: "Global warming".to_string(),
+            basis: BenchmarkBasis::PerCapita,
+            value: 2000.0,
+            source: "Test".to_string(),
+        };
+        assert!(diet.compare(0.5, None, BenchmarkBasis::PerKgProduct).is_err());
+    }
+
+    #[test]
+    fn rice_methane_adjustment_follows_the_regions_agro_ecological_zone() {
+        let loader = crate::production::data::DataLoader::new();
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.load_climate_adjustments(loader.climate_adjustments.clone());
+        engine.load_region_zones(loader.region_zones.clone());
+        engine.load_climate_adjustment_rules(loader.climate_adjustment_rules.clone());
+
+        let rice_gwp = |region: &str| {
+            let mut farm = farm_with_fertilizer_cost(200.0);
+            farm.region = Some(region.to_string());
+            farm.foods[0].name = "Rice".to_string();
+            farm.foods[0].crop_type = Some("Rice".to_string());
+            engine.clone().perform_comprehensive_assessment(&mut farm).unwrap();
+            farm.results.unwrap().breakdown_by_food.values().next().unwrap()["Global warming"].clone()
+        };
+
+        let savannah = rice_gwp("Northern Region"); // Tamale, Guinea savannah
+        let rainforest = rice_gwp("Western"); // High rainforest
+        assert!(savannah.value < rainforest.value, "savannah {} vs rainforest {}", savannah.value, rainforest.value);
+        assert!(rainforest.contributing_sources.iter().any(|source| source.contains("High rainforest, Paddy CH4")),
+            "{:?}", rainforest.contributing_sources);
+
+        // Guinea savannah rice keeps the factor as it is, so no adjustment is listed
+        assert!(!savannah.contributing_sources.iter().any(|source| source.starts_with("Climate adjustment")),
+            "{:?}", savannah.contributing_sources);
+    }
+
+    #[test]
+    fn sahel_location_lowers_tropical_decomposition_versus_humid_coast() {
+        let loader = crate::production::data::DataLoader::new();
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.load_climate_adjustments(loader.climate_adjustments.clone());
+        engine.load_climate_zones(loader.climate_zones.clone());
+
+        let soil_degradation = |latitude: f64, longitude: f64| {
+            let mut farm = farm_with_fertilizer_cost(200.0);
+            farm.country = Country::Nigeria;
+            farm.coordinates = Some(GeoCoordinates { latitude, longitude });
+            assert!(engine.climate_zone(&farm).is_some());
+            engine.clone().perform_comprehensive_assessment(&mut farm).unwrap();
+            farm.results.unwrap().breakdown_by_food.values().next().unwrap()["Soil degradation"].value
+        };
+
+        let sahel = soil_degradation(13.1, 5.2); // Sokoto
+        let coast = soil_degradation(6.5, 3.4); // Lagos
+        assert!(sahel < coast, "Sahel {sahel} vs coast {coast}");
+
+        // Without coordinates the country default (humid tropical) set applies
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.country = Country::Nigeria;
+        assert_eq!(engine.climate_zone(&farm), None);
+        engine.clone().perform_comprehensive_assessment(&mut farm).unwrap();
+        let default = farm.results.unwrap().breakdown_by_food.values().next().unwrap()["Soil degradation"].value;
+        assert_eq!(default, coast);
+    }
+
+    #[test]
+    fn the_nutrient_plan_n2o_effect_is_counted_once_in_each_section() {
+        let label = PracticeEffect::FertilizerN2O.label();
+        let mentions = |result: &MidpointResult| result.contributing_sources.iter().filter(|s| s.starts_with(label)).count();
+        let assess = |farm: &mut Assessment| {
+            let fertilization = farm.management_practices.as_mut().unwrap().fertilization.as_mut().unwrap();
+            fertilization.soil_test_based = true;
+            fertilization.follows_nutrient_plan = true;
+            AfricanLCAEngine::new(methodology()).perform_comprehensive_assessment(farm).unwrap();
+            farm.results.take().unwrap()
+        };
+
+        // The inventory models the N applied, so the breakdown leaves its 0.8 multiplier out
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        let results = assess(&mut farm);
+        assert_eq!(mentions(&results.midpoint_impacts["Global warming"]), 1);
+        let breakdown = results.breakdown_by_food.values().next().unwrap();
+        assert_eq!(mentions(&breakdown["Global warming"]), 1);
+        assert!(breakdown["Global warming"].contributing_sources.iter()
+            .any(|s| s.starts_with(label) && s.contains("not applied")));
+
+        let mut unpractised = farm_with_fertilizer_cost(200.0);
+        let fertilization = unpractised.management_practices.as_mut().unwrap().fertilization.as_mut().unwrap();
+        fertilization.soil_test_based = false;
+        fertilization.follows_nutrient_plan = false;
+        AfricanLCAEngine::new(methodology()).perform_comprehensive_assessment(&mut unpractised).unwrap();
+        let unpractised = unpractised.results.unwrap();
+        assert_eq!(
+            unpractised.breakdown_by_food.values().next().unwrap()["Global warming"].value,
+            breakdown["Global warming"].value
+        );
+
+        // Without fertilizer applications nothing in the inventory carries the effect
+        let mut unfertilized = farm_with_fertilizer_cost(200.0);
+        unfertilized.management_practices.as_mut().unwrap().fertilization.as_mut().unwrap().uses_fertilizers = false;
+        let results = assess(&mut unfertilized);
+        assert_eq!(mentions(&results.midpoint_impacts["Global warming"]), 0);
+        let breakdown = results.breakdown_by_food.values().next().unwrap();
+        assert_eq!(mentions(&breakdown["Global warming"]), 1);
+        assert!(breakdown["Global warming"].contributing_sources.iter().any(|s| s == &format!("{}: ×0.80", label)));
+    }
+
+    #[test]
+    fn incremental_food_update_matches_full_recompute() {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.management_practices = None;
+        let maize = farm.foods[0].clone();
+        farm.foods.push(FoodItem { id: "2".to_string(), name: "Cowpea".to_string(), category: FoodCategory::Legumes, crop_type: Some("Cowpea".to_string()), quantity_kg: 800.0, ..maize.clone() });
+        farm.foods.push(FoodItem { id: "3".to_string(), name: "Rice".to_string(), crop_type: Some("Rice".to_string()), quantity_kg: 1500.0, ..maize.clone() });
+
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.perform_assessment(&mut farm).unwrap();
+        let edited = FoodItem { quantity_kg: 3000.0, production_system: Some(ProductionSystem::Organic), ..farm.foods[1].clone() };
+        engine.update_food(&mut farm, 1, edited).unwrap();
+
+        let mut full = farm.clone();
+        full.results = None;
+        AfricanLCAEngine::new(methodology()).perform_assessment(&mut full).unwrap();
+        assert_eq!(serde_json::to_value(&farm.results).unwrap(), serde_json::to_value(&full.results).unwrap());
+
+        assert!(matches!(engine.update_food(&mut farm, 3, maize), Err(EngineError::NoSuchFood { index: 3, count: 3 })));
+    }
+
+    #[test]
+    fn incremental_update_of_a_comprehensive_assessment_matches_full_recompute() {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        let maize = farm.foods[0].clone();
+        farm.foods.push(FoodItem { id: "2".to_string(), name: "Cowpea".to_string(), category: FoodCategory::Legumes, crop_type: Some("Cowpea".to_string()), quantity_kg: 800.0, ..maize.clone() });
+
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.perform_assessment(&mut farm).unwrap();
+        let edited = FoodItem { quantity_kg: 3000.0, ..farm.foods[1].clone() };
+        engine.update_food(&mut farm, 1, edited).unwrap();
+
+        let full = |farm: &Assessment| {
+            let mut full = Assessment { results: None, ..farm.clone() };
+            AfricanLCAEngine::new(methodology()).perform_assessment(&mut full).unwrap();
+            serde_json::to_value(&full.results).unwrap()
+        };
+        assert_eq!(serde_json::to_value(&farm.results).unwrap(), full(&farm));
+
+        // A food edited outside update_food is not one the cached impacts were computed for
+        farm.foods[0].production_system = Some(ProductionSystem::Organic);
+        let edited = FoodItem { quantity_kg: 1200.0, ..farm.foods[1].clone() };
+        engine.update_food(&mut farm, 1, edited).unwrap();
+        assert_eq!(serde_json::to_value(&farm.results).unwrap(), full(&farm));
+    }
+
+    #[test]
+    fn thirty_percent_losses_carry_thirty_percent_of_impacts() {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.foods[0].post_harvest_losses = Some(30.0);
+        let results = assessed(farm).results.unwrap();
+
+        let footprint = results.food_loss_footprint.unwrap();
+        for (category, total) in results.midpoint_impacts.iter().filter(|(_, r)| r.value != 0.0) {
+            let lost = &footprint[category];
+            assert!((lost.share_percent - 30.0).abs() < 1e-9, "{category}: {}%", lost.share_percent);
+            assert!((lost.value - 0.3 * total.value).abs() < 1e-9 * total.value.abs().max(1.0));
+        }
+    }
+
+    fn dairy_global_warming_per_kg(milk_yield_kg_per_cow: f64, concentrate_kg_per_day: f64) -> f64 {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.management_practices = None;
+        let herd_size = 20;
+        let milk = &mut farm.foods[0];
+        milk.name = "Fresh milk".to_string();
+        milk.category = FoodCategory::Dairy;
+        milk.crop_type = None;
+        milk.area_allocated = None;
+        milk.quantity_kg = herd_size as f64 * milk_yield_kg_per_cow;
+        milk.dairy = Some(DairyHerdProfile {
+            herd_size,
+            milk_yield_kg_per_cow,
+            feed_intake_kg_dm_per_day: None,
+            feed_digestibility_percent: None,
+            feed_protein_percent: None,
+            concentrate_kg_per_day,
+            manure_system: None,
+        });
+        let quantity_kg = milk.quantity_kg;
+        assessed(farm).results.unwrap().midpoint_impacts["Global warming"].value / quantity_kg
+    }
+
+    #[test]
+    fn low_yield_herds_emit_more_per_kg_of_milk() {
+        // Local zebu on grazing versus a crossbred herd on 3 kg/day of concentrate
+        let local = dairy_global_warming_per_kg(600.0, 0.0);
+        let crossbred = dairy_global_warming_per_kg(4000.0, 3.0);
+
+        assert!(local > 2.0 * crossbred, "local {local}, crossbred {crossbred}");
+        // Herd data replaces the flat 3.2 kg CO2-eq/kg dairy factor
+        assert!((crossbred - 3.2).abs() > 1.0, "crossbred {crossbred}");
+    }
+
+    #[test]
+    fn a_ranch_gets_no_dietary_warning() {
+        // 500 head of cattle sold at 150 kg carcass each
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.foods[0] = FoodItem {
+            id: "beef".to_string(),
+            name: "Beef".to_string(),
+            category: FoodCategory::Meat,
+            crop_type: Some("Cattle".to_string()),
+            quantity_kg: 75_000.0,
+            area_allocated: None,
+            head_count: Some(500),
+            ..farm.foods[0].clone()
+        };
+        farm.management_practices.as_mut().unwrap().fertilization = None;
+        let data_quality = assessed(farm).results.unwrap().data_quality;
+
+        assert!(data_quality.calculation_warnings.is_empty(), "{:?}", data_quality.calculation_warnings);
+        assert!(!data_quality.warnings.iter().any(|warning| warning.contains("meat")), "{:?}", data_quality.warnings);
+    }
+
+    #[test]
+    fn implausible_yield_warns_without_blocking_the_run() {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.foods[0].quantity_kg = 100_000.0; // 100 t on 1 ha
+        let data_quality = assessed(farm).results.unwrap().data_quality;
+
+        assert_eq!(data_quality.calculation_warnings.len(), 1);
+        assert_eq!(data_quality.calculation_warnings[0].kind, CalculationWarningKind::ImplausiblyHighYield);
+        assert!(data_quality.warnings.contains(&data_quality.calculation_warnings[0].message));
+    }
+
+    #[test]
+    fn global_warming_only_skips_water_and_land() {
+        let carbon_only = LCAMethodology { impact_categories: Some(vec!["Global warming".to_string()]), ..methodology() };
+        let mut hybrid = farm_with_fertilizer_cost(200.0);
+        hybrid.management_practices = None;
+
+        for mut farm in [farm_with_fertilizer_cost(200.0), hybrid] {
+            let full = assessed(farm.clone()).results.unwrap();
+            farm.methodology = carbon_only.clone();
+            AfricanLCAEngine::new(carbon_only.clone()).perform_assessment(&mut farm).unwrap();
+            let results = farm.results.unwrap();
+
+            assert_eq!(results.midpoint_impacts.keys().collect::<Vec<_>>(), ["Global warming"]);
+            assert!(results.breakdown_by_food.values().all(|impacts| impacts.keys().eq(["Global warming"].iter())));
+            assert_eq!(results.midpoint_impacts["Global warming"].value, full.midpoint_impacts["Global warming"].value);
+            // Resource Scarcity draws only on water and fossil depletion
+            assert!(!results.endpoint_impacts.contains_key("Resource Scarcity"));
+            assert!(results.endpoint_impacts.contains_key("Human Health"));
+            assert!((results.single_score.weighting_factors.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+
+        let unknown = LCAMethodology { impact_categories: Some(vec!["Ozone depletion".to_string()]), ..methodology() };
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        let error = AfricanLCAEngine::new(unknown).perform_assessment(&mut farm).unwrap_err();
+        assert!(matches!(
+            error,
+            EngineError::Input(InputError::UnknownValue { ref field, ref value, ref valid })
+                if field == "impact_categories" && value == "Ozone depletion" && valid.iter().any(|c| c == "Global warming")
+        ), "{:?}", error);
+    }
+
+    #[test]
+    fn strict_mode_refuses_an_empty_factor_set() {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.management_practices = None;
+
+        let mut strict = AfricanLCAEngine::new(methodology());
+        strict.set_strict(true);
+        let error = strict.perform_assessment(&mut farm.clone()).unwrap_err();
+        assert!(matches!(error, EngineError::Factor(FactorError::NoneLoaded)), "{:?}", error);
+        assert!(matches!(
+            strict.perform_comprehensive_assessment(&mut farm_with_fertilizer_cost(200.0)),
+            Err(EngineError::Factor(FactorError::NoneLoaded))
+        ));
+
+        // Lenient by default, but the results say the numbers are default estimates
+        let warnings = assessed(farm.clone()).results.unwrap().data_quality.warnings;
+        assert!(warnings.iter().any(|w| w.starts_with("No impact factors loaded")));
+
+        let mut data_loader = crate::production::data::DataLoader::new();
+        data_loader.load_default_factors().unwrap();
+        strict.load_factor_index(Arc::new(FactorIndex::from_factors(data_loader.get_factors())));
+        strict.perform_assessment(&mut farm).unwrap();
+        assert!(farm.results.unwrap().data_quality.warnings.iter().all(|w| !w.starts_with("No impact factors loaded")));
+    }
+
+    #[test]
+    fn clones_share_loaded_factors_until_one_adds_to_them() {
+        let mut data_loader = crate::production::data::DataLoader::new();
+        data_loader.load_default_factors().unwrap();
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.load_factor_index(Arc::new(FactorIndex::from_factors(data_loader.get_factors())));
+
+        let mut clone = engine.clone();
+        assert!(Arc::ptr_eq(&engine.impact_factors, &clone.impact_factors));
+        assert!(Arc::ptr_eq(&engine.scope_table, &clone.scope_table));
+
+        #[allow(deprecated)]
+        clone.load_impact_factors(vec![ImpactFactor {
+            crop_type: Some("Okra".to_string()),
+            ..data_loader.get_factors()[0].clone()
+        }]);
+        assert_eq!(clone.impact_factors.len(), engine.impact_factors.len() + 1);
+        assert!(!Arc::ptr_eq(&engine.impact_factors, &clone.impact_factors));
+    }
+
+    #[test]
+    fn ghana_tomatoes_use_the_ghana_tomato_factors() {
+        let mut data_loader = crate::production::data::DataLoader::new();
+        data_loader.load_default_factors().unwrap();
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.load_factor_index(Arc::new(FactorIndex::from_factors(data_loader.get_factors())));
+
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.foods[0] = FoodItem {
+            name: "Tomato".to_string(),
+            category: FoodCategory::Vegetables,
+            crop_type: Some("Tomato".to_string()),
+            ..farm.foods[0].clone()
+        };
+        engine.perform_assessment(&mut farm).unwrap();
+
+        let tomato = farm.results.unwrap().breakdown_by_food.into_values().next().unwrap();
+        for category in ["Global warming", "Water consumption", "Land use"] {
+            let sources = &tomato[category].contributing_sources;
+            assert!(sources.iter().all(|source| !source.starts_with("Default estimate")), "{category}: {sources:?}");
+        }
+        assert!(tomato["Land use"].contributing_sources[0].contains("MoFA SRID Ghana"));
+        assert_eq!(tomato["Land use"].value, 1.3 * 2000.0);
+    }
+
+    #[test]
+    fn result_metadata_is_attached_on_request_and_matches_the_units() {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.methodology.include_result_metadata = true;
+        let engine = AfricanLCAEngine::new(farm.methodology.clone());
+        let farm = assessed(farm);
+        let results = farm.results.as_ref().unwrap();
+
+        for impacts in std::iter::once(&results.midpoint_impacts).chain(results.breakdown_by_food.values()) {
+            for (category, result) in impacts {
+                let metadata = result.metadata.as_ref().unwrap();
+                assert_eq!(metadata.unit.numerator, engine.get_impact_unit(category), "{category}");
+                assert_eq!(metadata.unit.to_string(), result.unit);
+                assert_eq!(metadata.characterization_method, Some(CharacterizationMethod::ReCiPe2016));
+            }
+        }
+        // Farm midpoints are per kg of product
+        let gwp = results.midpoint_impacts["Global warming"].metadata.as_ref().unwrap();
+        assert_eq!((gwp.category_id.as_str(), gwp.unit.denominator.as_deref()), ("global_warming", Some("kg")));
+        let health = results.endpoint_impacts["Human Health"].metadata.as_ref().unwrap();
+        assert_eq!(health.category_id, "human_health");
+        assert_eq!(health.unit, StructuredUnit::parse(&results.endpoint_impacts["Human Health"].unit));
+
+        // Off by default, and then absent from the JSON
+        let plain = assessed(farm_with_fertilizer_cost(200.0));
+        let json = serde_json::to_value(plain.results.as_ref().unwrap()).unwrap();
+        assert!(json["midpoint_impacts"]["Global warming"].get("metadata").is_none());
+        assert_eq!(StructuredUnit::parse("species.yr per kg").denominator.as_deref(), Some("kg"));
+    }
+
+    #[test]
+    fn eggs_oils_and_nuts_no_longer_fall_back_to_cereals() {
+        let engine = AfricanLCAEngine::new(methodology());
+        let cereals = |impact: &str| engine.get_default_impact_factor(&FoodCategory::Cereals, impact);
+        // Categories the table covers, where roots differ from cereals too (water scarcity is derived)
+        let tabulated: Vec<String> = engine.get_impact_categories().into_iter()
+            .filter(|impact| engine.get_default_impact_factor(&FoodCategory::Roots, impact) != cereals(impact))
+            .collect();
+        assert!(tabulated.len() >= 12, "{tabulated:?}");
+        for category in [FoodCategory::Eggs, FoodCategory::Oils, FoodCategory::Nuts] {
+            for impact in &tabulated {
+                let factor = engine.get_default_impact_factor(&category, impact);
+                assert_ne!(factor, cereals(impact), "{category:?} {impact}");
+            }
+        }
+
+        let mut data_loader = crate::production::data::DataLoader::new();
+        data_loader.load_default_factors().unwrap();
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.load_factor_index(Arc::new(FactorIndex::from_factors(data_loader.get_factors())));
+        let maize = farm_with_fertilizer_cost(200.0).foods[0].clone();
+        for (category, crop, country) in [
+            (FoodCategory::Eggs, "Layers", Country::Ghana),
+            (FoodCategory::Eggs, "Layers", Country::Nigeria),
+            (FoodCategory::Oils, "Oil palm", Country::Ghana),
+            (FoodCategory::Oils, "Oil palm", Country::Nigeria),
+            (FoodCategory::Nuts, "Groundnuts", Country::Ghana),
+            (FoodCategory::Nuts, "Groundnuts", Country::Nigeria),
+            (FoodCategory::Nuts, "Shea", Country::Ghana),
+            (FoodCategory::Nuts, "Shea", Country::Nigeria),
+        ] {
+            let food = FoodItem { category, crop_type: Some(crop.to_string()), ..maize.clone() };
+            let hierarchy = engine.build_lookup_hierarchy(&food, &country);
+            let (_, source, _, _) = engine.find_best_factor(&food, &country, "Global warming", &hierarchy).unwrap();
+            assert!(!source.starts_with("Default estimate"), "{crop} in {country:?}: {source}");
+        }
+    }
+
+    #[test]
+    fn overrides_beat_loaded_and_default_factors() {
+        let mut data_loader = crate::production::data::DataLoader::new();
+        data_loader.load_default_factors().unwrap();
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.load_factor_index(Arc::new(FactorIndex::from_factors(data_loader.get_factors())));
+
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        let maize = farm.foods[0].clone();
+        let moringa = FoodItem { id: "2".to_string(), name: "Moringa".to_string(), category: FoodCategory::Other, crop_type: None, ..maize.clone() };
+        let gwp = |engine: &AfricanLCAEngine, food: &FoodItem| engine
+            .find_best_factor(food, &Country::Ghana, "Global warming", &engine.build_lookup_hierarchy(food, &Country::Ghana))
+            .unwrap();
+        assert!(gwp(&engine, &maize).1.contains("Ghana"));
+        assert!(gwp(&engine, &moringa).1.starts_with("Default estimate"));
+
+        let measured = |value_per_kg| ImpactFactor {
+            value_per_kg,
+            uncertainty_range: (value_per_kg * 0.9, value_per_kg * 1.1),
+            source: "Farm records 2024".to_string(),
+            ..data_loader.get_factors()[0].clone()
+        };
+        engine.override_factor(FoodCategory::Cereals, Country::Ghana, Some("Maize"), "Global warming", measured(0.21));
+        engine.override_factor(FoodCategory::Other, Country::Global, None, "Global warming", measured(0.05));
+
+        for (food, value) in [(&maize, 0.21), (&moringa, 0.05)] {
+            let (factor, source, _, pedigree) = gwp(&engine, food);
+            assert_eq!(factor, value);
+            assert_eq!(source, "Primary data: Farm records 2024");
+            assert_eq!(pedigree.calculate_overall_quality_score(), PedigreeScore {
+                reliability: 1, completeness: 1, temporal_correlation: 1, geographical_correlation: 1, technological_correlation: 1,
+            }.calculate_overall_quality_score());
+        }
+        // Other categories keep the loaded factors
+        let water = engine.find_best_factor(&maize, &Country::Ghana, "Water consumption", &engine.build_lookup_hierarchy(&maize, &Country::Ghana)).unwrap();
+        assert!(!water.1.starts_with(PRIMARY_DATA));
+
+        engine.perform_assessment(&mut farm).unwrap();
+        let data_quality = farm.results.unwrap().data_quality;
+        assert_eq!(data_quality.factor_overrides, [FactorOverride {
+            food_id: "1".to_string(),
+            impact_category: "Global warming".to_string(),
+            value_per_kg: 0.21,
+            source: "Primary data: Farm records 2024".to_string(),
+        }]);
+        assert!(data_quality.data_source_mix.iter().any(|mix| mix.source_type == DataSource::Primary));
+    }
+
+    #[test]
+    fn sparse_primary_data_is_high_quality_but_incomplete() {
+        let mut data_loader = crate::production::data::DataLoader::new();
+        data_loader.load_default_factors().unwrap();
+        let mut engine = AfricanLCAEngine::new(methodology());
+        for category in engine.get_impact_categories() {
+            engine.override_factor(FoodCategory::Cereals, Country::Ghana, Some("Maize"), &category, ImpactFactor {
+                unit: impact_unit(&category).to_string(),
+                source: "Farm records 2024".to_string(),
+                ..data_loader.get_factors()[0].clone()
+            });
+        }
+
+        // Measured factors for the only food, but nothing else about the farm
+        let mut sparse = Assessment { management_practices: None, ..farm_with_fertilizer_cost(200.0) };
+        sparse.foods[0].area_allocated = None;
+        engine.perform_assessment(&mut sparse).unwrap();
+        let quality = sparse.results.unwrap().data_quality;
+        assert!(quality.quality_score > 0.9, "{}", quality.quality_score);
+        assert_eq!(quality.completeness_score, 0.0);
+        assert!(quality.recommendations.iter().any(|r| r.contains("soil type") && r.contains("equipment and energy")), "{:?}", quality.recommendations);
+
+        // Same factors on a described farm: as good, and far more complete
+        let mut described = Assessment { region: Some("Ashanti".to_string()), ..farm_with_grid_electricity(100.0) };
+        described.foods[0].post_harvest_losses = Some(10.0);
+        engine.perform_assessment(&mut described).unwrap();
+        assert_eq!(described.missing_inputs(), ["farm profile", "soil type", "water management"]);
+        let described_quality = described.results.unwrap().data_quality;
+        assert_eq!(described_quality.quality_score, quality.quality_score);
+        assert_eq!(described_quality.completeness_score, 5.0 / 8.0);
+    }
+
+    #[test]
+    fn a_gsd_given_with_the_factor_replaces_the_one_its_pedigree_implies() {
+        let mut engine = AfricanLCAEngine::new(methodology());
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        let maize = farm.foods[0].clone();
+        let gwp = |engine: &AfricanLCAEngine| engine
+            .find_best_factor(&maize, &Country::Ghana, "Global warming", &engine.build_lookup_hierarchy(&maize, &Country::Ghana))
+            .unwrap().2;
+        let mut data_loader = crate::production::data::DataLoader::new();
+        data_loader.load_default_factors().unwrap();
+        let factor = |gsd| ImpactFactor { gsd, ..data_loader.get_factors()[0].clone() };
+
+        engine.override_factor(FoodCategory::Cereals, Country::Ghana, Some("Maize"), "Global warming", factor(None));
+        assert!(gwp(&engine) > 1.0 && gwp(&engine) != 1.3);
+
+        // Unusable GSDs are ignored in favour of the pedigree
+        let from_pedigree = gwp(&engine);
+        for unusable in [0.5, f64::NAN] {
+            engine.override_factor(FoodCategory::Cereals, Country::Ghana, Some("Maize"), "Global warming", factor(Some(unusable)));
+            assert_eq!(gwp(&engine), from_pedigree);
+        }
+
+        engine.override_factor(FoodCategory::Cereals, Country::Ghana, Some("Maize"), "Global warming", factor(Some(1.3)));
+        assert_eq!(gwp(&engine), 1.3);
+        engine.perform_assessment(&mut farm).unwrap();
+        let maize = farm.results.unwrap().breakdown_by_food.into_values().next().unwrap();
+        let result = &maize["Global warming"];
+        assert_eq!(result.geometric_sd, Some(1.3));
+        assert_eq!(result.uncertainty_range, lognormal_interval(result.value, 1.3));
+    }
+
+    #[test]
+    fn unimplemented_characterization_methods_are_flagged_in_the_results() {
+        let warnings = |characterization_method| {
+            let mut farm = farm_with_fertilizer_cost(200.0);
+            AfricanLCAEngine::new(LCAMethodology { characterization_method, ..methodology() })
+                .perform_assessment(&mut farm).unwrap();
+            farm.results.unwrap().data_quality.warnings
+        };
+
+        let traci = warnings(CharacterizationMethod::TRACI);
+        let flagged: Vec<_> = traci.iter().filter(|warning| warning.starts_with("Characterization method TRACI is not implemented")).collect();
+        assert_eq!(flagged.len(), 1, "{traci:?}");
+        assert!(flagged[0].ends_with("Implemented methods: IpccAr6, IpccAr5, ReCiPe2016"), "{}", flagged[0]);
+
+        for implemented in CharacterizationMethod::IMPLEMENTED {
+            assert!(warnings(implemented).iter().all(|warning| !warning.starts_with("Characterization method")));
+        }
+    }
+
+    fn assessed(mut assessment: Assessment) -> Assessment {
+        AfricanLCAEngine::new(methodology()).perform_assessment(&mut assessment).unwrap();
+        assessment
+    }
+
+    #[test]
+    fn unreported_fertilization_is_a_data_gap_not_zero() {
+        let gap = "Fertilization not reported";
+        let fertilizer_flows = |engine: &AfricanLCAEngine| engine.lci_calculator.get_inventory().values()
+            .filter(|item| item.source.contains("fertilizer") || item.source.contains("NPK"))
+            .count();
+
+        let mut engine = AfricanLCAEngine::new(methodology());
+        let mut unreported = farm_with_fertilizer_cost(200.0);
+        unreported.management_practices.as_mut().unwrap().fertilization = None;
+        engine.perform_assessment(&mut unreported).unwrap();
+        assert_eq!(fertilizer_flows(&engine), 0);
+        assert!(unreported.results.unwrap().data_quality.warnings.iter().any(|w| w.starts_with(gap)));
+
+        // "No fertilizer" is an answer, and so is an actual application
+        let mut none_used = farm_with_fertilizer_cost(200.0);
+        none_used.management_practices.as_mut().unwrap().fertilization.as_mut().unwrap().uses_fertilizers = false;
+        engine.perform_assessment(&mut none_used).unwrap();
+        assert!(none_used.results.unwrap().data_quality.warnings.iter().all(|w| !w.contains("fertilizer emissions are a data gap")));
+
+        let mut fertilized = farm_with_fertilizer_cost(200.0);
+        engine.perform_assessment(&mut fertilized).unwrap();
+        assert!(fertilizer_flows(&engine) > 0);
+        assert!(fertilized.results.unwrap().data_quality.warnings.iter().all(|w| !w.starts_with(gap)));
+    }
+
+    #[test]
+    fn each_intervention_lowers_its_target_impact() {
+        let mut baseline = farm_with_fertilizer_cost(200.0);
+        if let Some(ref mut practices) = baseline.management_practices {
+            practices.water_management.irrigation_system = Some("Flood irrigation".to_string());
+            practices.pest_management.pesticides_used.push(PesticideApplication {
+                pesticide_type: "Insecticide".to_string(),
+                active_ingredient: "Lambda-cyhalothrin".to_string(),
+                application_rate: 1.0,
+                applications_per_season: 4,
+                target_pests: vec!["Fall armyworm".to_string()],
+                treated_area_ha: None,
+            });
+        }
+
+        let scenarios = [
+            ("Drip irrigation", assessed(baseline.with_irrigation("Drip irrigation")), "Water consumption"),
+            ("IPM", assessed(baseline.with_ipm(true)), "Global warming"),
+            ("20% less fertilizer", assessed(baseline.with_fertilizer_reduction(0.2)), "Global warming"),
+            ("Compost", assessed(baseline.with_compost("Poultry litter")), "Soil degradation"),
+            ("No-till", assessed(baseline.with_conservation_practice("No-till")), "Soil degradation"),
+        ];
+        let baseline = assessed(baseline);
+
+        let named: Vec<(&str, &Assessment)> = scenarios.iter().map(|(name, a, _)| (*name, a)).collect();
+        let comparison = compare_assessments(&baseline, &named).unwrap();
+
+        for ((name, _, category), result) in scenarios.iter().zip(&comparison) {
+            assert_eq!(result.scenario_name, *name);
+            let change = result.impact_changes[*category];
+            assert!(change < 0.0, "{name}: {category} changed by {change:.1}%");
+        }
+
+        // Builders leave the baseline untouched and produce fresh, unassessed copies
+        assert_eq!(baseline.management_practices.as_ref().unwrap().pest_management.pesticides_used[0].applications_per_season, 4);
+        let plan = baseline.with_nutrient_plan();
+        assert!(plan.results.is_none() && plan.id != baseline.id);
+        assert!(plan.management_practices.unwrap().fertilization.unwrap().follows_nutrient_plan);
+    }
+
+    #[test]
+    fn scenario_changes_inside_the_uncertainty_are_not_claimed_as_improvements() {
+        let mut baseline = farm_with_fertilizer_cost(200.0);
+        baseline.management_practices.as_mut().unwrap().water_management.irrigation_system = Some("Flood irrigation".to_string());
+        let scenarios = [
+            ("No fertilizer", assessed(baseline.with_fertilizer_reduction(1.0))),
+            ("1% less fertilizer", assessed(baseline.with_fertilizer_reduction(0.01))),
+            ("Drip irrigation", assessed(baseline.with_irrigation("Drip irrigation"))),
+        ];
+        let baseline = assessed(baseline);
+        let named: Vec<(&str, &Assessment)> = scenarios.iter().map(|(name, a)| (*name, a)).collect();
+        let comparison = compare_assessments(&baseline, &named).unwrap();
+
+        // No fertilizer leaves no fertilizer emissions: clear of the baseline's range
+        let none = &comparison[0];
+        assert_eq!(none.significance["Global warming"], Significance::SignificantlyBetter);
+        assert!(none.description.starts_with("Lowers ") && none.description.contains("Global warming"), "{}", none.description);
+
+        // A 1% cut lowers global warming a little, well inside its ±20% range
+        let trim = &comparison[1];
+        assert!(trim.impact_changes["Global warming"] < -0.5, "{}", trim.impact_changes["Global warming"]);
+        assert_eq!(trim.significance["Global warming"], Significance::Indistinguishable);
+        assert_eq!(trim.description, "Lowers Freshwater eutrophication; Global warming, Marine eutrophication, \
+            Mineral depletion, Terrestrial acidification lower, but within the uncertainty");
+
+        // Drip's 3000 m3/ha, ±40%, is clear of flood irrigation's 8000
+        let drip = &comparison[2];
+        assert_eq!(drip.significance["Water consumption"], Significance::SignificantlyBetter);
+        assert!(drip.description.starts_with("Lowers Water consumption"), "{}", drip.description);
+    }
+
+    fn farm_with_grid_electricity(kwh_per_month: f64) -> Assessment {
+        Assessment {
+            equipment_energy: Some(EquipmentEnergy {
+                equipment: vec![],
+                energy_sources: vec![EnergyUsage {
+                    energy_type: "Grid Electricity".to_string(),
+                    monthly_consumption: kwh_per_month,
+                    primary_use: "Irrigation".to_string(),
+                    cost: None,
+                    currency: None,
+                }],
+                fuel_consumption: vec![FuelUsage {
+                    fuel_type: "Diesel".to_string(),
+                    monthly_consumption: 30.0,
+                    primary_use: "Land preparation".to_string(),
+                    cost: None,
+                    currency: None,
+                }],
+            }),
+            ..farm_with_fertilizer_cost(200.0)
+        }
+    }
+
+    #[test]
+    fn ghg_scopes_add_up_to_annual_global_warming() {
+        let farm = assessed(farm_with_grid_electricity(100.0));
+        let results = farm.results.unwrap();
+        let scopes = results.ghg_scopes.expect("scope breakdown");
+
+        let annual = results.midpoint_impacts["Global warming"].value * 2000.0;
+        assert!((scopes.total() - annual).abs() < 1e-9 * annual, "{} vs {}", scopes.total(), annual);
+        let sources = |scope: &ScopeTotal| scope.sources.iter().map(|s| s.source.clone()).collect::<Vec<_>>().join(" | ");
+        assert!(sources(&scopes.scope_1).contains("Diesel consumption") && sources(&scopes.scope_1).contains("Direct N2O"));
+        assert!(sources(&scopes.scope_2).contains("Grid Electricity consumption"));
+        assert!(sources(&scopes.scope_3).contains("Production and transport of NPK"));
+        assert!(scopes.scope_1.sources.iter().chain(&scopes.scope_2.sources).chain(&scopes.scope_3.sources)
+            .all(|source| !source.source.starts_with("Unattributed")));
+    }
+
+    #[test]
+    fn grid_electricity_only_moves_scope_2() {
+        let scopes = |kwh| assessed(farm_with_grid_electricity(kwh)).results.unwrap().ghg_scopes.unwrap();
+        let (low, high) = (scopes(100.0), scopes(300.0));
+
+        assert!((high.scope_1.kg_co2e - low.scope_1.kg_co2e).abs() < 1e-6);
+        assert!((high.scope_3.kg_co2e - low.scope_3.kg_co2e).abs() < 1e-6);
+        let added = 200.0 * 12.0 * EmissionFactorsDatabase::default().electricity_co2(&Country::Ghana);
+        assert!((high.scope_2.kg_co2e - low.scope_2.kg_co2e - added).abs() < 1e-6);
+    }
+
+    #[test]
+    fn reassigned_hired_equipment_fuel_moves_to_scope_3() {
+        let mut table = ScopeTable::default();
+        table.reassign("Land preparation", Scope::Scope3, "Category 1: purchased services (contracted ploughing)");
+        let mut engine = AfricanLCAEngine::new(methodology());
+        engine.load_scope_table(table);
+        let mut farm = farm_with_grid_electricity(100.0);
+        engine.perform_assessment(&mut farm).unwrap();
+
+        let scopes = farm.results.unwrap().ghg_scopes.unwrap();
+        assert!(scopes.scope_1.sources.iter().all(|source| !source.source.contains("Diesel")));
+        let diesel = scopes.scope_3.sources.iter().find(|source| source.source.contains("Diesel")).expect("diesel in scope 3");
+        assert!((diesel.kg_co2e - 30.0 * 12.0 * 2.68).abs() < 1e-9);
+    }
+
+    #[test]
+    fn carbon_footprint_components_add_up_to_global_warming_per_kg() {
+        let mut farm = farm_with_grid_electricity(100.0);
+        farm.foods[0].name = "Rice".to_string();
+        farm.foods[0].area_allocated = Some(1.0);
+        let results = assessed(farm.clone()).results.unwrap();
+
+        let gwp = results.midpoint_impacts["Global warming"].value;
+        let footprints = results.product_carbon_footprints.expect("carbon footprints");
+        assert_eq!(footprints.len(), farm.foods.len());
+        let pcf = &footprints[&farm.foods[0].id];
+        assert_eq!(pcf.declared_unit, "1 kg");
+        let sum = pcf.fossil_gwp + pcf.biogenic_gwp + pcf.land_use_change_gwp;
+        assert!((sum - gwp).abs() < 1e-9 * gwp, "{} vs {}", sum, gwp);
+        assert!(pcf.biogenic_gwp > 0.0 && pcf.fossil_gwp > 0.0, "rice CH4 is biogenic, diesel fossil");
+        assert_eq!(pcf.land_use_change_gwp, 0.0);
+        assert!(pcf.boundary_statement.starts_with("Cradle to gate"));
+    }
+
+    #[test]
+    fn per_hectare_impacts_are_totals_over_the_area() {
+        let mut farm = farm_with_fertilizer_cost(200.0);
+        farm.foods[0].area_allocated = Some(0.8);
+        farm.management_practices.as_mut().unwrap().water_management.irrigation_system = Some("Sprinkler".to_string());
+        let results = assessed(farm.clone()).results.unwrap();
+        let per_ha = results.impacts_per_ha.expect("per-hectare impacts");
+
+        assert_eq!(per_ha.len(), results.midpoint_impacts.len());
+        for (category, result) in &results.midpoint_impacts {
+            assert_eq!(result.basis, ImpactBasis::PerKgProduct, "{}", category);
+            assert!(result.unit.ends_with(" per kg"), "{}: {}", category, result.unit);
+            let total = result.value * 2000.0;
+            assert!((per_ha[category].value - total / 0.8).abs() <= 1e-9 * total.abs(), "{}", category);
+            assert_eq!(per_ha[category].basis, ImpactBasis::PerHectare);
+            assert_eq!(per_ha[category].unit, result.unit.replace(" per kg", " per ha"));
+        }
+
+        // A hectare occupied for a year, and the sprinkler's 5000 m3 at Ghana's AWARE factor of 20
+        assert!((per_ha["Land use"].value - 10_000.0).abs() < 1e-6);
+        assert!((per_ha["Water consumption"].value - 5000.0).abs() < 1e-6);
+        assert!((per_ha["Water scarcity"].value - 5000.0 * 20.0).abs() < 1e-6);
+
+        // A food without an area leaves the per-hectare view out
+        farm.foods.push(FoodItem { id: "2".to_string(), area_allocated: None, ..farm.foods[0].clone() });
+        assert!(assessed(farm).results.unwrap().impacts_per_ha.is_none());
+    }
+
+    #[test]
+    fn global_warming_over_its_target_is_flagged() {
+        let mut farm = assessed(farm_with_fertilizer_cost(200.0));
+        let per_kg = farm.midpoint_impacts_per_kg().unwrap();
+        let gwp = per_kg["Global warming"].0;
+        let targets = HashMap::from([
+            ("Global warming".to_string(), gwp / 2.0),
+            ("Water consumption".to_string(), per_kg["Water consumption"].0 + 1.0),
+            ("Ozone depletion".to_string(), 1.0), // not assessed
+        ]);
+        farm.check_targets(&targets).unwrap();
+
+        let compliance = farm.results.unwrap().target_compliance.unwrap();
+        let global_warming = &compliance["Global warming"];
+        assert!(!global_warming.meets_target);
+        assert!((global_warming.gap - gwp / 2.0).abs() < 1e-12);
+        assert_eq!(global_warming.unit, "kg CO2-eq per kg");
+        assert!(compliance["Water consumption"].meets_target);
+        assert!(!compliance.contains_key("Ozone depletion") && !compliance.contains_key("Land use"));
+    }
+}
@@ -1,4 +1,4 @@
-/**
+/*!
  * Life Cycle Inventory (LCI) Module - ISO 14040/14044 Compliant
  *
  * This module implements proper LCI calculations by computing environmental
@@ -25,13 +25,58 @@
  */
 
 use crate::models::*;
-use std::collections::HashMap;
+use crate::error::EngineError;
+use crate::units::{Area, Energy, Mass};
+use crate::production::data::typical_yield;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
 use log::{info, warn};
 
+/// N2O has 273x the warming potential of CO2 over 100 years (IPCC AR6)
+pub const GWP100_N2O: f64 = 273.0;
+/// CH4 has 28x the warming potential of CO2 over 100 years (fossil, AR6)
+pub const GWP100_CH4: f64 = 28.0;
+
+/// NH3 acidifies 1.88x as much as SO2 (ReCiPe midpoint, kg SO2-eq per kg NH3)
+pub const ACIDIFICATION_NH3: f64 = 1.88;
+
+/// Lower heating values (IPCC 2006 Vol 2 Table 1.2 at typical densities), MJ per litre
+pub const DIESEL_MJ_PER_L: f64 = 36.0;
+pub const PETROL_MJ_PER_L: f64 = 32.0;
+
+/// kg CO2-eq per kg of an inventory substance, as characterized in calculate_midpoint_impacts
+fn gwp_factor(substance: &str) -> Option<f64> {
+    match substance {
+        "Carbon dioxide (CO2)" | "Carbon dioxide (CO2) equivalent" => Some(1.0),
+        "Methane (CH4)" => Some(GWP100_CH4),
+        s if s.contains("N2O") => Some(GWP100_N2O),
+        _ => None,
+    }
+}
+
 // ======================================================================
 // EMISSION FACTORS DATABASE - Based on Scientific Literature
 // ======================================================================
 
+/// Embodied manufacturing emissions (kg CO2-eq per unit) and expected lifetime (years) of
+/// farm equipment, by the equipment types offered in the assessment form.
+/// Source: Ecoinvent 3.8 agricultural machinery (~4 kg CO2-eq per kg of machine), typical masses
+pub fn embodied_equipment_emissions(equipment_type: &str) -> (f64, f64) {
+    match equipment_type {
+        "Hand Tools" => (15.0, 5.0),
+        "Animal-drawn implements" => (150.0, 10.0),
+        "Small Tractor (<40HP)" => (8000.0, 12.0),
+        "Medium Tractor (40-100HP)" => (16000.0, 12.0),
+        "Large Tractor (>100HP)" => (32000.0, 15.0),
+        "Combine Harvester" => (48000.0, 15.0),
+        "Thresher" => (3000.0, 10.0),
+        "Planter/Seeder" => (4000.0, 12.0),
+        "Sprayer" => (600.0, 8.0),
+        "Water Pump" => (400.0, 8.0),
+        _ => (1000.0, 10.0), // Conservative default for unlisted equipment
+    }
+}
+
 /// Emission factors for agricultural inputs and activities
 /// All factors are peer-reviewed and cited
 pub struct EmissionFactorsDatabase {
@@ -70,6 +115,15 @@ pub struct EmissionFactorsDatabase {
     /// Water consumption factor for irrigation (no emission, but resource use)
     pub water_use_factor: f64, // Typically 1:1 for direct use
 
+    /// Seasonal crop water requirement (evapotranspiration, m3 per ha). Rain supplies what
+    /// irrigation does not (green water).
+    /// Source: FAO Irrigation and Drainage Paper 56; 450 mm for a West African cereal season
+    pub crop_water_requirement: f64,
+
+    /// Nitrogen concentration the receiving water may reach (kg N per m3), for grey water.
+    /// Source: Water Footprint Assessment Manual (Hoekstra et al. 2011), 10 mg N/L
+    pub grey_water_n_standard: f64,
+
     /// CH4 emissions from rice paddies (kg CH4 per ha per season)
     /// Source: IPCC 2019, Chapter 5
     pub ch4_from_rice_paddies: EmissionFactor,
@@ -85,6 +139,28 @@ pub struct EmissionFactorsDatabase {
     pub ef4_volatilisation: f64,
     /// EF5: kg N2O-N per kg N lost to leaching/runoff.
     pub ef5_leaching: f64,
+
+    /// Aquafeed production and delivery (kg CO2-eq per kg feed): commercial pellets,
+    /// farm-made feed and high-fishmeal feed
+    /// Source: Pelletier & Tyedmers 2010 (tilapia feeds); Henriksson et al. 2017
+    pub co2_from_commercial_aquafeed: EmissionFactor,
+    pub co2_from_farm_made_aquafeed: EmissionFactor,
+    pub co2_from_fishmeal_aquafeed: EmissionFactor,
+
+    /// CH4 from the sediment of freshwater fish ponds (kg CH4 per ha per year)
+    /// Source: IPCC 2013 Wetlands Supplement, Ch 4
+    pub ch4_from_aquaculture_ponds: EmissionFactor,
+
+    /// N2O from fish farming (kg N2O-N per kg fish produced)
+    /// Source: IPCC 2013 Wetlands Supplement, Ch 4
+    pub n2o_from_aquaculture: EmissionFactor,
+
+    /// Electricity to pump one m3 of exchange water (kWh), ~5 m head at 50% pump efficiency
+    pub aquaculture_pumping_kwh_per_m3: f64,
+
+    /// Raising one tree seedling in a nursery (kg CO2-eq): polybag, substrate, watering
+    /// Source: Ntiamoah & Afrane 2008 (Ghana cocoa); Perez Neira 2016
+    pub co2_per_nursery_seedling: EmissionFactor,
 }
 
 #[derive(Debug, Clone)]
@@ -189,6 +265,8 @@ impl Default for EmissionFactorsDatabase {
 
             // Water use: Direct measurement
             water_use_factor: 1.0,
+            crop_water_requirement: 4500.0,
+            grey_water_n_standard: 0.010,
 
             // Methane from rice paddies: 200 kg CH4 per ha per season (continuously flooded)
             // Source: IPCC 2019, Chapter 5
@@ -206,6 +284,107 @@ impl Default for EmissionFactorsDatabase {
             frac_leach: 0.24,         // fraction of applied N leached/run off (wet climate)
             ef4_volatilisation: 0.010, // kg N2O-N per kg N volatilised
             ef5_leaching: 0.011,      // kg N2O-N per kg N leached
+
+            // Soybean meal, maize and fishmeal, extruded; lower with local by-products,
+            // higher with fishmeal reduction and fishing fuel
+            co2_from_commercial_aquafeed: EmissionFactor {
+                value: 1.5,
+                unit: "kg CO2-eq per kg feed".to_string(),
+                source: "Pelletier & Tyedmers 2010; Henriksson et al. 2017".to_string(),
+                year: 2017,
+                uncertainty: 30.0,
+                geographical_validity: "Global, tropical tilapia and catfish feeds".to_string(),
+            },
+            co2_from_farm_made_aquafeed: EmissionFactor {
+                value: 0.75,
+                unit: "kg CO2-eq per kg feed".to_string(),
+                source: "Henriksson et al. 2017, farm-made feeds".to_string(),
+                year: 2017,
+                uncertainty: 40.0,
+                geographical_validity: "Smallholder feeds of bran, maize and oilseed cake".to_string(),
+            },
+            co2_from_fishmeal_aquafeed: EmissionFactor {
+                value: 2.4,
+                unit: "kg CO2-eq per kg feed".to_string(),
+                source: "Pelletier & Tyedmers 2010; Henriksson et al. 2017".to_string(),
+                year: 2017,
+                uncertainty: 35.0,
+                geographical_validity: "High-protein catfish and starter feeds".to_string(),
+            },
+
+            // Methane from pond sediment: 183 kg CH4 per ha per year
+            ch4_from_aquaculture_ponds: EmissionFactor {
+                value: 183.0,
+                unit: "kg CH4 per ha per year".to_string(),
+                source: "IPCC 2013 Wetlands Supplement, Ch 4".to_string(),
+                year: 2013,
+                uncertainty: 90.0, // varies with depth, organic loading and drainage
+                geographical_validity: "Freshwater aquaculture ponds".to_string(),
+            },
+
+            // Nitrous oxide: 0.00169 kg N2O-N per kg fish produced
+            n2o_from_aquaculture: EmissionFactor {
+                value: 0.00169,
+                unit: "kg N2O-N per kg fish".to_string(),
+                source: "IPCC 2013 Wetlands Supplement, Ch 4".to_string(),
+                year: 2013,
+                uncertainty: 90.0,
+                geographical_validity: "Global default".to_string(),
+            },
+
+            aquaculture_pumping_kwh_per_m3: 0.03,
+
+            co2_per_nursery_seedling: EmissionFactor {
+                value: 0.1,
+                unit: "kg CO2-eq per seedling".to_string(),
+                source: "Ntiamoah & Afrane 2008; Perez Neira 2016".to_string(),
+                year: 2016,
+                uncertainty: 60.0,
+                geographical_validity: "West African cocoa and tree crop nurseries".to_string(),
+            },
+        }
+    }
+}
+
+impl EmissionFactorsDatabase {
+    /// CO2 from producing and transporting one kg of a fertilizer
+    pub fn fertilizer_production_co2(&self, fertilizer_type: &str) -> f64 {
+        match fertilizer_type {
+            "Urea" => self.co2_from_urea_production.value,
+            "NPK Compound" | "NPK" => self.co2_from_npk_production.value,
+            _ => 1.0, // Generic factor
+        }
+    }
+
+    /// Share of a fertilizer's N volatilised as NH3 (IPCC 2019 Refinement, Vol 4, Ch 11,
+    /// Table 11.3, disaggregated FracGASF): urea far more than nitrate-based fertilizers.
+    /// Indirect N2O keeps the aggregated `frac_gasf`.
+    pub fn nh3_volatilisation_fraction(&self, fertilizer_type: &str) -> f64 {
+        match fertilizer_type {
+            "Urea" => 0.15,
+            "Ammonium Sulfate" | "Diammonium Phosphate (DAP)" | "DAP" => 0.08,
+            "Calcium Ammonium Nitrate" | "CAN" | "Ammonium Nitrate" => 0.05,
+            "Calcium Nitrate" | "Potassium Nitrate" => 0.01,
+            other if ["manure", "compost", "organic"].iter().any(|o| other.to_lowercase().contains(o)) => 0.21, // FracGASM
+            _ => self.frac_gasf,
+        }
+    }
+
+    /// CO2-eq from producing and delivering one kg of fish feed
+    pub fn aquafeed_co2(&self, feed_type: &AquaFeedType) -> f64 {
+        match feed_type {
+            AquaFeedType::CommercialPellets => self.co2_from_commercial_aquafeed.value,
+            AquaFeedType::FarmMade => self.co2_from_farm_made_aquafeed.value,
+            AquaFeedType::HighFishmeal => self.co2_from_fishmeal_aquafeed.value,
+        }
+    }
+
+    /// CO2 from one kWh of grid electricity
+    pub fn electricity_co2(&self, country: &Country) -> f64 {
+        match country {
+            Country::Ghana => self.co2_from_electricity_ghana.value,
+            Country::Nigeria => self.co2_from_electricity_nigeria.value,
+            _ => 0.50, // Global average
         }
     }
 }
@@ -215,8 +394,33 @@ impl Default for EmissionFactorsDatabase {
 // ======================================================================
 
 pub struct LCICalculator {
-    emission_factors: EmissionFactorsDatabase,
-    inventory: HashMap<String, InventoryItem>,
+    emission_factors: Arc<EmissionFactorsDatabase>, // shared by fresh() calculators
+    inventory: BTreeMap<String, InventoryItem>,
+    activities: Vec<InventoryItem>, // items as added, before merging by substance
+    data_gaps: Vec<String>, // inputs the last inventory could not account for
+    captured_effects: BTreeSet<PracticeEffect>, // practice effects the last inventory accounts for
+}
+
+/// A management practice effect the category-factor breakdown would otherwise apply as a
+/// multiplier. An inventory that already models the effect records it, so the effect is
+/// counted once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PracticeEffect {
+    SoilConservation, // conservation practices and compost, in Soil degradation
+    FertilizerN2O, // soil testing and nutrient planning, through the N applied
+    NutrientRunoff, // nutrient planning, through the N leached
+    IrrigationEfficiency, // the irrigation system, through the water applied
+}
+
+impl PracticeEffect {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PracticeEffect::SoilConservation => "Soil conservation effect",
+            PracticeEffect::FertilizerN2O => "Nutrient plan N2O effect",
+            PracticeEffect::NutrientRunoff => "Nutrient plan runoff effect",
+            PracticeEffect::IrrigationEfficiency => "Irrigation efficiency effect",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -236,11 +440,31 @@ pub enum EnvironmentalCompartment {
     Resource, // For resource extraction/use
 }
 
+impl Default for LCICalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LCICalculator {
     pub fn new() -> Self {
         Self {
-            emission_factors: EmissionFactorsDatabase::default(),
-            inventory: HashMap::new(),
+            emission_factors: Arc::new(EmissionFactorsDatabase::default()),
+            inventory: BTreeMap::new(),
+            activities: Vec::new(),
+            data_gaps: Vec::new(),
+            captured_effects: BTreeSet::new(),
+        }
+    }
+
+    /// Calculator with the same emission factors and an empty inventory
+    pub fn fresh(&self) -> Self {
+        Self {
+            emission_factors: Arc::clone(&self.emission_factors),
+            inventory: BTreeMap::new(),
+            activities: Vec::new(),
+            data_gaps: Vec::new(),
+            captured_effects: BTreeSet::new(),
         }
     }
 
@@ -248,16 +472,26 @@ impl LCICalculator {
     pub fn calculate_inventory(
         &mut self,
         assessment: &Assessment,
-    ) -> Result<HashMap<String, InventoryItem>, Box<dyn std::error::Error>> {
+    ) -> Result<BTreeMap<String, InventoryItem>, EngineError> {
 
         info!("Starting LCI calculation for assessment {}", assessment.id);
 
         // Clear previous inventory
         self.inventory.clear();
+        self.activities.clear();
+        self.data_gaps.clear();
+        self.captured_effects.clear();
 
         // 1. Calculate emissions from fertilizers (N2O, production CO2)
         if let Some(ref mgmt) = assessment.management_practices {
-            self.calculate_fertilizer_emissions(&mgmt.fertilization, &assessment.foods)?;
+            // Extended characterization derives Soil degradation from the same practices
+            self.captured_effects.insert(PracticeEffect::SoilConservation);
+            match &mgmt.fertilization {
+                Some(fertilization) => self.calculate_fertilizer_emissions(fertilization, &assessment.foods)?,
+                None => self.data_gaps.push(
+                    "Fertilization not reported - fertilizer emissions are a data gap, not zero".to_string()
+                ),
+            }
         }
 
         // 2. Calculate emissions from fuel consumption (CO2)
@@ -265,19 +499,59 @@ impl LCICalculator {
         // For now, we'll use a simplified approach based on farm size
         self.calculate_energy_emissions(assessment)?;
 
+        // 2b. Amortized equipment manufacturing, only when the methodology includes capital goods
+        if assessment.methodology.include_capital_goods {
+            if let Some(ref equipment_energy) = assessment.equipment_energy {
+                self.calculate_capital_goods_emissions(&equipment_energy.equipment, &assessment.foods);
+            }
+        }
+
         // 3. Calculate emissions from pesticide production
         if let Some(ref mgmt) = assessment.management_practices {
-            self.calculate_pesticide_emissions(&mgmt.pest_management)?;
+            self.calculate_pesticide_emissions(&mgmt.pest_management, &assessment.foods)?;
         }
 
-        // 4. Calculate water consumption
+        // 4. Calculate water consumption and, unless metered energy covers it, pumping
         if let Some(ref mgmt) = assessment.management_practices {
             self.calculate_water_consumption(&mgmt.water_management, &assessment.foods)?;
+            if assessment.equipment_energy.is_none() {
+                for item in self.calculate_irrigation_pumping(&mgmt.water_management, &assessment.foods, &assessment.country) {
+                    self.add_inventory_item(item);
+                }
+            }
         }
 
         // 5. Calculate crop-specific emissions (e.g., CH4 from rice)
         self.calculate_crop_specific_emissions(&assessment.foods)?;
 
+        // 5b. Calculate aquaculture N and P discharge from feed
+        self.calculate_aquaculture_discharge(&assessment.foods)?;
+
+        // 5c. Calculate poultry and aquaculture feed, manure, pond and housing emissions
+        for food in &assessment.foods {
+            let include_housing_energy = assessment.equipment_energy.is_none();
+            for item in self.calculate_poultry_inventory(food, &assessment.country, include_housing_energy) {
+                self.add_inventory_item(item);
+            }
+            for item in self.calculate_aquaculture_inventory(food, &assessment.country, include_housing_energy) {
+                self.add_inventory_item(item);
+            }
+        }
+
+        // 5d. Calculate dairy enteric, manure and feed emissions
+        for food in &assessment.foods {
+            for item in self.calculate_dairy_inventory(food) {
+                self.add_inventory_item(item);
+            }
+        }
+
+        // 5e. Amortize the establishment of tree crop plantations
+        for food in &assessment.foods {
+            for item in self.calculate_perennial_inventory(food) {
+                self.add_inventory_item(item);
+            }
+        }
+
         // 6. Calculate land use
         self.calculate_land_use(&assessment.foods)?;
 
@@ -288,39 +562,81 @@ impl LCICalculator {
 
     /// Calculate N2O and CO2 emissions from fertilizer use
     /// Following IPCC 2019 methodology
+    /// Area of all crops (ha). Crops without a reported area are taken at the area their
+    /// quantity needs at a typical yield, and the estimate is recorded as a data gap; without
+    /// it, fertilizer spread per hectare would silently vanish from the results.
+    fn cultivated_area_ha(&mut self, foods: &[FoodItem]) -> f64 {
+        let mut total_area_ha = 0.0;
+        for food in foods {
+            if let Some(area_ha) = food.area_allocated.filter(|area| *area > 0.0) {
+                total_area_ha += area_ha;
+            } else if let Some(yield_t_per_ha) = typical_yield(food).filter(|_| food.quantity_kg > 0.0) {
+                let area_ha = Mass::kg(food.quantity_kg).in_tonnes() / yield_t_per_ha;
+                self.data_gaps.push(format!(
+                    "Area of {} not reported - estimated at {:.2} ha from a typical yield of {} t/ha",
+                    food.name, area_ha, yield_t_per_ha
+                ));
+                total_area_ha += area_ha;
+            }
+        }
+        total_area_ha
+    }
+
     fn calculate_fertilizer_emissions(
         &mut self,
         fertilization: &FertilizationPractices,
         foods: &[FoodItem],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), EngineError> {
 
         if !fertilization.uses_fertilizers {
             return Ok(());
         }
-
-        // Calculate total area under cultivation
-        let total_area_ha: f64 = foods.iter()
-            .filter_map(|f| f.area_allocated)
-            .sum();
-
-        if total_area_ha == 0.0 {
-            warn!("No area allocated for crops, cannot calculate fertilizer emissions accurately");
+        if fertilization.fertilizer_applications.is_empty() {
+            self.data_gaps.push(
+                "Fertilizer use reported without any applications - fertilizer emissions are a data gap, not zero".to_string()
+            );
             return Ok(());
         }
 
+        // Calculate total area under cultivation, when an application doesn't give its own
+        let total_area_ha = if fertilization.fertilizer_applications.iter().any(|app| app.treated_area_ha.is_none()) {
+            self.cultivated_area_ha(foods)
+        } else {
+            0.0
+        };
+
         for app in &fertilization.fertilizer_applications {
+            // Use the treated area when given; otherwise assume the whole cultivated area
+            let area_ha = match app.treated_area_ha {
+                Some(treated) => treated,
+                None => {
+                    info!("No treated area for {} - assuming full cultivated area (reduced precision)",
+                          app.fertilizer_type);
+                    total_area_ha
+                }
+            };
+
+            if area_ha == 0.0 {
+                warn!("No area allocated for crops, cannot calculate fertilizer emissions accurately");
+                self.data_gaps.push(format!(
+                    "No cultivated area for {} - its fertilizer emissions are a data gap, not zero",
+                    app.fertilizer_type
+                ));
+                continue;
+            }
+
             // Calculate total N applied
-            let n_content = self.get_nitrogen_content(&app.fertilizer_type, &app.npk_ratio);
+            let n_content = nitrogen_content(&app.fertilizer_type, &app.npk_ratio);
 
             // Total N applied (kg N)
             // application_rate is in kg fertilizer/ha/season
             // n_content is fraction of N in the fertilizer
             let n_applied_per_ha = app.application_rate * n_content;
             let applications_per_year = app.applications_per_season as f64;
-            let total_n_applied = n_applied_per_ha * total_area_ha * applications_per_year;
+            let total_n_applied = n_applied_per_ha * area_ha * applications_per_year;
 
             info!("Fertilizer application: {} kg/ha/season × {} ha × {} times = {} kg N/year",
-                  n_applied_per_ha, total_area_ha, applications_per_year, total_n_applied);
+                  n_applied_per_ha, area_ha, applications_per_year, total_n_applied);
 
             // IPCC 2019 Equation 11.1: Direct N2O emissions
             // N2O-N = N_applied × EF1
@@ -330,6 +646,10 @@ impl LCICalculator {
             // Convert N2O-N to N2O (molecular weight ratio: 44/28)
             let n2o_direct = n2o_n_direct * (44.0 / 28.0);
 
+            // The N applied reflects any soil testing and nutrient planning
+            self.captured_effects.insert(PracticeEffect::FertilizerN2O);
+            self.captured_effects.insert(PracticeEffect::NutrientRunoff);
+
             // Add to inventory
             self.add_inventory_item(InventoryItem {
                 substance: "Dinitrogen monoxide (N2O)".to_string(),
@@ -340,13 +660,8 @@ impl LCICalculator {
             });
 
             // CO2 from fertilizer production
-            let production_co2 = match app.fertilizer_type.as_str() {
-                "Urea" => app.application_rate * total_area_ha * applications_per_year
-                         * self.emission_factors.co2_from_urea_production.value,
-                "NPK Compound" | "NPK" => app.application_rate * total_area_ha * applications_per_year
-                                         * self.emission_factors.co2_from_npk_production.value,
-                _ => app.application_rate * total_area_ha * applications_per_year * 1.0, // Generic factor
-            };
+            let production_co2 = app.application_rate * area_ha * applications_per_year
+                * self.emission_factors.fertilizer_production_co2(&app.fertilizer_type);
 
             self.add_inventory_item(InventoryItem {
                 substance: "Carbon dioxide (CO2)".to_string(),
@@ -359,7 +674,7 @@ impl LCICalculator {
             // Mineral depletion from fertilizer production
             // NPK requires phosphate rock (P) and potash (K) mining
             // Urea requires natural gas (but that's fossil depletion, not mineral)
-            let total_fertilizer_kg = app.application_rate * total_area_ha * applications_per_year;
+            let total_fertilizer_kg = app.application_rate * area_ha * applications_per_year;
 
             match app.fertilizer_type.as_str() {
                 "NPK Compound" | "NPK" => {
@@ -428,6 +743,16 @@ impl LCICalculator {
                 source: format!("Indirect N2O emissions from {} (volatilisation + leaching)", app.fertilizer_type),
             });
 
+            // NH3 volatilised from the fertilizer, by its form of N
+            let nh3 = total_n_applied * self.emission_factors.nh3_volatilisation_fraction(&app.fertilizer_type) * (17.0 / 14.0);
+            self.add_inventory_item(InventoryItem {
+                substance: "Ammonia (NH3)".to_string(),
+                quantity: nh3,
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Air,
+                source: format!("NH3 volatilisation from {} application", app.fertilizer_type),
+            });
+
             // Nitrate leaching to water: ONLY the leached fraction becomes nitrate. The
             // volatilised N leaves to air as NH3/NOx and must not also be counted as nitrate
             // (the old code reused a single 20% loss for both, breaking the N mass balance).
@@ -439,38 +764,25 @@ impl LCICalculator {
                 compartment: EnvironmentalCompartment::Water,
                 source: format!("Nitrate leaching from {} application", app.fertilizer_type),
             });
+
+            // Grey water: the volume that dilutes the leached N to the water quality standard
+            self.add_inventory_item(InventoryItem {
+                substance: "Water, grey".to_string(),
+                quantity: n_leached / self.emission_factors.grey_water_n_standard,
+                unit: "m3".to_string(),
+                compartment: EnvironmentalCompartment::Resource,
+                source: format!("Grey water diluting nitrate from {} application", app.fertilizer_type),
+            });
         }
 
         Ok(())
     }
 
-    /// Get nitrogen content of fertilizer
-    fn get_nitrogen_content(&self, fertilizer_type: &str, npk_ratio: &Option<String>) -> f64 {
-        match fertilizer_type {
-            "Urea" => 0.46, // 46% N
-            "Diammonium Phosphate (DAP)" | "DAP" => 0.18, // 18% N
-            "NPK Compound" | "NPK" => {
-                // Parse NPK ratio if provided (e.g., "15-15-15")
-                if let Some(ratio) = npk_ratio {
-                    if let Some(n_str) = ratio.split('-').next() {
-                        if let Ok(n_percent) = n_str.trim().parse::<f64>() {
-                            return n_percent / 100.0;
-                        }
-                    }
-                }
-                0.15 // Default 15% N for NPK
-            }
-            "Ammonium Sulfate" => 0.21, // 21% N
-            "Calcium Ammonium Nitrate" | "CAN" => 0.27, // 27% N
-            _ => 0.10, // Conservative default
-        }
-    }
-
     /// Calculate CO2 emissions from energy use
     fn calculate_energy_emissions(
         &mut self,
         assessment: &Assessment,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), EngineError> {
 
         // Check if we have actual equipment/energy data
         if let Some(ref equipment_energy) = assessment.equipment_energy {
@@ -495,8 +807,20 @@ impl LCICalculator {
                     quantity: co2_from_fuel,
                     unit: "kg".to_string(),
                     compartment: EnvironmentalCompartment::Air,
-                    source: format!("{} consumption: {} L/month ({:.1} L/year)",
-                                   fuel.fuel_type, fuel.monthly_consumption, annual_consumption_l),
+                    source: format!("{} consumption: {} L/month ({:.1} L/year) for {}",
+                                   fuel.fuel_type, fuel.monthly_consumption, annual_consumption_l, fuel.primary_use),
+                });
+
+                let (substance, mj_per_l) = match fuel.fuel_type.as_str() {
+                    "Petrol" | "Petrol/Gasoline" | "Gasoline" => ("Energy, petrol", PETROL_MJ_PER_L),
+                    _ => ("Energy, diesel", DIESEL_MJ_PER_L), // other fuels are costed as diesel, as for CO2
+                };
+                self.add_inventory_item(InventoryItem {
+                    substance: substance.to_string(),
+                    quantity: annual_consumption_l * mj_per_l,
+                    unit: "MJ".to_string(),
+                    compartment: EnvironmentalCompartment::Resource,
+                    source: format!("{} for {}: {:.0} L × {} MJ/L", fuel.fuel_type, fuel.primary_use, annual_consumption_l, mj_per_l),
                 });
             }
 
@@ -505,11 +829,7 @@ impl LCICalculator {
                 if energy.energy_type.contains("Electricity") || energy.energy_type.contains("Grid") {
                     let annual_consumption_kwh = energy.monthly_consumption * 12.0;
 
-                    let electricity_ef = match assessment.country {
-                        Country::Ghana => self.emission_factors.co2_from_electricity_ghana.value,
-                        Country::Nigeria => self.emission_factors.co2_from_electricity_nigeria.value,
-                        _ => 0.50, // Global average
-                    };
+                    let electricity_ef = self.emission_factors.electricity_co2(&assessment.country);
 
                     let co2_from_electricity = annual_consumption_kwh * electricity_ef;
 
@@ -522,6 +842,10 @@ impl LCICalculator {
                                        energy.energy_type, energy.monthly_consumption,
                                        annual_consumption_kwh, energy.primary_use),
                     });
+                    self.add_inventory_item(electricity_use(
+                        annual_consumption_kwh,
+                        format!("{} for {}: {:.0} kWh", energy.energy_type, energy.primary_use, annual_consumption_kwh),
+                    ));
                 }
             }
 
@@ -537,24 +861,68 @@ impl LCICalculator {
             "No equipment/energy data on assessment; skipping Rust energy fallback \
              (Python activity defaults own screening estimates when applicable)."
         );
+        self.data_gaps.push(
+            "Equipment and energy use not reported - energy consumption is a data gap, not zero".to_string()
+        );
 
         Ok(())
     }
 
+    /// Embodied emissions of equipment manufacturing, amortized straight-line over the expected
+    /// lifetime and allocated to the year's output. Equipment older than its lifetime is written off.
+    fn calculate_capital_goods_emissions(&mut self, equipment: &[FarmEquipment], foods: &[FoodItem]) {
+        let output_kg: f64 = foods.iter().map(|f| f.quantity_kg).sum();
+
+        for item in equipment {
+            let (embodied_co2_eq, lifetime_years) = embodied_equipment_emissions(&item.equipment_type);
+            if f64::from(item.age) >= lifetime_years {
+                info!("{} is {} years old - fully amortized, no capital goods burden", item.equipment_type, item.age);
+                continue;
+            }
+
+            let annual_co2_eq = embodied_co2_eq / lifetime_years;
+            let per_kg = if output_kg > 0.0 { annual_co2_eq / output_kg } else { 0.0 };
+
+            self.add_inventory_item(InventoryItem {
+                substance: "Carbon dioxide (CO2) equivalent".to_string(),
+                quantity: annual_co2_eq,
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Air,
+                source: format!("Capital goods: {} manufacturing ({:.0} kg CO2-eq over {:.0} years, {:.4} kg CO2-eq/kg output)",
+                               item.equipment_type, embodied_co2_eq, lifetime_years, per_kg),
+            });
+        }
+    }
+
     /// Calculate emissions from pesticide production
     fn calculate_pesticide_emissions(
         &mut self,
         pest_management: &PestManagement,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        foods: &[FoodItem],
+    ) -> Result<(), EngineError> {
+
+        let total_area_ha: f64 = foods.iter()
+            .filter_map(|f| f.area_allocated)
+            .sum();
 
         for pesticide in &pest_management.pesticides_used {
-            // Calculate total active ingredient applied
-            // application_rate is typically in kg or L per ha
-            // We need to estimate total area treated
+            // application_rate is in kg or L active ingredient per ha
+            let (treated_area_ha, precision_note) = match pesticide.treated_area_ha {
+                Some(treated) => (treated, ""),
+                None if total_area_ha > 0.0 => {
+                    warn!("No treated area for {} - assuming all {} ha cultivated (reduced precision)",
+                          pesticide.pesticide_type, total_area_ha);
+                    (total_area_ha, " - full-area assumption")
+                }
+                None => {
+                    warn!("No treated or cultivated area for {} - using per-hectare rate", pesticide.pesticide_type);
+                    (1.0, " - per-hectare rate, area unknown")
+                }
+            };
 
-            // For now, assume pesticide is applied to all crop area
-            // TODO: Get actual treated area from user input
-            let total_ai_kg = pesticide.application_rate * pesticide.applications_per_season as f64;
+            let total_ai_kg = pesticide.application_rate
+                * pesticide.applications_per_season as f64
+                * treated_area_ha;
 
             let production_impact = total_ai_kg * self.emission_factors.pesticide_production_impact.value;
 
@@ -563,8 +931,8 @@ impl LCICalculator {
                 quantity: production_impact,
                 unit: "kg CO2-eq".to_string(),
                 compartment: EnvironmentalCompartment::Air,
-                source: format!("Production of {} pesticide ({})",
-                               pesticide.pesticide_type, pesticide.active_ingredient),
+                source: format!("Production of {} pesticide ({}){}",
+                               pesticide.pesticide_type, pesticide.active_ingredient, precision_note),
             });
         }
 
@@ -576,23 +944,28 @@ impl LCICalculator {
         &mut self,
         water_management: &WaterManagement,
         foods: &[FoodItem],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), EngineError> {
 
         // Get total area
         let total_area_ha: f64 = foods.iter()
             .filter_map(|f| f.area_allocated)
             .sum();
 
-        // Estimate irrigation water use based on system type
-        let water_use_m3_per_ha = match water_management.irrigation_system.as_deref() {
-            Some("Drip irrigation") | Some("Drip Irrigation") => 3000.0, // Efficient
-            Some("Sprinkler") | Some("Sprinkler System") => 5000.0, // Moderate
-            Some("Flood irrigation") | Some("Furrow irrigation") => 8000.0, // Inefficient
-            None | Some("None (Rainfed)") => 0.0, // Rainfed
-            _ => 4000.0, // Default moderate use
-        };
-
+        let water_use_m3_per_ha = irrigation_m3_per_ha(water_management);
         let total_water_m3 = water_use_m3_per_ha * total_area_ha;
+        self.captured_effects.insert(PracticeEffect::IrrigationEfficiency);
+
+        // Rain covers the crop's water requirement where irrigation does not
+        let green_water_m3 = (self.emission_factors.crop_water_requirement - water_use_m3_per_ha).max(0.0) * total_area_ha;
+        if green_water_m3 > 0.0 {
+            self.add_inventory_item(InventoryItem {
+                substance: "Water, green".to_string(),
+                quantity: green_water_m3,
+                unit: "m3".to_string(),
+                compartment: EnvironmentalCompartment::Resource,
+                source: format!("Rainwater evapotranspired by crops ({} ha)", total_area_ha),
+            });
+        }
 
         if total_water_m3 > 0.0 {
             self.add_inventory_item(InventoryItem {
@@ -608,11 +981,69 @@ impl LCICalculator {
         Ok(())
     }
 
+    /// Fuel or grid electricity to lift the irrigation water from its source and pressurize
+    /// it: volume × (lift + operating head) × ρg, over the pump set's efficiency. Empty for
+    /// rainfed farms, gravity-fed water and farms that did not say how they pump; solar pumps
+    /// have no operating emissions.
+    pub fn calculate_irrigation_pumping(
+        &self,
+        water_management: &WaterManagement,
+        foods: &[FoodItem],
+        country: &Country,
+    ) -> Vec<InventoryItem> {
+        let Some(pump) = water_management.pump_type else { return Vec::new() };
+        let total_area_ha: f64 = foods.iter()
+            .filter_map(|f| f.area_allocated)
+            .sum();
+        let water_m3 = irrigation_m3_per_ha(water_management) * total_area_ha;
+        let head_m = water_management.lift_height_m() + water_management.operating_head_m();
+        // 1000 kg/m3 × 9.81 m/s2 × head, J to kWh
+        let hydraulic_kwh = water_m3 * 1000.0 * 9.81 * head_m / 3.6e6;
+        let input_kwh = hydraulic_kwh / pump.efficiency();
+        if input_kwh <= 0.0 {
+            return Vec::new();
+        }
+        let label = format!("{:.0} m3 lifted {:.0} m", water_m3, head_m);
+
+        match pump {
+            PumpType::Diesel => {
+                let diesel_l = Energy::kwh(input_kwh).in_mj() / DIESEL_MJ_PER_L;
+                vec![
+                    InventoryItem {
+                        substance: "Carbon dioxide (CO2)".to_string(),
+                        quantity: diesel_l * self.emission_factors.co2_from_diesel.value,
+                        unit: "kg".to_string(),
+                        compartment: EnvironmentalCompartment::Air,
+                        source: format!("Diesel irrigation pump: {:.0} L/year for {}", diesel_l, label),
+                    },
+                    InventoryItem {
+                        substance: "Energy, diesel".to_string(),
+                        quantity: diesel_l * DIESEL_MJ_PER_L,
+                        unit: "MJ".to_string(),
+                        compartment: EnvironmentalCompartment::Resource,
+                        source: format!("Diesel irrigation pump: {:.0} L × {} MJ/L", diesel_l, DIESEL_MJ_PER_L),
+                    },
+                ]
+            }
+            PumpType::Electric => vec![
+                InventoryItem {
+                    substance: "Carbon dioxide (CO2)".to_string(),
+                    quantity: input_kwh * self.emission_factors.electricity_co2(country),
+                    unit: "kg".to_string(),
+                    compartment: EnvironmentalCompartment::Air,
+                    source: format!("Electric irrigation pump: {:.0} kWh/year for {}", input_kwh, label),
+                },
+                electricity_use(input_kwh, format!("Electric irrigation pump for {}", label)),
+            ],
+            PumpType::Solar | PumpType::Gravity => Vec::new(),
+        }
+    }
+
     /// Calculate crop-specific emissions (e.g., CH4 from rice)
     fn calculate_crop_specific_emissions(
         &mut self,
         foods: &[FoodItem],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), EngineError> {
 
         for food in foods {
             // Check if crop is rice (flooded rice paddies emit CH4)
@@ -635,128 +1066,598 @@ impl LCICalculator {
         Ok(())
     }
 
-    /// Calculate land use
-    fn calculate_land_use(
+    /// Calculate N and P discharged to water from aquaculture feed (uneaten feed and excreta)
+    /// Mass balance: nutrient in feed - nutrient retained in fish biomass
+    fn calculate_aquaculture_discharge(
         &mut self,
         foods: &[FoodItem],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), EngineError> {
 
-        let total_land_m2: f64 = foods.iter()
-            .filter_map(|f| f.area_allocated.map(|a| a * 10000.0)) // Convert ha to m2
-            .sum();
+        for food in foods {
+            let aquaculture = match (&food.category, &food.aquaculture) {
+                (FoodCategory::Fish, Some(aquaculture)) => aquaculture,
+                _ => continue,
+            };
+
+            let feed_kg = food.quantity_kg * aquaculture.feed_conversion_ratio;
+            let feed_n = feed_kg * aquaculture.feed_protein_percent.unwrap_or(30.0) / 100.0 / 6.25;
+            let feed_p = feed_kg * aquaculture.feed_phosphorus_percent.unwrap_or(1.0) / 100.0;
+
+            // Whole fish retains ~2.8% N (17.5% protein) and ~0.45% P (Bureau & Hua 2010)
+            let n_released = (feed_n - food.quantity_kg * 0.028).max(0.0);
+            let p_released = (feed_p - food.quantity_kg * 0.0045).max(0.0);
+
+            // Fraction of released nutrients leaving the system (pond sediment retention,
+            // denitrification, RAS solids removal); open cages discharge directly
+            let system_discharge = match aquaculture.system {
+                AquacultureSystem::Cage => 0.95,
+                AquacultureSystem::ConcreteTank => 0.80,
+                AquacultureSystem::EarthenPond => 0.50,
+                AquacultureSystem::RecirculatingSystem => 0.40,
+            };
+            let treatment_factor = if aquaculture.effluent_treatment && aquaculture.system != AquacultureSystem::Cage {
+                0.3 // settling/wetland treatment removes ~70%
+            } else {
+                1.0
+            };
+            let discharge_fraction = system_discharge * treatment_factor;
 
-        if total_land_m2 > 0.0 {
             self.add_inventory_item(InventoryItem {
-                substance: "Land occupation, annual crop".to_string(),
-                quantity: total_land_m2,
-                unit: "m2*year".to_string(),
-                compartment: EnvironmentalCompartment::Resource,
-                source: "Agricultural land occupation".to_string(),
+                substance: "Nitrogen (N)".to_string(),
+                quantity: n_released * discharge_fraction,
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Water,
+                source: format!("Aquaculture N discharge from {} ({:?}, FCR {:.1})",
+                               food.name, aquaculture.system, aquaculture.feed_conversion_ratio),
+            });
+
+            self.add_inventory_item(InventoryItem {
+                substance: "Phosphorus (P)".to_string(),
+                quantity: p_released * discharge_fraction,
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Water,
+                source: format!("Aquaculture P discharge from {} ({:?}, FCR {:.1})",
+                               food.name, aquaculture.system, aquaculture.feed_conversion_ratio),
             });
         }
 
         Ok(())
     }
 
-    /// Add inventory item to the collection
-    fn add_inventory_item(&mut self, item: InventoryItem) {
-        let key = format!("{}_{:?}", item.substance, item.compartment);
+    /// Inventory for fish from a farm described by `AquacultureDetails`: feed production,
+    /// aeration and pumping electricity, pond CH4 and N2O (IPCC 2013 Wetlands Supplement)
+    /// and the water exchanged. The culture volume is estimated from the harvest and the
+    /// system's typical yield per m3. Empty for foods without details; the N and P the fish
+    /// release are in calculate_aquaculture_discharge. Electricity is skipped when the
+    /// user's metered energy already covers it.
+    pub fn calculate_aquaculture_inventory(
+        &self,
+        food: &FoodItem,
+        country: &Country,
+        include_energy: bool,
+    ) -> Vec<InventoryItem> {
+        let aquaculture = match (&food.category, &food.aquaculture) {
+            (FoodCategory::Fish, Some(aquaculture)) => aquaculture,
+            _ => return Vec::new(),
+        };
+        let system = aquaculture.system;
+        let feed_type = aquaculture.feed_type.unwrap_or(AquaFeedType::CommercialPellets);
+        let label = format!("{} ({:?}, FCR {:.1})", food.name, system, aquaculture.feed_conversion_ratio);
+        let mut items = Vec::new();
+
+        let feed_kg = food.quantity_kg * aquaculture.feed_conversion_ratio;
+        items.push(InventoryItem {
+            substance: "Carbon dioxide (CO2) equivalent".to_string(),
+            quantity: feed_kg * self.emission_factors.aquafeed_co2(&feed_type),
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Feed production for {}: {:.0} kg {:?} feed", label, feed_kg, feed_type),
+        });
+        // m2*year per kg feed: crop ingredients at West African yields; fishmeal needs no land
+        let land_per_kg_feed = match feed_type {
+            AquaFeedType::CommercialPellets => 1.8,
+            AquaFeedType::FarmMade => 2.5,
+            AquaFeedType::HighFishmeal => 1.0,
+        };
+        items.push(InventoryItem {
+            substance: "Land occupation, feed crops".to_string(),
+            quantity: feed_kg * land_per_kg_feed,
+            unit: "m2*year".to_string(),
+            compartment: EnvironmentalCompartment::Resource,
+            source: format!("Feed crop land for {}", label),
+        });
+
+        let volume_m3 = food.quantity_kg / system.annual_yield_kg_per_m3();
+        let aeration_hours = aquaculture.aeration_hours_per_day
+            .unwrap_or_else(|| system.default_aeration_hours())
+            .clamp(0.0, 24.0);
+        let exchange_percent = aquaculture.water_exchange_rate
+            .unwrap_or_else(|| system.default_water_exchange_percent())
+            .max(0.0);
+        let exchange_m3 = volume_m3 * exchange_percent / 100.0 * 365.0;
+
+        if include_energy {
+            let aeration_kwh = volume_m3 / 1000.0 * system.aeration_kw_per_1000_m3() * aeration_hours * 365.0;
+            let pumping_kwh = exchange_m3 * self.emission_factors.aquaculture_pumping_kwh_per_m3;
+            let electricity_ef = self.emission_factors.electricity_co2(country);
+            for (kwh, use_) in [(aeration_kwh, "Aeration"), (pumping_kwh, "Water exchange pumping")] {
+                if kwh <= 0.0 {
+                    continue;
+                }
+                items.push(InventoryItem {
+                    substance: "Carbon dioxide (CO2)".to_string(),
+                    quantity: kwh * electricity_ef,
+                    unit: "kg".to_string(),
+                    compartment: EnvironmentalCompartment::Air,
+                    source: format!("{} electricity for {}: {:.0} kWh/year", use_, label, kwh),
+                });
+                items.push(electricity_use(kwh, format!("{} electricity for {}", use_, label)));
+            }
+        }
 
-        // If item already exists, aggregate quantities
-        if let Some(existing) = self.inventory.get_mut(&key) {
-            existing.quantity += item.quantity;
-            existing.source = format!("{}, {}", existing.source, item.source);
-        } else {
-            self.inventory.insert(key, item);
+        // Anaerobic sediment in earthen ponds, ~1.2 m deep
+        if system == AquacultureSystem::EarthenPond {
+            let pond_ha = volume_m3 / 1.2 / 10_000.0;
+            items.push(InventoryItem {
+                substance: "Methane (CH4)".to_string(),
+                quantity: pond_ha * self.emission_factors.ch4_from_aquaculture_ponds.value,
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Air,
+                source: format!("Pond sediment CH4 from {} ({:.2} ha)", label, pond_ha),
+            });
+        }
+        items.push(InventoryItem {
+            substance: "Dinitrogen monoxide (N2O)".to_string(),
+            quantity: food.quantity_kg * self.emission_factors.n2o_from_aquaculture.value * 44.0 / 28.0,
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Pond N2O from {}", label),
+        });
+
+        if exchange_m3 > 0.0 {
+            items.push(InventoryItem {
+                substance: "Water".to_string(),
+                quantity: exchange_m3,
+                unit: "m3".to_string(),
+                compartment: EnvironmentalCompartment::Resource,
+                source: format!("Water exchange for {}: {:.1}% of {:.0} m3 a day", label, exchange_percent, volume_m3),
+            });
         }
-    }
 
-    /// Get the complete inventory
-    pub fn get_inventory(&self) -> &HashMap<String, InventoryItem> {
-        &self.inventory
+        items
     }
-}
-
-#[cfg(test)]
-mod n_balance_tests {
-    use super::*;
 
-    fn maize(area_ha: f64) -> FoodItem {
-        FoodItem {
-            id: "1".to_string(),
-            name: "Maize".to_string(),
-            quantity_kg: 1000.0,
-            category: FoodCategory::Cereals,
-            crop_type: None,
-            origin_country: None,
-            production_system: None,
-            seasonal_factor: None,
-            variety: None,
-            area_allocated: Some(area_ha),
-            cropping_pattern: None,
-            intercropping_partners: None,
-            post_harvest_losses: None,
+    /// This year's share of establishing a tree crop plantation described by a
+    /// `PerennialCropProfile`: nursery seedlings, land preparation diesel and the fertilizer N
+    /// of the immature years, spread straight-line over the productive lifetime (PAS 2050-1).
+    /// Empty before the first harvest, once the plantation has outlived its expected
+    /// lifetime, and for foods without a profile or a known area.
+    pub fn calculate_perennial_inventory(&self, food: &FoodItem) -> Vec<InventoryItem> {
+        let Some(profile) = &food.perennial else { return Vec::new() };
+        let crop = food.crop_type.as_deref().unwrap_or(&food.name);
+        if !profile.amortizing(crop) {
+            return Vec::new();
         }
-    }
+        let Some(area_ha) = food.area_allocated
+            .or_else(|| typical_yield(food).filter(|y| *y > 0.0).map(|y| food.quantity_kg / y))
+        else {
+            return Vec::new();
+        };
 
-    fn urea_100kg() -> FertilizationPractices {
-        FertilizationPractices {
-            uses_fertilizers: true,
-            fertilizer_applications: vec![FertilizerApplication {
-                fertilizer_type: "Urea".to_string(), // 46% N
-                npk_ratio: None,
-                application_rate: 100.0, // kg/ha/season
-                applications_per_season: 1,
-                cost: None,
-                currency: None,
-            }],
-            soil_test_based: false,
-            follows_nutrient_plan: false,
+        let lifetime = profile.productive_lifetime_years(crop);
+        let share = area_ha / lifetime;
+        let inputs = &profile.establishment_inputs;
+        let label = format!("{} ({} years old, {:.1} ha, over {:.0} productive years)",
+                            food.name, profile.plantation_age_years, area_ha, lifetime);
+        let mut items = Vec::new();
+
+        let seedlings = profile.seedlings_per_ha(crop);
+        items.push(InventoryItem {
+            substance: "Carbon dioxide (CO2) equivalent".to_string(),
+            quantity: seedlings * self.emission_factors.co2_per_nursery_seedling.value * share,
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Nursery seedlings for establishing {}: {:.0} per ha", label, seedlings),
+        });
+
+        if inputs.land_preparation_diesel_l_per_ha > 0.0 {
+            let diesel_l = inputs.land_preparation_diesel_l_per_ha * share;
+            items.push(InventoryItem {
+                substance: "Carbon dioxide (CO2)".to_string(),
+                quantity: diesel_l * self.emission_factors.co2_from_diesel.value,
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Air,
+                source: format!("Land preparation diesel for establishing {}: {:.0} L/ha",
+                                label, inputs.land_preparation_diesel_l_per_ha),
+            });
+            items.push(InventoryItem {
+                substance: "Energy, diesel".to_string(),
+                quantity: diesel_l * DIESEL_MJ_PER_L,
+                unit: "MJ".to_string(),
+                compartment: EnvironmentalCompartment::Resource,
+                source: format!("Land preparation diesel for establishing {}", label),
+            });
         }
-    }
 
-    fn flow_by_source<'a>(calc: &'a LCICalculator, needle: &str) -> f64 {
-        calc.get_inventory()
-            .values()
-            .find(|i| i.source.contains(needle))
-            .unwrap_or_else(|| panic!("no inventory flow with source containing '{needle}'"))
-            .quantity
+        let immature_n_kg = inputs.fertilizer_n_kg_per_ha_per_year * f64::from(profile.immature_years(crop)) * share;
+        if immature_n_kg > 0.0 {
+            // As urea, 46% N
+            items.push(InventoryItem {
+                substance: "Carbon dioxide (CO2)".to_string(),
+                quantity: immature_n_kg / 0.46 * self.emission_factors.fertilizer_production_co2("Urea"),
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Air,
+                source: format!("Production and transport of fertilizer for establishing {}", label),
+            });
+            items.push(InventoryItem {
+                substance: "Dinitrogen monoxide (N2O)".to_string(),
+                quantity: immature_n_kg * self.emission_factors.n2o_from_n_fertilizer.value * 44.0 / 28.0,
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Air,
+                source: format!("Direct N2O from immature-year fertilizer for establishing {}", label),
+            });
+        }
+
+        items
     }
 
-    // The mass-balance fix: indirect N2O and nitrate must be derived from the SEPARATE IPCC
-    // volatilisation (FracGASF/EF4) and leaching (FracLEACH/EF5) pathways, and the volatilised
-    // N must not also be counted as leached nitrate.
-    #[test]
-    fn indirect_n2o_and_nitrate_use_separate_ipcc_pathways() {
-        let mut calc = LCICalculator::new();
-        calc.calculate_fertilizer_emissions(&urea_100kg(), &vec![maize(1.0)])
-            .expect("fertiliser emissions");
+    /// Inventory for a flock described by a `PoultryProfile` (IPCC 2019 Vol 4 Ch 10 Tier 1
+    /// manure management, feed N mass balance). Empty for foods without a profile.
+    /// Housing electricity is skipped when the user's metered energy already covers it.
+    pub fn calculate_poultry_inventory(
+        &self,
+        food: &FoodItem,
+        country: &Country,
+        include_housing_energy: bool,
+    ) -> Vec<InventoryItem> {
+        let profile = match (&food.category, &food.poultry) {
+            (FoodCategory::Poultry | FoodCategory::Eggs, Some(profile)) => profile,
+            _ => return Vec::new(),
+        };
 
-        let n_applied = 100.0 * 0.46; // 46 kg N
-        let (frac_gasf, frac_leach, ef4, ef5) = (0.11, 0.24, 0.010, 0.011);
-        let n2o_ratio = 44.0 / 28.0;
+        let birds = profile.flock_size as f64;
+        let manure_system = profile.manure_system
+            .unwrap_or_else(|| profile.system.default_manure_system());
+        let label = format!("{} ({:?}, {} birds)", food.name, profile.system, profile.flock_size);
+        let mut items = Vec::new();
+
+        // Feed: layer and broiler rations are mostly maize and soybean meal. Laying flocks are
+        // restocked with pullets reared on ~7 kg feed each over 18 weeks, spread over a
+        // 72-week laying cycle.
+        let default_fcr = match food.category {
+            FoodCategory::Eggs => 2.1, // kg feed per kg eggs
+            _ => 1.9,                  // kg feed per kg broiler meat
+        };
+        let rearing_feed_kg = match food.category {
+            FoodCategory::Eggs => birds * 7.0 * 52.0 / 72.0,
+            _ => 0.0,
+        };
+        let feed_kg = food.quantity_kg * profile.feed_conversion_ratio.unwrap_or(default_fcr) + rearing_feed_kg;
+
+        items.push(InventoryItem {
+            substance: "Carbon dioxide (CO2) equivalent".to_string(),
+            quantity: feed_kg * 0.8, // kg CO2-eq per kg compound feed (cultivation, milling, transport)
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: if rearing_feed_kg > 0.0 {
+                format!("Feed production for {}: {:.0} kg feed, {:.0} kg of it rearing pullets", label, feed_kg, rearing_feed_kg)
+            } else {
+                format!("Feed production for {}: {:.0} kg feed", label, feed_kg)
+            },
+        });
+        items.push(InventoryItem {
+            substance: "Land occupation, feed crops".to_string(),
+            quantity: feed_kg * 3.0, // m2*year per kg feed at West African maize/soy yields
+            unit: "m2*year".to_string(),
+            compartment: EnvironmentalCompartment::Resource,
+            source: format!("Feed crop land for {}", label),
+        });
+
+        // N excreted = feed N - N retained in eggs (~1.9%) or meat (~2.9%)
+        let feed_n = feed_kg * profile.feed_protein_percent.unwrap_or(17.0) / 100.0 / 6.25;
+        let product_n = food.quantity_kg * match food.category {
+            FoodCategory::Eggs => 0.019,
+            _ => 0.029,
+        };
+        let n_excreted = (feed_n - product_n).max(0.0);
 
-        let direct = flow_by_source(&calc, "Direct N2O");
-        let indirect = flow_by_source(&calc, "Indirect N2O");
-        let nitrate = flow_by_source(&calc, "Nitrate leaching");
+        // Methane conversion factor, direct N2O (EF3) and NH3 volatilisation (FracGAS) by system
+        let (mcf, ef3, frac_gas) = match manure_system {
+            PoultryManureSystem::Litter => (0.015, 0.001, 0.40),
+            PoultryManureSystem::Slurry => (0.30, 0.002, 0.55), // pit storage, warm climate
+            PoultryManureSystem::Range => (0.0047, 0.003, 0.21),
+        };
 
-        assert!((direct - n_applied * 0.01 * n2o_ratio).abs() < 1e-9);
+        // CH4 = VS × B0 (0.39 m3/kg VS) × 0.67 kg/m3 × MCF; a hen in lay excretes ~7.3 kg VS
+        // a year (0.02 kg/day, IPCC 2006 Table 10A-9), a broiler ~5.5
+        let vs_kg_per_bird = match food.category {
+            FoodCategory::Eggs => 7.3,
+            _ => 5.5,
+        };
+        items.push(InventoryItem {
+            substance: "Methane (CH4)".to_string(),
+            quantity: birds * vs_kg_per_bird * 0.39 * 0.67 * mcf,
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Manure CH4 from {} ({:?} manure)", label, manure_system),
+        });
+
+        let n_volatilised = n_excreted * frac_gas;
+        let n2o_n = n_excreted * ef3 + n_volatilised * self.emission_factors.ef4_volatilisation;
+        items.push(InventoryItem {
+            substance: "Dinitrogen monoxide (N2O)".to_string(),
+            quantity: n2o_n * 44.0 / 28.0,
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Manure N2O from {} ({:?} manure, {:.0} kg N excreted)",
+                           label, manure_system, n_excreted),
+        });
+        items.push(InventoryItem {
+            substance: "Ammonia (NH3)".to_string(),
+            quantity: n_volatilised * 17.0 / 14.0,
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Manure NH3 from {} ({:?} manure)", label, manure_system),
+        });
+
+        // Housing footprint (m2 per bird), plus ~4 m2 of outdoor range per free-range bird
+        let (house_m2, range_m2, kwh_per_bird) = match profile.system {
+            PoultrySystem::Battery => (0.05, 0.0, 2.0),    // lighting, ventilation, manure belts
+            PoultrySystem::DeepLitter => (0.14, 0.0, 1.2),
+            PoultrySystem::FreeRange => (0.14, 4.0, 0.6),  // naturally lit and ventilated
+        };
+        items.push(InventoryItem {
+            substance: "Land occupation, poultry housing and range".to_string(),
+            quantity: birds * (house_m2 + range_m2),
+            unit: "m2*year".to_string(),
+            compartment: EnvironmentalCompartment::Resource,
+            source: format!("Housing and range for {}", label),
+        });
+
+        if include_housing_energy {
+            let electricity_ef = self.emission_factors.electricity_co2(country);
+            items.push(InventoryItem {
+                substance: "Carbon dioxide (CO2)".to_string(),
+                quantity: birds * kwh_per_bird * electricity_ef,
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Air,
+                source: format!("Housing electricity for {}: {:.0} kWh/year", label, birds * kwh_per_bird),
+            });
+            items.push(electricity_use(birds * kwh_per_bird, format!("Housing electricity for {}", label)));
+        }
 
-        let expect_indirect =
-            (n_applied * frac_gasf * ef4 + n_applied * frac_leach * ef5) * n2o_ratio;
-        assert!((indirect - expect_indirect).abs() < 1e-9, "indirect {indirect} != {expect_indirect}");
+        items
+    }
 
-        // Nitrate reflects the leached fraction ONLY (FracLEACH), not the volatilised N.
-        let expect_nitrate = n_applied * frac_leach * (62.0 / 14.0);
-        assert!((nitrate - expect_nitrate).abs() < 1e-9, "nitrate {nitrate} != {expect_nitrate}");
+    /// Inventory for milk from a herd described by a `DairyHerdProfile` (IPCC 2019 Vol 4
+    /// Ch 10 Tier 2 enteric CH4 and manure management). Herd emissions over a year are
+    /// allocated to the food's quantity by the herd's milk output, so per-kg burdens fall
+    /// as yield per cow rises. Empty for foods without a profile.
+    pub fn calculate_dairy_inventory(&self, food: &FoodItem) -> Vec<InventoryItem> {
+        let profile = match (&food.category, &food.dairy) {
+            (FoodCategory::Dairy, Some(profile)) => profile,
+            _ => return Vec::new(),
+        };
+        let herd_milk_kg = profile.herd_size as f64 * profile.milk_yield_kg_per_cow;
+        if herd_milk_kg <= 0.0 {
+            return Vec::new();
+        }
 
-        // Regression guard against the old bug (single 20% loss reused for both pathways):
-        // nitrate must equal the leaching-only value, not the lumped-loss value.
-        let old_lumped = n_applied * 0.20 * (62.0 / 14.0);
-        assert!((nitrate - old_lumped).abs() > 1e-9, "nitrate still uses the lumped 20% loss");
+        let cow_years = profile.herd_size as f64;
+        let allocation = food.quantity_kg / herd_milk_kg;
+        let manure_system = profile.manure_system.unwrap_or(DairyManureSystem::Pasture);
+        let label = format!("{} ({} cows, {:.0} kg milk/cow)", food.name, profile.herd_size, profile.milk_yield_kg_per_cow);
+        let mut items = Vec::new();
+
+        // Dry matter intake: ~6 kg/day maintenance for a 300-350 kg cow, plus 0.4 kg per kg milk
+        let daily_milk = profile.milk_yield_kg_per_cow / 365.0;
+        let dmi = profile.feed_intake_kg_dm_per_day.unwrap_or(6.0 + 0.4 * daily_milk);
+        let concentrate = profile.concentrate_kg_per_day.clamp(0.0, dmi);
+        let digestibility = profile.feed_digestibility_percent.unwrap_or(60.0);
+        let gross_energy = dmi * 18.45; // MJ/day
+
+        // Enteric CH4 = GE × Ym / 55.65 MJ/kg; Ym falls with feed digestibility (Table 10.12)
+        let ym = if digestibility >= 70.0 {
+            0.057
+        } else if digestibility > 62.0 {
+            0.063
+        } else {
+            0.065
+        };
+        items.push(InventoryItem {
+            substance: "Methane (CH4)".to_string(),
+            quantity: cow_years * gross_energy * ym * 365.0 / 55.65 * allocation,
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Enteric fermentation from {} (DE {:.0}%, Ym {:.1}%)", label, digestibility, ym * 100.0),
+        });
+
+        // Volatile solids (Eq 10.24): undigested energy plus 4% urinary energy, 8% ash
+        let volatile_solids = gross_energy * (1.0 - digestibility / 100.0 + 0.04) * 0.92 / 18.45;
+        // Methane conversion factor, direct N2O (EF3) and NH3 volatilisation (FracGAS), warm climate
+        let (mcf, ef3, frac_gas) = match manure_system {
+            DairyManureSystem::Pasture => (0.0047, 0.02, 0.21),
+            DairyManureSystem::SolidStorage => (0.05, 0.01, 0.30),
+            DairyManureSystem::DailySpread => (0.01, 0.0, 0.07),
+            DairyManureSystem::Liquid => (0.73, 0.005, 0.40),
+        };
+        // B0 = 0.13 m3 CH4/kg VS for low-productivity cattle, 0.67 kg/m3
+        items.push(InventoryItem {
+            substance: "Methane (CH4)".to_string(),
+            quantity: cow_years * volatile_solids * 365.0 * 0.13 * 0.67 * mcf * allocation,
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Manure CH4 from {} ({:?} manure)", label, manure_system),
+        });
+
+        // N excreted = feed N - N in milk (3.3% protein)
+        let feed_n = dmi * profile.feed_protein_percent.unwrap_or(12.0) / 100.0 / 6.25;
+        let milk_n = daily_milk * 0.033 / 6.38;
+        let n_excreted = cow_years * (feed_n - milk_n).max(0.0) * 365.0 * allocation;
+        let n_volatilised = n_excreted * frac_gas;
+        let n2o_n = n_excreted * ef3 + n_volatilised * self.emission_factors.ef4_volatilisation;
+        items.push(InventoryItem {
+            substance: "Dinitrogen monoxide (N2O)".to_string(),
+            quantity: n2o_n * 44.0 / 28.0,
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Manure N2O from {} ({:?} manure, {:.0} kg N excreted)",
+                           label, manure_system, n_excreted),
+        });
+        items.push(InventoryItem {
+            substance: "Ammonia (NH3)".to_string(),
+            quantity: n_volatilised * 17.0 / 14.0,
+            unit: "kg".to_string(),
+            compartment: EnvironmentalCompartment::Air,
+            source: format!("Manure NH3 from {} ({:?} manure)", label, manure_system),
+        });
+
+        // Purchased concentrate carries its cultivation burden; the rest is grazed or cut forage
+        let concentrate_kg = cow_years * concentrate * 365.0 * allocation;
+        let forage_kg = cow_years * (dmi - concentrate) * 365.0 * allocation;
+        if concentrate_kg > 0.0 {
+            items.push(InventoryItem {
+                substance: "Carbon dioxide (CO2) equivalent".to_string(),
+                quantity: concentrate_kg * 0.8, // kg CO2-eq per kg compound feed
+                unit: "kg".to_string(),
+                compartment: EnvironmentalCompartment::Air,
+                source: format!("Concentrate production for {}: {:.0} kg feed", label, concentrate_kg),
+            });
+            items.push(InventoryItem {
+                substance: "Land occupation, feed crops".to_string(),
+                quantity: concentrate_kg * 3.0,
+                unit: "m2*year".to_string(),
+                compartment: EnvironmentalCompartment::Resource,
+                source: format!("Feed crop land for {}", label),
+            });
+        }
+        items.push(InventoryItem {
+            substance: "Land occupation, pasture".to_string(),
+            quantity: forage_kg * 2.5, // m2*year per kg DM at ~4 t/ha savanna grassland
+            unit: "m2*year".to_string(),
+            compartment: EnvironmentalCompartment::Resource,
+            source: format!("Grazing and forage land for {}: {:.0} kg DM", label, forage_kg),
+        });
+
+        items
+    }
+
+    /// Calculate land use
+    fn calculate_land_use(
+        &mut self,
+        foods: &[FoodItem],
+    ) -> Result<(), EngineError> {
+
+        let total_land_m2: f64 = foods.iter()
+            .filter_map(|f| f.area_allocated.map(|a| Area::ha(a).in_m2()))
+            .sum();
+
+        if total_land_m2 > 0.0 {
+            self.add_inventory_item(InventoryItem {
+                substance: "Land occupation, annual crop".to_string(),
+                quantity: total_land_m2,
+                unit: "m2*year".to_string(),
+                compartment: EnvironmentalCompartment::Resource,
+                source: "Agricultural land occupation".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Add inventory item to the collection
+    fn add_inventory_item(&mut self, item: InventoryItem) {
+        let key = format!("{}_{:?}", item.substance, item.compartment);
+        self.activities.push(item.clone());
+
+        // If item already exists, aggregate quantities
+        if let Some(existing) = self.inventory.get_mut(&key) {
+            existing.quantity += item.quantity;
+            existing.source = format!("{}, {}", existing.source, item.source);
+        } else {
+            self.inventory.insert(key, item);
+        }
+    }
+
+    /// Get the complete inventory
+    pub fn get_inventory(&self) -> &BTreeMap<String, InventoryItem> {
+        &self.inventory
+    }
+
+    /// Inputs the last inventory left out because they were not reported
+    pub fn data_gaps(&self) -> &[String] {
+        &self.data_gaps
+    }
+
+    /// Management practice effects the last inventory already accounts for
+    pub fn captured_effects(&self) -> &BTreeSet<PracticeEffect> {
+        &self.captured_effects
+    }
+
+    /// Global warming of each activity in the last inventory, as (source, kg CO2-eq/year)
+    pub fn gwp_by_activity(&self) -> Vec<(String, f64)> {
+        self.activities.iter()
+            .filter_map(|item| gwp_factor(&item.substance).map(|factor| (item.source.clone(), item.quantity * factor)))
+            .collect()
     }
 }
 
+/// Nitrogen content of a fertilizer (kg N per kg product)
+pub fn nitrogen_content(fertilizer_type: &str, npk_ratio: &Option<String>) -> f64 {
+    match fertilizer_type {
+        "Urea" => 0.46, // 46% N
+        "Diammonium Phosphate (DAP)" | "DAP" => 0.18, // 18% N
+        "NPK Compound" | "NPK" => {
+            // Parse NPK ratio if provided (e.g., "15-15-15")
+            if let Some(ratio) = npk_ratio {
+                if let Some(n_str) = ratio.split('-').next() {
+                    if let Ok(n_percent) = n_str.trim().parse::<f64>() {
+                        return n_percent / 100.0;
+                    }
+                }
+            }
+            0.15 // Default 15% N for NPK
+        }
+        "Ammonium Sulfate" => 0.21, // 21% N
+        "Calcium Ammonium Nitrate" | "CAN" => 0.27, // 27% N
+        "Ammonium Nitrate" => 0.34, // 34% N
+        _ => 0.10, // Conservative default
+    }
+}
+
+/// Irrigation water applied per hectare, by irrigation system
+fn irrigation_m3_per_ha(water_management: &WaterManagement) -> f64 {
+    match water_management.irrigation_system.as_deref() {
+        Some("Drip irrigation") | Some("Drip Irrigation") => 3000.0, // Efficient
+        Some("Sprinkler") | Some("Sprinkler System") => 5000.0, // Moderate
+        Some("Flood irrigation") | Some("Furrow irrigation") => 8000.0, // Inefficient
+        None | Some("None (Rainfed)") => 0.0, // Rainfed
+        _ => 4000.0, // Default moderate use
+    }
+}
+
+/// Final energy of `kwh` of electricity, as the inventory flow "Energy consumption" sums
+fn electricity_use(kwh: f64, source: String) -> InventoryItem {
+    InventoryItem {
+        substance: "Energy, electricity".to_string(),
+        quantity: Energy::kwh(kwh).in_mj(),
+        unit: "MJ".to_string(),
+        compartment: EnvironmentalCompartment::Resource,
+        source,
+    }
+}
+
+/// Green, blue ("Water") and grey water of an inventory per kg of `production_kg`; None when
+/// the inventory has no water flows, i.e. the farm's water management was not given
+pub fn water_footprint(inventory: &BTreeMap<String, InventoryItem>, production_kg: f64) -> Option<WaterFootprint> {
+    let volume = |substance: &str| inventory.values()
+        .filter(|item| item.substance == substance)
+        .map(|item| item.quantity)
+        .sum::<f64>();
+    let (green, blue, grey) = (volume("Water, green"), volume("Water"), volume("Water, grey"));
+    (green + blue + grey > 0.0).then(|| WaterFootprint::per_kg(green, blue, grey, production_kg))
+}
+
 // ======================================================================
 // LCI TO LCIA BRIDGE - Convert Inventory to Impact Categories
 // ======================================================================
@@ -766,10 +1667,10 @@ impl LCICalculator {
     /// This is where we go from "kg N2O" to "kg CO2-eq global warming potential"
     pub fn calculate_midpoint_impacts(
         &self,
-        inventory: &HashMap<String, InventoryItem>,
-    ) -> Result<HashMap<String, MidpointResult>, Box<dyn std::error::Error>> {
+        inventory: &BTreeMap<String, InventoryItem>,
+    ) -> Result<BTreeMap<String, MidpointResult>, EngineError> {
 
-        let mut impacts = HashMap::new();
+        let mut impacts = BTreeMap::new();
 
         // Initialize all impact categories
         let impact_categories = vec![
@@ -786,27 +1687,31 @@ impl LCICalculator {
             "Mineral depletion",
             "Particulate matter formation",
             "Photochemical oxidation",
+            "Energy consumption",
         ];
 
         for category in &impact_categories {
             impacts.insert(category.to_string(), MidpointResult {
                 value: 0.0,
                 unit: self.get_impact_unit(category),
+                basis: ImpactBasis::Total,
                 uncertainty_range: (0.0, 0.0),
+                geometric_sd: None,
                 data_quality_score: 0.8, // High quality - calculated from primary data
                 contributing_sources: Vec::new(),
+                metadata: None,
             });
         }
 
         // Characterization factors (IPCC AR6, ReCiPe 2016)
-        let gwp_n2o = 273.0; // N2O has 273x warming potential of CO2 (100-year horizon)
-        let gwp_ch4 = 28.0;  // CH4 has 28x warming potential of CO2 (fossil) - AR6
+        let gwp_n2o = GWP100_N2O;
+        let gwp_ch4 = GWP100_CH4;
 
         // Calculate Global Warming Potential
         let mut gwp_total = 0.0;
         let mut gwp_sources = Vec::new();
 
-        for (_, item) in inventory {
+        for item in inventory.values() {
             match item.substance.as_str() {
                 "Carbon dioxide (CO2)" | "Carbon dioxide (CO2) equivalent" => {
                     gwp_total += item.quantity; // Already in kg CO2
@@ -835,7 +1740,7 @@ impl LCICalculator {
         }
 
         // Water consumption
-        for (_, item) in inventory {
+        for item in inventory.values() {
             if item.substance == "Water" {
                 if let Some(water_result) = impacts.get_mut("Water consumption") {
                     water_result.value += item.quantity; // m3
@@ -843,19 +1748,55 @@ impl LCICalculator {
                 }
             }
         }
+        if let Some(water_result) = impacts.get_mut("Water consumption") {
+            // Estimated from the irrigation system and area rather than metered
+            water_result.uncertainty_range = (water_result.value * 0.6, water_result.value * 1.4);
+        }
+
+        // Energy consumption: final energy of fuels and electricity used on the farm
+        for item in inventory.values() {
+            if item.substance.starts_with("Energy, ") && item.compartment == EnvironmentalCompartment::Resource {
+                if let Some(energy_result) = impacts.get_mut("Energy consumption") {
+                    energy_result.value += item.quantity; // MJ
+                    energy_result.contributing_sources.push(item.source.clone());
+                }
+            }
+        }
+        if let Some(energy_result) = impacts.get_mut("Energy consumption") {
+            energy_result.uncertainty_range = (energy_result.value * 0.9, energy_result.value * 1.1); // metered, as for fossil depletion
+        }
 
         // Land use
-        for (_, item) in inventory {
+        for item in inventory.values() {
             if item.substance.contains("Land occupation") {
                 if let Some(land_result) = impacts.get_mut("Land use") {
-                    land_result.value += item.quantity / 10000.0; // Convert m2*year to m2a
+                    land_result.value += item.quantity; // m2*year is m2a
                     land_result.contributing_sources.push(item.source.clone());
                 }
             }
         }
+        if let Some(land_result) = impacts.get_mut("Land use") {
+            land_result.uncertainty_range = (land_result.value * 0.8, land_result.value * 1.2); // farm-reported areas
+        }
+
+        // Terrestrial acidification (NH3 from fertilizer and manure)
+        for item in inventory.values() {
+            if item.substance == "Ammonia (NH3)" && item.compartment == EnvironmentalCompartment::Air {
+                if let Some(acidification_result) = impacts.get_mut("Terrestrial acidification") {
+                    let so2_eq = item.quantity * ACIDIFICATION_NH3;
+                    acidification_result.value += so2_eq;
+                    acidification_result.contributing_sources.push(format!(
+                        "{}: {:.1} kg NH3 ({:.1} kg SO2-eq)", item.source, item.quantity, so2_eq
+                    ));
+                }
+            }
+        }
+        if let Some(acidification_result) = impacts.get_mut("Terrestrial acidification") {
+            acidification_result.uncertainty_range = (acidification_result.value * 0.6, acidification_result.value * 1.4);
+        }
 
         // Freshwater eutrophication (from nitrate leaching)
-        for (_, item) in inventory {
+        for item in inventory.values() {
             if item.substance.contains("Nitrate") {
                 if let Some(eutroph_result) = impacts.get_mut("Freshwater eutrophication") {
                     // Convert NO3 to P-equivalent using ReCiPe characterization
@@ -867,6 +1808,16 @@ impl LCICalculator {
             }
         }
 
+        // Freshwater eutrophication (direct P discharge, e.g. aquaculture effluent)
+        for item in inventory.values() {
+            if item.substance == "Phosphorus (P)" && item.compartment == EnvironmentalCompartment::Water {
+                if let Some(eutroph_result) = impacts.get_mut("Freshwater eutrophication") {
+                    eutroph_result.value += item.quantity; // ReCiPe 2016: 1 kg P = 1 kg P-eq
+                    eutroph_result.contributing_sources.push(item.source.clone());
+                }
+            }
+        }
+
         info!("Calculated {} midpoint impact categories from LCI", impacts.len());
 
         Ok(impacts)
@@ -887,7 +1838,586 @@ impl LCICalculator {
             "Mineral depletion" => "kg Fe-eq".to_string(),
             "Particulate matter formation" => "PM2.5-eq".to_string(),
             "Photochemical oxidation" => "kg NMVOC-eq".to_string(),
+            "Energy consumption" => "MJ".to_string(),
             _ => "Unknown".to_string(),
         }
     }
 }
+
+#[cfg(test)]
+mod n_balance_tests {
+    use super::*;
+
+    fn maize(area_ha: f64) -> FoodItem {
+        FoodItem {
+            id: "1".to_string(),
+            name: "Maize".to_string(),
+            quantity_kg: 1000.0,
+            category: FoodCategory::Cereals,
+            crop_type: None,
+            origin_country: None,
+            production_system: None,
+            seasonal_factor: None,
+            variety: None,
+            area_allocated: Some(area_ha),
+            cropping_pattern: None,
+            intercropping_partners: None,
+            post_harvest_losses: None,
+            aquaculture: None,
+            poultry: None,
+            dairy: None,
+            perennial: None,
+            head_count: None,
+        }
+    }
+
+    fn urea_100kg() -> FertilizationPractices {
+        FertilizationPractices {
+            uses_fertilizers: true,
+            fertilizer_applications: vec![FertilizerApplication {
+                fertilizer_type: "Urea".to_string(), // 46% N
+                npk_ratio: None,
+                application_rate: 100.0, // kg/ha/season
+                applications_per_season: 1,
+                cost: None,
+                currency: None,
+                treated_area_ha: None,
+            }],
+            soil_test_based: false,
+            follows_nutrient_plan: false,
+        }
+    }
+
+    fn flow_by_source(calc: &LCICalculator, needle: &str) -> f64 {
+        calc.get_inventory()
+            .values()
+            .find(|i| i.source.contains(needle))
+            .unwrap_or_else(|| panic!("no inventory flow with source containing '{needle}'"))
+            .quantity
+    }
+
+    // The mass-balance fix: indirect N2O and nitrate must be derived from the SEPARATE IPCC
+    // volatilisation (FracGASF/EF4) and leaching (FracLEACH/EF5) pathways, and the volatilised
+    // N must not also be counted as leached nitrate.
+    #[test]
+    fn fertilizer_on_crops_without_area_uses_an_estimated_area() {
+        let mut calc = LCICalculator::new();
+        let unmapped = FoodItem { area_allocated: None, quantity_kg: 1900.0, ..maize(0.0) };
+        calc.calculate_fertilizer_emissions(&urea_100kg(), &[unmapped]).unwrap();
+
+        // 1.9 t at the typical 1.9 t/ha of maize: the same emissions as a reported hectare
+        let mut mapped = LCICalculator::new();
+        mapped.calculate_fertilizer_emissions(&urea_100kg(), &[maize(1.0)]).unwrap();
+        let direct = flow_by_source(&calc, "Direct N2O");
+        assert!(direct > 0.0);
+        assert!((direct - flow_by_source(&mapped, "Direct N2O")).abs() < 1e-9);
+        assert!(calc.data_gaps().iter().any(|gap| gap.starts_with("Area of Maize not reported - estimated at 1.00 ha")));
+        assert!(mapped.data_gaps().is_empty());
+
+        // Nothing to estimate from: no emissions, but not a clean result either
+        let mut livestock = LCICalculator::new();
+        let goats = FoodItem { category: FoodCategory::Meat, name: "Goat".to_string(), ..maize(0.0) };
+        livestock.calculate_fertilizer_emissions(&urea_100kg(), &[goats]).unwrap();
+        assert!(livestock.get_inventory().is_empty());
+        assert!(livestock.data_gaps().iter().any(|gap| gap.contains("fertilizer emissions are a data gap")));
+    }
+
+    #[test]
+    fn indirect_n2o_and_nitrate_use_separate_ipcc_pathways() {
+        let mut calc = LCICalculator::new();
+        calc.calculate_fertilizer_emissions(&urea_100kg(), &[maize(1.0)])
+            .expect("fertiliser emissions");
+
+        let n_applied = 100.0 * 0.46; // 46 kg N
+        let (frac_gasf, frac_leach, ef4, ef5) = (0.11, 0.24, 0.010, 0.011);
+        let n2o_ratio = 44.0 / 28.0;
+
+        let direct = flow_by_source(&calc, "Direct N2O");
+        let indirect = flow_by_source(&calc, "Indirect N2O");
+        let nitrate = flow_by_source(&calc, "Nitrate leaching");
+
+        assert!((direct - n_applied * 0.01 * n2o_ratio).abs() < 1e-9);
+
+        let expect_indirect =
+            (n_applied * frac_gasf * ef4 + n_applied * frac_leach * ef5) * n2o_ratio;
+        assert!((indirect - expect_indirect).abs() < 1e-9, "indirect {indirect} != {expect_indirect}");
+
+        // Nitrate reflects the leached fraction ONLY (FracLEACH), not the volatilised N.
+        let expect_nitrate = n_applied * frac_leach * (62.0 / 14.0);
+        assert!((nitrate - expect_nitrate).abs() < 1e-9, "nitrate {nitrate} != {expect_nitrate}");
+
+        // Regression guard against the old bug (single 20% loss reused for both pathways):
+        // nitrate must equal the leaching-only value, not the lumped-loss value.
+        let old_lumped = n_applied * 0.20 * (62.0 / 14.0);
+        assert!((nitrate - old_lumped).abs() > 1e-9, "nitrate still uses the lumped 20% loss");
+    }
+
+    #[test]
+    fn urea_acidifies_more_than_ammonium_nitrate_at_equal_n() {
+        let acidification = |fertilizer_type: &str, rate: f64| {
+            let mut fertilization = urea_100kg();
+            fertilization.fertilizer_applications[0].fertilizer_type = fertilizer_type.to_string();
+            fertilization.fertilizer_applications[0].application_rate = rate;
+            let mut calc = LCICalculator::new();
+            calc.calculate_fertilizer_emissions(&fertilization, &[maize(1.0)])
+                .expect("fertiliser emissions");
+            calc.calculate_midpoint_impacts(calc.get_inventory()).expect("impacts")
+                ["Terrestrial acidification"].value
+        };
+
+        // 46 kg N either way
+        let urea = acidification("Urea", 100.0);
+        let ammonium_nitrate = acidification("Ammonium Nitrate", 46.0 / 0.34);
+
+        let expect_urea = 46.0 * 0.15 * (17.0 / 14.0) * ACIDIFICATION_NH3;
+        assert!((urea - expect_urea).abs() < 1e-9, "urea {urea} != {expect_urea}");
+        assert!(urea > 2.0 * ammonium_nitrate, "urea {urea} vs ammonium nitrate {ammonium_nitrate}");
+    }
+
+    fn insecticide(treated_area_ha: Option<f64>) -> PestManagement {
+        PestManagement {
+            management_approach: "Chemical".to_string(),
+            uses_ipm: false,
+            pesticides_used: vec![PesticideApplication {
+                pesticide_type: "Insecticide".to_string(),
+                active_ingredient: "Lambda-cyhalothrin".to_string(),
+                application_rate: 0.5, // kg a.i./ha
+                applications_per_season: 2,
+                target_pests: vec!["Fall armyworm".to_string()],
+                treated_area_ha,
+            }],
+            monitoring_frequency: None,
+        }
+    }
+
+    #[test]
+    fn treating_half_the_area_halves_pesticide_burden() {
+        let foods = [maize(4.0)];
+
+        let mut full = LCICalculator::new();
+        full.calculate_pesticide_emissions(&insecticide(None), &foods)
+            .expect("pesticide emissions");
+        let mut half = LCICalculator::new();
+        half.calculate_pesticide_emissions(&insecticide(Some(2.0)), &foods)
+            .expect("pesticide emissions");
+
+        let full_burden = flow_by_source(&full, "Insecticide pesticide");
+        let half_burden = flow_by_source(&half, "Insecticide pesticide");
+        assert!((full_burden - 0.5 * 2.0 * 4.0 * 10.0).abs() < 1e-9);
+        assert!((half_burden - full_burden / 2.0).abs() < 1e-9);
+    }
+
+    fn farmed_fish(system: AquacultureSystem, effluent_treatment: bool) -> FoodItem {
+        FoodItem {
+            id: "2".to_string(),
+            name: "Tilapia".to_string(),
+            quantity_kg: 10_000.0,
+            category: FoodCategory::Fish,
+            crop_type: Some("Tilapia".to_string()),
+            origin_country: None,
+            production_system: None,
+            seasonal_factor: None,
+            variety: None,
+            area_allocated: None,
+            cropping_pattern: None,
+            intercropping_partners: None,
+            post_harvest_losses: None,
+            aquaculture: Some(AquacultureDetails {
+                system,
+                feed_conversion_ratio: 1.6,
+                feed_protein_percent: Some(32.0),
+                feed_phosphorus_percent: Some(1.0),
+                effluent_treatment,
+                feed_type: None,
+                aeration_hours_per_day: None,
+                water_exchange_rate: None,
+            }),
+            poultry: None,
+            dairy: None,
+            perennial: None,
+            head_count: None,
+        }
+    }
+
+    fn assessment_with(foods: Vec<FoodItem>) -> Assessment {
+        Assessment {
+            schema_version: crate::schema::current_schema_version(),
+            id: uuid::Uuid::new_v4(),
+            company_name: "Test Fish Farm".to_string(),
+            country: Country::Ghana,
+            currency: Currency::GHS,
+            exchange_rates: ExchangeRates::default(),
+            region: None,
+            coordinates: None,
+            foods,
+            assessment_date: chrono::Utc::now(),
+            methodology: LCAMethodologyBuilder::new()
+                .characterization_method(CharacterizationMethod::ReCiPe2016)
+                .build(),
+            results: None,
+            farm_profile: None,
+            management_practices: None,
+            equipment_energy: None,
+        }
+    }
+
+    fn eutrophication(food: FoodItem) -> (f64, f64) {
+        use crate::production::lci_extended::LCIExtendedCharacterization;
+
+        let assessment = assessment_with(vec![food]);
+        let mut calc = LCICalculator::new();
+        let inventory = calc.calculate_inventory(&assessment).expect("inventory");
+        let impacts = calc.calculate_extended_midpoint_impacts(&inventory, &assessment).expect("impacts");
+        (impacts["Marine eutrophication"].value, impacts["Freshwater eutrophication"].value)
+    }
+
+    #[test]
+    fn aquaculture_feed_nutrients_reach_eutrophication_via_lci() {
+        let (cage_marine, cage_freshwater) = eutrophication(farmed_fish(AquacultureSystem::Cage, false));
+        let (ras_marine, ras_freshwater) = eutrophication(farmed_fish(AquacultureSystem::RecirculatingSystem, true));
+
+        // N: 16 t feed × 32% / 6.25 = 819.2 kg N; 280 kg retained; 95% discharged; 50% reaches sea
+        let expected_cage_marine = (819.2 - 280.0) * 0.95 * 0.5 / 10_000.0;
+        assert!((cage_marine - expected_cage_marine).abs() < 1e-9, "marine {cage_marine}");
+        assert!(cage_freshwater > 0.0);
+
+        // Open cages discharge far more than treated RAS
+        assert!(cage_marine > 5.0 * ras_marine);
+        assert!(cage_freshwater > 5.0 * ras_freshwater);
+    }
+
+    fn laying_flock(system: PoultrySystem) -> FoodItem {
+        FoodItem {
+            id: "3".to_string(),
+            name: "Eggs".to_string(),
+            quantity_kg: 150_000.0, // 10,000 layers × 15 kg eggs
+            category: FoodCategory::Eggs,
+            crop_type: None,
+            origin_country: None,
+            production_system: None,
+            seasonal_factor: None,
+            variety: None,
+            area_allocated: None,
+            cropping_pattern: None,
+            intercropping_partners: None,
+            post_harvest_losses: None,
+            aquaculture: None,
+            poultry: Some(PoultryProfile {
+                system,
+                flock_size: 10_000,
+                feed_conversion_ratio: None,
+                feed_protein_percent: None,
+                manure_system: None,
+            }),
+            dairy: None,
+            perennial: None,
+            head_count: None,
+        }
+    }
+
+    fn poultry_flow(items: &[InventoryItem], substance: &str) -> f64 {
+        items.iter()
+            .filter(|i| i.substance.starts_with(substance))
+            .map(|i| i.quantity)
+            .sum()
+    }
+
+    #[test]
+    fn free_range_trades_housing_energy_for_land() {
+        let calc = LCICalculator::new();
+        let battery = calc.calculate_poultry_inventory(&laying_flock(PoultrySystem::Battery), &Country::Ghana, true);
+        let free_range = calc.calculate_poultry_inventory(&laying_flock(PoultrySystem::FreeRange), &Country::Ghana, true);
+
+        // Range adds 4 m2 per bird on top of the same feed land
+        let land_gap = poultry_flow(&free_range, "Land occupation") - poultry_flow(&battery, "Land occupation");
+        assert!((land_gap - 10_000.0 * (4.0 + 0.14 - 0.05)).abs() < 1e-6, "land gap {land_gap}");
+
+        // 2.0 vs 0.6 kWh per bird on the Ghana grid
+        let battery_kwh_co2 = poultry_flow(&battery, "Carbon dioxide (CO2)") - poultry_flow(&battery, "Carbon dioxide (CO2) equivalent");
+        let free_range_kwh_co2 = poultry_flow(&free_range, "Carbon dioxide (CO2)") - poultry_flow(&free_range, "Carbon dioxide (CO2) equivalent");
+        assert!((battery_kwh_co2 - 20_000.0 * 0.45).abs() < 1e-6);
+        assert!(free_range_kwh_co2 < battery_kwh_co2);
+
+        // Wet droppings under cages ferment; droppings on range barely do
+        assert!(poultry_flow(&battery, "Methane (CH4)") > 50.0 * poultry_flow(&free_range, "Methane (CH4)"));
+        assert!(poultry_flow(&battery, "Ammonia (NH3)") > poultry_flow(&free_range, "Ammonia (NH3)"));
+
+        // Metered farm energy replaces the housing estimate
+        let metered = calc.calculate_poultry_inventory(&laying_flock(PoultrySystem::Battery), &Country::Ghana, false);
+        assert!(metered.iter().all(|i| !i.source.starts_with("Housing electricity")));
+    }
+
+    #[test]
+    fn laying_flocks_carry_pullet_rearing_feed_and_layer_manure() {
+        let calc = LCICalculator::new();
+        let eggs = laying_flock(PoultrySystem::DeepLitter);
+        let broilers = FoodItem { category: FoodCategory::Poultry, ..eggs.clone() };
+        let layer_items = calc.calculate_poultry_inventory(&eggs, &Country::Ghana, true);
+        let broiler_items = calc.calculate_poultry_inventory(&broilers, &Country::Ghana, true);
+
+        // 150 t of eggs at FCR 2.1, plus 7 kg of rearing feed per hen over 72 weeks of lay
+        let feed = layer_items.iter().find(|i| i.source.starts_with("Feed production")).unwrap();
+        let feed_kg = 150_000.0 * 2.1 + 10_000.0 * 7.0 * 52.0 / 72.0;
+        assert!((feed.quantity - feed_kg * 0.8).abs() < 1e-6, "{}", feed.quantity);
+        assert!(feed.source.contains("rearing pullets"));
+
+        let ch4_ratio = poultry_flow(&layer_items, "Methane (CH4)") / poultry_flow(&broiler_items, "Methane (CH4)");
+        assert!((ch4_ratio - 7.3 / 5.5).abs() < 1e-9);
+    }
+
+    fn aquaculture_global_warming(calc: &LCICalculator, items: &[InventoryItem]) -> f64 {
+        let inventory: BTreeMap<String, InventoryItem> = items.iter()
+            .map(|item| (format!("{}_{:?}", item.substance, item.compartment), item.clone()))
+            .collect();
+        calc.calculate_midpoint_impacts(&inventory).expect("impacts")["Global warming"].value
+    }
+
+    #[test]
+    fn intensive_catfish_outweighs_extensive_tilapia() {
+        let calc = LCICalculator::new();
+        let mut catfish = farmed_fish(AquacultureSystem::ConcreteTank, false);
+        catfish.name = "Catfish".to_string();
+        if let Some(details) = catfish.aquaculture.as_mut() {
+            details.feed_conversion_ratio = 1.8;
+            details.feed_type = Some(AquaFeedType::HighFishmeal);
+            details.aeration_hours_per_day = Some(24.0);
+        }
+        let mut tilapia = farmed_fish(AquacultureSystem::EarthenPond, false);
+        if let Some(details) = tilapia.aquaculture.as_mut() {
+            details.feed_conversion_ratio = 1.2;
+            details.feed_type = Some(AquaFeedType::FarmMade);
+        }
+        let catfish_items = calc.calculate_aquaculture_inventory(&catfish, &Country::Nigeria, true);
+        let tilapia_items = calc.calculate_aquaculture_inventory(&tilapia, &Country::Nigeria, true);
+
+        // 10 t of fish at FCR 1.8 on fishmeal-rich feed
+        let feed = catfish_items.iter().find(|i| i.source.starts_with("Feed production")).unwrap();
+        assert!((feed.quantity - 18_000.0 * 2.4).abs() < 1e-6, "{}", feed.quantity);
+
+        // Ponds emit sediment CH4 but need no aeration; tanks are aerated around the clock
+        assert!(tilapia_items.iter().any(|i| i.substance == "Methane (CH4)"));
+        assert!(!catfish_items.iter().any(|i| i.substance == "Methane (CH4)"));
+        assert!(catfish_items.iter().any(|i| i.source.starts_with("Aeration electricity")));
+        assert!(!tilapia_items.iter().any(|i| i.source.starts_with("Aeration electricity")));
+        assert!(poultry_flow(&catfish_items, "Water") > 0.0);
+
+        let catfish_gwp = aquaculture_global_warming(&calc, &catfish_items);
+        let tilapia_gwp = aquaculture_global_warming(&calc, &tilapia_items);
+        assert!(catfish_gwp > 2.0 * tilapia_gwp, "{} vs {}", catfish_gwp, tilapia_gwp);
+
+        // Metered farm energy replaces the aeration and pumping estimates
+        let metered = calc.calculate_aquaculture_inventory(&catfish, &Country::Nigeria, false);
+        assert!(metered.iter().all(|i| !i.source.contains("electricity")));
+        assert!(calc.calculate_aquaculture_inventory(&maize(2.0), &Country::Ghana, true).is_empty());
+    }
+
+    #[test]
+    fn poultry_profile_feeds_extended_characterization() {
+        use crate::production::lci_extended::LCIExtendedCharacterization;
+
+        let assessment = assessment_with(vec![laying_flock(PoultrySystem::DeepLitter)]);
+        let mut calc = LCICalculator::new();
+        let inventory = calc.calculate_inventory(&assessment).expect("inventory");
+        let impacts = calc.calculate_extended_midpoint_impacts(&inventory, &assessment).expect("impacts");
+
+        // Feed alone is 2.1 kg × 0.8 kg CO2-eq per kg eggs
+        assert!(impacts["Global warming"].value > 2.1 * 0.8);
+        assert!(impacts["Terrestrial acidification"].value > 0.0);
+        assert!(impacts["Land use"].value > 0.0);
+    }
+
+    #[test]
+    fn land_and_water_midpoints_carry_an_uncertainty_range() {
+        let item = |substance: &str, quantity: f64, unit: &str| (substance.to_string(), InventoryItem {
+            substance: substance.to_string(),
+            quantity,
+            unit: unit.to_string(),
+            compartment: EnvironmentalCompartment::Resource,
+            source: "Maize".to_string(),
+        });
+        let inventory = BTreeMap::from([
+            item("Water", 5000.0, "m3"),
+            item("Land occupation, annual crop", 10_000.0, "m2*year"),
+        ]);
+        let impacts = LCICalculator::new().calculate_midpoint_impacts(&inventory).unwrap();
+        assert_eq!(impacts["Water consumption"].uncertainty_range, (3000.0, 7000.0));
+        assert_eq!(impacts["Land use"].uncertainty_range, (8000.0, 12_000.0));
+    }
+
+    fn cocoa_farm(plantation_age_years: u32, quantity_kg: f64) -> FoodItem {
+        FoodItem {
+            name: "Cocoa".to_string(),
+            crop_type: Some("Cocoa".to_string()),
+            category: FoodCategory::Other,
+            quantity_kg,
+            perennial: Some(PerennialCropProfile {
+                plantation_age_years,
+                expected_productive_lifetime_years: None,
+                establishment_inputs: EstablishmentInputs {
+                    land_preparation_diesel_l_per_ha: 20.0,
+                    fertilizer_n_kg_per_ha_per_year: 30.0,
+                    ..EstablishmentInputs::default()
+                },
+                shade_system: true,
+            }),
+            ..maize(2.0)
+        }
+    }
+
+    fn cocoa_global_warming_per_kg(food: FoodItem) -> f64 {
+        let quantity_kg = food.quantity_kg;
+        let mut practices = ManagementPractices::baseline();
+        practices.fertilization = Some(urea_100kg());
+        let mut assessment = assessment_with(vec![food]);
+        assessment.management_practices = Some(practices);
+        let mut calc = LCICalculator::new();
+        let inventory = calc.calculate_inventory(&assessment).expect("inventory");
+        calc.calculate_midpoint_impacts(&inventory).expect("impacts")["Global warming"].value / quantity_kg
+    }
+
+    #[test]
+    fn young_cocoa_carries_more_establishment_per_kg_than_a_mature_farm() {
+        // Same 2 ha and annual inputs; trees in their first bearing year give half a mature yield
+        let young = cocoa_global_warming_per_kg(cocoa_farm(4, 500.0));
+        let mature = cocoa_global_warming_per_kg(cocoa_farm(20, 1000.0));
+        assert!(young > mature, "{} vs {}", young, mature);
+
+        // 2 ha of 1111 seedlings, 20 L diesel and 3 immature years of 30 kg N, over 25 years
+        let calc = LCICalculator::new();
+        let items = calc.calculate_perennial_inventory(&cocoa_farm(20, 1000.0));
+        let nursery = items.iter().find(|i| i.source.starts_with("Nursery seedlings")).unwrap();
+        assert!((nursery.quantity - 2.0 * 1111.0 * 0.1 / 25.0).abs() < 1e-9);
+        let n2o = items.iter().find(|i| i.substance == "Dinitrogen monoxide (N2O)").unwrap();
+        assert!((n2o.quantity - 2.0 * 90.0 * 0.01 * 44.0 / 28.0 / 25.0).abs() < 1e-9);
+
+        // Nothing to amortize before the first harvest or past the productive lifetime
+        assert!(calc.calculate_perennial_inventory(&cocoa_farm(2, 0.0)).is_empty());
+        assert!(calc.calculate_perennial_inventory(&cocoa_farm(30, 800.0)).is_empty());
+
+        // Shade trees make the plot agroforestry
+        assert_eq!(cocoa_farm(20, 1000.0).effective_production_system(), Some(ProductionSystem::Agroforestry));
+    }
+
+    fn irrigated_global_warming(water_source: &str, pump_type: PumpType, lift_height_m: Option<f64>) -> f64 {
+        let mut practices = ManagementPractices::baseline();
+        practices.water_management = WaterManagement {
+            water_source: vec![water_source.to_string()],
+            irrigation_system: Some("Furrow irrigation".to_string()),
+            water_conservation_practices: vec![],
+            pump_type: Some(pump_type),
+            lift_height_m,
+        };
+        let mut assessment = assessment_with(vec![maize(2.0)]);
+        assessment.management_practices = Some(practices);
+        let mut calc = LCICalculator::new();
+        let inventory = calc.calculate_inventory(&assessment).expect("inventory");
+        calc.calculate_midpoint_impacts(&inventory).expect("impacts")["Global warming"].value
+    }
+
+    #[test]
+    fn deep_borehole_diesel_pumping_outweighs_gravity_fed_irrigation() {
+        let gravity = irrigated_global_warming("River", PumpType::Gravity, None);
+        let borehole = irrigated_global_warming("Borehole", PumpType::Diesel, None);
+
+        // 16,000 m3 lifted 60 m: 2,616 kWh of water power from a 12.5% efficient pump set
+        let diesel_l = 16_000.0 * 1000.0 * 9.81 * 60.0 / 3.6e6 / 0.125 * 3.6 / DIESEL_MJ_PER_L;
+        let co2 = diesel_l * EmissionFactorsDatabase::default().co2_from_diesel.value;
+        assert!((borehole - gravity - co2).abs() < 1e-6, "{} vs {}", borehole - gravity, co2);
+        assert!(borehole > 2.0 * gravity, "{} vs {}", borehole, gravity);
+
+        // Lift drives the energy; sun-powered pumps have no operating emissions
+        assert!(irrigated_global_warming("Borehole", PumpType::Electric, Some(20.0))
+            < irrigated_global_warming("Borehole", PumpType::Electric, None));
+        assert_eq!(irrigated_global_warming("Borehole", PumpType::Solar, None), gravity);
+    }
+
+    fn equipment(equipment_type: &str, power_source: &str, age: u32) -> FarmEquipment {
+        FarmEquipment {
+            equipment_type: equipment_type.to_string(),
+            power_source: power_source.to_string(),
+            age,
+            hours_per_year: 300.0,
+            fuel_efficiency: None,
+        }
+    }
+
+    fn global_warming_with(equipment: Vec<FarmEquipment>, include_capital_goods: bool) -> f64 {
+        let mut assessment = assessment_with(vec![maize(2.0)]);
+        assessment.methodology.include_capital_goods = include_capital_goods;
+        assessment.equipment_energy = Some(EquipmentEnergy {
+            equipment,
+            energy_sources: Vec::new(),
+            fuel_consumption: Vec::new(),
+        });
+        let mut calc = LCICalculator::new();
+        let inventory = calc.calculate_inventory(&assessment).expect("inventory");
+        calc.calculate_midpoint_impacts(&inventory).expect("impacts")["Global warming"].value
+    }
+
+    #[test]
+    fn capital_goods_weigh_more_on_mechanized_farms() {
+        let mechanized = vec![
+            equipment("Medium Tractor (40-100HP)", "Diesel engine", 4),
+            equipment("Thresher", "Diesel engine", 2),
+        ];
+        let manual = vec![
+            equipment("Hand Tools", "Manual/Human power", 1),
+            equipment("Animal-drawn implements", "Animal power", 3),
+        ];
+
+        // Off by default: equipment lists change nothing
+        assert_eq!(global_warming_with(mechanized.clone(), false), global_warming_with(manual.clone(), false));
+
+        let mechanized_increase = global_warming_with(mechanized.clone(), true) - global_warming_with(mechanized, false);
+        let manual_increase = global_warming_with(manual.clone(), true) - global_warming_with(manual, false);
+        // 16000/12 + 3000/10 and 15/5 + 150/10 kg CO2-eq per year
+        assert!((mechanized_increase - (16000.0 / 12.0 + 300.0)).abs() < 1e-9);
+        assert!((manual_increase - 18.0).abs() < 1e-9);
+        assert!(mechanized_increase > manual_increase);
+
+        // Written off after its lifetime
+        let old_tractor = vec![equipment("Medium Tractor (40-100HP)", "Diesel engine", 15)];
+        assert_eq!(global_warming_with(old_tractor.clone(), true), global_warming_with(old_tractor, false));
+    }
+
+    #[test]
+    fn fuel_and_electricity_are_reported_as_energy_consumption() {
+        use crate::production::lci_extended::LCIExtendedCharacterization;
+
+        let mut assessment = assessment_with(vec![maize(2.0)]);
+        let energy = |assessment: &Assessment| {
+            let mut calc = LCICalculator::new();
+            let inventory = calc.calculate_inventory(assessment).expect("inventory");
+            let impacts = calc.calculate_extended_midpoint_impacts(&inventory, assessment).expect("impacts");
+            (impacts["Energy consumption"].clone(), calc.data_gaps().to_vec())
+        };
+
+        let (unreported, gaps) = energy(&assessment);
+        assert_eq!(unreported.value, 0.0);
+        assert!(gaps.iter().any(|gap| gap.contains("energy consumption is a data gap")), "{:?}", gaps);
+
+        assessment.equipment_energy = Some(EquipmentEnergy {
+            equipment: vec![equipment("Medium Tractor (40-100HP)", "Diesel engine", 4)],
+            energy_sources: vec![EnergyUsage {
+                energy_type: "Grid Electricity".to_string(),
+                monthly_consumption: 50.0,
+                primary_use: "Irrigation pump".to_string(),
+                cost: None,
+                currency: None,
+            }],
+            fuel_consumption: vec![FuelUsage {
+                fuel_type: "Diesel".to_string(),
+                monthly_consumption: 10.0,
+                primary_use: "Tractor".to_string(),
+                cost: None,
+                currency: None,
+            }],
+        });
+        let (reported, gaps) = energy(&assessment);
+        // 120 L diesel × 36 MJ/L + 600 kWh × 3.6 MJ/kWh over 1000 kg of maize
+        assert!((reported.value - (120.0 * 36.0 + 600.0 * 3.6) / 1000.0).abs() < 1e-9, "{}", reported.value);
+        assert_eq!(reported.unit, "MJ per kg");
+        assert_eq!(reported.contributing_sources.len(), 2);
+        assert!(gaps.is_empty(), "{:?}", gaps);
+    }
+}
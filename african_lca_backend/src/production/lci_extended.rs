@@ -1,4 +1,4 @@
-/**
+/*!
  * Extended LCI Characterization Module
  *
  * This module implements comprehensive LCIA characterization factors for:
@@ -15,25 +15,26 @@
  */
 
 use crate::models::*;
-use crate::production::lci::{LCICalculator, InventoryItem, EnvironmentalCompartment};
-use std::collections::HashMap;
+use crate::error::EngineError;
+use crate::production::lci::{LCICalculator, InventoryItem, EnvironmentalCompartment, ACIDIFICATION_NH3};
+use std::collections::BTreeMap;
 use log::info;
 
 /// Extended characterization factors for comprehensive LCIA
 pub trait LCIExtendedCharacterization {
     fn calculate_extended_midpoint_impacts(
         &self,
-        inventory: &HashMap<String, InventoryItem>,
+        inventory: &BTreeMap<String, InventoryItem>,
         assessment: &Assessment,
-    ) -> Result<HashMap<String, MidpointResult>, Box<dyn std::error::Error>>;
+    ) -> Result<BTreeMap<String, MidpointResult>, EngineError>;
 }
 
 impl LCIExtendedCharacterization for LCICalculator {
     fn calculate_extended_midpoint_impacts(
         &self,
-        inventory: &HashMap<String, InventoryItem>,
+        inventory: &BTreeMap<String, InventoryItem>,
         assessment: &Assessment,
-    ) -> Result<HashMap<String, MidpointResult>, Box<dyn std::error::Error>> {
+    ) -> Result<BTreeMap<String, MidpointResult>, EngineError> {
 
         // Start with basic midpoint impacts
         let mut impacts = self.calculate_midpoint_impacts(inventory)?;
@@ -47,30 +48,37 @@ impl LCIExtendedCharacterization for LCICalculator {
             .filter_map(|f| f.area_allocated)
             .sum();
 
-        if total_production_kg == 0.0 {
+        // Nothing produced: no per-kg basis, so the inventory totals are reported as they are
+        if total_production_kg <= 0.0 {
             return Ok(impacts);
         }
 
+        // Only the categories the methodology selects are calculated
+        let selected = |category: &str| assessment.methodology.includes_category(category);
+
         // 1. Water Scarcity (AWARE methodology)
-        if let Some(water_consumption) = impacts.get("Water consumption") {
+        if let Some(water_consumption) = impacts.get("Water consumption").filter(|_| selected("Water scarcity")) {
             let aware_factor = get_aware_factor(&assessment.country, &assessment.region);
             let water_scarcity = water_consumption.value * aware_factor;
 
             impacts.insert("Water scarcity".to_string(), MidpointResult {
                 value: water_scarcity / total_production_kg,
                 unit: "m3 H2O-eq per kg".to_string(),
+                basis: ImpactBasis::PerKgProduct,
                 uncertainty_range: (water_scarcity * 0.7 / total_production_kg,
                                    water_scarcity * 1.3 / total_production_kg),
+                geometric_sd: None,
                 data_quality_score: 0.75,
                 contributing_sources: vec![
                     format!("Water consumption: {:.1} m³ × AWARE factor {:.1} = {:.1} m³ H2O-eq",
                            water_consumption.value, aware_factor, water_scarcity)
                 ],
+                metadata: None,
             });
         }
 
         // 2. Biodiversity Loss (MSA - Mean Species Abundance)
-        if total_area_ha > 0.0 {
+        if total_area_ha > 0.0 && selected("Biodiversity loss") {
             let biodiversity_impact = calculate_biodiversity_impact(
                 &assessment.foods,
                 &assessment.management_practices,
@@ -82,10 +90,10 @@ impl LCIExtendedCharacterization for LCICalculator {
         }
 
         // 3. Soil Degradation
-        if let Some(mgmt) = &assessment.management_practices {
+        if let Some(mgmt) = assessment.management_practices.as_ref().filter(|_| selected("Soil degradation")) {
             let soil_degradation = calculate_soil_degradation(
                 &mgmt.soil_management,
-                &mgmt.fertilization,
+                mgmt.fertilization.as_ref(),
                 &assessment.foods,
                 total_area_ha,
                 total_production_kg
@@ -95,58 +103,67 @@ impl LCIExtendedCharacterization for LCICalculator {
         }
 
         // 4. Marine Eutrophication (from N runoff)
-        let marine_eutro = calculate_marine_eutrophication(
-            inventory,
-            total_production_kg
-        );
-        impacts.insert("Marine eutrophication".to_string(), marine_eutro);
+        if selected("Marine eutrophication") {
+            let marine_eutro = calculate_marine_eutrophication(
+                inventory,
+                total_production_kg
+            );
+            impacts.insert("Marine eutrophication".to_string(), marine_eutro);
+        }
 
         // 5. Terrestrial Acidification (from NH3 and NOx)
-        let terrestrial_acid = calculate_terrestrial_acidification(
-            inventory,
-            &assessment.management_practices,
-            total_production_kg
-        );
-        impacts.insert("Terrestrial acidification".to_string(), terrestrial_acid);
+        if selected("Terrestrial acidification") {
+            let terrestrial_acid = calculate_terrestrial_acidification(
+                inventory,
+                total_production_kg
+            );
+            impacts.insert("Terrestrial acidification".to_string(), terrestrial_acid);
+        }
 
         // 6. Particulate Matter Formation
-        let pm_formation = calculate_particulate_matter(
-            inventory,
-            total_production_kg
-        );
-        impacts.insert("Particulate matter formation".to_string(), pm_formation);
+        if selected("Particulate matter formation") {
+            let pm_formation = calculate_particulate_matter(
+                inventory,
+                total_production_kg
+            );
+            impacts.insert("Particulate matter formation".to_string(), pm_formation);
+        }
 
         // 7. Photochemical Oxidation
-        let photo_ox = calculate_photochemical_oxidation(
-            inventory,
-            total_production_kg
-        );
-        impacts.insert("Photochemical oxidation".to_string(), photo_ox);
+        if selected("Photochemical oxidation") {
+            let photo_ox = calculate_photochemical_oxidation(
+                inventory,
+                total_production_kg
+            );
+            impacts.insert("Photochemical oxidation".to_string(), photo_ox);
+        }
 
         // 8. Fossil Depletion (from fuel consumption)
-        let fossil_dep = calculate_fossil_depletion(
-            inventory,
-            total_production_kg
-        );
-        impacts.insert("Fossil depletion".to_string(), fossil_dep);
+        if selected("Fossil depletion") {
+            let fossil_dep = calculate_fossil_depletion(
+                inventory,
+                total_production_kg
+            );
+            impacts.insert("Fossil depletion".to_string(), fossil_dep);
+        }
 
         // 9. Mineral Depletion (from fertilizer extraction)
-        let mineral_dep = calculate_mineral_depletion(
-            inventory,
-            &assessment.management_practices,
-            total_production_kg
-        );
-        impacts.insert("Mineral depletion".to_string(), mineral_dep);
-
-        // Scale all impacts to per-kg basis
-        for (category, result) in impacts.iter_mut() {
-            if !result.unit.contains("per kg") && category != "Land use" {
-                result.value /= total_production_kg;
-                result.unit = format!("{} per kg", result.unit);
-                result.uncertainty_range = (
-                    result.uncertainty_range.0 / total_production_kg,
-                    result.uncertainty_range.1 / total_production_kg
-                );
+        if selected("Mineral depletion") {
+            let mineral_dep = calculate_mineral_depletion(
+                inventory,
+                &assessment.management_practices,
+                total_production_kg
+            );
+            impacts.insert("Mineral depletion".to_string(), mineral_dep);
+        }
+
+        impacts.retain(|category, _| selected(category)
+            || (category == "Water consumption" && selected("Water scarcity")));
+
+        // Scale the inventory totals to per-kg basis, so every category shares one
+        for result in impacts.values_mut() {
+            if let Some(per_kg) = result.on_basis(ImpactBasis::PerKgProduct, total_production_kg, None) {
+                *result = per_kg;
             }
         }
 
@@ -166,7 +183,7 @@ fn get_aware_factor(country: &Country, region: &Option<String>) -> f64 {
         (Country::Ghana, _) => 15.0, // Southern regions
         (Country::Nigeria, Some(r)) if r.contains("Northern") || r.contains("Sokoto") || r.contains("Kano") => 35.0,
         (Country::Nigeria, _) => 18.0, // Southern regions
-        _ => 20.0, // Global average
+        (Country::Global, _) => GLOBAL_AVERAGE_AWARE,
     }
 }
 
@@ -183,7 +200,7 @@ fn calculate_biodiversity_impact(
     for food in foods {
         if let Some(area_ha) = food.area_allocated {
             // Base MSA loss depends on production system
-            let base_msa_loss = match &food.production_system {
+            let base_msa_loss = match &food.effective_production_system() {
                 Some(ProductionSystem::Intensive) => 0.30, // 30% biodiversity loss
                 Some(ProductionSystem::Organic) => 0.10, // 10% loss
                 Some(ProductionSystem::Agroforestry) => 0.05, // 5% loss
@@ -209,24 +226,27 @@ fn calculate_biodiversity_impact(
             biodiversity_loss += crop_biodiversity_loss;
             sources.push(format!("{}: {:.0} m²·yr (production system: {:?})",
                                 food.name, crop_biodiversity_loss,
-                                food.production_system.as_ref().unwrap_or(&ProductionSystem::Conventional)));
+                                food.effective_production_system().unwrap_or(ProductionSystem::Conventional)));
         }
     }
 
     MidpointResult {
         value: biodiversity_loss / total_production_kg,
-        unit: "PDF·m²·yr per kg".to_string(), // PDF = Potentially Disappeared Fraction
+        unit: "MSA*m2*yr per kg".to_string(), // MSA loss over the occupied area
+        basis: ImpactBasis::PerKgProduct,
         uncertainty_range: (biodiversity_loss * 0.5 / total_production_kg,
                            biodiversity_loss * 1.5 / total_production_kg),
+        geometric_sd: None,
         data_quality_score: 0.6, // Moderate quality - based on production system proxy
         contributing_sources: sources,
+        metadata: None,
     }
 }
 
 fn calculate_soil_degradation(
     soil_mgmt: &SoilManagement,
-    _fertilization: &FertilizationPractices,
-    _foods: &[FoodItem],
+    _fertilization: Option<&FertilizationPractices>,
+    foods: &[FoodItem],
     total_area_ha: f64,
     total_production_kg: f64,
 ) -> MidpointResult {
@@ -237,6 +257,14 @@ fn calculate_soil_degradation(
     // Base soil erosion: 10 tons per hectare per year for conventional tillage
     let base_erosion_t_per_ha = 10.0;
 
+    // Tree crops under shade are not tilled, and canopy and litter cover the soil
+    let shaded_area_ha: f64 = foods.iter()
+        .filter(|food| food.perennial.as_ref().is_some_and(|profile| profile.shade_system))
+        .filter_map(|food| food.area_allocated)
+        .sum::<f64>()
+        .min(total_area_ha);
+    let open_area_ha = total_area_ha - shaded_area_ha;
+
     // Conservation practice adjustments
     let conservation_factor = if soil_mgmt.conservation_practices.iter().any(|p| p.contains("No-till") || p.contains("Minimum")) {
         0.3 // 70% reduction with no-till
@@ -248,17 +276,23 @@ fn calculate_soil_degradation(
         1.0 // No conservation
     };
 
-    let erosion_total = base_erosion_t_per_ha * total_area_ha * conservation_factor;
+    let erosion_total = base_erosion_t_per_ha * open_area_ha * conservation_factor;
     soil_degradation += erosion_total * 1000.0; // Convert to kg
 
     sources.push(format!("Soil erosion: {:.1} t/ha × {:.1} ha × {:.2} conservation factor = {:.0} kg",
-                        base_erosion_t_per_ha, total_area_ha, conservation_factor, erosion_total * 1000.0));
+                        base_erosion_t_per_ha, open_area_ha, conservation_factor, erosion_total * 1000.0));
+    if shaded_area_ha > 0.0 {
+        let shaded_erosion = base_erosion_t_per_ha * shaded_area_ha * 0.2; // 80% less under canopy
+        soil_degradation += shaded_erosion * 1000.0;
+        sources.push(format!("Soil erosion under shade-grown tree crops: {:.1} ha × 0.20 = {:.0} kg",
+                            shaded_area_ha, shaded_erosion * 1000.0));
+    }
 
-    // Soil organic carbon loss
+    // Soil organic carbon loss; shade tree litter keeps up the carbon of shaded plots
     if soil_mgmt.uses_compost {
         sources.push("Compost use: reduces soil degradation".to_string());
-    } else {
-        let soc_loss = total_area_ha * 500.0; // 500 kg C/ha/year loss without organic inputs
+    } else if open_area_ha > 0.0 {
+        let soc_loss = open_area_ha * 500.0; // 500 kg C/ha/year loss without organic inputs
         soil_degradation += soc_loss;
         sources.push(format!("Soil organic carbon loss: {:.0} kg", soc_loss));
     }
@@ -270,15 +304,18 @@ fn calculate_soil_degradation(
     MidpointResult {
         value: soil_degradation / total_production_kg,
         unit: "kg soil-eq per kg".to_string(),
+        basis: ImpactBasis::PerKgProduct,
         uncertainty_range: (soil_degradation * 0.6 / total_production_kg,
                            soil_degradation * 1.4 / total_production_kg),
+        geometric_sd: None,
         data_quality_score: 0.7,
         contributing_sources: sources,
+        metadata: None,
     }
 }
 
 fn calculate_marine_eutrophication(
-    inventory: &HashMap<String, InventoryItem>,
+    inventory: &BTreeMap<String, InventoryItem>,
     total_production_kg: f64,
 ) -> MidpointResult {
 
@@ -286,7 +323,7 @@ fn calculate_marine_eutrophication(
     let mut sources = Vec::new();
 
     // Get nitrate leaching from inventory
-    for (_, item) in inventory {
+    for item in inventory.values() {
         if item.substance.contains("Nitrate") {
             // Nitrate leaching to water → runoff to marine environment
             // Assume 50% reaches marine systems (simplified)
@@ -296,49 +333,51 @@ fn calculate_marine_eutrophication(
         }
     }
 
+    // Direct N discharge to water (aquaculture effluent)
+    for item in inventory.values() {
+        if item.substance == "Nitrogen (N)" && item.compartment == EnvironmentalCompartment::Water {
+            // Same freshwater-to-marine transfer fraction as leached nitrate
+            let marine_n = item.quantity * 0.5;
+            n_runoff += marine_n;
+            sources.push(format!("{}: {:.1} kg N to coastal waters", item.source, marine_n));
+        }
+    }
+
     // Characterization: 1 kg N = 1 kg N-eq (marine eutrophication potential)
     let marine_eutro = n_runoff;
 
     MidpointResult {
         value: marine_eutro / total_production_kg,
         unit: "kg N-eq per kg".to_string(),
+        basis: ImpactBasis::PerKgProduct,
         uncertainty_range: (marine_eutro * 0.5 / total_production_kg,
                            marine_eutro * 1.5 / total_production_kg),
+        geometric_sd: None,
         data_quality_score: 0.65,
         contributing_sources: sources,
+        metadata: None,
     }
 }
 
 fn calculate_terrestrial_acidification(
-    inventory: &HashMap<String, InventoryItem>,
-    management_practices: &Option<ManagementPractices>,
+    inventory: &BTreeMap<String, InventoryItem>,
     total_production_kg: f64,
 ) -> MidpointResult {
 
     let mut so2_eq = 0.0;
     let mut sources = Vec::new();
 
-    // NH3 volatilization from fertilizers (20% of applied N volatilizes)
-    if let Some(mgmt) = management_practices {
-        if mgmt.fertilization.uses_fertilizers {
-            let total_n_applied: f64 = mgmt.fertilization.fertilizer_applications.iter()
-                .map(|app| {
-                    let n_content = get_n_content(&app.fertilizer_type, &app.npk_ratio);
-                    app.application_rate * n_content * app.applications_per_season as f64
-                })
-                .sum();
-
-            let nh3_volatilized = total_n_applied * 0.20; // 20% volatilizes as NH3
-            let nh3_to_so2_eq = nh3_volatilized * (17.0 / 14.0) * 1.88; // NH3 → SO2-eq (characterization factor 1.88)
-
+    // NH3 volatilised from fertilizer and manure, as quantified in the inventory
+    for item in inventory.values() {
+        if item.substance == "Ammonia (NH3)" && item.compartment == EnvironmentalCompartment::Air {
+            let nh3_to_so2_eq = item.quantity * ACIDIFICATION_NH3;
             so2_eq += nh3_to_so2_eq;
-            sources.push(format!("NH3 volatilization: {:.1} kg NH3 ({:.1} kg SO2-eq)",
-                                nh3_volatilized * (17.0 / 14.0), nh3_to_so2_eq));
+            sources.push(format!("{}: {:.1} kg NH3 ({:.1} kg SO2-eq)", item.source, item.quantity, nh3_to_so2_eq));
         }
     }
 
     // NOx from fuel combustion
-    for (_, item) in inventory {
+    for item in inventory.values() {
         if item.source.contains("Diesel") || item.source.contains("Petrol") {
             // Estimate NOx emissions: 0.02 kg NOx per L fuel
             // Extract fuel consumption from source string (simplified)
@@ -354,15 +393,18 @@ fn calculate_terrestrial_acidification(
     MidpointResult {
         value: so2_eq / total_production_kg,
         unit: "kg SO2-eq per kg".to_string(),
+        basis: ImpactBasis::PerKgProduct,
         uncertainty_range: (so2_eq * 0.6 / total_production_kg,
                            so2_eq * 1.4 / total_production_kg),
+        geometric_sd: None,
         data_quality_score: 0.7,
         contributing_sources: sources,
+        metadata: None,
     }
 }
 
 fn calculate_particulate_matter(
-    inventory: &HashMap<String, InventoryItem>,
+    inventory: &BTreeMap<String, InventoryItem>,
     total_production_kg: f64,
 ) -> MidpointResult {
 
@@ -370,7 +412,7 @@ fn calculate_particulate_matter(
     let mut sources = Vec::new();
 
     // PM2.5 from diesel combustion: 0.1 g per L
-    for (_, item) in inventory {
+    for item in inventory.values() {
         if item.source.contains("Diesel") {
             if let Some(fuel_l) = extract_fuel_consumption(&item.source) {
                 let pm25 = fuel_l * 0.0001; // 0.1 g/L = 0.0001 kg/L
@@ -387,16 +429,19 @@ fn calculate_particulate_matter(
 
     MidpointResult {
         value: pm25_eq / total_production_kg,
-        unit: "kg PM2.5-eq per kg".to_string(),
+        unit: "PM2.5-eq per kg".to_string(),
+        basis: ImpactBasis::PerKgProduct,
         uncertainty_range: (pm25_eq * 0.5 / total_production_kg,
                            pm25_eq * 1.5 / total_production_kg),
+        geometric_sd: None,
         data_quality_score: 0.6,
         contributing_sources: sources,
+        metadata: None,
     }
 }
 
 fn calculate_photochemical_oxidation(
-    inventory: &HashMap<String, InventoryItem>,
+    inventory: &BTreeMap<String, InventoryItem>,
     total_production_kg: f64,
 ) -> MidpointResult {
 
@@ -404,7 +449,7 @@ fn calculate_photochemical_oxidation(
     let mut sources = Vec::new();
 
     // NMVOC from fuel combustion: 0.5 g per L diesel, 2 g per L petrol
-    for (_, item) in inventory {
+    for item in inventory.values() {
         if let Some(fuel_l) = extract_fuel_consumption(&item.source) {
             let nmvoc = if item.source.contains("Diesel") {
                 fuel_l * 0.0005 // 0.5 g/L
@@ -422,15 +467,18 @@ fn calculate_photochemical_oxidation(
     MidpointResult {
         value: nmvoc_eq / total_production_kg,
         unit: "kg NMVOC-eq per kg".to_string(),
+        basis: ImpactBasis::PerKgProduct,
         uncertainty_range: (nmvoc_eq * 0.5 / total_production_kg,
                            nmvoc_eq * 1.5 / total_production_kg),
+        geometric_sd: None,
         data_quality_score: 0.65,
         contributing_sources: sources,
+        metadata: None,
     }
 }
 
 fn calculate_fossil_depletion(
-    inventory: &HashMap<String, InventoryItem>,
+    inventory: &BTreeMap<String, InventoryItem>,
     total_production_kg: f64,
 ) -> MidpointResult {
 
@@ -440,7 +488,7 @@ fn calculate_fossil_depletion(
     // Convert fuel consumption to oil equivalents
     // Diesel: 1 L = 0.85 kg oil-eq
     // Petrol: 1 L = 0.83 kg oil-eq
-    for (_, item) in inventory {
+    for item in inventory.values() {
         if let Some(fuel_l) = extract_fuel_consumption(&item.source) {
             let oil_equiv = if item.source.contains("Diesel") {
                 fuel_l * 0.85
@@ -458,15 +506,18 @@ fn calculate_fossil_depletion(
     MidpointResult {
         value: oil_eq / total_production_kg,
         unit: "kg oil-eq per kg".to_string(),
+        basis: ImpactBasis::PerKgProduct,
         uncertainty_range: (oil_eq * 0.9 / total_production_kg,
                            oil_eq * 1.1 / total_production_kg),
+        geometric_sd: None,
         data_quality_score: 0.85, // High quality - direct measurement
         contributing_sources: sources,
+        metadata: None,
     }
 }
 
 fn calculate_mineral_depletion(
-    inventory: &HashMap<String, InventoryItem>,
+    inventory: &BTreeMap<String, InventoryItem>,
     _management_practices: &Option<ManagementPractices>,
     total_production_kg: f64,
 ) -> MidpointResult {
@@ -475,7 +526,7 @@ fn calculate_mineral_depletion(
     let mut sources = Vec::new();
 
     // Read mineral depletion from inventory (phosphate rock, potash)
-    for (_key, item) in inventory {
+    for item in inventory.values() {
         if item.compartment == EnvironmentalCompartment::Resource {
             // Items already in Fe-eq from LCI
             if item.substance.contains("Phosphate") || item.substance.contains("Potash") {
@@ -494,33 +545,18 @@ fn calculate_mineral_depletion(
     MidpointResult {
         value: per_kg_value,
         unit: "kg Fe-eq per kg".to_string(),
+        basis: ImpactBasis::PerKgProduct,
         uncertainty_range: (fe_eq * 0.7 / total_production_kg,
                            fe_eq * 1.3 / total_production_kg),
+        geometric_sd: None,
         data_quality_score: if fe_eq > 0.0 { 0.75 } else { 0.7 },
         contributing_sources: sources,
+        metadata: None,
     }
 }
 
 // Helper functions
 
-fn get_n_content(fertilizer_type: &str, npk_ratio: &Option<String>) -> f64 {
-    match fertilizer_type {
-        "Urea" => 0.46,
-        "DAP" | "Diammonium Phosphate (DAP)" => 0.18,
-        "NPK Compound" | "NPK" => {
-            if let Some(ratio) = npk_ratio {
-                if let Some(n_str) = ratio.split('-').next() {
-                    if let Ok(n_percent) = n_str.trim().parse::<f64>() {
-                        return n_percent / 100.0;
-                    }
-                }
-            }
-            0.15
-        }
-        _ => 0.10,
-    }
-}
-
 fn extract_fuel_consumption(source: &str) -> Option<f64> {
     // Extract fuel consumption from source string like "Diesel: 145 L/month (1740.0 L/year)"
     if let Some(start) = source.find('(') {
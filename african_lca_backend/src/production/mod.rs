@@ -3,9 +3,17 @@ pub mod lca;
 pub mod data;
 pub mod lci;
 pub mod lci_extended;
+pub mod interpretation;
+pub mod ghg_scopes;
+pub mod carbon_footprint;
+pub mod factor_index;
 
 pub use models::*;
 pub use lca::*;
 pub use data::*;
 pub use lci::*;
-pub use lci_extended::*;
\ No newline at end of file
+pub use lci_extended::*;
+pub use interpretation::*;
+pub use ghg_scopes::*;
+pub use carbon_footprint::*;
+pub use factor_index::*;
\ No newline at end of file
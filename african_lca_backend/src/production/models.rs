@@ -1,13 +1,15 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 use uuid::Uuid;
+use crate::error::{EngineError, InputError};
 use chrono::{DateTime, Utc};
 
 // ======================================================================
 // CORE DATA MODELS - Unified for both simple and comprehensive assessments
 // ======================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FoodItem {
     pub id: String,
     pub name: String,
@@ -24,9 +26,30 @@ pub struct FoodItem {
     pub cropping_pattern: Option<CroppingPattern>,
     pub intercropping_partners: Option<Vec<String>>,
     pub post_harvest_losses: Option<f64>, // percentage
+    #[serde(default)]
+    pub aquaculture: Option<AquacultureDetails>, // Fish category only
+    #[serde(default)]
+    pub poultry: Option<PoultryProfile>, // Poultry and Eggs categories only
+    #[serde(default)]
+    pub dairy: Option<DairyHerdProfile>, // Dairy category only
+    #[serde(default)]
+    pub perennial: Option<PerennialCropProfile>, // tree crops: cocoa, cashew, oil palm, mango
+    #[serde(default)]
+    pub head_count: Option<u32>, // animals sold or slaughtered over the year; Meat and Poultry only
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl FoodItem {
+    /// The declared production system, or agroforestry for tree crops grown under shade trees
+    pub fn effective_production_system(&self) -> Option<ProductionSystem> {
+        match &self.perennial {
+            Some(profile) if profile.shade_system => Some(ProductionSystem::Agroforestry),
+            _ => self.production_system,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
 pub enum FoodCategory {
     Cereals,
     Legumes,
@@ -43,7 +66,8 @@ pub enum FoodCategory {
     Other,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
 pub enum ProductionSystem {
     Intensive,
     Extensive,
@@ -55,14 +79,14 @@ pub enum ProductionSystem {
     Conventional,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SeasonalFactor {
     WetSeason,
     DrySeason,
     YearRound,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CroppingPattern {
     Monoculture,
     Intercropping,
@@ -71,17 +95,213 @@ pub enum CroppingPattern {
     CropRotation,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AquacultureDetails {
+    pub system: AquacultureSystem,
+    pub feed_conversion_ratio: f64, // kg feed per kg live-weight gain
+    pub feed_protein_percent: Option<f64>, // defaults to 30% crude protein
+    pub feed_phosphorus_percent: Option<f64>, // defaults to 1.0% P
+    #[serde(default)]
+    pub effluent_treatment: bool, // settling ponds, constructed wetlands, RAS solids removal
+    #[serde(default)]
+    pub feed_type: Option<AquaFeedType>, // defaults to commercial pellets
+    #[serde(default)]
+    pub aeration_hours_per_day: Option<f64>, // paddlewheels or blowers; defaults by system
+    #[serde(default)]
+    pub water_exchange_rate: Option<f64>, // % of the culture volume replaced a day; defaults by system
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AquacultureSystem {
+    EarthenPond,
+    ConcreteTank,
+    Cage,
+    RecirculatingSystem,
+}
+
+impl AquacultureSystem {
+    /// Typical annual harvest per m3 of culture volume (kg), from which the volume to aerate
+    /// and exchange is estimated: fertilized ponds yield 5-8 t/ha at 1.2 m depth, tanks and
+    /// RAS are stocked far more densely, cages sit in open water
+    pub fn annual_yield_kg_per_m3(&self) -> f64 {
+        match self {
+            AquacultureSystem::EarthenPond => 0.6,
+            AquacultureSystem::ConcreteTank => 20.0,
+            AquacultureSystem::Cage => 50.0,
+            AquacultureSystem::RecirculatingSystem => 60.0,
+        }
+    }
+
+    /// Aerator or blower power per 1,000 m3 of culture volume (kW)
+    pub fn aeration_kw_per_1000_m3(&self) -> f64 {
+        match self {
+            AquacultureSystem::EarthenPond => 0.75, // one 1 hp paddlewheel per 0.1 ha
+            AquacultureSystem::ConcreteTank => 5.0,
+            AquacultureSystem::Cage => 0.0, // open water exchanges with the lake or reservoir
+            AquacultureSystem::RecirculatingSystem => 20.0, // blowers, pumps and biofilter
+        }
+    }
+
+    pub fn default_aeration_hours(&self) -> f64 {
+        match self {
+            AquacultureSystem::EarthenPond | AquacultureSystem::Cage => 0.0,
+            AquacultureSystem::ConcreteTank => 12.0,
+            AquacultureSystem::RecirculatingSystem => 24.0,
+        }
+    }
+
+    /// Daily water exchange (% of volume): pond top-up for seepage and evaporation, flushing
+    /// of tanks, and RAS make-up water
+    pub fn default_water_exchange_percent(&self) -> f64 {
+        match self {
+            AquacultureSystem::EarthenPond => 1.0,
+            AquacultureSystem::ConcreteTank => 20.0,
+            AquacultureSystem::Cage => 0.0,
+            AquacultureSystem::RecirculatingSystem => 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AquaFeedType {
+    CommercialPellets, // extruded or sinking pellets of soybean meal, maize and some fishmeal
+    FarmMade, // local bran, maize and oilseed cake, mixed on the farm
+    HighFishmeal, // high-protein feeds, e.g. catfish starter diets
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoultryProfile {
+    pub system: PoultrySystem,
+    pub flock_size: u32, // average birds housed over the year
+    pub feed_conversion_ratio: Option<f64>, // kg feed per kg product; defaults by category
+    pub feed_protein_percent: Option<f64>, // defaults to 17% crude protein
+    pub manure_system: Option<PoultryManureSystem>, // defaults by housing system
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PoultrySystem {
+    Battery,
+    DeepLitter,
+    FreeRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PoultryManureSystem {
+    Litter, // dry litter removed between flocks
+    Slurry, // droppings collected wet in pits or on belts under cages
+    Range, // deposited outdoors on the range
+}
+
+impl PoultrySystem {
+    pub fn default_manure_system(&self) -> PoultryManureSystem {
+        match self {
+            PoultrySystem::Battery => PoultryManureSystem::Slurry,
+            PoultrySystem::DeepLitter => PoultryManureSystem::Litter,
+            PoultrySystem::FreeRange => PoultryManureSystem::Range,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DairyHerdProfile {
+    pub herd_size: u32, // cows in milk over the year
+    pub milk_yield_kg_per_cow: f64, // kg milk per cow per year for the breed
+    pub feed_intake_kg_dm_per_day: Option<f64>, // defaults from maintenance plus milk yield
+    pub feed_digestibility_percent: Option<f64>, // defaults to 60% DE (tropical grass and residues)
+    pub feed_protein_percent: Option<f64>, // defaults to 12% crude protein
+    #[serde(default)]
+    pub concentrate_kg_per_day: f64, // purchased concentrate per cow, part of the intake
+    pub manure_system: Option<DairyManureSystem>, // defaults to pasture
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DairyManureSystem {
+    Pasture, // deposited while grazing
+    SolidStorage, // kraal or heap storage
+    DailySpread, // collected and spread on fields daily
+    Liquid, // slurry tank or lagoon
+}
+
+/// A tree crop's plantation, whose establishment (nursery, land preparation and the
+/// immature years of inputs without harvest) is amortized over its productive years
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerennialCropProfile {
+    pub plantation_age_years: u32, // years since planting
+    pub expected_productive_lifetime_years: Option<f64>, // bearing years; defaults by crop
+    #[serde(default)]
+    pub establishment_inputs: EstablishmentInputs,
+    #[serde(default)]
+    pub shade_system: bool, // shade trees kept over the crop (agroforestry)
+}
+
+/// What went into establishing a hectare of plantation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EstablishmentInputs {
+    pub immature_years: Option<u32>, // years before first harvest; defaults by crop
+    pub seedlings_per_ha: Option<f64>, // defaults to the crop's usual planting density
+    #[serde(default)]
+    pub land_preparation_diesel_l_per_ha: f64, // 0 for manual clearing
+    #[serde(default)]
+    pub fertilizer_n_kg_per_ha_per_year: f64, // over the immature years
+}
+
+impl PerennialCropProfile {
+    /// Typical (immature years, productive lifetime, seedlings per ha) of a tree crop
+    fn crop_defaults(crop: &str) -> (u32, f64, f64) {
+        let crop = crop.to_lowercase();
+        if crop.contains("cocoa") {
+            (3, 25.0, 1111.0) // 3 m × 3 m
+        } else if crop.contains("cashew") {
+            (3, 30.0, 100.0)
+        } else if crop.contains("palm") {
+            (3, 25.0, 143.0) // 9 m triangular
+        } else if crop.contains("mango") {
+            (4, 30.0, 100.0)
+        } else {
+            (3, 20.0, 400.0)
+        }
+    }
+
+    pub fn immature_years(&self, crop: &str) -> u32 {
+        self.establishment_inputs.immature_years.unwrap_or(Self::crop_defaults(crop).0)
+    }
+
+    pub fn productive_lifetime_years(&self, crop: &str) -> f64 {
+        self.expected_productive_lifetime_years
+            .filter(|years| *years > 0.0)
+            .unwrap_or(Self::crop_defaults(crop).1)
+    }
+
+    pub fn seedlings_per_ha(&self, crop: &str) -> f64 {
+        self.establishment_inputs.seedlings_per_ha.unwrap_or(Self::crop_defaults(crop).2)
+    }
+
+    /// Whether this year's harvest still carries a share of the establishment: the
+    /// plantation bears and is not yet past its expected productive lifetime
+    pub fn amortizing(&self, crop: &str) -> bool {
+        let age = f64::from(self.plantation_age_years);
+        let immature = f64::from(self.immature_years(crop));
+        age >= immature && age < immature + self.productive_lifetime_years(crop)
+    }
+}
+
 // ======================================================================
 // ASSESSMENT STRUCTURE
 // ======================================================================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Assessment {
+    #[serde(default = "crate::schema::current_schema_version")]
+    pub schema_version: String, // layout of this document, see `schema`
     pub id: Uuid,
     pub company_name: String,
     pub country: Country,
     pub currency: Currency, // Primary currency for this assessment
+    #[serde(default)]
+    pub exchange_rates: ExchangeRates, // costs in other currencies are converted at these
     pub region: Option<String>,
+    #[serde(default)]
+    pub coordinates: Option<GeoCoordinates>, // farm location; selects the climate zone when given
     pub foods: Vec<FoodItem>,
     pub assessment_date: DateTime<Utc>,
     pub methodology: LCAMethodology,
@@ -93,6 +313,169 @@ pub struct Assessment {
     pub equipment_energy: Option<EquipmentEnergy>, // NEW: Equipment and energy data
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoCoordinates {
+    pub latitude: f64,  // decimal degrees, north positive
+    pub longitude: f64, // decimal degrees, east positive
+}
+
+/// Climate zone a farm sits in; each has its own set of climate adjustments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ClimateZone {
+    Arid,          // Sahel and Sahara fringe, < 400 mm rain
+    SemiArid,      // Sudan/Guinea savanna, 400-1000 mm
+    HumidTropical, // forest belt and coast, > 1000 mm
+}
+
+impl ClimateZone {
+    /// Prefix of this zone's entries in the climate adjustment table
+    pub fn key_prefix(&self) -> &'static str {
+        match self {
+            ClimateZone::Arid => "Arid",
+            ClimateZone::SemiArid => "SemiArid",
+            ClimateZone::HumidTropical => "HumidTropical",
+        }
+    }
+}
+
+/// Agro-ecological zone of a region, finer than ClimateZone; the climate adjustment rules
+/// are keyed by it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AgroEcologicalZone {
+    Sahel,               // < 600 mm, one short rainy season
+    SudanSavannah,       // 600-1000 mm
+    GuineaSavannah,      // 1000-1300 mm, one rainy season
+    CoastalSavannah,     // dry coastal belt around Accra, 700-900 mm
+    DerivedSavannah,     // forest-savannah transition, two rainy seasons
+    SemiDeciduousForest, // 1200-1600 mm
+    HighRainforest,      // > 1600 mm, lowland rainforest
+    MangroveSwamp,       // Niger Delta, permanently wet
+}
+
+impl AgroEcologicalZone {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AgroEcologicalZone::Sahel => "Sahel",
+            AgroEcologicalZone::SudanSavannah => "Sudan savannah",
+            AgroEcologicalZone::GuineaSavannah => "Guinea savannah",
+            AgroEcologicalZone::CoastalSavannah => "Coastal savannah",
+            AgroEcologicalZone::DerivedSavannah => "Derived savannah",
+            AgroEcologicalZone::SemiDeciduousForest => "Semi-deciduous forest",
+            AgroEcologicalZone::HighRainforest => "High rainforest",
+            AgroEcologicalZone::MangroveSwamp => "Mangrove swamp",
+        }
+    }
+
+    /// Broad climate zone the agro-ecological zone belongs to
+    pub fn climate_zone(&self) -> ClimateZone {
+        match self {
+            AgroEcologicalZone::Sahel => ClimateZone::Arid,
+            AgroEcologicalZone::SudanSavannah
+            | AgroEcologicalZone::GuineaSavannah
+            | AgroEcologicalZone::CoastalSavannah => ClimateZone::SemiArid,
+            AgroEcologicalZone::DerivedSavannah
+            | AgroEcologicalZone::SemiDeciduousForest
+            | AgroEcologicalZone::HighRainforest
+            | AgroEcologicalZone::MangroveSwamp => ClimateZone::HumidTropical,
+        }
+    }
+}
+
+// Scenario builders: each returns a modified, unassessed copy for "test an intervention" runs
+impl Assessment {
+    /// Midpoint results per kg of total production as (value, unit without "per kg"), or
+    /// None before the assessment has run or without production
+    pub fn midpoint_impacts_per_kg(&self) -> Option<HashMap<String, (f64, String)>> {
+        let results = self.results.as_ref()?;
+        let production_kg: f64 = self.foods.iter().map(|food| food.quantity_kg).sum();
+        results.midpoint_impacts.iter()
+            .map(|(category, result)| {
+                let per_kg = result.on_basis(ImpactBasis::PerKgProduct, production_kg, None)?;
+                Some((category.clone(), (per_kg.value, StructuredUnit::parse(&per_kg.unit).numerator)))
+            })
+            .collect()
+    }
+
+    /// Compare the results with per-kg `targets` (midpoint category -> limit) and store the
+    /// outcome in LCAResults::target_compliance. Categories without a target, or targets for
+    /// categories that were not assessed, get no entry.
+    pub fn check_targets(&mut self, targets: &HashMap<String, f64>) -> Result<(), EngineError> {
+        let per_kg = self.midpoint_impacts_per_kg()
+            .ok_or_else(|| EngineError::NotApplicable("Targets can only be checked on an assessed farm with production".to_string()))?;
+        let compliance = crate::production::interpretation::target_compliance(&per_kg, "kg", targets);
+        if let Some(results) = self.results.as_mut() {
+            results.target_compliance = Some(compliance);
+        }
+        Ok(())
+    }
+
+    /// Switch to the given irrigation system (e.g. "Drip irrigation")
+    pub fn with_irrigation(&self, irrigation_system: &str) -> Assessment {
+        self.scenario(|mp| mp.water_management.irrigation_system = Some(irrigation_system.to_string()))
+    }
+
+    /// Adopt (or drop) integrated pest management. Scouting replaces calendar spraying,
+    /// so adopting IPM halves the number of pesticide applications (rounded up).
+    pub fn with_ipm(&self, uses_ipm: bool) -> Assessment {
+        self.scenario(|mp| {
+            let pest = &mut mp.pest_management;
+            if uses_ipm && !pest.uses_ipm {
+                pest.management_approach = "Integrated Pest Management".to_string();
+                for pesticide in pest.pesticides_used.iter_mut() {
+                    pesticide.applications_per_season = pesticide.applications_per_season.div_ceil(2);
+                }
+            }
+            pest.uses_ipm = uses_ipm;
+        })
+    }
+
+    /// Cut every fertilizer application rate by `fraction` (0.2 = 20% less)
+    pub fn with_fertilizer_reduction(&self, fraction: f64) -> Assessment {
+        let factor = 1.0 - fraction.clamp(0.0, 1.0);
+        self.scenario(|mp| {
+            for application in mp.fertilization.iter_mut().flat_map(|f| f.fertilizer_applications.iter_mut()) {
+                application.application_rate *= factor;
+            }
+        })
+    }
+
+    /// Apply compost from the given source
+    pub fn with_compost(&self, source: &str) -> Assessment {
+        self.scenario(|mp| {
+            mp.soil_management.uses_compost = true;
+            mp.soil_management.compost_source = Some(source.to_string());
+        })
+    }
+
+    /// Add a soil conservation practice (e.g. "No-till", "Contour farming")
+    pub fn with_conservation_practice(&self, practice: &str) -> Assessment {
+        self.scenario(|mp| {
+            if !mp.soil_management.conservation_practices.iter().any(|p| p == practice) {
+                mp.soil_management.conservation_practices.push(practice.to_string());
+            }
+        })
+    }
+
+    /// Base soil testing and fertilizer rates on a nutrient management plan. Fertilization
+    /// that was not reported stays unreported.
+    pub fn with_nutrient_plan(&self) -> Assessment {
+        self.scenario(|mp| {
+            if let Some(fertilization) = mp.fertilization.as_mut() {
+                fertilization.soil_test_based = true;
+                fertilization.follows_nutrient_plan = true;
+            }
+        })
+    }
+
+    fn scenario(&self, mutate: impl FnOnce(&mut ManagementPractices)) -> Assessment {
+        let mut scenario = self.clone();
+        scenario.id = Uuid::new_v4();
+        scenario.results = None;
+        mutate(scenario.management_practices.get_or_insert_with(ManagementPractices::baseline));
+        scenario
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FarmProfile {
     pub farmer_name: String,
@@ -105,7 +488,7 @@ pub struct FarmProfile {
     pub participates_in_programs: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FarmType {
     Smallholder,
     SmallScale,
@@ -115,7 +498,7 @@ pub enum FarmType {
     MixedLivestock,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FarmingSystem {
     Subsistence,
     SemiCommercial,
@@ -129,11 +512,45 @@ pub enum FarmingSystem {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ManagementPractices {
     pub soil_management: SoilManagement,
-    pub fertilization: FertilizationPractices,
+    pub fertilization: Option<FertilizationPractices>, // None when not reported, unlike uses_fertilizers: false
     pub water_management: WaterManagement,
     pub pest_management: PestManagement,
 }
 
+impl ManagementPractices {
+    /// Conventional practice with nothing declared, used when a scenario adds the first practice
+    pub fn baseline() -> Self {
+        Self {
+            soil_management: SoilManagement {
+                soil_type: None,
+                uses_compost: false,
+                compost_source: None,
+                conservation_practices: vec![],
+                soil_testing_frequency: None,
+            },
+            fertilization: Some(FertilizationPractices {
+                uses_fertilizers: false,
+                fertilizer_applications: vec![],
+                soil_test_based: false,
+                follows_nutrient_plan: false,
+            }),
+            water_management: WaterManagement {
+                water_source: vec![],
+                irrigation_system: None,
+                water_conservation_practices: vec![],
+                pump_type: None,
+                lift_height_m: None,
+            },
+            pest_management: PestManagement {
+                management_approach: "Conventional".to_string(),
+                uses_ipm: false,
+                pesticides_used: vec![],
+                monitoring_frequency: None,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SoilManagement {
     pub soil_type: Option<SoilType>,
@@ -143,7 +560,7 @@ pub struct SoilManagement {
     pub soil_testing_frequency: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SoilType {
     Sandy,
     Clay,
@@ -171,6 +588,8 @@ pub struct FertilizerApplication {
     pub applications_per_season: u32,
     pub cost: Option<f64>,
     pub currency: Option<Currency>,
+    #[serde(default)]
+    pub treated_area_ha: Option<f64>, // defaults to total cultivated area
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -178,6 +597,58 @@ pub struct WaterManagement {
     pub water_source: Vec<String>,
     pub irrigation_system: Option<String>,
     pub water_conservation_practices: Vec<String>,
+    #[serde(default)]
+    pub pump_type: Option<PumpType>, // None: pumping energy not modeled
+    #[serde(default)]
+    pub lift_height_m: Option<f64>, // defaults by water source
+}
+
+impl WaterManagement {
+    /// Height the water is lifted from its source (m): the reported lift, else a typical
+    /// depth for the deepest source named
+    pub fn lift_height_m(&self) -> f64 {
+        if let Some(lift) = self.lift_height_m {
+            return lift.max(0.0);
+        }
+        let named = |source: &str| self.water_source.iter().any(|s| s.to_lowercase().contains(source));
+        if named("borehole") {
+            60.0
+        } else if named("well") {
+            15.0
+        } else {
+            5.0 // rivers, dams and ponds
+        }
+    }
+
+    /// Pressure the irrigation system runs at, as metres of head on top of the lift
+    pub fn operating_head_m(&self) -> f64 {
+        match self.irrigation_system.as_deref() {
+            Some("Drip irrigation") | Some("Drip Irrigation") => 10.0,
+            Some("Sprinkler") | Some("Sprinkler System") => 30.0,
+            _ => 0.0, // surface irrigation
+        }
+    }
+}
+
+/// What lifts irrigation water
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PumpType {
+    Diesel,
+    Electric,
+    Solar,
+    Gravity,
+}
+
+impl PumpType {
+    /// Share of the energy bought (diesel or grid electricity) that reaches the water:
+    /// a 50% efficient pump, driven by a 25% efficient small diesel engine or a 90% motor
+    pub fn efficiency(&self) -> f64 {
+        match self {
+            PumpType::Diesel => 0.5 * 0.25,
+            PumpType::Electric | PumpType::Solar => 0.5 * 0.9,
+            PumpType::Gravity => 1.0,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -195,6 +666,8 @@ pub struct PesticideApplication {
     pub application_rate: f64,
     pub applications_per_season: u32,
     pub target_pests: Vec<String>,
+    #[serde(default)]
+    pub treated_area_ha: Option<f64>, // defaults to total cultivated area
 }
 
 // ======================================================================
@@ -236,6 +709,7 @@ pub struct FuelUsage {
     pub monthly_consumption: f64, // liters
     pub primary_use: String,
     pub cost: Option<f64>,
+    pub currency: Option<Currency>,
 }
 
 // ======================================================================
@@ -250,9 +724,179 @@ pub struct LCAMethodology {
     pub characterization_method: CharacterizationMethod,
     pub normalization_method: Option<NormalizationMethod>,
     pub weighting_method: Option<WeightingMethod>,
+    #[serde(default = "default_include_inventory")]
+    pub include_inventory: bool, // attach the elementary flows to LCAResults
+    #[serde(default)]
+    pub include_capital_goods: bool, // amortize equipment manufacturing into farm results
+    #[serde(default)]
+    pub modeling_approach: ModelingApproach,
+    #[serde(default)]
+    pub impact_categories: Option<Vec<String>>, // midpoint categories to assess; None assesses all
+    #[serde(default)]
+    pub include_result_metadata: bool, // attach ResultMetadata to midpoint and endpoint results
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_include_inventory() -> bool {
+    true
+}
+
+impl LCAMethodology {
+    /// Farm assessments: cradle to farm gate per kg of product, normalized against African
+    /// references and weighted with African priorities
+    pub fn african_production() -> Self {
+        Self {
+            functional_unit: "1 kg product".to_string(),
+            system_boundary: SystemBoundary::CradleToGate,
+            allocation_method: AllocationMethod::Mass,
+            characterization_method: CharacterizationMethod::IpccAr6,
+            normalization_method: Some(NormalizationMethod::AfricanContext),
+            weighting_method: Some(WeightingMethod::AfricanPriorities),
+            include_inventory: true,
+            include_capital_goods: false,
+            modeling_approach: ModelingApproach::Attributional,
+            impact_categories: None,
+            include_result_metadata: false,
+        }
+    }
+
+    /// Facility assessments: gate to gate per tonne of product. Facility construction and
+    /// machinery are outside the gate-to-gate scope, so capital goods stay out.
+    pub fn african_processing() -> Self {
+        Self {
+            functional_unit: "1 tonne product".to_string(),
+            system_boundary: SystemBoundary::GateToGate,
+            ..Self::african_production()
+        }
+    }
+
+    /// ISO 14044 without its optional steps: cradle to gate per kg, no normalization or
+    /// weighting
+    pub fn iso_default() -> Self {
+        Self {
+            normalization_method: None,
+            weighting_method: None,
+            ..Self::african_production()
+        }
+    }
+
+    /// Builder starting from this methodology, e.g. a preset
+    pub fn to_builder(self) -> LCAMethodologyBuilder {
+        LCAMethodologyBuilder { methodology: self }
+    }
+
+    /// Note for the results when the selected characterization method is one the engines
+    /// do not apply, so its name is not mistaken for the method behind the impacts
+    pub fn characterization_warning(&self) -> Option<String> {
+        (!self.characterization_method.is_implemented()).then(|| format!(
+            "Characterization method {:?} is not implemented; impacts were characterized with the engine's own factors. Implemented methods: {}",
+            self.characterization_method,
+            CharacterizationMethod::IMPLEMENTED.map(|method| format!("{:?}", method)).join(", ")
+        ))
+    }
+
+    /// Whether `category` is among the categories to assess
+    pub fn includes_category(&self, category: &str) -> bool {
+        self.impact_categories.as_ref()
+            .is_none_or(|categories| categories.iter().any(|c| c == category))
+    }
+
+    /// The selected categories must be non-empty and among the engine's `known` categories
+    pub fn validate_impact_categories(&self, known: &[String]) -> Result<(), InputError> {
+        let Some(categories) = &self.impact_categories else {
+            return Ok(());
+        };
+        if categories.is_empty() {
+            return Err(InputError::invalid("impact_categories", "empty; omit it to assess all categories"));
+        }
+        if let Some(unknown) = categories.iter().find(|c| !known.contains(c)) {
+            return Err(InputError::UnknownValue {
+                field: "impact_categories".to_string(),
+                value: unknown.clone(),
+                valid: known.to_vec(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Customizes an `LCAMethodology`, by default `LCAMethodology::iso_default()`
+#[derive(Debug, Clone)]
+pub struct LCAMethodologyBuilder {
+    methodology: LCAMethodology,
+}
+
+impl Default for LCAMethodologyBuilder {
+    fn default() -> Self {
+        LCAMethodology::iso_default().to_builder()
+    }
+}
+
+impl LCAMethodologyBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn functional_unit(mut self, functional_unit: impl Into<String>) -> Self {
+        self.methodology.functional_unit = functional_unit.into();
+        self
+    }
+
+    pub fn system_boundary(mut self, system_boundary: SystemBoundary) -> Self {
+        self.methodology.system_boundary = system_boundary;
+        self
+    }
+
+    pub fn allocation_method(mut self, allocation_method: AllocationMethod) -> Self {
+        self.methodology.allocation_method = allocation_method;
+        self
+    }
+
+    pub fn characterization_method(mut self, characterization_method: CharacterizationMethod) -> Self {
+        self.methodology.characterization_method = characterization_method;
+        self
+    }
+
+    pub fn normalization_method(mut self, normalization_method: Option<NormalizationMethod>) -> Self {
+        self.methodology.normalization_method = normalization_method;
+        self
+    }
+
+    pub fn weighting_method(mut self, weighting_method: Option<WeightingMethod>) -> Self {
+        self.methodology.weighting_method = weighting_method;
+        self
+    }
+
+    pub fn include_inventory(mut self, include_inventory: bool) -> Self {
+        self.methodology.include_inventory = include_inventory;
+        self
+    }
+
+    pub fn include_capital_goods(mut self, include_capital_goods: bool) -> Self {
+        self.methodology.include_capital_goods = include_capital_goods;
+        self
+    }
+
+    pub fn modeling_approach(mut self, modeling_approach: ModelingApproach) -> Self {
+        self.methodology.modeling_approach = modeling_approach;
+        self
+    }
+
+    pub fn impact_categories(mut self, impact_categories: Option<Vec<String>>) -> Self {
+        self.methodology.impact_categories = impact_categories;
+        self
+    }
+
+    pub fn include_result_metadata(mut self, include_result_metadata: bool) -> Self {
+        self.methodology.include_result_metadata = include_result_metadata;
+        self
+    }
+
+    pub fn build(self) -> LCAMethodology {
+        self.methodology
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SystemBoundary {
     CradleToGate,
     CradleToGrave,
@@ -260,7 +904,7 @@ pub enum SystemBoundary {
     FarmToFork,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AllocationMethod {
     Mass,
     Economic,
@@ -268,7 +912,18 @@ pub enum AllocationMethod {
     Causal,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Attributional results share out the average burdens of the system; consequential results
+/// follow the change a decision causes. Under `Consequential` the processing engine uses
+/// marginal grid factors and credits utilized byproducts by system expansion instead of
+/// allocating shared burdens to them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModelingApproach {
+    #[default]
+    Attributional,
+    Consequential,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CharacterizationMethod {
     IpccAr6,
     IpccAr5,
@@ -278,7 +933,21 @@ pub enum CharacterizationMethod {
     CML,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl CharacterizationMethod {
+    /// Methods the engines characterize with: IPCC GWP100 for climate change and ReCiPe 2016
+    /// midpoint-to-endpoint factors. The others are accepted but not applied.
+    pub const IMPLEMENTED: [CharacterizationMethod; 3] = [
+        CharacterizationMethod::IpccAr6,
+        CharacterizationMethod::IpccAr5,
+        CharacterizationMethod::ReCiPe2016,
+    ];
+
+    pub fn is_implemented(&self) -> bool {
+        Self::IMPLEMENTED.contains(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NormalizationMethod {
     AfricanContext,
     GlobalContext,
@@ -286,16 +955,63 @@ pub enum NormalizationMethod {
     None,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WeightingMethod {
     AfricanPriorities,
     EqualWeights,
     ExpertJudgment,
     SocialPreferences,
+    CustomWeights(BTreeMap<String, f64>), // endpoint category -> weight
     None,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+impl WeightingMethod {
+    pub const ENDPOINT_CATEGORIES: [&'static str; 3] = ["Human Health", "Ecosystem Quality", "Resource Scarcity"];
+
+    /// Endpoint weights (summing to 1.0). Weighting is a value choice (ISO 14044 4.4.3.4),
+    /// so each set states whose values it represents.
+    pub fn weights(&self) -> BTreeMap<String, f64> {
+        let set = |hh: f64, eq: f64, rs: f64| BTreeMap::from([
+            ("Human Health".to_string(), hh),
+            ("Ecosystem Quality".to_string(), eq),
+            ("Resource Scarcity".to_string(), rs),
+        ]);
+        match self {
+            // Health burdens weigh heaviest where health systems are stretched
+            WeightingMethod::AfricanPriorities => set(0.40, 0.35, 0.25),
+            // Eco-indicator 99 / ReCiPe hierarchist panel average
+            WeightingMethod::ExpertJudgment => set(0.40, 0.40, 0.20),
+            // Stated-preference surveys in West Africa rank health and livelihood resources
+            // (water, land, soil fertility) above ecosystems
+            WeightingMethod::SocialPreferences => set(0.50, 0.20, 0.30),
+            WeightingMethod::CustomWeights(weights) => weights.clone(),
+            // Equal weighting (ISO default), also used when no method is chosen
+            WeightingMethod::EqualWeights | WeightingMethod::None => set(0.333, 0.333, 0.334),
+        }
+    }
+
+    /// Weights must be non-negative, name known endpoint categories and sum to 1.0 (±0.01)
+    pub fn validate(&self) -> Result<(), InputError> {
+        let weights = self.weights();
+        if let Some(category) = weights.keys().find(|c| !Self::ENDPOINT_CATEGORIES.contains(&c.as_str())) {
+            return Err(InputError::UnknownValue {
+                field: "weighting_method".to_string(),
+                value: category.clone(),
+                valid: Self::ENDPOINT_CATEGORIES.iter().map(|c| c.to_string()).collect(),
+            });
+        }
+        if let Some((category, weight)) = weights.iter().find(|(_, w)| **w < 0.0) {
+            return Err(InputError::invalid(format!("weighting_method.{}", category), format!("cannot be negative, got {}", weight)));
+        }
+        let total: f64 = weights.values().sum();
+        if (total - 1.0).abs() > 0.01 {
+            return Err(InputError::invalid("weighting_method", format!("endpoint weights sum to {:.3}, expected 1.0", total)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Country {
     Ghana,
     Nigeria,
@@ -332,7 +1048,7 @@ impl Country {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Currency {
     GHS, // Ghana Cedi
     NGN, // Nigerian Naira
@@ -365,6 +1081,189 @@ impl Currency {
     }
 }
 
+impl FromStr for Currency {
+    type Err = String;
+
+    /// ISO 4217 code, in any case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "GHS" => Ok(Currency::GHS),
+            "NGN" => Ok(Currency::NGN),
+            "USD" => Ok(Currency::USD),
+            _ => Err(format!("Unsupported currency: {} (use GHS, NGN or USD)", s)),
+        }
+    }
+}
+
+/// Units of each currency per US dollar, and the period they hold for. Costs in different
+/// currencies are converted through the dollar at these rates; callers with current rates
+/// pass their own in the runner config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    pub as_of: String, // e.g. "2024 averages" or a date
+    pub ghs_per_usd: f64,
+    pub ngn_per_usd: f64,
+}
+
+impl Default for ExchangeRates {
+    /// 2024 averages of the Bank of Ghana and Central Bank of Nigeria interbank rates, rounded
+    fn default() -> Self {
+        Self {
+            as_of: "2024 averages".to_string(),
+            ghs_per_usd: 15.0,
+            ngn_per_usd: 1_500.0,
+        }
+    }
+}
+
+impl ExchangeRates {
+    pub fn per_usd(&self, currency: &Currency) -> f64 {
+        match currency {
+            Currency::GHS => self.ghs_per_usd,
+            Currency::NGN => self.ngn_per_usd,
+            Currency::USD => 1.0,
+        }
+    }
+
+    /// `amount` of `from` in `to`, converted through the US dollar
+    pub fn convert(&self, amount: f64, from: &Currency, to: &Currency) -> f64 {
+        if from == to {
+            amount
+        } else {
+            amount / self.per_usd(from) * self.per_usd(to)
+        }
+    }
+
+    /// Rates must be positive for conversions to mean anything
+    pub fn validate(&self) -> Result<(), InputError> {
+        for (field, rate) in [("exchange_rates.ghs_per_usd", self.ghs_per_usd), ("exchange_rates.ngn_per_usd", self.ngn_per_usd)] {
+            if !(rate.is_finite() && rate > 0.0) {
+                return Err(InputError::invalid(field, format!("{} is not a positive exchange rate", rate)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Annual spend on the costed farm inputs, summed in one reporting currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostRollup {
+    pub currency: Currency,
+    pub exchange_rates: ExchangeRates, // line items in other currencies were converted at these
+    pub fertilizer: f64,
+    pub energy: f64, // energy sources and fuel
+    pub warnings: Vec<String>,
+}
+
+impl CostRollup {
+    pub fn total(&self) -> f64 {
+        self.fertilizer + self.energy
+    }
+}
+
+impl Assessment {
+    /// Annual fertilizer and energy spend in the assessment's currency. Every line item is
+    /// converted from its own currency before summing; a cost entered without a currency
+    /// is taken to be in the assessment's and gets a warning.
+    pub fn cost_rollup(&self) -> CostRollup {
+        let mut rollup = CostRollup {
+            currency: self.currency,
+            exchange_rates: self.exchange_rates.clone(),
+            fertilizer: 0.0,
+            energy: 0.0,
+            warnings: Vec::new(),
+        };
+        let mut convert = |amount: f64, currency: &Option<Currency>, item: String| match currency {
+            Some(currency) => self.exchange_rates.convert(amount, currency, &self.currency),
+            None => {
+                rollup.warnings.push(format!(
+                    "Cost of {} has no currency; assumed to be {}", item, self.currency.code()
+                ));
+                amount
+            }
+        };
+
+        let mut fertilizer = 0.0;
+        let fertilizer_applications = self.management_practices.iter()
+            .flat_map(|p| p.fertilization.iter())
+            .flat_map(|f| f.fertilizer_applications.iter());
+        for app in fertilizer_applications {
+            // Cost is entered per application
+            if let Some(cost) = app.cost {
+                fertilizer += convert(cost * app.applications_per_season as f64, &app.currency, app.fertilizer_type.clone());
+            }
+        }
+
+        let mut energy = 0.0;
+        if let Some(ref equipment_energy) = self.equipment_energy {
+            // Energy cost is entered per month, fuel cost per liter
+            for source in &equipment_energy.energy_sources {
+                if let Some(cost) = source.cost {
+                    energy += convert(cost * 12.0, &source.currency, source.energy_type.clone());
+                }
+            }
+            for fuel in &equipment_energy.fuel_consumption {
+                if let Some(cost) = fuel.cost {
+                    energy += convert(cost * fuel.monthly_consumption * 12.0, &fuel.currency, fuel.fuel_type.clone());
+                }
+            }
+        }
+
+        rollup.fertilizer = fertilizer;
+        rollup.energy = energy;
+        rollup
+    }
+
+    /// Share of the inputs the farm model uses that this assessment provides (0-1), each
+    /// weighted equally. A sparse assessment scores low however good its factors are; the
+    /// pedigree quality of those is DataQuality::quality_score.
+    pub fn input_completeness(&self) -> f64 {
+        let checklist = self.input_checklist();
+        checklist.iter().map(|(_, provided)| provided).sum::<f64>() / checklist.len() as f64
+    }
+
+    /// Inputs of `input_completeness` the assessment lacks, in full or for some foods
+    pub fn missing_inputs(&self) -> Vec<&'static str> {
+        self.input_checklist().into_iter()
+            .filter(|(_, provided)| *provided < 1.0)
+            .map(|(input, _)| input)
+            .collect()
+    }
+
+    /// Each input with the share provided: per-food inputs count as the share of foods that
+    /// have them, farm inputs as 0 or 1
+    fn input_checklist(&self) -> Vec<(&'static str, f64)> {
+        let share_of_foods = |has: fn(&FoodItem) -> bool| if self.foods.is_empty() {
+            0.0
+        } else {
+            self.foods.iter().filter(|food| has(food)).count() as f64 / self.foods.len() as f64
+        };
+        // Livestock are sized by their herd or system instead of land
+        let production_basis = |food: &FoodItem| match food.category {
+            FoodCategory::Fish => food.aquaculture.is_some(),
+            FoodCategory::Poultry => food.poultry.is_some() || food.head_count.is_some(),
+            FoodCategory::Eggs => food.poultry.is_some(),
+            FoodCategory::Dairy => food.dairy.is_some(),
+            FoodCategory::Meat => food.head_count.is_some(),
+            _ => food.area_allocated.is_some(),
+        };
+        let management = self.management_practices.as_ref();
+        let provided = |present: bool| if present { 1.0 } else { 0.0 };
+        vec![
+            ("area or herd of each food", share_of_foods(production_basis)),
+            ("post-harvest losses of each food", share_of_foods(|food| food.post_harvest_losses.is_some())),
+            ("farm location", provided(self.coordinates.is_some() || self.region.is_some())),
+            ("farm profile", provided(self.farm_profile.is_some())),
+            ("soil type", provided(management.is_some_and(|m| m.soil_management.soil_type.is_some()))),
+            ("fertilization", provided(management.is_some_and(|m| m.fertilization.is_some()))),
+            ("water management", provided(management.is_some_and(|m| {
+                !m.water_management.water_source.is_empty() || m.water_management.irrigation_system.is_some()
+            }))),
+            ("equipment and energy", provided(self.equipment_energy.is_some())),
+        ]
+    }
+}
+
 // ======================================================================
 // LCA RESULTS STRUCTURE
 // ======================================================================
@@ -382,11 +1281,11 @@ pub struct LciFlow {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LCAResults {
-    pub midpoint_impacts: HashMap<String, MidpointResult>,
-    pub endpoint_impacts: HashMap<String, EndpointResult>,
+    pub midpoint_impacts: BTreeMap<String, MidpointResult>,
+    pub endpoint_impacts: BTreeMap<String, EndpointResult>,
     pub single_score: SingleScoreResult,
     pub data_quality: DataQuality,
-    pub breakdown_by_food: HashMap<String, HashMap<String, MidpointResult>>,
+    pub breakdown_by_food: BTreeMap<String, BTreeMap<String, MidpointResult>>,
     pub sensitivity_analysis: Option<SensitivityAnalysis>,
     pub comparative_analysis: Option<ComparativeAnalysis>,
 
@@ -395,18 +1294,333 @@ pub struct LCAResults {
     pub benchmarking: Option<BenchmarkingResults>,
     pub recommendations: Option<Vec<Recommendation>>,
 
-    // On-farm LCI (elementary flows) for the Python characterization path.
+    // On-farm LCI (elementary flows) for the Python characterization path and the report,
+    // sorted by compartment, substance and source. None when the methodology excludes it.
     #[serde(default)]
     pub lci_inventory: Option<Vec<LciFlow>>,
+
+    // Per-product impact intensities for multi-product processing facilities
+    #[serde(default)]
+    pub product_footprints: Option<BTreeMap<String, ProductFootprint>>,
+
+    // Size class a processing facility was benchmarked and scaled as
+    #[serde(default)]
+    pub capacity_range: Option<crate::processing::models::CapacityRange>,
+
+    // Share of each midpoint embedded in food that is lost after harvest or in processing
+    #[serde(default)]
+    pub food_loss_footprint: Option<BTreeMap<String, FoodLossFootprint>>,
+
+    // Annual Global warming by GHG Protocol scope, for corporate reporting
+    #[serde(default)]
+    pub ghg_scopes: Option<crate::production::ghg_scopes::ScopeBreakdown>,
+
+    // Each targeted midpoint against its limit per functional unit; see Assessment::check_targets
+    #[serde(default)]
+    pub target_compliance: Option<BTreeMap<String, TargetCompliance>>,
+
+    // ISO 14067 carbon footprint per declared unit of each food or product, keyed by its id
+    #[serde(default)]
+    pub product_carbon_footprints: Option<BTreeMap<String, crate::production::carbon_footprint::ProductCarbonFootprint>>,
+
+    // Green, blue and grey water per kg; None when the farm's water management is not known
+    #[serde(default)]
+    pub water_footprint: Option<WaterFootprint>,
+
+    // Each midpoint over the farm's cultivated area, for comparing systems on the same land;
+    // None unless every food reports its area
+    #[serde(default)]
+    pub impacts_per_ha: Option<BTreeMap<String, MidpointResult>>,
+}
+
+impl LCAResults {
+    /// Attach `ResultMetadata` to every midpoint and endpoint result, including the
+    /// per-food and per-hectare breakdowns
+    pub fn attach_metadata(&mut self, characterization_method: &CharacterizationMethod) {
+        attach_midpoint_metadata(&mut self.midpoint_impacts, characterization_method);
+        attach_endpoint_metadata(&mut self.endpoint_impacts, characterization_method);
+        for impacts in self.breakdown_by_food.values_mut().chain(self.impacts_per_ha.as_mut()) {
+            attach_midpoint_metadata(impacts, characterization_method);
+        }
+    }
+
+    /// Round every midpoint, endpoint and single score for display, each to the
+    /// significant figures its uncertainty supports. Only for output; calculations keep
+    /// full precision.
+    pub fn round_for_display(&mut self) {
+        let midpoints = std::iter::once(&mut self.midpoint_impacts)
+            .chain(self.breakdown_by_food.values_mut())
+            .chain(self.impacts_per_ha.as_mut());
+        for result in midpoints.flat_map(|impacts| impacts.values_mut()) {
+            round_to_uncertainty(&mut result.value, &mut result.uncertainty_range);
+        }
+        for result in self.endpoint_impacts.values_mut() {
+            round_to_uncertainty(&mut result.value, &mut result.uncertainty_range);
+        }
+        round_to_uncertainty(&mut self.single_score.value, &mut self.single_score.uncertainty_range);
+    }
+}
+
+/// Significant figures a result with this 95% range can claim: two when the range reaches
+/// more than 20% either side of the value, three when it is tighter or unknown
+pub fn display_significant_figures(value: f64, uncertainty_range: (f64, f64)) -> usize {
+    let (low, high) = uncertainty_range;
+    if value == 0.0 || (high - low).abs() / (2.0 * value.abs()) <= 0.2 { 3 } else { 2 }
+}
+
+/// `value` rounded to `figures` significant figures
+pub fn round_significant(value: f64, figures: usize) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    format!("{:.*e}", figures.max(1) - 1, value).parse().unwrap_or(value)
+}
+
+/// Round a value and its range to the significant figures the range supports
+pub fn round_to_uncertainty(value: &mut f64, uncertainty_range: &mut (f64, f64)) {
+    let figures = display_significant_figures(*value, *uncertainty_range);
+    *value = round_significant(*value, figures);
+    *uncertainty_range = (round_significant(uncertainty_range.0, figures), round_significant(uncertainty_range.1, figures));
+}
+
+pub fn attach_midpoint_metadata(
+    impacts: &mut BTreeMap<String, MidpointResult>,
+    characterization_method: &CharacterizationMethod
+) {
+    for (category, result) in impacts {
+        result.metadata = Some(ResultMetadata::new(category, &result.unit, characterization_method));
+    }
+}
+
+pub fn attach_endpoint_metadata(
+    impacts: &mut BTreeMap<String, EndpointResult>,
+    characterization_method: &CharacterizationMethod
+) {
+    for (category, result) in impacts {
+        result.metadata = Some(ResultMetadata::new(category, &result.unit, characterization_method));
+    }
+}
+
+/// One midpoint against a user-supplied limit, both per functional unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetCompliance {
+    pub target: f64,
+    pub value: f64,
+    pub unit: String, // e.g. "kg CO2-eq per kg"
+    pub gap: f64, // value - target; positive when the target is exceeded
+    pub meets_target: bool,
+}
+
+/// Impact of one midpoint category attributable to food that was produced but lost
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoodLossFootprint {
+    pub value: f64, // in the midpoint's unit
+    pub unit: String,
+    pub share_percent: f64, // of the category total
+}
+
+/// Water footprint as the Water Footprint Network splits it (Hoekstra et al. 2011): green is
+/// rainwater evapotranspired by the crop, blue is irrigation water withdrawn from rivers and
+/// aquifers, grey is the freshwater needed to dilute leached nitrate to the quality standard.
+/// Only blue water competes with other users, so only blue water is characterized for Water
+/// scarcity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaterFootprint {
+    pub unit: String, // "m3 per kg"
+    pub green: f64,
+    pub blue: f64,
+    pub grey: f64,
+    pub total: f64,
+}
+
+impl WaterFootprint {
+    /// Footprint per kg from annual green, blue and grey volumes (m3)
+    pub fn per_kg(green_m3: f64, blue_m3: f64, grey_m3: f64, production_kg: f64) -> Self {
+        let per_kg = |m3: f64| if production_kg > 0.0 { m3 / production_kg } else { 0.0 };
+        Self {
+            unit: "m3 per kg".to_string(),
+            green: per_kg(green_m3),
+            blue: per_kg(blue_m3),
+            grey: per_kg(grey_m3),
+            total: per_kg(green_m3 + blue_m3 + grey_m3),
+        }
+    }
+}
+
+/// Impact intensity of one processed product, per tonne of output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductFootprint {
+    pub annual_production: f64, // tonnes
+    pub allocation_share: f64, // share of facility-level burdens assigned to this product
+    pub allocation_basis: AllocationMethod,
+    pub per_tonne: BTreeMap<String, f64>,
+    pub cumulative_yield: f64, // product of step yield efficiencies (0-1)
+    pub yield_adjusted_per_tonne: BTreeMap<String, f64>,
+}
+
+/// What a midpoint result is expressed per. A farm's midpoints are per kg of its total
+/// production; a facility's, and each food's share of a farm, are annual totals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ImpactBasis {
+    #[default]
+    Total,
+    PerKgProduct,
+    PerHectare,
+}
+
+impl ImpactBasis {
+    /// What units on this basis end in
+    pub fn unit_suffix(&self) -> &'static str {
+        match self {
+            ImpactBasis::Total => "",
+            ImpactBasis::PerKgProduct => " per kg",
+            ImpactBasis::PerHectare => " per ha",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MidpointResult {
     pub value: f64,
     pub unit: String,
+    #[serde(default)]
+    pub basis: ImpactBasis,
     pub uncertainty_range: (f64, f64),
+    /// Geometric standard deviation of the lognormal behind the range, when it comes from
+    /// pedigree-scored factors; the range is then its 95% interval, median / GSD² to median * GSD²
+    #[serde(default)]
+    pub geometric_sd: Option<f64>,
     pub data_quality_score: f64,
     pub contributing_sources: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ResultMetadata>,
+}
+
+/// 95% interval of a lognormal with the given median and geometric standard deviation, the
+/// ecoinvent convention of median / GSD² to median * GSD². A negative median (a net credit)
+/// gets the mirrored interval.
+pub fn lognormal_interval(median: f64, geometric_sd: f64) -> (f64, f64) {
+    let factor = geometric_sd.powi(2);
+    if median >= 0.0 { (median / factor, median * factor) } else { (median * factor, median / factor) }
+}
+
+impl MidpointResult {
+    /// Add `addition` to this result, as when summing foods or products into a total.
+    /// Central values add. The distances from the central value to each end of the
+    /// uncertainty ranges combine in quadrature (independent errors), so an addition without
+    /// uncertainty moves the range with the value and never narrows it. Data quality is
+    /// weighted by contribution.
+    ///
+    /// A sum of lognormals is not lognormal; the total keeps the GSD its combined range implies,
+    /// provided every part had one.
+    pub fn aggregate(&mut self, addition: &MidpointResult) {
+        // Taken over as is when either side is empty, as when a total starts from zero
+        let is_empty = |result: &MidpointResult| result.value == 0.0 && result.uncertainty_range == (0.0, 0.0);
+        let kept_geometric_sd = if is_empty(self) {
+            Some(addition.geometric_sd)
+        } else if is_empty(addition) {
+            Some(self.geometric_sd)
+        } else {
+            None
+        };
+        let both_lognormal = self.geometric_sd.is_some() && addition.geometric_sd.is_some();
+
+        let below = |result: &MidpointResult| (result.value - result.uncertainty_range.0).max(0.0);
+        let above = |result: &MidpointResult| (result.uncertainty_range.1 - result.value).max(0.0);
+        let combined_below = below(self).hypot(below(addition));
+        let combined_above = above(self).hypot(above(addition));
+
+        let (self_weight, addition_weight) = (self.value.abs(), addition.value.abs());
+        if self_weight + addition_weight > 0.0 {
+            self.data_quality_score = (self.data_quality_score * self_weight + addition.data_quality_score * addition_weight)
+                / (self_weight + addition_weight);
+        }
+
+        self.value += addition.value;
+        let lower = self.value - combined_below;
+        self.uncertainty_range = (
+            if self.value >= 0.0 { lower.max(0.0) } else { lower }, // non-negative totals stay non-negative
+            self.value + combined_above
+        );
+        self.contributing_sources.extend(addition.contributing_sources.iter().cloned());
+        self.geometric_sd = kept_geometric_sd
+            .unwrap_or_else(|| if both_lognormal { self.implied_geometric_sd() } else { None });
+    }
+
+    /// Scale the result by a positive adjustment factor; the range scales with it, so the
+    /// relative uncertainty and GSD are unchanged
+    pub fn scale(&mut self, factor: f64) {
+        self.value *= factor;
+        self.uncertainty_range = (self.uncertainty_range.0 * factor, self.uncertainty_range.1 * factor);
+    }
+
+    /// GSD of the lognormal whose 95% interval is the range, (high / low)^(1/4) on the
+    /// magnitudes; none when the range reaches zero or changes sign
+    fn implied_geometric_sd(&self) -> Option<f64> {
+        let (low, high) = self.uncertainty_range;
+        let (small, large) = (low.abs().min(high.abs()), low.abs().max(high.abs()));
+        (low * high > 0.0 && large.is_finite()).then(|| (large / small).powf(0.25))
+    }
+}
+
+/// Categories whose net result can legitimately be negative, when credits for avoided
+/// production (biogas electricity, recycling, byproducts displacing feed) or stored soil
+/// carbon outweigh the burdens. The others measure physical flows that cannot be negative.
+pub fn allows_net_credit(category: &str) -> bool {
+    matches!(category, "Global warming" | "Fossil depletion" | "Soil degradation")
+}
+
+impl MidpointResult {
+    /// This result on another basis, through the total: `production_kg` and `area_ha` are
+    /// the denominators. None when the one either basis needs is unknown or not positive.
+    pub fn on_basis(&self, basis: ImpactBasis, production_kg: f64, area_ha: Option<f64>) -> Option<MidpointResult> {
+        let denominator = |basis: ImpactBasis| match basis {
+            ImpactBasis::Total => Some(1.0),
+            ImpactBasis::PerKgProduct => Some(production_kg),
+            ImpactBasis::PerHectare => area_ha,
+        }.filter(|denominator| *denominator > 0.0);
+        let mut result = self.clone();
+        result.scale(denominator(self.basis)? / denominator(basis)?);
+        result.unit = format!("{}{}", StructuredUnit::parse(&self.unit).numerator, basis.unit_suffix());
+        result.basis = basis;
+        Some(result)
+    }
+
+    /// The uncertainty range as a 95% interval, ordered; None when no range was estimated
+    /// (zero width) or it does not contain the value. Nothing emitted is exactly zero.
+    pub fn interval(&self) -> Option<(f64, f64)> {
+        let (a, b) = self.uncertainty_range;
+        let (low, high) = (a.min(b), a.max(b));
+        ((high > low || self.value == 0.0) && (low..=high).contains(&self.value)).then_some((low, high))
+    }
+
+    /// Settle the sign of a final result. A net credit is kept, with its range ordered and a
+    /// note among the sources; a negative value in a category that cannot be negative is a
+    /// calculation artefact and is set to zero. Returns the data quality warning to report.
+    pub fn settle_sign(&mut self, category: &str) -> Option<String> {
+        if self.value >= 0.0 {
+            return None;
+        }
+        if allows_net_credit(category) {
+            let (low, high) = self.uncertainty_range;
+            self.uncertainty_range = (low.min(high), low.max(high));
+            self.contributing_sources.push(format!(
+                "Net credit: avoided burdens exceed emissions by {:.3} {}", -self.value, self.unit
+            ));
+            Some(format!(
+                "{} is a net credit ({:.3} {}): avoided burdens outweigh the emissions",
+                category, self.value, self.unit
+            ))
+        } else {
+            let warning = format!(
+                "{} came out negative ({:.3} {}) and was set to zero, as it cannot be negative",
+                category, self.value, self.unit
+            );
+            self.value = 0.0;
+            self.uncertainty_range = (0.0, self.uncertainty_range.0.max(self.uncertainty_range.1).max(0.0));
+            Some(warning)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -416,6 +1630,128 @@ pub struct EndpointResult {
     pub uncertainty_range: (f64, f64),
     pub normalization_factor: Option<f64>,
     pub regional_adaptation_factor: Option<f64>,
+    // Damage from each midpoint category characterized into the endpoint
+    #[serde(default)]
+    pub contributions: BTreeMap<String, DamageContribution>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ResultMetadata>,
+}
+
+/// Machine-readable description of a midpoint or endpoint result, attached when the
+/// methodology asks for it, so consumers can label values without parsing `unit`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultMetadata {
+    pub category_id: String, // e.g. "global_warming" for "Global warming"
+    pub unit: StructuredUnit,
+    /// Method the result was characterized with; None when the selected method is not
+    /// implemented and the engine's own factors were applied
+    pub characterization_method: Option<CharacterizationMethod>,
+}
+
+impl ResultMetadata {
+    pub fn new(category: &str, unit: &str, characterization_method: &CharacterizationMethod) -> Self {
+        Self {
+            category_id: category_id(category),
+            unit: StructuredUnit::parse(unit),
+            characterization_method: characterization_method.is_implemented().then_some(*characterization_method),
+        }
+    }
+}
+
+/// Identifier of an impact category: its name in lower case, words joined by underscores
+pub fn category_id(category: &str) -> String {
+    category.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// A unit split into the quantity measured and the reference it is expressed per, e.g.
+/// "kg CO2-eq per kg" into "kg CO2-eq" and "kg"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructuredUnit {
+    pub numerator: String,
+    pub denominator: Option<String>, // None for totals
+}
+
+impl StructuredUnit {
+    pub fn parse(unit: &str) -> Self {
+        match unit.split_once(" per ") {
+            Some((numerator, denominator)) => Self {
+                numerator: numerator.trim().to_string(),
+                denominator: Some(denominator.trim().to_string()),
+            },
+            None => Self { numerator: unit.trim().to_string(), denominator: None },
+        }
+    }
+}
+
+impl std::fmt::Display for StructuredUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.denominator {
+            Some(denominator) => write!(f, "{} per {}", self.numerator, denominator),
+            None => write!(f, "{}", self.numerator),
+        }
+    }
+}
+
+/// Part of an endpoint, or of the single score, that comes from one source
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DamageContribution {
+    pub value: f64, // in the unit of the total
+    pub percentage: f64, // of the total
+    /// Range of the value, kept for the dominant contributor, whose uncertainty drives the total's
+    #[serde(default)]
+    pub uncertainty_range: Option<(f64, f64)>,
+}
+
+impl DamageContribution {
+    /// Contributions of `parts`, each a source with its value and the value's uncertainty
+    /// range, to their sum. Percentages add up to 100 unless the sum is zero.
+    pub fn shares(parts: impl IntoIterator<Item = (String, f64, (f64, f64))>) -> BTreeMap<String, DamageContribution> {
+        let parts: Vec<_> = parts.into_iter().collect();
+        let total: f64 = parts.iter().map(|(_, value, _)| value).sum();
+        let dominant = parts.iter()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .map(|(source, _, _)| source.clone());
+        parts.into_iter()
+            .map(|(source, value, range)| {
+                let contribution = DamageContribution {
+                    value,
+                    percentage: if total != 0.0 { value / total * 100.0 } else { 0.0 },
+                    uncertainty_range: (dominant.as_ref() == Some(&source)).then_some(range),
+                };
+                (source, contribution)
+            })
+            .collect()
+    }
+
+    /// The contribution with value and range multiplied by `factor`, as when a total is
+    /// expressed per unit of product
+    pub fn scaled(&self, factor: f64) -> DamageContribution {
+        DamageContribution {
+            value: self.value * factor,
+            percentage: self.percentage,
+            uncertainty_range: self.uncertainty_range.map(|(low, high)| (low * factor, high * factor)),
+        }
+    }
+}
+
+/// Damage from each midpoint of `midpoint` characterized into `endpoint`, with
+/// `characterization` given as (endpoint, midpoint, factor per midpoint unit)
+pub fn endpoint_contributions(
+    endpoint: &str,
+    characterization: &[(&str, &str, f64)],
+    midpoint: &BTreeMap<String, MidpointResult>
+) -> BTreeMap<String, DamageContribution> {
+    DamageContribution::shares(characterization.iter()
+        .filter(|(name, _, _)| *name == endpoint)
+        .filter_map(|(_, category, factor)| {
+            let result = midpoint.get(*category)?;
+            let range = (result.uncertainty_range.0 * factor, result.uncertainty_range.1 * factor);
+            Some((category.to_string(), result.value * factor, (range.0.min(range.1), range.0.max(range.1))))
+        }))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -423,8 +1759,14 @@ pub struct SingleScoreResult {
     pub value: f64,
     pub unit: String,
     pub uncertainty_range: (f64, f64),
-    pub weighting_factors: HashMap<String, f64>,
+    pub weighting_factors: BTreeMap<String, f64>,
     pub methodology: String,
+    // Normalized, weighted score of each endpoint, in person-equivalents
+    #[serde(default)]
+    pub endpoint_contributions: BTreeMap<String, DamageContribution>,
+    // Band, percentile among comparable assessments and the drivers of the score
+    #[serde(default)]
+    pub interpretation: Option<crate::production::interpretation::SingleScoreInterpretation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -432,12 +1774,44 @@ pub struct DataQuality {
     pub overall_confidence: ConfidenceLevel,
     pub data_source_mix: Vec<DataSourceContribution>,
     pub regional_adaptation: bool,
-    pub completeness_score: f64,
+    pub completeness_score: f64, // share of the possible inputs provided
+    #[serde(default)]
+    pub quality_score: f64, // mean pedigree quality of the factors used
     pub temporal_representativeness: f64,
     pub geographical_representativeness: f64,
     pub technological_representativeness: f64,
     pub warnings: Vec<String>,
     pub recommendations: Vec<String>,
+    #[serde(default)]
+    pub calculation_warnings: Vec<CalculationWarning>, // also listed in `warnings`
+    #[serde(default)]
+    pub factor_overrides: Vec<FactorOverride>, // primary data used in place of database factors
+}
+
+/// A primary-data factor that replaced the database or default factor of one food
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactorOverride {
+    pub food_id: String,
+    pub impact_category: String,
+    pub value_per_kg: f64,
+    pub source: String,
+}
+
+/// An input the calculation went ahead with but that is probably mistaken
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalculationWarning {
+    pub food_id: String,
+    pub kind: CalculationWarningKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CalculationWarningKind {
+    ImplausiblyHighYield, // typically tonnes entered as kg
+    ImplausiblyLowYield,
+    ImplausibleOutputPerHead, // typically a mistyped number of animals
+    ImplausibleWaterUse,
+    NitrogenAboveAgronomicMaximum,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -447,7 +1821,8 @@ pub struct DataSourceContribution {
     pub quality_score: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
 pub enum ConfidenceLevel {
     High,
     Medium,
@@ -455,8 +1830,9 @@ pub enum ConfidenceLevel {
     VeryLow,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DataSource {
+    Primary, // measured by the assessed company
     CountrySpecific(Country),
     Regional(String),
     Global,
@@ -474,13 +1850,13 @@ pub struct ManagementAnalysis {
     pub fertilizer_efficiency: f64,
     pub water_use_efficiency: f64,
     pub pest_management_score: f64,
-    pub sustainability_indicators: HashMap<String, f64>,
+    pub sustainability_indicators: BTreeMap<String, f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchmarkingResults {
-    pub farm_type_comparison: HashMap<String, f64>,
-    pub regional_comparison: HashMap<String, f64>,
+    pub farm_type_comparison: BTreeMap<String, f64>,
+    pub regional_comparison: BTreeMap<String, f64>,
     pub performance_percentile: f64, // 0-100
     pub best_practices_identified: Vec<String>,
 }
@@ -490,13 +1866,78 @@ pub struct Recommendation {
     pub category: RecommendationCategory,
     pub title: String,
     pub description: String,
-    pub potential_impact_reduction: HashMap<String, f64>,
+    pub potential_impact_reduction: BTreeMap<String, f64>,
     pub implementation_difficulty: DifficultyLevel,
     pub cost_category: CostCategory,
     pub priority: Priority,
+    #[serde(default)]
+    pub estimated_annual_savings: Option<f64>, // in the assessment's currency
+    #[serde(default)]
+    pub payback_months: Option<f64>,
+    #[serde(default)]
+    pub marginal_abatement_cost: Option<f64>, // per kg CO2-eq avoided, in the assessment's currency
+}
+
+/// Years over which a measure's up-front cost is spread when costing its abatement
+const MEASURE_LIFETIME_YEARS: f64 = 5.0;
+
+/// Carbon price up to which a measure that costs money is still worth medium priority,
+/// USD per kg CO2-eq ($50/t)
+const REFERENCE_CARBON_PRICE_USD: f64 = 0.05;
+
+impl Recommendation {
+    /// Net yearly cost per kg CO2-eq the measure avoids from a farm or facility emitting
+    /// `global_warming_kg`, in the assessment's currency; negative when it saves more than it
+    /// costs. Costed measures spread the up-front cost implied by their payback over the
+    /// measure's lifetime; the others take their cost category's reference cost. None when
+    /// the measure does not reduce global warming.
+    pub fn abatement_cost(&self, global_warming_kg: f64, currency: &Currency, rates: &ExchangeRates) -> Option<f64> {
+        let avoided_kg = self.potential_impact_reduction.get("Global warming")? / 100.0 * global_warming_kg;
+        if avoided_kg <= 0.0 || !avoided_kg.is_finite() {
+            return None;
+        }
+        let annual_cost = match (self.estimated_annual_savings, self.payback_months) {
+            (Some(savings), Some(payback_months)) => savings * payback_months / 12.0 / MEASURE_LIFETIME_YEARS,
+            _ => self.cost_category.reference_annual_cost_usd() * rates.per_usd(currency),
+        };
+        Some((annual_cost - self.estimated_annual_savings.unwrap_or(0.0)) / avoided_kg)
+    }
+
+    fn global_warming_reduction(&self) -> f64 {
+        self.potential_impact_reduction.get("Global warming").copied().unwrap_or(0.0)
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Cost each recommendation's abatement, set its priority from it and sort the cheapest
+/// abatement first. Measures that pay for themselves are high priority, those below the
+/// reference carbon price medium, the rest low; among equal costs the larger reduction comes
+/// first. Measures that do not reduce global warming keep their priority and go last.
+pub fn prioritize_recommendations(
+    recommendations: &mut [Recommendation],
+    global_warming_kg: f64,
+    currency: &Currency,
+    rates: &ExchangeRates
+) {
+    let carbon_price = REFERENCE_CARBON_PRICE_USD * rates.per_usd(currency);
+    for recommendation in recommendations.iter_mut() {
+        recommendation.marginal_abatement_cost = recommendation.abatement_cost(global_warming_kg, currency, rates);
+        recommendation.priority = match recommendation.marginal_abatement_cost {
+            Some(cost) if cost <= 0.0 => Priority::High,
+            Some(cost) if cost <= carbon_price => Priority::Medium,
+            Some(_) => Priority::Low,
+            None => recommendation.priority,
+        };
+    }
+    recommendations.sort_by(|a, b| {
+        let cost = match (a.marginal_abatement_cost, b.marginal_abatement_cost) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        };
+        cost.then_with(|| b.global_warming_reduction().total_cmp(&a.global_warming_reduction()))
+    });
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RecommendationCategory {
     SoilManagement,
     WaterManagement,
@@ -508,14 +1949,14 @@ pub enum RecommendationCategory {
     SystemDesign,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DifficultyLevel {
     Low,
     Medium,
     High,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CostCategory {
     NoCost,
     LowCost,
@@ -523,7 +1964,20 @@ pub enum CostCategory {
     HighCost,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+impl CostCategory {
+    /// Typical yearly cost of a smallholder measure in the category, in USD, with up-front
+    /// costs spread over the years the measure serves (2024 prices)
+    pub fn reference_annual_cost_usd(self) -> f64 {
+        match self {
+            CostCategory::NoCost => 0.0,
+            CostCategory::LowCost => 20.0,
+            CostCategory::MediumCost => 150.0,
+            CostCategory::HighCost => 600.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Priority {
     High,
     Medium,
@@ -537,10 +1991,27 @@ pub enum Priority {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SensitivityAnalysis {
     pub most_influential_parameters: Vec<InfluentialParameter>,
-    pub uncertainty_contributions: HashMap<String, f64>,
+    pub uncertainty_contributions: BTreeMap<String, f64>,
     pub scenario_analysis: Vec<ScenarioResult>,
 }
 
+/// Sampled distribution of one impact category
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MonteCarloResult {
+    pub iterations: usize,
+    pub seed: u64, // reported so the run can be reproduced
+    pub median: f64,
+    pub percentile_2_5: f64,
+    pub percentile_97_5: f64,
+}
+
+impl MonteCarloResult {
+    /// 95% interval of the samples, for Significance::of
+    pub fn interval(&self) -> (f64, f64) {
+        (self.percentile_2_5, self.percentile_97_5)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InfluentialParameter {
     pub parameter_name: String,
@@ -553,7 +2024,10 @@ pub struct InfluentialParameter {
 pub struct ScenarioResult {
     pub scenario_name: String,
     pub description: String,
-    pub impact_changes: HashMap<String, f64>,
+    pub impact_changes: BTreeMap<String, f64>,
+    // Per category of impact_changes whose uncertainty is known, against the baseline's
+    #[serde(default)]
+    pub significance: BTreeMap<String, crate::production::interpretation::Significance>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -570,24 +2044,32 @@ pub struct BenchmarkComparison {
     pub benchmark_value: f64,
     pub percentage_difference: f64,
     pub performance_category: PerformanceCategory,
+    // What both values are per; None in results from before bases, which compared totals
+    #[serde(default)]
+    pub basis: Option<crate::production::interpretation::BenchmarkBasis>,
+    // Whether the uncertainty interval clears the benchmark; None in results from before
+    #[serde(default)]
+    pub significance: Option<crate::production::interpretation::Significance>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegionalComparison {
     pub region_name: String,
-    pub impact_ratios: HashMap<String, f64>,
+    pub impact_ratios: BTreeMap<String, f64>, // intensity per kg over the region's
+    #[serde(default)]
+    pub significance: BTreeMap<String, crate::production::interpretation::Significance>, // per category, as impact_ratios
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BestPractice {
     pub practice_name: String,
     pub description: String,
-    pub potential_impact_reduction: HashMap<String, f64>,
+    pub potential_impact_reduction: BTreeMap<String, f64>,
     pub implementation_difficulty: DifficultyLevel,
     pub cost_category: CostCategory,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PerformanceCategory {
     Excellent,
     Good,
@@ -613,6 +2095,11 @@ pub struct ImpactFactor {
     pub year: i32,
     pub uncertainty_range: (f64, f64),
     pub pedigree_score: PedigreeScore,
+    /// Geometric standard deviation of the lognormal the source gives for the factor, basic
+    /// and pedigree uncertainty together. Results use it in place of the GSD derived from the
+    /// range and pedigree scores.
+    #[serde(default)]
+    pub gsd: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -624,8 +2111,28 @@ pub struct PedigreeScore {
     pub technological_correlation: u8,
 }
 
+/// Pedigree matrix of the ecoinvent v3 data quality guideline (Weidema et al. 2013): the
+/// variance of the log-transformed data each indicator adds, by score 1-5.
+///
+/// | Indicator                 | 1 | 2        | 3      | 4      | 5     |
+/// |---------------------------|---|----------|--------|--------|-------|
+/// | Reliability               | 0 | 0.0006   | 0.002  | 0.008  | 0.04  |
+/// | Completeness              | 0 | 0.0001   | 0.0006 | 0.002  | 0.008 |
+/// | Temporal correlation      | 0 | 0.0002   | 0.002  | 0.008  | 0.04  |
+/// | Geographical correlation  | 0 | 0.000025 | 0.0001 | 0.0006 | 0.002 |
+/// | Technological correlation | 0 | 0.0006   | 0.008  | 0.04   | 0.12  |
+const PEDIGREE_VARIANCES: [[f64; 5]; 5] = [
+    [0.0, 0.0006, 0.002, 0.008, 0.04],
+    [0.0, 0.0001, 0.0006, 0.002, 0.008],
+    [0.0, 0.0002, 0.002, 0.008, 0.04],
+    [0.0, 0.000025, 0.0001, 0.0006, 0.002],
+    [0.0, 0.0006, 0.008, 0.04, 0.12],
+];
+
 impl PedigreeScore {
-    pub fn calculate_uncertainty_factor(&self) -> f64 {
+    /// Variance of the log-transformed data added by the five indicators. Scores outside
+    /// 1-5 count as the nearest valid score.
+    pub fn pedigree_variance(&self) -> f64 {
         let indicators = [
             self.reliability,
             self.completeness,
@@ -633,22 +2140,22 @@ impl PedigreeScore {
             self.geographical_correlation,
             self.technological_correlation,
         ];
-        
-        let uncertainty_factors: [[f64; 5]; 5] = [
-            [1.00, 1.05, 1.10, 1.20, 1.50],
-            [1.00, 1.02, 1.05, 1.10, 1.20],
-            [1.00, 1.03, 1.10, 1.20, 1.50],
-            [1.00, 1.01, 1.02, 1.10, 1.50],
-            [1.00, 1.05, 1.20, 1.50, 2.00],
-        ];
-        
-        let mut total_variance = 0.0;
-        for (i, &indicator) in indicators.iter().enumerate() {
-            let factor = uncertainty_factors[i][(indicator - 1) as usize];
-            total_variance += (factor.ln()).powi(2);
-        }
-        
-        total_variance.sqrt().exp()
+        indicators.iter()
+            .zip(&PEDIGREE_VARIANCES)
+            .map(|(&score, variances)| variances[score.clamp(1, 5) as usize - 1])
+            .sum()
+    }
+
+    /// Geometric standard deviation of the lognormal: exp of the square root of the basic
+    /// variance plus the pedigree variances
+    pub fn geometric_standard_deviation(&self, basic_variance: f64) -> f64 {
+        (basic_variance + self.pedigree_variance()).sqrt().exp()
+    }
+
+    /// GSD95 from the pedigree alone: the factor (GSD²) by which the 95% interval extends
+    /// either side of the median, exp(2·√Σ pedigree variances) in ecoinvent's formula
+    pub fn calculate_uncertainty_factor(&self) -> f64 {
+        self.geometric_standard_deviation(0.0).powi(2)
     }
 
     pub fn calculate_overall_quality_score(&self) -> f64 {
@@ -658,6 +2165,59 @@ impl PedigreeScore {
     }
 }
 
+/// Kinds of flow in ecoinvent's default basic uncertainty table: the variance of the
+/// log-transformed data that remains with perfect pedigree scores
+///
+/// | Flow type                                         | Basic variance |
+/// |---------------------------------------------------|----------------|
+/// | Demand of energy, materials, water; resources; CO2 | 0.0006         |
+/// | Land occupation                                   | 0.04           |
+/// | Land transformation                               | 0.12           |
+/// | NOx, N2O, CH4, NH3, NMVOC, NO3 and PO4 emissions   | 0.04           |
+/// | PM2.5 emissions                                   | 0.3            |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowType {
+    Demand,
+    LandOccupation,
+    LandTransformation,
+    Emission,
+    FineParticulates,
+}
+
+impl FlowType {
+    pub fn basic_variance(self) -> f64 {
+        match self {
+            FlowType::Demand => 0.0006,
+            FlowType::LandOccupation => 0.04,
+            FlowType::LandTransformation => 0.12,
+            FlowType::Emission => 0.04,
+            FlowType::FineParticulates => 0.3,
+        }
+    }
+
+    /// The flow that dominates an impact category's result on farms: field N2O and CH4 for
+    /// global warming, irrigation water for water use, and so on
+    pub fn of_category(category: &str) -> Self {
+        match category {
+            "Water consumption" | "Water scarcity" | "Fossil depletion" | "Mineral depletion" | "Energy consumption" => FlowType::Demand,
+            "Land use" | "Biodiversity loss" => FlowType::LandOccupation,
+            "Soil degradation" => FlowType::LandTransformation,
+            "Particulate matter formation" => FlowType::FineParticulates,
+            _ => FlowType::Emission,
+        }
+    }
+}
+
+/// Name a value serializes as: the variant name of a fieldless enum, compact JSON otherwise.
+/// For string keys and columns that must match the JSON rather than the Debug output.
+pub fn serialized_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        Ok(other) => other.to_string(),
+        Err(_) => String::new(),
+    }
+}
+
 // ======================================================================
 // CHARACTERIZATION FACTORS
 // ======================================================================
@@ -681,6 +2241,10 @@ pub struct GlobalWarmingFactors {
     pub ch4_rice: f64,
 }
 
+/// AWARE world-average characterization factor for unspecified water use
+/// (m3 world-eq per m3 consumed, AWARE 1.2). Applied where no regional factor is known.
+pub const GLOBAL_AVERAGE_AWARE: f64 = 42.95;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WaterScarcityFactors {
     pub ghana_aware: f64,
@@ -732,7 +2296,7 @@ impl Default for CharacterizationFactors {
                 ghana_aware: 20.0,
                 nigeria_north_aware: 30.0,
                 nigeria_south_aware: 15.0,
-                global_average_aware: 1.0,
+                global_average_aware: GLOBAL_AVERAGE_AWARE,
             },
             biodiversity: BiodiversityFactors {
                 intensive_msa: 0.2,
@@ -757,4 +2321,249 @@ impl Default for CharacterizationFactors {
             },
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn result(value: f64, range: (f64, f64)) -> MidpointResult {
+        MidpointResult {
+            value,
+            unit: "kg CO2-eq".to_string(),
+            basis: ImpactBasis::Total,
+            uncertainty_range: range,
+            geometric_sd: None,
+            data_quality_score: 0.7,
+            contributing_sources: vec![format!("{} kg CO2-eq", value)],
+            metadata: None,
+        }
+    }
+
+    /// A non-negative result whose range lies around its value
+    fn any_result() -> impl Strategy<Value = MidpointResult> {
+        (0.0..1e4f64, 0.0..1.0f64, 0.0..2.0f64)
+            .prop_map(|(value, below, above)| result(value, (value * (1.0 - below), value * (1.0 + above))))
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0)
+    }
+
+    #[test]
+    fn wide_ranges_round_to_two_significant_figures_and_tight_ones_to_three() {
+        let mut wide = result(1.6730000001, (0.84, 2.51)); // +/-50%
+        round_to_uncertainty(&mut wide.value, &mut wide.uncertainty_range);
+        assert_eq!((wide.value, wide.uncertainty_range), (1.7, (0.84, 2.5)));
+
+        let mut tight = result(1.6730000001, (1.59, 1.76)); // +/-5%
+        round_to_uncertainty(&mut tight.value, &mut tight.uncertainty_range);
+        assert_eq!((tight.value, tight.uncertainty_range), (1.67, (1.59, 1.76)));
+    }
+
+    #[test]
+    fn symmetric_ranges_add_in_quadrature() {
+        let mut total = result(10.0, (7.0, 13.0));
+        total.aggregate(&result(20.0, (14.0, 26.0)));
+        let half_width = 45.0f64.sqrt();
+        assert_eq!(total.value, 30.0);
+        assert!(close(total.uncertainty_range.0, 30.0 - half_width));
+        assert!(close(total.uncertainty_range.1, 30.0 + half_width));
+        assert_eq!(total.contributing_sources.len(), 2);
+    }
+
+    #[test]
+    fn an_empty_total_takes_the_first_result() {
+        let mut total = MidpointResult { data_quality_score: 0.0, contributing_sources: Vec::new(), ..result(0.0, (0.0, 0.0)) };
+        let first = result(5.0, (3.0, 9.0));
+        total.aggregate(&first);
+        assert_eq!(total.value, first.value);
+        assert_eq!(total.uncertainty_range, first.uncertainty_range);
+        assert_eq!(total.data_quality_score, first.data_quality_score);
+    }
+
+    #[test]
+    fn negative_totals_keep_their_range() {
+        let mut total = result(-4.0, (-5.0, -3.0));
+        total.aggregate(&result(1.0, (1.0, 1.0)));
+        assert_eq!(total.value, -3.0);
+        assert!(total.uncertainty_range.0 < total.value && total.value < total.uncertainty_range.1);
+    }
+
+    #[test]
+    fn credits_stay_negative_and_impossible_negatives_are_zeroed() {
+        let mut credit = result(-50.0, (-35.0, -65.0));
+        let warning = credit.settle_sign("Global warming").unwrap();
+        assert_eq!((credit.value, credit.uncertainty_range), (-50.0, (-65.0, -35.0)));
+        assert!(warning.contains("net credit") && credit.contributing_sources.last().unwrap().starts_with("Net credit"));
+
+        let mut water = MidpointResult { unit: "m3".to_string(), ..result(-2.0, (-2.6, -1.4)) };
+        assert!(water.settle_sign("Water consumption").unwrap().contains("set to zero"));
+        assert_eq!((water.value, water.uncertainty_range), (0.0, (0.0, 0.0)));
+
+        assert!(result(3.0, (2.0, 4.0)).settle_sign("Water consumption").is_none());
+    }
+
+    fn pedigree(scores: [u8; 5]) -> PedigreeScore {
+        let [reliability, completeness, temporal_correlation, geographical_correlation, technological_correlation] = scores;
+        PedigreeScore { reliability, completeness, temporal_correlation, geographical_correlation, technological_correlation }
+    }
+
+    #[test]
+    fn pedigree_variances_reproduce_the_published_uncertainty_factors() {
+        // ecoinvent's 95% factors (GSD²) per indicator and score, which the v3 variances encode
+        let published = [
+            [1.00, 1.05, 1.10, 1.20, 1.50],
+            [1.00, 1.02, 1.05, 1.10, 1.20],
+            [1.00, 1.03, 1.10, 1.20, 1.50],
+            [1.00, 1.01, 1.02, 1.05, 1.10],
+            [1.00, 1.05, 1.20, 1.50, 2.00],
+        ];
+        for (indicator, factors) in published.iter().enumerate() {
+            for (score, factor) in (1..=5).zip(factors) {
+                let mut scores = [1; 5];
+                scores[indicator] = score;
+                let computed = pedigree(scores).calculate_uncertainty_factor();
+                assert!((computed - factor).abs() < 0.01, "indicator {} score {}: {} vs {}", indicator, score, computed, factor);
+            }
+        }
+
+        // Perfect scores leave the basic uncertainty: 0.0006 is the familiar 1.05 of energy demand
+        let perfect = pedigree([1; 5]).geometric_standard_deviation(FlowType::Demand.basic_variance());
+        assert!((perfect.powi(2) - 1.05).abs() < 0.001, "{}", perfect.powi(2));
+
+        // Scores of 2 throughout on top of it: exp(sqrt(0.0006 + 0.001525)) = 1.04718
+        let scored = pedigree([2; 5]).geometric_standard_deviation(FlowType::Demand.basic_variance());
+        assert!(close(scored, (0.0006f64 + 0.0006 + 0.0001 + 0.0002 + 0.000025 + 0.0006).sqrt().exp()));
+        assert!((scored - 1.04718).abs() < 1e-5, "{}", scored);
+
+        // Scores outside 1-5 count as the nearest score rather than panicking
+        assert_eq!(pedigree([0, 9, 1, 1, 1]).pedigree_variance(), pedigree([1, 5, 1, 1, 1]).pedigree_variance());
+    }
+
+    #[test]
+    fn poorer_pedigrees_give_larger_gsds() {
+        let gsds: Vec<f64> = (1..=5).map(|score| pedigree([score; 5]).calculate_uncertainty_factor()).collect();
+        assert!(gsds.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", gsds);
+        assert_eq!(gsds[0], 1.0);
+
+        // Each indicator on its own widens the distribution, on top of any basic uncertainty
+        for indicator in 0..5 {
+            let mut poor = [1; 5];
+            poor[indicator] = 4;
+            let basic_variance = FlowType::Demand.basic_variance();
+            assert!(pedigree(poor).geometric_standard_deviation(basic_variance) > pedigree([1; 5]).geometric_standard_deviation(basic_variance));
+        }
+    }
+
+    #[test]
+    fn lognormal_results_keep_a_geometric_sd_when_summed() {
+        let lognormal = |value: f64, gsd: f64| MidpointResult {
+            geometric_sd: Some(gsd),
+            ..result(value, lognormal_interval(value, gsd))
+        };
+        let (low, high) = lognormal_interval(10.0, 1.5);
+        assert!(close(low, 10.0 / 2.25) && close(high, 22.5));
+        assert_eq!(lognormal_interval(-10.0, 1.5), (-22.5, -10.0 / 2.25));
+
+        let mut total = MidpointResult { data_quality_score: 0.0, contributing_sources: Vec::new(), ..result(0.0, (0.0, 0.0)) };
+        total.aggregate(&lognormal(10.0, 1.5));
+        assert_eq!(total.geometric_sd, Some(1.5));
+
+        total.aggregate(&lognormal(10.0, 1.5));
+        let (low, high) = total.uncertainty_range;
+        let gsd = total.geometric_sd.unwrap();
+        assert!(close(gsd, (high / low).powf(0.25)));
+        assert!(gsd > 1.0 && gsd < 1.5, "independent parts average out: {}", gsd);
+
+        total.aggregate(&result(5.0, (4.0, 6.0)));
+        assert_eq!(total.geometric_sd, None);
+    }
+
+    #[test]
+    fn methodology_presets_fix_their_boundary_and_functional_unit() {
+        let production = LCAMethodology::african_production();
+        assert_eq!(production.system_boundary, SystemBoundary::CradleToGate);
+        assert_eq!(production.functional_unit, "1 kg product");
+        assert_eq!(production.weighting_method, Some(WeightingMethod::AfricanPriorities));
+
+        let processing = LCAMethodology::african_processing();
+        assert_eq!(processing.system_boundary, SystemBoundary::GateToGate);
+        assert_eq!(processing.functional_unit, "1 tonne product");
+        assert!(!processing.include_capital_goods);
+
+        let iso = LCAMethodology::iso_default();
+        assert_eq!(iso.system_boundary, SystemBoundary::CradleToGate);
+        assert_eq!(iso.functional_unit, "1 kg product");
+        assert!(iso.normalization_method.is_none() && iso.weighting_method.is_none());
+        assert!(iso.include_inventory);
+    }
+
+    #[test]
+    fn the_builder_changes_only_what_it_is_told() {
+        let custom = LCAMethodology::african_processing().to_builder()
+            .characterization_method(CharacterizationMethod::ReCiPe2016)
+            .impact_categories(Some(vec!["Global warming".to_string()]))
+            .build();
+        assert_eq!(custom.characterization_method, CharacterizationMethod::ReCiPe2016);
+        assert_eq!(custom.impact_categories.as_deref(), Some(&["Global warming".to_string()][..]));
+        assert_eq!(custom.system_boundary, SystemBoundary::GateToGate);
+        assert_eq!(custom.functional_unit, "1 tonne product");
+
+        let default = LCAMethodologyBuilder::new().build();
+        assert_eq!(serde_json::to_value(default).unwrap(), serde_json::to_value(LCAMethodology::iso_default()).unwrap());
+    }
+
+    #[test]
+    fn results_move_between_bases_through_the_total() {
+        let total = result(2000.0, (1600.0, 2400.0));
+        let per_kg = total.on_basis(ImpactBasis::PerKgProduct, 1000.0, None).unwrap();
+        assert_eq!((per_kg.value, per_kg.uncertainty_range), (2.0, (1.6, 2.4)));
+        assert_eq!(per_kg.unit, "kg CO2-eq per kg");
+
+        let per_ha = per_kg.on_basis(ImpactBasis::PerHectare, 1000.0, Some(0.5)).unwrap();
+        assert_eq!((per_ha.value, per_ha.unit.as_str()), (4000.0, "kg CO2-eq per ha"));
+
+        // No area, or no production, to divide by
+        assert!(per_kg.on_basis(ImpactBasis::PerHectare, 1000.0, None).is_none());
+        assert!(total.on_basis(ImpactBasis::PerKgProduct, 0.0, None).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn aggregated_value_is_the_sum(results in prop::collection::vec(any_result(), 1..20)) {
+            let mut total = results[0].clone();
+            for addition in &results[1..] {
+                total.aggregate(addition);
+            }
+            let sum: f64 = results.iter().map(|r| r.value).sum();
+            prop_assert!(close(total.value, sum));
+            prop_assert!(total.uncertainty_range.0 <= total.value && total.value <= total.uncertainty_range.1);
+            prop_assert_eq!(total.contributing_sources.len(), results.len());
+        }
+
+        #[test]
+        fn uncertainty_never_narrows(results in prop::collection::vec(any_result(), 2..20)) {
+            let mut total = results[0].clone();
+            for addition in &results[1..] {
+                let (below, above) = (total.value - total.uncertainty_range.0, total.uncertainty_range.1 - total.value);
+                total.aggregate(addition);
+                prop_assert!(total.value - total.uncertainty_range.0 >= below * (1.0 - 1e-12));
+                prop_assert!(total.uncertainty_range.1 - total.value >= above * (1.0 - 1e-12));
+            }
+        }
+
+        #[test]
+        fn a_certain_addition_moves_the_range_without_resizing_it(total in any_result(), value in 0.0..1e4f64) {
+            let mut aggregated = total.clone();
+            aggregated.aggregate(&result(value, (value, value)));
+            prop_assert!(close(aggregated.uncertainty_range.0, total.uncertainty_range.0 + value));
+            prop_assert!(close(aggregated.uncertainty_range.1, total.uncertainty_range.1 + value));
+
+            let mut unchanged = total.clone();
+            unchanged.aggregate(&result(0.0, (0.0, 0.0)));
+            prop_assert!(close(unchanged.uncertainty_range.0, total.uncertainty_range.0));
+            prop_assert!(close(unchanged.uncertainty_range.1, total.uncertainty_range.1));
+        }
+    }
+}
@@ -0,0 +1,725 @@
+/*!
+ * Printable reports
+ *
+ * Renders a completed farm or facility assessment as a self-contained HTML page that
+ * extension officers can print or share with farmers: the single score on a gauge, how each
+ * impact category splits between the foods or products, the main sources of the headline
+ * categories, data quality in words and the recommendations. Labels come in English and
+ * French; text the engines generate (recommendations, sources, warnings) is shown as is.
+ *
+ * The page is templates/report.html, compiled into the binary by askama, with its styles
+ * inline so it opens offline. With the `pdf` feature the same content is also available as
+ * a plain A4 PDF.
+ */
+
+use crate::error::ReportError;
+use crate::processing::ProcessingAssessment;
+use crate::production::*;
+use askama::Template;
+use std::str::FromStr;
+
+/// Categories whose main sources the report lists
+const HEADLINE_CATEGORIES: [&str; 3] = ["Global warming", "Water consumption", "Land use"];
+const MAX_SOURCES: usize = 5;
+const COLORS: [&str; 6] = ["#2e7d32", "#f9a825", "#1565c0", "#8e24aa", "#ef6c00", "#00838f"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    English,
+    French,
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" => Ok(Language::English),
+            "fr" | "french" | "français" | "francais" => Ok(Language::French),
+            _ => Err(format!("Unsupported report language: {} (use en or fr)", s)),
+        }
+    }
+}
+
+/// Fixed text of the report in one language
+pub struct Labels {
+    pub code: &'static str,
+    pub farm_title: &'static str,
+    pub facility_title: &'static str,
+    pub assessed_on: &'static str,
+    pub score: &'static str,
+    pub score_explanation: &'static str,
+    pub bands: [&'static str; 4], // excellent, good, average, poor
+    pub likely_range: &'static str,
+    pub categories: &'static str,
+    pub categories_explanation_farm: &'static str,
+    pub categories_explanation_facility: &'static str,
+    pub sources: &'static str,
+    pub data_quality: &'static str,
+    pub confidence: &'static str,
+    pub confidence_levels: [&'static str; 4], // high, medium, low, very low
+    pub completeness: &'static str,
+    pub temporal: &'static str,
+    pub geographical: &'static str,
+    pub technological: &'static str,
+    pub points_to_check: &'static str,
+    pub recommendations: &'static str,
+    pub no_recommendations: &'static str,
+    pub schema_version: &'static str,
+    pub priority: &'static str,
+    pub difficulty: &'static str,
+    pub cost: &'static str,
+    pub levels: [&'static str; 3], // low, medium, high
+    pub costs: [&'static str; 4],  // none, low, medium, high
+    pub savings: &'static str,
+    pub payback: &'static str,
+    pub months: &'static str,
+    pub decimal_comma: bool,
+}
+
+const ENGLISH: Labels = Labels {
+    code: "en",
+    farm_title: "Farm environmental report",
+    facility_title: "Processing facility environmental report",
+    assessed_on: "Assessed on",
+    score: "Overall environmental score",
+    score_explanation: "0% is the lowest impact; 50% is the reference impact for this kind of production; 100% is twice the reference.",
+    bands: ["Excellent", "Good", "Average", "Poor"],
+    likely_range: "Likely range",
+    categories: "Impacts by category",
+    categories_explanation_farm: "Each bar shows how the impact is shared between the foods produced.",
+    categories_explanation_facility: "Each bar shows how the impact is shared between the products made.",
+    sources: "Main sources",
+    data_quality: "How reliable are these results?",
+    confidence: "Overall confidence",
+    confidence_levels: ["High", "Medium", "Low", "Very low"],
+    completeness: "Completeness of the data",
+    temporal: "Data is recent",
+    geographical: "Data is from the region",
+    technological: "Data matches the practices",
+    points_to_check: "Points to check",
+    recommendations: "Recommended actions",
+    no_recommendations: "No actions were recommended.",
+    schema_version: "Results schema version",
+    priority: "Priority",
+    difficulty: "Difficulty",
+    cost: "Cost",
+    levels: ["Low", "Medium", "High"],
+    costs: ["No cost", "Low cost", "Medium cost", "High cost"],
+    savings: "Estimated savings per year",
+    payback: "Pays for itself in",
+    months: "months",
+    decimal_comma: false,
+};
+
+const FRENCH: Labels = Labels {
+    code: "fr",
+    farm_title: "Rapport environnemental de l'exploitation",
+    facility_title: "Rapport environnemental de l'unité de transformation",
+    assessed_on: "Évalué le",
+    score: "Score environnemental global",
+    score_explanation: "0 % correspond à l'impact le plus faible ; 50 % à l'impact de référence pour ce type de production ; 100 % au double de la référence.",
+    bands: ["Excellent", "Bon", "Moyen", "Faible"],
+    likely_range: "Plage probable",
+    categories: "Impacts par catégorie",
+    categories_explanation_farm: "Chaque barre montre la part de l'impact de chaque aliment produit.",
+    categories_explanation_facility: "Chaque barre montre la part de l'impact de chaque produit fabriqué.",
+    sources: "Principales sources",
+    data_quality: "Quelle est la fiabilité de ces résultats ?",
+    confidence: "Confiance globale",
+    confidence_levels: ["Élevée", "Moyenne", "Faible", "Très faible"],
+    completeness: "Complétude des données",
+    temporal: "Données récentes",
+    geographical: "Données de la région",
+    technological: "Données adaptées aux pratiques",
+    points_to_check: "Points à vérifier",
+    recommendations: "Actions recommandées",
+    no_recommendations: "Aucune action n'a été recommandée.",
+    schema_version: "Version du schéma des résultats",
+    priority: "Priorité",
+    difficulty: "Difficulté",
+    cost: "Coût",
+    levels: ["Faible", "Moyenne", "Élevée"],
+    costs: ["Sans coût", "Coût faible", "Coût moyen", "Coût élevé"],
+    savings: "Économies estimées par an",
+    payback: "Rentabilisé en",
+    months: "mois",
+    decimal_comma: true,
+};
+
+impl Language {
+    pub fn labels(self) -> &'static Labels {
+        match self {
+            Language::English => &ENGLISH,
+            Language::French => &FRENCH,
+        }
+    }
+
+    /// Name of an impact category in this language
+    pub fn category(self, category: &str) -> String {
+        if self == Language::English {
+            return category.to_string();
+        }
+        match category {
+            "Global warming" => "Réchauffement climatique",
+            "Water consumption" => "Consommation d'eau",
+            "Water scarcity" => "Pénurie d'eau",
+            "Land use" => "Utilisation des terres",
+            "Biodiversity loss" => "Perte de biodiversité",
+            "Soil degradation" => "Dégradation des sols",
+            "Terrestrial acidification" => "Acidification terrestre",
+            "Freshwater eutrophication" => "Eutrophisation des eaux douces",
+            "Marine eutrophication" => "Eutrophisation marine",
+            "Fossil depletion" => "Épuisement des ressources fossiles",
+            "Mineral depletion" => "Épuisement des ressources minérales",
+            "Particulate matter formation" => "Formation de particules fines",
+            "Photochemical oxidation" => "Oxydation photochimique",
+            "Energy consumption" => "Consommation d'énergie",
+            "Gross process water use" => "Eau de procédé brute",
+            "Wastewater generation" => "Production d'eaux usées",
+            "Solid waste generation" => "Production de déchets solides",
+            "Air pollution" => "Pollution de l'air",
+            "Raw material depletion" => "Épuisement des matières premières",
+            other => other,
+        }.to_string()
+    }
+}
+
+impl Labels {
+    /// A value with as many decimals as its size warrants; small values keep three
+    /// significant digits
+    pub fn number(&self, value: f64) -> String {
+        let text = match value.abs() {
+            v if v >= 100.0 => format!("{:.0}", value),
+            v if v >= 1.0 => format!("{:.2}", value),
+            0.0 => "0".to_string(),
+            v => format!("{:.*}", (2.0 - v.log10().floor()) as usize, value),
+        };
+        if self.decimal_comma { text.replace('.', ",") } else { text }
+    }
+
+    /// Band of a single score, as the engine interpreted it for the assessment's
+    /// methodology, or on the default bands for results from before interpretations
+    pub fn band(&self, score: &SingleScoreResult) -> &'static str {
+        let band = score.interpretation.as_ref()
+            .map_or_else(|| ScoreBands::default().band(score.value), |interpretation| interpretation.band);
+        match band {
+            ScoreBand::Excellent => self.bands[0],
+            ScoreBand::Good => self.bands[1],
+            ScoreBand::Average => self.bands[2],
+            ScoreBand::Poor => self.bands[3],
+        }
+    }
+
+    fn confidence_level(&self, level: &ConfidenceLevel) -> &'static str {
+        match level {
+            ConfidenceLevel::High => self.confidence_levels[0],
+            ConfidenceLevel::Medium => self.confidence_levels[1],
+            ConfidenceLevel::Low => self.confidence_levels[2],
+            ConfidenceLevel::VeryLow => self.confidence_levels[3],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportKind {
+    Farm,
+    Facility,
+}
+
+/// One food's or product's part of a category
+#[derive(Debug, Clone)]
+pub struct Share {
+    pub label: String,
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CategoryBar {
+    pub category: String,
+    pub value: f64,
+    pub unit: String,
+    pub shares: Vec<Share>, // by food or product, summing to 100 when the category has any impact
+    pub sources: Vec<String>,
+}
+
+/// What a report shows, independent of its language
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub kind: ReportKind,
+    pub name: String,
+    pub location: String,
+    pub assessment_date: String,
+    pub currency: String,
+    pub schema_version: String, // of the assessment it was made from
+    pub single_score: SingleScoreResult,
+    pub categories: Vec<CategoryBar>, // sorted by category
+    pub contributors: Vec<String>,    // foods or products, in the order of their colours
+    pub data_quality: DataQuality,
+    pub recommendations: Vec<Recommendation>,
+}
+
+impl Report {
+    pub fn for_farm(assessment: &Assessment) -> Result<Self, ReportError> {
+        let results = assessment.results.as_ref().ok_or(ReportError::NotAssessed)?;
+        let location = match &assessment.region {
+            Some(region) => format!("{}, {}", region, assessment.country),
+            None => assessment.country.to_string(),
+        };
+        Ok(Self::new(
+            ReportKind::Farm, assessment.company_name.clone(), location,
+            assessment.assessment_date.format("%Y-%m-%d").to_string(), assessment.currency.code().to_string(),
+            assessment.schema_version.clone(), results
+        ))
+    }
+
+    pub fn for_facility(assessment: &ProcessingAssessment) -> Result<Self, ReportError> {
+        let results = assessment.results.as_ref().ok_or(ReportError::NotAssessed)?;
+        let profile = &assessment.facility_profile;
+        let location = match &assessment.region {
+            Some(region) => format!("{}, {}", region, assessment.country),
+            None => assessment.country.to_string(),
+        };
+        Ok(Self::new(
+            ReportKind::Facility, format!("{} ({})", profile.facility_name, profile.company_name), location,
+            assessment.assessment_date.format("%Y-%m-%d").to_string(),
+            assessment.currency.code().to_string(), assessment.schema_version.clone(), results
+        ))
+    }
+
+    fn new(
+        kind: ReportKind,
+        name: String,
+        location: String,
+        assessment_date: String,
+        currency: String,
+        schema_version: String,
+        results: &LCAResults
+    ) -> Self {
+        // Breakdown keys are "<name> (<quantity>)"
+        let mut breakdown: Vec<(&str, &std::collections::BTreeMap<String, MidpointResult>)> = results.breakdown_by_food.iter()
+            .map(|(key, impacts)| (key.rfind(" (").map_or(key.as_str(), |end| &key[..end]), impacts))
+            .collect();
+        breakdown.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut categories: Vec<CategoryBar> = results.midpoint_impacts.iter()
+            .map(|(category, result)| {
+                let parts: Vec<(&str, f64)> = breakdown.iter()
+                    .map(|(label, impacts)| (*label, impacts.get(category).map_or(0.0, |r| r.value.max(0.0))))
+                    .collect();
+                let total: f64 = parts.iter().map(|(_, value)| value).sum();
+                CategoryBar {
+                    category: category.clone(),
+                    value: result.value,
+                    unit: result.unit.clone(),
+                    shares: parts.into_iter()
+                        .filter(|_| total > 0.0)
+                        .map(|(label, value)| Share { label: label.to_string(), percent: value / total * 100.0 })
+                        .collect(),
+                    sources: result.contributing_sources.clone(),
+                }
+            })
+            .collect();
+        categories.sort_by(|a, b| a.category.cmp(&b.category));
+
+        Self {
+            kind,
+            name,
+            location,
+            assessment_date,
+            currency,
+            schema_version,
+            single_score: results.single_score.clone(),
+            categories,
+            contributors: breakdown.iter().map(|(label, _)| label.to_string()).collect(),
+            data_quality: results.data_quality.clone(),
+            recommendations: results.recommendations.clone().unwrap_or_default(),
+        }
+    }
+
+    /// The report as a self-contained HTML page
+    pub fn to_html(&self, language: Language) -> Result<String, ReportError> {
+        Ok(ReportPage::new(self, language).render()?)
+    }
+
+    /// The report as an A4 PDF
+    #[cfg(feature = "pdf")]
+    pub fn to_pdf(&self, language: Language) -> Result<Vec<u8>, ReportError> {
+        pdf::render(&ReportPage::new(self, language))
+    }
+}
+
+struct SegmentView {
+    label: String,
+    percent: String, // for display
+    width: f64,      // bar width in %
+    color: &'static str,
+}
+
+struct CategoryView {
+    name: String,
+    value: String,
+    unit: String,
+    segments: Vec<SegmentView>,
+}
+
+struct SourcesView {
+    name: String,
+    value: String,
+    unit: String,
+    sources: Vec<String>,
+}
+
+struct RecommendationView {
+    title: String,
+    description: String,
+    priority: &'static str,
+    difficulty: &'static str,
+    cost: &'static str,
+    savings: Option<String>,
+    payback: Option<String>,
+}
+
+/// The report laid out in one language
+#[derive(Template)]
+#[template(path = "report.html")]
+struct ReportPage {
+    labels: &'static Labels,
+    title: &'static str,
+    name: String,
+    location: String,
+    assessment_date: String,
+    score: String,
+    score_position: f64, // all in % of the gauge
+    range_start: f64,
+    range_width: f64,
+    range: String,
+    band: &'static str,
+    categories_explanation: &'static str,
+    legend: Vec<(String, &'static str)>,
+    categories: Vec<CategoryView>,
+    sources: Vec<SourcesView>,
+    confidence: &'static str,
+    quality: Vec<(&'static str, String)>,
+    warnings: Vec<String>,
+    recommendations: Vec<RecommendationView>,
+    schema_version: String,
+}
+
+impl ReportPage {
+    fn new(report: &Report, language: Language) -> Self {
+        let labels = language.labels();
+        let percent = |value: f64| (value * 100.0).clamp(0.0, 100.0);
+        let score = &report.single_score;
+        let color = |index: usize| COLORS[index % COLORS.len()];
+
+        let categories = report.categories.iter()
+            .map(|bar| CategoryView {
+                name: language.category(&bar.category),
+                value: labels.number(bar.value),
+                unit: bar.unit.clone(),
+                segments: bar.shares.iter()
+                    .map(|share| SegmentView {
+                        label: share.label.clone(),
+                        percent: format!("{:.0}", share.percent),
+                        width: share.percent,
+                        color: color(report.contributors.iter().position(|c| *c == share.label).unwrap_or(0)),
+                    })
+                    .collect(),
+            })
+            .collect();
+        let sources = HEADLINE_CATEGORIES.iter()
+            .filter_map(|headline| report.categories.iter().find(|bar| bar.category == *headline))
+            .map(|bar| SourcesView {
+                name: language.category(&bar.category),
+                value: labels.number(bar.value),
+                unit: bar.unit.clone(),
+                sources: bar.sources.iter().take(MAX_SOURCES).cloned().collect(),
+            })
+            .collect();
+
+        let quality = &report.data_quality;
+        let recommendations = report.recommendations.iter()
+            .map(|recommendation| RecommendationView {
+                title: recommendation.title.clone(),
+                description: recommendation.description.clone(),
+                priority: match recommendation.priority {
+                    Priority::Low => labels.levels[0],
+                    Priority::Medium => labels.levels[1],
+                    Priority::High => labels.levels[2],
+                },
+                difficulty: match recommendation.implementation_difficulty {
+                    DifficultyLevel::Low => labels.levels[0],
+                    DifficultyLevel::Medium => labels.levels[1],
+                    DifficultyLevel::High => labels.levels[2],
+                },
+                cost: match recommendation.cost_category {
+                    CostCategory::NoCost => labels.costs[0],
+                    CostCategory::LowCost => labels.costs[1],
+                    CostCategory::MediumCost => labels.costs[2],
+                    CostCategory::HighCost => labels.costs[3],
+                },
+                savings: recommendation.estimated_annual_savings
+                    .map(|savings| format!("{} {}", labels.number(savings), report.currency)),
+                payback: recommendation.payback_months
+                    .map(|months| format!("{:.0} {}", months, labels.months)),
+            })
+            .collect();
+
+        Self {
+            labels,
+            title: match report.kind {
+                ReportKind::Farm => labels.farm_title,
+                ReportKind::Facility => labels.facility_title,
+            },
+            name: report.name.clone(),
+            location: report.location.clone(),
+            assessment_date: report.assessment_date.clone(),
+            score: format!("{:.0}%", percent(score.value)),
+            score_position: percent(score.value),
+            range_start: percent(score.uncertainty_range.0),
+            range_width: (percent(score.uncertainty_range.1) - percent(score.uncertainty_range.0)).max(0.0),
+            range: format!("{:.0}% - {:.0}%", percent(score.uncertainty_range.0), percent(score.uncertainty_range.1)),
+            band: labels.band(score),
+            categories_explanation: match report.kind {
+                ReportKind::Farm => labels.categories_explanation_farm,
+                ReportKind::Facility => labels.categories_explanation_facility,
+            },
+            legend: report.contributors.iter().enumerate().map(|(index, label)| (label.clone(), color(index))).collect(),
+            categories,
+            sources,
+            confidence: labels.confidence_level(&quality.overall_confidence),
+            quality: vec![
+                (labels.completeness, format!("{:.0}%", percent(quality.completeness_score))),
+                (labels.temporal, format!("{:.0}%", percent(quality.temporal_representativeness))),
+                (labels.geographical, format!("{:.0}%", percent(quality.geographical_representativeness))),
+                (labels.technological, format!("{:.0}%", percent(quality.technological_representativeness))),
+            ],
+            warnings: quality.warnings.clone(),
+            recommendations,
+            schema_version: report.schema_version.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "pdf")]
+mod pdf {
+    use super::{ReportError, ReportPage};
+    use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+
+    const LINE_CHARS: usize = 95;
+
+    /// Writes lines top to bottom, starting a new page when one is full
+    struct Writer {
+        document: PdfDocumentReference,
+        layer: PdfLayerReference,
+        regular: IndirectFontRef,
+        bold: IndirectFontRef,
+        y: f32,
+    }
+
+    impl Writer {
+        fn line(&mut self, text: &str, size: f32, bold: bool) {
+            for chunk in wrap(text, LINE_CHARS * 10 / size as usize) {
+                if self.y < 20.0 {
+                    let (page, layer) = self.document.add_page(Mm(210.0), Mm(297.0), "Report");
+                    self.layer = self.document.get_page(page).get_layer(layer);
+                    self.y = 277.0;
+                }
+                let font = if bold { &self.bold } else { &self.regular };
+                self.layer.use_text(chunk, size, Mm(20.0), Mm(self.y), font);
+                self.y -= size * 0.5;
+            }
+        }
+
+        fn gap(&mut self) {
+            self.y -= 4.0;
+        }
+    }
+
+    fn wrap(text: &str, width: usize) -> Vec<String> {
+        let mut lines = vec![String::new()];
+        for word in text.split_whitespace() {
+            let line = lines.last_mut().unwrap();
+            if !line.is_empty() && line.chars().count() + word.chars().count() >= width {
+                lines.push(word.to_string());
+            } else {
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+        }
+        lines
+    }
+
+    pub(super) fn render(page: &ReportPage) -> Result<Vec<u8>, ReportError> {
+        let labels = page.labels;
+        let (document, first_page, first_layer) = PdfDocument::new(page.title, Mm(210.0), Mm(297.0), "Report");
+        let mut writer = Writer {
+            layer: document.get_page(first_page).get_layer(first_layer),
+            regular: document.add_builtin_font(BuiltinFont::Helvetica)?,
+            bold: document.add_builtin_font(BuiltinFont::HelveticaBold)?,
+            document,
+            y: 277.0,
+        };
+
+        writer.line(page.title, 18.0, true);
+        writer.line(&format!("{} - {} - {} {}", page.name, page.location, labels.assessed_on, page.assessment_date), 10.0, false);
+        writer.gap();
+        writer.line(&format!("{}: {} ({})", labels.score, page.score, page.band), 14.0, true);
+        writer.line(&format!("{}: {}", labels.likely_range, page.range), 10.0, false);
+        writer.line(labels.score_explanation, 9.0, false);
+        writer.gap();
+
+        writer.line(labels.categories, 14.0, true);
+        for category in &page.categories {
+            let shares: Vec<String> = category.segments.iter().map(|s| format!("{} {}%", s.label, s.percent)).collect();
+            writer.line(&format!("{}: {} {}  {}", category.name, category.value, category.unit, shares.join(", ")), 10.0, false);
+        }
+        writer.gap();
+
+        writer.line(labels.sources, 14.0, true);
+        for headline in &page.sources {
+            writer.line(&format!("{} ({} {})", headline.name, headline.value, headline.unit), 11.0, true);
+            for source in &headline.sources {
+                writer.line(&format!("- {}", source), 9.0, false);
+            }
+        }
+        writer.gap();
+
+        writer.line(labels.data_quality, 14.0, true);
+        writer.line(&format!("{}: {}", labels.confidence, page.confidence), 10.0, false);
+        for (label, value) in &page.quality {
+            writer.line(&format!("{}: {}", label, value), 10.0, false);
+        }
+        if !page.warnings.is_empty() {
+            writer.line(labels.points_to_check, 11.0, true);
+            for warning in &page.warnings {
+                writer.line(&format!("- {}", warning), 9.0, false);
+            }
+        }
+        writer.gap();
+
+        writer.line(labels.recommendations, 14.0, true);
+        if page.recommendations.is_empty() {
+            writer.line(labels.no_recommendations, 10.0, false);
+        }
+        for recommendation in &page.recommendations {
+            writer.line(&recommendation.title, 11.0, true);
+            writer.line(&recommendation.description, 10.0, false);
+            let mut details = format!(
+                "{}: {}  {}: {}  {}: {}",
+                labels.priority, recommendation.priority, labels.difficulty, recommendation.difficulty, labels.cost, recommendation.cost
+            );
+            if let Some(savings) = &recommendation.savings {
+                details.push_str(&format!("  {}: {}", labels.savings, savings));
+            }
+            if let Some(payback) = &recommendation.payback {
+                details.push_str(&format!("  {} {}", labels.payback, payback));
+            }
+            writer.line(&details, 9.0, false);
+        }
+        writer.gap();
+        writer.line(&format!("{}: {}", labels.schema_version, page.schema_version), 8.0, false);
+
+        Ok(writer.document.save_to_bytes()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{create_comprehensive_input, create_processing_input};
+    use crate::runner::{AssessmentRunner, RunnerConfig};
+
+    const FARM: &str = include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+    const FACILITY: &str = include_str!("../../app/processing/example_cassava_processing.json");
+
+    fn farm() -> Assessment {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        runner.run_comprehensive(create_comprehensive_input(&serde_json::from_str(FARM).unwrap()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn farm_report_shows_the_results() {
+        let farm = farm();
+        let results = farm.results.as_ref().unwrap();
+        let report = Report::for_farm(&farm).unwrap();
+        let html = report.to_html(Language::English).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<html lang=\"en\">"));
+        assert!(html.contains("Farm environmental report"));
+        assert!(html.contains(&format!("{:.0}%", results.single_score.value * 100.0)));
+        assert!(html.contains(&ENGLISH.number(results.midpoint_impacts["Global warming"].value)));
+        assert_eq!(html.matches("class=\"category\"").count(), results.midpoint_impacts.len());
+        assert!(html.contains(">Maize<") && html.contains(">Cowpea<"));
+
+        // Shares of each category add up across the foods
+        for bar in &report.categories {
+            let total: f64 = bar.shares.iter().map(|share| share.percent).sum();
+            assert!(bar.shares.is_empty() || (total - 100.0).abs() < 1e-9, "{} shares sum to {}", bar.category, total);
+        }
+        for recommendation in results.recommendations.as_ref().unwrap() {
+            assert!(html.contains(&recommendation.title.replace('&', "&amp;").replace('\'', "&#x27;")), "missing {}", recommendation.title);
+        }
+        assert!(html.contains("Overall confidence"));
+    }
+
+    #[test]
+    fn reports_are_localized_in_french() {
+        let farm = farm();
+        let html = Report::for_farm(&farm).unwrap().to_html(Language::French).unwrap();
+        assert!(html.contains("<html lang=\"fr\">"));
+        assert!(html.contains("Rapport environnemental de l&#x27;exploitation"));
+        assert!(html.contains("Réchauffement climatique"));
+        assert!(html.contains("Actions recommandées"));
+        assert!(!html.contains("Impacts by category"));
+
+        let gwp = farm.results.as_ref().unwrap().midpoint_impacts["Global warming"].value;
+        assert!(html.contains(&format!("{:.0}", gwp)) || html.contains(&format!("{:.2}", gwp).replace('.', ",")));
+        assert_eq!("fr".parse::<Language>().unwrap(), Language::French);
+        assert!("de".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn facility_report_splits_by_product() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let facility = runner.run_processing(create_processing_input(&serde_json::from_str(FACILITY).unwrap()).unwrap()).unwrap();
+        let html = Report::for_facility(&facility).unwrap().to_html(Language::English).unwrap();
+
+        assert!(html.contains("Processing facility environmental report"));
+        assert!(html.contains(&facility.facility_profile.facility_name));
+        for product in &facility.processed_products {
+            assert!(html.contains(&format!(">{}<", product.name)), "missing {}", product.name);
+        }
+
+        // Costs are in the currency the facility gave, not the one of its country
+        let facility = ProcessingAssessment { currency: Currency::USD, ..facility };
+        assert_eq!(Report::for_facility(&facility).unwrap().currency, "USD");
+    }
+
+    #[test]
+    fn numbers_keep_three_significant_digits() {
+        assert_eq!(ENGLISH.number(1234.56), "1235");
+        assert_eq!(ENGLISH.number(4.2), "4.20");
+        assert_eq!(ENGLISH.number(0.000123456), "0.000123");
+        assert_eq!(FRENCH.number(0.0456), "0,0456");
+        assert_eq!(FRENCH.number(0.0), "0");
+    }
+
+    #[test]
+    fn unassessed_farms_have_no_report() {
+        let mut farm = farm();
+        farm.results = None;
+        assert!(matches!(Report::for_farm(&farm), Err(ReportError::NotAssessed)));
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn pdf_report_is_a_pdf() {
+        let pdf = Report::for_farm(&farm()).unwrap().to_pdf(Language::French).unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+    }
+}
@@ -0,0 +1,546 @@
+/*!
+ * Assessment Runner - library entry point
+ *
+ * Runs farm, processing and company assessments from typed inputs without going through
+ * the CLI's JSON handling. Factors are loaded once by `AssessmentRunner::new`; the runner is
+ * cheap to clone and can be shared between threads, every call working on its own engine state.
+ */
+
+use crate::processing::{CompanyAssessment, ProcessedProduct, ProcessingAssessment, ProcessingBenchmark, ProcessingDataLoader,
+    ProcessingFacilityProfile, ProcessingImpactFactor, ProcessingLCAEngine, ProcessingOperations, ProcessingScaleCurve, RegionalFactorKey};
+use crate::production::*;
+use crate::error::EngineError;
+use crate::plausibility::{attach_warnings, Plausibility};
+use crate::schema::SCHEMA_VERSION;
+use crate::farm_to_fork::FarmToForkEngine;
+use chrono::Utc;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunnerConfig {
+    pub seed: Option<u64>, // for the stochastic methods; DEFAULT_SEED when None
+    pub include_inventory: bool, // attach the elementary flows to results
+    pub include_capital_goods: bool, // amortize farm equipment manufacturing
+    pub modeling_approach: ModelingApproach,
+    pub impact_categories: Option<Vec<String>>, // None assesses all categories
+    #[serde(default)]
+    pub plausibility: Plausibility, // sanity checks on the input before it is run
+    #[serde(default = "merge_duplicates_by_default")]
+    pub merge_duplicate_foods: bool, // sum foods listed more than once into one entry
+    #[serde(default)]
+    pub include_result_metadata: bool, // machine-readable category and unit metadata on results
+    #[serde(default)]
+    pub exchange_rates: ExchangeRates, // recorded on every assessment run
+}
+
+fn merge_duplicates_by_default() -> bool {
+    true
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            include_inventory: true,
+            include_capital_goods: false,
+            modeling_approach: ModelingApproach::Attributional,
+            impact_categories: None,
+            plausibility: Plausibility::default(),
+            merge_duplicate_foods: true,
+            include_result_metadata: false,
+            exchange_rates: ExchangeRates::default(),
+        }
+    }
+}
+
+/// Everything the runner's engines are loaded with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FactorDatabases {
+    pub impact_factors: Vec<ImpactFactor>,
+    pub climate_adjustments: HashMap<String, f64>,
+    pub climate_zones: Vec<ClimateZoneBand>,
+    pub region_zones: Vec<RegionZone>,
+    pub climate_adjustment_rules: Vec<ClimateAdjustmentRule>,
+    pub processing_factors: Vec<ProcessingImpactFactor>,
+    pub processing_benchmarks: Vec<ProcessingBenchmark>,
+    pub scale_curves: Vec<ProcessingScaleCurve>,
+    pub processing_regional_factors: Vec<(RegionalFactorKey, f64)>, // by key name, so the order is stable
+    pub capex_table: HashMap<String, f64>,
+}
+
+impl FactorDatabases {
+    /// The built-in defaults of DataLoader and ProcessingDataLoader
+    pub fn defaults() -> Self {
+        let mut data_loader = DataLoader::new();
+        if let Err(e) = data_loader.load_default_factors() {
+            warn!("Error loading default factors: {}", e);
+        }
+        let mut processing_loader = ProcessingDataLoader::new();
+        if let Err(e) = processing_loader.load_default_factors() {
+            warn!("Error loading processing factors: {}", e);
+        }
+
+        Self {
+            impact_factors: Arc::unwrap_or_clone(data_loader.impact_factors),
+            climate_adjustments: data_loader.climate_adjustments,
+            climate_zones: data_loader.climate_zones,
+            region_zones: data_loader.region_zones,
+            climate_adjustment_rules: data_loader.climate_adjustment_rules,
+            processing_factors: processing_loader.impact_factors,
+            processing_benchmarks: processing_loader.benchmarks,
+            scale_curves: processing_loader.scale_curves,
+            processing_regional_factors: {
+                let mut factors: Vec<_> = processing_loader.regional_factors.into_iter().collect();
+                factors.sort_by_cached_key(|(key, _)| key.to_string());
+                factors
+            },
+            capex_table: processing_loader.capex_table,
+        }
+    }
+}
+
+/// Farm assessment from the food list alone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleInput {
+    pub company_name: String,
+    pub country: Country,
+    #[serde(default)]
+    pub currency: Option<Currency>, // the country's own when not given
+    pub coordinates: Option<GeoCoordinates>,
+    pub foods: Vec<FoodItem>,
+    pub weighting_method: WeightingMethod,
+}
+
+/// Farm assessment with farm profile, management practices and equipment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComprehensiveInput {
+    pub company_name: String,
+    pub country: Country,
+    #[serde(default)]
+    pub currency: Option<Currency>, // the country's own when not given
+    pub region: Option<String>,
+    pub coordinates: Option<GeoCoordinates>,
+    pub foods: Vec<FoodItem>,
+    pub weighting_method: WeightingMethod,
+    pub farm_profile: Option<FarmProfile>,
+    pub management_practices: Option<ManagementPractices>,
+    pub equipment_energy: Option<EquipmentEnergy>,
+}
+
+/// Processing facility assessment, optionally compared with an earlier run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingInput {
+    pub facility_profile: ProcessingFacilityProfile,
+    pub processing_operations: ProcessingOperations,
+    pub processed_products: Vec<ProcessedProduct>,
+    pub country: Country,
+    #[serde(default)]
+    pub currency: Option<Currency>, // the country's own when not given
+    pub region: Option<String>,
+    pub previous_results: Option<ProcessingAssessment>, // assessed; fills trend_analysis
+}
+
+impl ProcessingInput {
+    /// Unassessed facility, as run by `run_processing` or listed in a company assessment
+    pub fn into_assessment(self, config: &RunnerConfig) -> ProcessingAssessment {
+        ProcessingAssessment {
+            schema_version: SCHEMA_VERSION.to_string(),
+            id: Uuid::new_v4(),
+            facility_profile: self.facility_profile,
+            processing_operations: self.processing_operations,
+            processed_products: self.processed_products,
+            currency: self.currency.unwrap_or_else(|| Currency::from_country(&self.country)),
+            exchange_rates: config.exchange_rates.clone(),
+            country: self.country,
+            region: self.region,
+            assessment_date: Utc::now(),
+            methodology: processing_methodology(config),
+            results: None,
+            trend_analysis: None,
+        }
+    }
+}
+
+/// Loads factors once and runs any number of assessments, from one or many threads.
+///
+/// ```
+/// use african_lca_backend::*;
+///
+/// let runner = AssessmentRunner::new(RunnerConfig::default());
+/// let maize: FoodItem = serde_json::from_value(serde_json::json!({
+///     "id": "maize", "name": "Maize", "quantity_kg": 1000.0, "category": "Cereals"
+/// })).unwrap();
+///
+/// let assessment = runner.run_simple(SimpleInput {
+///     company_name: "Tamale Grains".to_string(),
+///     country: Country::Ghana,
+///     currency: None,
+///     coordinates: None,
+///     foods: vec![maize],
+///     weighting_method: WeightingMethod::AfricanPriorities,
+/// }).unwrap();
+///
+/// let results = assessment.results.unwrap();
+/// assert!(results.midpoint_impacts["Global warming"].value > 0.0);
+/// ```
+#[derive(Clone)]
+pub struct AssessmentRunner {
+    config: RunnerConfig,
+    production: Arc<AfricanLCAEngine>, // factors loaded; cloned per run
+    processing: Arc<ProcessingLCAEngine>,
+}
+
+impl AssessmentRunner {
+    /// Runner on the built-in factor databases
+    pub fn new(config: RunnerConfig) -> Self {
+        Self::with_factors(config, FactorDatabases::defaults())
+    }
+
+    pub fn with_factors(config: RunnerConfig, factors: FactorDatabases) -> Self {
+        let mut production = AfricanLCAEngine::new_with_seed(farm_methodology(WeightingMethod::AfricanPriorities, &config), config.seed);
+        production.load_factor_index(Arc::new(factors.impact_factors.into_iter().collect()));
+        production.load_climate_adjustments(factors.climate_adjustments);
+        production.load_climate_zones(factors.climate_zones);
+        production.load_region_zones(factors.region_zones);
+        production.load_climate_adjustment_rules(factors.climate_adjustment_rules);
+
+        let mut processing = ProcessingLCAEngine::new(processing_methodology(&config));
+        processing.load_impact_factors(factors.processing_factors);
+        processing.load_benchmarks(factors.processing_benchmarks);
+        processing.load_scale_curves(factors.scale_curves);
+        processing.load_regional_factors(factors.processing_regional_factors);
+        processing.load_capex_table(factors.capex_table);
+
+        Self {
+            config,
+            production: Arc::new(production),
+            processing: Arc::new(processing),
+        }
+    }
+
+    pub fn config(&self) -> &RunnerConfig {
+        &self.config
+    }
+
+    /// Runner with other settings sharing this runner's loaded factors
+    pub fn with_config(&self, config: RunnerConfig) -> Self {
+        Self {
+            config,
+            production: Arc::clone(&self.production),
+            processing: Arc::clone(&self.processing),
+        }
+    }
+
+    pub fn run_simple(&self, input: SimpleInput) -> Result<Assessment, EngineError> {
+        let mut warnings = self.config.plausibility.enforce(self.config.plausibility.check_farm(&input.foods, None))?;
+        let foods = self.merge_duplicates(input.foods, &mut warnings);
+        let mut assessment = self.farm_assessment(input.company_name, input.country, None, input.coordinates, foods, input.weighting_method);
+        assessment.currency = input.currency.unwrap_or(assessment.currency);
+        self.farm_engine(&assessment).perform_assessment(&mut assessment)?;
+        attach_warnings(assessment.results.as_mut(), warnings);
+        Ok(assessment)
+    }
+
+    pub fn run_comprehensive(&self, input: ComprehensiveInput) -> Result<Assessment, EngineError> {
+        let plausibility = &self.config.plausibility;
+        let mut warnings = plausibility.enforce(plausibility.check_farm(&input.foods, input.management_practices.as_ref()))?;
+        let foods = self.merge_duplicates(input.foods, &mut warnings);
+        let mut assessment = self.farm_assessment(input.company_name, input.country, input.region, input.coordinates, foods, input.weighting_method);
+        assessment.currency = input.currency.unwrap_or(assessment.currency);
+        assessment.farm_profile = input.farm_profile;
+        assessment.management_practices = input.management_practices;
+        assessment.equipment_energy = input.equipment_energy;
+        self.farm_engine(&assessment).perform_comprehensive_assessment(&mut assessment)?;
+        attach_warnings(assessment.results.as_mut(), warnings);
+        Ok(assessment)
+    }
+
+    pub fn run_processing(&self, mut input: ProcessingInput) -> Result<ProcessingAssessment, EngineError> {
+        let plausibility = &self.config.plausibility;
+        let warnings = plausibility.enforce(plausibility.check_facility(&input.facility_profile, &input.processed_products, ""))?;
+        let previous = input.previous_results.take();
+        let mut assessment = input.into_assessment(&self.config);
+        let engine = self.processing_engine();
+        engine.perform_processing_assessment(&mut assessment)?;
+        attach_warnings(assessment.results.as_mut(), warnings);
+        if let Some(previous) = previous {
+            assessment.trend_analysis = Some(engine.analyze_trends(&assessment, &previous)?);
+        }
+        Ok(assessment)
+    }
+
+    pub fn run_company(&self, mut company: CompanyAssessment) -> Result<CompanyAssessment, EngineError> {
+        let plausibility = &self.config.plausibility;
+        let warnings = company.facilities.iter()
+            .enumerate()
+            .map(|(i, facility)| plausibility.enforce(plausibility.check_facility(
+                &facility.facility_profile, &facility.processed_products, &format!("facilities[{}]", i)
+            )))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.processing_engine().perform_company_assessment(&mut company)?;
+        for (facility, warnings) in company.facilities.iter_mut().zip(warnings) {
+            attach_warnings(facility.results.as_mut(), warnings);
+        }
+        Ok(company)
+    }
+
+    /// Foods listed more than once merged into one entry, unless the config keeps them apart.
+    /// Runs after the plausibility checks, whose field paths point into the input as given.
+    fn merge_duplicates(&self, foods: Vec<FoodItem>, warnings: &mut Vec<String>) -> Vec<FoodItem> {
+        if !self.config.merge_duplicate_foods {
+            return foods;
+        }
+        let (foods, merges) = merge_duplicate_foods(foods);
+        warnings.extend(merges);
+        foods
+    }
+
+    fn farm_assessment(
+        &self,
+        company_name: String,
+        country: Country,
+        region: Option<String>,
+        coordinates: Option<GeoCoordinates>,
+        foods: Vec<FoodItem>,
+        weighting_method: WeightingMethod,
+    ) -> Assessment {
+        Assessment {
+            schema_version: SCHEMA_VERSION.to_string(),
+            id: Uuid::new_v4(),
+            company_name,
+            currency: Currency::from_country(&country),
+            exchange_rates: self.config.exchange_rates.clone(),
+            country,
+            region,
+            coordinates,
+            foods,
+            assessment_date: Utc::now(),
+            methodology: farm_methodology(weighting_method, &self.config),
+            results: None,
+            farm_profile: None,
+            management_practices: None,
+            equipment_energy: None,
+        }
+    }
+
+    /// Farm-to-fork engine on this runner's factors, for a farm from this runner
+    pub fn farm_to_fork_engine(&self, farm: &Assessment) -> FarmToForkEngine {
+        FarmToForkEngine::new(self.farm_engine(farm), self.processing_engine())
+    }
+
+    /// Fresh engine state for one run, sharing nothing mutable with other runs
+    fn farm_engine(&self, assessment: &Assessment) -> AfricanLCAEngine {
+        let mut engine = AfricanLCAEngine::clone(&self.production);
+        engine.set_methodology(assessment.methodology.clone());
+        engine.set_seed(self.config.seed);
+        engine
+    }
+
+    /// Processing engine with this runner's methodology settings
+    fn processing_engine(&self) -> ProcessingLCAEngine {
+        let mut engine = ProcessingLCAEngine::clone(&self.processing);
+        engine.set_methodology(processing_methodology(&self.config));
+        engine
+    }
+}
+
+fn farm_methodology(weighting_method: WeightingMethod, config: &RunnerConfig) -> LCAMethodology {
+    LCAMethodology::african_production().to_builder()
+        .weighting_method(Some(weighting_method))
+        .include_inventory(config.include_inventory)
+        .include_capital_goods(config.include_capital_goods)
+        .modeling_approach(config.modeling_approach)
+        .impact_categories(config.impact_categories.clone())
+        .include_result_metadata(config.include_result_metadata)
+        .build()
+}
+
+fn processing_methodology(config: &RunnerConfig) -> LCAMethodology {
+    LCAMethodology::african_processing().to_builder()
+        .include_inventory(config.include_inventory)
+        .modeling_approach(config.modeling_approach)
+        .impact_categories(config.impact_categories.clone())
+        .include_result_metadata(config.include_result_metadata)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::InputError;
+    use crate::input::{run_input, AssessmentKind};
+    use crate::plausibility::Strictness;
+    use std::thread;
+
+    fn food(id: &str, category: &str, quantity_kg: f64) -> FoodItem {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "name": id, "quantity_kg": quantity_kg, "category": category
+        })).unwrap()
+    }
+
+    fn simple_input(quantity_kg: f64) -> SimpleInput {
+        SimpleInput {
+            company_name: "Test Farm".to_string(),
+            country: Country::Ghana,
+            currency: None,
+            coordinates: None,
+            foods: vec![food("maize", "Cereals", quantity_kg), food("cowpea", "Legumes", quantity_kg / 2.0)],
+            weighting_method: WeightingMethod::AfricanPriorities,
+        }
+    }
+
+    /// The farm's total; the result is per kg
+    fn global_warming(assessment: &Assessment) -> f64 {
+        let production_kg: f64 = assessment.foods.iter().map(|food| food.quantity_kg).sum();
+        assessment.results.as_ref().unwrap().midpoint_impacts["Global warming"].value * production_kg
+    }
+
+    #[test]
+    fn concurrent_runs_match_sequential_runs() {
+        let runner = AssessmentRunner::new(RunnerConfig { seed: Some(7), include_inventory: false, ..RunnerConfig::default() });
+        let quantities = [500.0, 1000.0, 2000.0, 4000.0];
+        let sequential: Vec<f64> = quantities.iter()
+            .map(|&q| global_warming(&runner.run_simple(simple_input(q)).unwrap()))
+            .collect();
+
+        let concurrent: Vec<f64> = thread::scope(|scope| {
+            let handles: Vec<_> = quantities.iter()
+                .map(|&q| {
+                    let runner = &runner;
+                    scope.spawn(move || global_warming(&runner.run_simple(simple_input(q)).unwrap()))
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        assert_eq!(sequential, concurrent);
+        assert!(sequential[3] > sequential[0]);
+    }
+
+    #[test]
+    fn cloned_runner_shares_factors_across_threads() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let expected = global_warming(&runner.run_simple(simple_input(1000.0)).unwrap());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let runner = runner.clone();
+                thread::spawn(move || {
+                    let assessment = runner.run_simple(simple_input(1000.0)).unwrap();
+                    (global_warming(&assessment), assessment.results.unwrap().lci_inventory.is_some())
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let (value, has_inventory) = handle.join().unwrap();
+            assert_eq!(value, expected);
+            assert!(has_inventory);
+        }
+        assert!(Arc::ptr_eq(&runner.production, &runner.clone().production));
+    }
+
+    #[test]
+    fn parallel_food_and_product_loops_match_a_single_thread() {
+        let farm: serde_json::Value = serde_json::from_str(include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json")).unwrap();
+        let facility: serde_json::Value = serde_json::from_str(include_str!("../../app/processing/example_cassava_processing.json")).unwrap();
+        let many = |mut input: serde_json::Value, field: &str, count: usize| {
+            let template = input[field][0].clone();
+            input[field] = (0..count).map(|i| {
+                let mut item = template.clone();
+                item["id"] = format!("item-{}", i).into();
+                item["name"] = format!("Item {}", i).into();
+                item["quantity_kg"] = (100.0 + i as f64 * 7.0).into();
+                item["annual_production"] = (1.0 + i as f64 * 0.2).into();
+                item
+            }).collect();
+            input
+        };
+        let runner = AssessmentRunner::new(RunnerConfig { seed: Some(7), ..RunnerConfig::default() });
+        let results = |threads: usize, kind: AssessmentKind, input: &serde_json::Value| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            let json = pool.install(|| run_input(&runner, kind, input)).unwrap();
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()["results"].take()
+        };
+
+        for (kind, input) in [
+            (AssessmentKind::Comprehensive, many(farm.clone(), "foods", 120)),
+            (AssessmentKind::Simple, many(farm, "foods", 120)),
+            (AssessmentKind::Processing, many(facility, "processed_products", 60)),
+        ] {
+            let serial = results(1, kind, &input);
+            assert!(!serial.is_null());
+            assert_eq!(serial, results(8, kind, &input), "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn weighting_method_applies_per_run() {
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let african = runner.run_simple(simple_input(1000.0)).unwrap();
+        let equal = runner.run_simple(SimpleInput { weighting_method: WeightingMethod::EqualWeights, ..simple_input(1000.0) }).unwrap();
+
+        assert_eq!(global_warming(&african), global_warming(&equal));
+        assert_ne!(
+            african.results.unwrap().single_score.weighting_factors,
+            equal.results.unwrap().single_score.weighting_factors
+        );
+    }
+
+    #[test]
+    fn implausible_inputs_warn_when_lenient_and_fail_when_strict() {
+        let lenient = AssessmentRunner::new(RunnerConfig::default());
+        let assessment = lenient.run_simple(simple_input(5e7)).unwrap();
+        let warnings = &assessment.results.unwrap().data_quality.warnings;
+        assert!(warnings.iter().any(|w| w.starts_with("foods[0].quantity_kg is 50000000")), "{:?}", warnings);
+
+        let mut config = RunnerConfig::default();
+        config.plausibility.strictness = Strictness::Strict;
+        let strict = lenient.with_config(config);
+        match strict.run_simple(simple_input(5e7)) {
+            Err(EngineError::Input(InputError::Implausible { field, .. })) => assert_eq!(field, "foods[0].quantity_kg"),
+            other => panic!("expected an implausible quantity, got {:?}", other.map(|_| ())),
+        }
+        // Impossible values fail either way
+        assert!(matches!(
+            lenient.run_simple(simple_input(5e9)),
+            Err(EngineError::Input(InputError::OutOfRange { .. }))
+        ));
+    }
+
+    #[test]
+    fn duplicate_foods_merge_into_one_breakdown_row() {
+        let maize = || food("maize", "Cereals", 1000.0);
+        let input = || SimpleInput { foods: vec![maize(), maize()], ..simple_input(1000.0) };
+
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        let merged = runner.run_simple(input()).unwrap();
+        assert_eq!(merged.foods.len(), 1);
+        assert_eq!(merged.foods[0].quantity_kg, 2000.0);
+        let results = merged.results.unwrap();
+        assert_eq!(results.breakdown_by_food.keys().collect::<Vec<_>>(), ["maize (2000kg)"]);
+        assert!(results.data_quality.warnings.iter().any(|w| w.starts_with("maize (id maize) was listed 2 times")));
+
+        // Kept apart, each entry keeps its own breakdown row
+        let separate_runner = runner.with_config(RunnerConfig { merge_duplicate_foods: false, ..RunnerConfig::default() });
+        let separate = separate_runner.run_simple(input()).unwrap();
+        assert_eq!(separate.foods.len(), 2);
+        assert_eq!(
+            separate.results.unwrap().breakdown_by_food.keys().collect::<Vec<_>>(),
+            ["maize (1000kg, id maize) #1", "maize (1000kg, id maize) #2"]
+        );
+
+        // Same name and quantity but different ids are different fields, not duplicates
+        let fields = vec![food("a", "Cereals", 1000.0), food("b", "Cereals", 1000.0)]
+            .into_iter()
+            .map(|field| FoodItem { name: "Maize".to_string(), ..field })
+            .collect();
+        let results = runner.run_simple(SimpleInput { foods: fields, ..simple_input(1000.0) }).unwrap().results.unwrap();
+        assert_eq!(results.breakdown_by_food.keys().collect::<Vec<_>>(), ["Maize (1000kg, id a)", "Maize (1000kg, id b)"]);
+        assert!(results.data_quality.warnings.iter().all(|w| !w.contains("was listed")));
+    }
+}
@@ -0,0 +1,344 @@
+/*!
+ * Versioned layout of serialized assessments
+ *
+ * Every farm and facility assessment written by this crate records the `schema_version` of
+ * its JSON layout, a semver string: a minor step adds optional fields, a major step adds
+ * required ones or renames. `SCHEMA_CHANGES` is the changelog; bump `SCHEMA_VERSION` and add
+ * an entry with its migration whenever the serialized layout of `Assessment` or
+ * `ProcessingAssessment` changes.
+ *
+ * `migrate` upgrades a document written by an older version to the current layout. Documents
+ * from before versions were recorded are dated by the fields they have.
+ */
+
+use crate::error::InputError;
+use crate::production::{Assessment, Country, Currency};
+use serde_json::{json, Map, Value};
+
+/// Layout of the assessments this build writes
+pub const SCHEMA_VERSION: &str = "3.1.0";
+
+/// Layout of the first farm assessments: no equipment and energy data, no currency
+const OLDEST_VERSION: &str = "1.0.0";
+
+/// One version of the layout and how to upgrade a document from the version before it
+pub struct SchemaChange {
+    pub version: &'static str,
+    pub summary: &'static str,
+    upgrade: fn(&mut Map<String, Value>) -> Result<(), InputError>,
+}
+
+/// Layout changes since `1.0.0`, oldest first
+pub const SCHEMA_CHANGES: &[SchemaChange] = &[
+    SchemaChange {
+        version: "1.1.0",
+        summary: "Farm assessments record `equipment_energy`, null when it was not collected",
+        upgrade: add_equipment_energy,
+    },
+    SchemaChange {
+        version: "2.0.0",
+        summary: "Farm assessments carry a required `currency` for their costs",
+        upgrade: add_currency,
+    },
+    SchemaChange {
+        version: "2.1.0",
+        summary: "Farm and facility assessments record their `schema_version`",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.2.0",
+        summary: "Single scores carry an optional `interpretation`, absent from older results",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.3.0",
+        summary: "Endpoints record the `contributions` of their midpoints and single scores their `endpoint_contributions`",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.4.0",
+        summary: "Facility raw material storage records an optional `storage_days`",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.5.0",
+        summary: "Methodologies may ask for `include_result_metadata`, which adds `metadata` to midpoint and endpoint results",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.6.0",
+        summary: "Aquaculture details may give `feed_type`, `aeration_hours_per_day` and `water_exchange_rate`",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.7.0",
+        summary: "Farm water management may give the irrigation `pump_type` and `lift_height_m`",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.8.0",
+        summary: "Foods may carry a `perennial` plantation profile for tree crops",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.9.0",
+        summary: "Benchmark comparisons state their `basis` and compare intensities instead of totals",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.10.0",
+        summary: "Fuel usage has an optional `currency` for its cost",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.11.0",
+        summary: "Foods have an optional `head_count` of animals sold, for the output-per-animal check",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.12.0",
+        summary: "Data quality reports the pedigree `quality_score` apart from `completeness_score`, now the share of inputs provided",
+        upgrade: split_quality_from_completeness,
+    },
+    SchemaChange {
+        version: "2.13.0",
+        summary: "Benchmark, regional and scenario comparisons state their `significance` against the uncertainty ranges",
+        upgrade: |_| Ok(()),
+    },
+    SchemaChange {
+        version: "2.14.0",
+        summary: "Midpoint results state their `basis`: a total, per kg of product or per hectare",
+        upgrade: add_impact_basis,
+    },
+    SchemaChange {
+        version: "3.0.0",
+        summary: "Facility assessments carry a required `currency` for their tariffs and costs, as farms do",
+        upgrade: add_currency,
+    },
+    SchemaChange {
+        version: "3.1.0",
+        summary: "Farm and facility assessments record the `exchange_rates` their costs were converted at",
+        upgrade: add_exchange_rates,
+    },
+];
+
+/// `SCHEMA_VERSION` as an owned string, for serde defaults
+pub fn current_schema_version() -> String {
+    SCHEMA_VERSION.to_string()
+}
+
+/// A serialized farm or facility assessment upgraded to the current layout. Documents
+/// already at `SCHEMA_VERSION` come back unchanged; those from a newer build are refused.
+pub fn migrate(mut document: Value) -> Result<Value, InputError> {
+    let fields = document.as_object_mut().ok_or_else(|| InputError::WrongType {
+        field: "assessment".to_string(),
+        expected: "an object",
+        value: "a JSON value of another type".to_string(),
+    })?;
+    let version = document_version(fields)?;
+    if version > parse_version(SCHEMA_VERSION)? {
+        return Err(InputError::Invalid {
+            field: "schema_version".to_string(),
+            reason: format!("{} is newer than this build supports ({})", format_version(version), SCHEMA_VERSION),
+        });
+    }
+
+    for change in SCHEMA_CHANGES {
+        if parse_version(change.version)? > version {
+            (change.upgrade)(fields)?;
+        }
+    }
+    fields.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+    Ok(document)
+}
+
+/// The recorded version, or the version an unversioned document's fields date it to
+fn document_version(fields: &Map<String, Value>) -> Result<(u64, u64, u64), InputError> {
+    match fields.get("schema_version") {
+        Some(Value::String(version)) => parse_version(version),
+        Some(other) => Err(InputError::WrongType {
+            field: "schema_version".to_string(),
+            expected: "a version string",
+            value: other.to_string(),
+        }),
+        None if !fields.contains_key("foods") => parse_version("2.0.0"),
+        None if fields.contains_key("currency") => parse_version("2.0.0"),
+        None if fields.contains_key("equipment_energy") => parse_version("1.1.0"),
+        None => parse_version(OLDEST_VERSION),
+    }
+}
+
+fn parse_version(version: &str) -> Result<(u64, u64, u64), InputError> {
+    let invalid = || InputError::Invalid {
+        field: "schema_version".to_string(),
+        reason: format!("{:?} is not a MAJOR.MINOR.PATCH version", version),
+    };
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().map_err(|_| invalid()));
+    let parsed = (
+        parts.next().ok_or_else(invalid)??,
+        parts.next().ok_or_else(invalid)??,
+        parts.next().ok_or_else(invalid)??,
+    );
+    match parts.next() {
+        Some(_) => Err(invalid()),
+        None => Ok(parsed),
+    }
+}
+
+fn format_version((major, minor, patch): (u64, u64, u64)) -> String {
+    format!("{}.{}.{}", major, minor, patch)
+}
+
+/// A farm-only field; facilities are left as they are
+fn add_equipment_energy(fields: &mut Map<String, Value>) -> Result<(), InputError> {
+    if fields.contains_key("foods") {
+        fields.entry("equipment_energy").or_insert(Value::Null);
+    }
+    Ok(())
+}
+
+/// Costs in the first farm assessments, and in facility assessments before 3.0.0, were in
+/// the currency of the country
+fn add_currency(fields: &mut Map<String, Value>) -> Result<(), InputError> {
+    if fields.contains_key("currency") {
+        return Ok(());
+    }
+    let country = fields.get("country").cloned().ok_or_else(|| InputError::Missing { field: "country".to_string() })?;
+    let country: Country = serde_json::from_value(country.clone()).map_err(|_| InputError::UnknownValue {
+        field: "country".to_string(),
+        value: country.to_string(),
+        valid: vec!["Ghana".to_string(), "Nigeria".to_string(), "Global".to_string()],
+    })?;
+    fields.insert("currency".to_string(), Currency::from_country(&country).code().into());
+    Ok(())
+}
+
+/// Farm results before 2.12.0 gave the pedigree quality as `completeness_score`; it moves to
+/// `quality_score` and completeness is scored again from the inputs. Facility completeness
+/// was already the share of factors with a pedigree, whose mean quality the source mix gives.
+fn split_quality_from_completeness(fields: &mut Map<String, Value>) -> Result<(), InputError> {
+    let input_completeness = if fields.contains_key("foods") {
+        let mut inputs = fields.clone();
+        inputs.insert("results".to_string(), Value::Null);
+        let assessment: Assessment = serde_json::from_value(Value::Object(inputs)).map_err(|error| InputError::Invalid {
+            field: "assessment".to_string(),
+            reason: format!("cannot score the completeness of its inputs: {}", error),
+        })?;
+        Some(assessment.input_completeness())
+    } else {
+        None
+    };
+    let Some(quality) = fields.get_mut("results")
+        .and_then(|results| results.get_mut("data_quality"))
+        .and_then(Value::as_object_mut) else {
+        return Ok(());
+    };
+    if quality.contains_key("quality_score") {
+        return Ok(());
+    }
+
+    let quality_score = match input_completeness {
+        Some(completeness) => quality.insert("completeness_score".to_string(), completeness.into()).unwrap_or(0.0.into()),
+        None => {
+            // Default estimates have no pedigree and were left out of the mean
+            let scored: Vec<(f64, f64)> = quality.get("data_source_mix").and_then(Value::as_array).into_iter().flatten()
+                .filter(|source| source["source_type"] != "Estimated")
+                .filter_map(|source| Some((source["percentage"].as_f64()?, source["quality_score"].as_f64()?)))
+                .collect();
+            let share: f64 = scored.iter().map(|(percentage, _)| percentage).sum();
+            let score = if share > 0.0 {
+                scored.iter().map(|(percentage, score)| percentage * score).sum::<f64>() / share
+            } else {
+                0.0
+            };
+            score.into()
+        }
+    };
+    quality.insert("quality_score".to_string(), quality_score);
+    Ok(())
+}
+
+/// Earlier farm results gave their basis only in the unit: "per kg" and "per ha" intensities,
+/// otherwise totals (the default)
+fn add_impact_basis(fields: &mut Map<String, Value>) -> Result<(), InputError> {
+    let Some(results) = fields.get_mut("results").and_then(Value::as_object_mut) else {
+        return Ok(());
+    };
+    let impacts = results.iter_mut()
+        .filter(|(key, _)| *key == "midpoint_impacts" || *key == "impacts_per_ha")
+        .filter_map(|(_, impacts)| impacts.as_object_mut())
+        .flat_map(|impacts| impacts.values_mut().filter_map(Value::as_object_mut));
+    for result in impacts {
+        let unit = result.get("unit").and_then(Value::as_str).unwrap_or_default();
+        let basis = if unit.ends_with(" per kg") {
+            "PerKgProduct"
+        } else if unit.ends_with(" per ha") {
+            "PerHectare"
+        } else {
+            "Total"
+        };
+        result.entry("basis").or_insert(basis.into());
+    }
+    Ok(())
+}
+
+/// Earlier builds converted at fixed 2024 averages of 14 GHS and 1,500 NGN per USD; only the
+/// facility capex table used 15 GHS
+fn add_exchange_rates(fields: &mut Map<String, Value>) -> Result<(), InputError> {
+    fields.entry("exchange_rates").or_insert_with(|| json!({
+        "as_of": "2024 averages",
+        "ghs_per_usd": 14.0,
+        "ngn_per_usd": 1500.0,
+    }));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changelog_is_in_order_and_ends_at_the_current_version() {
+        let versions: Vec<_> = SCHEMA_CHANGES.iter().map(|change| parse_version(change.version).unwrap()).collect();
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(parse_version(OLDEST_VERSION).unwrap() < versions[0]);
+        assert_eq!(SCHEMA_CHANGES.last().unwrap().version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn newer_and_malformed_versions_are_refused() {
+        for version in ["9.0.0", "2.1", "2.1.0.1", "two"] {
+            let error = migrate(json!({ "schema_version": version })).unwrap_err();
+            assert!(matches!(error, InputError::Invalid { .. }), "{version}: {error}");
+        }
+        assert!(migrate(json!([])).is_err());
+    }
+
+    #[test]
+    fn current_documents_are_unchanged() {
+        let document = json!({ "schema_version": SCHEMA_VERSION, "foods": [], "country": "Ghana" });
+        assert_eq!(migrate(document.clone()).unwrap(), document);
+    }
+
+    #[test]
+    fn earlier_farm_results_get_the_basis_of_their_units() {
+        let document = json!({
+            "schema_version": "2.13.0", "foods": [], "country": "Ghana",
+            "results": {
+                "midpoint_impacts": { "Global warming": { "value": 0.7, "unit": "kg CO2-eq per kg" } },
+                "impacts_per_ha": { "Global warming": { "value": 1300.0, "unit": "kg CO2-eq per ha" } }
+            }
+        });
+        let migrated = migrate(document).unwrap();
+        assert_eq!(migrated["results"]["midpoint_impacts"]["Global warming"]["basis"], "PerKgProduct");
+        assert_eq!(migrated["results"]["impacts_per_ha"]["Global warming"]["basis"], "PerHectare");
+    }
+
+    #[test]
+    fn earlier_facilities_get_the_currency_of_their_country() {
+        let document = json!({ "schema_version": "2.14.0", "facility_name": "Mill", "country": "Nigeria" });
+        let migrated = migrate(document).unwrap();
+        assert_eq!(migrated["currency"], "NGN");
+        assert!(migrated.get("equipment_energy").is_none());
+    }
+}
@@ -0,0 +1,151 @@
+/*!
+ * Single score shared by the farm and processing engines
+ *
+ * The optional ISO 14044 steps (4.4.3): each endpoint is divided by its normalization
+ * reference, giving person-equivalents, and the normalized endpoints are weighted and summed.
+ * The sum is shown on a 0-1 scale on which 1.0 is twice the reference impact.
+ *
+ * Farm endpoints are normalized against references per kg of product and facility
+ * endpoints per tonne of product against the same references scaled to the tonne, so equal
+ * burdens per kg give equal scores whichever engine assessed them.
+ */
+
+use crate::error::InputError;
+use crate::production::{DamageContribution, EndpointResult, SingleScoreResult, WeightingMethod};
+use std::collections::BTreeMap;
+
+/// Unit of single scores on the display scale
+pub const SCORE_UNIT: &str = "Environmental Impact Score (0-1 scale, 1.0 = 2× reference impact)";
+
+/// Raw score, in person-equivalents, shown as 1.0 on the display scale
+const FULL_SCALE: f64 = 2.0;
+
+/// African-context normalization reference of an endpoint per kg of product
+pub fn african_reference_per_kg(endpoint: &str) -> Option<f64> {
+    match endpoint {
+        "Human Health" => Some(5.2e-2),      // DALY
+        "Ecosystem Quality" => Some(4.1e-9), // species.yr, biodiversity hotspots
+        "Resource Scarcity" => Some(8.5e3),  // USD, tighter resource constraints
+        _ => None,
+    }
+}
+
+/// Normalization reference for an endpoint: the factor embedded in the result, else the
+/// global per-capita annual reference
+pub fn normalization_reference(category: &str, result: &EndpointResult) -> f64 {
+    // A zero or non-finite reference would turn the single score into infinity or NaN
+    result.normalization_factor.filter(|factor| factor.is_finite() && *factor > 0.0).unwrap_or(match category {
+        "Human Health" => 2.2e-2,      // DALY per kg global average
+        "Ecosystem Quality" => 1.8e-9, // species.yr per kg
+        "Resource Scarcity" => 4.2e3,  // USD per kg
+        _ => 1.0,
+    })
+}
+
+/// Weights of the endpoints present, rescaled to keep their original total, so a subset
+/// of impact categories is scored on what was assessed rather than counted as zero impact
+pub fn available_weights(
+    weights: BTreeMap<String, f64>,
+    endpoint: &BTreeMap<String, EndpointResult>
+) -> BTreeMap<String, f64> {
+    let total: f64 = weights.values().sum();
+    let available: BTreeMap<String, f64> = weights.into_iter()
+        .filter(|(category, _)| endpoint.contains_key(category))
+        .collect();
+    let available_total: f64 = available.values().sum();
+    if available_total <= 0.0 {
+        return available;
+    }
+    available.into_iter()
+        .map(|(category, weight)| (category, weight * total / available_total))
+        .collect()
+}
+
+/// Single score of endpoint results per functional unit, without interpretation, and the
+/// weighted contribution of each endpoint in person-equivalents. `reference_basis` names the
+/// normalization in the methodology note.
+pub fn weighted_single_score(
+    endpoint: &BTreeMap<String, EndpointResult>,
+    weighting_method: &WeightingMethod,
+    reference_basis: &str
+) -> Result<(SingleScoreResult, BTreeMap<String, f64>), InputError> {
+    // Weighting is value-choice dependent and should be transparent
+    weighting_method.validate()?;
+    let weighting_factors = available_weights(weighting_method.weights(), endpoint);
+
+    let mut single_score = 0.0;
+    let mut score_variance = 0.0;
+    let mut contributions = BTreeMap::new();
+    let mut weighted_ranges = Vec::new();
+    for (category, result) in endpoint {
+        let Some(weight) = weighting_factors.get(category) else { continue };
+        // Normalized score = endpoint / reference, in person-equivalents
+        let reference = normalization_reference(category, result);
+        let weighted_score = result.value / reference * weight;
+        single_score += weighted_score;
+        contributions.insert(category.clone(), weighted_score);
+        weighted_ranges.push((
+            category.clone(),
+            weighted_score,
+            (result.uncertainty_range.0 / reference * weight, result.uncertainty_range.1 / reference * weight),
+        ));
+
+        // Uncertainty propagation (ISO 14044 4.4.3.3), the range read as ±2 standard deviations
+        let result_sd = (result.uncertainty_range.1 - result.uncertainty_range.0) / 4.0;
+        score_variance += (result_sd / reference * weight).powi(2);
+    }
+    let score_sd = score_variance.sqrt();
+
+    let result = SingleScoreResult {
+        value: (single_score / FULL_SCALE).clamp(0.0, 1.0),
+        unit: SCORE_UNIT.to_string(),
+        uncertainty_range: (
+            ((single_score - 2.0 * score_sd) / FULL_SCALE).max(0.0),
+            ((single_score + 2.0 * score_sd) / FULL_SCALE).min(1.0)
+        ),
+        weighting_factors,
+        methodology: format!(
+            "ISO 14044 compliant: {} normalization with {:?} weighting. Raw score: {:.3} person-equiv.",
+            reference_basis, weighting_method, single_score
+        ),
+        endpoint_contributions: DamageContribution::shares(weighted_ranges),
+        interpretation: None,
+    };
+    Ok((result, contributions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(value: f64, normalization_factor: Option<f64>) -> EndpointResult {
+        EndpointResult {
+            value,
+            unit: "-".to_string(),
+            uncertainty_range: (value, value),
+            normalization_factor,
+            regional_adaptation_factor: None,
+            contributions: BTreeMap::new(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn endpoints_are_normalized_weighted_and_scaled() {
+        let endpoints = BTreeMap::from([
+            ("Human Health".to_string(), endpoint(0.052, african_reference_per_kg("Human Health"))),
+            ("Resource Scarcity".to_string(), endpoint(17_000.0, african_reference_per_kg("Resource Scarcity"))),
+        ]);
+        let (score, contributions) = weighted_single_score(&endpoints, &WeightingMethod::EqualWeights, "Test").unwrap();
+
+        // Ecosystem Quality's weight is shared out over the two assessed endpoints
+        let weights = &score.weighting_factors;
+        assert!((weights["Human Health"] + weights["Resource Scarcity"] - 1.0).abs() < 1e-12);
+        assert!((contributions["Human Health"] - weights["Human Health"]).abs() < 1e-12);
+        assert!((contributions["Resource Scarcity"] - 2.0 * weights["Resource Scarcity"]).abs() < 1e-12);
+        let raw: f64 = contributions.values().sum();
+        assert!((score.value - raw / FULL_SCALE).abs() < 1e-12);
+        assert_eq!(score.uncertainty_range, (score.value, score.value));
+        assert!(score.methodology.starts_with("ISO 14044 compliant: Test normalization with EqualWeights weighting"));
+    }
+}
@@ -0,0 +1,210 @@
+/*!
+ * HTTP Server - assessment endpoints (feature `server`)
+ *
+ * Accepts the same JSON documents as the CLI and returns the same results JSON, so callers
+ * no longer pay a process start and a factor load per assessment. Factors are loaded once at
+ * startup; every request runs on the shared runner with the settings its input carries.
+ *
+ * GET  /health                     liveness
+ * POST /assessments/simple         food list only
+ * POST /assessments/comprehensive  farm profile, practices and equipment
+ * POST /assessments/processing     single facility, or a company when `facilities` is given
+ * POST /validate                   detect and parse an input without running it
+ *
+ * Errors are returned as {"error": {"code", "message"}} with the CLI's ErrorCode.
+ */
+
+use crate::input::*;
+use crate::runner::{AssessmentRunner, RunnerConfig};
+use axum::body::{to_bytes, Body};
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use log::info;
+use serde_json::json;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub port: u16,
+    pub max_body_bytes: usize, // larger requests get PAYLOAD_TOO_LARGE
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self { port: 8080, max_body_bytes: 2 * 1024 * 1024 }
+    }
+}
+
+struct ServerState {
+    runner: AssessmentRunner,
+    max_body_bytes: usize,
+}
+
+/// Load the factor databases and serve on all interfaces at `config.port`
+pub async fn serve(config: ServerConfig) -> std::io::Result<()> {
+    let listener = TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], config.port))).await?;
+    let runner = AssessmentRunner::new(RunnerConfig::default());
+    info!("Serving assessments on {}", listener.local_addr()?);
+    serve_on(listener, runner, config.max_body_bytes).await
+}
+
+/// Serve on an already bound listener with an already loaded runner
+pub async fn serve_on(listener: TcpListener, runner: AssessmentRunner, max_body_bytes: usize) -> std::io::Result<()> {
+    axum::serve(listener, router(runner, max_body_bytes)).await
+}
+
+pub fn router(runner: AssessmentRunner, max_body_bytes: usize) -> Router {
+    let state = Arc::new(ServerState { runner, max_body_bytes });
+    Router::new()
+        .route("/health", get(health))
+        .route("/assessments/simple", post(simple))
+        .route("/assessments/comprehensive", post(comprehensive))
+        .route("/assessments/processing", post(processing))
+        .route("/validate", post(validate))
+        .with_state(state)
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+async fn simple(State(state): State<Arc<ServerState>>, body: Body) -> Response {
+    assess(state, body, |_| AssessmentKind::Simple).await
+}
+
+async fn comprehensive(State(state): State<Arc<ServerState>>, body: Body) -> Response {
+    assess(state, body, |_| AssessmentKind::Comprehensive).await
+}
+
+async fn processing(State(state): State<Arc<ServerState>>, body: Body) -> Response {
+    assess(state, body, AssessmentKind::facility_or_company).await
+}
+
+async fn validate(State(state): State<Arc<ServerState>>, body: Body) -> Response {
+    let input = match read_json(&state, body).await {
+        Ok(input) => input,
+        Err(e) => return e.into_response(),
+    };
+    let kind = AssessmentKind::detect(&input);
+    match validate_input(kind, &input) {
+        Ok(()) => Json(json!({ "valid": true, "assessment_type": kind })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Run the body as the kind chosen from the detected one, off the async workers
+async fn assess(state: Arc<ServerState>, body: Body, kind: fn(AssessmentKind) -> AssessmentKind) -> Response {
+    let input = match read_json(&state, body).await {
+        Ok(input) => input,
+        Err(e) => return e.into_response(),
+    };
+    let kind = kind(AssessmentKind::detect(&input));
+    let runner = state.runner.with_config(runner_config(&input));
+
+    let result = tokio::task::spawn_blocking(move || run_input(&runner, kind, &input)).await
+        .unwrap_or_else(|e| Err(ErrorReport::new(ErrorCode::AssessmentFailed, e)));
+    match result {
+        Ok(json) => ([(header::CONTENT_TYPE, "application/json")], json).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn read_json(state: &ServerState, body: Body) -> Result<serde_json::Value, ErrorReport> {
+    let bytes = to_bytes(body, state.max_body_bytes).await
+        .map_err(|_| ErrorReport::new(
+            ErrorCode::PayloadTooLarge,
+            format!("Request body over {} bytes or unreadable", state.max_body_bytes),
+        ))?;
+    serde_json::from_slice(&bytes).map_err(|e| ErrorReport::new(ErrorCode::InvalidJson, e))
+}
+
+fn status(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::InvalidJson => StatusCode::BAD_REQUEST,
+        ErrorCode::InvalidInput => StatusCode::UNPROCESSABLE_ENTITY,
+        ErrorCode::AssessmentFailed | ErrorCode::SerializationFailed => StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+    }
+}
+
+impl IntoResponse for ErrorReport {
+    fn into_response(self) -> Response {
+        (status(self.code), Json(json!({ "error": self }))).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FARM: &str = include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+    const FACILITY: &str = include_str!("../../app/processing/example_cassava_processing.json");
+
+    async fn start(max_body_bytes: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let runner = AssessmentRunner::new(RunnerConfig::default());
+        tokio::spawn(serve_on(listener, runner, max_body_bytes));
+        format!("http://{}", address)
+    }
+
+    async fn post(base: &str, path: &str, body: &str) -> (StatusCode, serde_json::Value) {
+        let response = reqwest::Client::new()
+            .post(format!("{}{}", base, path))
+            .header(header::CONTENT_TYPE.as_str(), "application/json")
+            .body(body.to_string())
+            .send().await.unwrap();
+        let status = StatusCode::from_u16(response.status().as_u16()).unwrap();
+        (status, response.json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn endpoints_serve_fixture_payloads() {
+        let base = start(ServerConfig::default().max_body_bytes).await;
+
+        let health: serde_json::Value = reqwest::get(format!("{}/health", base)).await.unwrap().json().await.unwrap();
+        assert_eq!(health["status"], "ok");
+
+        let (status, farm) = post(&base, "/assessments/comprehensive", FARM).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(farm["management_practices"].is_object());
+        assert!(farm["results"]["midpoint_impacts"]["Global warming"]["value"].as_f64().unwrap() > 0.0);
+
+        let (status, simple) = post(&base, "/assessments/simple", FARM).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(simple["management_practices"].is_null());
+        assert!(simple["results"]["single_score"]["value"].is_number());
+
+        let (status, facility) = post(&base, "/assessments/processing", FACILITY).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(facility["results"]["midpoint_impacts"].is_object());
+
+        let (status, validation) = post(&base, "/validate", FACILITY).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(validation["assessment_type"], "processing");
+    }
+
+    #[tokio::test]
+    async fn errors_carry_cli_error_codes() {
+        let base = start(1024).await;
+
+        let (status, body) = post(&base, "/assessments/simple", "{not json").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "INVALID_JSON");
+
+        let (status, body) = post(&base, "/validate", r#"{"company_name": "No foods", "country": "Ghana"}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"]["code"], "INVALID_INPUT");
+        assert_eq!(body["error"]["message"], "Missing foods");
+        assert_eq!(body["error"]["field"], "foods");
+
+        let (status, body) = post(&base, "/assessments/comprehensive", FARM).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"]["code"], "PAYLOAD_TOO_LARGE");
+    }
+}
@@ -0,0 +1,411 @@
+/*!
+ * Assessment Storage - local SQLite history (feature `storage`)
+ *
+ * Keeps every farm and processing assessment with its results so offline users can look
+ * back over past runs without managing JSON files. The full documents are stored as JSON;
+ * country, company, date, headline results, foods/products and factor sources are also
+ * broken out into columns for querying.
+ *
+ * The schema lives in migrations/, embedded in the binary and applied in order on open;
+ * the database's `user_version` records how many have run.
+ */
+
+use crate::error::StorageError;
+use crate::processing::ProcessingAssessment;
+use crate::production::*;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Schema migrations, applied in order; never edit one that has been released
+const MIGRATIONS: &[&str] = &[
+    include_str!("../migrations/0001_initial.sql"),
+    include_str!("../migrations/0002_serialized_product_types.sql"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StoredKind {
+    Farm,
+    Processing,
+}
+
+impl StoredKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            StoredKind::Farm => "farm",
+            StoredKind::Processing => "processing",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StoredAssessment {
+    Farm(Box<Assessment>),
+    Processing(Box<ProcessingAssessment>),
+}
+
+/// One row of `list_assessments`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssessmentSummary {
+    pub id: Uuid,
+    pub kind: StoredKind,
+    pub company_name: String,
+    pub country: Country,
+    pub region: Option<String>,
+    pub assessment_date: DateTime<Utc>,
+    pub single_score: Option<f64>, // None when saved without results
+    pub global_warming: Option<f64>, // kg CO2-eq
+}
+
+/// Conditions on `list_assessments`; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct AssessmentFilter {
+    pub country: Option<Country>,
+    pub company_name: Option<String>, // case-insensitive substring
+    pub from: Option<DateTime<Utc>>, // inclusive
+    pub to: Option<DateTime<Utc>>, // exclusive
+}
+
+pub struct AssessmentStore {
+    conn: Connection,
+}
+
+impl AssessmentStore {
+    /// Open or create the database at `path`, bringing its schema up to date
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, StorageError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(mut conn: Connection) -> Result<Self, StorageError> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Number of migrations applied
+    pub fn schema_version(&self) -> Result<usize, StorageError> {
+        Ok(self.conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))? as usize)
+    }
+
+    /// Insert a farm assessment, replacing an earlier save with the same id
+    pub fn save_assessment(&mut self, assessment: &Assessment) -> Result<(), StorageError> {
+        let tx = self.conn.transaction()?;
+        let document = serde_json::to_string(&Assessment { results: None, ..assessment.clone() })?;
+        insert_assessment(&tx, assessment.id, StoredKind::Farm, &assessment.company_name, &assessment.country,
+                          &assessment.region, &assessment.assessment_date, &document, &assessment.results)?;
+        for (position, food) in assessment.foods.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO foods (assessment_id, position, food_id, name, category, quantity_kg, area_ha)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![assessment.id.to_string(), position as i64, food.id, food.name,
+                        serialized_name(&food.category), food.quantity_kg, food.area_allocated],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Insert a processing assessment, replacing an earlier save with the same id
+    pub fn save_processing_assessment(&mut self, assessment: &ProcessingAssessment) -> Result<(), StorageError> {
+        let tx = self.conn.transaction()?;
+        let document = serde_json::to_string(&ProcessingAssessment { results: None, ..assessment.clone() })?;
+        insert_assessment(&tx, assessment.id, StoredKind::Processing, &assessment.facility_profile.company_name,
+                          &assessment.country, &assessment.region, &assessment.assessment_date, &document, &assessment.results)?;
+        for (position, product) in assessment.processed_products.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO products (assessment_id, position, product_id, name, product_type, annual_production_t)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![assessment.id.to_string(), position as i64, product.id, product.name,
+                        serialized_name(&product.product_type), product.annual_production],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_assessment(&self, id: Uuid) -> Result<Option<StoredAssessment>, StorageError> {
+        let row = self.conn.query_row(
+            "SELECT a.kind, a.document, r.document FROM assessments a
+             LEFT JOIN results r ON r.assessment_id = a.id WHERE a.id = ?1",
+            params![id.to_string()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)),
+        ).optional()?;
+        let Some((kind, document, results)) = row else {
+            return Ok(None);
+        };
+
+        // Saved by an earlier build, the document may have an older layout
+        let document = crate::schema::migrate(serde_json::from_str(&document)?)?;
+        let results: Option<LCAResults> = results.map(|json| serde_json::from_str(&json)).transpose()?;
+        Ok(Some(if kind == StoredKind::Farm.as_str() {
+            StoredAssessment::Farm(Box::new(Assessment { results, ..serde_json::from_value(document)? }))
+        } else {
+            StoredAssessment::Processing(Box::new(ProcessingAssessment { results, ..serde_json::from_value(document)? }))
+        }))
+    }
+
+    /// Assessments matching the filter, newest first
+    pub fn list_assessments(&self, filter: &AssessmentFilter) -> Result<Vec<AssessmentSummary>, StorageError> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(ref country) = filter.country {
+            conditions.push("a.country = ?");
+            values.push(country.to_string());
+        }
+        if let Some(ref company) = filter.company_name {
+            conditions.push("a.company_name LIKE '%' || ? || '%'");
+            values.push(company.clone());
+        }
+        if let Some(from) = filter.from {
+            conditions.push("a.assessment_date >= ?");
+            values.push(timestamp(&from));
+        }
+        if let Some(to) = filter.to {
+            conditions.push("a.assessment_date < ?");
+            values.push(timestamp(&to));
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT a.id, a.kind, a.company_name, a.country, a.region, a.assessment_date, r.single_score, r.global_warming
+             FROM assessments a LEFT JOIN results r ON r.assessment_id = a.id
+             {} ORDER BY a.assessment_date DESC",
+            where_clause
+        ))?;
+        let rows = statement.query_map(params_from_iter(values), |row| Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, Option<String>>(4)?,
+            row.get::<_, String>(5)?,
+            row.get::<_, Option<f64>>(6)?,
+            row.get::<_, Option<f64>>(7)?,
+        )))?;
+
+        let mut summaries = Vec::new();
+        for row in rows {
+            let (id, kind, company_name, country, region, date, single_score, global_warming) = row?;
+            summaries.push(AssessmentSummary {
+                id: Uuid::parse_str(&id).map_err(|_| StorageError::Column { column: "id", value: id.clone() })?,
+                kind: column("kind", kind)?,
+                company_name,
+                country: column("country", country)?,
+                region,
+                assessment_date: DateTime::parse_from_rfc3339(&date)
+                    .map_err(|_| StorageError::Column { column: "assessment_date", value: date.clone() })?
+                    .with_timezone(&Utc),
+                single_score,
+                global_warming,
+            });
+        }
+        Ok(summaries)
+    }
+
+    /// Remove an assessment with its results, foods/products and provenance; false if absent
+    pub fn delete_assessment(&mut self, id: Uuid) -> Result<bool, StorageError> {
+        Ok(self.conn.execute("DELETE FROM assessments WHERE id = ?1", params![id.to_string()])? > 0)
+    }
+}
+
+fn migrate(conn: &mut Connection) -> Result<(), StorageError> {
+    let applied = conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))? as usize;
+    if applied > MIGRATIONS.len() {
+        return Err(StorageError::TooNew { found: applied, supported: MIGRATIONS.len() });
+    }
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", (version + 1) as i64)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// A column holding a serialized enum, e.g. the kind or country
+fn column<T: serde::de::DeserializeOwned>(column: &'static str, value: String) -> Result<T, StorageError> {
+    serde_json::from_value(serde_json::Value::String(value.clone())).map_err(|_| StorageError::Column { column, value })
+}
+
+/// Fixed-width UTC timestamps, so stored dates compare correctly as text
+fn timestamp(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_assessment(
+    tx: &Transaction,
+    id: Uuid,
+    kind: StoredKind,
+    company_name: &str,
+    country: &Country,
+    region: &Option<String>,
+    assessment_date: &DateTime<Utc>,
+    document: &str,
+    results: &Option<LCAResults>,
+) -> Result<(), StorageError> {
+    let id = id.to_string();
+    tx.execute("DELETE FROM assessments WHERE id = ?1", params![id])?;
+    tx.execute(
+        "INSERT INTO assessments (id, kind, company_name, country, region, assessment_date, document)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![id, kind.as_str(), company_name, country.to_string(), region, timestamp(assessment_date), document],
+    )?;
+
+    if let Some(results) = results {
+        tx.execute(
+            "INSERT INTO results (assessment_id, single_score, global_warming, overall_confidence, document)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                id,
+                results.single_score.value,
+                results.midpoint_impacts.get("Global warming").map(|r| r.value),
+                serialized_name(&results.data_quality.overall_confidence),
+                serde_json::to_string(results)?,
+            ],
+        )?;
+        let mut provenance = tx.prepare(
+            "INSERT INTO factor_provenance (assessment_id, impact_category, source) VALUES (?1, ?2, ?3)"
+        )?;
+        for (category, result) in &results.midpoint_impacts {
+            for source in &result.contributing_sources {
+                provenance.execute(params![id, category, source])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processing::ProductType;
+    use crate::runner::{AssessmentRunner, ComprehensiveInput, RunnerConfig};
+    use chrono::TimeZone;
+
+    fn farm(company_name: &str, country: Country) -> Assessment {
+        let maize: FoodItem = serde_json::from_value(serde_json::json!({
+            "id": "maize", "name": "Maize", "quantity_kg": 2000.0, "category": "Cereals", "area_allocated": 1.0
+        })).unwrap();
+        AssessmentRunner::new(RunnerConfig::default()).run_comprehensive(ComprehensiveInput {
+            company_name: company_name.to_string(),
+            country,
+            currency: None,
+            region: None,
+            coordinates: None,
+            foods: vec![maize],
+            weighting_method: WeightingMethod::AfricanPriorities,
+            farm_profile: None,
+            management_practices: None,
+            equipment_energy: None,
+        }).unwrap()
+    }
+
+    #[test]
+    fn full_assessment_round_trips() {
+        let mut store = AssessmentStore::open_in_memory().unwrap();
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len());
+
+        let assessment = farm("Tamale Grains", Country::Ghana);
+        assert!(assessment.results.is_some());
+        store.save_assessment(&assessment).unwrap();
+        // Saving again replaces rather than duplicates
+        store.save_assessment(&assessment).unwrap();
+
+        let Some(StoredAssessment::Farm(loaded)) = store.load_assessment(assessment.id).unwrap() else {
+            panic!("farm assessment not found");
+        };
+        // Compared as JSON parsed the same way, so float formatting can't differ
+        let expected: serde_json::Value = serde_json::from_str(&serde_json::to_string(&assessment).unwrap()).unwrap();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), expected);
+
+        let provenance: i64 = store.conn.query_row(
+            "SELECT COUNT(*) FROM factor_provenance WHERE assessment_id = ?1",
+            params![assessment.id.to_string()], |row| row.get(0)).unwrap();
+        assert!(provenance > 0);
+
+        assert!(store.delete_assessment(assessment.id).unwrap());
+        assert!(store.load_assessment(assessment.id).unwrap().is_none());
+        let orphans: i64 = store.conn.query_row("SELECT COUNT(*) FROM foods", [], |row| row.get(0)).unwrap();
+        assert_eq!(orphans, 0);
+    }
+
+    #[test]
+    fn product_types_stored_by_their_debug_spelling_are_renamed() {
+        let product_types = [
+            ProductType::FlourMaize,
+            ProductType::Other("Gari".to_string()),
+            ProductType::Other(r#"Kenkey "fine" \ coarse"#.to_string()),
+        ];
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        conn.execute(
+            "INSERT INTO assessments (id, kind, company_name, country, assessment_date, document)
+             VALUES ('a', 'processing', 'Mill', 'Ghana', '2024-01-01T00:00:00.000000Z', '{}')", []
+        ).unwrap();
+        for (position, product_type) in product_types.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO products (assessment_id, position, product_id, name, product_type, annual_production_t)
+                 VALUES ('a', ?1, 'p', 'Product', ?2, 1.0)",
+                params![position as i64, format!("{:?}", product_type)],
+            ).unwrap();
+        }
+
+        let store = AssessmentStore::with_connection(conn).unwrap();
+        let stored: Vec<String> = store.conn.prepare("SELECT product_type FROM products ORDER BY position").unwrap()
+            .query_map([], |row| row.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(stored, product_types.iter().map(serialized_name).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn databases_from_newer_builds_are_refused() {
+        let mut store = AssessmentStore::open_in_memory().unwrap();
+        store.conn.pragma_update(None, "user_version", (MIGRATIONS.len() + 1) as i64).unwrap();
+        assert!(matches!(
+            migrate(&mut store.conn),
+            Err(StorageError::TooNew { found, supported }) if found == MIGRATIONS.len() + 1 && supported == MIGRATIONS.len()
+        ));
+    }
+
+    #[test]
+    fn history_is_queried_by_country_company_and_date() {
+        let mut store = AssessmentStore::open_in_memory().unwrap();
+        let mut older = farm("Tamale Grains", Country::Ghana);
+        older.assessment_date = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let newer = farm("Kumasi Farms", Country::Ghana);
+        let nigerian = farm("Kano Agro", Country::Nigeria);
+        for assessment in [&older, &newer, &nigerian] {
+            store.save_assessment(assessment).unwrap();
+        }
+
+        let ids = |filter: AssessmentFilter| -> Vec<Uuid> {
+            store.list_assessments(&filter).unwrap().into_iter().map(|summary| summary.id).collect()
+        };
+        assert_eq!(ids(AssessmentFilter { country: Some(Country::Ghana), ..Default::default() }), vec![newer.id, older.id]);
+        assert_eq!(ids(AssessmentFilter { country: Some(Country::Nigeria), ..Default::default() }), vec![nigerian.id]);
+        assert_eq!(ids(AssessmentFilter { company_name: Some("tamale".to_string()), ..Default::default() }), vec![older.id]);
+        assert_eq!(ids(AssessmentFilter {
+            country: Some(Country::Ghana),
+            to: Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
+            ..Default::default()
+        }), vec![older.id]);
+
+        let summary = &store.list_assessments(&AssessmentFilter::default()).unwrap()[0];
+        assert_eq!(summary.kind, StoredKind::Farm);
+        assert!(summary.single_score.is_some() && summary.global_warming.is_some());
+    }
+}
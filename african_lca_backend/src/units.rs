@@ -0,0 +1,362 @@
+/*!
+ * Units - physical quantities at the boundaries of the inventory, factors and results
+ *
+ * Inside the engines quantities are plain f64 in a fixed unit (kg, m3, MJ, ha). Where a
+ * value enters in another unit - litres of process water per tonne, production in tonnes,
+ * metered kWh, factors declared per tonne or in litres - it goes through `Quantity` or one of
+ * the `Mass`, `Volume`, `Energy` and `Area` newtypes, which only convert between units of
+ * the same dimension.
+ */
+
+use crate::error::UnitError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// What a unit measures; only units of the same dimension convert into each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Dimension {
+    Mass,
+    Volume,
+    Energy,
+    Area,
+    Length,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Unit {
+    Kg,
+    Tonne,
+    Litre,
+    CubicMetre,
+    KWh,
+    MJ,
+    Hectare,
+    SquareMetre,
+    Km,
+}
+
+impl Unit {
+    pub fn dimension(self) -> Dimension {
+        match self {
+            Unit::Kg | Unit::Tonne => Dimension::Mass,
+            Unit::Litre | Unit::CubicMetre => Dimension::Volume,
+            Unit::KWh | Unit::MJ => Dimension::Energy,
+            Unit::Hectare | Unit::SquareMetre => Dimension::Area,
+            Unit::Km => Dimension::Length,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Unit::Kg => "kg",
+            Unit::Tonne => "t",
+            Unit::Litre => "L",
+            Unit::CubicMetre => "m3",
+            Unit::KWh => "kWh",
+            Unit::MJ => "MJ",
+            Unit::Hectare => "ha",
+            Unit::SquareMetre => "m2",
+            Unit::Km => "km",
+        }
+    }
+
+    /// `value` of this unit in the base unit of its dimension (kg, m3, MJ, ha, km). Units
+    /// smaller than the base divide, so that 2500 L is exactly 2.5 m3.
+    fn base_value(self, value: f64) -> f64 {
+        match self {
+            Unit::Kg | Unit::CubicMetre | Unit::MJ | Unit::Hectare | Unit::Km => value,
+            Unit::Tonne => value * 1000.0,
+            Unit::Litre => value / 1000.0,
+            Unit::KWh => value * 3.6,
+            Unit::SquareMetre => value / 10_000.0,
+        }
+    }
+
+    /// `value` in the base unit, in this unit
+    fn of_base(self, value: f64) -> f64 {
+        match self {
+            Unit::Kg | Unit::CubicMetre | Unit::MJ | Unit::Hectare | Unit::Km => value,
+            Unit::Tonne => value / 1000.0,
+            Unit::Litre => value * 1000.0,
+            Unit::KWh => value / 3.6,
+            Unit::SquareMetre => value * 10_000.0,
+        }
+    }
+
+    /// `value` of this unit in `to`
+    pub fn convert(self, value: f64, to: Unit) -> Result<f64, UnitError> {
+        if self.dimension() != to.dimension() {
+            return Err(UnitError::Incompatible { from: self, to });
+        }
+        Ok(to.of_base(self.base_value(value)))
+    }
+
+    /// How many of `to` make one of this unit
+    pub fn factor_to(self, to: Unit) -> Result<f64, UnitError> {
+        self.convert(1.0, to)
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "kg" => Ok(Unit::Kg),
+            "t" | "tonne" | "tonnes" => Ok(Unit::Tonne),
+            "L" | "l" | "litre" | "litres" | "liter" | "liters" => Ok(Unit::Litre),
+            "m3" | "m³" => Ok(Unit::CubicMetre),
+            "kWh" => Ok(Unit::KWh),
+            "MJ" => Ok(Unit::MJ),
+            "ha" => Ok(Unit::Hectare),
+            "m2" | "m²" => Ok(Unit::SquareMetre),
+            "km" => Ok(Unit::Km),
+            other => Err(UnitError::Unknown(other.to_string())),
+        }
+    }
+}
+
+/// A value with its unit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+impl Quantity {
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Self { value, unit }
+    }
+
+    /// The same quantity in `unit`
+    pub fn to(self, unit: Unit) -> Result<Quantity, UnitError> {
+        Ok(Quantity::new(self.unit.convert(self.value, unit)?, unit))
+    }
+
+    /// Value of the quantity in `unit`
+    pub fn value_in(self, unit: Unit) -> Result<f64, UnitError> {
+        self.to(unit).map(|quantity| quantity.value)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+/// Kilograms
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Mass(f64);
+
+impl Mass {
+    pub fn kg(kg: f64) -> Self {
+        Self(kg)
+    }
+
+    pub fn tonnes(tonnes: f64) -> Self {
+        Self(Unit::Tonne.base_value(tonnes))
+    }
+
+    pub fn in_kg(self) -> f64 {
+        self.0
+    }
+
+    pub fn in_tonnes(self) -> f64 {
+        Unit::Tonne.of_base(self.0)
+    }
+}
+
+/// Cubic metres
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Volume(f64);
+
+impl Volume {
+    pub fn m3(m3: f64) -> Self {
+        Self(m3)
+    }
+
+    pub fn litres(litres: f64) -> Self {
+        Self(Unit::Litre.base_value(litres))
+    }
+
+    pub fn in_m3(self) -> f64 {
+        self.0
+    }
+
+    pub fn in_litres(self) -> f64 {
+        Unit::Litre.of_base(self.0)
+    }
+}
+
+/// Megajoules
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Energy(f64);
+
+impl Energy {
+    pub fn mj(mj: f64) -> Self {
+        Self(mj)
+    }
+
+    pub fn kwh(kwh: f64) -> Self {
+        Self(Unit::KWh.base_value(kwh))
+    }
+
+    pub fn in_mj(self) -> f64 {
+        self.0
+    }
+
+    pub fn in_kwh(self) -> f64 {
+        Unit::KWh.of_base(self.0)
+    }
+}
+
+/// Hectares
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Area(f64);
+
+impl Area {
+    pub fn ha(ha: f64) -> Self {
+        Self(ha)
+    }
+
+    pub fn m2(m2: f64) -> Self {
+        Self(Unit::SquareMetre.base_value(m2))
+    }
+
+    pub fn in_ha(self) -> f64 {
+        self.0
+    }
+
+    pub fn in_m2(self) -> f64 {
+        Unit::SquareMetre.of_base(self.0)
+    }
+}
+
+/// Each newtype converts from a quantity of its dimension and back in its base unit
+macro_rules! dimensioned {
+    ($($name:ident => $base:expr),* $(,)?) => {$(
+        impl TryFrom<Quantity> for $name {
+            type Error = UnitError;
+
+            fn try_from(quantity: Quantity) -> Result<Self, UnitError> {
+                quantity.value_in($base).map(Self)
+            }
+        }
+
+        impl From<$name> for Quantity {
+            fn from(value: $name) -> Quantity {
+                Quantity::new(value.0, $base)
+            }
+        }
+    )*};
+}
+
+dimensioned!(Mass => Unit::Kg, Volume => Unit::CubicMetre, Energy => Unit::MJ, Area => Unit::Hectare);
+
+/// A factor unit split into its amount, what the amount is of, and the reference quantity:
+/// "kg CO2-eq per kg" is kg of CO2-eq per kg, "L/t" litres per tonne, "PM2.5-eq" has no
+/// amount unit of its own.
+struct FactorUnit<'a> {
+    amount: Option<Unit>,
+    numerator: &'a str, // amount and substance, as written
+    substance: &'a str,
+    per: Unit,
+}
+
+impl<'a> FactorUnit<'a> {
+    /// `per` is the reference quantity when the unit names none
+    fn parse(unit: &'a str, per: Unit) -> Result<Self, UnitError> {
+        let unit = unit.trim();
+        let (numerator, per) = match unit.split_once(" per ").or_else(|| unit.rsplit_once('/')) {
+            Some((numerator, reference)) => (numerator.trim(), reference.parse()?),
+            None => (unit, per),
+        };
+        let (first, substance) = numerator.split_once(' ').unwrap_or((numerator, ""));
+        Ok(Self { amount: first.parse().ok(), numerator, substance: substance.trim(), per })
+    }
+}
+
+/// Multiplier that turns a factor value declared in `declared` into one in `expected`, where
+/// both are per `per` unless they say otherwise. Water declared in "L" becomes m3 (× 0.001),
+/// a burden "per t" becomes one per kg (× 0.001); units that measure different things are an
+/// error rather than a silent mix-up.
+pub fn factor_scale(declared: &str, expected: &str, per: Unit) -> Result<f64, UnitError> {
+    let mismatch = || UnitError::Mismatch { declared: declared.trim().to_string(), expected: expected.to_string() };
+    if declared.trim() == expected {
+        return Ok(1.0);
+    }
+    let parsed = FactorUnit::parse(declared, per).map_err(|_| mismatch())?;
+    let wanted = FactorUnit::parse(expected, per)?;
+
+    let amount = match (parsed.amount, wanted.amount) {
+        (Some(from), Some(to)) if parsed.substance == wanted.substance => from.factor_to(to).map_err(|_| mismatch())?,
+        (None, None) if parsed.numerator == wanted.numerator => 1.0,
+        _ => return Err(mismatch()),
+    };
+    // A value per tonne is a thousandth of that value per kg
+    let reference = wanted.per.factor_to(parsed.per).map_err(|_| mismatch())?;
+    Ok(amount * reference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions_stay_within_a_dimension() {
+        assert_eq!(Quantity::new(2500.0, Unit::Litre).value_in(Unit::CubicMetre).unwrap(), 2.5);
+        assert_eq!(Quantity::new(3.0, Unit::Tonne).value_in(Unit::Kg).unwrap(), 3000.0);
+        assert_eq!(Quantity::new(10.0, Unit::KWh).value_in(Unit::MJ).unwrap(), 36.0);
+        assert_eq!(Quantity::new(1.0, Unit::Hectare).value_in(Unit::SquareMetre).unwrap(), 10_000.0);
+        assert_eq!(
+            Quantity::new(1.0, Unit::Litre).to(Unit::Kg),
+            Err(UnitError::Incompatible { from: Unit::Litre, to: Unit::Kg })
+        );
+        assert!(Mass::try_from(Quantity::new(1.0, Unit::CubicMetre)).is_err());
+    }
+
+    #[test]
+    fn newtypes_keep_their_base_unit() {
+        assert_eq!(Volume::litres(1500.0).in_m3(), 1.5);
+        assert_eq!(Volume::m3(2.0).in_litres(), 2000.0);
+        assert_eq!(Mass::tonnes(1.2).in_kg(), 1200.0);
+        assert_eq!(Mass::kg(450.0).in_tonnes(), 0.45);
+        assert_eq!(Energy::kwh(100.0).in_mj(), 360.0);
+        assert_eq!(Area::m2(5000.0).in_ha(), 0.5);
+        assert_eq!(Quantity::from(Volume::litres(250.0)), Quantity::new(0.25, Unit::CubicMetre));
+        assert_eq!(Mass::try_from(Quantity::new(2.0, Unit::Tonne)).unwrap(), Mass::kg(2000.0));
+    }
+
+    #[test]
+    fn factor_units_convert_amount_and_reference() {
+        // Water factors labelled in litres but expected in m3
+        assert_eq!(factor_scale("L", "m3", Unit::Kg).unwrap(), 0.001);
+        assert_eq!(factor_scale("L/kg", "m3", Unit::Kg).unwrap(), 0.001);
+        // A burden per tonne of product, read as one per kg
+        assert_eq!(factor_scale("kg CO2-eq per t", "kg CO2-eq", Unit::Kg).unwrap(), 0.001);
+        assert_eq!(factor_scale("t CO2-eq", "kg CO2-eq", Unit::Kg).unwrap(), 1000.0);
+        assert_eq!(factor_scale("kWh", "MJ", Unit::Kg).unwrap(), 3.6);
+        assert_eq!(factor_scale("PM2.5-eq", "PM2.5-eq", Unit::Kg).unwrap(), 1.0);
+        // Per-kg factors of the processing side, where values are per tonne
+        assert_eq!(factor_scale("m3 per kg", "m3", Unit::Tonne).unwrap(), 1000.0);
+    }
+
+    #[test]
+    fn factor_units_of_another_quantity_are_rejected() {
+        for (declared, expected) in [("kg SO2-eq", "kg CO2-eq"), ("kg", "m3"), ("m3 per ha", "m3"), ("MSA*m2*yr", "m2a crop-eq")] {
+            assert_eq!(
+                factor_scale(declared, expected, Unit::Kg),
+                Err(UnitError::Mismatch { declared: declared.to_string(), expected: expected.to_string() }),
+                "{} as {}", declared, expected
+            );
+        }
+    }
+}
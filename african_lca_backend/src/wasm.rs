@@ -0,0 +1,75 @@
+/*!
+ * WebAssembly bindings (feature `wasm`)
+ *
+ * In-browser assessment previews without a server round trip. The default factor databases
+ * are embedded as bincode rather than built from source literals, which keeps the module
+ * small; the JSON in and out is the same as the CLI's, errors as {"error": {"code", "message"}}.
+ *
+ * Build with `wasm-pack build --no-default-features --features wasm`.
+ */
+
+use crate::input::*;
+use crate::runner::{AssessmentRunner, FactorDatabases, RunnerConfig};
+use serde_json::json;
+use std::sync::OnceLock;
+use wasm_bindgen::prelude::*;
+
+/// FactorDatabases::defaults() serialized with bincode. Regenerate after changing the
+/// defaults with `cargo run --example export_factors --features wasm`.
+static EMBEDDED_FACTORS: &[u8] = include_bytes!("../data/default_factors.bin");
+
+pub fn encode_factors(factors: &FactorDatabases) -> Result<Vec<u8>, bincode::Error> {
+    bincode::serialize(factors)
+}
+
+pub fn embedded_factors() -> Result<FactorDatabases, bincode::Error> {
+    bincode::deserialize(EMBEDDED_FACTORS)
+}
+
+/// Loaded on first use and kept for the lifetime of the module
+fn runner() -> &'static AssessmentRunner {
+    static RUNNER: OnceLock<AssessmentRunner> = OnceLock::new();
+    RUNNER.get_or_init(|| {
+        let factors = embedded_factors().expect("embedded factor databases decode");
+        AssessmentRunner::with_factors(RunnerConfig::default(), factors)
+    })
+}
+
+#[wasm_bindgen]
+pub fn assess_comprehensive(json: &str) -> String {
+    assess(json, |_| AssessmentKind::Comprehensive)
+}
+
+#[wasm_bindgen]
+pub fn assess_processing(json: &str) -> String {
+    assess(json, AssessmentKind::facility_or_company)
+}
+
+fn assess(json: &str, kind: fn(AssessmentKind) -> AssessmentKind) -> String {
+    let result = serde_json::from_str::<serde_json::Value>(json)
+        .map_err(|e| ErrorReport::new(ErrorCode::InvalidJson, e))
+        .and_then(|input| {
+            let kind = kind(AssessmentKind::detect(&input));
+            run_input(&runner().with_config(runner_config(&input)), kind, &input)
+        });
+    result.unwrap_or_else(|e| json!({ "error": e }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_factors_match_the_defaults() {
+        // Compared as JSON so map ordering does not matter
+        let embedded = serde_json::to_value(embedded_factors().unwrap()).unwrap();
+        let defaults = serde_json::to_value(FactorDatabases::defaults()).unwrap();
+        assert!(embedded == defaults, "data/default_factors.bin is stale - run the export_factors example");
+    }
+
+    #[test]
+    fn errors_come_back_as_json() {
+        let error: serde_json::Value = serde_json::from_str(&assess_processing("{")).unwrap();
+        assert_eq!(error["error"]["code"], "INVALID_JSON");
+    }
+}
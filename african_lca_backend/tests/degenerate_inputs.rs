@@ -0,0 +1,160 @@
+//! Zero, empty and missing quantities through every kind of assessment: each run either
+//! fails with an error or produces finite results, and none panics.
+#![cfg(not(target_arch = "wasm32"))]
+
+use african_lca_backend::*;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+const FARM: &str = include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+const FACILITY: &str = include_str!("../../app/processing/example_cassava_processing.json");
+
+/// Names of the headline numbers of `results` that are NaN or infinite. serde_json writes
+/// those as null, so they are checked before serializing.
+fn non_finite<'a>(
+    midpoints: impl IntoIterator<Item = (&'a String, &'a MidpointResult)>,
+    endpoints: &BTreeMap<String, EndpointResult>,
+    single_score: &SingleScoreResult,
+) -> Vec<String> {
+    let mut found: Vec<String> = midpoints.into_iter()
+        .filter(|(_, result)| ![result.value, result.uncertainty_range.0, result.uncertainty_range.1].iter().all(|v| v.is_finite()))
+        .map(|(category, _)| category.clone())
+        .collect();
+    found.extend(endpoints.iter().filter(|(_, result)| !result.value.is_finite()).map(|(category, _)| category.clone()));
+    if !single_score.value.is_finite() {
+        found.push("single score".to_string());
+    }
+    found
+}
+
+fn farm_results(results: &LCAResults) -> Vec<String> {
+    let per_food = results.breakdown_by_food.values().flatten();
+    let per_ha = results.impacts_per_ha.iter().flatten();
+    let mut found = non_finite(results.midpoint_impacts.iter().chain(per_food).chain(per_ha), &results.endpoint_impacts, &results.single_score);
+    for (product, footprint) in results.product_footprints.iter().flatten() {
+        if !footprint.per_tonne.values().chain(footprint.yield_adjusted_per_tonne.values()).all(|v| v.is_finite()) {
+            found.push(format!("{} per tonne", product));
+        }
+    }
+    found
+}
+
+/// Run `input` and check that it fails with an error or has finite results
+fn assert_sound(name: &str, input: Value) {
+    let kind = AssessmentKind::detect(&input);
+    let runner = AssessmentRunner::new(runner_config(&input));
+    let found = match kind {
+        AssessmentKind::Simple => create_simple_input(&input).map_err(EngineError::from)
+            .and_then(|input| runner.run_simple(input)).map(|farm| farm_results(farm.results.as_ref().unwrap())),
+        AssessmentKind::Comprehensive => create_comprehensive_input(&input).map_err(EngineError::from)
+            .and_then(|input| runner.run_comprehensive(input)).map(|farm| farm_results(farm.results.as_ref().unwrap())),
+        AssessmentKind::Processing => create_processing_input(&input).map_err(EngineError::from)
+            .and_then(|input| runner.run_processing(input)).map(|facility| farm_results(facility.results.as_ref().unwrap())),
+        AssessmentKind::Company => create_company_assessment(&input).map_err(EngineError::from)
+            .and_then(|company| runner.run_company(company)).map(|company| {
+                let results = company.results.as_ref().unwrap();
+                let mut found = non_finite(&results.midpoint_impacts, &results.endpoint_impacts, &results.single_score);
+                found.extend(company.facilities.iter().flat_map(|facility| farm_results(facility.results.as_ref().unwrap())));
+                found
+            }),
+    };
+    match found {
+        Ok(found) => assert!(found.is_empty(), "{}: not finite: {:?}", name, found),
+        Err(e) => assert!(!e.to_string().is_empty(), "{}: error without a message", name),
+    }
+    // The JSON entry point must not panic either
+    let _ = run_input(&runner, kind, &input);
+}
+
+fn farm() -> Value {
+    serde_json::from_str(FARM).unwrap()
+}
+
+fn facility() -> Value {
+    serde_json::from_str(FACILITY).unwrap()
+}
+
+/// Run `input` through the JSON entry point and check it is rejected as invalid at `field`
+fn assert_rejected(name: &str, input: Value, field: &str) {
+    let runner = AssessmentRunner::new(runner_config(&input));
+    match run_input(&runner, AssessmentKind::detect(&input), &input) {
+        Err(report) => {
+            assert_eq!(report.code, ErrorCode::InvalidInput, "{}: {}", name, report.message);
+            assert_eq!(report.field.as_deref(), Some(field), "{}: {}", name, report.message);
+        }
+        Ok(_) => panic!("{}: assessed an input with nothing to assess", name),
+    }
+}
+
+#[test]
+fn empty_food_and_product_lists_are_rejected() {
+    assert_rejected("simple without foods", json!({ "company_name": "Empty", "country": "Ghana", "foods": [] }), "foods");
+
+    let mut comprehensive = farm();
+    comprehensive["foods"] = json!([]);
+    assert_rejected("comprehensive without foods", comprehensive, "foods");
+
+    let mut processing = facility();
+    processing["processed_products"] = json!([]);
+    assert_rejected("facility without products", processing, "processed_products");
+
+    assert_rejected("company without facilities", json!({ "company_name": "Empty", "facilities": [] }), "facilities");
+}
+
+#[test]
+fn farms_without_production_are_rejected() {
+    let simple = json!({
+        "company_name": "Nothing harvested", "country": "Ghana",
+        "foods": [{ "id": "1", "name": "Maize", "quantity_kg": 0.0, "category": "Cereals" }]
+    });
+    assert_rejected("simple with zero maize", simple, "foods");
+
+    for area in [1.0, 0.0] {
+        let mut comprehensive = farm();
+        for food in comprehensive["foods"].as_array_mut().unwrap() {
+            food["quantity_kg"] = 0.0.into();
+            food["area_allocated"] = area.into();
+        }
+        assert_rejected(&format!("farm with 0 kg on {} ha", area), comprehensive, "foods");
+    }
+
+    // One empty plot among others is still a harvest
+    let mut one_failed = farm();
+    one_failed["foods"][0]["quantity_kg"] = 0.0.into();
+    assert_sound("farm with one food at 0 kg", one_failed);
+}
+
+#[test]
+fn zero_quantities_and_areas() {
+    let mut comprehensive = farm();
+    for food in comprehensive["foods"].as_array_mut().unwrap() {
+        food["area_allocated"] = 0.0.into();
+    }
+    assert_sound("farm without allocated areas", comprehensive);
+
+    let mut no_farm = farm();
+    no_farm["farm_profile"]["total_farm_size"] = 0.0.into();
+    for food in no_farm["foods"].as_array_mut().unwrap() {
+        food.as_object_mut().unwrap().remove("area_allocated");
+    }
+    assert_sound("farm without areas", no_farm);
+}
+
+#[test]
+fn zero_capacity_and_production() {
+    let mut no_capacity = facility();
+    no_capacity["facility_profile"]["processing_capacity"] = 0.0.into();
+    assert_sound("facility without capacity", no_capacity);
+
+    let mut idle = facility();
+    idle["facility_profile"]["operational_days_per_year"] = 0.into();
+    idle["facility_profile"]["operational_hours_per_day"] = 0.0.into();
+    assert_sound("idle facility", idle);
+
+    let mut nothing_made = facility();
+    for product in nothing_made["processed_products"].as_array_mut().unwrap() {
+        product["annual_production"] = 0.0.into();
+    }
+    assert_sound("facility without production", nothing_made.clone());
+    assert_sound("company of idle sites", json!({ "company_name": "Idle", "facilities": [nothing_made] }));
+}
@@ -0,0 +1,109 @@
+//! Run with `cargo test --features ffi --test ffi`
+#![cfg(all(not(target_arch = "wasm32"), feature = "ffi"))]
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+// Links against the library's exported symbols, as a C caller would
+use african_lca_backend as _;
+
+#[repr(C)]
+struct LcaRunner {
+    _opaque: [u8; 0],
+}
+
+extern "C" {
+    fn lca_runner_new() -> *mut LcaRunner;
+    fn lca_runner_free(runner: *mut LcaRunner);
+    fn lca_run_assessment(runner: *const LcaRunner, input_json: *const c_char, out_json: *mut *mut c_char, out_len: *mut usize) -> i32;
+    fn lca_last_error_message() -> *const c_char;
+    fn lca_string_free(s: *mut c_char);
+}
+
+const FARM: &str = include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+const FACILITY: &str = include_str!("../../app/processing/example_cassava_processing.json");
+
+/// Code, results and last error message of one call
+fn run(runner: *const LcaRunner, input: &[u8]) -> (i32, Option<serde_json::Value>, Option<String>) {
+    let input = CString::new(input).unwrap();
+    let (mut out, mut len) = (ptr::null_mut(), usize::MAX);
+    unsafe {
+        let code = lca_run_assessment(runner, input.as_ptr(), &mut out, &mut len);
+        let results = (!out.is_null()).then(|| {
+            let bytes = std::slice::from_raw_parts(out as *const u8, len);
+            let results = serde_json::from_slice(bytes).unwrap();
+            lca_string_free(out);
+            results
+        });
+        let message = lca_last_error_message();
+        let message = (!message.is_null()).then(|| CStr::from_ptr(message).to_string_lossy().into_owned());
+        (code, results, message)
+    }
+}
+
+#[test]
+fn assessments_round_trip_through_the_c_interface() {
+    let runner = unsafe { lca_runner_new() };
+    assert!(!runner.is_null());
+
+    let (code, farm, message) = run(runner, FARM.as_bytes());
+    assert_eq!(code, 0);
+    assert!(message.is_none());
+    assert!(farm.unwrap()["results"]["midpoint_impacts"]["Global warming"]["value"].as_f64().unwrap() > 0.0);
+
+    let (code, facility, _) = run(runner, FACILITY.as_bytes());
+    assert_eq!(code, 0);
+    assert!(facility.unwrap()["results"]["midpoint_impacts"].is_object());
+
+    let (code, results, message) = run(runner, b"{not json");
+    assert_eq!(code, 2);
+    assert!(results.is_none());
+    assert!(message.is_some());
+
+    let (code, _, message) = run(runner, br#"{"company_name": "No foods", "country": "Ghana"}"#);
+    assert_eq!(code, 3);
+    assert_eq!(message.as_deref(), Some("Missing foods"));
+
+    let (code, _, _) = run(runner, &[0xff, 0xfe]);
+    assert_eq!(code, 1);
+
+    unsafe { lca_runner_free(runner) };
+}
+
+#[test]
+fn null_pointers_are_rejected() {
+    let (code, results, message) = run(ptr::null(), FARM.as_bytes());
+    assert_eq!(code, 1);
+    assert!(results.is_none());
+    assert!(message.unwrap().contains("must not be null"));
+
+    unsafe {
+        assert_eq!(lca_run_assessment(ptr::null(), ptr::null(), ptr::null_mut(), ptr::null_mut()), 1);
+        lca_runner_free(ptr::null_mut());
+        lca_string_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn header_matches_the_exported_functions() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", manifest_dir)).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{}/src/ffi.rs", manifest_dir))
+        .generate()
+        .expect("cbindgen parses src/ffi.rs")
+        .write(&mut generated);
+
+    let generated = String::from_utf8(generated).unwrap();
+    let path = format!("{}/include/african_lca.h", manifest_dir);
+    if std::env::var_os("LCA_UPDATE_HEADER").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    let committed = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        generated == committed,
+        "include/african_lca.h is stale - rerun this test with LCA_UPDATE_HEADER=1"
+    );
+}
@@ -0,0 +1,155 @@
+//! Results of fixture assessments against checked-in golden files.
+//! After an intended change of results, regenerate them with
+//! `UPDATE_GOLDEN=1 cargo test --test golden` and review the diff.
+#![cfg(not(target_arch = "wasm32"))]
+
+use african_lca_backend::*;
+use serde_json::Value;
+use std::path::Path;
+
+/// Relative difference below which two numbers of a result are the same. Golden files are
+/// written at `to_json`'s precision, so anything larger is a change in the calculation.
+const TOLERANCE: f64 = 1e-9;
+
+/// Results of one run, serialized as the CLI writes them. The assessment ids and dates are
+/// left out: they identify the run, not its outcome.
+fn results(input: &Value) -> String {
+    let runner = AssessmentRunner::new(runner_config(input));
+    let json = match AssessmentKind::detect(input) {
+        AssessmentKind::Simple => to_json(&runner.run_simple(create_simple_input(input).unwrap()).unwrap().results, true),
+        AssessmentKind::Comprehensive => to_json(&runner.run_comprehensive(create_comprehensive_input(input).unwrap()).unwrap().results, true),
+        AssessmentKind::Processing => to_json(&runner.run_processing(create_processing_input(input).unwrap()).unwrap().results, true),
+        AssessmentKind::Company => {
+            let company = runner.run_company(create_company_assessment(input).unwrap()).unwrap();
+            let facilities: Vec<_> = company.facilities.iter().map(|facility| &facility.results).collect();
+            to_json(&serde_json::json!({ "facilities": facilities, "company": company.results }), true)
+        }
+    };
+    json.unwrap() + "\n"
+}
+
+/// Paths at which `actual` differs from `expected`, numbers compared within `TOLERANCE`
+fn differences(path: &str, expected: &Value, actual: &Value, found: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Number(e), Value::Number(a)) => {
+            let (e, a) = (e.as_f64().unwrap(), a.as_f64().unwrap());
+            if (e - a).abs() > TOLERANCE * e.abs().max(a.abs()) {
+                found.push(format!("{}: expected {}, got {}", path, e, a));
+            }
+        }
+        (Value::Object(e), Value::Object(a)) => {
+            for key in e.keys().chain(a.keys().filter(|key| !e.contains_key(*key))) {
+                let path = format!("{}.{}", path, key);
+                match (e.get(key), a.get(key)) {
+                    (Some(e), Some(a)) => differences(&path, e, a, found),
+                    (Some(_), None) => found.push(format!("{}: missing", path)),
+                    _ => found.push(format!("{}: unexpected", path)),
+                }
+            }
+        }
+        (Value::Array(e), Value::Array(a)) if e.len() == a.len() => {
+            for (i, (e, a)) in e.iter().zip(a).enumerate() {
+                differences(&format!("{}[{}]", path, i), e, a, found);
+            }
+        }
+        (e, a) if e != a => found.push(format!("{}: expected {}, got {}", path, e, a)),
+        _ => {}
+    }
+}
+
+/// Run the input at `input` (relative to the repository root) and compare its results with
+/// tests/golden/<name>.json
+fn assert_golden(name: &str, input: &str) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let input_path = root.join(input);
+    let input: Value = serde_json::from_str(&std::fs::read_to_string(&input_path).unwrap())
+        .unwrap_or_else(|e| panic!("{}: {}", input_path.display(), e));
+
+    let first = results(&input);
+    assert!(first == results(&input), "{} serializes differently between two runs", name);
+
+    let path = root.join("tests/golden").join(format!("{}.json", name));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, &first).unwrap();
+    }
+    let golden = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("{}: {} (run with UPDATE_GOLDEN=1 to create it)", path.display(), e));
+
+    let actual: Value = serde_json::from_str(&first).unwrap();
+    assert_one_basis(name, "results", &actual);
+
+    let mut found = Vec::new();
+    differences("results", &serde_json::from_str(&golden).unwrap(), &actual, &mut found);
+    assert!(
+        found.is_empty(),
+        "{} differs from {} in {} places; rerun with UPDATE_GOLDEN=1 if the change is intended:\n{}",
+        name, path.display(), found.len(), found.iter().take(20).cloned().collect::<Vec<_>>().join("\n")
+    );
+}
+
+/// Every set of midpoints in `results` (a farm's or facility's, and a farm's per hectare) is
+/// on one basis, so that no category is a total next to intensities
+fn assert_one_basis(name: &str, path: &str, results: &Value) {
+    match results {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let path = format!("{}.{}", path, key);
+                if key == "midpoint_impacts" || key == "impacts_per_ha" {
+                    let bases: std::collections::BTreeSet<_> = value.as_object().into_iter().flat_map(|impacts| impacts.values())
+                        .map(|result| result["basis"].as_str().unwrap())
+                        .collect();
+                    assert!(bases.len() <= 1, "{}: {} mixes bases {:?}", name, path, bases);
+                } else {
+                    assert_one_basis(name, &path, value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| assert_one_basis(name, path, value)),
+        _ => {}
+    }
+}
+
+#[test]
+fn simple_farm_results_match_golden_file() {
+    assert_golden("simple_ghana_smallholder", "tests/fixtures/simple_ghana_smallholder.json");
+}
+
+#[test]
+fn farm_results_match_golden_file() {
+    assert_golden("ghana_maize_cowpea_intercrop", "../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+}
+
+#[test]
+fn irrigated_farm_results_match_golden_file() {
+    assert_golden("nigeria_irrigated_rice", "../engine/case_studies/nigeria_irrigated_rice.json");
+}
+
+#[test]
+fn facility_results_match_golden_file() {
+    assert_golden("example_cassava_processing", "../app/processing/example_cassava_processing.json");
+}
+
+#[test]
+fn mill_results_match_golden_file() {
+    assert_golden("example_maize_mill", "../app/processing/example_assessment.json");
+}
+
+#[test]
+fn company_results_match_golden_file() {
+    assert_golden("company_two_sites", "tests/fixtures/company_two_sites.json");
+}
+
+#[test]
+fn differences_allow_for_rounding_but_not_for_changes() {
+    let golden = serde_json::json!({ "gwp": 1.0, "categories": ["Global warming"] });
+    let mut found = Vec::new();
+    differences("results", &golden, &serde_json::json!({ "gwp": 1.0 + 1e-12, "categories": ["Global warming"] }), &mut found);
+    assert!(found.is_empty(), "{:?}", found);
+
+    differences("results", &golden, &serde_json::json!({ "gwp": 1.001, "categories": [], "extra": null }), &mut found);
+    assert_eq!(found, [
+        "results.categories: expected [\"Global warming\"], got []",
+        "results.gwp: expected 1, got 1.001",
+        "results.extra: unexpected",
+    ]);
+}
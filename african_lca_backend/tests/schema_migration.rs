@@ -0,0 +1,88 @@
+//! Results written by older versions, upgraded by `migrate` to the current schema. The
+//! fixtures in tests/fixtures/schema are assessments in the layout of their version.
+#![cfg(not(target_arch = "wasm32"))]
+
+use african_lca_backend::*;
+use serde_json::Value;
+
+const FARM_1_0_0: &str = include_str!("fixtures/schema/farm_1_0_0.json");
+const FARM_1_1_0: &str = include_str!("fixtures/schema/farm_1_1_0.json");
+const FACILITY_UNVERSIONED: &str = include_str!("fixtures/schema/facility_unversioned.json");
+
+fn migrated(fixture: &str) -> Value {
+    migrate(serde_json::from_str(fixture).unwrap()).unwrap()
+}
+
+#[test]
+fn farm_results_from_before_equipment_energy_and_currency_read_as_current() {
+    // Costs were in the farm's currency before it was recorded
+    assert!(serde_json::from_str::<Assessment>(FARM_1_0_0).is_err());
+
+    let json = migrated(FARM_1_0_0);
+    assert_eq!(json["schema_version"], SCHEMA_VERSION);
+    assert_eq!(json["equipment_energy"], Value::Null);
+    let assessment: Assessment = serde_json::from_value(json).unwrap();
+    assert_eq!(assessment.currency, Currency::GHS);
+    assert!(assessment.results.is_some());
+
+    // Costs were converted at the rates of the build that wrote it, not today's defaults
+    assert_eq!(assessment.exchange_rates.ghs_per_usd, 14.0);
+    assert_ne!(assessment.exchange_rates, ExchangeRates::default());
+}
+
+#[test]
+fn farm_results_from_before_currency_read_as_current() {
+    assert!(serde_json::from_str::<Assessment>(FARM_1_1_0).is_err());
+
+    let assessment: Assessment = serde_json::from_value(migrated(FARM_1_1_0)).unwrap();
+    assert_eq!(assessment.schema_version, SCHEMA_VERSION);
+    assert_eq!(assessment.currency, Currency::GHS);
+    assert!(assessment.equipment_energy.is_none());
+}
+
+#[test]
+fn unversioned_facility_results_are_brought_to_the_current_version() {
+    let json = migrated(FACILITY_UNVERSIONED);
+    let mut stamped: Value = serde_json::from_str(FACILITY_UNVERSIONED).unwrap();
+    stamped["schema_version"] = SCHEMA_VERSION.into();
+    stamped["currency"] = "NGN".into();
+    stamped["exchange_rates"] = serde_json::json!({ "as_of": "2024 averages", "ghs_per_usd": 14.0, "ngn_per_usd": 1500.0 });
+    // Only the Hybrid factors had a pedigree; completeness was already their share
+    let quality_score = json["results"]["data_quality"]["quality_score"].as_f64().unwrap();
+    assert!((quality_score - 0.75).abs() < 1e-9, "{quality_score}");
+    stamped["results"]["data_quality"]["quality_score"] = quality_score.into();
+    for result in stamped["results"]["midpoint_impacts"].as_object_mut().unwrap().values_mut() {
+        result["basis"] = "Total".into();
+    }
+    assert_eq!(json, stamped);
+
+    let facility: ProcessingAssessment = serde_json::from_value(json).unwrap();
+    assert_eq!(facility.currency, Currency::NGN);
+    assert!(facility.results.is_some());
+}
+
+#[test]
+fn farm_pedigree_quality_moves_out_of_completeness() {
+    let original: Value = serde_json::from_str(FARM_1_1_0).unwrap();
+    let assessment: Assessment = serde_json::from_value(migrated(FARM_1_1_0)).unwrap();
+    let quality = assessment.results.as_ref().unwrap().data_quality.clone();
+    assert_eq!(quality.quality_score, original["results"]["data_quality"]["completeness_score"].as_f64().unwrap());
+    assert_eq!(quality.completeness_score, assessment.input_completeness());
+    assert_ne!(quality.completeness_score, quality.quality_score);
+}
+
+#[test]
+fn migrating_twice_changes_nothing_more() {
+    let once = migrated(FARM_1_0_0);
+    assert_eq!(migrate(once.clone()).unwrap(), once);
+}
+
+#[test]
+fn exports_record_the_schema_version() {
+    let assessment: Assessment = serde_json::from_value(migrated(FARM_1_1_0)).unwrap();
+    let csv = export::simapro::simapro_csv(&assessment).unwrap();
+    assert!(csv.contains(&format!("{{Results schema version: {}}}", SCHEMA_VERSION)));
+
+    let html = Report::for_farm(&assessment).unwrap().to_html(report::Language::English).unwrap();
+    assert!(html.contains(SCHEMA_VERSION));
+}
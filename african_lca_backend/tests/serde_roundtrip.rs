@@ -0,0 +1,193 @@
+//! Serde representation of the public model types. Fieldless enum variants are written as
+//! plain strings of the variant name, variants with data as `{"Variant": data}`; the names
+//! are part of the JSON interface and must not change with the Rust spelling.
+#![cfg(not(target_arch = "wasm32"))]
+
+use african_lca_backend::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+const FARM: &str = include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+const FACILITY: &str = include_str!("../../app/processing/example_cassava_processing.json");
+const COMPANY: &str = include_str!("fixtures/company_two_sites.json");
+const FARM_RESULTS: &str = include_str!("golden/ghana_maize_cowpea_intercrop.json");
+
+/// `value` written as `wire`, and read back equal
+fn assert_wire<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T, wire: Value) {
+    assert_eq!(serde_json::to_value(&value).unwrap(), wire, "{:?}", value);
+    assert_eq!(serde_json::from_value::<T>(wire).unwrap(), value);
+}
+
+/// Every variant of each enum is written as its own name
+macro_rules! plain_strings {
+    ($($ty:ty: $($variant:ident),+;)+) => {
+        $($(assert_wire(<$ty>::$variant, json!(stringify!($variant)));)+)+
+    };
+}
+
+/// `value` serialized, read back and serialized again gives the same JSON
+fn assert_round_trip<T: Serialize + DeserializeOwned>(value: &T) -> Value {
+    let json = serde_json::to_value(value).unwrap();
+    let read: T = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&read).unwrap(), json);
+    json
+}
+
+#[test]
+fn fieldless_enums_are_plain_variant_names() {
+    plain_strings! {
+        FoodCategory:
+            Cereals, Legumes, Vegetables, Fruits, Meat, Poultry, Fish, Dairy, Eggs, Oils, Nuts, Roots, Other;
+        ProductionSystem:
+            Intensive, Extensive, Smallholder, Agroforestry, Irrigated, Rainfed, Organic, Conventional;
+        SeasonalFactor: WetSeason, DrySeason, YearRound;
+        CroppingPattern: Monoculture, Intercropping, RelayCropping, Agroforestry, CropRotation;
+        AquacultureSystem: EarthenPond, ConcreteTank, Cage, RecirculatingSystem;
+        AquaFeedType: CommercialPellets, FarmMade, HighFishmeal;
+        PumpType: Diesel, Electric, Solar, Gravity;
+        PoultrySystem: Battery, DeepLitter, FreeRange;
+        PoultryManureSystem: Litter, Slurry, Range;
+        DairyManureSystem: Pasture, SolidStorage, DailySpread, Liquid;
+        ClimateZone: Arid, SemiArid, HumidTropical;
+        AgroEcologicalZone: Sahel, SudanSavannah, GuineaSavannah, CoastalSavannah, DerivedSavannah,
+            SemiDeciduousForest, HighRainforest, MangroveSwamp;
+        FarmType: Smallholder, SmallScale, MediumScale, Commercial, Cooperative, MixedLivestock;
+        FarmingSystem:
+            Subsistence, SemiCommercial, Commercial, Organic, Agroecological, Conventional, IntegratedFarming;
+        SoilType: Sandy, Clay, Loam, SandyLoam, ClayLoam, SiltLoam, Lateritic, Volcanic;
+        SystemBoundary: CradleToGate, CradleToGrave, GateToGate, FarmToFork;
+        AllocationMethod: Mass, Economic, SystemExpansion, Causal;
+        ModelingApproach: Attributional, Consequential;
+        CharacterizationMethod: IpccAr6, IpccAr5, ReCiPe2016, ReCiPe2008, TRACI, CML;
+        NormalizationMethod: AfricanContext, GlobalContext, EuropeanContext, None;
+        Country: Ghana, Nigeria, Global;
+        Currency: GHS, NGN, USD;
+        CalculationWarningKind: ImplausiblyHighYield, ImplausiblyLowYield, ImplausibleOutputPerHead, ImplausibleWaterUse,
+            NitrogenAboveAgronomicMaximum;
+        ConfidenceLevel: High, Medium, Low, VeryLow;
+        RecommendationCategory:
+            SoilManagement, WaterManagement, FertilizerOptimization, PestManagement, EnergyEfficiency,
+            PostHarvest, CropSelection, SystemDesign;
+        DifficultyLevel: Low, Medium, High;
+        CostCategory: NoCost, LowCost, MediumCost, HighCost;
+        Priority: High, Medium, Low;
+        PerformanceCategory: Excellent, Good, Average, BelowAverage, Poor;
+        Scope: Scope1, Scope2, Scope3;
+        CarbonOrigin: Fossil, Biogenic, LandUseChange;
+        ContributionLevel: SingleScore, Endpoint, Midpoint, InventoryFlow, SourceActivity;
+        Dimension: Mass, Volume, Energy, Area, Length;
+        Unit: Kg, Tonne, Litre, CubicMetre, KWh, MJ, Hectare, SquareMetre, Km;
+        Severity: Warning, Error;
+        processing::ProcessingFacilityType:
+            Mill, Bakery, CassivaProcessing, RiceProcessing, PalmOilMill, CocoaProcessing, FishProcessing,
+            MeatProcessing, DairyProcessing, FruitProcessing, VegetableProcessing, General;
+        processing::LocationType: Urban, PeriUrban, Rural, Industrial;
+        processing::EnergySource:
+            GridElectricity, DieselGenerator, SolarPower, Biomass, LPG, NaturalGas, HydroElectricity,
+            WindPower, Mixed;
+        processing::WaterTreatment: None, BasicFiltration, ChemicalTreatment, ReverseOsmosis, Comprehensive;
+        processing::WastewaterTreatment:
+            None, BasicSedimentation, BiologicalTreatment, ChemicalTreatment, Advanced;
+        processing::WasteComposition: CropResidue, FoodScraps, Sludge, Paper, Plastic, Metal;
+        processing::WasteDisposalMethod:
+            Landfill, Incineration, Composting, AnaerobicDigestion, Recycling, Mixed, OpenBurning,
+            EnergyRecovery;
+        processing::TransportMode: Truck, Rail, Ship, Mixed;
+        processing::RefrigerantType: R22, R134a, R404A, R600a, Ammonia, CO2;
+        processing::EquipmentAge: New, Recent, Mature, Old, VeryOld;
+        processing::MaintenanceFrequency: Daily, Weekly, Monthly, Quarterly, Biannual, Annual, Irregular;
+        processing::AutomationLevel: Manual, SemiAutomated, HighlyAutomated, FullyAutomated;
+        processing::DryingMethod: Sun, SolarAssisted, Biomass, Electric, Diesel;
+        processing::PackagingMaterial:
+            PlasticBag, PaperBag, Jute, Polypropylene, Cardboard, Metal, Glass, Composite;
+        processing::QualityGrade: Premium, Standard, Basic, Industrial;
+        processing::MarketDestination: Local, Regional, National, Export, Mixed;
+        processing::TrendStatus: Improved, Stable, Regressed;
+        processing::CapacityRange: Small, Medium, Large, VeryLarge;
+        processing::ProcessingRecommendationCategory:
+            EnergyEfficiency, WaterConservation, WasteReduction, ProcessOptimization, RawMaterialSourcing,
+            EquipmentUpgrade, RenewableEnergy, WasteToEnergy;
+        processing::ImplementationCost: Low, Medium, High, VeryHigh;
+        processing::ComplexityLevel: Simple, Moderate, Complex, VeryComplex;
+        processing::Priority: Critical, High, Medium, Low;
+        processing::RegionalFactorKind:
+            GridEmission, CaptiveGenerationEmission, MarginalGridEmission, ProcessingWaterScarcity,
+            WasteManagementEfficiency, LandfillGasCapture, ProcessingProductivity;
+    }
+}
+
+#[test]
+fn renamed_enums_use_their_declared_case() {
+    assert_wire(AssessmentKind::Comprehensive, json!("comprehensive"));
+    assert_wire(Strictness::Lenient, json!("lenient"));
+    assert_wire(ErrorCode::InvalidInput, json!("INVALID_INPUT"));
+}
+
+#[test]
+fn variants_with_data_are_tagged_by_name() {
+    assert_wire(processing::ProductType::FlourMaize, json!("FlourMaize"));
+    assert_wire(processing::ProductType::Other("Gari".to_string()), json!({ "Other": "Gari" }));
+    assert_wire(
+        WeightingMethod::CustomWeights(BTreeMap::from([("Human Health".to_string(), 0.5)])),
+        json!({ "CustomWeights": { "Human Health": 0.5 } }),
+    );
+    assert_wire(WeightingMethod::None, json!("None"));
+    assert_wire(DataSource::CountrySpecific(Country::Ghana), json!({ "CountrySpecific": "Ghana" }));
+    assert_wire(DataSource::Regional("West Africa".to_string()), json!({ "Regional": "West Africa" }));
+}
+
+#[test]
+fn assessments_and_results_round_trip() {
+    let runner = AssessmentRunner::new(RunnerConfig::default());
+
+    let farm: Value = serde_json::from_str(FARM).unwrap();
+    let assessment = runner.run_comprehensive(create_comprehensive_input(&farm).unwrap()).unwrap();
+    let json = assert_round_trip(&assessment);
+    assert!(json["results"]["midpoint_impacts"].is_object());
+
+    let facility: Value = serde_json::from_str(FACILITY).unwrap();
+    assert_round_trip(&runner.run_processing(create_processing_input(&facility).unwrap()).unwrap());
+
+    let company: Value = serde_json::from_str(COMPANY).unwrap();
+    assert_round_trip(&runner.run_company(create_company_assessment(&company).unwrap()).unwrap());
+
+    assert_round_trip(&FactorDatabases::defaults());
+    assert_round_trip(&RunnerConfig::default());
+}
+
+/// Fields added since results were first stored are optional, so results saved before them
+/// still load
+#[test]
+fn results_without_later_fields_still_deserialize() {
+    fn strip(value: &mut Value, fields: &[&str]) {
+        match value {
+            Value::Object(map) => {
+                map.retain(|key, _| !fields.contains(&key.as_str()));
+                map.values_mut().for_each(|value| strip(value, fields));
+            }
+            Value::Array(items) => items.iter_mut().for_each(|value| strip(value, fields)),
+            _ => {}
+        }
+    }
+    let mut old: Value = serde_json::from_str(FARM_RESULTS).unwrap();
+    strip(&mut old, &["geometric_sd", "marginal_abatement_cost", "impacts_per_ha"]);
+
+    let results: LCAResults = serde_json::from_value(old).unwrap();
+    assert!(results.impacts_per_ha.is_none());
+    assert!(results.midpoint_impacts.values().all(|result| result.geometric_sd.is_none()));
+    assert!(results.recommendations.unwrap().iter().all(|rec| rec.marginal_abatement_cost.is_none()));
+
+    // Foods as first written, before the aquaculture, poultry and dairy profiles
+    let food: FoodItem = serde_json::from_value(json!({
+        "id": "maize", "name": "Maize", "quantity_kg": 1000.0, "category": "Cereals",
+        "crop_type": "Maize", "origin_country": "Ghana", "production_system": "Smallholder",
+        "seasonal_factor": "WetSeason", "variety": null, "area_allocated": 1.0,
+        "cropping_pattern": "Intercropping", "intercropping_partners": ["cowpea"], "post_harvest_losses": null
+    })).unwrap();
+    assert_eq!(food.category, FoodCategory::Cereals);
+    assert_eq!(food.production_system, Some(ProductionSystem::Smallholder));
+    assert!(food.aquaculture.is_none() && food.poultry.is_none() && food.dairy.is_none());
+}
@@ -0,0 +1,20 @@
+//! Run in a browser or Node with `wasm-pack test --node -- --no-default-features --features wasm`
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use african_lca_backend::wasm::{assess_comprehensive, assess_processing};
+use wasm_bindgen_test::*;
+
+const FARM: &str = include_str!("../../engine/case_studies/ghana_maize_cowpea_intercrop.json");
+const FACILITY: &str = include_str!("../../app/processing/example_cassava_processing.json");
+
+#[wasm_bindgen_test]
+fn comprehensive_assessment_runs_in_wasm() {
+    let assessment: serde_json::Value = serde_json::from_str(&assess_comprehensive(FARM)).unwrap();
+    assert!(assessment["results"]["midpoint_impacts"]["Global warming"]["value"].as_f64().unwrap() > 0.0);
+}
+
+#[wasm_bindgen_test]
+fn processing_assessment_runs_in_wasm() {
+    let assessment: serde_json::Value = serde_json::from_str(&assess_processing(FACILITY)).unwrap();
+    assert!(assessment["results"]["midpoint_impacts"].is_object());
+}
@@ -95,6 +95,9 @@ def extract_onfarm_lci(result: dict) -> tuple[list[dict], list[str]]:
         if sub == "Carbon dioxide (CO2)" and any(mk in src for mk in _ENERGY_COMBUSTION_MARKERS):
             dropped_energy += 1
             continue
+        if sub.startswith("Energy, "):                # final energy use, same activity as above
+            dropped_energy += 1
+            continue
         key = SUBSTANCE_MAP.get(sub)
         if not key:
             notes.append(f"unmapped Rust flow '{sub}' (source: {item.get('source')})")